@@ -32,3 +32,42 @@ impl access_mode_seal::Seal for ReadWrite {}
 impl AccessMode for ReadWrite {
     const KIND: AccessModeKind = AccessModeKind::ReadWrite;
 }
+
+mod readable_seal {
+    pub trait Seal {}
+}
+
+/// An [AccessMode] that allows reading: [Read] or [ReadWrite].
+///
+/// Useful as a bound for code that only reads from a storage binding, but should accept both
+/// read-only and read-write bindings:
+///
+/// ```ignore
+/// fn sum<A: Readable>(device: &Device, input: Storage<[u32], A>) -> ... {
+///     let resources = Resources {
+///         input: input.downgrade(),
+///     };
+///
+///     ...
+/// }
+/// ```
+pub trait Readable: AccessMode + readable_seal::Seal {}
+
+impl readable_seal::Seal for Read {}
+impl Readable for Read {}
+
+impl readable_seal::Seal for ReadWrite {}
+impl Readable for ReadWrite {}
+
+mod writable_seal {
+    pub trait Seal {}
+}
+
+/// An [AccessMode] that allows writing: [Write] or [ReadWrite].
+pub trait Writable: AccessMode + writable_seal::Seal {}
+
+impl writable_seal::Seal for Write {}
+impl Writable for Write {}
+
+impl writable_seal::Seal for ReadWrite {}
+impl Writable for ReadWrite {}
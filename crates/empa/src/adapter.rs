@@ -23,6 +23,11 @@ flags! {
         ShaderF16 = 1 << 8,
         Bgra8UNormStorage = 1 << 9,
         TimestampQueryInsideEncoders = 1 << 10,
+        Multiview = 1 << 11,
+        TextureFormat16BitNorm = 1 << 12,
+        PipelineStatisticsQuery = 1 << 13,
+        MultiDrawIndirectCount = 1 << 14,
+        Subgroups = 1 << 15,
     }
 }
 
@@ -64,6 +69,14 @@ pub struct Limits {
     pub max_compute_workgroup_size_y: u32,
     pub max_compute_workgroup_size_z: u32,
     pub max_compute_workgroups_per_dimension: u32,
+    /// The smallest subgroup size the adapter may use; `0` if subgroups are not supported.
+    ///
+    /// Reported by the adapter; ignored when requesting a device.
+    pub min_subgroup_size: u32,
+    /// The largest subgroup size the adapter may use; `0` if subgroups are not supported.
+    ///
+    /// Reported by the adapter; ignored when requesting a device.
+    pub max_subgroup_size: u32,
 }
 
 impl Default for Limits {
@@ -99,6 +112,8 @@ impl Default for Limits {
             max_compute_workgroup_size_y: 256,
             max_compute_workgroup_size_z: 64,
             max_compute_workgroups_per_dimension: 65535,
+            min_subgroup_size: 0,
+            max_subgroup_size: 0,
         }
     }
 }
@@ -135,11 +150,13 @@ impl Adapter {
     where
         Flags: Into<FlagSet<Feature>> + Copy,
     {
+        let features: FlagSet<Feature> = descriptor.required_features.into();
+        let limits = descriptor.required_limits;
+
         self.handle
             .request_device(descriptor)
-            .map_ok(|(device_handle, primary_queue_handle)| Device {
-                device_handle,
-                primary_queue_handle,
+            .map_ok(move |(device_handle, primary_queue_handle)| {
+                Device::new(device_handle, primary_queue_handle, features, limits)
             })
             .map_err(|inner| RequestDeviceError { inner })
     }
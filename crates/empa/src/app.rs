@@ -0,0 +1,181 @@
+//! A target-independent application structure.
+//!
+//! Implement [App] once and run it with the runner for the target: [run_canvas] on the web (with
+//! the `arwa` feature), or `empa_winit::run_app` on native targets. The runner creates the device
+//! and the presentation surface, and calls [App::frame] for every frame.
+//!
+//! ```ignore
+//! struct MyApp;
+//!
+//! impl App for MyApp {
+//!     async fn init(context: &AppContext) -> Result<Self, Box<dyn Error>> {
+//!         Ok(MyApp)
+//!     }
+//!
+//!     fn frame(&mut self, context: &AppContext, frame: &Frame) {
+//!         // Record and submit commands that render to `frame.texture`...
+//!     }
+//! }
+//!
+//! #[cfg(target_arch = "wasm32")]
+//! fn main() {
+//!     arwa::spawn_local(async {
+//!         empa::app::run_canvas::<MyApp>(canvas).await.unwrap();
+//!     });
+//! }
+//!
+//! #[cfg(not(target_arch = "wasm32"))]
+//! fn main() {
+//!     empa_winit::run_app::<MyApp>(Default::default()).unwrap();
+//! }
+//! ```
+
+use std::error::Error;
+use std::future::Future;
+
+use crate::device::Device;
+use crate::texture::format::bgra8unorm;
+use crate::texture::{Texture2D, TextureUsages};
+
+/// The texture format of the frames presented by an [App].
+///
+/// Supported for presentation on both native surfaces and web canvases.
+pub type FrameFormat = bgra8unorm;
+
+/// The usage flags of the frames presented by an [App].
+pub type FrameUsage = TextureUsages!(RenderAttachment);
+
+/// The texture that an [App] renders to for a frame.
+pub type FrameTexture = Texture2D<FrameFormat, FrameUsage>;
+
+/// The current frame, passed to [App::frame].
+pub struct Frame<'a> {
+    /// The texture to render to; presented after [App::frame] returns.
+    pub texture: &'a FrameTexture,
+    /// The time in milliseconds since the first frame.
+    pub time: f64,
+    /// The time in milliseconds since the previous frame; zero for the first frame.
+    pub delta_time: f64,
+}
+
+/// The state a runner shares with an [App].
+pub struct AppContext {
+    device: Device,
+    width: u32,
+    height: u32,
+}
+
+impl AppContext {
+    /// Creates a new context; only needed when implementing a runner.
+    pub fn new(device: Device, width: u32, height: u32) -> Self {
+        AppContext {
+            device,
+            width,
+            height,
+        }
+    }
+
+    pub fn device(&self) -> &Device {
+        &self.device
+    }
+
+    /// The current width of the frame textures.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// The current height of the frame textures.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Updates the size of the frame textures; only needed when implementing a runner.
+    pub fn set_size(&mut self, width: u32, height: u32) {
+        self.width = width;
+        self.height = height;
+    }
+}
+
+/// Application callbacks driven by a runner.
+pub trait App: Sized {
+    /// Called once after the device and the presentation surface have been created.
+    fn init(context: &AppContext) -> impl Future<Output = Result<Self, Box<dyn Error>>>;
+
+    /// Called after the frame textures were resized; the context already reports the new size.
+    fn resize(&mut self, context: &AppContext, width: u32, height: u32) {
+        let _ = (context, width, height);
+    }
+
+    /// Called for every frame.
+    ///
+    /// All commands that render to the frame's texture must have been submitted when this returns.
+    fn frame(&mut self, context: &AppContext, frame: &Frame);
+}
+
+/// Runs the `App` in the given `canvas`, rendering a frame for every animation frame.
+///
+/// Resizes the canvas's drawing buffer to match its displayed size before each frame (see
+/// [AnimationLoop::auto_resize](crate::arwa::AnimationLoop::auto_resize)). Only returns if
+/// initialization fails.
+#[cfg(all(feature = "web", feature = "arwa", not(feature = "noop")))]
+pub async fn run_canvas<A: App>(
+    canvas: arwa::html::HtmlCanvasElement,
+) -> Result<(), Box<dyn Error>> {
+    use crate::arwa::{
+        AlphaMode, AnimationLoop, CanvasConfiguration, HtmlCanvasElementExt, NavigatorExt,
+        RequestAdapterOptions,
+    };
+    use crate::device::DeviceDescriptor;
+    use crate::texture;
+
+    let adapter = arwa::window::window()
+        .navigator()
+        .empa()
+        .request_adapter(&RequestAdapterOptions::default())
+        .await
+        .ok_or("adapter not found")?;
+    let device = adapter.request_device(&DeviceDescriptor::default()).await?;
+
+    let context = canvas.empa_context().configure(&CanvasConfiguration {
+        device: &device,
+        format: bgra8unorm,
+        usage: texture::Usages::render_attachment(),
+        view_formats: (),
+        alpha_mode: AlphaMode::Opaque,
+        frame_latency: Default::default(),
+    });
+
+    let mut frame_pacer = context.frame_pacer();
+    let mut app_context = AppContext::new(device, canvas.width(), canvas.height());
+    let mut app = A::init(&app_context).await?;
+    let mut animation_loop = AnimationLoop::new().auto_resize(true);
+    let mut first_frame_time = None;
+
+    loop {
+        frame_pacer.wait().await;
+
+        let animation_frame = animation_loop.next_frame(&context).await;
+
+        if animation_frame.resized {
+            let width = canvas.width();
+            let height = canvas.height();
+
+            app_context.set_size(width, height);
+            app.resize(&app_context, width, height);
+        }
+
+        let start = *first_frame_time.get_or_insert(animation_frame.time);
+        let texture = context.get_current_texture();
+
+        app.frame(
+            &app_context,
+            &Frame {
+                texture: &texture,
+                time: animation_frame.time - start,
+                delta_time: animation_frame.delta_time,
+            },
+        );
+
+        frame_pacer.frame_submitted();
+    }
+}
@@ -1,24 +1,29 @@
+use std::cell::{Cell, RefCell};
 use std::future::Future;
 use std::marker;
 use std::pin::Pin;
-use std::task::{Context, Poll};
+use std::rc::Rc;
+use std::task::{Context, Poll, Waker};
 
 use arrayvec::ArrayVec;
 use arwa::html::HtmlCanvasElement;
 use arwa::image_bitmap::ImageBitmap;
-use arwa::window::WindowNavigator;
+use arwa::window::{window, WindowNavigator};
 use arwa::worker::WorkerNavigator;
+use futures::Stream;
+use wasm_bindgen::closure::Closure;
 use wasm_bindgen::{JsCast, JsValue, UnwrapThrowExt};
 use wasm_bindgen_futures::JsFuture;
 use web_sys::{
     Gpu, GpuCanvasAlphaMode, GpuCanvasConfiguration, GpuCanvasContext, GpuImageCopyExternalImage,
     GpuImageCopyTextureTagged, GpuOrigin2dDict, GpuOrigin3dDict, GpuPowerPreference,
-    GpuRequestAdapterOptions,
+    GpuRequestAdapterOptions, OffscreenCanvas, ResizeObserver, ResizeObserverEntry,
 };
 
 use crate::adapter::Adapter;
 use crate::device::{Device, Queue};
 use crate::driver::web::{size_3d_to_web_sys, texture_format_to_str, texture_format_to_web_sys};
+use crate::frame_pacing::{FrameLatency, FramePacer};
 use crate::texture;
 use crate::texture::format::{
     bgra8unorm, bgra8unorm_srgb, r16float, r32float, r8unorm, rg16float, rg32float, rg8unorm,
@@ -46,6 +51,13 @@ impl NavigatorExt for WindowNavigator {
     }
 }
 
+impl navigator_ext_seal::Seal for web_sys::WorkerNavigator {}
+impl NavigatorExt for web_sys::WorkerNavigator {
+    fn empa(&self) -> Empa {
+        Empa { inner: self.gpu() }
+    }
+}
+
 impl navigator_ext_seal::Seal for WorkerNavigator {}
 impl NavigatorExt for WorkerNavigator {
     fn empa(&self) -> Empa {
@@ -191,22 +203,61 @@ where
     // Ignoring for now, see comment above.
     // pub color_space: PredefinedColorSpace,
     pub alpha_mode: AlphaMode,
+    /// The frame latency used by [ConfiguredCanvasContext::frame_pacer].
+    pub frame_latency: FrameLatency,
+}
+
+mod canvas_seal {
+    pub trait Seal {}
+}
+
+/// A canvas that can provide a WebGPU context: either an [HtmlCanvasElement] or an
+/// [OffscreenCanvas].
+pub trait Canvas: canvas_seal::Seal {
+    fn width(&self) -> u32;
+
+    fn height(&self) -> u32;
 }
 
-pub struct CanvasContext {
+impl canvas_seal::Seal for HtmlCanvasElement {}
+impl Canvas for HtmlCanvasElement {
+    fn width(&self) -> u32 {
+        HtmlCanvasElement::width(self)
+    }
+
+    fn height(&self) -> u32 {
+        HtmlCanvasElement::height(self)
+    }
+}
+
+impl canvas_seal::Seal for OffscreenCanvas {}
+impl Canvas for OffscreenCanvas {
+    fn width(&self) -> u32 {
+        OffscreenCanvas::width(self)
+    }
+
+    fn height(&self) -> u32 {
+        OffscreenCanvas::height(self)
+    }
+}
+
+pub struct CanvasContext<C = HtmlCanvasElement> {
     inner: GpuCanvasContext,
-    canvas: HtmlCanvasElement,
+    canvas: C,
 }
 
-impl CanvasContext {
-    pub fn canvas(&self) -> &HtmlCanvasElement {
+impl<C> CanvasContext<C>
+where
+    C: Canvas,
+{
+    pub fn canvas(&self) -> &C {
         &self.canvas
     }
 
     pub fn configure<F, U, V>(
         self,
         configuration: &CanvasConfiguration<F, U, V>,
-    ) -> ConfiguredCanvasContext<F, U>
+    ) -> ConfiguredCanvasContext<F, U, C>
     where
         F: CanvasContextFormat,
         U: texture::UsageFlags,
@@ -219,11 +270,12 @@ impl CanvasContext {
             // color_space,
             alpha_mode,
             usage,
+            frame_latency,
             ..
         } = configuration;
 
         let mut config = GpuCanvasConfiguration::new(
-            &device.device_handle.inner,
+            &device.device_handle().inner,
             texture_format_to_web_sys(&F::FORMAT_ID),
         );
 
@@ -246,30 +298,47 @@ impl CanvasContext {
         ConfiguredCanvasContext {
             inner: self.inner,
             canvas: self.canvas,
+            device: (*device).clone(),
             view_formats: view_formats.formats().collect(),
             usage: *usage,
+            frame_latency: *frame_latency,
+            auto_reconfigure: None,
             _marker: Default::default(),
         }
     }
 }
 
-pub struct ConfiguredCanvasContext<F, U> {
+pub struct ConfiguredCanvasContext<F, U, C = HtmlCanvasElement> {
     inner: GpuCanvasContext,
-    canvas: HtmlCanvasElement,
+    canvas: C,
+    device: Device,
     view_formats: ArrayVec<TextureFormatId, 8>,
     usage: U,
+    frame_latency: FrameLatency,
+    auto_reconfigure: Option<CanvasResizeObserver>,
     _marker: marker::PhantomData<F>,
 }
 
-impl<F, U> ConfiguredCanvasContext<F, U>
+impl<F, U, C> ConfiguredCanvasContext<F, U, C>
 where
     F: CanvasContextFormat,
     U: texture::UsageFlags,
+    C: Canvas,
 {
-    pub fn canvas(&self) -> &HtmlCanvasElement {
+    pub fn canvas(&self) -> &C {
         &self.canvas
     }
 
+    pub fn frame_latency(&self) -> FrameLatency {
+        self.frame_latency
+    }
+
+    /// Returns a [FramePacer] that caps the number of frames in flight to the
+    /// [frame_latency](CanvasConfiguration::frame_latency) this context was configured with.
+    pub fn frame_pacer(&self) -> FramePacer {
+        FramePacer::new(self.device.queue(), self.frame_latency)
+    }
+
     pub fn get_current_texture(&self) -> Texture2D<F, U> {
         Texture2D::from_swap_chain_texture(
             self.inner.get_current_texture().into(),
@@ -280,7 +349,7 @@ where
         )
     }
 
-    pub fn unconfigure(self) -> CanvasContext {
+    pub fn unconfigure(self) -> CanvasContext<C> {
         let ConfiguredCanvasContext { inner, canvas, .. } = self;
 
         inner.unconfigure();
@@ -289,6 +358,320 @@ where
     }
 }
 
+impl<F, U> ConfiguredCanvasContext<F, U, HtmlCanvasElement>
+where
+    F: CanvasContextFormat,
+    U: texture::UsageFlags,
+{
+    /// If enabled, keeps the canvas's drawing buffer matched to its displayed size (taking into
+    /// account the device pixel ratio) whenever the canvas element is resized.
+    ///
+    /// Subsequent [get_current_texture](Self::get_current_texture) calls return textures of the
+    /// new size. Any other attachments that must match the canvas size (e.g. depth buffers) still
+    /// need to be recreated; use [observe_resize](Self::observe_resize) to be notified.
+    pub fn auto_reconfigure(mut self, auto_reconfigure: bool) -> Self {
+        if !auto_reconfigure {
+            self.auto_reconfigure = None;
+        } else if self.auto_reconfigure.is_none() {
+            let canvas = self.canvas.clone();
+
+            self.auto_reconfigure = Some(CanvasResizeObserver::new(&self.canvas, move |size| {
+                let as_web_sys: &web_sys::HtmlCanvasElement = canvas.as_ref();
+
+                if as_web_sys.width() != size.width || as_web_sys.height() != size.height {
+                    as_web_sys.set_width(size.width);
+                    as_web_sys.set_height(size.height);
+                }
+            }));
+        }
+
+        self
+    }
+
+    /// Returns a stream that yields the canvas's displayed size in physical pixels whenever the
+    /// canvas element is resized.
+    ///
+    /// The stream first yields the canvas's current size, and then its new size after every
+    /// resize. If the canvas is resized several times between polls, only the most recent size is
+    /// yielded.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let context = canvas.empa_context().configure(&configuration).auto_reconfigure(true);
+    /// let mut resizes = context.observe_resize();
+    ///
+    /// while let Some(size) = resizes.next().await {
+    ///     depth_texture = create_depth_texture(&device, size.width, size.height);
+    /// }
+    /// ```
+    pub fn observe_resize(&self) -> CanvasResizes {
+        let state = Rc::new(RefCell::new(CanvasResizesState {
+            size: None,
+            waker: None,
+        }));
+        let observer_state = state.clone();
+
+        let observer = CanvasResizeObserver::new(&self.canvas, move |size| {
+            let mut state = observer_state.borrow_mut();
+
+            state.size = Some(size);
+
+            if let Some(waker) = state.waker.take() {
+                waker.wake();
+            }
+        });
+
+        CanvasResizes {
+            state,
+            _observer: observer,
+        }
+    }
+}
+
+/// The size of a canvas's drawing buffer in physical pixels.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct CanvasSize {
+    pub width: u32,
+    pub height: u32,
+}
+
+struct CanvasResizesState {
+    size: Option<CanvasSize>,
+    waker: Option<Waker>,
+}
+
+/// A stream of canvas sizes; obtained with [ConfiguredCanvasContext::observe_resize].
+///
+/// Stops observing the canvas when dropped.
+pub struct CanvasResizes {
+    state: Rc<RefCell<CanvasResizesState>>,
+    _observer: CanvasResizeObserver,
+}
+
+impl Stream for CanvasResizes {
+    type Item = CanvasSize;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut state = self.state.borrow_mut();
+
+        if let Some(size) = state.size.take() {
+            Poll::Ready(Some(size))
+        } else {
+            state.waker = Some(cx.waker().clone());
+
+            Poll::Pending
+        }
+    }
+}
+
+struct CanvasResizeObserver {
+    inner: ResizeObserver,
+    _callback: Closure<dyn FnMut(js_sys::Array)>,
+}
+
+impl CanvasResizeObserver {
+    fn new<T>(canvas: &HtmlCanvasElement, mut on_resize: T) -> Self
+    where
+        T: FnMut(CanvasSize) + 'static,
+    {
+        let callback = Closure::<dyn FnMut(js_sys::Array)>::new(move |entries: js_sys::Array| {
+            let pixel_ratio = web_sys::window()
+                .map(|w| w.device_pixel_ratio())
+                .unwrap_or(1.0);
+
+            // Only the canvas is observed, so only the last entry is relevant.
+            if let Some(entry) = entries.iter().last() {
+                let rect = entry.unchecked_into::<ResizeObserverEntry>().content_rect();
+
+                on_resize(CanvasSize {
+                    width: (rect.width() * pixel_ratio).round().max(1.0) as u32,
+                    height: (rect.height() * pixel_ratio).round().max(1.0) as u32,
+                });
+            }
+        });
+
+        let inner = ResizeObserver::new(callback.as_ref().unchecked_ref()).unwrap_throw();
+        let as_web_sys: &web_sys::HtmlCanvasElement = canvas.as_ref();
+
+        inner.observe(as_web_sys);
+
+        CanvasResizeObserver {
+            inner,
+            _callback: callback,
+        }
+    }
+}
+
+impl Drop for CanvasResizeObserver {
+    fn drop(&mut self) {
+        self.inner.disconnect();
+    }
+}
+
+/// Information about a frame produced by an [AnimationLoop].
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct AnimationFrame {
+    /// The `requestAnimationFrame` timestamp for this frame in milliseconds.
+    pub time: f64,
+    /// The time elapsed since the previous frame in milliseconds.
+    ///
+    /// Zero for the first frame, and for the first frame after the loop is resumed.
+    pub delta_time: f64,
+    /// Whether or not the canvas was resized since the previous frame.
+    pub resized: bool,
+}
+
+/// Pauses and resumes an [AnimationLoop]; obtained with [AnimationLoop::controller].
+#[derive(Clone)]
+pub struct AnimationLoopController {
+    paused: Rc<Cell<bool>>,
+}
+
+impl AnimationLoopController {
+    pub fn pause(&self) {
+        self.paused.set(true);
+    }
+
+    pub fn resume(&self) {
+        self.paused.set(false);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.get()
+    }
+}
+
+/// A frame loop driven by `requestAnimationFrame`.
+///
+/// # Example
+///
+/// ```ignore
+/// let mut animation_loop = AnimationLoop::new().auto_resize(true);
+///
+/// loop {
+///     let frame = animation_loop.next_frame(&context).await;
+///
+///     // Render using `frame.delta_time`...
+/// }
+/// ```
+pub struct AnimationLoop {
+    paused: Rc<Cell<bool>>,
+    was_paused: bool,
+    auto_resize: bool,
+    last_time: Option<f64>,
+}
+
+impl AnimationLoop {
+    pub fn new() -> Self {
+        AnimationLoop {
+            paused: Rc::new(Cell::new(false)),
+            was_paused: false,
+            auto_resize: false,
+            last_time: None,
+        }
+    }
+
+    /// If enabled, resizes the canvas's drawing buffer to match its displayed size (taking into
+    /// account the device pixel ratio) before each frame.
+    ///
+    /// The context's textures will match the new size; note that any other attachments that must
+    /// match the canvas size (e.g. depth buffers) need to be recreated when
+    /// [AnimationFrame::resized] is `true`.
+    pub fn auto_resize(mut self, auto_resize: bool) -> Self {
+        self.auto_resize = auto_resize;
+
+        self
+    }
+
+    pub fn controller(&self) -> AnimationLoopController {
+        AnimationLoopController {
+            paused: self.paused.clone(),
+        }
+    }
+
+    /// Waits for the next animation frame.
+    ///
+    /// While the loop is paused, this will not resolve.
+    pub async fn next_frame<F, U>(
+        &mut self,
+        context: &ConfiguredCanvasContext<F, U>,
+    ) -> AnimationFrame
+    where
+        F: CanvasContextFormat,
+        U: texture::UsageFlags,
+    {
+        let window = window();
+
+        loop {
+            let time = window.request_animation_frame().await;
+
+            if self.paused.get() {
+                self.was_paused = true;
+
+                continue;
+            }
+
+            let delta_time = match self.last_time {
+                Some(last_time) if !self.was_paused => time - last_time,
+                _ => 0.0,
+            };
+
+            self.last_time = Some(time);
+            self.was_paused = false;
+
+            let resized = self.auto_resize && fit_canvas_to_display_size(context.canvas());
+
+            return AnimationFrame {
+                time,
+                delta_time,
+                resized,
+            };
+        }
+    }
+
+    /// Calls `f` for every animation frame, indefinitely.
+    pub async fn run<F, U, T>(mut self, context: &ConfiguredCanvasContext<F, U>, mut f: T)
+    where
+        F: CanvasContextFormat,
+        U: texture::UsageFlags,
+        T: FnMut(AnimationFrame),
+    {
+        loop {
+            f(self.next_frame(context).await);
+        }
+    }
+}
+
+impl Default for AnimationLoop {
+    fn default() -> Self {
+        AnimationLoop::new()
+    }
+}
+
+fn fit_canvas_to_display_size(canvas: &HtmlCanvasElement) -> bool {
+    let as_web_sys: &web_sys::HtmlCanvasElement = canvas.as_ref();
+    let pixel_ratio = web_sys::window()
+        .map(|w| w.device_pixel_ratio())
+        .unwrap_or(1.0);
+
+    let width = (as_web_sys.client_width() as f64 * pixel_ratio)
+        .round()
+        .max(1.0) as u32;
+    let height = (as_web_sys.client_height() as f64 * pixel_ratio)
+        .round()
+        .max(1.0) as u32;
+
+    if as_web_sys.width() != width || as_web_sys.height() != height {
+        as_web_sys.set_width(width);
+        as_web_sys.set_height(height);
+
+        true
+    } else {
+        false
+    }
+}
+
 mod html_canvas_element_ext_seal {
     pub trait Seal {}
 }
@@ -313,6 +696,34 @@ impl HtmlCanvasElementExt for HtmlCanvasElement {
     }
 }
 
+mod offscreen_canvas_ext_seal {
+    pub trait Seal {}
+}
+
+/// Provides a WebGPU context for an [OffscreenCanvas].
+///
+/// This allows rendering from a dedicated worker: transfer control of an [HtmlCanvasElement] to an
+/// [OffscreenCanvas] on the main thread (`transferControlToOffscreen`) and post it to the worker as
+/// a transferable. Note that empa's objects wrap JavaScript objects that are bound to the thread
+/// (realm) that created them; they are not `Send` and cannot be posted to another thread. Instead,
+/// request an adapter and device inside the worker (see the [NavigatorExt] implementation for
+/// [WorkerNavigator]) and create all GPU resources there.
+pub trait OffscreenCanvasExt: offscreen_canvas_ext_seal::Seal {
+    fn empa_context(&self) -> CanvasContext<OffscreenCanvas>;
+}
+
+impl offscreen_canvas_ext_seal::Seal for OffscreenCanvas {}
+impl OffscreenCanvasExt for OffscreenCanvas {
+    fn empa_context(&self) -> CanvasContext<OffscreenCanvas> {
+        let inner = self.get_context("webgpu").unwrap_throw().unwrap_throw();
+
+        CanvasContext {
+            inner: inner.unchecked_into(),
+            canvas: self.clone(),
+        }
+    }
+}
+
 mod queue_ext_seal {
     pub trait Seal {}
 }
@@ -4,21 +4,25 @@ use std::mem::MaybeUninit;
 use std::ops::{
     Deref, DerefMut, Range, RangeFrom, RangeFull, RangeInclusive, RangeTo, RangeToInclusive, Rem,
 };
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
 use std::{error, fmt, marker, mem, slice};
 
 use atomic_counter::AtomicCounter;
 pub use empa_macros::BufferUsages;
+use flagset::FlagSet;
 
-use crate::access_mode::{AccessMode, Read};
+use crate::access_mode::{AccessMode, Read, ReadWrite, Readable};
 use crate::buffer::{
-    CopyDst, CopySrc, MapRead, MapWrite, StorageBinding, UniformBinding, UsageFlags,
+    BufferUsage, CopyDst, CopySrc, MapRead, MapWrite, StorageBinding, UniformBinding, UsageFlags,
     ValidUsageFlags,
 };
-use crate::device::{Device, ID_GEN};
+use crate::command::{CommandEncoder, ComputePassEncoder};
+use crate::device::{Device, Queue, ID_GEN};
 use crate::driver::{
     Buffer as _, BufferDescriptor, Device as _, Driver, Dvr, ImageCopyBuffer, MapMode,
 };
+use crate::memory::MemoryAllocation;
+use crate::render_pipeline::Vertex;
 use crate::texture::{ImageDataByteLayout, ImageDataLayout};
 use crate::{abi, driver};
 
@@ -27,6 +31,11 @@ type MappedInternal<'a, T> = <BufferHandle as driver::Buffer<Dvr>>::Mapped<'a, T
 type MappedMutInternal<'a, T> = <BufferHandle as driver::Buffer<Dvr>>::MappedMut<'a, T>;
 type BufferBinding = <Dvr as Driver>::BufferBinding;
 
+/// Projects a value of type `T` onto a field of type `P` at a fixed offset inside the value.
+///
+/// Created with the [projection] macro. Used with [Buffer::project_to] and [View::project_to] to
+/// obtain a view on a single field of a buffer, e.g. to map the field, or to update the field with
+/// [Queue::write_buffer].
 #[derive(Clone, Copy)]
 pub struct Projection<T, P> {
     offset_in_bytes: usize,
@@ -34,12 +43,36 @@ pub struct Projection<T, P> {
 }
 
 impl<T, P> Projection<T, P> {
+    /// Creates a projection onto a field of type `P` at `offset_in_bytes` inside of `T`.
+    ///
+    /// # Safety
+    ///
+    /// The `offset_in_bytes` must be the offset of a field of type `P` inside of `T`, and must be a
+    /// multiple of the alignment of `P`.
     pub const unsafe fn from_offset_in_bytes(offset_in_bytes: usize) -> Self {
         Projection {
             offset_in_bytes,
             _marker: marker::PhantomData,
         }
     }
+
+    /// The offset in bytes of the projected field inside of `T`.
+    pub const fn offset_in_bytes(&self) -> usize {
+        self.offset_in_bytes
+    }
+
+    /// Composes this projection with a `projection` onto a field of `P`, e.g. to project onto a
+    /// field of a nested struct.
+    ///
+    /// ```ignore
+    /// let projection = projection!(Uniforms => light).then(projection!(Light => color));
+    /// ```
+    pub const fn then<Q>(self, projection: Projection<P, Q>) -> Projection<T, Q> {
+        Projection {
+            offset_in_bytes: self.offset_in_bytes + projection.offset_in_bytes,
+            _marker: marker::PhantomData,
+        }
+    }
 }
 
 #[doc(hidden)]
@@ -55,17 +88,67 @@ macro_rules! projection {
 pub use crate::projection;
 
 /// Signals that an error occurred when trying to map a buffer.
+///
+/// A failed map operation leaves the buffer unmapped; the buffer may be mapped again.
 #[derive(Clone, PartialEq, Eq, Debug)]
-pub struct MapError;
+pub enum MapError {
+    /// The buffer was destroyed before the map operation completed.
+    Destroyed,
+    /// The map operation was aborted before it completed, e.g. because the buffer was unmapped.
+    Aborted,
+    /// The device was lost.
+    DeviceLost,
+    /// The map operation failed validation.
+    Validation(String),
+    /// Any other error reported by the device.
+    Other(String),
+}
 
 impl fmt::Display for MapError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "error occurred when trying to map a buffer")
+        match self {
+            MapError::Destroyed => write!(f, "failed to map buffer: the buffer was destroyed"),
+            MapError::Aborted => write!(f, "failed to map buffer: the map operation was aborted"),
+            MapError::DeviceLost => write!(f, "failed to map buffer: the device was lost"),
+            MapError::Validation(message) => {
+                write!(f, "failed to map buffer: validation error: {}", message)
+            }
+            MapError::Other(message) => write!(f, "failed to map buffer: {}", message),
+        }
     }
 }
 
 impl error::Error for MapError {}
 
+/// Signals that an error occurred when trying to create a buffer.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum CreateBufferError {
+    /// The size of the buffer (in bytes) exceeds the device's `max_buffer_size` limit.
+    SizeLimitExceeded { size: u64, max_size: u64 },
+    /// The combination of usage flags is not supported by the device.
+    InvalidUsage,
+    /// Any other error reported by the device.
+    Other(String),
+}
+
+impl fmt::Display for CreateBufferError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CreateBufferError::SizeLimitExceeded { size, max_size } => write!(
+                f,
+                "buffer size (`{}` bytes) exceeds the maximum buffer size (`{}` bytes)",
+                size, max_size
+            ),
+            CreateBufferError::InvalidUsage => {
+                write!(f, "the combination of buffer usages is not supported")
+            }
+            CreateBufferError::Other(message) => write!(f, "failed to create buffer: {}", message),
+        }
+    }
+}
+
+impl error::Error for CreateBufferError {}
+
 pub trait AsBuffer<T>
 where
     T: ?Sized,
@@ -75,7 +158,7 @@ where
         device: &Device,
         mapped_at_creation: bool,
         usage: Usage,
-    ) -> Buffer<T, Usage>
+    ) -> Result<Buffer<T, Usage>, CreateBufferError>
     where
         Usage: ValidUsageFlags;
 }
@@ -90,18 +173,21 @@ where
         device: &Device,
         mapped_at_creation: bool,
         usage: Usage,
-    ) -> Buffer<T, Usage>
+    ) -> Result<Buffer<T, Usage>, CreateBufferError>
     where
         Usage: ValidUsageFlags,
     {
-        let id = ID_GEN.get();
+        let id = BufferId(ID_GEN.get());
         let size_in_bytes = mem::size_of::<T>();
 
-        let handle = device.device_handle.create_buffer(&BufferDescriptor {
-            size: size_in_bytes,
-            usage_flags: Usage::FLAG_SET,
-            mapped_at_creation: true,
-        });
+        let (handle, allocation) = create_buffer_handle(
+            device,
+            &BufferDescriptor {
+                size: size_in_bytes,
+                usage_flags: Usage::FLAG_SET,
+                mapped_at_creation: true,
+            },
+        )?;
 
         #[allow(unused_mut)]
         let mut mapped = handle.mapped_mut(0, size_in_bytes);
@@ -123,16 +209,17 @@ where
 
         let internal = BufferInternal {
             handle,
+            allocation,
             id,
             len: 1,
-            map_context: Mutex::new(map_context),
+            map_context: Arc::new(Mutex::new(map_context)),
             usage,
         };
 
-        Buffer {
+        Ok(Buffer {
             internal,
             _marker: Default::default(),
-        }
+        })
     }
 }
 
@@ -146,20 +233,23 @@ where
         device: &Device,
         mapped_at_creation: bool,
         usage: Usage,
-    ) -> Buffer<[T], Usage>
+    ) -> Result<Buffer<[T], Usage>, CreateBufferError>
     where
         Usage: ValidUsageFlags,
     {
-        let id = ID_GEN.get();
+        let id = BufferId(ID_GEN.get());
         let data = self.borrow();
         let slice_len = data.len();
         let size_in_bytes = mem::size_of::<T>() * slice_len;
 
-        let handle = device.device_handle.create_buffer(&BufferDescriptor {
-            size: size_in_bytes,
-            usage_flags: Usage::FLAG_SET,
-            mapped_at_creation: true,
-        });
+        let (handle, allocation) = create_buffer_handle(
+            device,
+            &BufferDescriptor {
+                size: size_in_bytes,
+                usage_flags: Usage::FLAG_SET,
+                mapped_at_creation: true,
+            },
+        )?;
 
         #[allow(unused_mut)]
         let mut mapped = handle.mapped_mut(0, size_in_bytes);
@@ -181,24 +271,125 @@ where
 
         let internal = BufferInternal {
             handle,
+            allocation,
             id,
             len: slice_len,
-            map_context: Mutex::new(map_context),
+            map_context: Arc::new(Mutex::new(map_context)),
             usage,
         };
 
-        Buffer {
+        Ok(Buffer {
             internal,
             _marker: Default::default(),
+        })
+    }
+}
+
+impl<T, U> Buffer<[T], U>
+where
+    T: Copy + 'static,
+    U: ValidUsageFlags,
+{
+    // Initializes each element by writing the result of `f` directly into the buffer's memory
+    // while it is mapped at creation.
+    pub(crate) fn create_with<F>(
+        device: &Device,
+        len: usize,
+        usage: U,
+        mut f: F,
+    ) -> Result<Self, CreateBufferError>
+    where
+        F: FnMut(usize) -> T,
+    {
+        let element_size = mem::size_of::<T>();
+        let Some(size_in_bytes) = element_size.checked_mul(len) else {
+            return Err(CreateBufferError::SizeLimitExceeded {
+                size: (element_size as u64).saturating_mul(len as u64),
+                max_size: device.limits().max_buffer_size,
+            });
+        };
+        let id = BufferId(ID_GEN.get());
+
+        let (handle, allocation) = create_buffer_handle(
+            device,
+            &BufferDescriptor {
+                size: size_in_bytes,
+                usage_flags: U::FLAG_SET,
+                mapped_at_creation: true,
+            },
+        )?;
+
+        #[allow(unused_mut)]
+        let mut mapped = handle.mapped_mut(0, size_in_bytes);
+
+        if element_size > 0 {
+            for (index, chunk) in mapped.as_mut().chunks_exact_mut(element_size).enumerate() {
+                let value = f(index);
+
+                chunk.copy_from_slice(unsafe { value_to_bytes(&value) });
+            }
         }
+
+        #[allow(dropping_references)]
+        mem::drop(mapped);
+
+        handle.unmap();
+
+        let internal = BufferInternal {
+            handle,
+            allocation,
+            id,
+            len,
+            map_context: Arc::new(Mutex::new(MapContext::new())),
+            usage,
+        };
+
+        Ok(Buffer {
+            internal,
+            _marker: Default::default(),
+        })
+    }
+}
+
+fn create_buffer_handle(
+    device: &Device,
+    descriptor: &BufferDescriptor,
+) -> Result<(BufferHandle, MemoryAllocation), CreateBufferError> {
+    let max_size = device.limits().max_buffer_size;
+
+    if descriptor.size as u64 > max_size {
+        return Err(CreateBufferError::SizeLimitExceeded {
+            size: descriptor.size as u64,
+            max_size,
+        });
+    }
+
+    let handle = device.device_handle().create_buffer(descriptor)?;
+    let allocation = device
+        .memory_tracker()
+        .track_buffer(descriptor.size as u64, descriptor.usage_flags);
+
+    Ok((handle, allocation))
+}
+
+// Identifies a buffer in usage validation messages. Dropping the ID (along with the buffer it
+// identifies) removes the buffer's label.
+pub(crate) struct BufferId(usize);
+
+#[cfg(feature = "usage_validation")]
+impl Drop for BufferId {
+    fn drop(&mut self) {
+        crate::usage_validation::remove_buffer_label(self.0);
     }
 }
 
 pub(crate) struct BufferInternal<U> {
     pub(crate) handle: BufferHandle,
-    id: usize,
+    #[allow(unused)]
+    allocation: MemoryAllocation,
+    id: BufferId,
     len: usize,
-    map_context: Mutex<MapContext>,
+    map_context: Arc<Mutex<MapContext>>,
     usage: U,
 }
 
@@ -217,7 +408,20 @@ impl<U> BufferInternal<U> {
 
         mc.initial_range = start..end;
 
-        self.handle.map(mode, start..end)
+        let map = self.handle.map(mode, start..end);
+        let map_context = self.map_context.clone();
+
+        async move {
+            let result = map.await;
+
+            // A failed map operation leaves the buffer unmapped, so allow the buffer to be mapped
+            // again.
+            if result.is_err() {
+                map_context.lock().unwrap().initial_range = 0..0;
+            }
+
+            result
+        }
     }
 
     fn unmap_internal(&self) {
@@ -242,8 +446,36 @@ where
         self.internal.unmap_internal()
     }
 
+    /// Destroys the buffer, immediately releasing its GPU memory.
+    ///
+    /// Any bind groups or recorded commands that still reference the buffer become invalid;
+    /// submitting work that uses the buffer after it was destroyed results in a validation error.
+    pub fn destroy(self) {
+        self.internal.handle.destroy();
+    }
+
+    /// Sets a label that identifies this buffer in the messages reported by usage validation.
+    ///
+    /// Only has an effect if the `usage_validation` feature is enabled. The label is retained until
+    /// the buffer is dropped or [destroyed](Buffer::destroy).
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let particles = device.create_buffer(particle_data, buffer::Usages::storage_binding());
+    ///
+    /// particles.set_label("particles");
+    /// ```
+    pub fn set_label(&self, label: &str) {
+        #[cfg(feature = "usage_validation")]
+        crate::usage_validation::set_buffer_label(self.internal.id.0, label);
+
+        #[cfg(not(feature = "usage_validation"))]
+        let _ = label;
+    }
+
     pub(crate) fn id(&self) -> usize {
-        self.internal.id
+        self.internal.id.0
     }
 }
 
@@ -255,21 +487,221 @@ where
     pub fn usage(&self) -> U {
         self.internal.usage
     }
+
+    /// Erases the usage type of this buffer, see [AnyUsageBuffer].
+    pub fn into_any_usage(self) -> AnyUsageBuffer<T> {
+        let BufferInternal {
+            handle,
+            allocation,
+            id,
+            len,
+            map_context,
+            ..
+        } = self.internal;
+
+        AnyUsageBuffer {
+            internal: BufferInternal {
+                handle,
+                allocation,
+                id,
+                len,
+                map_context,
+                usage: (),
+            },
+            usage: U::FLAG_SET,
+            _marker: Default::default(),
+        }
+    }
+}
+
+impl<T, U> From<Buffer<T, U>> for AnyUsageBuffer<T>
+where
+    T: ?Sized,
+    U: UsageFlags,
+{
+    fn from(buffer: Buffer<T, U>) -> Self {
+        buffer.into_any_usage()
+    }
+}
+
+/// A buffer of which the usage flags are only known at runtime.
+///
+/// A [Buffer]'s usage type parameter is unwieldy to spell out in the fields of application
+/// structs. An `AnyUsageBuffer` erases the usage type, so that buffers can be stored without
+/// naming it; the buffer's usage flags are instead tracked at runtime. Recover a typed buffer
+/// with [try_into_usages](AnyUsageBuffer::try_into_usages) before using it in commands or
+/// bindings:
+///
+/// ```ignore
+/// struct AppState {
+///     uniforms: AnyUsageBuffer<Uniforms>,
+/// }
+///
+/// let state = AppState {
+///     uniforms: device
+///         .create_buffer(uniforms, buffer::Usages::uniform_binding().and_copy_dst())
+///         .into_any_usage(),
+/// };
+///
+/// let uniforms: Buffer<Uniforms, BufferUsages!(UniformBinding | CopyDst)> =
+///     state.uniforms.try_into_usages().unwrap();
+/// ```
+pub struct AnyUsageBuffer<T>
+where
+    T: ?Sized,
+{
+    internal: BufferInternal<()>,
+    usage: FlagSet<BufferUsage>,
+    _marker: marker::PhantomData<T>,
+}
+
+impl<T> AnyUsageBuffer<T>
+where
+    T: ?Sized,
+{
+    /// The usage flags this buffer was created with.
+    pub fn usage(&self) -> FlagSet<BufferUsage> {
+        self.usage
+    }
+
+    /// Whether this buffer was created with (at least) the usage flags of usage type `U`.
+    pub fn supports_usages<U>(&self) -> bool
+    where
+        U: UsageFlags,
+    {
+        self.usage.contains(U::FLAG_SET)
+    }
+
+    /// Converts this buffer back into a buffer with usage type `U`.
+    ///
+    /// The buffer's usage flags must include all usage flags of `U`, but may include additional
+    /// flags. Returns an error that gives back this buffer otherwise.
+    pub fn try_into_usages<U>(self) -> Result<Buffer<T, U>, UsageMismatch<T>>
+    where
+        U: UsageFlags,
+    {
+        if !self.supports_usages::<U>() {
+            let missing = U::FLAG_SET - self.usage;
+
+            return Err(UsageMismatch {
+                buffer: self,
+                missing,
+            });
+        }
+
+        let BufferInternal {
+            handle,
+            allocation,
+            id,
+            len,
+            map_context,
+            ..
+        } = self.internal;
+
+        Ok(Buffer {
+            internal: BufferInternal {
+                handle,
+                allocation,
+                id,
+                len,
+                map_context,
+                usage: U::INSTANCE,
+            },
+            _marker: Default::default(),
+        })
+    }
+
+    /// Destroys the buffer, immediately releasing its GPU memory.
+    ///
+    /// See [Buffer::destroy].
+    pub fn destroy(self) {
+        self.internal.handle.destroy();
+    }
+}
+
+impl<T> AnyUsageBuffer<[T]> {
+    /// Returns the number of elements contained in this buffer.
+    pub fn len(&self) -> usize {
+        self.internal.len
+    }
+
+    /// Returns `true` if the buffer contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.internal.len == 0
+    }
+}
+
+/// Returned by [AnyUsageBuffer::try_into_usages] if the buffer does not support the requested
+/// usages.
+pub struct UsageMismatch<T>
+where
+    T: ?Sized,
+{
+    buffer: AnyUsageBuffer<T>,
+    missing: FlagSet<BufferUsage>,
+}
+
+impl<T> UsageMismatch<T>
+where
+    T: ?Sized,
+{
+    /// The requested usage flags that the buffer was not created with.
+    pub fn missing(&self) -> FlagSet<BufferUsage> {
+        self.missing
+    }
+
+    /// Recovers the buffer for which the conversion failed.
+    pub fn into_buffer(self) -> AnyUsageBuffer<T> {
+        self.buffer
+    }
+}
+
+impl<T> fmt::Debug for UsageMismatch<T>
+where
+    T: ?Sized,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UsageMismatch")
+            .field("missing", &self.missing)
+            .finish()
+    }
+}
+
+impl<T> fmt::Display for UsageMismatch<T>
+where
+    T: ?Sized,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the buffer was not created with the required usages (missing: {:?})",
+            self.missing
+        )
+    }
 }
 
+impl<T> error::Error for UsageMismatch<T> where T: ?Sized {}
+
 impl<T, U> Buffer<MaybeUninit<T>, U>
 where
     U: ValidUsageFlags,
 {
-    pub(crate) fn create_uninit(device: &Device, mapped_at_creation: bool, usage: U) -> Self {
-        let id = ID_GEN.get();
+    pub(crate) fn create_uninit(
+        device: &Device,
+        mapped_at_creation: bool,
+        usage: U,
+    ) -> Result<Self, CreateBufferError> {
+        let id = BufferId(ID_GEN.get());
         let size_in_bytes = mem::size_of::<T>();
 
-        let handle = device.device_handle.create_buffer(&BufferDescriptor {
-            size: size_in_bytes,
-            usage_flags: U::FLAG_SET,
-            mapped_at_creation,
-        });
+        let (handle, allocation) = create_buffer_handle(
+            device,
+            &BufferDescriptor {
+                size: size_in_bytes,
+                usage_flags: U::FLAG_SET,
+                mapped_at_creation,
+            },
+        )?;
 
         let mut map_context = MapContext::new();
 
@@ -279,16 +711,17 @@ where
 
         let internal = BufferInternal {
             handle,
+            allocation,
             id,
             len: 1,
-            map_context: Mutex::new(map_context),
+            map_context: Arc::new(Mutex::new(map_context)),
             usage,
         };
 
-        Buffer {
+        Ok(Buffer {
             internal,
             _marker: Default::default(),
-        }
+        })
     }
 }
 
@@ -316,15 +749,18 @@ where
         len: usize,
         mapped_at_creation: bool,
         usage: U,
-    ) -> Self {
-        let id = ID_GEN.get();
+    ) -> Result<Self, CreateBufferError> {
+        let id = BufferId(ID_GEN.get());
         let size_in_bytes = mem::size_of::<T>() * len;
 
-        let handle = device.device_handle.create_buffer(&BufferDescriptor {
-            size: size_in_bytes,
-            usage_flags: U::FLAG_SET,
-            mapped_at_creation,
-        });
+        let (handle, allocation) = create_buffer_handle(
+            device,
+            &BufferDescriptor {
+                size: size_in_bytes,
+                usage_flags: U::FLAG_SET,
+                mapped_at_creation,
+            },
+        )?;
 
         let mut map_context = MapContext::new();
 
@@ -334,16 +770,17 @@ where
 
         let internal = BufferInternal {
             handle,
+            allocation,
             id,
             len,
-            map_context: Mutex::new(map_context),
+            map_context: Arc::new(Mutex::new(map_context)),
             usage,
         };
 
-        Buffer {
+        Ok(Buffer {
             internal,
             _marker: Default::default(),
-        }
+        })
     }
 }
 
@@ -360,6 +797,69 @@ impl<T, U> Buffer<[MaybeUninit<T>], U> {
             _marker: Default::default(),
         }
     }
+
+    /// Initializes the buffer with the `data` by writing it to the `queue`, and converts to
+    /// `Buffer<[T]>`.
+    ///
+    /// The write is ordered before any work submitted to the `queue` afterwards.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `data` does not match the length of the buffer.
+    pub fn write_all(self, queue: &Queue, data: &[T]) -> Buffer<[T], U>
+    where
+        T: Copy + 'static,
+        U: CopyDst,
+    {
+        let buffer = unsafe { self.assume_init() };
+
+        queue.write_buffer_slice(buffer.view(), data);
+
+        buffer
+    }
+
+    /// Records a command that sets all bytes in the buffer to zero on the `encoder`, and converts
+    /// to `Buffer<[T]>`.
+    ///
+    /// Note that WebGPU already initializes a buffer's contents to zero when the buffer is
+    /// created; this explicitly clears the buffer in the encoder's command sequence, e.g. when the
+    /// buffer is reused.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the size of the buffer in bytes is not a multiple of `4`.
+    #[cfg(feature = "bytemuck")]
+    pub fn fill_zero(self, encoder: CommandEncoder) -> (CommandEncoder, Buffer<[T], U>)
+    where
+        T: bytemuck::Zeroable,
+        U: CopyDst + 'static,
+    {
+        let buffer = unsafe { self.assume_init() };
+        let encoder = encoder.clear_buffer_slice(buffer.view());
+
+        (encoder, buffer)
+    }
+
+    /// Initializes the buffer with a compute pass, and converts to `Buffer<[T]>`.
+    ///
+    /// Begins a compute pass on the `encoder` and calls `f` with the pass encoder and the buffer
+    /// (typed as initialized, so that it can be bound as storage); `f` is expected to record
+    /// dispatches that write every element of the buffer. The pass is ended when `f` returns.
+    /// Elements that are not written retain their initial zero value.
+    #[cfg(feature = "compute")]
+    pub fn init_with_compute_pass<F, P, R>(
+        self,
+        encoder: CommandEncoder,
+        f: F,
+    ) -> (CommandEncoder, Buffer<[T], U>)
+    where
+        F: FnOnce(ComputePassEncoder<(), ()>, &Buffer<[T], U>) -> ComputePassEncoder<P, R>,
+    {
+        let buffer = unsafe { self.assume_init() };
+        let encoder = f(encoder.begin_compute_pass(), &buffer).end();
+
+        (encoder, buffer)
+    }
 }
 
 impl<T, U> Buffer<T, U> {
@@ -409,8 +909,10 @@ impl<T, U> Buffer<T, U> {
 
         Uniform {
             inner: self.internal.handle.binding(0, self.size_in_bytes()),
-            _offset: 0,
-            _size: self.size_in_bytes(),
+            #[cfg(feature = "usage_validation")]
+            buffer_id: self.internal.id.0,
+            offset: 0,
+            size: self.size_in_bytes(),
             _marker: Default::default(),
         }
     }
@@ -426,8 +928,10 @@ impl<T, U> Buffer<T, U> {
 
         Storage {
             inner: self.internal.handle.binding(0, self.size_in_bytes()),
-            _offset: 0,
-            _size: self.size_in_bytes(),
+            #[cfg(feature = "usage_validation")]
+            buffer_id: self.internal.id.0,
+            offset: 0,
+            size: self.size_in_bytes(),
             _marker: Default::default(),
         }
     }
@@ -531,8 +1035,47 @@ impl<T, U> Buffer<[T], U> {
 
         Storage {
             inner: self.internal.handle.binding(0, self.size_in_bytes()),
-            _offset: 0,
-            _size: self.size_in_bytes(),
+            #[cfg(feature = "usage_validation")]
+            buffer_id: self.internal.id.0,
+            offset: 0,
+            size: self.size_in_bytes(),
+            _marker: Default::default(),
+        }
+    }
+
+    /// Returns a read-only storage binding that exposes the vertex data in this buffer as an
+    /// `array<u32>` for vertex pulling.
+    ///
+    /// See [vertex_pulling_source](crate::util::vertex_pulling_source) for generating the
+    /// corresponding WGSL fetch function.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer is empty, or if the vertex stride is not a multiple of `4`.
+    pub fn vertex_pulling_storage(&self) -> Storage<[u32], Read>
+    where
+        T: Vertex,
+        U: StorageBinding,
+    {
+        let stride = T::LAYOUT.array_stride;
+
+        if stride.rem(4) != 0 {
+            panic!(
+                "vertex stride `{}` must be a multiple of `4` for vertex pulling",
+                stride
+            );
+        }
+
+        if self.size_in_bytes() == 0 {
+            panic!("Cannot use zero-sized buffer as a resource binding");
+        }
+
+        Storage {
+            inner: self.internal.handle.binding(0, self.size_in_bytes()),
+            #[cfg(feature = "usage_validation")]
+            buffer_id: self.internal.id.0,
+            offset: 0,
+            size: self.size_in_bytes(),
             _marker: Default::default(),
         }
     }
@@ -670,7 +1213,7 @@ where
     T: ?Sized,
 {
     pub(crate) fn id(&self) -> usize {
-        self.buffer.id
+        self.buffer.id.0
     }
 }
 
@@ -749,6 +1292,14 @@ impl<'a, T, U> View<'a, T, U> {
         }
     }
 
+    /// Binds the region of the buffer covered by this view as a uniform buffer.
+    ///
+    /// This allows several uniform values to be sub-allocated from a single buffer, e.g. by binding
+    /// `buffer.get(1).unwrap().uniform()`. When the binding is used to create a bind group, the
+    /// offset of the view must be a multiple of the device's
+    /// [min_uniform_buffer_offset_alignment](crate::adapter::Limits::min_uniform_buffer_offset_alignment)
+    /// and its size must not exceed the device's
+    /// [max_uniform_buffer_binding_size](crate::adapter::Limits::max_uniform_buffer_binding_size).
     pub fn uniform(&self) -> Uniform<'a, T>
     where
         T: abi::Sized,
@@ -763,8 +1314,10 @@ impl<'a, T, U> View<'a, T, U> {
                 .buffer
                 .handle
                 .binding(self.offset_in_bytes(), self.size_in_bytes()),
-            _offset: self.offset_in_bytes(),
-            _size: self.size_in_bytes(),
+            #[cfg(feature = "usage_validation")]
+            buffer_id: self.buffer.id.0,
+            offset: self.offset_in_bytes(),
+            size: self.size_in_bytes(),
             _marker: Default::default(),
         }
     }
@@ -783,8 +1336,10 @@ impl<'a, T, U> View<'a, T, U> {
                 .buffer
                 .handle
                 .binding(self.offset_in_bytes(), self.size_in_bytes()),
-            _offset: self.offset_in_bytes(),
-            _size: self.size_in_bytes(),
+            #[cfg(feature = "usage_validation")]
+            buffer_id: self.buffer.id.0,
+            offset: self.offset_in_bytes(),
+            size: self.size_in_bytes(),
             _marker: Default::default(),
         }
     }
@@ -914,6 +1469,14 @@ impl<'a, T, U> View<'a, [T], U> {
         }
     }
 
+    /// Binds the region of the buffer covered by this view as a storage buffer.
+    ///
+    /// This allows a sub-range of a buffer to be bound, e.g. `buffer.get(64..128).unwrap()
+    /// .storage()`. When the binding is used to create a bind group, the offset of the view must be
+    /// a multiple of the device's
+    /// [min_storage_buffer_offset_alignment](crate::adapter::Limits::min_storage_buffer_offset_alignment)
+    /// and its size must not exceed the device's
+    /// [max_storage_buffer_binding_size](crate::adapter::Limits::max_storage_buffer_binding_size).
     pub fn storage<A: AccessMode>(&self) -> Storage<'a, [T], A>
     where
         T: abi::Sized,
@@ -928,8 +1491,10 @@ impl<'a, T, U> View<'a, [T], U> {
                 .buffer
                 .handle
                 .binding(self.offset_in_bytes(), self.size_in_bytes()),
-            _offset: self.offset_in_bytes(),
-            _size: self.size_in_bytes(),
+            #[cfg(feature = "usage_validation")]
+            buffer_id: self.buffer.id.0,
+            offset: self.offset_in_bytes(),
+            size: self.size_in_bytes(),
             _marker: Default::default(),
         }
     }
@@ -1345,8 +1910,10 @@ where
     T: ?Sized,
 {
     pub(crate) inner: BufferBinding,
-    pub(crate) _offset: usize,
-    pub(crate) _size: usize,
+    #[cfg(feature = "usage_validation")]
+    pub(crate) buffer_id: usize,
+    pub(crate) offset: usize,
+    pub(crate) size: usize,
     _marker: marker::PhantomData<&'a T>,
 }
 
@@ -1356,11 +1923,42 @@ where
     T: ?Sized,
 {
     pub(crate) inner: BufferBinding,
-    pub(crate) _offset: usize,
-    pub(crate) _size: usize,
+    #[cfg(feature = "usage_validation")]
+    pub(crate) buffer_id: usize,
+    pub(crate) offset: usize,
+    pub(crate) size: usize,
     _marker: marker::PhantomData<(&'a T, A)>,
 }
 
+impl<'a, T, A> Storage<'a, T, A>
+where
+    T: ?Sized,
+    A: Readable,
+{
+    /// Converts this binding into a read-only binding for the same buffer region.
+    ///
+    /// For a binding that is already read-only, this returns an equivalent binding.
+    pub fn downgrade(self) -> Storage<'a, T, Read> {
+        Storage {
+            inner: self.inner,
+            #[cfg(feature = "usage_validation")]
+            buffer_id: self.buffer_id,
+            offset: self.offset,
+            size: self.size,
+            _marker: Default::default(),
+        }
+    }
+}
+
+impl<'a, T> From<Storage<'a, T, ReadWrite>> for Storage<'a, T, Read>
+where
+    T: ?Sized,
+{
+    fn from(storage: Storage<'a, T, ReadWrite>) -> Self {
+        storage.downgrade()
+    }
+}
+
 pub(crate) fn image_copy_buffer_validate(
     image_copy_buffer: &ImageCopyBuffer<Dvr>,
     size: (u32, u32, u32),
@@ -1386,12 +1984,14 @@ pub(crate) fn image_copy_buffer_validate(
         height_in_blocks
     );
 
-    let min_size =
-        image_copy_buffer.blocks_per_row * image_copy_buffer.rows_per_image * depth_or_layers;
+    let min_size = image_copy_buffer.bytes_per_block
+        * image_copy_buffer.blocks_per_row
+        * image_copy_buffer.rows_per_image
+        * depth_or_layers;
 
     assert!(
         image_copy_buffer.size >= min_size as usize,
-        "buffer view must contains enough elements for the copy size (`{}` blocks)",
+        "buffer view must contains enough bytes for the copy size (`{}` bytes)",
         min_size
     );
 }
@@ -1489,6 +2089,7 @@ where
 {
     let BufferInternal {
         handle: inner,
+        allocation,
         id,
         map_context,
         usage,
@@ -1500,6 +2101,7 @@ where
     Buffer {
         internal: BufferInternal {
             handle: inner,
+            allocation,
             id,
             len: size_in_bytes,
             map_context,
@@ -1516,6 +2118,7 @@ where
 {
     let BufferInternal {
         handle: inner,
+        allocation,
         id,
         map_context,
         usage,
@@ -1527,6 +2130,7 @@ where
     Buffer {
         internal: BufferInternal {
             handle: inner,
+            allocation,
             id,
             len: size_in_bytes,
             map_context,
@@ -1543,6 +2147,7 @@ where
 {
     let BufferInternal {
         handle: inner,
+        allocation,
         id,
         map_context,
         usage,
@@ -1557,6 +2162,7 @@ where
         Ok(Buffer {
             internal: BufferInternal {
                 handle: inner,
+                allocation,
                 id,
                 len: 1,
                 map_context,
@@ -1586,6 +2192,7 @@ where
 {
     let BufferInternal {
         handle: inner,
+        allocation,
         id,
         map_context,
         usage,
@@ -1600,6 +2207,7 @@ where
         Ok(Buffer {
             internal: BufferInternal {
                 handle: inner,
+                allocation,
                 id,
                 len: len / size_in_bytes,
                 map_context,
@@ -1731,7 +2339,7 @@ where
 
     let size_in_bytes = mem::size_of::<T>();
 
-    if len != size_in_bytes {
+    if len != size_in_bytes || offset_in_bytes % mem::align_of::<T>() != 0 {
         Err(CastError)
     } else {
         Ok(View {
@@ -1751,7 +2359,10 @@ where
     if let Ok(ok) = try_view_from_bytes(view) {
         ok
     } else {
-        panic!("the length of the byte slice must be equal to the target type's size in bytes");
+        panic!(
+            "the length of the byte slice must be equal to the target type's size in bytes and \
+            its offset must be a multiple of the target type's alignment"
+        );
     }
 }
 
@@ -1769,7 +2380,7 @@ where
 
     let size_in_bytes = mem::size_of::<T>();
 
-    if len.rem(size_in_bytes) != 0 {
+    if len.rem(size_in_bytes) != 0 || offset_in_bytes % mem::align_of::<T>() != 0 {
         Err(CastError)
     } else {
         Ok(View {
@@ -1790,7 +2401,8 @@ where
         ok
     } else {
         panic!(
-            "the length of the byte slice must be a multiple of the target type's size in bytes"
+            "the length of the byte slice must be a multiple of the target type's size in bytes \
+            and its offset must be a multiple of the target type's alignment"
         );
     }
 }
@@ -1,6 +1,6 @@
 use flagset::FlagSet;
 
-use crate::driver::BufferUsage;
+pub use crate::driver::BufferUsage;
 use crate::type_flag::{TypeFlag, O, X};
 
 mod usage_flags_seal {
@@ -11,6 +11,9 @@ mod usage_flags_seal {
     pub trait Seal {
         #[doc(hidden)]
         const FLAG_SET: FlagSet<BufferUsage>;
+
+        #[doc(hidden)]
+        const INSTANCE: Self;
     }
 }
 
@@ -143,6 +146,10 @@ impl<
         MapRead,
     >
 {
+    const INSTANCE: Self = Usages {
+        _marker: std::marker::PhantomData,
+    };
+
     const FLAG_SET: FlagSet<BufferUsage> = {
         let mut bits = 0;
 
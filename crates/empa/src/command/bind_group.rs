@@ -14,10 +14,18 @@ mod bind_groups_seal {
     pub trait Seal {}
 }
 
+/// A set of bind groups that may be bound with
+/// [set_bind_groups](crate::command::ResourceBindingCommandEncoder::set_bind_groups).
+///
+/// A single bind group reference sets the bind group for group `0`. A tuple of up to 4
+/// [BindGroupSlot]s sets the bind groups for the corresponding group indices; use `()` to skip a
+/// group index that the pipeline's shaders do not use (e.g. `(&group_0, (), &group_2)`).
 pub trait BindGroups: bind_groups_seal::Seal {
     type Layout: TypedPipelineLayout;
 
-    type Encodings: Iterator<Item = BindGroupEncoding>;
+    /// For each group index, the encoding of the bind group for that index, or `None` if the group
+    /// index was skipped.
+    type Encodings: Iterator<Item = Option<BindGroupEncoding>>;
 
     fn encodings(&self) -> Self::Encodings;
 }
@@ -25,29 +33,77 @@ pub trait BindGroups: bind_groups_seal::Seal {
 impl bind_groups_seal::Seal for () {}
 impl BindGroups for () {
     type Layout = ();
-    type Encodings = iter::Empty<BindGroupEncoding>;
+    type Encodings = iter::Empty<Option<BindGroupEncoding>>;
 
     fn encodings(&self) -> Self::Encodings {
         iter::empty()
     }
 }
 
+impl<'a, B> bind_groups_seal::Seal for &'a BindGroup<B> where B: TypedBindGroupLayout {}
+impl<'a, B> BindGroups for &'a BindGroup<B>
+where
+    B: TypedBindGroupLayout,
+{
+    type Layout = (B,);
+
+    type Encodings = iter::Once<Option<BindGroupEncoding>>;
+
+    fn encodings(&self) -> Self::Encodings {
+        iter::once(Some(self.to_encoding()))
+    }
+}
+
+mod bind_group_slot_seal {
+    pub trait Seal {}
+}
+
+/// A single element of a tuple of [BindGroups]: either a reference to a bind group, or `()` for a
+/// group index that is not used.
+///
+/// A skipped group index corresponds to a `()` placeholder in the pipeline's layout; the
+/// pipeline's shaders must not use any bindings in that group.
+pub trait BindGroupSlot: bind_group_slot_seal::Seal {
+    type Layout: TypedBindGroupLayout;
+
+    fn encoding(&self) -> Option<BindGroupEncoding>;
+}
+
+impl<'a, B> bind_group_slot_seal::Seal for &'a BindGroup<B> where B: TypedBindGroupLayout {}
+impl<'a, B> BindGroupSlot for &'a BindGroup<B>
+where
+    B: TypedBindGroupLayout,
+{
+    type Layout = B;
+
+    fn encoding(&self) -> Option<BindGroupEncoding> {
+        Some(self.to_encoding())
+    }
+}
+
+impl bind_group_slot_seal::Seal for () {}
+impl BindGroupSlot for () {
+    type Layout = ();
+
+    fn encoding(&self) -> Option<BindGroupEncoding> {
+        None
+    }
+}
+
 macro_rules! impl_bind_groups {
     ($n:literal, $($B:ident),*) => {
-        #[allow(unused_parens)]
-        impl<'a, $($B),*> bind_groups_seal::Seal for ($(&'a BindGroup<$B>),*) where $($B: TypedBindGroupLayout),* {}
+        impl<$($B),*> bind_groups_seal::Seal for ($($B,)*) where $($B: BindGroupSlot),* {}
 
-        #[allow(unused_parens)]
-        impl<'a, $($B),*> BindGroups for ($(&'a BindGroup<$B>),*) where $($B: TypedBindGroupLayout),* {
-            type Layout = ($($B,)*);
+        impl<$($B),*> BindGroups for ($($B,)*) where $($B: BindGroupSlot),* {
+            type Layout = ($($B::Layout,)*);
 
-            type Encodings = <[BindGroupEncoding; $n] as IntoIterator>::IntoIter;
+            type Encodings = <[Option<BindGroupEncoding>; $n] as IntoIterator>::IntoIter;
 
             fn encodings(&self) -> Self::Encodings {
                 #[allow(non_snake_case)]
-                let ($($B),*) = self;
+                let ($($B,)*) = self;
 
-                [$($B.to_encoding()),*].into_iter()
+                [$($B.encoding()),*].into_iter()
             }
         }
     }
@@ -57,3 +113,228 @@ impl_bind_groups!(1, B0);
 impl_bind_groups!(2, B0, B1);
 impl_bind_groups!(3, B0, B1, B2);
 impl_bind_groups!(4, B0, B1, B2, B3);
+
+/// Marks a group index for which no bind group has been set, see [BoundBindGroups].
+pub struct Unset;
+
+mod bound_bind_groups_seal {
+    pub trait Seal {}
+}
+
+/// The [BindGroupSlot]s of a set of [BindGroups], per group index.
+///
+/// Group indices that the bind groups do not cover are [Unset].
+pub trait BoundBindGroups: bound_bind_groups_seal::Seal {
+    type Group0;
+
+    type Group1;
+
+    type Group2;
+
+    type Group3;
+}
+
+impl bound_bind_groups_seal::Seal for () {}
+impl BoundBindGroups for () {
+    type Group0 = Unset;
+    type Group1 = Unset;
+    type Group2 = Unset;
+    type Group3 = Unset;
+}
+
+impl<'a, B> bound_bind_groups_seal::Seal for &'a BindGroup<B> where B: TypedBindGroupLayout {}
+impl<'a, B> BoundBindGroups for &'a BindGroup<B>
+where
+    B: TypedBindGroupLayout,
+{
+    type Group0 = Self;
+    type Group1 = Unset;
+    type Group2 = Unset;
+    type Group3 = Unset;
+}
+
+macro_rules! impl_bound_bind_groups {
+    ($($B:ident),*; $G0:ty, $G1:ty, $G2:ty, $G3:ty) => {
+        impl<$($B),*> bound_bind_groups_seal::Seal for ($($B,)*) where $($B: BindGroupSlot),* {}
+
+        impl<$($B),*> BoundBindGroups for ($($B,)*) where $($B: BindGroupSlot),* {
+            type Group0 = $G0;
+            type Group1 = $G1;
+            type Group2 = $G2;
+            type Group3 = $G3;
+        }
+    }
+}
+
+impl_bound_bind_groups!(B0; B0, Unset, Unset, Unset);
+impl_bound_bind_groups!(B0, B1; B0, B1, Unset, Unset);
+impl_bound_bind_groups!(B0, B1, B2; B0, B1, B2, Unset);
+impl_bound_bind_groups!(B0, B1, B2, B3; B0, B1, B2, B3);
+
+mod reuse_bind_groups_seal {
+    pub trait Seal<L> {}
+}
+
+/// Implemented for bound bind groups that remain valid for a pipeline with the pipeline layout
+/// `L`, because the layout of the bind group at each of the group indices of `L` is identical to
+/// the bind group layout `L` declares for that index.
+///
+/// The [Reused](ReuseBindGroups::Reused) bind groups are the bind groups at the group indices of
+/// `L`; bind groups at higher group indices remain bound, but are not used by the pipeline.
+pub trait ReuseBindGroups<L>: reuse_bind_groups_seal::Seal<L> {
+    type Reused: BindGroups<Layout = L>;
+}
+
+impl<R, L> reuse_bind_groups_seal::Seal<L> for R where R: BoundBindGroups {}
+
+impl<R> ReuseBindGroups<()> for R
+where
+    R: BoundBindGroups,
+{
+    type Reused = ();
+}
+
+impl<R, L0> ReuseBindGroups<(L0,)> for R
+where
+    R: BoundBindGroups,
+    R::Group0: BindGroupSlot<Layout = L0>,
+{
+    type Reused = (R::Group0,);
+}
+
+impl<R, L0, L1> ReuseBindGroups<(L0, L1)> for R
+where
+    R: BoundBindGroups,
+    R::Group0: BindGroupSlot<Layout = L0>,
+    R::Group1: BindGroupSlot<Layout = L1>,
+{
+    type Reused = (R::Group0, R::Group1);
+}
+
+impl<R, L0, L1, L2> ReuseBindGroups<(L0, L1, L2)> for R
+where
+    R: BoundBindGroups,
+    R::Group0: BindGroupSlot<Layout = L0>,
+    R::Group1: BindGroupSlot<Layout = L1>,
+    R::Group2: BindGroupSlot<Layout = L2>,
+{
+    type Reused = (R::Group0, R::Group1, R::Group2);
+}
+
+impl<R, L0, L1, L2, L3> ReuseBindGroups<(L0, L1, L2, L3)> for R
+where
+    R: BoundBindGroups,
+    R::Group0: BindGroupSlot<Layout = L0>,
+    R::Group1: BindGroupSlot<Layout = L1>,
+    R::Group2: BindGroupSlot<Layout = L2>,
+    R::Group3: BindGroupSlot<Layout = L3>,
+{
+    type Reused = (R::Group0, R::Group1, R::Group2, R::Group3);
+}
+
+/// Keeps the bind group that is currently bound at a group index when updating bind groups with
+/// [update_bind_groups](crate::command::ResourceBindingCommandEncoder::update_bind_groups).
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct KeepBound;
+
+mod update_bind_group_slot_seal {
+    pub trait Seal<Current> {}
+}
+
+/// A single element of a tuple of [UpdateBindGroups]: a reference to a bind group, `()` for a
+/// group index that is not used, or [KeepBound] to keep the `Current` bind group.
+pub trait UpdateBindGroupSlot<Current>: update_bind_group_slot_seal::Seal<Current> {
+    type Updated: BindGroupSlot;
+
+    /// The encoding of the bind group for this slot (as with [BindGroupSlot::encoding]), or `None`
+    /// if the slot keeps its current bind group.
+    fn update_encoding(&self) -> Option<Option<BindGroupEncoding>>;
+}
+
+impl<Current> update_bind_group_slot_seal::Seal<Current> for KeepBound {}
+impl<Current> UpdateBindGroupSlot<Current> for KeepBound
+where
+    Current: BindGroupSlot,
+{
+    type Updated = Current;
+
+    fn update_encoding(&self) -> Option<Option<BindGroupEncoding>> {
+        None
+    }
+}
+
+impl<'a, B, Current> update_bind_group_slot_seal::Seal<Current> for &'a BindGroup<B> where
+    B: TypedBindGroupLayout
+{
+}
+impl<'a, B, Current> UpdateBindGroupSlot<Current> for &'a BindGroup<B>
+where
+    B: TypedBindGroupLayout,
+{
+    type Updated = Self;
+
+    fn update_encoding(&self) -> Option<Option<BindGroupEncoding>> {
+        Some(Some(self.to_encoding()))
+    }
+}
+
+impl<Current> update_bind_group_slot_seal::Seal<Current> for () {}
+impl<Current> UpdateBindGroupSlot<Current> for () {
+    type Updated = ();
+
+    fn update_encoding(&self) -> Option<Option<BindGroupEncoding>> {
+        Some(None)
+    }
+}
+
+mod update_bind_groups_seal {
+    pub trait Seal<R> {}
+}
+
+/// A tuple of up to 4 [UpdateBindGroupSlot]s that updates the bound bind groups `R` with
+/// [update_bind_groups](crate::command::ResourceBindingCommandEncoder::update_bind_groups).
+///
+/// Like a tuple of [BindGroups], except that a slot may be [KeepBound] to keep the bind group
+/// that is currently bound at that group index, e.g. `(KeepBound, &material_group)`.
+pub trait UpdateBindGroups<R>: update_bind_groups_seal::Seal<R> {
+    type Updated: BindGroups;
+
+    /// For each group index, the encoding of the new bind group for that index (as with
+    /// [BindGroups::encodings]), or `None` if the group index keeps its current bind group.
+    type Encodings: Iterator<Item = Option<Option<BindGroupEncoding>>>;
+
+    fn update_encodings(&self) -> Self::Encodings;
+}
+
+macro_rules! impl_update_bind_groups {
+    ($n:literal, $($B:ident: $G:ident),*) => {
+        impl<R, $($B),*> update_bind_groups_seal::Seal<R> for ($($B,)*)
+        where
+            R: BoundBindGroups,
+            $($B: UpdateBindGroupSlot<R::$G>),*
+        {
+        }
+
+        impl<R, $($B),*> UpdateBindGroups<R> for ($($B,)*)
+        where
+            R: BoundBindGroups,
+            $($B: UpdateBindGroupSlot<R::$G>),*
+        {
+            type Updated = ($($B::Updated,)*);
+
+            type Encodings = <[Option<Option<BindGroupEncoding>>; $n] as IntoIterator>::IntoIter;
+
+            fn update_encodings(&self) -> Self::Encodings {
+                #[allow(non_snake_case)]
+                let ($($B,)*) = self;
+
+                [$($B.update_encoding()),*].into_iter()
+            }
+        }
+    }
+}
+
+impl_update_bind_groups!(1, B0: Group0);
+impl_update_bind_groups!(2, B0: Group0, B1: Group1);
+impl_update_bind_groups!(3, B0: Group0, B1: Group1, B2: Group2);
+impl_update_bind_groups!(4, B0: Group0, B1: Group1, B2: Group2, B3: Group3);
@@ -5,8 +5,8 @@ use std::{marker, mem};
 use crate::abi::{MemoryUnit, MemoryUnitLayout};
 use crate::buffer::image_copy_buffer_validate;
 use crate::command::{
-    BindGroupEncoding, BindGroups, IndexBuffer, IndexBufferEncoding, VertexBufferEncoding,
-    VertexBuffers,
+    BindGroups, IndexBuffer, IndexBufferEncoding, ReuseBindGroups, UpdateBindGroups,
+    VertexBufferEncoding, VertexBuffers,
 };
 use crate::compute_pipeline::ComputePipeline;
 use crate::device::Device;
@@ -17,29 +17,48 @@ use crate::driver::{
     RenderBundleEncoder as _, RenderEncoder, RenderPassEncoder as _, ResolveQuerySet,
     SetIndexBuffer, SetVertexBuffer,
 };
-use crate::query::{OcclusionQuerySet, TimestampQuerySet};
+#[cfg(not(any(feature = "web", feature = "noop")))]
+use crate::query::PipelineStatisticsQuerySet;
+use crate::query::{OcclusionQuerySet, TimestampQuerySet, QUERY_RESOLVE_ALIGNMENT};
 use crate::render_pipeline::{PipelineIndexFormat, PipelineIndexFormatCompatible, RenderPipeline};
 use crate::render_target::{
-    MultisampleRenderLayout, ReadOnly, RenderLayout, RenderLayoutCompatible, TypedColorLayout,
-    TypedMultisampleColorLayout, ValidRenderTarget,
+    MultisampleRenderLayout, ReadOnly, RenderLayout, RenderLayoutCompatible, SampleCount,
+    TypedColorLayout, TypedMultisampleColorLayout, TypedRenderLayout, ValidRenderTarget,
 };
-use crate::texture::format::{DepthStencilRenderable, ImageData, TextureFormat, TextureFormatId};
-use crate::texture::ImageCopySize3D;
+use crate::texture::format::{
+    CopyCompatible, DepthStencilRenderable, ImageBufferDataFormat, ImageCopyTextureFormat,
+    ImageData, TextureFormat, TextureFormatId,
+};
+use crate::texture::{ImageCopySize3D, ImageDataLayout};
 use crate::type_flag::{TypeFlag, O, X};
+#[cfg(feature = "usage_validation")]
+use crate::usage_validation::{BufferUsageKind, UsageValidator};
 use crate::{abi, buffer, driver, texture};
 
 pub struct CommandBuffer {
     pub(crate) handle: <Dvr as Driver>::CommandBufferHandle,
 }
 
+/// The bind group ID that is tracked for group indices that were skipped with a `()`
+/// placeholder; bind group IDs generated for actual bind groups never reach this value.
+const EMPTY_BIND_GROUP_ID: usize = usize::MAX;
+
 pub struct CommandEncoder {
     handle: <Dvr as Driver>::CommandEncoderHandle,
+    max_workgroups_per_dimension: u32,
+    empty_bind_group: <Dvr as Driver>::BindGroupHandle,
+    #[cfg(feature = "usage_validation")]
+    usage_validator: UsageValidator,
 }
 
 impl CommandEncoder {
     pub(crate) fn new(device: &Device) -> Self {
         CommandEncoder {
-            handle: device.device_handle.create_command_encoder(),
+            handle: device.device_handle().create_command_encoder(),
+            max_workgroups_per_dimension: device.limits().max_compute_workgroups_per_dimension,
+            empty_bind_group: device.empty_bind_group().clone(),
+            #[cfg(feature = "usage_validation")]
+            usage_validator: UsageValidator::new(),
         }
     }
 
@@ -377,11 +396,75 @@ impl CommandEncoder {
         self.sub_image_copy_texture_to_buffer_internal(src.inner, dst.inner, size)
     }
 
-    pub fn image_copy_texture_to_texture<F>(
+    /// Copies the image data in `src` to the `dst` texture, where the `src` data is laid out with
+    /// [ImageDataLayout::padded].
+    ///
+    /// Panics if the element size of `T` does not match the block size of format `F`, or if `src`
+    /// does not contain enough elements for the `dst` image.
+    pub fn copy_buffer_to_texture<T, U, F>(
+        self,
+        src: buffer::View<[T], U>,
+        dst: texture::ImageCopyDst<F>,
+    ) -> Self
+    where
+        T: ImageData<F>,
+        U: buffer::CopySrc,
+        F: ImageBufferDataFormat,
+    {
+        assert_eq!(
+            mem::size_of::<T>() as u32,
+            F::BYTES_PER_BLOCK,
+            "size of the element type must match the format's bytes per block"
+        );
+
+        let layout = ImageDataLayout::padded::<F>(ImageCopySize3D {
+            width: dst.inner.width,
+            height: dst.inner.height,
+            depth_or_layers: dst.inner.depth_or_layers,
+        });
+
+        self.image_copy_buffer_to_texture(src.image_copy_src(layout), dst)
+    }
+
+    /// Copies the image data in the `src` texture to `dst`, where the `dst` data is laid out with
+    /// [ImageDataLayout::padded].
+    ///
+    /// Panics if the element size of `T` does not match the block size of format `F`, or if `dst`
+    /// does not contain enough elements for the `src` image.
+    pub fn copy_texture_to_buffer<F, T, U>(
+        self,
+        src: texture::ImageCopySrc<F>,
+        dst: buffer::View<[T], U>,
+    ) -> Self
+    where
+        F: ImageBufferDataFormat,
+        T: ImageData<F>,
+        U: buffer::CopyDst,
+    {
+        assert_eq!(
+            mem::size_of::<T>() as u32,
+            F::BYTES_PER_BLOCK,
+            "size of the element type must match the format's bytes per block"
+        );
+
+        let layout = ImageDataLayout::padded::<F>(ImageCopySize3D {
+            width: src.inner.width,
+            height: src.inner.height,
+            depth_or_layers: src.inner.depth_or_layers,
+        });
+
+        self.image_copy_texture_to_buffer(src, dst.image_copy_dst(layout))
+    }
+
+    pub fn image_copy_texture_to_texture<F0, F1>(
         mut self,
-        src: texture::ImageCopyToTextureSrc<F>,
-        dst: texture::ImageCopyFromTextureDst<F>,
-    ) -> Self {
+        src: texture::ImageCopyToTextureSrc<F0>,
+        dst: texture::ImageCopyFromTextureDst<F1>,
+    ) -> Self
+    where
+        F0: ImageCopyTextureFormat,
+        F1: CopyCompatible<F0>,
+    {
         assert!(
             src.inner.width == dst.inner.width,
             "`src` and `dst` widths must match"
@@ -404,12 +487,16 @@ impl CommandEncoder {
         self
     }
 
-    pub fn sub_image_copy_texture_to_texture<F>(
+    pub fn sub_image_copy_texture_to_texture<F0, F1>(
         mut self,
-        src: texture::SubImageCopyToTextureSrc<F>,
-        dst: texture::SubImageCopyFromTextureDst<F>,
+        src: texture::SubImageCopyToTextureSrc<F0>,
+        dst: texture::SubImageCopyFromTextureDst<F1>,
         size: ImageCopySize3D,
-    ) -> Self {
+    ) -> Self
+    where
+        F0: ImageCopyTextureFormat,
+        F1: CopyCompatible<F0>,
+    {
         size.validate_with_block_size(src.inner.block_size);
         src.inner.validate_src_with_size(size);
         dst.inner.validate_dst_with_size(size);
@@ -446,6 +533,7 @@ impl CommandEncoder {
         self
     }
 
+    #[cfg(feature = "compute")]
     pub fn begin_compute_pass(mut self) -> ComputePassEncoder<(), ()> {
         let handle = self.handle.begin_compute_pass();
 
@@ -458,6 +546,7 @@ impl CommandEncoder {
         }
     }
 
+    #[cfg(feature = "render")]
     pub fn begin_render_pass<T, Q>(
         mut self,
         descriptor: RenderPassDescriptor<T, Q>,
@@ -465,6 +554,9 @@ impl CommandEncoder {
     where
         T: ValidRenderTarget,
     {
+        #[cfg(feature = "usage_validation")]
+        self.usage_validator.begin_scope("render pass");
+
         let handle = self.handle.begin_render_pass(driver::RenderPassDescriptor {
             color_attachments: descriptor
                 .render_target
@@ -485,18 +577,38 @@ impl CommandEncoder {
             current_vertex_buffers: [None, None, None, None, None, None, None, None],
             current_index_buffer: None,
             current_bind_group_ids: [None; 4],
+            occlusion_query_set_len: descriptor.occlusion_query_set_len,
             _marker: Default::default(),
         }
     }
 
+    /// Writes a timestamp to the query at `index` of the `query_set` once all previously recorded
+    /// commands have completed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `index` is out of bounds for the `query_set`.
     pub fn write_timestamp(mut self, query_set: &TimestampQuerySet, index: usize) -> Self {
-        assert!(index < query_set.len(), "index out of bounds");
+        assert!(
+            index < query_set.len(),
+            "query index `{}` out of bounds for a timestamp query set of length `{}`",
+            index,
+            query_set.len()
+        );
 
         self.handle.write_timestamp(&query_set.handle, index);
 
         self
     }
 
+    /// Resolves the occlusion queries starting at `offset` into the `view`.
+    ///
+    /// The number of queries resolved is the length of the `view`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the resolve range is out of bounds, or if the `view` does not start at a multiple
+    /// of [QUERY_RESOLVE_ALIGNMENT] bytes into its buffer.
     pub fn resolve_occlusion_query_set<U>(
         mut self,
         query_set: &OcclusionQuerySet,
@@ -510,6 +622,11 @@ impl CommandEncoder {
         let end = start + view.len();
 
         assert!(end <= query_set.len(), "resolve range out of bounds");
+        assert!(
+            view.offset_in_bytes() % QUERY_RESOLVE_ALIGNMENT == 0,
+            "the resolve destination offset must be a multiple of `{}` bytes",
+            QUERY_RESOLVE_ALIGNMENT
+        );
 
         self.handle.resolve_query_set(ResolveQuerySet {
             query_set: &query_set.handle,
@@ -521,6 +638,23 @@ impl CommandEncoder {
         self
     }
 
+    /// Resolves the timestamp queries starting at `offset` into the `view`.
+    ///
+    /// The number of queries resolved is the length of the `view`. To resolve an arbitrary range of
+    /// queries into an arbitrary region of a buffer, pass the start of the range as the `offset`
+    /// and a sub-slice of the buffer as the `view`:
+    ///
+    /// ```ignore
+    /// // Resolves queries `4..8` into elements `32..36` of the resolve buffer.
+    /// let region = resolve_buffer.view().get(32..36).unwrap();
+    ///
+    /// encoder.resolve_timestamp_query_set(&query_set, 4, region)
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the resolve range is out of bounds, or if the `view` does not start at a multiple
+    /// of [QUERY_RESOLVE_ALIGNMENT] bytes into its buffer.
     pub fn resolve_timestamp_query_set<U>(
         mut self,
         query_set: &TimestampQuerySet,
@@ -534,6 +668,126 @@ impl CommandEncoder {
         let end = start + view.len();
 
         assert!(end <= query_set.len(), "resolve range out of bounds");
+        assert!(
+            view.offset_in_bytes() % QUERY_RESOLVE_ALIGNMENT == 0,
+            "the resolve destination offset must be a multiple of `{}` bytes",
+            QUERY_RESOLVE_ALIGNMENT
+        );
+
+        self.handle.resolve_query_set(ResolveQuerySet {
+            query_set: &query_set.handle,
+            query_range: start..end,
+            destination: &view.buffer.handle,
+            destination_offset: view.offset_in_bytes(),
+        });
+
+        self
+    }
+
+    /// Resolves the timestamp queries in the `range` of each of the given query sets into a single
+    /// `view`, e.g. for frame-wide profiling across several passes.
+    ///
+    /// Because resolve destinations must be aligned to [QUERY_RESOLVE_ALIGNMENT] bytes, the
+    /// results for each query set start at the next multiple of
+    /// `QUERY_RESOLVE_ALIGNMENT / size_of::<u64>()` elements after the results for the preceding
+    /// query set. Returns the element offset into the `view` at which the results for each query
+    /// set start, in order, alongside the encoder.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the ranges is out of bounds for its query set, if the `view` does not
+    /// start at a multiple of [QUERY_RESOLVE_ALIGNMENT] bytes into its buffer, or if the `view` is
+    /// too small to hold all results.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let (encoder, offsets) = encoder.resolve_timestamp_query_sets(
+    ///     [(&shadow_queries, 0..2), (&scene_queries, 0..4)],
+    ///     resolve_buffer.view(),
+    /// );
+    /// ```
+    pub fn resolve_timestamp_query_sets<'b, I, U>(
+        self,
+        query_sets: I,
+        view: buffer::View<[u64], U>,
+    ) -> (Self, Vec<usize>)
+    where
+        I: IntoIterator<Item = (&'b TimestampQuerySet, Range<usize>)>,
+        U: buffer::QueryResolve,
+    {
+        let stride = QUERY_RESOLVE_ALIGNMENT / mem::size_of::<u64>();
+
+        let mut encoder = self;
+        let mut offsets = Vec::new();
+        let mut next_offset = 0;
+
+        for (query_set, range) in query_sets {
+            assert!(
+                range.start <= range.end && range.end <= query_set.len(),
+                "resolve range out of bounds"
+            );
+
+            let start = next_offset;
+            let end = start + range.len();
+
+            let region = view
+                .get(start..end)
+                .expect("`view` is too small to hold the resolved queries of all query sets");
+
+            if !range.is_empty() {
+                encoder = encoder.resolve_timestamp_query_set(query_set, range.start, region);
+            }
+
+            offsets.push(start);
+
+            next_offset = end.div_ceil(stride) * stride;
+        }
+
+        (encoder, offsets)
+    }
+
+    /// Resolves the pipeline statistics queries starting at `offset` into the `view`.
+    ///
+    /// Each query resolves to one `u64` value for each selected statistic (see
+    /// [PipelineStatisticsTypes::count](crate::query::PipelineStatisticsTypes::count)), so the
+    /// length of the `view` must be a multiple of that count; use
+    /// [PipelineStatisticsQuerySet::decode] to interpret the resolved values.
+    ///
+    /// Only available on native targets.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the resolve range is out of bounds, or if the length of the `view` is not a
+    /// multiple of the number of selected statistics.
+    #[cfg(not(any(feature = "web", feature = "noop")))]
+    pub fn resolve_pipeline_statistics_query_set<U>(
+        mut self,
+        query_set: &PipelineStatisticsQuerySet,
+        offset: usize,
+        view: buffer::View<[u64], U>,
+    ) -> Self
+    where
+        U: buffer::QueryResolve,
+    {
+        let count = query_set.statistics().count();
+
+        assert!(
+            view.len() % count == 0,
+            "the view length (`{}`) must be a multiple of the number of statistics per query (`{}`)",
+            view.len(),
+            count
+        );
+
+        let start = offset;
+        let end = start + view.len() / count;
+
+        assert!(end <= query_set.len(), "resolve range out of bounds");
+        assert!(
+            view.offset_in_bytes() % QUERY_RESOLVE_ALIGNMENT == 0,
+            "the resolve destination offset must be a multiple of `{}` bytes",
+            QUERY_RESOLVE_ALIGNMENT
+        );
 
         self.handle.resolve_query_set(ResolveQuerySet {
             query_set: &query_set.handle,
@@ -557,11 +811,39 @@ mod resource_binding_command_encoder_seal {
 }
 
 pub trait ResourceBindingCommandEncoder: resource_binding_command_encoder_seal::Seal {
+    /// The bind groups that are currently bound.
+    type Resources;
+
     type WithResources<RNew>;
 
+    /// Sets the bind groups for subsequent commands.
+    ///
+    /// The layouts of the bind groups must match the bind group layouts of the pipeline layout
+    /// of the current pipeline exactly. To use a bind group created for a different but
+    /// compatible layout, first [cast](crate::resource_binding::BindGroup::cast) it; if the
+    /// layouts are not compatible, the cast reports the binding at which they differ.
     fn set_bind_groups<RNew>(self, bind_groups: RNew) -> Self::WithResources<RNew>
     where
         RNew: BindGroups;
+
+    /// Sets the bind groups for subsequent commands, keeping the currently bound bind group for
+    /// each group index that is set to [KeepBound](crate::command::KeepBound).
+    ///
+    /// This allows updating only the bind groups that change, e.g. after switching to a pipeline
+    /// that shares the bind group layout for group `0` with the previous pipeline, but uses a
+    /// different bind group layout for group `1`:
+    ///
+    /// ```ignore
+    /// let encoder = encoder
+    ///     .set_pipeline(&textured_pipeline)
+    ///     .update_bind_groups((KeepBound, &material_group));
+    /// ```
+    ///
+    /// As with [set_bind_groups](Self::set_bind_groups), the layouts of the resulting bind groups
+    /// must match the bind group layouts of the pipeline layout of the current pipeline.
+    fn update_bind_groups<RNew>(self, bind_groups: RNew) -> Self::WithResources<RNew::Updated>
+    where
+        RNew: UpdateBindGroups<Self::Resources>;
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
@@ -572,6 +854,83 @@ pub struct DispatchWorkgroups {
     pub count_z: u32,
 }
 
+impl DispatchWorkgroups {
+    /// Returns a dispatch of at least `workgroup_count` workgroups, where no dimension exceeds
+    /// `max_per_dimension` workgroups.
+    ///
+    /// If `workgroup_count` does not exceed `max_per_dimension`, the dispatch is one-dimensional.
+    /// Otherwise the X dimension is set to `max_per_dimension` and the remaining workgroups are
+    /// spread over the Y and (if necessary) Z dimensions. The resulting dispatch may contain more
+    /// workgroups than requested; a kernel can recover the linear workgroup index as
+    /// `(workgroup_id.z * num_workgroups.y + workgroup_id.y) * num_workgroups.x + workgroup_id.x`
+    /// and should discard invocations for which the linear index is out of range.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `workgroup_count` exceeds `max_per_dimension` cubed.
+    pub fn linear(workgroup_count: u32, max_per_dimension: u32) -> Self {
+        if workgroup_count <= max_per_dimension {
+            return DispatchWorkgroups {
+                count_x: workgroup_count,
+                count_y: 1,
+                count_z: 1,
+            };
+        }
+
+        let rows = workgroup_count.div_ceil(max_per_dimension);
+
+        if rows <= max_per_dimension {
+            return DispatchWorkgroups {
+                count_x: max_per_dimension,
+                count_y: rows,
+                count_z: 1,
+            };
+        }
+
+        let layers = rows.div_ceil(max_per_dimension);
+
+        assert!(
+            layers <= max_per_dimension,
+            "`{}` workgroups cannot be dispatched with a maximum of `{}` workgroups per dimension",
+            workgroup_count,
+            max_per_dimension
+        );
+
+        DispatchWorkgroups {
+            count_x: max_per_dimension,
+            count_y: max_per_dimension,
+            count_z: layers,
+        }
+    }
+}
+
+/// A one-dimensional dispatch of `count` workgroups that is not constrained by the device's
+/// `max_compute_workgroups_per_dimension` limit.
+///
+/// See [ComputePassEncoder::dispatch_workgroups_linear].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct DispatchWorkgroupsLinear {
+    pub count: u32,
+}
+
+impl DispatchWorkgroupsLinear {
+    /// Splits the dispatch such that no dimension exceeds `max_per_dimension` workgroups.
+    ///
+    /// See [DispatchWorkgroups::linear].
+    pub fn split(&self, max_per_dimension: u32) -> DispatchWorkgroups {
+        DispatchWorkgroups::linear(self.count, max_per_dimension)
+    }
+
+    /// Creates a buffer that holds the linear workgroup `count`, e.g. for binding as a uniform
+    /// that a kernel uses to discard the excess workgroups of a split dispatch.
+    pub fn create_count_buffer<U>(&self, device: &Device, usage: U) -> buffer::Buffer<u32, U>
+    where
+        U: buffer::ValidUsageFlags,
+    {
+        device.create_buffer(self.count, usage)
+    }
+}
+
 unsafe impl abi::Sized for DispatchWorkgroups {
     const LAYOUT: &'static [MemoryUnit] = &[
         MemoryUnit {
@@ -594,11 +953,13 @@ pub struct ComputePassEncoder<Pipeline, Resources> {
     command_encoder: CommandEncoder,
     current_pipeline_id: Option<usize>,
     current_bind_group_ids: [Option<usize>; 4],
-    _marker: marker::PhantomData<(*const Pipeline, *const Resources)>,
+    _marker: marker::PhantomData<fn() -> (Pipeline, Resources)>,
 }
 
 impl<P, R> resource_binding_command_encoder_seal::Seal for ComputePassEncoder<P, R> {}
 impl<P, R> ResourceBindingCommandEncoder for ComputePassEncoder<P, R> {
+    type Resources = R;
+
     type WithResources<RNew> = ComputePassEncoder<P, RNew>;
 
     fn set_bind_groups<RNew>(self, bind_groups: RNew) -> Self::WithResources<RNew>
@@ -614,10 +975,57 @@ impl<P, R> ResourceBindingCommandEncoder for ComputePassEncoder<P, R> {
         } = self;
 
         for (i, encoding) in bind_groups.encodings().enumerate() {
-            let BindGroupEncoding {
-                bind_group_handle,
-                id,
-            } = encoding;
+            let (bind_group_handle, id) = if let Some(encoding) = encoding {
+                (encoding.bind_group_handle, encoding.id)
+            } else {
+                (
+                    command_encoder.empty_bind_group.clone(),
+                    EMPTY_BIND_GROUP_ID,
+                )
+            };
+
+            if current_bind_group_ids[i] != Some(id) {
+                handle.set_bind_group(i as u32, &bind_group_handle);
+
+                current_bind_group_ids[i] = Some(id);
+            }
+        }
+
+        ComputePassEncoder {
+            handle,
+            command_encoder,
+            current_pipeline_id,
+            current_bind_group_ids,
+            _marker: Default::default(),
+        }
+    }
+
+    fn update_bind_groups<RNew>(self, bind_groups: RNew) -> Self::WithResources<RNew::Updated>
+    where
+        RNew: UpdateBindGroups<R>,
+    {
+        let ComputePassEncoder {
+            mut handle,
+            command_encoder,
+            current_pipeline_id,
+            mut current_bind_group_ids,
+            ..
+        } = self;
+
+        for (i, encoding) in bind_groups.update_encodings().enumerate() {
+            // Keep the bind group that is currently bound.
+            let Some(encoding) = encoding else {
+                continue;
+            };
+
+            let (bind_group_handle, id) = if let Some(encoding) = encoding {
+                (encoding.bind_group_handle, encoding.id)
+            } else {
+                (
+                    command_encoder.empty_bind_group.clone(),
+                    EMPTY_BIND_GROUP_ID,
+                )
+            };
 
             if current_bind_group_ids[i] != Some(id) {
                 handle.set_bind_group(i as u32, &bind_group_handle);
@@ -662,6 +1070,42 @@ impl<P, R> ComputePassEncoder<P, R> {
         }
     }
 
+    /// Begins a pipeline statistics query that collects statistics for the dispatches that are
+    /// recorded until [end_pipeline_statistics_query](Self::end_pipeline_statistics_query) is
+    /// called, and stores the result in the query at `query_index`.
+    ///
+    /// Only available on native targets; requires
+    /// [Feature::PipelineStatisticsQuery](crate::adapter::Feature).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `query_index` is out of bounds.
+    #[cfg(not(any(feature = "web", feature = "noop")))]
+    pub fn begin_pipeline_statistics_query(
+        mut self,
+        query_set: &PipelineStatisticsQuerySet,
+        query_index: u32,
+    ) -> Self {
+        assert!(
+            (query_index as usize) < query_set.len(),
+            "query index out of bounds"
+        );
+
+        self.handle
+            .begin_pipeline_statistics_query(&query_set.handle, query_index);
+
+        self
+    }
+
+    /// Ends the pipeline statistics query that was started with
+    /// [begin_pipeline_statistics_query](Self::begin_pipeline_statistics_query).
+    #[cfg(not(any(feature = "web", feature = "noop")))]
+    pub fn end_pipeline_statistics_query(mut self) -> Self {
+        self.handle.end_pipeline_statistics_query();
+
+        self
+    }
+
     pub fn end(self) -> CommandEncoder {
         self.handle.end();
 
@@ -680,11 +1124,32 @@ where
             count_z,
         } = dispatch_workgroups;
 
+        #[cfg(feature = "usage_validation")]
+        self.validate_usages(None);
+
         self.handle.dispatch_workgroups(count_x, count_y, count_z);
 
         self
     }
 
+    /// Dispatches `dispatch.count` workgroups, splitting the dispatch over the Y and Z dimensions
+    /// if the count exceeds the device's `max_compute_workgroups_per_dimension` limit.
+    ///
+    /// When the dispatch is split, the total number of workgroups may exceed `dispatch.count`.
+    /// The kernel should compute the linear workgroup index as
+    /// `(workgroup_id.z * num_workgroups.y + workgroup_id.y) * num_workgroups.x + workgroup_id.x`
+    /// and return early if it is not less than the original count; the count can be provided to
+    /// the kernel with [DispatchWorkgroupsLinear::create_count_buffer].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the count exceeds the limit cubed.
+    pub fn dispatch_workgroups_linear(self, dispatch: DispatchWorkgroupsLinear) -> Self {
+        let max_per_dimension = self.command_encoder.max_workgroups_per_dimension;
+
+        self.dispatch_workgroups(dispatch.split(max_per_dimension))
+    }
+
     pub fn dispatch_workgroups_indirect<U>(
         mut self,
         view: buffer::View<DispatchWorkgroups, U>,
@@ -692,6 +1157,9 @@ where
     where
         U: buffer::Indirect,
     {
+        #[cfg(feature = "usage_validation")]
+        self.validate_usages(Some(view.id()));
+
         self.handle
             .dispatch_workgroups_indirect(&view.buffer.handle, view.offset_in_bytes());
 
@@ -699,7 +1167,24 @@ where
     }
 }
 
+#[cfg(feature = "usage_validation")]
+impl<P, R> ComputePassEncoder<P, R> {
+    /// Begins a new usage scope for a dispatch, and records the buffers used by the current bind
+    /// groups and by the `indirect_buffer_id`, if any.
+    fn validate_usages(&mut self, indirect_buffer_id: Option<usize>) {
+        let validator = &mut self.command_encoder.usage_validator;
+
+        validator.begin_scope("compute dispatch");
+        validator.record_bind_groups(&self.current_bind_group_ids);
+
+        if let Some(id) = indirect_buffer_id {
+            validator.record(id, BufferUsageKind::Indirect);
+        }
+    }
+}
+
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(C)]
 pub struct Draw {
     pub vertex_count: u32,
@@ -730,6 +1215,7 @@ unsafe impl abi::Sized for Draw {
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(C)]
 pub struct DrawIndexed {
     pub index_count: u32,
@@ -820,6 +1306,7 @@ pub trait DrawIndexedCommandEncoder: draw_indexed_command_encoder_seal::Seal {
 }
 
 #[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Viewport {
     pub x: f32,
     pub y: f32,
@@ -830,6 +1317,7 @@ pub struct Viewport {
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ScissorRect {
     pub x: u32,
     pub y: u32,
@@ -838,6 +1326,7 @@ pub struct ScissorRect {
 }
 
 #[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BlendConstant {
     pub r: f32,
     pub g: f32,
@@ -891,6 +1380,7 @@ impl EndRenderPass for () {}
 pub struct RenderPassDescriptor<'a, RenderTarget, OcclusionQueryState> {
     render_target: &'a RenderTarget,
     occlusion_query_set: Option<&'a <Dvr as Driver>::QuerySetHandle>,
+    occlusion_query_set_len: usize,
     _marker: marker::PhantomData<OcclusionQueryState>,
 }
 
@@ -934,9 +1424,25 @@ impl<'a> RenderPassDescriptor<'a, (), ()> {
             );
         }
 
+        let views = T::RenderLayout::LAYOUT
+            .multiview
+            .map(|v| v.get())
+            .unwrap_or(1);
+
+        for attachment in render_target.color_target_encodings() {
+            if attachment.inner.is_some() && attachment.layers != views {
+                panic!("attachment layer count must match the view count")
+            }
+        }
+
+        if depth_stencil_attachment.inner.is_some() && depth_stencil_attachment.layers != views {
+            panic!("attachment layer count must match the view count")
+        }
+
         RenderPassDescriptor {
             render_target,
             occlusion_query_set: None,
+            occlusion_query_set_len: 0,
             _marker: Default::default(),
         }
     }
@@ -950,6 +1456,7 @@ impl<'a, T> RenderPassDescriptor<'a, T, ()> {
         RenderPassDescriptor {
             render_target: self.render_target,
             occlusion_query_set: Some(&occlusion_query_set.handle),
+            occlusion_query_set_len: occlusion_query_set.len(),
             _marker: Default::default(),
         }
     }
@@ -964,14 +1471,17 @@ pub struct RenderPassEncoder<Target, Pipeline, Vertex, Index, Resources, Occlusi
     current_vertex_buffers: [Option<CurrentBufferRange>; 8],
     current_index_buffer: Option<CurrentBufferRange>,
     current_bind_group_ids: [Option<usize>; 4],
-    _marker: marker::PhantomData<(
-        *const Target,
-        *const Pipeline,
-        *const Vertex,
-        *const Index,
-        *const Resources,
-        OcclusionQueryState,
-    )>,
+    occlusion_query_set_len: usize,
+    _marker: marker::PhantomData<
+        fn() -> (
+            Target,
+            Pipeline,
+            Vertex,
+            Index,
+            Resources,
+            OcclusionQueryState,
+        ),
+    >,
 }
 
 impl<T, P, V, I, R, Q> resource_binding_command_encoder_seal::Seal
@@ -979,6 +1489,8 @@ impl<T, P, V, I, R, Q> resource_binding_command_encoder_seal::Seal
 {
 }
 impl<T, P, V, I, R, Q> ResourceBindingCommandEncoder for RenderPassEncoder<T, P, V, I, R, Q> {
+    type Resources = R;
+
     type WithResources<RNew> = RenderPassEncoder<T, P, V, I, RNew, Q>;
 
     fn set_bind_groups<RNew>(self, bind_groups: RNew) -> Self::WithResources<RNew>
@@ -992,14 +1504,19 @@ impl<T, P, V, I, R, Q> ResourceBindingCommandEncoder for RenderPassEncoder<T, P,
             current_vertex_buffers,
             current_index_buffer,
             mut current_bind_group_ids,
+            occlusion_query_set_len,
             ..
         } = self;
 
         for (i, encoding) in bind_groups.encodings().enumerate() {
-            let BindGroupEncoding {
-                bind_group_handle,
-                id,
-            } = encoding;
+            let (bind_group_handle, id) = if let Some(encoding) = encoding {
+                (encoding.bind_group_handle, encoding.id)
+            } else {
+                (
+                    command_encoder.empty_bind_group.clone(),
+                    EMPTY_BIND_GROUP_ID,
+                )
+            };
 
             if current_bind_group_ids[i] != Some(id) {
                 handle.set_bind_group(i as u32, &bind_group_handle);
@@ -1015,6 +1532,56 @@ impl<T, P, V, I, R, Q> ResourceBindingCommandEncoder for RenderPassEncoder<T, P,
             current_vertex_buffers,
             current_index_buffer,
             current_bind_group_ids,
+            occlusion_query_set_len,
+            _marker: Default::default(),
+        }
+    }
+
+    fn update_bind_groups<RNew>(self, bind_groups: RNew) -> Self::WithResources<RNew::Updated>
+    where
+        RNew: UpdateBindGroups<R>,
+    {
+        let RenderPassEncoder {
+            mut handle,
+            command_encoder,
+            current_pipeline_id,
+            current_vertex_buffers,
+            current_index_buffer,
+            mut current_bind_group_ids,
+            occlusion_query_set_len,
+            ..
+        } = self;
+
+        for (i, encoding) in bind_groups.update_encodings().enumerate() {
+            // Keep the bind group that is currently bound.
+            let Some(encoding) = encoding else {
+                continue;
+            };
+
+            let (bind_group_handle, id) = if let Some(encoding) = encoding {
+                (encoding.bind_group_handle, encoding.id)
+            } else {
+                (
+                    command_encoder.empty_bind_group.clone(),
+                    EMPTY_BIND_GROUP_ID,
+                )
+            };
+
+            if current_bind_group_ids[i] != Some(id) {
+                handle.set_bind_group(i as u32, &bind_group_handle);
+
+                current_bind_group_ids[i] = Some(id);
+            }
+        }
+
+        RenderPassEncoder {
+            handle,
+            command_encoder,
+            current_pipeline_id,
+            current_vertex_buffers,
+            current_index_buffer,
+            current_bind_group_ids,
+            occlusion_query_set_len,
             _marker: Default::default(),
         }
     }
@@ -1040,6 +1607,7 @@ impl<T, P, V, I, R, Q> RenderStateEncoder<T> for RenderPassEncoder<T, P, V, I, R
             current_vertex_buffers,
             current_index_buffer,
             current_bind_group_ids,
+            occlusion_query_set_len,
             ..
         } = self;
 
@@ -1054,6 +1622,7 @@ impl<T, P, V, I, R, Q> RenderStateEncoder<T> for RenderPassEncoder<T, P, V, I, R
             current_vertex_buffers,
             current_index_buffer,
             current_bind_group_ids,
+            occlusion_query_set_len,
             _marker: Default::default(),
         }
     }
@@ -1069,6 +1638,7 @@ impl<T, P, V, I, R, Q> RenderStateEncoder<T> for RenderPassEncoder<T, P, V, I, R
             mut current_vertex_buffers,
             current_index_buffer,
             current_bind_group_ids,
+            occlusion_query_set_len,
             ..
         } = self;
 
@@ -1098,6 +1668,7 @@ impl<T, P, V, I, R, Q> RenderStateEncoder<T> for RenderPassEncoder<T, P, V, I, R
             current_vertex_buffers,
             current_index_buffer,
             current_bind_group_ids,
+            occlusion_query_set_len,
             _marker: Default::default(),
         }
     }
@@ -1113,6 +1684,7 @@ impl<T, P, V, I, R, Q> RenderStateEncoder<T> for RenderPassEncoder<T, P, V, I, R
             current_vertex_buffers,
             mut current_index_buffer,
             current_bind_group_ids,
+            occlusion_query_set_len,
             ..
         } = self;
 
@@ -1145,13 +1717,120 @@ impl<T, P, V, I, R, Q> RenderStateEncoder<T> for RenderPassEncoder<T, P, V, I, R
             current_vertex_buffers,
             current_index_buffer,
             current_bind_group_ids,
+            occlusion_query_set_len,
             _marker: Default::default(),
         }
     }
 }
 
 impl<T, P, V, I, R, Q> RenderPassEncoder<T, P, V, I, R, Q> {
+    /// Sets the `pipeline` for subsequent draw commands, keeping the bind groups that are currently
+    /// bound.
+    ///
+    /// Unlike [set_pipeline](RenderStateEncoder::set_pipeline), this does not require setting the
+    /// bind groups again before drawing with the new pipeline, if the layout of the currently
+    /// bound bind group for each of the group indices the new pipeline uses is identical to the
+    /// pipeline's bind group layout for that index (see
+    /// [reuse_bind_groups](Self::reuse_bind_groups)). This fails to compile if the layouts are not
+    /// identical; in that case, use
+    /// [update_bind_groups](ResourceBindingCommandEncoder::update_bind_groups) after
+    /// [set_pipeline](RenderStateEncoder::set_pipeline) to replace only the bind groups that
+    /// differ.
+    pub fn set_pipeline_compatible<PT, PV, PI, PR>(
+        self,
+        pipeline: &RenderPipeline<PT, PV, PI, PR>,
+    ) -> RenderPassEncoder<T, RenderPipeline<PT, PV, PI, PR>, V, I, R::Reused, Q>
+    where
+        PT: RenderLayoutCompatible<T>,
+        R: ReuseBindGroups<PR>,
+    {
+        self.set_pipeline(pipeline).reuse_bind_groups()
+    }
+}
+
+impl<T, PT, PV, PI, PR, V, I, R, Q>
+    RenderPassEncoder<T, RenderPipeline<PT, PV, PI, PR>, V, I, R, Q>
+{
+    /// Reuses the bind groups that are currently bound for the current pipeline.
+    ///
+    /// Bind groups remain bound when switching pipelines, but the encoder's type only accepts draw
+    /// commands after the bind groups have been set for the current pipeline's layout. If the
+    /// previous pipeline bound bind groups with the same bind group layouts for the group indices
+    /// the current pipeline uses, this marks these bind groups as set for the current pipeline,
+    /// without recording any commands. Bind groups for higher group indices remain bound, but are
+    /// not used by the current pipeline.
+    ///
+    /// ```ignore
+    /// let encoder = encoder
+    ///     .set_pipeline(&lit_pipeline)
+    ///     .set_bind_groups((&camera_group, &material_group))
+    ///     .draw(draw)
+    ///     // The depth-only pipeline uses only the camera group
+    ///     .set_pipeline(&depth_pipeline)
+    ///     .reuse_bind_groups()
+    ///     .draw(draw);
+    /// ```
+    ///
+    /// Fails to compile if the layout of the bind group that is bound at any of the group indices
+    /// the current pipeline uses does not match the current pipeline's bind group layout for that
+    /// index.
+    pub fn reuse_bind_groups(
+        self,
+    ) -> RenderPassEncoder<T, RenderPipeline<PT, PV, PI, PR>, V, I, R::Reused, Q>
+    where
+        R: ReuseBindGroups<PR>,
+    {
+        let RenderPassEncoder {
+            handle,
+            command_encoder,
+            current_pipeline_id,
+            current_vertex_buffers,
+            current_index_buffer,
+            current_bind_group_ids,
+            occlusion_query_set_len,
+            ..
+        } = self;
+
+        RenderPassEncoder {
+            handle,
+            command_encoder,
+            current_pipeline_id,
+            current_vertex_buffers,
+            current_index_buffer,
+            current_bind_group_ids,
+            occlusion_query_set_len,
+            _marker: Default::default(),
+        }
+    }
+}
+
+impl<T, P, V, I, R, Q> RenderPassEncoder<T, P, V, I, R, Q> {
+    /// Sets the viewport used during the rasterization stage.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `viewport.min_depth` or `viewport.max_depth` are not in the range `0.0..=1.0`, or
+    /// if `viewport.min_depth` is greater than `viewport.max_depth`.
     pub fn set_viewport(mut self, viewport: &Viewport) -> Self {
+        let Viewport {
+            min_depth,
+            max_depth,
+            ..
+        } = *viewport;
+
+        assert!(
+            (0.0..=1.0).contains(&min_depth) && (0.0..=1.0).contains(&max_depth),
+            "viewport depth range `{}..={}` must be within `0.0..=1.0`",
+            min_depth,
+            max_depth
+        );
+        assert!(
+            min_depth <= max_depth,
+            "viewport `min_depth` (`{}`) must not be greater than `max_depth` (`{}`)",
+            min_depth,
+            max_depth
+        );
+
         self.handle.set_viewport(viewport);
 
         self
@@ -1169,12 +1848,52 @@ impl<T, P, V, I, R, Q> RenderPassEncoder<T, P, V, I, R, Q> {
         self
     }
 
+    /// Sets the reference value that the stencil test compares against, and that
+    /// [StencilOperation::Replace](crate::render_pipeline::StencilOperation::Replace) writes.
+    ///
+    /// The reference value is reset to `0` at the start of each render pass.
     pub fn set_stencil_reference(mut self, stencil_reference: u32) -> Self {
         self.handle.set_stencil_reference(stencil_reference);
 
         self
     }
 
+    /// Begins a pipeline statistics query that collects statistics for the draws that are recorded
+    /// until [end_pipeline_statistics_query](Self::end_pipeline_statistics_query) is called, and
+    /// stores the result in the query at `query_index`.
+    ///
+    /// Only available on native targets; requires
+    /// [Feature::PipelineStatisticsQuery](crate::adapter::Feature).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `query_index` is out of bounds.
+    #[cfg(not(any(feature = "web", feature = "noop")))]
+    pub fn begin_pipeline_statistics_query(
+        mut self,
+        query_set: &PipelineStatisticsQuerySet,
+        query_index: u32,
+    ) -> Self {
+        assert!(
+            (query_index as usize) < query_set.len(),
+            "query index out of bounds"
+        );
+
+        self.handle
+            .begin_pipeline_statistics_query(&query_set.handle, query_index);
+
+        self
+    }
+
+    /// Ends the pipeline statistics query that was started with
+    /// [begin_pipeline_statistics_query](Self::begin_pipeline_statistics_query).
+    #[cfg(not(any(feature = "web", feature = "noop")))]
+    pub fn end_pipeline_statistics_query(mut self) -> Self {
+        self.handle.end_pipeline_statistics_query();
+
+        self
+    }
+
     pub fn clear_state(self) -> ClearRenderPassEncoder<T, Q> {
         let RenderPassEncoder {
             handle,
@@ -1183,6 +1902,7 @@ impl<T, P, V, I, R, Q> RenderPassEncoder<T, P, V, I, R, Q> {
             current_vertex_buffers,
             current_index_buffer,
             current_bind_group_ids,
+            occlusion_query_set_len,
             ..
         } = self;
 
@@ -1193,6 +1913,7 @@ impl<T, P, V, I, R, Q> RenderPassEncoder<T, P, V, I, R, Q> {
             current_vertex_buffers,
             current_index_buffer,
             current_bind_group_ids,
+            occlusion_query_set_len,
             _marker: Default::default(),
         }
     }
@@ -1205,6 +1926,7 @@ impl<T, P, V, I, R, Q> RenderPassEncoder<T, P, V, I, R, Q> {
             current_vertex_buffers,
             current_index_buffer,
             current_bind_group_ids,
+            occlusion_query_set_len,
             ..
         } = self;
 
@@ -1220,6 +1942,7 @@ impl<T, P, V, I, R, Q> RenderPassEncoder<T, P, V, I, R, Q> {
             current_vertex_buffers,
             current_index_buffer,
             current_bind_group_ids,
+            occlusion_query_set_len,
             _marker: Default::default(),
         }
     }
@@ -1236,6 +1959,7 @@ impl<T, P, V, I, R, Q> RenderPassEncoder<T, P, V, I, R, Q> {
             current_vertex_buffers,
             current_index_buffer,
             current_bind_group_ids,
+            occlusion_query_set_len,
             ..
         } = self;
 
@@ -1254,6 +1978,7 @@ impl<T, P, V, I, R, Q> RenderPassEncoder<T, P, V, I, R, Q> {
             current_vertex_buffers,
             current_index_buffer,
             current_bind_group_ids,
+            occlusion_query_set_len,
             _marker: Default::default(),
         }
     }
@@ -1267,6 +1992,9 @@ where
     R: BindGroups<Layout = PR>,
 {
     fn draw(mut self, draw: Draw) -> Self {
+        #[cfg(feature = "usage_validation")]
+        self.validate_usages(&[]);
+
         self.handle.draw(draw);
 
         self
@@ -1276,6 +2004,9 @@ where
     where
         U: buffer::Indirect,
     {
+        #[cfg(feature = "usage_validation")]
+        self.validate_usages(&[view.id()]);
+
         self.handle
             .draw_indirect(&view.buffer.handle, view.offset_in_bytes());
 
@@ -1297,6 +2028,9 @@ where
     R: BindGroups<Layout = PR>,
 {
     fn draw_indexed(mut self, draw_indexed: DrawIndexed) -> Self {
+        #[cfg(feature = "usage_validation")]
+        self.validate_usages(&[]);
+
         self.handle.draw_indexed(draw_indexed);
 
         self
@@ -1306,6 +2040,9 @@ where
     where
         U: buffer::Indirect,
     {
+        #[cfg(feature = "usage_validation")]
+        self.validate_usages(&[view.id()]);
+
         self.handle
             .draw_indexed_indirect(&view.buffer.handle, view.offset_in_bytes());
 
@@ -1313,11 +2050,113 @@ where
     }
 }
 
+impl<T, PT, PV, PI, PR, V, I, R, Q> RenderPassEncoder<T, RenderPipeline<PT, PV, PI, PR>, V, I, R, Q>
+where
+    PI: PipelineIndexFormat,
+    V: VertexBuffers<Layout = PV>,
+    I: IndexBuffer,
+    I::IndexData: PipelineIndexFormatCompatible<PI>,
+    R: BindGroups<Layout = PR>,
+{
+    /// Records up to `max_count` indexed indirect draws with the arguments in consecutive elements
+    /// of the `args` view, where the actual number of draws is read from the `count` view when the
+    /// pass executes (and is clamped to `max_count`).
+    ///
+    /// This allows a compute pass (e.g. a culling pass) to write both the draw arguments and the
+    /// number of draws on the GPU.
+    ///
+    /// On native targets, requires [Feature::MultiDrawIndirectCount](crate::adapter::Feature).
+    /// WebGPU has no indirect draw count; on the web this is emulated by recording `max_count`
+    /// indirect draws and ignoring the `count` view. For the emulation to produce the same result,
+    /// the pass that writes the arguments must set the `instance_count` of every draw at or beyond
+    /// the count to `0`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_count` is greater than the length of the `args` view, or if the offset of the
+    /// `args` view or the `count` view is not a multiple of `4`.
+    pub fn multi_draw_indexed_indirect_count<U0, U1>(
+        mut self,
+        args: buffer::View<[DrawIndexed], U0>,
+        count: buffer::View<u32, U1>,
+        max_count: u32,
+    ) -> Self
+    where
+        U0: buffer::Indirect,
+        U1: buffer::Indirect,
+    {
+        assert!(
+            max_count as usize <= args.len(),
+            "`max_count` (`{}`) must not be greater than the length of the `args` view (`{}`)",
+            max_count,
+            args.len()
+        );
+        assert!(
+            args.offset_in_bytes() % 4 == 0,
+            "the offset of the `args` view (`{}`) must be a multiple of `4`",
+            args.offset_in_bytes()
+        );
+        assert!(
+            count.offset_in_bytes() % 4 == 0,
+            "the offset of the `count` view (`{}`) must be a multiple of `4`",
+            count.offset_in_bytes()
+        );
+
+        #[cfg(feature = "usage_validation")]
+        self.validate_usages(&[args.id(), count.id()]);
+
+        self.handle
+            .multi_draw_indexed_indirect_count(driver::MultiDrawIndexedIndirectCount {
+                buffer_handle: &args.buffer.handle,
+                offset: args.offset_in_bytes(),
+                count_buffer_handle: &count.buffer.handle,
+                count_offset: count.offset_in_bytes(),
+                max_count,
+            });
+
+        self
+    }
+}
+
+#[cfg(feature = "usage_validation")]
+impl<T, P, V, I, R, Q> RenderPassEncoder<T, P, V, I, R, Q> {
+    /// Records the buffers used by the current bind groups, vertex buffers and index buffer, and
+    /// by the given indirect buffers, in the render pass's usage scope.
+    fn validate_usages(&mut self, indirect_buffer_ids: &[usize]) {
+        let validator = &mut self.command_encoder.usage_validator;
+
+        validator.record_bind_groups(&self.current_bind_group_ids);
+
+        for vertex_buffer in self.current_vertex_buffers.iter().flatten() {
+            validator.record(vertex_buffer.id, BufferUsageKind::Vertex);
+        }
+
+        if let Some(index_buffer) = &self.current_index_buffer {
+            validator.record(index_buffer.id, BufferUsageKind::Index);
+        }
+
+        for id in indirect_buffer_ids {
+            validator.record(*id, BufferUsageKind::Indirect);
+        }
+    }
+}
+
 impl<T, P, V, I, R, Q> RenderPassEncoder<T, P, V, I, R, Q>
 where
     Q: BeginOcclusionQuery,
 {
-    pub fn begin_occlusion_query(self, query_index: u32) -> RenderPassEncoder<T, P, V, I, R, X> {
+    /// Begins an occlusion query that counts the samples that pass the depth and stencil tests for
+    /// the draw commands recorded until [end_occlusion_query](Self::end_occlusion_query) is
+    /// called, and stores the result in the query at `query_index` of the pass's occlusion query
+    /// set.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `query_index` is out of bounds for the pass's occlusion query set.
+    pub fn begin_occlusion_query(
+        self,
+        query_index: u32,
+    ) -> RenderPassEncoder<T, P, V, I, R, OcclusionQueryState<X>> {
         let RenderPassEncoder {
             mut handle,
             command_encoder,
@@ -1325,9 +2164,17 @@ where
             current_vertex_buffers,
             current_index_buffer,
             current_bind_group_ids,
+            occlusion_query_set_len,
             ..
         } = self;
 
+        assert!(
+            (query_index as usize) < occlusion_query_set_len,
+            "query index `{}` out of bounds for an occlusion query set of length `{}`",
+            query_index,
+            occlusion_query_set_len
+        );
+
         handle.begin_occlusion_query(query_index);
 
         RenderPassEncoder {
@@ -1337,6 +2184,7 @@ where
             current_vertex_buffers,
             current_index_buffer,
             current_bind_group_ids,
+            occlusion_query_set_len,
             _marker: Default::default(),
         }
     }
@@ -1346,7 +2194,7 @@ impl<T, P, V, I, R, Q> RenderPassEncoder<T, P, V, I, R, Q>
 where
     Q: EndOcclusionQuery,
 {
-    pub fn end_occlusion_query(self) -> RenderPassEncoder<T, P, V, I, R, X> {
+    pub fn end_occlusion_query(self) -> RenderPassEncoder<T, P, V, I, R, OcclusionQueryState<O>> {
         let RenderPassEncoder {
             mut handle,
             command_encoder,
@@ -1354,6 +2202,7 @@ where
             current_vertex_buffers,
             current_index_buffer,
             current_bind_group_ids,
+            occlusion_query_set_len,
             ..
         } = self;
 
@@ -1366,6 +2215,7 @@ where
             current_vertex_buffers,
             current_index_buffer,
             current_bind_group_ids,
+            occlusion_query_set_len,
             _marker: Default::default(),
         }
     }
@@ -1409,6 +2259,16 @@ pub struct RenderBundleEncoderDescriptor<Target> {
 }
 
 impl RenderBundleEncoderDescriptor<()> {
+    /// Creates a descriptor for render bundles that render to color attachments with the formats
+    /// in color layout `C`.
+    ///
+    /// For depth-only render bundles (e.g. for shadow map passes), use `()` as the color layout
+    /// and add a depth-stencil format:
+    ///
+    /// ```ignore
+    /// let descriptor =
+    ///     RenderBundleEncoderDescriptor::new::<()>().depth_stencil_format::<depth32float>();
+    /// ```
     pub fn new<C>() -> RenderBundleEncoderDescriptor<RenderLayout<C, ()>>
     where
         C: TypedColorLayout,
@@ -1428,6 +2288,9 @@ impl RenderBundleEncoderDescriptor<()> {
     where
         C: TypedMultisampleColorLayout,
     {
+        #[allow(clippy::let_unit_value)]
+        let _ = SampleCount::<SAMPLES>::ASSERT_SUPPORTED;
+
         RenderBundleEncoderDescriptor {
             color_formats: Cow::Borrowed(C::COLOR_FORMATS),
             depth_stencil_format: None,
@@ -1547,19 +2410,14 @@ pub struct RenderBundleEncoder<Target, Pipeline, Vertex, Index, Resources> {
     current_vertex_buffers: [Option<CurrentBufferRange>; 8],
     current_index_buffer: Option<CurrentBufferRange>,
     current_bind_group_ids: [Option<usize>; 4],
-    _marker: marker::PhantomData<(
-        *const Target,
-        *const Pipeline,
-        *const Vertex,
-        *const Index,
-        *const Resources,
-    )>,
+    empty_bind_group: <Dvr as Driver>::BindGroupHandle,
+    _marker: marker::PhantomData<fn() -> (Target, Pipeline, Vertex, Index, Resources)>,
 }
 
 impl<T, P, V, I, R> RenderBundleEncoder<T, P, V, I, R> {
     pub fn new(device: &Device, descriptor: &RenderBundleEncoderDescriptor<T>) -> Self {
         let handle = device
-            .device_handle
+            .device_handle()
             .create_render_bundle_encoder(&descriptor.to_driver());
 
         RenderBundleEncoder {
@@ -1568,6 +2426,7 @@ impl<T, P, V, I, R> RenderBundleEncoder<T, P, V, I, R> {
             current_vertex_buffers: [None, None, None, None, None, None, None, None],
             current_index_buffer: None,
             current_bind_group_ids: [None; 4],
+            empty_bind_group: device.empty_bind_group().clone(),
             _marker: Default::default(),
         }
     }
@@ -1587,6 +2446,8 @@ impl<T, P, V, I, R> resource_binding_command_encoder_seal::Seal
 {
 }
 impl<T, P, V, I, R> ResourceBindingCommandEncoder for RenderBundleEncoder<T, P, V, I, R> {
+    type Resources = R;
+
     type WithResources<RNew> = RenderBundleEncoder<T, P, V, I, RNew>;
 
     fn set_bind_groups<RNew>(self, bind_groups: RNew) -> Self::WithResources<RNew>
@@ -1599,14 +2460,60 @@ impl<T, P, V, I, R> ResourceBindingCommandEncoder for RenderBundleEncoder<T, P,
             current_vertex_buffers,
             current_index_buffer,
             mut current_bind_group_ids,
+            empty_bind_group,
             ..
         } = self;
 
         for (i, encoding) in bind_groups.encodings().enumerate() {
-            let BindGroupEncoding {
-                bind_group_handle,
-                id,
-            } = encoding;
+            let (bind_group_handle, id) = if let Some(encoding) = encoding {
+                (encoding.bind_group_handle, encoding.id)
+            } else {
+                (empty_bind_group.clone(), EMPTY_BIND_GROUP_ID)
+            };
+
+            if current_bind_group_ids[i] != Some(id) {
+                handle.set_bind_group(i as u32, &bind_group_handle);
+
+                current_bind_group_ids[i] = Some(id);
+            }
+        }
+
+        RenderBundleEncoder {
+            handle,
+            current_pipeline_id,
+            current_vertex_buffers,
+            current_index_buffer,
+            current_bind_group_ids,
+            empty_bind_group,
+            _marker: Default::default(),
+        }
+    }
+
+    fn update_bind_groups<RNew>(self, bind_groups: RNew) -> Self::WithResources<RNew::Updated>
+    where
+        RNew: UpdateBindGroups<R>,
+    {
+        let RenderBundleEncoder {
+            mut handle,
+            current_pipeline_id,
+            current_vertex_buffers,
+            current_index_buffer,
+            mut current_bind_group_ids,
+            empty_bind_group,
+            ..
+        } = self;
+
+        for (i, encoding) in bind_groups.update_encodings().enumerate() {
+            // Keep the bind group that is currently bound.
+            let Some(encoding) = encoding else {
+                continue;
+            };
+
+            let (bind_group_handle, id) = if let Some(encoding) = encoding {
+                (encoding.bind_group_handle, encoding.id)
+            } else {
+                (empty_bind_group.clone(), EMPTY_BIND_GROUP_ID)
+            };
 
             if current_bind_group_ids[i] != Some(id) {
                 handle.set_bind_group(i as u32, &bind_group_handle);
@@ -1621,6 +2528,7 @@ impl<T, P, V, I, R> ResourceBindingCommandEncoder for RenderBundleEncoder<T, P,
             current_vertex_buffers,
             current_index_buffer,
             current_bind_group_ids,
+            empty_bind_group,
             _marker: Default::default(),
         }
     }
@@ -1645,6 +2553,7 @@ impl<T, P, V, I, R> RenderStateEncoder<T> for RenderBundleEncoder<T, P, V, I, R>
             current_vertex_buffers,
             current_index_buffer,
             current_bind_group_ids,
+            empty_bind_group,
             ..
         } = self;
 
@@ -1658,6 +2567,7 @@ impl<T, P, V, I, R> RenderStateEncoder<T> for RenderBundleEncoder<T, P, V, I, R>
             current_vertex_buffers,
             current_index_buffer,
             current_bind_group_ids,
+            empty_bind_group,
             _marker: Default::default(),
         }
     }
@@ -1672,6 +2582,7 @@ impl<T, P, V, I, R> RenderStateEncoder<T> for RenderBundleEncoder<T, P, V, I, R>
             mut current_vertex_buffers,
             current_index_buffer,
             current_bind_group_ids,
+            empty_bind_group,
             ..
         } = self;
 
@@ -1700,6 +2611,7 @@ impl<T, P, V, I, R> RenderStateEncoder<T> for RenderBundleEncoder<T, P, V, I, R>
             current_vertex_buffers,
             current_index_buffer,
             current_bind_group_ids,
+            empty_bind_group,
             _marker: Default::default(),
         }
     }
@@ -1714,6 +2626,7 @@ impl<T, P, V, I, R> RenderStateEncoder<T> for RenderBundleEncoder<T, P, V, I, R>
             current_vertex_buffers,
             mut current_index_buffer,
             current_bind_group_ids,
+            empty_bind_group,
             ..
         } = self;
 
@@ -1745,6 +2658,73 @@ impl<T, P, V, I, R> RenderStateEncoder<T> for RenderBundleEncoder<T, P, V, I, R>
             current_vertex_buffers,
             current_index_buffer,
             current_bind_group_ids,
+            empty_bind_group,
+            _marker: Default::default(),
+        }
+    }
+}
+
+impl<T, P, V, I, R> RenderBundleEncoder<T, P, V, I, R> {
+    /// Sets the `pipeline` for subsequent draw commands, keeping the bind groups that are currently
+    /// bound.
+    ///
+    /// Unlike [set_pipeline](RenderStateEncoder::set_pipeline), this does not require setting the
+    /// bind groups again before drawing with the new pipeline, if the layout of the currently
+    /// bound bind group for each of the group indices the new pipeline uses is identical to the
+    /// pipeline's bind group layout for that index (see
+    /// [reuse_bind_groups](Self::reuse_bind_groups)). This fails to compile if the layouts are not
+    /// identical; in that case, use
+    /// [update_bind_groups](ResourceBindingCommandEncoder::update_bind_groups) after
+    /// [set_pipeline](RenderStateEncoder::set_pipeline) to replace only the bind groups that
+    /// differ.
+    pub fn set_pipeline_compatible<PT, PV, PI, PR>(
+        self,
+        pipeline: &RenderPipeline<PT, PV, PI, PR>,
+    ) -> RenderBundleEncoder<T, RenderPipeline<PT, PV, PI, PR>, V, I, R::Reused>
+    where
+        PT: RenderLayoutCompatible<T>,
+        R: ReuseBindGroups<PR>,
+    {
+        self.set_pipeline(pipeline).reuse_bind_groups()
+    }
+}
+
+impl<T, PT, PV, PI, PR, V, I, R> RenderBundleEncoder<T, RenderPipeline<PT, PV, PI, PR>, V, I, R> {
+    /// Reuses the bind groups that are currently bound for the current pipeline.
+    ///
+    /// Bind groups remain bound when switching pipelines, but the encoder's type only accepts draw
+    /// commands after the bind groups have been set for the current pipeline's layout. If the
+    /// previous pipeline bound bind groups with the same bind group layouts for the group indices
+    /// the current pipeline uses, this marks these bind groups as set for the current pipeline,
+    /// without recording any commands. Bind groups for higher group indices remain bound, but are
+    /// not used by the current pipeline.
+    ///
+    /// Fails to compile if the layout of the bind group that is bound at any of the group indices
+    /// the current pipeline uses does not match the current pipeline's bind group layout for that
+    /// index. See also [RenderPassEncoder::reuse_bind_groups].
+    pub fn reuse_bind_groups(
+        self,
+    ) -> RenderBundleEncoder<T, RenderPipeline<PT, PV, PI, PR>, V, I, R::Reused>
+    where
+        R: ReuseBindGroups<PR>,
+    {
+        let RenderBundleEncoder {
+            handle,
+            current_pipeline_id,
+            current_vertex_buffers,
+            current_index_buffer,
+            current_bind_group_ids,
+            empty_bind_group,
+            ..
+        } = self;
+
+        RenderBundleEncoder {
+            handle,
+            current_pipeline_id,
+            current_vertex_buffers,
+            current_index_buffer,
+            current_bind_group_ids,
+            empty_bind_group,
             _marker: Default::default(),
         }
     }
@@ -3,7 +3,9 @@ use std::ops::Range;
 use crate::buffer;
 use crate::buffer::Buffer;
 use crate::driver::{Driver, Dvr};
-use crate::render_pipeline::{IndexData, IndexFormat};
+use crate::render_pipeline::{DynamicIndexData, IndexData, IndexFormat};
+#[cfg(feature = "render")]
+use crate::util::{MeshIndexBuffer, MeshIndices};
 
 pub struct IndexBufferEncoding {
     pub(crate) buffer: <Dvr as Driver>::BufferHandle,
@@ -17,7 +19,7 @@ mod index_buffer_seal {
 }
 
 pub trait IndexBuffer: index_buffer_seal::Seal {
-    type IndexData: IndexData;
+    type IndexData;
 
     fn to_encoding(&self) -> IndexBufferEncoding;
 }
@@ -70,3 +72,20 @@ where
         }
     }
 }
+
+#[cfg(feature = "render")]
+impl<'a, U> index_buffer_seal::Seal for &'a MeshIndices<U> where U: buffer::Index {}
+#[cfg(feature = "render")]
+impl<'a, U> IndexBuffer for &'a MeshIndices<U>
+where
+    U: buffer::Index,
+{
+    type IndexData = DynamicIndexData;
+
+    fn to_encoding(&self) -> IndexBufferEncoding {
+        match &self.buffer {
+            MeshIndexBuffer::U16(buffer) => buffer.to_encoding(),
+            MeshIndexBuffer::U32(buffer) => buffer.to_encoding(),
+        }
+    }
+}
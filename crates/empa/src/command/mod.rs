@@ -4,6 +4,11 @@ pub use self::bind_group::*;
 mod encoder;
 pub use self::encoder::*;
 
+#[cfg(not(feature = "web"))]
+mod parallel_encoder;
+#[cfg(not(feature = "web"))]
+pub use self::parallel_encoder::*;
+
 mod index;
 pub use self::index::*;
 
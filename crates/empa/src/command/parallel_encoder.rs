@@ -0,0 +1,74 @@
+use std::cell::RefCell;
+use std::thread;
+
+use crate::command::{CommandBuffer, CommandEncoder};
+use crate::device::Device;
+
+/// Records commands on multiple threads and submits them in order.
+///
+/// Only available on native targets.
+///
+/// # Example
+///
+/// ```ignore
+/// ParallelEncoder::scope(&device, |s| {
+///     s.encode(|encoder| encoder.copy_buffer_to_buffer(a.view(), b.view()));
+///     s.encode(|encoder| encoder.copy_buffer_to_buffer(c.view(), d.view()));
+/// });
+/// ```
+pub struct ParallelEncoder;
+
+impl ParallelEncoder {
+    /// Creates a scope in which command encoders may be recorded concurrently.
+    ///
+    /// Each call to [ParallelEncoderScope::encode] records into a new [CommandEncoder] on a
+    /// separate thread. When the scope ends, all threads are joined and the resulting command
+    /// buffers are submitted to the device's queue in the order in which
+    /// [ParallelEncoderScope::encode] was called.
+    pub fn scope<'env, F>(device: &'env Device, f: F)
+    where
+        F: for<'scope> FnOnce(&ParallelEncoderScope<'scope, 'env>),
+    {
+        let command_buffers: Vec<CommandBuffer> = thread::scope(|scope| {
+            let encoder_scope = ParallelEncoderScope {
+                device,
+                scope,
+                handles: RefCell::new(Vec::new()),
+            };
+
+            f(&encoder_scope);
+
+            encoder_scope
+                .handles
+                .into_inner()
+                .into_iter()
+                .map(|handle| handle.join().expect("encoding thread panicked"))
+                .collect()
+        });
+
+        let queue = device.queue();
+
+        for command_buffer in command_buffers {
+            queue.submit(command_buffer);
+        }
+    }
+}
+
+pub struct ParallelEncoderScope<'scope, 'env: 'scope> {
+    device: &'env Device,
+    scope: &'scope thread::Scope<'scope, 'env>,
+    handles: RefCell<Vec<thread::ScopedJoinHandle<'scope, CommandBuffer>>>,
+}
+
+impl<'scope, 'env> ParallelEncoderScope<'scope, 'env> {
+    /// Records commands into a new [CommandEncoder] on a separate thread.
+    pub fn encode<F>(&self, f: F)
+    where
+        F: FnOnce(CommandEncoder) -> CommandEncoder + Send + 'scope,
+    {
+        let encoder = self.device.create_command_encoder();
+        let handle = self.scope.spawn(move || f(encoder).finish());
+
+        self.handles.borrow_mut().push(handle);
+    }
+}
@@ -4,23 +4,37 @@ use std::marker;
 
 use atomic_counter::AtomicCounter;
 use empa_reflect::ShaderStage;
+use futures::future::{self, Either};
 use futures::FutureExt;
 
-use crate::device::{Device, ID_GEN};
+use crate::adapter::Feature;
+use crate::buffer;
+use crate::command::{BindGroups, ComputePassEncoder, DispatchWorkgroups};
+use crate::device::{Device, PipelineCreationError, ID_GEN};
 use crate::driver;
 use crate::driver::{Device as _, Driver, Dvr};
 use crate::pipeline_constants::PipelineConstants;
 use crate::resource_binding::{PipelineLayout, TypedPipelineLayout};
-use crate::shader_module::{ShaderModule, ShaderSourceInternal};
+use crate::shader_module::{
+    Compute, EntryPointName, ShaderModule, ShaderSourceInternal, WorkgroupVariable,
+};
 
 pub struct ComputePipeline<L> {
     pub(crate) handle: <Dvr as Driver>::ComputePipelineHandle,
     id: usize,
+    workgroup_size: Option<[u32; 3]>,
+    max_workgroups_per_dimension: u32,
     _marker: marker::PhantomData<*const L>,
 }
 
 impl<L> ComputePipeline<L> {
-    pub(crate) fn new_sync(device: &Device, descriptor: &ComputePipelineDescriptor<L>) -> Self {
+    pub(crate) fn new_sync(
+        device: &Device,
+        descriptor: &ComputePipelineDescriptor<L>,
+    ) -> Result<Self, PipelineCreationError> {
+        validate_workgroup_storage(device, &descriptor.compute_stage)?;
+        validate_subgroups(device, &descriptor.compute_stage)?;
+
         let desc = driver::ComputePipelineDescriptor {
             layout: &descriptor.layout,
             shader_module: &descriptor.compute_stage.shader_module,
@@ -28,20 +42,28 @@ impl<L> ComputePipeline<L> {
             constants: &descriptor.compute_stage.pipeline_constants,
         };
 
+        let handle = device.device_handle().create_compute_pipeline(&desc)?;
         let id = ID_GEN.get();
-        let handle = device.device_handle.create_compute_pipeline(&desc);
 
-        ComputePipeline {
+        Ok(ComputePipeline {
             handle,
             id,
+            workgroup_size: descriptor.compute_stage.workgroup_size,
+            max_workgroups_per_dimension: device.limits().max_compute_workgroups_per_dimension,
             _marker: Default::default(),
-        }
+        })
     }
 
     pub(crate) fn new_async(
         device: &Device,
         descriptor: &ComputePipelineDescriptor<L>,
-    ) -> impl Future<Output = Self> {
+    ) -> impl Future<Output = Result<Self, PipelineCreationError>> {
+        if let Err(err) = validate_workgroup_storage(device, &descriptor.compute_stage)
+            .and_then(|_| validate_subgroups(device, &descriptor.compute_stage))
+        {
+            return Either::Left(future::ready(Err(err)));
+        }
+
         let desc = driver::ComputePipelineDescriptor {
             layout: &descriptor.layout,
             shader_module: &descriptor.compute_stage.shader_module,
@@ -49,23 +71,84 @@ impl<L> ComputePipeline<L> {
             constants: &descriptor.compute_stage.pipeline_constants,
         };
 
-        device
-            .device_handle
+        let workgroup_size = descriptor.compute_stage.workgroup_size;
+        let max_workgroups_per_dimension = device.limits().max_compute_workgroups_per_dimension;
+
+        let future = device
+            .device_handle()
             .create_compute_pipeline_async(&desc)
-            .map(|handle| {
-                let id = ID_GEN.get();
-
-                ComputePipeline {
-                    handle,
-                    id,
-                    _marker: Default::default(),
-                }
-            })
+            .map(move |result| {
+                result.map(|handle| {
+                    let id = ID_GEN.get();
+
+                    ComputePipeline {
+                        handle,
+                        id,
+                        workgroup_size,
+                        max_workgroups_per_dimension,
+                        _marker: Default::default(),
+                    }
+                })
+            });
+
+        Either::Right(future)
     }
 
     pub(crate) fn id(&self) -> usize {
         self.id
     }
+
+    /// The workgroup size declared by the pipeline's entry point.
+    ///
+    /// Returns `None` if the pipeline was created from an unparsed shader source.
+    pub fn workgroup_size(&self) -> Option<[u32; 3]> {
+        self.workgroup_size
+    }
+
+    /// Returns the workgroup counts needed to invoke the pipeline at least once for each of `len`
+    /// elements.
+    ///
+    /// The required workgroup count is `len` divided by the number of invocations per workgroup,
+    /// rounded up. If this count exceeds the device's `max_compute_workgroups_per_dimension` limit,
+    /// the workgroups are split over the Y and Z dimensions (see
+    /// [DispatchWorkgroups::linear]). The total number of invocations may exceed `len`, so the
+    /// kernel must bounds-check the element index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the pipeline's workgroup size is not known (see [workgroup_size]), or if the
+    /// element count is too large to be dispatched with a single dispatch.
+    ///
+    /// [workgroup_size]: ComputePipeline::workgroup_size
+    pub fn dispatch_size_for(&self, len: usize) -> DispatchWorkgroups {
+        let [x, y, z] = self
+            .workgroup_size
+            .expect("the workgroup size for the pipeline's entry point is unknown");
+        let invocations_per_workgroup = (x * y * z) as usize;
+        let workgroup_count = len.div_ceil(invocations_per_workgroup);
+        let workgroup_count = u32::try_from(workgroup_count)
+            .expect("the number of elements is too large for a single dispatch");
+
+        DispatchWorkgroups::linear(workgroup_count, self.max_workgroups_per_dimension)
+    }
+
+    /// Sets this pipeline on the `encoder` and dispatches enough workgroups to invoke the pipeline
+    /// at least once for each element in the buffer `view`.
+    ///
+    /// See [dispatch_size_for](ComputePipeline::dispatch_size_for) for details on how the workgroup
+    /// counts are computed.
+    pub fn dispatch_for<P, R, T, U>(
+        &self,
+        encoder: ComputePassEncoder<P, R>,
+        view: buffer::View<[T], U>,
+    ) -> ComputePassEncoder<ComputePipeline<L>, R>
+    where
+        R: BindGroups<Layout = L>,
+    {
+        encoder
+            .set_pipeline(self)
+            .dispatch_workgroups(self.dispatch_size_for(view.len()))
+    }
 }
 
 pub struct ComputePipelineDescriptor<L> {
@@ -131,14 +214,25 @@ impl<Layout: TypedPipelineLayout> ComputePipelineDescriptorBuilder<PipelineLayou
                 );
             }
 
+            if let Err(mismatch) = entry
+                .binding_type
+                .check_buffer_layout(&resource_binding.binding_type)
+            {
+                panic!(
+                    "the buffer layout for binding `{}` in group `{}` does not match the shader \
+                     layout {}",
+                    resource_binding.binding, resource_binding.group, mismatch
+                );
+            }
+
             if entry.binding_type != resource_binding.binding_type {
                 panic!(
-                    "the binding type for binding `{}` in group `{}` does not match the shader \
-                type (shader: {:#?}, actual: {:#?})",
+                    "binding `{}` in group `{}` {}",
                     resource_binding.binding,
                     resource_binding.group,
-                    &resource_binding.binding_type,
-                    &entry.binding_type
+                    entry
+                        .binding_type
+                        .describe_mismatch(&resource_binding.binding_type)
                 )
             }
         }
@@ -179,6 +273,69 @@ pub struct ComputeStage {
     pub(crate) entry_point: String,
     pub(crate) pipeline_constants: HashMap<String, f64>,
     pub(crate) shader_meta: ShaderSourceInternal,
+    pub(crate) workgroup_size: Option<[u32; 3]>,
+    pub(crate) workgroup_variables: Vec<WorkgroupVariable>,
+    pub(crate) uses_subgroups: bool,
+}
+
+/// Checks that the workgroup storage the compute stage uses does not exceed the device's
+/// `max_compute_workgroup_storage_size` limit, so that the error can list the offending variables
+/// rather than report a backend failure.
+fn validate_workgroup_storage(
+    device: &Device,
+    compute_stage: &ComputeStage,
+) -> Result<(), PipelineCreationError> {
+    let size: u32 = compute_stage
+        .workgroup_variables
+        .iter()
+        .map(|variable| variable.size.div_ceil(16) * 16)
+        .sum();
+    let max_size = device.limits().max_compute_workgroup_storage_size;
+
+    if size > max_size {
+        let variables = compute_stage
+            .workgroup_variables
+            .iter()
+            .map(|variable| {
+                format!(
+                    "`{}` (`{}` bytes)",
+                    variable.name.as_deref().unwrap_or("<unnamed>"),
+                    variable.size
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        return Err(PipelineCreationError {
+            message: format!(
+                "the entry point uses `{}` bytes of workgroup storage, which exceeds the device's \
+                 `max_compute_workgroup_storage_size` limit (`{}` bytes); workgroup variables: {}",
+                size, max_size, variables
+            ),
+            entry_point: Some(compute_stage.entry_point.clone()),
+        });
+    }
+
+    Ok(())
+}
+
+/// Checks that the device was created with [Feature::Subgroups] if the compute stage uses subgroup
+/// operations, so that the error names the missing feature rather than report a shader validation
+/// failure.
+fn validate_subgroups(
+    device: &Device,
+    compute_stage: &ComputeStage,
+) -> Result<(), PipelineCreationError> {
+    if compute_stage.uses_subgroups && !device.features().contains(Feature::Subgroups) {
+        return Err(PipelineCreationError {
+            message: "the entry point uses subgroup operations, which require the device to be \
+                      created with `Feature::Subgroups`"
+                .to_string(),
+            entry_point: Some(compute_stage.entry_point.clone()),
+        });
+    }
+
+    Ok(())
 }
 
 pub struct ComputeStageBuilder {
@@ -187,8 +344,20 @@ pub struct ComputeStageBuilder {
 }
 
 impl ComputeStageBuilder {
-    pub fn begin(shader_module: &ShaderModule, entry_point: &str) -> Self {
+    /// Begins building a compute stage for the `entry_point` in the `shader_module`.
+    ///
+    /// The `entry_point` may be an entry point name, or a typed
+    /// [ComputeEntryPoint](crate::shader_module::ComputeEntryPoint) declared with the
+    /// [shader_entry_points](crate::shader_module::shader_entry_points) macro.
+    pub fn begin<E>(shader_module: &ShaderModule, entry_point: E) -> Self
+    where
+        E: EntryPointName<Compute>,
+    {
+        let entry_point = entry_point.entry_point_name();
         let shader_meta = shader_module.meta.clone();
+        let mut workgroup_size = None;
+        let mut workgroup_variables = Vec::new();
+        let mut uses_subgroups = false;
 
         if shader_meta.is_parsed() {
             let entry_index = shader_meta
@@ -200,6 +369,14 @@ impl ComputeStageBuilder {
                 stage == Some(ShaderStage::Compute),
                 "entry point is not a compute stage"
             );
+
+            workgroup_size = shader_meta.entry_point_workgroup_size(entry_index);
+            workgroup_variables = shader_meta
+                .entry_point_workgroup_variables(entry_index)
+                .unwrap_or_default();
+            uses_subgroups = shader_meta
+                .entry_point_uses_subgroups(entry_index)
+                .unwrap_or_default();
         }
 
         let compute_stage = ComputeStage {
@@ -207,6 +384,9 @@ impl ComputeStageBuilder {
             entry_point: entry_point.to_string(),
             pipeline_constants: Default::default(),
             shader_meta,
+            workgroup_size,
+            workgroup_variables,
+            uses_subgroups,
         };
 
         ComputeStageBuilder {
@@ -224,6 +404,8 @@ impl ComputeStageBuilder {
             .shader_meta
             .build_constants(pipeline_constants);
 
+        self.has_constants = true;
+
         self
     }
 
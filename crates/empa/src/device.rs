@@ -1,26 +1,40 @@
+#[cfg(feature = "compute")]
+use std::collections::HashMap;
+use std::error::Error;
 use std::future::Future;
 use std::mem::MaybeUninit;
-use std::{mem, slice};
+use std::ops::Range;
+use std::sync::{Arc, Weak};
+use std::{fmt, iter, mem, slice};
 
 use atomic_counter::RelaxedCounter;
+use flagset::FlagSet;
 use lazy_static::lazy_static;
 
 use crate::adapter::{Feature, Limits};
-use crate::buffer::{AsBuffer, Buffer};
+use crate::buffer::{AsBuffer, Buffer, CreateBufferError};
 use crate::command::{
     CommandBuffer, CommandEncoder, RenderBundleEncoder, RenderBundleEncoderDescriptor,
 };
 use crate::compute_pipeline::{ComputePipeline, ComputePipelineDescriptor};
-use crate::driver::{Driver, Dvr, Queue as _, WriteBufferOperation, WriteTextureOperation};
+use crate::driver::{
+    Device as _, Driver, Dvr, Queue as _, WriteBufferOperation, WriteTextureOperation,
+};
+use crate::memory::{MemoryReport, MemoryTracker};
 use crate::query::{OcclusionQuerySet, TimestampQuerySet};
+#[cfg(not(any(feature = "web", feature = "noop")))]
+use crate::query::{PipelineStatisticsQuerySet, PipelineStatisticsTypes};
 use crate::render_pipeline::{RenderPipeline, RenderPipelineDescriptor};
+#[cfg(feature = "compute")]
+use crate::resource_binding::TypedPipelineLayout;
 use crate::resource_binding::{
     BindGroup, BindGroupLayout, BindGroupLayoutEntry, BindGroupLayouts, PipelineLayout, Resources,
     TypedBindGroupLayout,
 };
 use crate::sampler::{
-    AnisotropicSamplerDescriptor, ComparisonSampler, ComparisonSamplerDescriptor,
-    NonFilteringSampler, NonFilteringSamplerDescriptor, Sampler, SamplerDescriptor,
+    AnisotropicSamplerDescriptor, AnisotropicSamplerError, ComparisonSampler,
+    ComparisonSamplerDescriptor, NonFilteringSampler, NonFilteringSamplerDescriptor, Sampler,
+    SamplerCache, SamplerDescriptor,
 };
 use crate::shader_module::{ShaderModule, ShaderSource};
 use crate::texture::format::{
@@ -28,11 +42,11 @@ use crate::texture::format::{
     ViewFormats,
 };
 use crate::texture::{
-    ImageCopySize3D, ImageDataByteLayout, ImageDataLayout, Texture1D, Texture1DDescriptor,
-    Texture2D, Texture2DDescriptor, Texture3D, Texture3DDescriptor, TextureMultisampled2D,
-    TextureMultisampled2DDescriptor,
+    CreateTextureError, ImageCopySize3D, ImageDataByteLayout, ImageDataLayout, Texture1D,
+    Texture1DDescriptor, Texture2D, Texture2DDescriptor, Texture3D, Texture3DDescriptor,
+    TextureMultisampled2D, TextureMultisampled2DDescriptor,
 };
-use crate::{buffer, texture};
+use crate::{buffer, driver, texture};
 
 lazy_static! {
     pub(crate) static ref ID_GEN: RelaxedCounter = RelaxedCounter::new(1);
@@ -53,14 +67,203 @@ impl Default for DeviceDescriptor<Feature> {
     }
 }
 
+/// A logical connection to a GPU.
+///
+/// Cloning a `Device` is cheap: all clones share the same underlying device and primary queue.
+/// The device is released when the last clone is dropped; resources created from the device (e.g.
+/// buffers and textures) do not keep the `Device` alive. Use [Device::downgrade] to obtain a
+/// [WeakDevice] for long-lived caches that should not keep the device alive.
+///
+/// On native targets a `Device` is `Send` and `Sync`: it may be shared across threads, and the
+/// [CommandEncoder]s and [RenderBundleEncoder]s it creates may be moved to other threads to record
+/// commands concurrently. On the web, a `Device` is bound to the thread that created it.
 #[derive(Clone)]
 pub struct Device {
-    pub(crate) device_handle: <Dvr as Driver>::DeviceHandle,
-    pub(crate) primary_queue_handle: <Dvr as Driver>::QueueHandle,
+    inner: Arc<DeviceInner>,
+}
+
+struct DeviceInner {
+    device_handle: <Dvr as Driver>::DeviceHandle,
+    primary_queue_handle: <Dvr as Driver>::QueueHandle,
+    features: FlagSet<Feature>,
+    limits: Limits,
+    memory: Arc<MemoryTracker>,
+    sampler_cache: SamplerCache,
+    empty_bind_group: <Dvr as Driver>::BindGroupHandle,
+}
+
+/// A reference to a [Device] that does not keep the device alive.
+///
+/// Obtained with [Device::downgrade].
+#[derive(Clone)]
+pub struct WeakDevice {
+    inner: Weak<DeviceInner>,
+}
+
+impl WeakDevice {
+    /// Returns the [Device] if it is still alive, or `None` if all strong references to the
+    /// device have been dropped.
+    pub fn upgrade(&self) -> Option<Device> {
+        self.inner.upgrade().map(|inner| Device { inner })
+    }
 }
 
 impl Device {
+    pub(crate) fn new(
+        device_handle: <Dvr as Driver>::DeviceHandle,
+        primary_queue_handle: <Dvr as Driver>::QueueHandle,
+        features: FlagSet<Feature>,
+        limits: Limits,
+    ) -> Self {
+        // Bound for group indices that are skipped with a `()` placeholder in `set_bind_groups`.
+        let empty_bind_group_layout =
+            device_handle.create_bind_group_layout(driver::BindGroupLayoutDescriptor {
+                entries: iter::empty(),
+            });
+        let empty_bind_group = device_handle.create_bind_group(driver::BindGroupDescriptor {
+            layout: &empty_bind_group_layout,
+            entries: iter::empty(),
+        });
+
+        Device {
+            inner: Arc::new(DeviceInner {
+                device_handle,
+                primary_queue_handle,
+                features,
+                limits,
+                memory: Default::default(),
+                sampler_cache: Default::default(),
+                empty_bind_group,
+            }),
+        }
+    }
+
+    pub(crate) fn device_handle(&self) -> &<Dvr as Driver>::DeviceHandle {
+        &self.inner.device_handle
+    }
+
+    pub(crate) fn primary_queue_handle(&self) -> &<Dvr as Driver>::QueueHandle {
+        &self.inner.primary_queue_handle
+    }
+
+    pub(crate) fn memory_tracker(&self) -> &Arc<MemoryTracker> {
+        &self.inner.memory
+    }
+
+    pub(crate) fn sampler_cache(&self) -> &SamplerCache {
+        &self.inner.sampler_cache
+    }
+
+    pub(crate) fn empty_bind_group(&self) -> &<Dvr as Driver>::BindGroupHandle {
+        &self.inner.empty_bind_group
+    }
+
+    /// Reports the memory allocated for the buffers and textures that were created with this device
+    /// and that are still alive.
+    ///
+    /// Useful for exposing memory budgets and for detecting leaked resources, such as readback
+    /// buffers that were never dropped. On native targets the report also includes `wgpu-core`'s
+    /// internal resource report.
+    pub fn report_memory_usage(&self) -> MemoryReport {
+        let tracker = self.memory_tracker();
+        let (buffer_count, buffer_bytes, buffer_bytes_by_usage) = tracker.buffer_totals();
+        let (texture_count, texture_bytes, texture_bytes_by_usage) = tracker.texture_totals();
+
+        MemoryReport {
+            buffer_count,
+            buffer_bytes,
+            buffer_bytes_by_usage,
+            texture_count,
+            texture_bytes,
+            texture_bytes_by_usage,
+            #[cfg(not(any(feature = "web", feature = "noop")))]
+            native_report: self.device_handle().global().generate_report(),
+        }
+    }
+
+    /// Whether the device is polled automatically on a background thread, see
+    /// [set_auto_poll](Device::set_auto_poll).
+    #[cfg(not(any(feature = "web", feature = "noop")))]
+    pub fn auto_poll(&self) -> bool {
+        self.device_handle().auto_poll()
+    }
+
+    /// Enables or disables automatic polling of the device on a background thread.
+    ///
+    /// On native targets, the futures returned by e.g. [Buffer::map_read] and
+    /// [Queue::on_submitted_work_done] only resolve when the device is polled. By default, the
+    /// device is polled on a background thread while such futures are pending, so that awaiting
+    /// them behaves the same as on the web. Applications that poll the device themselves (e.g.
+    /// once per frame with [Device::poll]) may disable automatic polling; pending futures then only
+    /// resolve when the application polls the device. Re-enabling automatic polling resumes
+    /// polling for futures that are still pending.
+    ///
+    /// Automatic polling is a device-wide setting that applies to all clones of the device.
+    #[cfg(not(any(feature = "web", feature = "noop")))]
+    pub fn set_auto_poll(&self, enabled: bool) {
+        self.device_handle().set_auto_poll(enabled);
+    }
+
+    /// Processes completed GPU work, invoking the callbacks of completed buffer mappings and
+    /// resolving the futures returned by [Queue::on_submitted_work_done].
+    ///
+    /// Depending on `maintain`, this either returns immediately ([Maintain::Poll]), or blocks until
+    /// the work for a specific submission ([Maintain::Wait]) or all submitted work
+    /// ([Maintain::WaitIdle]) has completed. This allows native applications to implement frame
+    /// pacing, deferred resource destruction and readbacks without awaiting futures.
+    ///
+    /// Returns `true` if the queue is empty, `false` if there is more work queued up.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the device was lost or if waiting for the work timed out.
+    #[cfg(not(any(feature = "web", feature = "noop")))]
+    pub fn poll(&self, maintain: Maintain) -> bool {
+        let maintain = match maintain {
+            Maintain::Poll => wgt::Maintain::Poll,
+            Maintain::Wait(index) => wgt::Maintain::WaitForSubmissionIndex(index.handle),
+            Maintain::WaitIdle => wgt::Maintain::Wait,
+        };
+
+        self.device_handle().poll(maintain)
+    }
+
+    /// Creates a [WeakDevice] that refers to this device without keeping it alive.
+    pub fn downgrade(&self) -> WeakDevice {
+        WeakDevice {
+            inner: Arc::downgrade(&self.inner),
+        }
+    }
+
+    pub fn features(&self) -> FlagSet<Feature> {
+        self.inner.features
+    }
+
+    pub fn limits(&self) -> &Limits {
+        &self.inner.limits
+    }
+
+    /// Creates a buffer initialized with the given `data`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer cannot be created, see [try_create_buffer](Device::try_create_buffer)
+    /// for a variant that returns an error instead.
     pub fn create_buffer<D, T, U>(&self, data: D, usage: U) -> Buffer<T, U>
+    where
+        D: AsBuffer<T>,
+        T: ?Sized,
+        U: buffer::ValidUsageFlags,
+    {
+        self.try_create_buffer(data, usage)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_create_buffer<D, T, U>(
+        &self,
+        data: D,
+        usage: U,
+    ) -> Result<Buffer<T, U>, CreateBufferError>
     where
         D: AsBuffer<T>,
         T: ?Sized,
@@ -69,15 +272,83 @@ impl Device {
         data.as_buffer(self, false, usage)
     }
 
+    /// Creates a slice buffer of `len` elements, where the element at index `i` is initialized with
+    /// the result of `f(i)`.
+    ///
+    /// The elements are written directly into the buffer's memory; no intermediate allocation is
+    /// made.
+    pub fn create_buffer_with<T, U, F>(&self, len: usize, f: F, usage: U) -> Buffer<[T], U>
+    where
+        T: Copy + 'static,
+        U: buffer::ValidUsageFlags,
+        F: FnMut(usize) -> T,
+    {
+        Buffer::create_with(self, len, usage, f).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Creates a slice buffer of `len` elements, initialized with the first `len` elements yielded
+    /// by `iter`.
+    ///
+    /// The elements are written directly into the buffer's memory; no intermediate allocation is
+    /// made.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `iter` yields fewer than `len` elements.
+    pub fn create_buffer_from_iter<T, U, I>(&self, len: usize, iter: I, usage: U) -> Buffer<[T], U>
+    where
+        T: Copy + 'static,
+        U: buffer::ValidUsageFlags,
+        I: IntoIterator<Item = T>,
+    {
+        let mut iter = iter.into_iter();
+
+        self.create_buffer_with(
+            len,
+            |index| {
+                iter.next().unwrap_or_else(|| {
+                    panic!(
+                        "iterator yielded `{}` elements, expected `{}` elements",
+                        index, len
+                    )
+                })
+            },
+            usage,
+        )
+    }
+
+    /// Creates a slice buffer initialized with a copy of the `data` slice.
+    ///
+    /// The data is copied directly into the buffer's memory; no intermediate allocation is made.
+    pub fn create_buffer_from_slice<T, U>(&self, data: &[T], usage: U) -> Buffer<[T], U>
+    where
+        T: Copy + 'static,
+        U: buffer::ValidUsageFlags,
+    {
+        self.create_buffer(data, usage)
+    }
+
     pub fn create_buffer_mapped<D, T, U>(&self, data: D, usage: U) -> Buffer<T, U>
     where
         D: AsBuffer<T>,
         U: buffer::ValidUsageFlags,
     {
         data.as_buffer(self, true, usage)
+            .unwrap_or_else(|err| panic!("{}", err))
     }
 
     pub fn create_buffer_uninit<T, U>(&self, usage: U) -> Buffer<MaybeUninit<T>, U>
+    where
+        U: buffer::ValidUsageFlags,
+    {
+        self.try_create_buffer_uninit(usage)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_create_buffer_uninit<T, U>(
+        &self,
+        usage: U,
+    ) -> Result<Buffer<MaybeUninit<T>, U>, CreateBufferError>
     where
         U: buffer::ValidUsageFlags,
     {
@@ -88,7 +359,7 @@ impl Device {
     where
         U: buffer::ValidUsageFlags,
     {
-        Buffer::create_uninit(self, true, usage)
+        Buffer::create_uninit(self, true, usage).unwrap_or_else(|err| panic!("{}", err))
     }
 
     #[cfg(feature = "bytemuck")]
@@ -97,7 +368,7 @@ impl Device {
         T: bytemuck::Zeroable,
         U: buffer::ValidUsageFlags,
     {
-        unsafe { Buffer::create_uninit(self, false, usage).assume_init() }
+        unsafe { self.create_buffer_uninit(usage).assume_init() }
     }
 
     #[cfg(feature = "bytemuck")]
@@ -106,7 +377,39 @@ impl Device {
         T: bytemuck::Zeroable,
         U: buffer::ValidUsageFlags,
     {
-        unsafe { Buffer::create_uninit(self, true, usage).assume_init() }
+        unsafe { self.create_buffer_uninit_mapped(usage).assume_init() }
+    }
+
+    /// Creates a buffer that is mapped at creation and initialized by `f`, which writes the
+    /// initial contents directly into the buffer's mapped memory.
+    ///
+    /// This is the fastest way to initialize a buffer on all backends: it requires neither a
+    /// staging buffer copy nor a [Queue::write_buffer]. The memory `f` receives is zeroed; the
+    /// buffer is unmapped before it is returned.
+    ///
+    /// ```ignore
+    /// let buffer = device.create_buffer_mapped_with(
+    ///     buffer::Usages::uniform_binding(),
+    ///     |uniforms: &mut Uniforms| {
+    ///         uniforms.light_count = lights.len() as u32;
+    ///         uniforms.lights[..lights.len()].copy_from_slice(&lights);
+    ///     },
+    /// );
+    /// ```
+    #[cfg(feature = "bytemuck")]
+    pub fn create_buffer_mapped_with<T, U, F>(&self, usage: U, f: F) -> Buffer<T, U>
+    where
+        T: bytemuck::Zeroable,
+        U: buffer::ValidUsageFlags,
+        F: FnOnce(&mut T),
+    {
+        let buffer: Buffer<T, U> = self.create_buffer_zeroed_mapped(usage);
+
+        f(&mut Buffer::<T, U>::mapped_mut(&buffer));
+
+        buffer.unmap();
+
+        buffer
     }
 
     pub fn create_slice_buffer_uninit<T, U>(
@@ -114,6 +417,18 @@ impl Device {
         len: usize,
         usage: U,
     ) -> Buffer<[MaybeUninit<T>], U>
+    where
+        U: buffer::ValidUsageFlags,
+    {
+        self.try_create_slice_buffer_uninit(len, usage)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_create_slice_buffer_uninit<T, U>(
+        &self,
+        len: usize,
+        usage: U,
+    ) -> Result<Buffer<[MaybeUninit<T>], U>, CreateBufferError>
     where
         U: buffer::ValidUsageFlags,
     {
@@ -128,16 +443,18 @@ impl Device {
     where
         U: buffer::ValidUsageFlags,
     {
-        Buffer::create_slice_uninit(self, len, true, usage)
+        Buffer::create_slice_uninit(self, len, true, usage).unwrap_or_else(|err| panic!("{}", err))
     }
 
+    /// Creates a slice buffer of `len` elements with all bytes set to zero.
     #[cfg(feature = "bytemuck")]
     pub fn create_slice_buffer_zeroed<T, U>(&self, len: usize, usage: U) -> Buffer<[T], U>
     where
         T: bytemuck::Zeroable,
         U: buffer::ValidUsageFlags,
     {
-        unsafe { Buffer::create_slice_uninit(self, len, false, usage).assume_init() }
+        // WebGPU initializes the contents of a buffer to zero when the buffer is created.
+        unsafe { self.create_slice_buffer_uninit(len, usage).assume_init() }
     }
 
     #[cfg(feature = "bytemuck")]
@@ -146,7 +463,35 @@ impl Device {
         T: bytemuck::Zeroable,
         U: buffer::ValidUsageFlags,
     {
-        unsafe { Buffer::create_slice_uninit(self, len, true, usage).assume_init() }
+        unsafe {
+            self.create_slice_buffer_uninit_mapped(len, usage)
+                .assume_init()
+        }
+    }
+
+    /// Creates a slice buffer of `len` elements that is mapped at creation and initialized by `f`,
+    /// which writes the initial contents directly into the buffer's mapped memory.
+    ///
+    /// See [create_buffer_mapped_with](Device::create_buffer_mapped_with) for details.
+    #[cfg(feature = "bytemuck")]
+    pub fn create_slice_buffer_mapped_with<T, U, F>(
+        &self,
+        len: usize,
+        usage: U,
+        f: F,
+    ) -> Buffer<[T], U>
+    where
+        T: bytemuck::Zeroable,
+        U: buffer::ValidUsageFlags,
+        F: FnOnce(&mut [T]),
+    {
+        let buffer = self.create_slice_buffer_zeroed_mapped(len, usage);
+
+        f(&mut buffer.mapped_mut());
+
+        buffer.unmap();
+
+        buffer
     }
 
     pub fn create_bind_group_layout<T>(&self) -> BindGroupLayout<T>
@@ -185,31 +530,97 @@ impl Device {
         ShaderModule::new(self, source)
     }
 
+    #[cfg(feature = "compute")]
     pub fn create_compute_pipeline<R>(
         &self,
         descriptor: &ComputePipelineDescriptor<R>,
-    ) -> impl Future<Output = ComputePipeline<R>> {
+    ) -> impl Future<Output = Result<ComputePipeline<R>, PipelineCreationError>> {
         ComputePipeline::new_async(self, descriptor)
     }
 
+    /// Creates a compute pipeline without waiting for the device to finish compiling it.
+    ///
+    /// Note that on the web, pipeline creation errors are not reported synchronously; use
+    /// [create_compute_pipeline](Device::create_compute_pipeline) if the error needs to be
+    /// handled.
+    #[cfg(feature = "compute")]
     pub fn create_compute_pipeline_sync<R>(
         &self,
         descriptor: &ComputePipelineDescriptor<R>,
-    ) -> ComputePipeline<R> {
+    ) -> Result<ComputePipeline<R>, PipelineCreationError> {
         ComputePipeline::new_sync(self, descriptor)
     }
 
+    /// Creates a compute pipeline for every compute entry point in the `shader_module` (see
+    /// [ShaderModule::compute_entry_points]), keyed by entry point name.
+    ///
+    /// All pipelines share the `layout`, which must match the resource bindings of the shader
+    /// module. Resolves to the first error if any of the pipelines fails to compile.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `layout` does not match the resource bindings of the shader module, or if the
+    /// shader module declares pipeline constants without fallback values.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let kernels = device
+    ///     .create_compute_pipelines_for_all_entries(&shader, &pipeline_layout)
+    ///     .await?;
+    ///
+    /// encoder = encoder
+    ///     .begin_compute_pass()
+    ///     .set_pipeline(&kernels["prefix_sum"])
+    ///     ...
+    /// ```
+    #[cfg(feature = "compute")]
+    pub fn create_compute_pipelines_for_all_entries<R>(
+        &self,
+        shader_module: &ShaderModule,
+        layout: &PipelineLayout<R>,
+    ) -> impl Future<Output = Result<HashMap<String, ComputePipeline<R>>, PipelineCreationError>>
+    where
+        R: TypedPipelineLayout,
+    {
+        use futures::{future, TryFutureExt};
+
+        use crate::compute_pipeline::{ComputePipelineDescriptorBuilder, ComputeStageBuilder};
+
+        let pipelines = shader_module
+            .compute_entry_points()
+            .map(|entry_point| {
+                let descriptor = ComputePipelineDescriptorBuilder::begin()
+                    .layout(layout)
+                    .compute(ComputeStageBuilder::begin(shader_module, entry_point).finish())
+                    .finish();
+                let name = entry_point.to_string();
+
+                ComputePipeline::new_async(self, &descriptor)
+                    .map_ok(move |pipeline| (name, pipeline))
+            })
+            .collect::<Vec<_>>();
+
+        future::try_join_all(pipelines).map_ok(|pipelines| pipelines.into_iter().collect())
+    }
+
+    #[cfg(feature = "render")]
     pub fn create_render_pipeline<T, V, I, R>(
         &self,
         descriptor: &RenderPipelineDescriptor<T, V, I, R>,
-    ) -> impl Future<Output = RenderPipeline<T, V, I, R>> {
+    ) -> impl Future<Output = Result<RenderPipeline<T, V, I, R>, PipelineCreationError>> {
         RenderPipeline::new_async(self, descriptor)
     }
 
+    /// Creates a render pipeline without waiting for the device to finish compiling it.
+    ///
+    /// Note that on the web, pipeline creation errors are not reported synchronously; use
+    /// [create_render_pipeline](Device::create_render_pipeline) if the error needs to be handled.
+    #[cfg(feature = "render")]
     pub fn create_render_pipeline_sync<T, V, I, R>(
         &self,
         descriptor: &RenderPipelineDescriptor<T, V, I, R>,
-    ) -> RenderPipeline<T, V, I, R> {
+    ) -> Result<RenderPipeline<T, V, I, R>, PipelineCreationError> {
         RenderPipeline::new_sync(self, descriptor)
     }
 
@@ -217,7 +628,33 @@ impl Device {
         Sampler::new(self, descriptor)
     }
 
+    /// Returns a sampler for the given `descriptor`, reusing a previously created sampler if one
+    /// was created with an equal descriptor through this method.
+    ///
+    /// Samplers are immutable, so sharing them is safe. Prefer this over
+    /// [create_sampler](Device::create_sampler) when many materials or objects each request their
+    /// own sampler, to avoid creating large numbers of identical samplers. Cached samplers live
+    /// until the device is dropped.
+    pub fn get_or_create_sampler(&self, descriptor: &SamplerDescriptor) -> Sampler {
+        Sampler::cached(self, descriptor)
+    }
+
+    /// Creates an anisotropic sampler.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the descriptor's `max_anisotropy` is not greater than `1`, see
+    /// [try_create_anisotropic_sampler](Device::try_create_anisotropic_sampler) for a variant that
+    /// returns an error instead.
     pub fn create_anisotropic_sampler(&self, descriptor: &AnisotropicSamplerDescriptor) -> Sampler {
+        self.try_create_anisotropic_sampler(descriptor)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_create_anisotropic_sampler(
+        &self,
+        descriptor: &AnisotropicSamplerDescriptor,
+    ) -> Result<Sampler, AnisotropicSamplerError> {
         Sampler::anisotropic(self, descriptor)
     }
 
@@ -241,7 +678,20 @@ impl Device {
     ) -> Texture1D<F, U>
     where
         F: Texture1DFormat,
-        U: texture::UsageFlags,
+        U: texture::SupportedUsages<F>,
+        V: ViewFormats<F>,
+    {
+        self.try_create_texture_1d(descriptor)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_create_texture_1d<F, U, V>(
+        &self,
+        descriptor: &Texture1DDescriptor<F, U, V>,
+    ) -> Result<Texture1D<F, U>, CreateTextureError>
+    where
+        F: Texture1DFormat,
+        U: texture::SupportedUsages<F>,
         V: ViewFormats<F>,
     {
         Texture1D::new(self, descriptor)
@@ -253,7 +703,20 @@ impl Device {
     ) -> Texture2D<F, U>
     where
         F: Texture2DFormat,
-        U: texture::UsageFlags,
+        U: texture::SupportedUsages<F>,
+        V: ViewFormats<F>,
+    {
+        self.try_create_texture_2d(descriptor)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_create_texture_2d<F, U, V>(
+        &self,
+        descriptor: &Texture2DDescriptor<F, U, V>,
+    ) -> Result<Texture2D<F, U>, CreateTextureError>
+    where
+        F: Texture2DFormat,
+        U: texture::SupportedUsages<F>,
         V: ViewFormats<F>,
     {
         Texture2D::new(self, descriptor)
@@ -265,7 +728,20 @@ impl Device {
     ) -> Texture3D<F, U>
     where
         F: Texture3DFormat,
-        U: texture::UsageFlags,
+        U: texture::SupportedUsages<F>,
+        V: ViewFormats<F>,
+    {
+        self.try_create_texture_3d(descriptor)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_create_texture_3d<F, U, V>(
+        &self,
+        descriptor: &Texture3DDescriptor<F, U, V>,
+    ) -> Result<Texture3D<F, U>, CreateTextureError>
+    where
+        F: Texture3DFormat,
+        U: texture::SupportedUsages<F>,
         V: ViewFormats<F>,
     {
         Texture3D::new(self, descriptor)
@@ -275,6 +751,18 @@ impl Device {
         &self,
         descriptor: &TextureMultisampled2DDescriptor,
     ) -> TextureMultisampled2D<F, U, SAMPLES>
+    where
+        F: MultisampleFormat,
+        U: texture::UsageFlags + texture::RenderAttachment,
+    {
+        self.try_create_texture_multisampled_2d(descriptor)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_create_texture_multisampled_2d<F, U, const SAMPLES: u8>(
+        &self,
+        descriptor: &TextureMultisampled2DDescriptor,
+    ) -> Result<TextureMultisampled2D<F, U, SAMPLES>, CreateTextureError>
     where
         F: MultisampleFormat,
         U: texture::UsageFlags + texture::RenderAttachment,
@@ -290,10 +778,28 @@ impl Device {
         TimestampQuerySet::new(self, len)
     }
 
+    /// Creates a set of `len` queries that collect the given pipeline `statistics`.
+    ///
+    /// Only available on native targets; requires
+    /// [Feature::PipelineStatisticsQuery](crate::adapter::Feature).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `statistics` does not select any statistics.
+    #[cfg(not(any(feature = "web", feature = "noop")))]
+    pub fn create_pipeline_statistics_query_set(
+        &self,
+        len: usize,
+        statistics: PipelineStatisticsTypes,
+    ) -> PipelineStatisticsQuerySet {
+        PipelineStatisticsQuerySet::new(self, len, statistics)
+    }
+
     pub fn create_command_encoder(&self) -> CommandEncoder {
         CommandEncoder::new(self)
     }
 
+    #[cfg(feature = "render")]
     pub fn create_render_bundle_encoder<T>(
         &self,
         descriptor: &RenderBundleEncoderDescriptor<T>,
@@ -303,20 +809,99 @@ impl Device {
 
     pub fn queue(&self) -> Queue {
         Queue {
-            handle: self.primary_queue_handle.clone(),
+            handle: self.primary_queue_handle().clone(),
+            write_buffer_chunk_size: DEFAULT_WRITE_BUFFER_CHUNK_SIZE,
         }
     }
 }
 
+/// The default maximum size in bytes of the individual writes into which a [Queue] splits large
+/// buffer writes, see [Queue::with_write_buffer_chunk_size].
+pub const DEFAULT_WRITE_BUFFER_CHUNK_SIZE: usize = 16 * 1024 * 1024;
+
 pub struct Queue {
     pub(crate) handle: <Dvr as Driver>::QueueHandle,
+    write_buffer_chunk_size: usize,
+}
+
+type SubmissionIndexHandle = <<Dvr as Driver>::QueueHandle as driver::Queue<Dvr>>::SubmissionIndex;
+
+/// Identifies a submission to a [Queue], returned by [Queue::submit].
+#[derive(Clone, Copy, Debug)]
+pub struct SubmissionIndex {
+    #[cfg_attr(any(feature = "web", feature = "noop"), allow(dead_code))]
+    handle: SubmissionIndexHandle,
+}
+
+/// Determines whether [Device::poll] blocks.
+#[cfg(not(any(feature = "web", feature = "noop")))]
+#[derive(Clone, Copy, Debug)]
+pub enum Maintain {
+    /// Processes completed work without blocking.
+    Poll,
+    /// Blocks until the work for the given submission has completed.
+    Wait(SubmissionIndex),
+    /// Blocks until all submitted work has completed.
+    WaitIdle,
 }
 
 impl Queue {
-    pub fn submit(&self, command_buffer: CommandBuffer) {
-        self.handle.submit(&command_buffer.handle);
+    /// Sets the maximum size in bytes of the individual writes into which buffer writes are split.
+    ///
+    /// Defaults to [DEFAULT_WRITE_BUFFER_CHUNK_SIZE]. On the web, each write copies its data into
+    /// a JavaScript-side buffer; splitting large writes into chunks bounds the size of these
+    /// copies, which avoids memory spikes (and allocation failures) for very large uploads. Other
+    /// drivers issue each write as a single write; there, only
+    /// [write_buffer_large](Queue::write_buffer_large) splits its writes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `chunk_size` is `0` or not a multiple of `4` bytes.
+    pub fn with_write_buffer_chunk_size(mut self, chunk_size: usize) -> Self {
+        assert!(
+            chunk_size > 0 && chunk_size % 4 == 0,
+            "write buffer chunk size `{}` must be a non-zero multiple of `4` bytes",
+            chunk_size
+        );
+
+        self.write_buffer_chunk_size = chunk_size;
+
+        self
+    }
+
+    /// Submits the `command_buffer` for execution.
+    ///
+    /// Returns a [SubmissionIndex] that identifies the submission. On native targets, the index
+    /// may be passed to [Device::poll] to wait for the submission's work to complete.
+    pub fn submit(&self, command_buffer: CommandBuffer) -> SubmissionIndex {
+        SubmissionIndex {
+            handle: self.handle.submit(&command_buffer.handle),
+        }
     }
 
+    /// Returns a future that resolves when all work submitted to this queue up to this point has
+    /// completed.
+    pub fn on_submitted_work_done(&self) -> impl Future<Output = ()> {
+        self.handle.on_submitted_work_done()
+    }
+
+    /// Writes the `data` to the `dst` buffer view.
+    ///
+    /// The write is ordered before any work submitted to the queue afterwards. Combined with a
+    /// [projection](buffer::projection), this allows updating a single field of a (large) uniform
+    /// struct:
+    ///
+    /// ```ignore
+    /// queue.write_buffer(uniforms.project_to(projection!(Uniforms => time)), &time);
+    /// ```
+    ///
+    /// To update a field that is smaller than `4` bytes (e.g. a `u16` field), use
+    /// [write_buffer_projected](Queue::write_buffer_projected) instead.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the offset of the `dst` view or the size of `T` is not a multiple of `4` bytes, as
+    /// required for buffer writes.
     pub fn write_buffer<T, U>(&self, dst: buffer::View<T, U>, data: &T)
     where
         T: Copy + 'static,
@@ -327,13 +912,60 @@ impl Queue {
 
         let data = unsafe { slice::from_raw_parts(ptr, len) };
 
-        self.handle.write_buffer(WriteBufferOperation {
-            buffer_handle: &dst.buffer.handle,
-            offset: dst.offset_in_bytes(),
-            data,
-        });
+        self.write_buffer_internal(&dst.buffer.handle, dst.offset_in_bytes(), data);
+    }
+
+    /// Writes the field of the `data` identified by the `projection` to the corresponding field
+    /// of the `dst` buffer view.
+    ///
+    /// Buffer writes must start and end at a multiple of `4` bytes. If the field does not, the
+    /// write is widened to the enclosing 4-byte aligned range, which includes the neighbouring
+    /// bytes of the `data`. This allows updating fields smaller than `4` bytes:
+    ///
+    /// ```ignore
+    /// let flags = projection!(Uniforms => flags);
+    ///
+    /// queue.write_buffer_projected(uniforms.view(), flags, &uniforms_data);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the offset of the `dst` view is not a multiple of `4` bytes, or if the widened
+    /// range extends past the end of `T`.
+    pub fn write_buffer_projected<T, P, U>(
+        &self,
+        dst: buffer::View<T, U>,
+        projection: buffer::Projection<T, P>,
+        data: &T,
+    ) where
+        T: Copy + 'static,
+        U: buffer::CopyDst,
+    {
+        let ptr = data as *const T as *const u8;
+        let len = mem::size_of::<T>();
+
+        let data = unsafe { slice::from_raw_parts(ptr, len) };
+        let range = widen_write_range(projection.offset_in_bytes(), mem::size_of::<P>());
+
+        assert!(
+            range.end <= len,
+            "the widened write range `{:?}` extends past the end of the data",
+            range
+        );
+
+        self.write_buffer_internal(
+            &dst.buffer.handle,
+            dst.offset_in_bytes() + range.start,
+            &data[range],
+        );
     }
 
+    /// Writes the `data` to the `dst` buffer slice view.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of the `dst` view does not match the length of the `data`, or if the
+    /// offset of the `dst` view or the size of the `data` is not a multiple of `4` bytes.
     pub fn write_buffer_slice<T, U>(&self, dst: buffer::View<[T], U>, data: &[T])
     where
         T: Copy + 'static,
@@ -350,9 +982,99 @@ impl Queue {
 
         let data = unsafe { slice::from_raw_parts(ptr, len) };
 
+        self.write_buffer_internal(&dst.buffer.handle, dst.offset_in_bytes(), data);
+    }
+
+    /// Writes the `data` to the `dst` buffer view in chunks, yielding to the event loop between
+    /// chunks.
+    ///
+    /// Behaves like [write_buffer_slice](Queue::write_buffer_slice), except that the returned
+    /// future issues one write per chunk (see
+    /// [with_write_buffer_chunk_size](Queue::with_write_buffer_chunk_size)) and yields between
+    /// chunks. On the web, this keeps the page responsive while uploading very large amounts of
+    /// data:
+    ///
+    /// ```ignore
+    /// queue.write_buffer_large(vertices.view(), &vertex_data).await;
+    /// ```
+    ///
+    /// The `dst` buffer must not be used by any work submitted to the queue before the returned
+    /// future resolves, as that work would observe a partially written buffer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of the `dst` view does not match the length of the `data`, or if the
+    /// offset of the `dst` view or the size of the `data` is not a multiple of `4` bytes.
+    pub async fn write_buffer_large<T, U>(&self, dst: buffer::View<'_, [T], U>, data: &[T])
+    where
+        T: Copy + 'static,
+        U: buffer::CopyDst,
+    {
+        assert_eq!(
+            dst.len(),
+            data.len(),
+            "the size of the buffer view `len` does not match the size of the data"
+        );
+
+        let ptr = data as *const [T] as *const u8;
+        let len = mem::size_of::<T>() * data.len();
+
+        let data = unsafe { slice::from_raw_parts(ptr, len) };
+        let offset = dst.offset_in_bytes();
+
+        Self::assert_write_alignment(offset, data.len());
+
+        for (i, (offset, chunk)) in
+            write_chunks(offset, data, self.write_buffer_chunk_size).enumerate()
+        {
+            if i > 0 {
+                yield_now().await;
+            }
+
+            self.handle.write_buffer(WriteBufferOperation {
+                buffer_handle: &dst.buffer.handle,
+                offset,
+                data: chunk,
+            });
+        }
+    }
+
+    fn assert_write_alignment(offset: usize, size: usize) {
+        assert!(
+            offset % 4 == 0,
+            "buffer write offset `{}` must be a multiple of `4` bytes",
+            offset
+        );
+        assert!(
+            size % 4 == 0,
+            "buffer write size `{}` must be a multiple of `4` bytes",
+            size
+        );
+    }
+
+    fn write_buffer_internal(
+        &self,
+        buffer_handle: &<Dvr as Driver>::BufferHandle,
+        offset: usize,
+        data: &[u8],
+    ) {
+        Self::assert_write_alignment(offset, data.len());
+
+        // On the web, split large writes into chunks, so that the data is never copied into a
+        // JavaScript-side buffer more than a chunk's worth at a time.
+        #[cfg(feature = "web")]
+        for (offset, chunk) in write_chunks(offset, data, self.write_buffer_chunk_size) {
+            self.handle.write_buffer(WriteBufferOperation {
+                buffer_handle,
+                offset,
+                data: chunk,
+            });
+        }
+
+        #[cfg(not(feature = "web"))]
         self.handle.write_buffer(WriteBufferOperation {
-            buffer_handle: &dst.buffer.handle,
-            offset: dst.offset_in_bytes(),
+            buffer_handle,
+            offset,
             data,
         });
     }
@@ -533,3 +1255,150 @@ impl Queue {
         self.write_texture_raw_internal(dst.inner, bytes, layout, size);
     }
 }
+
+/// Signals that the device failed to create a compute or render pipeline.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct PipelineCreationError {
+    pub(crate) message: String,
+    pub(crate) entry_point: Option<String>,
+}
+
+impl PipelineCreationError {
+    /// The error message reported by the device.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// The name of the shader entry point the error relates to, if known.
+    pub fn entry_point(&self) -> Option<&str> {
+        self.entry_point.as_deref()
+    }
+}
+
+impl fmt::Display for PipelineCreationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(entry_point) = &self.entry_point {
+            write!(
+                f,
+                "failed to create pipeline (entry point `{}`): {}",
+                entry_point, self.message
+            )
+        } else {
+            write!(f, "failed to create pipeline: {}", self.message)
+        }
+    }
+}
+
+impl Error for PipelineCreationError {}
+
+/// Yields to the event loop: resolves on a later turn of the JavaScript event loop, so that the
+/// browser gets a chance to handle other tasks in the meantime.
+#[cfg(all(feature = "web", not(feature = "noop")))]
+async fn yield_now() {
+    use wasm_bindgen::JsCast;
+
+    let promise = js_sys::Promise::new(&mut |resolve, _| {
+        let set_timeout: js_sys::Function =
+            js_sys::Reflect::get(&js_sys::global(), &"setTimeout".into())
+                .expect("global `setTimeout` function not found")
+                .unchecked_into();
+
+        set_timeout
+            .call2(&wasm_bindgen::JsValue::UNDEFINED, &resolve, &0.into())
+            .unwrap();
+    });
+
+    wasm_bindgen_futures::JsFuture::from(promise).await.unwrap();
+}
+
+/// Yields once to the executor, allowing other tasks to make progress.
+#[cfg(not(all(feature = "web", not(feature = "noop"))))]
+async fn yield_now() {
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    struct YieldNow {
+        yielded: bool,
+    }
+
+    impl Future for YieldNow {
+        type Output = ();
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+            if self.yielded {
+                Poll::Ready(())
+            } else {
+                self.yielded = true;
+
+                cx.waker().wake_by_ref();
+
+                Poll::Pending
+            }
+        }
+    }
+
+    YieldNow { yielded: false }.await
+}
+
+/// Splits the `data` that is written at `offset` into chunks of at most `chunk_size` bytes, and
+/// returns each chunk along with the offset at which it is written.
+fn write_chunks(
+    offset: usize,
+    data: &[u8],
+    chunk_size: usize,
+) -> impl Iterator<Item = (usize, &[u8])> {
+    data.chunks(chunk_size)
+        .enumerate()
+        .map(move |(i, chunk)| (offset + i * chunk_size, chunk))
+}
+
+/// Widens the byte range of `size` bytes at `offset` to the enclosing range that starts and ends
+/// at a multiple of `4` bytes, as required for buffer writes.
+fn widen_write_range(offset: usize, size: usize) -> Range<usize> {
+    let start = offset - offset % 4;
+    let end = (offset + size).div_ceil(4) * 4;
+
+    start..end
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn widen_write_range_aligned() {
+        assert_eq!(widen_write_range(0, 4), 0..4);
+        assert_eq!(widen_write_range(8, 16), 8..24);
+    }
+
+    #[test]
+    fn widen_write_range_unaligned_sub_field() {
+        // A `u16` field at offset `2` of a struct.
+        assert_eq!(widen_write_range(2, 2), 0..4);
+        // A `u8` field at offset `5`.
+        assert_eq!(widen_write_range(5, 1), 4..8);
+        // A `[u16; 3]` field at offset `6`.
+        assert_eq!(widen_write_range(6, 6), 4..12);
+    }
+
+    #[test]
+    fn write_chunks_smaller_than_data() {
+        let data: Vec<u8> = (0..10).collect();
+
+        let chunks: Vec<(usize, &[u8])> = write_chunks(8, &data, 4).collect();
+
+        assert_eq!(
+            chunks,
+            vec![(8, &data[0..4]), (12, &data[4..8]), (16, &data[8..10])]
+        );
+    }
+
+    #[test]
+    fn write_chunks_larger_than_data() {
+        let data = [1u8, 2, 3, 4];
+
+        let chunks: Vec<(usize, &[u8])> = write_chunks(4, &data, 16).collect();
+
+        assert_eq!(chunks, vec![(4, &data[..])]);
+    }
+}
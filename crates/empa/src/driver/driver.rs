@@ -1,21 +1,24 @@
 use std::borrow::Borrow;
 use std::collections::HashMap;
 use std::error::Error;
+use std::fmt;
 use std::future::Future;
+use std::num::NonZeroU32;
 use std::ops::{Range, RangeInclusive};
 
 use flagset::{flags, FlagSet};
 
 use crate::adapter::{Feature, Limits};
-use crate::buffer::MapError;
+use crate::buffer::{CreateBufferError, MapError};
 use crate::command::{BlendConstant, Draw, DrawIndexed, ScissorRect, Viewport};
-use crate::device::DeviceDescriptor;
+use crate::device::{DeviceDescriptor, PipelineCreationError};
 use crate::render_pipeline::{
-    BlendState, ColorWrite, CullMode, FrontFace, IndexFormat, VertexBufferLayout,
+    BlendState, ColorWriteMask, CullMode, FrontFace, IndexFormat, VertexBufferLayout,
 };
 use crate::render_target::{LoadOp, StoreOp};
 use crate::sampler::{AddressMode, FilterMode};
 use crate::texture::format::TextureFormatId;
+use crate::texture::CreateTextureError;
 use crate::CompareFunction;
 
 pub trait Driver: Sized {
@@ -62,13 +65,23 @@ pub trait Device<D>: Clone + Sized
 where
     D: Driver,
 {
-    type CreateComputePipelineAsync: Future<Output = D::ComputePipelineHandle>;
+    type CreateComputePipelineAsync: Future<
+        Output = Result<D::ComputePipelineHandle, PipelineCreationError>,
+    >;
 
-    type CreateRenderPipelineAsync: Future<Output = D::RenderPipelineHandle>;
+    type CreateRenderPipelineAsync: Future<
+        Output = Result<D::RenderPipelineHandle, PipelineCreationError>,
+    >;
 
-    fn create_buffer(&self, descriptor: &BufferDescriptor) -> D::BufferHandle;
+    fn create_buffer(
+        &self,
+        descriptor: &BufferDescriptor,
+    ) -> Result<D::BufferHandle, CreateBufferError>;
 
-    fn create_texture(&self, descriptor: &TextureDescriptor) -> D::TextureHandle;
+    fn create_texture(
+        &self,
+        descriptor: &TextureDescriptor,
+    ) -> Result<D::TextureHandle, CreateTextureError>;
 
     fn create_sampler(&self, descriptor: &SamplerDescriptor) -> D::SamplerHandle;
 
@@ -98,7 +111,7 @@ where
     fn create_compute_pipeline(
         &self,
         descriptor: &ComputePipelineDescriptor<D>,
-    ) -> D::ComputePipelineHandle;
+    ) -> Result<D::ComputePipelineHandle, PipelineCreationError>;
 
     fn create_compute_pipeline_async(
         &self,
@@ -108,7 +121,7 @@ where
     fn create_render_pipeline(
         &self,
         descriptor: &RenderPipelineDescriptor<D>,
-    ) -> D::RenderPipelineHandle;
+    ) -> Result<D::RenderPipelineHandle, PipelineCreationError>;
 
     fn create_render_pipeline_async(
         &self,
@@ -163,6 +176,7 @@ pub struct BufferDescriptor {
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TextureDimensions {
     One,
     Two,
@@ -170,6 +184,7 @@ pub enum TextureDimensions {
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TextureViewDimension {
     One,
     Two,
@@ -220,9 +235,42 @@ impl Default for SamplerDescriptor {
 }
 
 #[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum QueryType {
     Occlusion,
     Timestamp,
+    PipelineStatistics(PipelineStatisticsTypes),
+}
+
+/// Selects the statistics that a pipeline statistics query collects.
+///
+/// The statistics are resolved in the order of the fields of this struct; each selected statistic
+/// resolves to a single `u64` value.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PipelineStatisticsTypes {
+    /// The number of times the vertex shader is invoked.
+    pub vertex_shader_invocations: bool,
+    /// The number of primitives that are processed by the clipper.
+    pub clipper_invocations: bool,
+    /// The number of primitives that are output by the clipper.
+    pub clipper_primitives_out: bool,
+    /// The number of times the fragment shader is invoked.
+    pub fragment_shader_invocations: bool,
+    /// The number of times the compute shader is invoked.
+    pub compute_shader_invocations: bool,
+}
+
+impl PipelineStatisticsTypes {
+    /// The number of selected statistics, which is the number of `u64` values each query resolves
+    /// to.
+    pub const fn count(&self) -> usize {
+        self.vertex_shader_invocations as usize
+            + self.clipper_invocations as usize
+            + self.clipper_primitives_out as usize
+            + self.fragment_shader_invocations as usize
+            + self.compute_shader_invocations as usize
+    }
 }
 
 #[derive(Clone, Copy, PartialEq, Debug)]
@@ -268,6 +316,8 @@ where
     fn unmap(&self);
 
     fn binding(&self, offset: usize, size: usize) -> D::BufferBinding;
+
+    fn destroy(&self);
 }
 
 pub struct TextureViewDescriptor {
@@ -283,6 +333,8 @@ where
     D: Driver,
 {
     fn texture_view(&self, descriptor: &TextureViewDescriptor) -> D::TextureView;
+
+    fn destroy(&self);
 }
 
 pub struct CopyBufferToBuffer<'a, D>
@@ -371,7 +423,8 @@ where
     fn finish(self) -> D::CommandBufferHandle;
 }
 
-#[derive(Clone, Copy, PartialEq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TextureAspect {
     All,
     StencilOnly,
@@ -482,11 +535,20 @@ pub trait Queue<D>: Sized
 where
     D: Driver,
 {
-    fn submit(&self, command_buffer: &D::CommandBufferHandle);
+    type OnSubmittedWorkDone: Future<Output = ()>;
+
+    type SubmissionIndex: Copy + fmt::Debug + 'static;
+
+    fn submit(&self, command_buffer: &D::CommandBufferHandle) -> Self::SubmissionIndex;
+
+    fn on_submitted_work_done(&self) -> Self::OnSubmittedWorkDone;
 
     fn write_buffer(&self, operation: WriteBufferOperation<D>);
 
     fn write_texture(&self, operation: WriteTextureOperation<D>);
+
+    /// The number of nanoseconds per tick of a timestamp query result.
+    fn timestamp_period(&self) -> f32;
 }
 
 pub enum BindingResource<'a, D>
@@ -538,6 +600,7 @@ where
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BufferBindingType {
     Uniform,
     Storage,
@@ -545,6 +608,7 @@ pub enum BufferBindingType {
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SamplerBindingType {
     Filtering,
     NonFiltering,
@@ -552,6 +616,7 @@ pub enum SamplerBindingType {
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TextureSampleType {
     Float,
     UnfilterableFloat,
@@ -561,6 +626,7 @@ pub enum TextureSampleType {
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(unused)]
 pub enum StorageTextureAccess {
     ReadOnly,
@@ -569,6 +635,7 @@ pub enum StorageTextureAccess {
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BindingType {
     Buffer(BufferBindingType),
     Sampler(SamplerBindingType),
@@ -600,6 +667,7 @@ pub struct PipelineLayoutDescriptor<I> {
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum StencilOperation {
     Keep,
     Zero,
@@ -612,6 +680,7 @@ pub enum StencilOperation {
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct StencilFaceState {
     pub compare: CompareFunction,
     pub depth_fail_op: StencilOperation,
@@ -631,6 +700,7 @@ impl Default for StencilFaceState {
 }
 
 #[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DepthStencilState {
     pub format: TextureFormatId,
     pub depth_write_enabled: bool,
@@ -645,6 +715,7 @@ pub struct DepthStencilState {
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MultisampleState {
     pub count: u32,
     pub mask: u32,
@@ -652,6 +723,7 @@ pub struct MultisampleState {
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PrimitiveTopology {
     PointList,
     LineList,
@@ -661,6 +733,7 @@ pub enum PrimitiveTopology {
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PrimitiveState {
     pub topology: PrimitiveTopology,
     pub strip_index_format: Option<IndexFormat>,
@@ -682,7 +755,7 @@ where
 pub struct ColorTargetState {
     pub format: TextureFormatId,
     pub blend: Option<BlendState>,
-    pub write_mask: FlagSet<ColorWrite>,
+    pub write_mask: ColorWriteMask,
 }
 
 pub struct FragmentState<'a, D>
@@ -705,6 +778,7 @@ where
     pub depth_stencil_state: Option<&'a DepthStencilState>,
     pub fragment_state: Option<FragmentState<'a, D>>,
     pub multisample_state: Option<&'a MultisampleState>,
+    pub multiview: Option<NonZeroU32>,
 }
 
 pub trait ProgrammablePassEncoder<D>: Sized
@@ -724,6 +798,10 @@ where
 
     fn dispatch_workgroups_indirect(&mut self, buffer_handle: &D::BufferHandle, offset: usize);
 
+    fn begin_pipeline_statistics_query(&mut self, query_set: &D::QuerySetHandle, query_index: u32);
+
+    fn end_pipeline_statistics_query(&mut self);
+
     fn end(self);
 }
 
@@ -747,6 +825,18 @@ where
     pub range: Option<Range<usize>>,
 }
 
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct MultiDrawIndexedIndirectCount<'a, D>
+where
+    D: Driver,
+{
+    pub buffer_handle: &'a D::BufferHandle,
+    pub offset: usize,
+    pub count_buffer_handle: &'a D::BufferHandle,
+    pub count_offset: usize,
+    pub max_count: u32,
+}
+
 pub trait RenderEncoder<D>: ProgrammablePassEncoder<D>
 where
     D: Driver,
@@ -782,6 +872,12 @@ where
 
     fn end_occlusion_query(&mut self);
 
+    fn begin_pipeline_statistics_query(&mut self, query_set: &D::QuerySetHandle, query_index: u32);
+
+    fn end_pipeline_statistics_query(&mut self);
+
+    fn multi_draw_indexed_indirect_count(&mut self, op: MultiDrawIndexedIndirectCount<D>);
+
     fn execute_bundles<'a>(&'a mut self) -> D::ExecuteRenderBundlesEncoder<'a>;
 
     fn end(self);
@@ -1,14 +1,26 @@
 pub mod driver;
 pub use driver::*;
 
-#[cfg(feature = "web")]
+#[cfg(feature = "noop")]
+pub mod noop;
+
+#[cfg(feature = "noop")]
+pub type Dvr = noop::Driver;
+
+#[cfg(all(feature = "web", not(feature = "noop")))]
 pub mod web;
 
-#[cfg(feature = "web")]
+#[cfg(all(feature = "web", not(feature = "noop")))]
 pub type Dvr = web::Driver;
 
-#[cfg(not(feature = "web"))]
+#[cfg(not(any(feature = "web", feature = "noop")))]
 pub mod native;
 
-#[cfg(not(feature = "web"))]
+#[cfg(all(feature = "trace", not(any(feature = "web", feature = "noop"))))]
+pub mod trace;
+
+#[cfg(all(feature = "trace", not(any(feature = "web", feature = "noop"))))]
+pub type Dvr = trace::Driver;
+
+#[cfg(not(any(feature = "web", feature = "noop", feature = "trace")))]
 pub type Dvr = native::Driver;
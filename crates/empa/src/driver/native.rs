@@ -7,14 +7,15 @@ use std::pin::Pin;
 use std::ptr::NonNull;
 use std::sync::atomic::Ordering::{Acquire, Relaxed, Release};
 use std::sync::atomic::{AtomicBool, AtomicUsize};
-use std::sync::{atomic, Arc};
-use std::task::{Context, Poll};
+use std::sync::{atomic, Arc, Mutex};
+use std::task::{Context, Poll, Waker};
 use std::thread::JoinHandle;
 use std::{future, mem, ptr, slice, thread};
 
 use arrayvec::ArrayVec;
 use flagset::FlagSet;
 use wgc::command::{bundle_ffi, compute_commands, render_commands};
+use wgc::device::queue::WrappedSubmissionIndex;
 use wgc::gfx_select;
 use wgc::global::Global;
 use wgc::id::{
@@ -22,35 +23,38 @@ use wgc::id::{
     ComputePipelineId, DeviceId, PipelineLayoutId, QuerySetId, QueueId, RenderBundleId,
     RenderPipelineId, SamplerId, ShaderModuleId, TextureId, TextureViewId,
 };
+use wgc::resource::BufferAccessError;
 use wgt::Maintain;
 
 use crate::adapter::{Feature, Limits};
-use crate::buffer::MapError;
+use crate::buffer::{CreateBufferError, MapError};
 use crate::command::{BlendConstant, Draw, DrawIndexed, ScissorRect, Viewport};
-use crate::device::DeviceDescriptor;
+use crate::device::{DeviceDescriptor, PipelineCreationError};
 use crate::driver::{
     Adapter, BindGroupDescriptor, BindGroupEntry, BindGroupLayoutDescriptor, BindGroupLayoutEntry,
     BindingResource, BindingType, Buffer, BufferBindingType, BufferDescriptor, BufferUsage,
     ClearBuffer, ColorTargetState, CommandEncoder, ComputePassEncoder, ComputePipelineDescriptor,
     CopyBufferToBuffer, CopyBufferToTexture, CopyTextureToBuffer, CopyTextureToTexture,
     DepthStencilOperations, DepthStencilState, Device, ExecuteRenderBundlesEncoder,
-    ImageCopyBuffer, ImageCopyTexture, ImageDataLayout, MapMode, MultisampleState,
-    PipelineLayoutDescriptor, PrimitiveState, PrimitiveTopology, ProgrammablePassEncoder,
-    QuerySetDescriptor, QueryType, Queue, RenderBundleEncoder, RenderBundleEncoderDescriptor,
-    RenderEncoder, RenderPassColorAttachment, RenderPassDepthStencilAttachment,
-    RenderPassDescriptor, RenderPassEncoder, RenderPipelineDescriptor, ResolveQuerySet,
-    SamplerBindingType, SamplerDescriptor, SetIndexBuffer, SetVertexBuffer, ShaderStage,
-    StencilFaceState, StencilOperation, StorageTextureAccess, Texture, TextureAspect,
-    TextureDescriptor, TextureDimensions, TextureSampleType, TextureUsage, TextureViewDescriptor,
-    TextureViewDimension, WriteBufferOperation, WriteTextureOperation,
+    ImageCopyBuffer, ImageCopyTexture, ImageDataLayout, MapMode, MultiDrawIndexedIndirectCount,
+    MultisampleState, PipelineLayoutDescriptor, PipelineStatisticsTypes, PrimitiveState,
+    PrimitiveTopology, ProgrammablePassEncoder, QuerySetDescriptor, QueryType, Queue,
+    RenderBundleEncoder, RenderBundleEncoderDescriptor, RenderEncoder, RenderPassColorAttachment,
+    RenderPassDepthStencilAttachment, RenderPassDescriptor, RenderPassEncoder,
+    RenderPipelineDescriptor, ResolveQuerySet, SamplerBindingType, SamplerDescriptor,
+    SetIndexBuffer, SetVertexBuffer, ShaderStage, StencilFaceState, StencilOperation,
+    StorageTextureAccess, Texture, TextureAspect, TextureDescriptor, TextureDimensions,
+    TextureSampleType, TextureUsage, TextureViewDescriptor, TextureViewDimension,
+    WriteBufferOperation, WriteTextureOperation,
 };
 use crate::render_pipeline::{
-    BlendComponent, BlendFactor, BlendState, ColorWrite, CullMode, FrontFace, IndexFormat,
+    BlendComponent, BlendFactor, BlendState, ColorWriteMask, CullMode, FrontFace, IndexFormat,
     VertexAttribute, VertexBufferLayout, VertexFormat, VertexStepMode,
 };
 use crate::render_target::{LoadOp, StoreOp};
 use crate::sampler::{AddressMode, FilterMode};
 use crate::texture::format::TextureFormatId;
+use crate::texture::CreateTextureError;
 use crate::{driver, CompareFunction};
 
 // External reference counting based on the `Arc` implementation
@@ -104,6 +108,11 @@ impl Drop for DropTracker {
     }
 }
 
+// The reference count is atomic, so the tracker may be shared across threads in the same way as
+// an `Arc<AtomicUsize>`.
+unsafe impl Send for DropTracker {}
+unsafe impl Sync for DropTracker {}
+
 struct DropTrackerInner {
     count: AtomicUsize,
 }
@@ -118,8 +127,14 @@ impl Drop for PollToken {
     }
 }
 
+/// Polls the device on a background thread while there are pending futures (buffer mappings,
+/// submitted work done), so that these futures resolve without the user polling the device.
+///
+/// Automatic polling may be disabled for applications that poll the device themselves (see
+/// [DeviceHandle::set_auto_poll]); the pending futures then only resolve when the device is polled.
 struct PollRunner {
     done: Arc<AtomicBool>,
+    enabled: Arc<AtomicBool>,
     wait_count: Arc<AtomicUsize>,
     thread_handle: Option<JoinHandle<()>>,
 }
@@ -128,12 +143,14 @@ impl PollRunner {
     fn new(global: Arc<Global>, device_id: DeviceId) -> Self {
         let done = Arc::new(AtomicBool::new(false));
         let done_clone = done.clone();
+        let enabled = Arc::new(AtomicBool::new(true));
+        let enabled_clone = enabled.clone();
         let wait_count = Arc::new(AtomicUsize::new(0));
         let wait_count_clone = wait_count.clone();
 
         let thread_handle = thread::spawn(move || {
             while !done_clone.load(Acquire) {
-                while wait_count_clone.load(Acquire) > 0 {
+                while enabled_clone.load(Acquire) && wait_count_clone.load(Acquire) > 0 {
                     gfx_select!(device_id =>
                     global.device_poll(device_id, Maintain::Wait))
                     .expect("device timed out");
@@ -145,21 +162,38 @@ impl PollRunner {
 
         PollRunner {
             done,
+            enabled,
             wait_count,
             thread_handle: Some(thread_handle),
         }
     }
 
-    fn wait(&self) -> PollToken {
-        let wait_count = self.wait_count.clone();
+    fn is_enabled(&self) -> bool {
+        self.enabled.load(Acquire)
+    }
 
-        wait_count.fetch_add(1, Release);
+    fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Release);
+
+        if enabled {
+            self.unpark();
+        }
+    }
 
+    fn unpark(&self) {
         self.thread_handle
             .as_ref()
-            .expect("cannot call wait on a dropped PollRunner")
+            .expect("cannot unpark a dropped PollRunner")
             .thread()
             .unpark();
+    }
+
+    fn wait(&self) -> PollToken {
+        let wait_count = self.wait_count.clone();
+
+        wait_count.fetch_add(1, Release);
+
+        self.unpark();
 
         PollToken { wait_count }
     }
@@ -281,6 +315,7 @@ impl Adapter<Driver> for AdapterHandle {
             global: self.global.clone(),
             id: queue_id,
             drop_tracker: DropTracker::new(),
+            poll_runner: device_handle.poll_runner.clone(),
         };
 
         ready(Ok((device_handle, primary_queue_handle)))
@@ -311,16 +346,42 @@ pub struct DeviceHandle {
 }
 
 impl DeviceHandle {
+    pub fn global(&self) -> &Arc<Global> {
+        &self.global
+    }
+
     pub fn id(&self) -> DeviceId {
         self.id
     }
+
+    pub fn auto_poll(&self) -> bool {
+        self.poll_runner.is_enabled()
+    }
+
+    pub fn set_auto_poll(&self, enabled: bool) {
+        self.poll_runner.set_enabled(enabled);
+    }
+
+    pub fn poll(&self, maintain: Maintain<WrappedSubmissionIndex>) -> bool {
+        let res = gfx_select!(self.id => self.global.device_poll(self.id, maintain));
+
+        match res {
+            Ok(queue_empty) => queue_empty,
+            Err(err) => panic!("{}", err),
+        }
+    }
 }
 
 impl Device<Driver> for DeviceHandle {
-    type CreateComputePipelineAsync = future::Ready<ComputePipelineHandle>;
-    type CreateRenderPipelineAsync = future::Ready<RenderPipelineHandle>;
+    type CreateComputePipelineAsync =
+        future::Ready<Result<ComputePipelineHandle, PipelineCreationError>>;
+    type CreateRenderPipelineAsync =
+        future::Ready<Result<RenderPipelineHandle, PipelineCreationError>>;
 
-    fn create_buffer(&self, descriptor: &BufferDescriptor) -> BufferHandle {
+    fn create_buffer(
+        &self,
+        descriptor: &BufferDescriptor,
+    ) -> Result<BufferHandle, CreateBufferError> {
         let descriptor = wgc::resource::BufferDescriptor {
             label: None,
             size: descriptor.size as u64,
@@ -335,18 +396,35 @@ impl Device<Driver> for DeviceHandle {
         ));
 
         if let Some(err) = err {
-            panic!("{}", err)
+            return Err(match err {
+                wgc::resource::CreateBufferError::MaxBufferSize { requested, maximum } => {
+                    CreateBufferError::SizeLimitExceeded {
+                        size: requested,
+                        max_size: maximum,
+                    }
+                }
+                wgc::resource::CreateBufferError::InvalidUsage(_)
+                | wgc::resource::CreateBufferError::UsageMismatch(_) => {
+                    CreateBufferError::InvalidUsage
+                }
+                err => CreateBufferError::Other(error_message(&err)),
+            });
         }
 
-        BufferHandle {
+        Ok(BufferHandle {
             global: self.global.clone(),
             id,
             drop_tracker: DropTracker::new(),
             poll_runner: self.poll_runner.clone(),
-        }
+        })
     }
 
-    fn create_texture(&self, descriptor: &TextureDescriptor) -> TextureHandle {
+    fn create_texture(
+        &self,
+        descriptor: &TextureDescriptor,
+    ) -> Result<TextureHandle, CreateTextureError> {
+        let format = descriptor.format;
+
         let view_formats = descriptor
             .view_formats
             .iter()
@@ -370,14 +448,23 @@ impl Device<Driver> for DeviceHandle {
         ));
 
         if let Some(err) = err {
-            panic!("{}", err)
+            return Err(match err {
+                wgc::resource::CreateTextureError::InvalidUsage(_)
+                | wgc::resource::CreateTextureError::InvalidFormatUsages(..) => {
+                    CreateTextureError::InvalidUsage
+                }
+                wgc::resource::CreateTextureError::MissingFeatures(..) => {
+                    CreateTextureError::UnsupportedFormat(format)
+                }
+                err => CreateTextureError::Other(error_message(&err)),
+            });
         }
 
-        TextureHandle {
+        Ok(TextureHandle {
             global: self.global.clone(),
             id,
             drop_tracker: Some(DropTracker::new()),
-        }
+        })
     }
 
     fn create_sampler(&self, descriptor: &SamplerDescriptor) -> SamplerHandle {
@@ -571,7 +658,9 @@ impl Device<Driver> for DeviceHandle {
     fn create_compute_pipeline(
         &self,
         descriptor: &ComputePipelineDescriptor<Driver>,
-    ) -> ComputePipelineHandle {
+    ) -> Result<ComputePipelineHandle, PipelineCreationError> {
+        let entry_point = descriptor.entry_point;
+
         let descriptor = wgc::pipeline::ComputePipelineDescriptor {
             label: None,
             layout: Some(descriptor.layout.id),
@@ -591,14 +680,24 @@ impl Device<Driver> for DeviceHandle {
         ));
 
         if let Some(err) = err {
-            panic!("{}", err)
+            let entry_point = match &err {
+                wgc::pipeline::CreateComputePipelineError::Stage(_) => {
+                    Some(entry_point.to_string())
+                }
+                _ => None,
+            };
+
+            return Err(PipelineCreationError {
+                message: error_message(&err),
+                entry_point,
+            });
         }
 
-        ComputePipelineHandle {
+        Ok(ComputePipelineHandle {
             global: self.global.clone(),
             id,
             drop_tracker: DropTracker::new(),
-        }
+        })
     }
 
     fn create_compute_pipeline_async(
@@ -611,7 +710,10 @@ impl Device<Driver> for DeviceHandle {
     fn create_render_pipeline(
         &self,
         descriptor: &RenderPipelineDescriptor<Driver>,
-    ) -> RenderPipelineHandle {
+    ) -> Result<RenderPipelineHandle, PipelineCreationError> {
+        let vertex_entry_point = descriptor.vertex_state.entry_point;
+        let fragment_entry_point = descriptor.fragment_state.as_ref().map(|s| s.entry_point);
+
         let vertex_buffers: ArrayVec<_, { wgc::MAX_VERTEX_BUFFERS }> = descriptor
             .vertex_state
             .vertex_buffer_layouts
@@ -655,7 +757,7 @@ impl Device<Driver> for DeviceHandle {
                     targets: targets.as_slice().into(),
                 }
             }),
-            multiview: None,
+            multiview: descriptor.multiview,
         };
 
         let (id, err) = gfx_select!(self.id => self.global.device_create_render_pipeline(
@@ -666,14 +768,28 @@ impl Device<Driver> for DeviceHandle {
         ));
 
         if let Some(err) = err {
-            panic!("{}", err)
+            let entry_point = match &err {
+                wgc::pipeline::CreateRenderPipelineError::Stage { stage, .. } => {
+                    if stage.contains(wgt::ShaderStages::FRAGMENT) {
+                        fragment_entry_point.map(|e| e.to_string())
+                    } else {
+                        Some(vertex_entry_point.to_string())
+                    }
+                }
+                _ => None,
+            };
+
+            return Err(PipelineCreationError {
+                message: error_message(&err),
+                entry_point,
+            });
         }
 
-        RenderPipelineHandle {
+        Ok(RenderPipelineHandle {
             global: self.global.clone(),
             id,
             drop_tracker: DropTracker::new(),
-        }
+        })
     }
 
     fn create_render_pipeline_async(
@@ -758,6 +874,12 @@ pub struct BufferHandle {
     poll_runner: Arc<PollRunner>,
 }
 
+impl BufferHandle {
+    pub fn id(&self) -> BufferId {
+        self.id
+    }
+}
+
 impl Buffer<Driver> for BufferHandle {
     type Map = Map;
     type Mapped<'a, E: 'a> = &'a [E];
@@ -839,6 +961,14 @@ impl Buffer<Driver> for BufferHandle {
             size: NonZeroU64::new(size as u64),
         }
     }
+
+    fn destroy(&self) {
+        let res = gfx_select!(self.id => self.global.buffer_destroy(self.id));
+
+        if let Err(err) = res {
+            panic!("{}", err);
+        }
+    }
 }
 
 impl Drop for BufferHandle {
@@ -927,8 +1057,8 @@ impl Future for Map {
                 )
             );
 
-            if let Err(_) = res {
-                return Poll::Ready(Err(MapError));
+            if let Err(err) = res {
+                return Poll::Ready(Err(map_error(err)));
             }
 
             this.state.poll_token = Some(this.poll_runner.wait());
@@ -936,24 +1066,30 @@ impl Future for Map {
             return Poll::Pending;
         }
 
-        if let Some(status) = this.state.status.as_ref() {
+        if let Some(status) = this.state.status.take() {
             // It's unclear if this case can ever happen as the callback should have already dropped
             // the token, but just in case: make sure the PollRunner will not run indefinitely.
             if let Some(token) = this.state.poll_token.take() {
                 mem::drop(token);
             }
 
-            if status.is_err() {
-                Poll::Ready(Err(MapError))
-            } else {
-                Poll::Ready(Ok(()))
-            }
+            Poll::Ready(status.map_err(map_error))
         } else {
             Poll::Pending
         }
     }
 }
 
+fn map_error(err: BufferAccessError) -> MapError {
+    match err {
+        BufferAccessError::Device(wgc::device::DeviceError::Lost) => MapError::DeviceLost,
+        BufferAccessError::Destroyed => MapError::Destroyed,
+        BufferAccessError::MapAborted => MapError::Aborted,
+        BufferAccessError::Device(err) => MapError::Other(err.to_string()),
+        err => MapError::Validation(err.to_string()),
+    }
+}
+
 #[derive(Clone)]
 pub struct TextureHandle {
     global: Arc<Global>,
@@ -1003,6 +1139,14 @@ impl Texture<Driver> for TextureHandle {
             drop_tracker: DropTracker::new(),
         }
     }
+
+    fn destroy(&self) {
+        let res = gfx_select!(self.id => self.global.texture_destroy(self.id));
+
+        if let Err(err) = res {
+            panic!("{}", err);
+        }
+    }
 }
 
 impl Drop for TextureHandle {
@@ -1237,6 +1381,18 @@ impl ComputePassEncoder<Driver> for ComputePassEncoderHandle {
         );
     }
 
+    fn begin_pipeline_statistics_query(&mut self, query_set: &QuerySetHandle, query_index: u32) {
+        compute_commands::wgpu_compute_pass_begin_pipeline_statistics_query(
+            &mut self.compute_pass,
+            query_set.id,
+            query_index,
+        );
+    }
+
+    fn end_pipeline_statistics_query(&mut self) {
+        compute_commands::wgpu_compute_pass_end_pipeline_statistics_query(&mut self.compute_pass);
+    }
+
     fn end(self) {
         let encoder_id = self.compute_pass.parent_id();
 
@@ -1384,6 +1540,29 @@ impl RenderPassEncoder<Driver> for RenderPassEncoderHandle {
         render_commands::wgpu_render_pass_end_occlusion_query(&mut self.render_pass);
     }
 
+    fn begin_pipeline_statistics_query(&mut self, query_set: &QuerySetHandle, query_index: u32) {
+        render_commands::wgpu_render_pass_begin_pipeline_statistics_query(
+            &mut self.render_pass,
+            query_set.id,
+            query_index,
+        );
+    }
+
+    fn end_pipeline_statistics_query(&mut self) {
+        render_commands::wgpu_render_pass_end_pipeline_statistics_query(&mut self.render_pass);
+    }
+
+    fn multi_draw_indexed_indirect_count(&mut self, op: MultiDrawIndexedIndirectCount<Driver>) {
+        render_commands::wgpu_render_pass_multi_draw_indexed_indirect_count(
+            &mut self.render_pass,
+            op.buffer_handle.id,
+            op.offset as u64,
+            op.count_buffer_handle.id,
+            op.count_offset as u64,
+            op.max_count,
+        );
+    }
+
     fn execute_bundles(&mut self) -> ExecuteRenderBundlesEncoderHandle {
         ExecuteRenderBundlesEncoderHandle {
             render_pass: &mut self.render_pass,
@@ -1560,18 +1739,65 @@ pub struct QueueHandle {
     global: Arc<Global>,
     id: QueueId,
     drop_tracker: DropTracker,
+    poll_runner: Arc<PollRunner>,
+}
+
+impl QueueHandle {
+    pub fn id(&self) -> QueueId {
+        self.id
+    }
 }
 
 impl Queue<Driver> for QueueHandle {
-    fn submit(&self, command_buffer: &CommandBufferHandle) {
+    type OnSubmittedWorkDone = OnSubmittedWorkDone;
+
+    type SubmissionIndex = WrappedSubmissionIndex;
+
+    fn submit(&self, command_buffer: &CommandBufferHandle) -> WrappedSubmissionIndex {
         let res = gfx_select!(self.id => self.global.queue_submit(
             self.id,
             &[command_buffer.id],
         ));
 
+        match res {
+            Ok(index) => index,
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    fn on_submitted_work_done(&self) -> OnSubmittedWorkDone {
+        // Register the callback eagerly (rather than on first poll), so that the future resolves
+        // for the work that was submitted at the time of this call, not at the time of first poll.
+        let state = Arc::new(Mutex::new(WorkDoneState {
+            done: false,
+            waker: None,
+            poll_token: Some(self.poll_runner.wait()),
+        }));
+        let state_clone = state.clone();
+
+        let closure =
+            wgc::device::queue::SubmittedWorkDoneClosure::from_rust(Box::new(move || {
+                let mut state = state_clone.lock().unwrap();
+
+                state.done = true;
+
+                if let Some(token) = state.poll_token.take() {
+                    mem::drop(token);
+                }
+
+                if let Some(waker) = state.waker.take() {
+                    waker.wake();
+                }
+            }));
+
+        let res =
+            gfx_select!(self.id => self.global.queue_on_submitted_work_done(self.id, closure));
+
         if let Err(err) = res {
             panic!("{}", err)
         }
+
+        OnSubmittedWorkDone { state }
     }
 
     fn write_buffer(&self, operation: WriteBufferOperation<Driver>) {
@@ -1600,6 +1826,15 @@ impl Queue<Driver> for QueueHandle {
             panic!("{}", err)
         }
     }
+
+    fn timestamp_period(&self) -> f32 {
+        let res = gfx_select!(self.id => self.global.queue_get_timestamp_period(self.id));
+
+        match res {
+            Ok(period) => period,
+            Err(err) => panic!("{}", err),
+        }
+    }
 }
 
 impl Drop for QueueHandle {
@@ -1610,6 +1845,33 @@ impl Drop for QueueHandle {
     }
 }
 
+struct WorkDoneState {
+    done: bool,
+    waker: Option<Waker>,
+    poll_token: Option<PollToken>,
+}
+
+#[must_use = "futures do nothing if they are not polled"]
+pub struct OnSubmittedWorkDone {
+    state: Arc<Mutex<WorkDoneState>>,
+}
+
+impl Future for OnSubmittedWorkDone {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.state.lock().unwrap();
+
+        if state.done {
+            Poll::Ready(())
+        } else {
+            state.waker = Some(cx.waker().clone());
+
+            Poll::Pending
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct SamplerHandle {
     global: Arc<Global>,
@@ -1715,6 +1977,21 @@ impl Drop for ShaderModuleHandle {
     }
 }
 
+// The top-level wgpu-core errors tend to be terse; include the messages of the underlying errors.
+fn error_message(err: &dyn Error) -> String {
+    let mut message = err.to_string();
+    let mut source = err.source();
+
+    while let Some(err) = source {
+        message.push_str(": ");
+        message.push_str(&err.to_string());
+
+        source = err.source();
+    }
+
+    message
+}
+
 fn features_from_wgc(raw: wgt::Features) -> FlagSet<Feature> {
     let mut features = FlagSet::from(Feature::Depth24UNormStencil8);
 
@@ -1758,6 +2035,26 @@ fn features_from_wgc(raw: wgt::Features) -> FlagSet<Feature> {
         features |= Feature::TimestampQueryInsideEncoders
     }
 
+    if raw.contains(wgt::Features::MULTIVIEW) {
+        features |= Feature::Multiview
+    }
+
+    if raw.contains(wgt::Features::TEXTURE_FORMAT_16BIT_NORM) {
+        features |= Feature::TextureFormat16BitNorm
+    }
+
+    if raw.contains(wgt::Features::PIPELINE_STATISTICS_QUERY) {
+        features |= Feature::PipelineStatisticsQuery
+    }
+
+    if raw.contains(wgt::Features::MULTI_DRAW_INDIRECT_COUNT) {
+        features |= Feature::MultiDrawIndirectCount
+    }
+
+    if raw.contains(wgt::Features::SUBGROUP) {
+        features |= Feature::Subgroups
+    }
+
     features
 }
 
@@ -1804,6 +2101,26 @@ pub fn features_to_wgc(features: &FlagSet<Feature>) -> wgt::Features {
         out |= wgt::Features::TIMESTAMP_QUERY_INSIDE_ENCODERS;
     }
 
+    if features.contains(Feature::Multiview) {
+        out |= wgt::Features::MULTIVIEW;
+    }
+
+    if features.contains(Feature::TextureFormat16BitNorm) {
+        out |= wgt::Features::TEXTURE_FORMAT_16BIT_NORM;
+    }
+
+    if features.contains(Feature::PipelineStatisticsQuery) {
+        out |= wgt::Features::PIPELINE_STATISTICS_QUERY;
+    }
+
+    if features.contains(Feature::MultiDrawIndirectCount) {
+        out |= wgt::Features::MULTI_DRAW_INDIRECT_COUNT;
+    }
+
+    if features.contains(Feature::Subgroups) {
+        out |= wgt::Features::SUBGROUP;
+    }
+
     out
 }
 
@@ -1841,6 +2158,8 @@ pub fn limits_from_wgc(limits: &wgt::Limits) -> Limits {
         max_compute_workgroup_size_y: limits.max_compute_workgroup_size_y,
         max_compute_workgroup_size_z: limits.max_compute_workgroup_size_z,
         max_compute_workgroups_per_dimension: limits.max_compute_workgroups_per_dimension,
+        min_subgroup_size: limits.min_subgroup_size,
+        max_subgroup_size: limits.max_subgroup_size,
     }
 }
 
@@ -1914,6 +2233,7 @@ pub fn texture_format_to_wgc(texture_format: &TextureFormatId) -> wgt::TextureFo
         TextureFormatId::bgra8unorm_srgb => wgt::TextureFormat::Bgra8UnormSrgb,
         TextureFormatId::rgb9e5ufloat => wgt::TextureFormat::Rgb9e5Ufloat,
         TextureFormatId::rgb10a2unorm => wgt::TextureFormat::Rgb10a2Unorm,
+        TextureFormatId::rgb10a2uint => wgt::TextureFormat::Rgb10a2Uint,
         TextureFormatId::rg11b10ufloat => wgt::TextureFormat::Rg11b10Float,
         TextureFormatId::rg32uint => wgt::TextureFormat::Rg32Uint,
         TextureFormatId::rg32sint => wgt::TextureFormat::Rg32Sint,
@@ -1921,6 +2241,18 @@ pub fn texture_format_to_wgc(texture_format: &TextureFormatId) -> wgt::TextureFo
         TextureFormatId::rgba16uint => wgt::TextureFormat::Rgba16Uint,
         TextureFormatId::rgba16sint => wgt::TextureFormat::Rgba16Sint,
         TextureFormatId::rgba16float => wgt::TextureFormat::Rgba16Float,
+        #[cfg(feature = "norm16")]
+        TextureFormatId::r16unorm => wgt::TextureFormat::R16Unorm,
+        #[cfg(feature = "norm16")]
+        TextureFormatId::r16snorm => wgt::TextureFormat::R16Snorm,
+        #[cfg(feature = "norm16")]
+        TextureFormatId::rg16unorm => wgt::TextureFormat::Rg16Unorm,
+        #[cfg(feature = "norm16")]
+        TextureFormatId::rg16snorm => wgt::TextureFormat::Rg16Snorm,
+        #[cfg(feature = "norm16")]
+        TextureFormatId::rgba16unorm => wgt::TextureFormat::Rgba16Unorm,
+        #[cfg(feature = "norm16")]
+        TextureFormatId::rgba16snorm => wgt::TextureFormat::Rgba16Snorm,
         TextureFormatId::rgba32uint => wgt::TextureFormat::Rgba32Uint,
         TextureFormatId::rgba32sint => wgt::TextureFormat::Rgba32Sint,
         TextureFormatId::rgba32float => wgt::TextureFormat::Rgba32Float,
@@ -2243,7 +2575,38 @@ pub fn query_type_to_wgc(query_type: &QueryType) -> wgt::QueryType {
     match query_type {
         QueryType::Occlusion => wgt::QueryType::Occlusion,
         QueryType::Timestamp => wgt::QueryType::Timestamp,
+        QueryType::PipelineStatistics(types) => {
+            wgt::QueryType::PipelineStatistics(pipeline_statistics_types_to_wgc(types))
+        }
+    }
+}
+
+pub fn pipeline_statistics_types_to_wgc(
+    types: &PipelineStatisticsTypes,
+) -> wgt::PipelineStatisticsTypes {
+    let mut out = wgt::PipelineStatisticsTypes::empty();
+
+    if types.vertex_shader_invocations {
+        out |= wgt::PipelineStatisticsTypes::VERTEX_SHADER_INVOCATIONS;
+    }
+
+    if types.clipper_invocations {
+        out |= wgt::PipelineStatisticsTypes::CLIPPER_INVOCATIONS;
     }
+
+    if types.clipper_primitives_out {
+        out |= wgt::PipelineStatisticsTypes::CLIPPER_PRIMITIVES_OUT;
+    }
+
+    if types.fragment_shader_invocations {
+        out |= wgt::PipelineStatisticsTypes::FRAGMENT_SHADER_INVOCATIONS;
+    }
+
+    if types.compute_shader_invocations {
+        out |= wgt::PipelineStatisticsTypes::COMPUTE_SHADER_INVOCATIONS;
+    }
+
+    out
 }
 
 pub fn vertex_step_mode_to_wgc(vertex_step_mode: &VertexStepMode) -> wgt::VertexStepMode {
@@ -2473,7 +2836,7 @@ pub fn blend_state_to_wgc(blend_state: &BlendState) -> wgt::BlendState {
     }
 }
 
-pub fn color_write_wgc(color_write: &FlagSet<ColorWrite>) -> wgt::ColorWrites {
+pub fn color_write_wgc(color_write: &ColorWriteMask) -> wgt::ColorWrites {
     wgt::ColorWrites::from_bits_retain(color_write.bits())
 }
 
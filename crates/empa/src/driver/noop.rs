@@ -0,0 +1,1004 @@
+use std::alloc::Layout;
+use std::borrow::Borrow;
+use std::error::Error;
+use std::future::{ready, Ready};
+use std::ops::Range;
+use std::ptr::NonNull;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::{alloc, mem, slice};
+
+use flagset::FlagSet;
+
+use crate::adapter::{Feature, Limits};
+use crate::buffer::{CreateBufferError, MapError};
+use crate::command::{BlendConstant, Draw, DrawIndexed, ScissorRect, Viewport};
+use crate::device::{DeviceDescriptor, PipelineCreationError};
+use crate::driver;
+use crate::driver::{
+    Adapter, BindGroupDescriptor, BindGroupEntry, BindGroupLayoutDescriptor, BindGroupLayoutEntry,
+    Buffer, BufferDescriptor, ClearBuffer, CommandEncoder, ComputePassEncoder,
+    ComputePipelineDescriptor, CopyBufferToBuffer, CopyBufferToTexture, CopyTextureToBuffer,
+    CopyTextureToTexture, Device, ExecuteRenderBundlesEncoder, MapMode,
+    MultiDrawIndexedIndirectCount, PipelineLayoutDescriptor, ProgrammablePassEncoder,
+    QuerySetDescriptor, Queue, RenderBundleEncoder, RenderBundleEncoderDescriptor, RenderEncoder,
+    RenderPassColorAttachment, RenderPassDescriptor, RenderPassEncoder, RenderPipelineDescriptor,
+    ResolveQuerySet, SamplerDescriptor, SetIndexBuffer, SetVertexBuffer, Texture,
+    TextureDescriptor, TextureViewDescriptor, WriteBufferOperation, WriteTextureOperation,
+};
+use crate::render_pipeline::IndexFormat;
+use crate::texture::CreateTextureError;
+
+/// Identifies a resource created by a device that uses the no-op driver.
+///
+/// Identifiers are assigned sequentially per device, starting at `1`, in the order in which the
+/// resources are created.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct ResourceId(pub u64);
+
+/// An operation recorded by a command encoder, compute pass encoder, render pass encoder or render
+/// bundle encoder.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Command {
+    CopyBufferToBuffer {
+        source: ResourceId,
+        source_offset: usize,
+        destination: ResourceId,
+        destination_offset: usize,
+        size: usize,
+    },
+    CopyBufferToTexture {
+        buffer: ResourceId,
+        buffer_offset: usize,
+        texture: ResourceId,
+        mip_level: u32,
+        origin: (u32, u32, u32),
+        copy_size: (u32, u32, u32),
+    },
+    CopyTextureToBuffer {
+        texture: ResourceId,
+        mip_level: u32,
+        origin: (u32, u32, u32),
+        buffer: ResourceId,
+        buffer_offset: usize,
+        copy_size: (u32, u32, u32),
+    },
+    CopyTextureToTexture {
+        source: ResourceId,
+        source_mip_level: u32,
+        source_origin: (u32, u32, u32),
+        destination: ResourceId,
+        destination_mip_level: u32,
+        destination_origin: (u32, u32, u32),
+        copy_size: (u32, u32, u32),
+    },
+    ClearBuffer {
+        buffer: ResourceId,
+        range: Range<usize>,
+    },
+    WriteTimestamp {
+        query_set: ResourceId,
+        index: usize,
+    },
+    ResolveQuerySet {
+        query_set: ResourceId,
+        query_range: Range<usize>,
+        destination: ResourceId,
+        destination_offset: usize,
+    },
+    BeginComputePass,
+    SetComputePipeline {
+        pipeline: ResourceId,
+    },
+    DispatchWorkgroups {
+        x: u32,
+        y: u32,
+        z: u32,
+    },
+    DispatchWorkgroupsIndirect {
+        buffer: ResourceId,
+        offset: usize,
+    },
+    EndComputePass,
+    BeginRenderPass {
+        /// The textures of the color attachment views; `None` for unused attachment slots.
+        color_attachments: Vec<Option<ResourceId>>,
+        /// The texture of the depth-stencil attachment view, if any.
+        depth_stencil_attachment: Option<ResourceId>,
+        occlusion_query_set: Option<ResourceId>,
+    },
+    SetRenderPipeline {
+        pipeline: ResourceId,
+    },
+    SetBindGroup {
+        index: u32,
+        bind_group: ResourceId,
+    },
+    SetIndexBuffer {
+        buffer: ResourceId,
+        index_format: IndexFormat,
+        range: Option<Range<usize>>,
+    },
+    SetVertexBuffer {
+        slot: u32,
+        buffer: ResourceId,
+        range: Option<Range<usize>>,
+    },
+    Draw(Draw),
+    DrawIndexed(DrawIndexed),
+    DrawIndirect {
+        buffer: ResourceId,
+        offset: usize,
+    },
+    DrawIndexedIndirect {
+        buffer: ResourceId,
+        offset: usize,
+    },
+    MultiDrawIndexedIndirectCount {
+        buffer: ResourceId,
+        offset: usize,
+        count_buffer: ResourceId,
+        count_offset: usize,
+        max_count: u32,
+    },
+    SetViewport(Viewport),
+    SetScissorRect(ScissorRect),
+    SetBlendConstant(BlendConstant),
+    SetStencilReference(u32),
+    BeginOcclusionQuery(u32),
+    EndOcclusionQuery,
+    BeginPipelineStatisticsQuery {
+        query_set: ResourceId,
+        index: u32,
+    },
+    EndPipelineStatisticsQuery,
+    ExecuteBundle {
+        bundle: ResourceId,
+        commands: Arc<Vec<Command>>,
+    },
+    EndRenderPass,
+}
+
+/// An operation recorded by a queue.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Operation {
+    Submit {
+        command_buffer: ResourceId,
+        commands: Arc<Vec<Command>>,
+    },
+    WriteBuffer {
+        buffer: ResourceId,
+        offset: usize,
+        data: Vec<u8>,
+    },
+    WriteTexture {
+        texture: ResourceId,
+        mip_level: u32,
+        origin: (u32, u32, u32),
+        extent: (u32, u32, u32),
+        data_len: usize,
+    },
+}
+
+#[derive(Clone)]
+struct IdGenerator {
+    next: Arc<AtomicU64>,
+}
+
+impl IdGenerator {
+    fn new() -> Self {
+        IdGenerator {
+            next: Arc::new(AtomicU64::new(1)),
+        }
+    }
+
+    fn get(&self) -> ResourceId {
+        ResourceId(self.next.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+pub struct Driver;
+
+impl driver::Driver for Driver {
+    type AdapterHandle = AdapterHandle;
+    type BindGroupHandle = BindGroupHandle;
+    type DeviceHandle = DeviceHandle;
+    type BufferHandle = BufferHandle;
+    type BufferBinding = BufferBinding;
+    type TextureHandle = TextureHandle;
+    type TextureView = TextureView;
+    type CommandEncoderHandle = CommandEncoderHandle;
+    type ComputePassEncoderHandle = ComputePassEncoderHandle;
+    type RenderPassEncoderHandle = RenderPassEncoderHandle;
+    type ExecuteRenderBundlesEncoder<'a> = ExecuteRenderBundlesEncoderHandle<'a>;
+    type RenderBundleEncoderHandle = RenderBundleEncoderHandle;
+    type CommandBufferHandle = CommandBufferHandle;
+    type RenderBundleHandle = RenderBundleHandle;
+    type QueueHandle = QueueHandle;
+    type SamplerHandle = SamplerHandle;
+    type BindGroupLayoutHandle = BindGroupLayoutHandle;
+    type PipelineLayoutHandle = PipelineLayoutHandle;
+    type ComputePipelineHandle = ComputePipelineHandle;
+    type RenderPipelineHandle = RenderPipelineHandle;
+    type QuerySetHandle = QuerySetHandle;
+    type ShaderModuleHandle = ShaderModuleHandle;
+}
+
+#[derive(Clone)]
+pub struct AdapterHandle {
+    features: FlagSet<Feature>,
+    limits: Limits,
+}
+
+impl AdapterHandle {
+    pub fn new(features: FlagSet<Feature>, limits: Limits) -> Self {
+        AdapterHandle { features, limits }
+    }
+}
+
+impl Adapter<Driver> for AdapterHandle {
+    type RequestDevice = Ready<Result<(DeviceHandle, QueueHandle), Box<dyn Error>>>;
+
+    fn supported_features(&self) -> FlagSet<Feature> {
+        self.features
+    }
+
+    fn supported_limits(&self) -> Limits {
+        self.limits
+    }
+
+    fn request_device<Flags>(&self, _descriptor: &DeviceDescriptor<Flags>) -> Self::RequestDevice
+    where
+        Flags: Into<FlagSet<Feature>> + Copy,
+    {
+        let log = Arc::new(Mutex::new(Vec::new()));
+
+        let device_handle = DeviceHandle {
+            ids: IdGenerator::new(),
+            log: log.clone(),
+        };
+        let queue_handle = QueueHandle { log };
+
+        ready(Ok((device_handle, queue_handle)))
+    }
+}
+
+#[derive(Clone)]
+pub struct DeviceHandle {
+    ids: IdGenerator,
+    log: Arc<Mutex<Vec<Operation>>>,
+}
+
+impl DeviceHandle {
+    pub fn log(&self) -> &Arc<Mutex<Vec<Operation>>> {
+        &self.log
+    }
+}
+
+impl Device<Driver> for DeviceHandle {
+    type CreateComputePipelineAsync = Ready<Result<ComputePipelineHandle, PipelineCreationError>>;
+    type CreateRenderPipelineAsync = Ready<Result<RenderPipelineHandle, PipelineCreationError>>;
+
+    fn create_buffer(
+        &self,
+        descriptor: &BufferDescriptor,
+    ) -> Result<BufferHandle, CreateBufferError> {
+        Ok(BufferHandle {
+            id: self.ids.get(),
+            memory: Arc::new(BufferMemory::new(descriptor.size)),
+        })
+    }
+
+    fn create_texture(
+        &self,
+        _descriptor: &TextureDescriptor,
+    ) -> Result<TextureHandle, CreateTextureError> {
+        Ok(TextureHandle { id: self.ids.get() })
+    }
+
+    fn create_sampler(&self, _descriptor: &SamplerDescriptor) -> SamplerHandle {
+        SamplerHandle { id: self.ids.get() }
+    }
+
+    fn create_bind_group_layout<I>(
+        &self,
+        _descriptor: BindGroupLayoutDescriptor<I>,
+    ) -> BindGroupLayoutHandle
+    where
+        I: IntoIterator<Item = BindGroupLayoutEntry>,
+    {
+        BindGroupLayoutHandle { id: self.ids.get() }
+    }
+
+    fn create_pipeline_layout<I>(
+        &self,
+        _descriptor: PipelineLayoutDescriptor<I>,
+    ) -> PipelineLayoutHandle
+    where
+        I: IntoIterator,
+        I::Item: Borrow<BindGroupLayoutHandle>,
+    {
+        PipelineLayoutHandle { id: self.ids.get() }
+    }
+
+    fn create_bind_group<'a, E>(
+        &self,
+        _descriptor: BindGroupDescriptor<Driver, E>,
+    ) -> BindGroupHandle
+    where
+        E: IntoIterator<Item = BindGroupEntry<'a, Driver>>,
+    {
+        BindGroupHandle { id: self.ids.get() }
+    }
+
+    fn create_query_set(&self, _descriptor: &QuerySetDescriptor) -> QuerySetHandle {
+        QuerySetHandle { id: self.ids.get() }
+    }
+
+    fn create_shader_module(&self, _source: &str) -> ShaderModuleHandle {
+        ShaderModuleHandle { id: self.ids.get() }
+    }
+
+    fn create_compute_pipeline(
+        &self,
+        _descriptor: &ComputePipelineDescriptor<Driver>,
+    ) -> Result<ComputePipelineHandle, PipelineCreationError> {
+        Ok(ComputePipelineHandle { id: self.ids.get() })
+    }
+
+    fn create_compute_pipeline_async(
+        &self,
+        descriptor: &ComputePipelineDescriptor<Driver>,
+    ) -> Self::CreateComputePipelineAsync {
+        ready(self.create_compute_pipeline(descriptor))
+    }
+
+    fn create_render_pipeline(
+        &self,
+        _descriptor: &RenderPipelineDescriptor<Driver>,
+    ) -> Result<RenderPipelineHandle, PipelineCreationError> {
+        Ok(RenderPipelineHandle { id: self.ids.get() })
+    }
+
+    fn create_render_pipeline_async(
+        &self,
+        descriptor: &RenderPipelineDescriptor<Driver>,
+    ) -> Self::CreateRenderPipelineAsync {
+        ready(self.create_render_pipeline(descriptor))
+    }
+
+    fn create_command_encoder(&self) -> CommandEncoderHandle {
+        CommandEncoderHandle {
+            ids: self.ids.clone(),
+            commands: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    fn create_render_bundle_encoder(
+        &self,
+        _descriptor: &RenderBundleEncoderDescriptor,
+    ) -> RenderBundleEncoderHandle {
+        RenderBundleEncoderHandle {
+            id: self.ids.get(),
+            commands: Vec::new(),
+        }
+    }
+}
+
+// Host memory that backs a buffer for the lifetime of the buffer, so that data written when the
+// buffer is mapped at creation, or written with a queue write, can be read back by mapping the
+// buffer. Note that the memory is not modified by any GPU commands (e.g. copies).
+struct BufferMemory {
+    ptr: NonNull<u8>,
+    layout: Layout,
+}
+
+impl BufferMemory {
+    fn new(size: usize) -> Self {
+        let layout = Layout::from_size_align(size.max(1), 16).unwrap();
+        let ptr = unsafe { alloc::alloc_zeroed(layout) };
+
+        BufferMemory {
+            ptr: NonNull::new(ptr).unwrap_or_else(|| alloc::handle_alloc_error(layout)),
+            layout,
+        }
+    }
+}
+
+impl Drop for BufferMemory {
+    fn drop(&mut self) {
+        unsafe { alloc::dealloc(self.ptr.as_ptr(), self.layout) }
+    }
+}
+
+unsafe impl Send for BufferMemory {}
+unsafe impl Sync for BufferMemory {}
+
+#[derive(Clone)]
+pub struct BufferHandle {
+    id: ResourceId,
+    memory: Arc<BufferMemory>,
+}
+
+impl BufferHandle {
+    pub fn id(&self) -> ResourceId {
+        self.id
+    }
+}
+
+impl Buffer<Driver> for BufferHandle {
+    type Map = Ready<Result<(), MapError>>;
+    type Mapped<'a, E: 'a> = &'a [E];
+    type MappedMut<'a, E: 'a> = &'a mut [E];
+
+    fn map(&self, _mode: MapMode, _range: Range<usize>) -> Self::Map {
+        ready(Ok(()))
+    }
+
+    // As with the other drivers, the rest of empa is responsible for not handing out overlapping
+    // mapped ranges.
+
+    fn mapped<'a, E>(&'a self, offset_in_bytes: usize, len_in_elements: usize) -> &'a [E] {
+        let size = len_in_elements * mem::size_of::<E>();
+
+        assert!(
+            offset_in_bytes + size <= self.memory.layout.size(),
+            "mapped range out of bounds"
+        );
+
+        unsafe {
+            let ptr = self.memory.ptr.as_ptr().add(offset_in_bytes) as *const E;
+
+            slice::from_raw_parts(ptr, len_in_elements)
+        }
+    }
+
+    fn mapped_mut<'a, E>(&'a self, offset_in_bytes: usize, len_in_elements: usize) -> &'a mut [E] {
+        let size = len_in_elements * mem::size_of::<E>();
+
+        assert!(
+            offset_in_bytes + size <= self.memory.layout.size(),
+            "mapped range out of bounds"
+        );
+
+        unsafe {
+            let ptr = self.memory.ptr.as_ptr().add(offset_in_bytes) as *mut E;
+
+            slice::from_raw_parts_mut(ptr, len_in_elements)
+        }
+    }
+
+    fn unmap(&self) {}
+
+    fn binding(&self, offset: usize, size: usize) -> BufferBinding {
+        BufferBinding {
+            buffer: self.id,
+            offset,
+            size,
+        }
+    }
+
+    fn destroy(&self) {}
+}
+
+#[derive(Clone)]
+pub struct BufferBinding {
+    #[allow(unused)]
+    buffer: ResourceId,
+    #[allow(unused)]
+    offset: usize,
+    #[allow(unused)]
+    size: usize,
+}
+
+#[derive(Clone)]
+pub struct TextureHandle {
+    id: ResourceId,
+}
+
+impl TextureHandle {
+    pub fn id(&self) -> ResourceId {
+        self.id
+    }
+}
+
+impl Texture<Driver> for TextureHandle {
+    fn texture_view(&self, _descriptor: &TextureViewDescriptor) -> TextureView {
+        TextureView { texture: self.id }
+    }
+
+    fn destroy(&self) {}
+}
+
+#[derive(Clone)]
+pub struct TextureView {
+    texture: ResourceId,
+}
+
+pub struct CommandEncoderHandle {
+    ids: IdGenerator,
+    commands: Arc<Mutex<Vec<Command>>>,
+}
+
+impl CommandEncoderHandle {
+    fn push(&mut self, command: Command) {
+        self.commands.lock().unwrap().push(command);
+    }
+}
+
+impl CommandEncoder<Driver> for CommandEncoderHandle {
+    fn copy_buffer_to_buffer(&mut self, op: CopyBufferToBuffer<Driver>) {
+        self.push(Command::CopyBufferToBuffer {
+            source: op.source.id,
+            source_offset: op.source_offset,
+            destination: op.destination.id,
+            destination_offset: op.destination_offset,
+            size: op.size,
+        });
+    }
+
+    fn copy_buffer_to_texture(&mut self, op: CopyBufferToTexture<Driver>) {
+        self.push(Command::CopyBufferToTexture {
+            buffer: op.source.buffer_handle.id,
+            buffer_offset: op.source.offset,
+            texture: op.destination.texture_handle.id,
+            mip_level: op.destination.mip_level,
+            origin: op.destination.origin,
+            copy_size: op.copy_size,
+        });
+    }
+
+    fn copy_texture_to_buffer(&mut self, op: CopyTextureToBuffer<Driver>) {
+        self.push(Command::CopyTextureToBuffer {
+            texture: op.source.texture_handle.id,
+            mip_level: op.source.mip_level,
+            origin: op.source.origin,
+            buffer: op.destination.buffer_handle.id,
+            buffer_offset: op.destination.offset,
+            copy_size: op.copy_size,
+        });
+    }
+
+    fn copy_texture_to_texture(&mut self, op: CopyTextureToTexture<Driver>) {
+        self.push(Command::CopyTextureToTexture {
+            source: op.source.texture_handle.id,
+            source_mip_level: op.source.mip_level,
+            source_origin: op.source.origin,
+            destination: op.destination.texture_handle.id,
+            destination_mip_level: op.destination.mip_level,
+            destination_origin: op.destination.origin,
+            copy_size: op.copy_size,
+        });
+    }
+
+    fn clear_buffer(&mut self, op: ClearBuffer<Driver>) {
+        self.push(Command::ClearBuffer {
+            buffer: op.buffer.id,
+            range: op.range,
+        });
+    }
+
+    fn begin_compute_pass(&mut self) -> ComputePassEncoderHandle {
+        self.push(Command::BeginComputePass);
+
+        ComputePassEncoderHandle {
+            commands: self.commands.clone(),
+        }
+    }
+
+    fn begin_render_pass<I>(
+        &mut self,
+        descriptor: RenderPassDescriptor<Driver, I>,
+    ) -> RenderPassEncoderHandle
+    where
+        I: IntoIterator<Item = Option<RenderPassColorAttachment<Driver>>>,
+    {
+        let color_attachments = descriptor
+            .color_attachments
+            .into_iter()
+            .map(|a| a.map(|a| a.view.texture))
+            .collect();
+
+        self.push(Command::BeginRenderPass {
+            color_attachments,
+            depth_stencil_attachment: descriptor.depth_stencil_attachment.map(|a| a.view.texture),
+            occlusion_query_set: descriptor.occlusion_query_set.map(|q| q.id),
+        });
+
+        RenderPassEncoderHandle {
+            commands: self.commands.clone(),
+        }
+    }
+
+    fn write_timestamp(&mut self, query_set: &QuerySetHandle, index: usize) {
+        self.push(Command::WriteTimestamp {
+            query_set: query_set.id,
+            index,
+        });
+    }
+
+    fn resolve_query_set(&mut self, op: ResolveQuerySet<Driver>) {
+        self.push(Command::ResolveQuerySet {
+            query_set: op.query_set.id,
+            query_range: op.query_range,
+            destination: op.destination.id,
+            destination_offset: op.destination_offset,
+        });
+    }
+
+    fn finish(self) -> CommandBufferHandle {
+        let commands = mem::take(&mut *self.commands.lock().unwrap());
+
+        CommandBufferHandle {
+            id: self.ids.get(),
+            commands: Arc::new(commands),
+        }
+    }
+}
+
+pub struct ComputePassEncoderHandle {
+    commands: Arc<Mutex<Vec<Command>>>,
+}
+
+impl ComputePassEncoderHandle {
+    fn push(&mut self, command: Command) {
+        self.commands.lock().unwrap().push(command);
+    }
+}
+
+impl ProgrammablePassEncoder<Driver> for ComputePassEncoderHandle {
+    fn set_bind_group(&mut self, index: u32, handle: &BindGroupHandle) {
+        self.push(Command::SetBindGroup {
+            index,
+            bind_group: handle.id,
+        });
+    }
+}
+
+impl ComputePassEncoder<Driver> for ComputePassEncoderHandle {
+    fn set_pipeline(&mut self, handle: &ComputePipelineHandle) {
+        self.push(Command::SetComputePipeline {
+            pipeline: handle.id,
+        });
+    }
+
+    fn dispatch_workgroups(&mut self, x: u32, y: u32, z: u32) {
+        self.push(Command::DispatchWorkgroups { x, y, z });
+    }
+
+    fn dispatch_workgroups_indirect(&mut self, buffer_handle: &BufferHandle, offset: usize) {
+        self.push(Command::DispatchWorkgroupsIndirect {
+            buffer: buffer_handle.id,
+            offset,
+        });
+    }
+
+    fn begin_pipeline_statistics_query(&mut self, query_set: &QuerySetHandle, query_index: u32) {
+        self.push(Command::BeginPipelineStatisticsQuery {
+            query_set: query_set.id,
+            index: query_index,
+        });
+    }
+
+    fn end_pipeline_statistics_query(&mut self) {
+        self.push(Command::EndPipelineStatisticsQuery);
+    }
+
+    fn end(mut self) {
+        self.push(Command::EndComputePass);
+    }
+}
+
+pub struct RenderPassEncoderHandle {
+    commands: Arc<Mutex<Vec<Command>>>,
+}
+
+impl RenderPassEncoderHandle {
+    fn push(&mut self, command: Command) {
+        self.commands.lock().unwrap().push(command);
+    }
+}
+
+impl ProgrammablePassEncoder<Driver> for RenderPassEncoderHandle {
+    fn set_bind_group(&mut self, index: u32, handle: &BindGroupHandle) {
+        self.push(Command::SetBindGroup {
+            index,
+            bind_group: handle.id,
+        });
+    }
+}
+
+impl RenderEncoder<Driver> for RenderPassEncoderHandle {
+    fn set_pipeline(&mut self, handle: &RenderPipelineHandle) {
+        self.push(Command::SetRenderPipeline {
+            pipeline: handle.id,
+        });
+    }
+
+    fn set_index_buffer(&mut self, op: SetIndexBuffer<Driver>) {
+        self.push(set_index_buffer_command(op));
+    }
+
+    fn set_vertex_buffer(&mut self, op: SetVertexBuffer<Driver>) {
+        self.push(set_vertex_buffer_command(op));
+    }
+
+    fn draw(&mut self, op: Draw) {
+        self.push(Command::Draw(op));
+    }
+
+    fn draw_indexed(&mut self, op: DrawIndexed) {
+        self.push(Command::DrawIndexed(op));
+    }
+
+    fn draw_indirect(&mut self, buffer_handle: &BufferHandle, offset: usize) {
+        self.push(Command::DrawIndirect {
+            buffer: buffer_handle.id,
+            offset,
+        });
+    }
+
+    fn draw_indexed_indirect(&mut self, buffer_handle: &BufferHandle, offset: usize) {
+        self.push(Command::DrawIndexedIndirect {
+            buffer: buffer_handle.id,
+            offset,
+        });
+    }
+}
+
+impl RenderPassEncoder<Driver> for RenderPassEncoderHandle {
+    fn set_viewport(&mut self, viewport: &Viewport) {
+        self.push(Command::SetViewport(*viewport));
+    }
+
+    fn set_scissor_rect(&mut self, scissor_rect: &ScissorRect) {
+        self.push(Command::SetScissorRect(*scissor_rect));
+    }
+
+    fn set_blend_constant(&mut self, blend_constant: &BlendConstant) {
+        self.push(Command::SetBlendConstant(*blend_constant));
+    }
+
+    fn set_stencil_reference(&mut self, stencil_reference: u32) {
+        self.push(Command::SetStencilReference(stencil_reference));
+    }
+
+    fn begin_occlusion_query(&mut self, query_index: u32) {
+        self.push(Command::BeginOcclusionQuery(query_index));
+    }
+
+    fn end_occlusion_query(&mut self) {
+        self.push(Command::EndOcclusionQuery);
+    }
+
+    fn begin_pipeline_statistics_query(&mut self, query_set: &QuerySetHandle, query_index: u32) {
+        self.push(Command::BeginPipelineStatisticsQuery {
+            query_set: query_set.id,
+            index: query_index,
+        });
+    }
+
+    fn end_pipeline_statistics_query(&mut self) {
+        self.push(Command::EndPipelineStatisticsQuery);
+    }
+
+    fn multi_draw_indexed_indirect_count(&mut self, op: MultiDrawIndexedIndirectCount<Driver>) {
+        self.push(Command::MultiDrawIndexedIndirectCount {
+            buffer: op.buffer_handle.id,
+            offset: op.offset,
+            count_buffer: op.count_buffer_handle.id,
+            count_offset: op.count_offset,
+            max_count: op.max_count,
+        });
+    }
+
+    fn execute_bundles<'a>(&'a mut self) -> ExecuteRenderBundlesEncoderHandle<'a> {
+        ExecuteRenderBundlesEncoderHandle { pass: self }
+    }
+
+    fn end(mut self) {
+        self.push(Command::EndRenderPass);
+    }
+}
+
+pub struct ExecuteRenderBundlesEncoderHandle<'a> {
+    pass: &'a mut RenderPassEncoderHandle,
+}
+
+impl ExecuteRenderBundlesEncoder<Driver> for ExecuteRenderBundlesEncoderHandle<'_> {
+    fn push_bundle(&mut self, bundle: &RenderBundleHandle) {
+        self.pass.push(Command::ExecuteBundle {
+            bundle: bundle.id,
+            commands: bundle.commands.clone(),
+        });
+    }
+
+    fn finish(self) {}
+}
+
+pub struct RenderBundleEncoderHandle {
+    id: ResourceId,
+    commands: Vec<Command>,
+}
+
+impl ProgrammablePassEncoder<Driver> for RenderBundleEncoderHandle {
+    fn set_bind_group(&mut self, index: u32, handle: &BindGroupHandle) {
+        self.commands.push(Command::SetBindGroup {
+            index,
+            bind_group: handle.id,
+        });
+    }
+}
+
+impl RenderEncoder<Driver> for RenderBundleEncoderHandle {
+    fn set_pipeline(&mut self, handle: &RenderPipelineHandle) {
+        self.commands.push(Command::SetRenderPipeline {
+            pipeline: handle.id,
+        });
+    }
+
+    fn set_index_buffer(&mut self, op: SetIndexBuffer<Driver>) {
+        self.commands.push(set_index_buffer_command(op));
+    }
+
+    fn set_vertex_buffer(&mut self, op: SetVertexBuffer<Driver>) {
+        self.commands.push(set_vertex_buffer_command(op));
+    }
+
+    fn draw(&mut self, op: Draw) {
+        self.commands.push(Command::Draw(op));
+    }
+
+    fn draw_indexed(&mut self, op: DrawIndexed) {
+        self.commands.push(Command::DrawIndexed(op));
+    }
+
+    fn draw_indirect(&mut self, buffer_handle: &BufferHandle, offset: usize) {
+        self.commands.push(Command::DrawIndirect {
+            buffer: buffer_handle.id,
+            offset,
+        });
+    }
+
+    fn draw_indexed_indirect(&mut self, buffer_handle: &BufferHandle, offset: usize) {
+        self.commands.push(Command::DrawIndexedIndirect {
+            buffer: buffer_handle.id,
+            offset,
+        });
+    }
+}
+
+impl RenderBundleEncoder<Driver> for RenderBundleEncoderHandle {
+    fn finish(self) -> RenderBundleHandle {
+        RenderBundleHandle {
+            id: self.id,
+            commands: Arc::new(self.commands),
+        }
+    }
+}
+
+fn set_index_buffer_command(op: SetIndexBuffer<Driver>) -> Command {
+    Command::SetIndexBuffer {
+        buffer: op.buffer_handle.id,
+        index_format: op.index_format,
+        range: op.range,
+    }
+}
+
+fn set_vertex_buffer_command(op: SetVertexBuffer<Driver>) -> Command {
+    Command::SetVertexBuffer {
+        slot: op.slot,
+        buffer: op.buffer_handle.id,
+        range: op.range,
+    }
+}
+
+#[derive(Clone)]
+pub struct CommandBufferHandle {
+    id: ResourceId,
+    commands: Arc<Vec<Command>>,
+}
+
+#[derive(Clone)]
+pub struct RenderBundleHandle {
+    id: ResourceId,
+    commands: Arc<Vec<Command>>,
+}
+
+#[derive(Clone)]
+pub struct QueueHandle {
+    log: Arc<Mutex<Vec<Operation>>>,
+}
+
+impl Queue<Driver> for QueueHandle {
+    type OnSubmittedWorkDone = Ready<()>;
+
+    type SubmissionIndex = ();
+
+    fn submit(&self, command_buffer: &CommandBufferHandle) {
+        self.log.lock().unwrap().push(Operation::Submit {
+            command_buffer: command_buffer.id,
+            commands: command_buffer.commands.clone(),
+        });
+    }
+
+    fn on_submitted_work_done(&self) -> Self::OnSubmittedWorkDone {
+        ready(())
+    }
+
+    fn write_buffer(&self, operation: WriteBufferOperation<Driver>) {
+        let WriteBufferOperation {
+            buffer_handle,
+            offset,
+            data,
+        } = operation;
+
+        buffer_handle
+            .mapped_mut::<u8>(offset, data.len())
+            .copy_from_slice(data);
+
+        self.log.lock().unwrap().push(Operation::WriteBuffer {
+            buffer: buffer_handle.id,
+            offset,
+            data: data.to_vec(),
+        });
+    }
+
+    fn write_texture(&self, operation: WriteTextureOperation<Driver>) {
+        self.log.lock().unwrap().push(Operation::WriteTexture {
+            texture: operation.image_copy_texture.texture_handle.id,
+            mip_level: operation.image_copy_texture.mip_level,
+            origin: operation.image_copy_texture.origin,
+            extent: operation.extent,
+            data_len: operation.data.len(),
+        });
+    }
+
+    fn timestamp_period(&self) -> f32 {
+        1.0
+    }
+}
+
+#[derive(Clone)]
+pub struct BindGroupHandle {
+    id: ResourceId,
+}
+
+#[derive(Clone)]
+pub struct SamplerHandle {
+    #[allow(unused)]
+    id: ResourceId,
+}
+
+#[derive(Clone)]
+pub struct BindGroupLayoutHandle {
+    #[allow(unused)]
+    id: ResourceId,
+}
+
+#[derive(Clone)]
+pub struct PipelineLayoutHandle {
+    #[allow(unused)]
+    id: ResourceId,
+}
+
+#[derive(Clone)]
+pub struct ComputePipelineHandle {
+    id: ResourceId,
+}
+
+#[derive(Clone)]
+pub struct RenderPipelineHandle {
+    id: ResourceId,
+}
+
+#[derive(Clone)]
+pub struct QuerySetHandle {
+    id: ResourceId,
+}
+
+#[derive(Clone)]
+pub struct ShaderModuleHandle {
+    #[allow(unused)]
+    id: ResourceId,
+}
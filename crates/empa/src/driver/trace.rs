@@ -0,0 +1,2840 @@
+//! A driver that wraps the native driver and records the operations it performs, such that they
+//! can be serialized to a trace and replayed later (see [crate::trace]).
+
+use std::borrow::Borrow;
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::fmt;
+use std::future::Future;
+use std::num::NonZeroU32;
+use std::ops::Range;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+
+use flagset::FlagSet;
+use serde::{Deserialize, Serialize};
+
+use crate::adapter::{Feature, Limits};
+use crate::buffer::CreateBufferError;
+use crate::command::{BlendConstant, Draw, DrawIndexed, ScissorRect, Viewport};
+use crate::device::{DeviceDescriptor, PipelineCreationError};
+use crate::driver;
+use crate::driver::native as inner;
+use crate::driver::{
+    Adapter, BindGroupDescriptor, BindGroupEntry, BindGroupLayoutDescriptor, BindGroupLayoutEntry,
+    BindingResource, BindingType, Buffer, BufferDescriptor, ClearBuffer, ColorTargetState,
+    CommandEncoder, ComputePassEncoder, ComputePipelineDescriptor, CopyBufferToBuffer,
+    CopyBufferToTexture, CopyTextureToBuffer, CopyTextureToTexture, DepthStencilOperations,
+    DepthStencilState, Device, ExecuteRenderBundlesEncoder, FragmentState, ImageCopyBuffer,
+    ImageCopyTexture, ImageDataLayout, MapMode, MultiDrawIndexedIndirectCount, MultisampleState,
+    PipelineLayoutDescriptor, PrimitiveState, ProgrammablePassEncoder, QuerySetDescriptor,
+    QueryType, Queue, RenderBundleEncoder, RenderBundleEncoderDescriptor, RenderEncoder,
+    RenderPassColorAttachment, RenderPassDepthStencilAttachment, RenderPassDescriptor,
+    RenderPassEncoder, RenderPipelineDescriptor, ResolveQuerySet, SamplerDescriptor,
+    SetIndexBuffer, SetVertexBuffer, Texture, TextureAspect, TextureDescriptor, TextureDimensions,
+    TextureViewDescriptor, TextureViewDimension, VertexState, WriteBufferOperation,
+    WriteTextureOperation,
+};
+use crate::render_pipeline::{
+    BlendState, IndexFormat, VertexAttribute, VertexBufferLayout, VertexStepMode,
+};
+use crate::render_target::{LoadOp, StoreOp};
+use crate::sampler::{AddressMode, FilterMode};
+use crate::texture::format::TextureFormatId;
+use crate::texture::CreateTextureError;
+use crate::CompareFunction;
+
+pub type TraceId = u64;
+
+/// Controls what data a trace captures.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct CaptureOptions {
+    /// Whether to include the contents of buffer and texture writes in the trace.
+    ///
+    /// If `false`, only a hash of the data is recorded; replaying such a trace will write zeroed
+    /// data instead.
+    pub include_data: bool,
+}
+
+impl Default for CaptureOptions {
+    fn default() -> Self {
+        CaptureOptions { include_data: true }
+    }
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct TraceData {
+    pub len: usize,
+    /// A 64-bit FNV-1a hash of the data.
+    pub hash: u64,
+    pub bytes: Option<Vec<u8>>,
+}
+
+impl TraceData {
+    fn new(data: &[u8], include_data: bool) -> Self {
+        let mut hash: u64 = 0xcbf29ce484222325;
+
+        for byte in data {
+            hash ^= *byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+
+        TraceData {
+            len: data.len(),
+            hash,
+            bytes: include_data.then(|| data.to_vec()),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct TraceSamplerDescriptor {
+    pub address_mode_u: AddressMode,
+    pub address_mode_v: AddressMode,
+    pub address_mode_w: AddressMode,
+    pub magnification_filter: FilterMode,
+    pub minification_filter: FilterMode,
+    pub mipmap_filter: FilterMode,
+    pub lod_clamp: (f32, f32),
+    pub max_anisotropy: u16,
+    pub compare: Option<CompareFunction>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct TraceBindGroupLayoutEntry {
+    pub binding: u32,
+    pub binding_type: BindingType,
+    pub visibility: u32,
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub enum TraceBindingResource {
+    Buffer {
+        buffer: TraceId,
+        offset: usize,
+        size: usize,
+    },
+    TextureView(TraceId),
+    Sampler(TraceId),
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct TraceVertexBufferLayout {
+    pub array_stride: usize,
+    pub step_mode: VertexStepMode,
+    pub attributes: Vec<VertexAttribute>,
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct TraceColorTargetState {
+    pub format: TextureFormatId,
+    pub blend: Option<BlendState>,
+    pub write_mask: u32,
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct TraceShaderStage {
+    pub shader_module: TraceId,
+    pub entry_point: String,
+    pub constants: HashMap<String, f64>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct TraceImageCopyBuffer {
+    pub buffer: TraceId,
+    pub offset: usize,
+    pub size: usize,
+    pub bytes_per_block: u32,
+    pub blocks_per_row: u32,
+    pub rows_per_image: u32,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct TraceImageCopyTexture {
+    pub texture: TraceId,
+    pub mip_level: u32,
+    pub origin: (u32, u32, u32),
+    pub aspect: TextureAspect,
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct TraceColorAttachment {
+    pub view: TraceId,
+    pub resolve_target: Option<TraceId>,
+    pub load_op: LoadOp<[f64; 4]>,
+    pub store_op: StoreOp,
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct TraceDepthStencilAttachment {
+    pub view: TraceId,
+    pub depth_operations: Option<(LoadOp<f32>, StoreOp)>,
+    pub stencil_operations: Option<(LoadOp<u32>, StoreOp)>,
+}
+
+/// A single recorded operation.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub enum TraceOp {
+    CreateBuffer {
+        id: TraceId,
+        size: usize,
+        usage: u32,
+    },
+    CreateTexture {
+        id: TraceId,
+        size: (u32, u32, u32),
+        mipmap_levels: u32,
+        sample_count: u32,
+        dimensions: TextureDimensions,
+        format: TextureFormatId,
+        usage: u32,
+        view_formats: Vec<TextureFormatId>,
+    },
+    CreateTextureView {
+        id: TraceId,
+        texture: TraceId,
+        format: TextureFormatId,
+        dimensions: TextureViewDimension,
+        aspect: TextureAspect,
+        mip_levels: Range<u32>,
+        layers: Range<u32>,
+    },
+    CreateSampler {
+        id: TraceId,
+        descriptor: TraceSamplerDescriptor,
+    },
+    CreateBindGroupLayout {
+        id: TraceId,
+        entries: Vec<TraceBindGroupLayoutEntry>,
+    },
+    CreatePipelineLayout {
+        id: TraceId,
+        bind_group_layouts: Vec<TraceId>,
+    },
+    CreateBindGroup {
+        id: TraceId,
+        layout: TraceId,
+        entries: Vec<(u32, TraceBindingResource)>,
+    },
+    CreateQuerySet {
+        id: TraceId,
+        query_type: QueryType,
+        len: usize,
+    },
+    CreateShaderModule {
+        id: TraceId,
+        source: String,
+    },
+    CreateComputePipeline {
+        id: TraceId,
+        layout: TraceId,
+        stage: TraceShaderStage,
+    },
+    CreateRenderPipeline {
+        id: TraceId,
+        layout: TraceId,
+        primitive_state: PrimitiveState,
+        vertex_stage: TraceShaderStage,
+        vertex_buffer_layouts: Vec<TraceVertexBufferLayout>,
+        depth_stencil_state: Option<DepthStencilState>,
+        fragment_stage: Option<(TraceShaderStage, Vec<TraceColorTargetState>)>,
+        multisample_state: Option<MultisampleState>,
+        multiview: Option<NonZeroU32>,
+    },
+    CreateRenderBundle {
+        id: TraceId,
+        color_formats: Vec<TextureFormatId>,
+        depth_stencil_format: Option<TextureFormatId>,
+        sample_count: u32,
+        depth_read_only: bool,
+        stencil_read_only: bool,
+        commands: Vec<TraceCommand>,
+    },
+    DestroyBuffer {
+        buffer: TraceId,
+    },
+    DestroyTexture {
+        texture: TraceId,
+    },
+    WriteBuffer {
+        buffer: TraceId,
+        offset: usize,
+        data: TraceData,
+    },
+    WriteTexture {
+        texture: TraceImageCopyTexture,
+        offset: usize,
+        bytes_per_row: u32,
+        rows_per_image: u32,
+        extent: (u32, u32, u32),
+        data: TraceData,
+    },
+    Submit {
+        commands: Vec<TraceCommand>,
+    },
+}
+
+impl TraceOp {
+    fn created_id(&self) -> Option<TraceId> {
+        match self {
+            TraceOp::CreateBuffer { id, .. }
+            | TraceOp::CreateTexture { id, .. }
+            | TraceOp::CreateTextureView { id, .. }
+            | TraceOp::CreateSampler { id, .. }
+            | TraceOp::CreateBindGroupLayout { id, .. }
+            | TraceOp::CreatePipelineLayout { id, .. }
+            | TraceOp::CreateBindGroup { id, .. }
+            | TraceOp::CreateQuerySet { id, .. }
+            | TraceOp::CreateShaderModule { id, .. }
+            | TraceOp::CreateComputePipeline { id, .. }
+            | TraceOp::CreateRenderPipeline { id, .. }
+            | TraceOp::CreateRenderBundle { id, .. } => Some(*id),
+            _ => None,
+        }
+    }
+}
+
+/// A single recorded command.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub enum TraceCommand {
+    CopyBufferToBuffer {
+        source: TraceId,
+        source_offset: usize,
+        destination: TraceId,
+        destination_offset: usize,
+        size: usize,
+    },
+    CopyBufferToTexture {
+        source: TraceImageCopyBuffer,
+        destination: TraceImageCopyTexture,
+        copy_size: (u32, u32, u32),
+    },
+    CopyTextureToBuffer {
+        source: TraceImageCopyTexture,
+        destination: TraceImageCopyBuffer,
+        copy_size: (u32, u32, u32),
+    },
+    CopyTextureToTexture {
+        source: TraceImageCopyTexture,
+        destination: TraceImageCopyTexture,
+        copy_size: (u32, u32, u32),
+    },
+    ClearBuffer {
+        buffer: TraceId,
+        range: Range<usize>,
+    },
+    WriteTimestamp {
+        query_set: TraceId,
+        index: usize,
+    },
+    ResolveQuerySet {
+        query_set: TraceId,
+        query_range: Range<usize>,
+        destination: TraceId,
+        destination_offset: usize,
+    },
+    BeginComputePass,
+    SetComputePipeline(TraceId),
+    DispatchWorkgroups(u32, u32, u32),
+    DispatchWorkgroupsIndirect {
+        buffer: TraceId,
+        offset: usize,
+    },
+    EndComputePass,
+    BeginRenderPass {
+        color_attachments: Vec<Option<TraceColorAttachment>>,
+        depth_stencil_attachment: Option<TraceDepthStencilAttachment>,
+        occlusion_query_set: Option<TraceId>,
+    },
+    SetRenderPipeline(TraceId),
+    SetBindGroup {
+        index: u32,
+        bind_group: TraceId,
+    },
+    SetIndexBuffer {
+        buffer: TraceId,
+        index_format: IndexFormat,
+        range: Option<Range<usize>>,
+    },
+    SetVertexBuffer {
+        slot: u32,
+        buffer: TraceId,
+        range: Option<Range<usize>>,
+    },
+    Draw(Draw),
+    DrawIndexed(DrawIndexed),
+    DrawIndirect {
+        buffer: TraceId,
+        offset: usize,
+    },
+    DrawIndexedIndirect {
+        buffer: TraceId,
+        offset: usize,
+    },
+    MultiDrawIndexedIndirectCount {
+        buffer: TraceId,
+        offset: usize,
+        count_buffer: TraceId,
+        count_offset: usize,
+        max_count: u32,
+    },
+    SetViewport(Viewport),
+    SetScissorRect(ScissorRect),
+    SetBlendConstant(BlendConstant),
+    SetStencilReference(u32),
+    BeginOcclusionQuery(u32),
+    EndOcclusionQuery,
+    BeginPipelineStatisticsQuery {
+        query_set: TraceId,
+        index: u32,
+    },
+    EndPipelineStatisticsQuery,
+    ExecuteBundles(Vec<TraceId>),
+    EndRenderPass,
+}
+
+struct TracerState {
+    next_id: TraceId,
+    // The creation operations for all live resources, such that a capture that is started after
+    // resources were created can still include them.
+    live: BTreeMap<TraceId, TraceOp>,
+    capture: Option<(CaptureOptions, Vec<TraceOp>)>,
+}
+
+#[derive(Clone)]
+pub struct Tracer {
+    state: Arc<Mutex<TracerState>>,
+}
+
+impl Tracer {
+    fn new() -> Self {
+        Tracer {
+            state: Arc::new(Mutex::new(TracerState {
+                next_id: 1,
+                live: BTreeMap::new(),
+                capture: None,
+            })),
+        }
+    }
+
+    fn next_id(&self) -> TraceId {
+        let mut state = self.state.lock().unwrap();
+        let id = state.next_id;
+
+        state.next_id += 1;
+
+        id
+    }
+
+    fn include_data(&self) -> bool {
+        self.state
+            .lock()
+            .unwrap()
+            .capture
+            .as_ref()
+            .map(|(options, _)| options.include_data)
+            .unwrap_or(false)
+    }
+
+    fn is_capturing(&self) -> bool {
+        self.state.lock().unwrap().capture.is_some()
+    }
+
+    fn record(&self, op: TraceOp) {
+        if let Some((_, ops)) = self.state.lock().unwrap().capture.as_mut() {
+            ops.push(op);
+        }
+    }
+
+    /// Registers a new resource with the given creation `op`, which must create a resource with
+    /// the given `id`.
+    fn register(&self, op: TraceOp, dependencies: Vec<Arc<Resource>>) -> Arc<Resource> {
+        let id = op.created_id().expect("not a creation operation");
+        let mut state = self.state.lock().unwrap();
+
+        if let Some((_, ops)) = state.capture.as_mut() {
+            ops.push(op.clone());
+        }
+
+        state.live.insert(id, op);
+
+        Arc::new(Resource {
+            id,
+            tracer: self.clone(),
+            _dependencies: dependencies,
+        })
+    }
+
+    pub fn begin_capture(&self, options: CaptureOptions) {
+        let mut state = self.state.lock().unwrap();
+
+        assert!(state.capture.is_none(), "a capture is already in progress");
+
+        let ops = state.live.values().cloned().collect();
+
+        state.capture = Some((options, ops));
+    }
+
+    pub fn end_capture(&self) -> Vec<TraceOp> {
+        let mut state = self.state.lock().unwrap();
+
+        let (_, ops) = state.capture.take().expect("no capture in progress");
+
+        ops
+    }
+}
+
+// Keeps the creation operation for a resource in the tracer's set of live resources while the
+// resource is alive. Also keeps the resources it depends on alive, such that e.g. a pipeline's
+// shader module can still be included in a later capture after the shader module was dropped.
+pub struct Resource {
+    id: TraceId,
+    tracer: Tracer,
+    _dependencies: Vec<Arc<Resource>>,
+}
+
+impl Drop for Resource {
+    fn drop(&mut self) {
+        self.tracer.state.lock().unwrap().live.remove(&self.id);
+    }
+}
+
+pub struct Driver;
+
+impl driver::Driver for Driver {
+    type AdapterHandle = AdapterHandle;
+    type BindGroupHandle = BindGroupHandle;
+    type DeviceHandle = DeviceHandle;
+    type BufferHandle = BufferHandle;
+    type BufferBinding = BufferBinding;
+    type TextureHandle = TextureHandle;
+    type TextureView = TextureView;
+    type CommandEncoderHandle = CommandEncoderHandle;
+    type ComputePassEncoderHandle = ComputePassEncoderHandle;
+    type RenderPassEncoderHandle = RenderPassEncoderHandle;
+    type ExecuteRenderBundlesEncoder<'a> = ExecuteRenderBundlesEncoderHandle<'a>;
+    type RenderBundleEncoderHandle = RenderBundleEncoderHandle;
+    type CommandBufferHandle = CommandBufferHandle;
+    type RenderBundleHandle = RenderBundleHandle;
+    type QueueHandle = QueueHandle;
+    type SamplerHandle = SamplerHandle;
+    type BindGroupLayoutHandle = BindGroupLayoutHandle;
+    type PipelineLayoutHandle = PipelineLayoutHandle;
+    type ComputePipelineHandle = ComputePipelineHandle;
+    type RenderPipelineHandle = RenderPipelineHandle;
+    type QuerySetHandle = QuerySetHandle;
+    type ShaderModuleHandle = ShaderModuleHandle;
+}
+
+#[derive(Clone)]
+pub struct AdapterHandle {
+    inner: inner::AdapterHandle,
+}
+
+impl AdapterHandle {
+    pub fn new(inner: inner::AdapterHandle) -> Self {
+        AdapterHandle { inner }
+    }
+}
+
+impl Adapter<Driver> for AdapterHandle {
+    type RequestDevice = RequestDevice;
+
+    fn supported_features(&self) -> FlagSet<Feature> {
+        self.inner.supported_features()
+    }
+
+    fn supported_limits(&self) -> Limits {
+        self.inner.supported_limits()
+    }
+
+    fn request_device<Flags>(&self, descriptor: &DeviceDescriptor<Flags>) -> RequestDevice
+    where
+        Flags: Into<FlagSet<Feature>> + Copy,
+    {
+        RequestDevice {
+            inner: self.inner.request_device(descriptor),
+        }
+    }
+}
+
+pub struct RequestDevice {
+    inner: <inner::AdapterHandle as Adapter<inner::Driver>>::RequestDevice,
+}
+
+impl Future for RequestDevice {
+    type Output = Result<(DeviceHandle, QueueHandle), Box<dyn Error>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // The native request future is `Unpin`.
+        let inner = Pin::new(&mut self.get_mut().inner);
+
+        inner.poll(cx).map_ok(|(device, queue)| {
+            let tracer = Tracer::new();
+
+            (
+                DeviceHandle {
+                    inner: device,
+                    tracer: tracer.clone(),
+                },
+                QueueHandle {
+                    inner: queue,
+                    tracer,
+                },
+            )
+        })
+    }
+}
+
+#[derive(Clone)]
+pub struct DeviceHandle {
+    inner: inner::DeviceHandle,
+    tracer: Tracer,
+}
+
+impl DeviceHandle {
+    pub fn global(&self) -> &Arc<wgc::global::Global> {
+        self.inner.global()
+    }
+
+    pub fn id(&self) -> wgc::id::DeviceId {
+        self.inner.id()
+    }
+
+    pub fn auto_poll(&self) -> bool {
+        self.inner.auto_poll()
+    }
+
+    pub fn set_auto_poll(&self, enabled: bool) {
+        self.inner.set_auto_poll(enabled)
+    }
+
+    pub fn poll(
+        &self,
+        maintain: wgt::Maintain<wgc::device::queue::WrappedSubmissionIndex>,
+    ) -> bool {
+        self.inner.poll(maintain)
+    }
+
+    pub fn tracer(&self) -> &Tracer {
+        &self.tracer
+    }
+}
+
+fn shader_stage(
+    shader_module: &ShaderModuleHandle,
+    entry_point: &str,
+    constants: &HashMap<String, f64>,
+) -> TraceShaderStage {
+    TraceShaderStage {
+        shader_module: shader_module.resource.id,
+        entry_point: entry_point.to_string(),
+        constants: constants.clone(),
+    }
+}
+
+impl Device<Driver> for DeviceHandle {
+    type CreateComputePipelineAsync = CreateComputePipelineAsync;
+    type CreateRenderPipelineAsync = CreateRenderPipelineAsync;
+
+    fn create_buffer(
+        &self,
+        descriptor: &BufferDescriptor,
+    ) -> Result<BufferHandle, CreateBufferError> {
+        let inner = self.inner.create_buffer(descriptor)?;
+        let id = self.tracer.next_id();
+        let resource = self.tracer.register(
+            TraceOp::CreateBuffer {
+                id,
+                size: descriptor.size,
+                usage: descriptor.usage_flags.bits(),
+            },
+            Vec::new(),
+        );
+
+        Ok(BufferHandle {
+            inner,
+            resource,
+            tracer: self.tracer.clone(),
+            mapped_mut_ranges: Arc::new(Mutex::new(Vec::new())),
+        })
+    }
+
+    fn create_texture(
+        &self,
+        descriptor: &TextureDescriptor,
+    ) -> Result<TextureHandle, CreateTextureError> {
+        Ok(TextureHandle {
+            inner: self.inner.create_texture(descriptor)?,
+            resource: self.register_texture(descriptor),
+            tracer: self.tracer.clone(),
+        })
+    }
+
+    fn create_sampler(&self, descriptor: &SamplerDescriptor) -> SamplerHandle {
+        let id = self.tracer.next_id();
+        let resource = self.tracer.register(
+            TraceOp::CreateSampler {
+                id,
+                descriptor: TraceSamplerDescriptor {
+                    address_mode_u: descriptor.address_mode_u,
+                    address_mode_v: descriptor.address_mode_v,
+                    address_mode_w: descriptor.address_mode_w,
+                    magnification_filter: descriptor.magnification_filter,
+                    minification_filter: descriptor.minification_filter,
+                    mipmap_filter: descriptor.mipmap_filter,
+                    lod_clamp: (*descriptor.lod_clamp.start(), *descriptor.lod_clamp.end()),
+                    max_anisotropy: descriptor.max_anisotropy,
+                    compare: descriptor.compare,
+                },
+            },
+            Vec::new(),
+        );
+
+        SamplerHandle {
+            inner: self.inner.create_sampler(descriptor),
+            resource,
+        }
+    }
+
+    fn create_bind_group_layout<I>(
+        &self,
+        descriptor: BindGroupLayoutDescriptor<I>,
+    ) -> BindGroupLayoutHandle
+    where
+        I: IntoIterator<Item = BindGroupLayoutEntry>,
+    {
+        let entries: Vec<BindGroupLayoutEntry> = descriptor.entries.into_iter().collect();
+
+        let id = self.tracer.next_id();
+        let resource = self.tracer.register(
+            TraceOp::CreateBindGroupLayout {
+                id,
+                entries: entries
+                    .iter()
+                    .map(|e| TraceBindGroupLayoutEntry {
+                        binding: e.binding,
+                        binding_type: e.binding_type,
+                        visibility: e.visibility.bits(),
+                    })
+                    .collect(),
+            },
+            Vec::new(),
+        );
+
+        BindGroupLayoutHandle {
+            inner: self
+                .inner
+                .create_bind_group_layout(BindGroupLayoutDescriptor { entries }),
+            resource,
+        }
+    }
+
+    fn create_pipeline_layout<I>(
+        &self,
+        descriptor: PipelineLayoutDescriptor<I>,
+    ) -> PipelineLayoutHandle
+    where
+        I: IntoIterator,
+        I::Item: Borrow<BindGroupLayoutHandle>,
+    {
+        let layouts: Vec<BindGroupLayoutHandle> = descriptor
+            .bind_group_layouts
+            .into_iter()
+            .map(|l| l.borrow().clone())
+            .collect();
+
+        let id = self.tracer.next_id();
+        let resource = self.tracer.register(
+            TraceOp::CreatePipelineLayout {
+                id,
+                bind_group_layouts: layouts.iter().map(|l| l.resource.id).collect(),
+            },
+            layouts.iter().map(|l| l.resource.clone()).collect(),
+        );
+
+        PipelineLayoutHandle {
+            inner: self.inner.create_pipeline_layout(PipelineLayoutDescriptor {
+                bind_group_layouts: layouts.iter().map(|l| &l.inner),
+            }),
+            resource,
+        }
+    }
+
+    fn create_bind_group<'a, E>(
+        &self,
+        descriptor: BindGroupDescriptor<Driver, E>,
+    ) -> BindGroupHandle
+    where
+        E: IntoIterator<Item = BindGroupEntry<'a, Driver>>,
+    {
+        let entries: Vec<BindGroupEntry<'a, Driver>> = descriptor.entries.into_iter().collect();
+
+        let mut dependencies = vec![descriptor.layout.resource.clone()];
+        let mut trace_entries = Vec::with_capacity(entries.len());
+
+        for entry in &entries {
+            let resource = match &entry.resource {
+                BindingResource::BufferBinding(b) => {
+                    dependencies.push(b.resource.clone());
+
+                    TraceBindingResource::Buffer {
+                        buffer: b.resource.id,
+                        offset: b.offset,
+                        size: b.size,
+                    }
+                }
+                BindingResource::TextureView(v) => {
+                    dependencies.push(v.resource.clone());
+
+                    TraceBindingResource::TextureView(v.resource.id)
+                }
+                BindingResource::Sampler(s) => {
+                    dependencies.push(s.resource.clone());
+
+                    TraceBindingResource::Sampler(s.resource.id)
+                }
+            };
+
+            trace_entries.push((entry.binding, resource));
+        }
+
+        let id = self.tracer.next_id();
+        let resource = self.tracer.register(
+            TraceOp::CreateBindGroup {
+                id,
+                layout: descriptor.layout.resource.id,
+                entries: trace_entries,
+            },
+            dependencies,
+        );
+
+        let inner = self.inner.create_bind_group(BindGroupDescriptor {
+            layout: &descriptor.layout.inner,
+            entries: entries.into_iter().map(|e| BindGroupEntry {
+                binding: e.binding,
+                resource: match e.resource {
+                    BindingResource::BufferBinding(b) => BindingResource::BufferBinding(b.inner),
+                    BindingResource::TextureView(v) => BindingResource::TextureView(v.inner),
+                    BindingResource::Sampler(s) => BindingResource::Sampler(&s.inner),
+                },
+            }),
+        });
+
+        BindGroupHandle { inner, resource }
+    }
+
+    fn create_query_set(&self, descriptor: &QuerySetDescriptor) -> QuerySetHandle {
+        let id = self.tracer.next_id();
+        let resource = self.tracer.register(
+            TraceOp::CreateQuerySet {
+                id,
+                query_type: descriptor.query_type,
+                len: descriptor.len,
+            },
+            Vec::new(),
+        );
+
+        QuerySetHandle {
+            inner: self.inner.create_query_set(descriptor),
+            resource,
+        }
+    }
+
+    fn create_shader_module(&self, source: &str) -> ShaderModuleHandle {
+        let id = self.tracer.next_id();
+        let resource = self.tracer.register(
+            TraceOp::CreateShaderModule {
+                id,
+                source: source.to_string(),
+            },
+            Vec::new(),
+        );
+
+        ShaderModuleHandle {
+            inner: self.inner.create_shader_module(source),
+            resource,
+        }
+    }
+
+    fn create_compute_pipeline(
+        &self,
+        descriptor: &ComputePipelineDescriptor<Driver>,
+    ) -> Result<ComputePipelineHandle, PipelineCreationError> {
+        let resource = self.register_compute_pipeline(descriptor);
+        let inner = self
+            .inner
+            .create_compute_pipeline(&compute_pipeline_descriptor_to_inner(descriptor))?;
+
+        Ok(ComputePipelineHandle { inner, resource })
+    }
+
+    fn create_compute_pipeline_async(
+        &self,
+        descriptor: &ComputePipelineDescriptor<Driver>,
+    ) -> CreateComputePipelineAsync {
+        let resource = self.register_compute_pipeline(descriptor);
+
+        CreateComputePipelineAsync {
+            inner: self
+                .inner
+                .create_compute_pipeline_async(&compute_pipeline_descriptor_to_inner(descriptor)),
+            resource: Some(resource),
+        }
+    }
+
+    fn create_render_pipeline(
+        &self,
+        descriptor: &RenderPipelineDescriptor<Driver>,
+    ) -> Result<RenderPipelineHandle, PipelineCreationError> {
+        let resource = self.register_render_pipeline(descriptor);
+        let inner = with_inner_render_pipeline_descriptor(descriptor, |d| {
+            self.inner.create_render_pipeline(d)
+        })?;
+
+        Ok(RenderPipelineHandle { inner, resource })
+    }
+
+    fn create_render_pipeline_async(
+        &self,
+        descriptor: &RenderPipelineDescriptor<Driver>,
+    ) -> CreateRenderPipelineAsync {
+        let resource = self.register_render_pipeline(descriptor);
+
+        CreateRenderPipelineAsync {
+            inner: with_inner_render_pipeline_descriptor(descriptor, |d| {
+                self.inner.create_render_pipeline_async(d)
+            }),
+            resource: Some(resource),
+        }
+    }
+
+    fn create_command_encoder(&self) -> CommandEncoderHandle {
+        CommandEncoderHandle {
+            inner: self.inner.create_command_encoder(),
+            recorder: Recorder::new(),
+        }
+    }
+
+    fn create_render_bundle_encoder(
+        &self,
+        descriptor: &RenderBundleEncoderDescriptor,
+    ) -> RenderBundleEncoderHandle {
+        RenderBundleEncoderHandle {
+            inner: self.inner.create_render_bundle_encoder(descriptor),
+            tracer: self.tracer.clone(),
+            color_formats: descriptor.color_formats.to_vec(),
+            depth_stencil_format: descriptor.depth_stencil_format,
+            sample_count: descriptor.sample_count,
+            depth_read_only: descriptor.depth_read_only,
+            stencil_read_only: descriptor.stencil_read_only,
+            recorder: Recorder::new(),
+        }
+    }
+}
+
+impl DeviceHandle {
+    fn register_texture(&self, descriptor: &TextureDescriptor) -> Arc<Resource> {
+        let id = self.tracer.next_id();
+
+        self.tracer.register(
+            TraceOp::CreateTexture {
+                id,
+                size: descriptor.size,
+                mipmap_levels: descriptor.mipmap_levels,
+                sample_count: descriptor.sample_count,
+                dimensions: descriptor.dimensions,
+                format: descriptor.format,
+                usage: descriptor.usage_flags.bits(),
+                view_formats: descriptor.view_formats.to_vec(),
+            },
+            Vec::new(),
+        )
+    }
+
+    fn register_compute_pipeline(
+        &self,
+        descriptor: &ComputePipelineDescriptor<Driver>,
+    ) -> Arc<Resource> {
+        let id = self.tracer.next_id();
+
+        self.tracer.register(
+            TraceOp::CreateComputePipeline {
+                id,
+                layout: descriptor.layout.resource.id,
+                stage: shader_stage(
+                    descriptor.shader_module,
+                    descriptor.entry_point,
+                    descriptor.constants,
+                ),
+            },
+            vec![
+                descriptor.layout.resource.clone(),
+                descriptor.shader_module.resource.clone(),
+            ],
+        )
+    }
+
+    fn register_render_pipeline(
+        &self,
+        descriptor: &RenderPipelineDescriptor<Driver>,
+    ) -> Arc<Resource> {
+        let vertex_state = &descriptor.vertex_state;
+
+        let mut dependencies = vec![
+            descriptor.layout.resource.clone(),
+            vertex_state.shader_module.resource.clone(),
+        ];
+
+        let fragment_stage = descriptor.fragment_state.as_ref().map(|f| {
+            dependencies.push(f.shader_module.resource.clone());
+
+            let targets = f
+                .targets
+                .iter()
+                .map(|t| TraceColorTargetState {
+                    format: t.format,
+                    blend: t.blend,
+                    write_mask: t.write_mask.bits(),
+                })
+                .collect();
+
+            (
+                shader_stage(f.shader_module, f.entry_point, f.constants),
+                targets,
+            )
+        });
+
+        let id = self.tracer.next_id();
+
+        self.tracer.register(
+            TraceOp::CreateRenderPipeline {
+                id,
+                layout: descriptor.layout.resource.id,
+                primitive_state: *descriptor.primitive_state,
+                vertex_stage: shader_stage(
+                    vertex_state.shader_module,
+                    vertex_state.entry_point,
+                    vertex_state.constants,
+                ),
+                vertex_buffer_layouts: vertex_state
+                    .vertex_buffer_layouts
+                    .iter()
+                    .map(|l| TraceVertexBufferLayout {
+                        array_stride: l.array_stride,
+                        step_mode: l.step_mode,
+                        attributes: l.attributes.to_vec(),
+                    })
+                    .collect(),
+                depth_stencil_state: descriptor.depth_stencil_state.copied(),
+                fragment_stage,
+                multisample_state: descriptor.multisample_state.copied(),
+                multiview: descriptor.multiview,
+            },
+            dependencies,
+        )
+    }
+}
+
+fn compute_pipeline_descriptor_to_inner<'a>(
+    descriptor: &ComputePipelineDescriptor<'a, Driver>,
+) -> ComputePipelineDescriptor<'a, inner::Driver> {
+    ComputePipelineDescriptor {
+        layout: &descriptor.layout.inner,
+        shader_module: &descriptor.shader_module.inner,
+        entry_point: descriptor.entry_point,
+        constants: descriptor.constants,
+    }
+}
+
+fn with_inner_render_pipeline_descriptor<R>(
+    descriptor: &RenderPipelineDescriptor<Driver>,
+    f: impl FnOnce(&RenderPipelineDescriptor<inner::Driver>) -> R,
+) -> R {
+    let vertex_state = &descriptor.vertex_state;
+
+    f(&RenderPipelineDescriptor {
+        layout: &descriptor.layout.inner,
+        primitive_state: descriptor.primitive_state,
+        vertex_state: VertexState {
+            shader_module: &vertex_state.shader_module.inner,
+            entry_point: vertex_state.entry_point,
+            constants: vertex_state.constants,
+            vertex_buffer_layouts: vertex_state.vertex_buffer_layouts,
+        },
+        depth_stencil_state: descriptor.depth_stencil_state,
+        fragment_state: descriptor.fragment_state.as_ref().map(|f| FragmentState {
+            shader_module: &f.shader_module.inner,
+            entry_point: f.entry_point,
+            constants: f.constants,
+            targets: f.targets,
+        }),
+        multisample_state: descriptor.multisample_state,
+        multiview: descriptor.multiview,
+    })
+}
+
+pub struct CreateComputePipelineAsync {
+    inner: <inner::DeviceHandle as Device<inner::Driver>>::CreateComputePipelineAsync,
+    resource: Option<Arc<Resource>>,
+}
+
+impl Future for CreateComputePipelineAsync {
+    type Output = Result<ComputePipelineHandle, PipelineCreationError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // The native pipeline creation future is `Unpin`.
+        let this = self.get_mut();
+
+        Pin::new(&mut this.inner).poll(cx).map(|result| {
+            let resource = this.resource.take().expect("cannot poll after completion");
+
+            result.map(|inner| ComputePipelineHandle { inner, resource })
+        })
+    }
+}
+
+pub struct CreateRenderPipelineAsync {
+    inner: <inner::DeviceHandle as Device<inner::Driver>>::CreateRenderPipelineAsync,
+    resource: Option<Arc<Resource>>,
+}
+
+impl Future for CreateRenderPipelineAsync {
+    type Output = Result<RenderPipelineHandle, PipelineCreationError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // The native pipeline creation future is `Unpin`.
+        let this = self.get_mut();
+
+        Pin::new(&mut this.inner).poll(cx).map(|result| {
+            let resource = this.resource.take().expect("cannot poll after completion");
+
+            result.map(|inner| RenderPipelineHandle { inner, resource })
+        })
+    }
+}
+
+#[derive(Clone)]
+pub struct BufferHandle {
+    inner: inner::BufferHandle,
+    resource: Arc<Resource>,
+    tracer: Tracer,
+    // Ranges (offset and size in bytes) that were mapped for writing; their contents are recorded
+    // as buffer writes when the buffer is unmapped.
+    mapped_mut_ranges: Arc<Mutex<Vec<(usize, usize)>>>,
+}
+
+impl Buffer<Driver> for BufferHandle {
+    type Map = <inner::BufferHandle as Buffer<inner::Driver>>::Map;
+    type Mapped<'a, E: 'a> = &'a [E];
+    type MappedMut<'a, E: 'a> = &'a mut [E];
+
+    fn map(&self, mode: MapMode, range: Range<usize>) -> Self::Map {
+        self.inner.map(mode, range)
+    }
+
+    fn mapped<'a, E>(&'a self, offset_in_bytes: usize, len_in_elements: usize) -> &'a [E] {
+        self.inner.mapped(offset_in_bytes, len_in_elements)
+    }
+
+    fn mapped_mut<'a, E>(&'a self, offset_in_bytes: usize, len_in_elements: usize) -> &'a mut [E] {
+        let size = len_in_elements * std::mem::size_of::<E>();
+
+        self.mapped_mut_ranges
+            .lock()
+            .unwrap()
+            .push((offset_in_bytes, size));
+
+        self.inner.mapped_mut(offset_in_bytes, len_in_elements)
+    }
+
+    fn unmap(&self) {
+        let ranges = std::mem::take(&mut *self.mapped_mut_ranges.lock().unwrap());
+
+        if self.tracer.is_capturing() {
+            let include_data = self.tracer.include_data();
+
+            for (offset, size) in ranges {
+                let data: &[u8] = self.inner.mapped(offset, size);
+
+                self.tracer.record(TraceOp::WriteBuffer {
+                    buffer: self.resource.id,
+                    offset,
+                    data: TraceData::new(data, include_data),
+                });
+            }
+        }
+
+        self.inner.unmap();
+    }
+
+    fn binding(&self, offset: usize, size: usize) -> BufferBinding {
+        BufferBinding {
+            inner: self.inner.binding(offset, size),
+            resource: self.resource.clone(),
+            offset,
+            size,
+        }
+    }
+
+    fn destroy(&self) {
+        self.tracer.record(TraceOp::DestroyBuffer {
+            buffer: self.resource.id,
+        });
+
+        self.inner.destroy();
+    }
+}
+
+#[derive(Clone)]
+pub struct BufferBinding {
+    inner: <inner::Driver as driver::Driver>::BufferBinding,
+    resource: Arc<Resource>,
+    offset: usize,
+    size: usize,
+}
+
+#[derive(Clone)]
+pub struct TextureHandle {
+    inner: inner::TextureHandle,
+    resource: Arc<Resource>,
+    tracer: Tracer,
+}
+
+impl TextureHandle {
+    /// Wraps a texture that was not created by the device (e.g. a surface texture); the texture is
+    /// recorded as if it were created with the given `descriptor`.
+    pub fn external(
+        device: &DeviceHandle,
+        inner: inner::TextureHandle,
+        descriptor: &TextureDescriptor,
+    ) -> Self {
+        TextureHandle {
+            inner,
+            resource: device.register_texture(descriptor),
+            tracer: device.tracer.clone(),
+        }
+    }
+
+    pub fn id(&self) -> wgc::id::TextureId {
+        self.inner.id()
+    }
+}
+
+impl Texture<Driver> for TextureHandle {
+    fn texture_view(&self, descriptor: &TextureViewDescriptor) -> TextureView {
+        let id = self.tracer.next_id();
+        let resource = self.tracer.register(
+            TraceOp::CreateTextureView {
+                id,
+                texture: self.resource.id,
+                format: descriptor.format,
+                dimensions: descriptor.dimensions,
+                aspect: descriptor.aspect,
+                mip_levels: descriptor.mip_levels.clone(),
+                layers: descriptor.layers.clone(),
+            },
+            vec![self.resource.clone()],
+        );
+
+        TextureView {
+            inner: self.inner.texture_view(descriptor),
+            resource,
+        }
+    }
+    fn destroy(&self) {
+        self.tracer.record(TraceOp::DestroyTexture {
+            texture: self.resource.id,
+        });
+
+        self.inner.destroy();
+    }
+}
+
+#[derive(Clone)]
+pub struct TextureView {
+    inner: <inner::Driver as driver::Driver>::TextureView,
+    resource: Arc<Resource>,
+}
+
+fn image_copy_buffer_to_inner<'a>(
+    op: &ImageCopyBuffer<'a, Driver>,
+) -> (ImageCopyBuffer<'a, inner::Driver>, TraceImageCopyBuffer) {
+    (
+        ImageCopyBuffer {
+            buffer_handle: &op.buffer_handle.inner,
+            offset: op.offset,
+            size: op.size,
+            bytes_per_block: op.bytes_per_block,
+            blocks_per_row: op.blocks_per_row,
+            rows_per_image: op.rows_per_image,
+        },
+        TraceImageCopyBuffer {
+            buffer: op.buffer_handle.resource.id,
+            offset: op.offset,
+            size: op.size,
+            bytes_per_block: op.bytes_per_block,
+            blocks_per_row: op.blocks_per_row,
+            rows_per_image: op.rows_per_image,
+        },
+    )
+}
+
+fn image_copy_texture_to_inner<'a>(
+    op: &ImageCopyTexture<'a, Driver>,
+) -> (ImageCopyTexture<'a, inner::Driver>, TraceImageCopyTexture) {
+    (
+        ImageCopyTexture {
+            texture_handle: &op.texture_handle.inner,
+            mip_level: op.mip_level,
+            origin: op.origin,
+            aspect: op.aspect,
+        },
+        TraceImageCopyTexture {
+            texture: op.texture_handle.resource.id,
+            mip_level: op.mip_level,
+            origin: op.origin,
+            aspect: op.aspect,
+        },
+    )
+}
+
+struct RecorderState {
+    commands: Vec<TraceCommand>,
+    dependencies: Vec<Arc<Resource>>,
+}
+
+// Command encoders share their recorder with the pass encoders they begin, such that commands are
+// recorded in encoding order.
+#[derive(Clone)]
+struct Recorder {
+    state: Arc<Mutex<RecorderState>>,
+}
+
+impl Recorder {
+    fn new() -> Self {
+        Recorder {
+            state: Arc::new(Mutex::new(RecorderState {
+                commands: Vec::new(),
+                dependencies: Vec::new(),
+            })),
+        }
+    }
+
+    fn push(&self, command: TraceCommand, dependencies: &[&Arc<Resource>]) {
+        let mut state = self.state.lock().unwrap();
+
+        state.commands.push(command);
+        state
+            .dependencies
+            .extend(dependencies.iter().map(|r| (*r).clone()));
+    }
+
+    fn take(&self) -> (Vec<TraceCommand>, Vec<Arc<Resource>>) {
+        let mut state = self.state.lock().unwrap();
+
+        (
+            std::mem::take(&mut state.commands),
+            std::mem::take(&mut state.dependencies),
+        )
+    }
+}
+
+pub struct CommandEncoderHandle {
+    inner: inner::CommandEncoderHandle,
+    recorder: Recorder,
+}
+
+impl CommandEncoder<Driver> for CommandEncoderHandle {
+    fn copy_buffer_to_buffer(&mut self, op: CopyBufferToBuffer<Driver>) {
+        self.recorder.push(
+            TraceCommand::CopyBufferToBuffer {
+                source: op.source.resource.id,
+                source_offset: op.source_offset,
+                destination: op.destination.resource.id,
+                destination_offset: op.destination_offset,
+                size: op.size,
+            },
+            &[&op.source.resource, &op.destination.resource],
+        );
+
+        self.inner.copy_buffer_to_buffer(CopyBufferToBuffer {
+            source: &op.source.inner,
+            source_offset: op.source_offset,
+            destination: &op.destination.inner,
+            destination_offset: op.destination_offset,
+            size: op.size,
+        });
+    }
+
+    fn copy_buffer_to_texture(&mut self, op: CopyBufferToTexture<Driver>) {
+        let (source, trace_source) = image_copy_buffer_to_inner(&op.source);
+        let (destination, trace_destination) = image_copy_texture_to_inner(&op.destination);
+
+        self.recorder.push(
+            TraceCommand::CopyBufferToTexture {
+                source: trace_source,
+                destination: trace_destination,
+                copy_size: op.copy_size,
+            },
+            &[
+                &op.source.buffer_handle.resource,
+                &op.destination.texture_handle.resource,
+            ],
+        );
+
+        self.inner.copy_buffer_to_texture(CopyBufferToTexture {
+            source,
+            destination,
+            copy_size: op.copy_size,
+        });
+    }
+
+    fn copy_texture_to_buffer(&mut self, op: CopyTextureToBuffer<Driver>) {
+        let (source, trace_source) = image_copy_texture_to_inner(&op.source);
+        let (destination, trace_destination) = image_copy_buffer_to_inner(&op.destination);
+
+        self.recorder.push(
+            TraceCommand::CopyTextureToBuffer {
+                source: trace_source,
+                destination: trace_destination,
+                copy_size: op.copy_size,
+            },
+            &[
+                &op.source.texture_handle.resource,
+                &op.destination.buffer_handle.resource,
+            ],
+        );
+
+        self.inner.copy_texture_to_buffer(CopyTextureToBuffer {
+            source,
+            destination,
+            copy_size: op.copy_size,
+        });
+    }
+
+    fn copy_texture_to_texture(&mut self, op: CopyTextureToTexture<Driver>) {
+        let (source, trace_source) = image_copy_texture_to_inner(&op.source);
+        let (destination, trace_destination) = image_copy_texture_to_inner(&op.destination);
+
+        self.recorder.push(
+            TraceCommand::CopyTextureToTexture {
+                source: trace_source,
+                destination: trace_destination,
+                copy_size: op.copy_size,
+            },
+            &[
+                &op.source.texture_handle.resource,
+                &op.destination.texture_handle.resource,
+            ],
+        );
+
+        self.inner.copy_texture_to_texture(CopyTextureToTexture {
+            source,
+            destination,
+            copy_size: op.copy_size,
+        });
+    }
+
+    fn clear_buffer(&mut self, op: ClearBuffer<Driver>) {
+        self.recorder.push(
+            TraceCommand::ClearBuffer {
+                buffer: op.buffer.resource.id,
+                range: op.range.clone(),
+            },
+            &[&op.buffer.resource],
+        );
+
+        self.inner.clear_buffer(ClearBuffer {
+            buffer: &op.buffer.inner,
+            range: op.range,
+        });
+    }
+
+    fn begin_compute_pass(&mut self) -> ComputePassEncoderHandle {
+        self.recorder.push(TraceCommand::BeginComputePass, &[]);
+
+        ComputePassEncoderHandle {
+            inner: self.inner.begin_compute_pass(),
+            recorder: self.recorder.clone(),
+        }
+    }
+
+    fn begin_render_pass<I>(
+        &mut self,
+        descriptor: RenderPassDescriptor<Driver, I>,
+    ) -> RenderPassEncoderHandle
+    where
+        I: IntoIterator<Item = Option<RenderPassColorAttachment<Driver>>>,
+    {
+        let color_attachments: Vec<Option<RenderPassColorAttachment<Driver>>> =
+            descriptor.color_attachments.into_iter().collect();
+
+        let mut dependencies = Vec::new();
+
+        let trace_color_attachments = color_attachments
+            .iter()
+            .map(|a| {
+                a.as_ref().map(|a| {
+                    dependencies.push(&a.view.resource);
+
+                    if let Some(resolve_target) = &a.resolve_target {
+                        dependencies.push(&resolve_target.resource);
+                    }
+
+                    TraceColorAttachment {
+                        view: a.view.resource.id,
+                        resolve_target: a.resolve_target.as_ref().map(|r| r.resource.id),
+                        load_op: a.load_op,
+                        store_op: a.store_op,
+                    }
+                })
+            })
+            .collect();
+
+        let trace_depth_stencil_attachment =
+            descriptor.depth_stencil_attachment.as_ref().map(|a| {
+                dependencies.push(&a.view.resource);
+
+                TraceDepthStencilAttachment {
+                    view: a.view.resource.id,
+                    depth_operations: a.depth_operations.as_ref().map(|o| (o.load_op, o.store_op)),
+                    stencil_operations: a
+                        .stencil_operations
+                        .as_ref()
+                        .map(|o| (o.load_op, o.store_op)),
+                }
+            });
+
+        if let Some(query_set) = descriptor.occlusion_query_set {
+            dependencies.push(&query_set.resource);
+        }
+
+        self.recorder.push(
+            TraceCommand::BeginRenderPass {
+                color_attachments: trace_color_attachments,
+                depth_stencil_attachment: trace_depth_stencil_attachment,
+                occlusion_query_set: descriptor.occlusion_query_set.map(|q| q.resource.id),
+            },
+            &dependencies,
+        );
+
+        let inner = self.inner.begin_render_pass(RenderPassDescriptor {
+            color_attachments: color_attachments.iter().map(|a| {
+                a.as_ref().map(|a| RenderPassColorAttachment {
+                    view: a.view.inner.clone(),
+                    resolve_target: a.resolve_target.as_ref().map(|r| r.inner.clone()),
+                    load_op: a.load_op,
+                    store_op: a.store_op,
+                })
+            }),
+            depth_stencil_attachment: descriptor.depth_stencil_attachment.as_ref().map(|a| {
+                RenderPassDepthStencilAttachment {
+                    view: a.view.inner.clone(),
+                    depth_operations: a.depth_operations.as_ref().map(|o| DepthStencilOperations {
+                        load_op: o.load_op,
+                        store_op: o.store_op,
+                    }),
+                    stencil_operations: a.stencil_operations.as_ref().map(|o| {
+                        DepthStencilOperations {
+                            load_op: o.load_op,
+                            store_op: o.store_op,
+                        }
+                    }),
+                }
+            }),
+            occlusion_query_set: descriptor.occlusion_query_set.map(|q| &q.inner),
+        });
+
+        RenderPassEncoderHandle {
+            inner,
+            recorder: self.recorder.clone(),
+        }
+    }
+
+    fn write_timestamp(&mut self, query_set: &QuerySetHandle, index: usize) {
+        self.recorder.push(
+            TraceCommand::WriteTimestamp {
+                query_set: query_set.resource.id,
+                index,
+            },
+            &[&query_set.resource],
+        );
+
+        self.inner.write_timestamp(&query_set.inner, index);
+    }
+
+    fn resolve_query_set(&mut self, op: ResolveQuerySet<Driver>) {
+        self.recorder.push(
+            TraceCommand::ResolveQuerySet {
+                query_set: op.query_set.resource.id,
+                query_range: op.query_range.clone(),
+                destination: op.destination.resource.id,
+                destination_offset: op.destination_offset,
+            },
+            &[&op.query_set.resource, &op.destination.resource],
+        );
+
+        self.inner.resolve_query_set(ResolveQuerySet {
+            query_set: &op.query_set.inner,
+            query_range: op.query_range,
+            destination: &op.destination.inner,
+            destination_offset: op.destination_offset,
+        });
+    }
+
+    fn finish(self) -> CommandBufferHandle {
+        let (commands, dependencies) = self.recorder.take();
+
+        CommandBufferHandle {
+            inner: self.inner.finish(),
+            commands: Arc::new(commands),
+            _dependencies: Arc::new(dependencies),
+        }
+    }
+}
+
+pub struct ComputePassEncoderHandle {
+    inner: inner::ComputePassEncoderHandle,
+    recorder: Recorder,
+}
+
+impl ProgrammablePassEncoder<Driver> for ComputePassEncoderHandle {
+    fn set_bind_group(&mut self, index: u32, handle: &BindGroupHandle) {
+        self.recorder.push(
+            TraceCommand::SetBindGroup {
+                index,
+                bind_group: handle.resource.id,
+            },
+            &[&handle.resource],
+        );
+
+        self.inner.set_bind_group(index, &handle.inner);
+    }
+}
+
+impl ComputePassEncoder<Driver> for ComputePassEncoderHandle {
+    fn set_pipeline(&mut self, handle: &ComputePipelineHandle) {
+        self.recorder.push(
+            TraceCommand::SetComputePipeline(handle.resource.id),
+            &[&handle.resource],
+        );
+
+        ComputePassEncoder::set_pipeline(&mut self.inner, &handle.inner);
+    }
+
+    fn dispatch_workgroups(&mut self, x: u32, y: u32, z: u32) {
+        self.recorder
+            .push(TraceCommand::DispatchWorkgroups(x, y, z), &[]);
+
+        self.inner.dispatch_workgroups(x, y, z);
+    }
+
+    fn dispatch_workgroups_indirect(&mut self, buffer_handle: &BufferHandle, offset: usize) {
+        self.recorder.push(
+            TraceCommand::DispatchWorkgroupsIndirect {
+                buffer: buffer_handle.resource.id,
+                offset,
+            },
+            &[&buffer_handle.resource],
+        );
+
+        self.inner
+            .dispatch_workgroups_indirect(&buffer_handle.inner, offset);
+    }
+
+    fn begin_pipeline_statistics_query(&mut self, query_set: &QuerySetHandle, query_index: u32) {
+        self.recorder.push(
+            TraceCommand::BeginPipelineStatisticsQuery {
+                query_set: query_set.resource.id,
+                index: query_index,
+            },
+            &[&query_set.resource],
+        );
+
+        self.inner
+            .begin_pipeline_statistics_query(&query_set.inner, query_index);
+    }
+
+    fn end_pipeline_statistics_query(&mut self) {
+        self.recorder
+            .push(TraceCommand::EndPipelineStatisticsQuery, &[]);
+
+        self.inner.end_pipeline_statistics_query();
+    }
+
+    fn end(self) {
+        self.recorder.push(TraceCommand::EndComputePass, &[]);
+
+        self.inner.end();
+    }
+}
+
+// Shared by render passes and render bundle encoders.
+macro_rules! impl_render_encoder {
+    ($encoder:ident) => {
+        impl ProgrammablePassEncoder<Driver> for $encoder {
+            fn set_bind_group(&mut self, index: u32, handle: &BindGroupHandle) {
+                self.recorder.push(
+                    TraceCommand::SetBindGroup {
+                        index,
+                        bind_group: handle.resource.id,
+                    },
+                    &[&handle.resource],
+                );
+
+                self.inner.set_bind_group(index, &handle.inner);
+            }
+        }
+
+        impl RenderEncoder<Driver> for $encoder {
+            fn set_pipeline(&mut self, handle: &RenderPipelineHandle) {
+                self.recorder.push(
+                    TraceCommand::SetRenderPipeline(handle.resource.id),
+                    &[&handle.resource],
+                );
+
+                RenderEncoder::set_pipeline(&mut self.inner, &handle.inner);
+            }
+
+            fn set_index_buffer(&mut self, op: SetIndexBuffer<Driver>) {
+                self.recorder.push(
+                    TraceCommand::SetIndexBuffer {
+                        buffer: op.buffer_handle.resource.id,
+                        index_format: op.index_format,
+                        range: op.range.clone(),
+                    },
+                    &[&op.buffer_handle.resource],
+                );
+
+                self.inner.set_index_buffer(SetIndexBuffer {
+                    buffer_handle: &op.buffer_handle.inner,
+                    index_format: op.index_format,
+                    range: op.range,
+                });
+            }
+
+            fn set_vertex_buffer(&mut self, op: SetVertexBuffer<Driver>) {
+                self.recorder.push(
+                    TraceCommand::SetVertexBuffer {
+                        slot: op.slot,
+                        buffer: op.buffer_handle.resource.id,
+                        range: op.range.clone(),
+                    },
+                    &[&op.buffer_handle.resource],
+                );
+
+                self.inner.set_vertex_buffer(SetVertexBuffer {
+                    slot: op.slot,
+                    buffer_handle: &op.buffer_handle.inner,
+                    range: op.range,
+                });
+            }
+
+            fn draw(&mut self, op: Draw) {
+                self.recorder.push(TraceCommand::Draw(op), &[]);
+
+                self.inner.draw(op);
+            }
+
+            fn draw_indexed(&mut self, op: DrawIndexed) {
+                self.recorder.push(TraceCommand::DrawIndexed(op), &[]);
+
+                self.inner.draw_indexed(op);
+            }
+
+            fn draw_indirect(&mut self, buffer_handle: &BufferHandle, offset: usize) {
+                self.recorder.push(
+                    TraceCommand::DrawIndirect {
+                        buffer: buffer_handle.resource.id,
+                        offset,
+                    },
+                    &[&buffer_handle.resource],
+                );
+
+                self.inner.draw_indirect(&buffer_handle.inner, offset);
+            }
+
+            fn draw_indexed_indirect(&mut self, buffer_handle: &BufferHandle, offset: usize) {
+                self.recorder.push(
+                    TraceCommand::DrawIndexedIndirect {
+                        buffer: buffer_handle.resource.id,
+                        offset,
+                    },
+                    &[&buffer_handle.resource],
+                );
+
+                self.inner
+                    .draw_indexed_indirect(&buffer_handle.inner, offset);
+            }
+        }
+    };
+}
+
+pub struct RenderPassEncoderHandle {
+    inner: inner::RenderPassEncoderHandle,
+    recorder: Recorder,
+}
+
+impl_render_encoder!(RenderPassEncoderHandle);
+
+impl RenderPassEncoder<Driver> for RenderPassEncoderHandle {
+    fn set_viewport(&mut self, viewport: &Viewport) {
+        self.recorder
+            .push(TraceCommand::SetViewport(*viewport), &[]);
+
+        self.inner.set_viewport(viewport);
+    }
+
+    fn set_scissor_rect(&mut self, scissor_rect: &ScissorRect) {
+        self.recorder
+            .push(TraceCommand::SetScissorRect(*scissor_rect), &[]);
+
+        self.inner.set_scissor_rect(scissor_rect);
+    }
+
+    fn set_blend_constant(&mut self, blend_constant: &BlendConstant) {
+        self.recorder
+            .push(TraceCommand::SetBlendConstant(*blend_constant), &[]);
+
+        self.inner.set_blend_constant(blend_constant);
+    }
+
+    fn set_stencil_reference(&mut self, stencil_reference: u32) {
+        self.recorder
+            .push(TraceCommand::SetStencilReference(stencil_reference), &[]);
+
+        self.inner.set_stencil_reference(stencil_reference);
+    }
+
+    fn begin_occlusion_query(&mut self, query_index: u32) {
+        self.recorder
+            .push(TraceCommand::BeginOcclusionQuery(query_index), &[]);
+
+        self.inner.begin_occlusion_query(query_index);
+    }
+
+    fn end_occlusion_query(&mut self) {
+        self.recorder.push(TraceCommand::EndOcclusionQuery, &[]);
+
+        self.inner.end_occlusion_query();
+    }
+
+    fn begin_pipeline_statistics_query(&mut self, query_set: &QuerySetHandle, query_index: u32) {
+        self.recorder.push(
+            TraceCommand::BeginPipelineStatisticsQuery {
+                query_set: query_set.resource.id,
+                index: query_index,
+            },
+            &[&query_set.resource],
+        );
+
+        self.inner
+            .begin_pipeline_statistics_query(&query_set.inner, query_index);
+    }
+
+    fn end_pipeline_statistics_query(&mut self) {
+        self.recorder
+            .push(TraceCommand::EndPipelineStatisticsQuery, &[]);
+
+        self.inner.end_pipeline_statistics_query();
+    }
+
+    fn multi_draw_indexed_indirect_count(&mut self, op: MultiDrawIndexedIndirectCount<Driver>) {
+        self.recorder.push(
+            TraceCommand::MultiDrawIndexedIndirectCount {
+                buffer: op.buffer_handle.resource.id,
+                offset: op.offset,
+                count_buffer: op.count_buffer_handle.resource.id,
+                count_offset: op.count_offset,
+                max_count: op.max_count,
+            },
+            &[&op.buffer_handle.resource, &op.count_buffer_handle.resource],
+        );
+
+        self.inner
+            .multi_draw_indexed_indirect_count(MultiDrawIndexedIndirectCount {
+                buffer_handle: &op.buffer_handle.inner,
+                offset: op.offset,
+                count_buffer_handle: &op.count_buffer_handle.inner,
+                count_offset: op.count_offset,
+                max_count: op.max_count,
+            });
+    }
+
+    fn execute_bundles<'a>(&'a mut self) -> ExecuteRenderBundlesEncoderHandle<'a> {
+        ExecuteRenderBundlesEncoderHandle {
+            inner: self.inner.execute_bundles(),
+            recorder: &self.recorder,
+            bundles: Vec::new(),
+        }
+    }
+
+    fn end(self) {
+        self.recorder.push(TraceCommand::EndRenderPass, &[]);
+
+        self.inner.end();
+    }
+}
+
+pub struct ExecuteRenderBundlesEncoderHandle<'a> {
+    inner: inner::ExecuteRenderBundlesEncoderHandle<'a>,
+    recorder: &'a Recorder,
+    bundles: Vec<Arc<Resource>>,
+}
+
+impl ExecuteRenderBundlesEncoder<Driver> for ExecuteRenderBundlesEncoderHandle<'_> {
+    fn push_bundle(&mut self, bundle: &RenderBundleHandle) {
+        self.bundles.push(bundle.resource.clone());
+        self.inner.push_bundle(&bundle.inner);
+    }
+
+    fn finish(self) {
+        let ids = self.bundles.iter().map(|b| b.id).collect();
+        let dependencies: Vec<&Arc<Resource>> = self.bundles.iter().collect();
+
+        self.recorder
+            .push(TraceCommand::ExecuteBundles(ids), &dependencies);
+
+        self.inner.finish();
+    }
+}
+
+pub struct RenderBundleEncoderHandle {
+    inner: inner::RenderBundleEncoderHandle,
+    tracer: Tracer,
+    color_formats: Vec<TextureFormatId>,
+    depth_stencil_format: Option<TextureFormatId>,
+    sample_count: u32,
+    depth_read_only: bool,
+    stencil_read_only: bool,
+    recorder: Recorder,
+}
+
+impl_render_encoder!(RenderBundleEncoderHandle);
+
+impl RenderBundleEncoder<Driver> for RenderBundleEncoderHandle {
+    fn finish(self) -> RenderBundleHandle {
+        let (commands, dependencies) = self.recorder.take();
+
+        let id = self.tracer.next_id();
+        let resource = self.tracer.register(
+            TraceOp::CreateRenderBundle {
+                id,
+                color_formats: self.color_formats,
+                depth_stencil_format: self.depth_stencil_format,
+                sample_count: self.sample_count,
+                depth_read_only: self.depth_read_only,
+                stencil_read_only: self.stencil_read_only,
+                commands,
+            },
+            dependencies,
+        );
+
+        RenderBundleHandle {
+            inner: self.inner.finish(),
+            resource,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct CommandBufferHandle {
+    inner: inner::CommandBufferHandle,
+    commands: Arc<Vec<TraceCommand>>,
+    _dependencies: Arc<Vec<Arc<Resource>>>,
+}
+
+#[derive(Clone)]
+pub struct QueueHandle {
+    inner: inner::QueueHandle,
+    tracer: Tracer,
+}
+
+impl Queue<Driver> for QueueHandle {
+    type OnSubmittedWorkDone = <inner::QueueHandle as Queue<inner::Driver>>::OnSubmittedWorkDone;
+
+    type SubmissionIndex = <inner::QueueHandle as Queue<inner::Driver>>::SubmissionIndex;
+
+    fn submit(&self, command_buffer: &CommandBufferHandle) -> Self::SubmissionIndex {
+        if self.tracer.is_capturing() {
+            self.tracer.record(TraceOp::Submit {
+                commands: command_buffer.commands.as_ref().clone(),
+            });
+        }
+
+        self.inner.submit(&command_buffer.inner)
+    }
+
+    fn on_submitted_work_done(&self) -> Self::OnSubmittedWorkDone {
+        self.inner.on_submitted_work_done()
+    }
+
+    fn write_buffer(&self, operation: WriteBufferOperation<Driver>) {
+        if self.tracer.is_capturing() {
+            self.tracer.record(TraceOp::WriteBuffer {
+                buffer: operation.buffer_handle.resource.id,
+                offset: operation.offset,
+                data: TraceData::new(operation.data, self.tracer.include_data()),
+            });
+        }
+
+        self.inner.write_buffer(WriteBufferOperation {
+            buffer_handle: &operation.buffer_handle.inner,
+            offset: operation.offset,
+            data: operation.data,
+        });
+    }
+
+    fn write_texture(&self, operation: WriteTextureOperation<Driver>) {
+        let (image_copy_texture, trace_texture) =
+            image_copy_texture_to_inner(&operation.image_copy_texture);
+
+        if self.tracer.is_capturing() {
+            let layout = &operation.image_data_layout;
+
+            self.tracer.record(TraceOp::WriteTexture {
+                texture: trace_texture,
+                offset: layout.offset,
+                bytes_per_row: layout.bytes_per_row,
+                rows_per_image: layout.rows_per_image,
+                extent: operation.extent,
+                data: TraceData::new(operation.data, self.tracer.include_data()),
+            });
+        }
+
+        self.inner.write_texture(WriteTextureOperation {
+            image_copy_texture,
+            image_data_layout: operation.image_data_layout,
+            extent: operation.extent,
+            data: operation.data,
+        });
+    }
+
+    fn timestamp_period(&self) -> f32 {
+        self.inner.timestamp_period()
+    }
+}
+
+macro_rules! resource_handle {
+    ($handle:ident) => {
+        #[derive(Clone)]
+        pub struct $handle {
+            inner: inner::$handle,
+            resource: Arc<Resource>,
+        }
+    };
+}
+
+resource_handle!(BindGroupHandle);
+resource_handle!(RenderBundleHandle);
+resource_handle!(SamplerHandle);
+resource_handle!(BindGroupLayoutHandle);
+resource_handle!(PipelineLayoutHandle);
+resource_handle!(ComputePipelineHandle);
+resource_handle!(RenderPipelineHandle);
+resource_handle!(QuerySetHandle);
+resource_handle!(ShaderModuleHandle);
+
+enum ReplayResource {
+    Buffer(inner::BufferHandle),
+    Texture(inner::TextureHandle),
+    TextureView(<inner::Driver as driver::Driver>::TextureView),
+    Sampler(inner::SamplerHandle),
+    BindGroupLayout(inner::BindGroupLayoutHandle),
+    PipelineLayout(inner::PipelineLayoutHandle),
+    BindGroup(inner::BindGroupHandle),
+    QuerySet(inner::QuerySetHandle),
+    ShaderModule(inner::ShaderModuleHandle),
+    ComputePipeline(inner::ComputePipelineHandle),
+    RenderPipeline(inner::RenderPipelineHandle),
+    RenderBundle(inner::RenderBundleHandle),
+}
+
+macro_rules! replay_resource_getter {
+    ($name:ident, $variant:ident, $handle:ty, $kind:literal) => {
+        fn $name(&self, id: TraceId) -> Result<&$handle, ReplayError> {
+            match self.resources.get(&id) {
+                Some(ReplayResource::$variant(handle)) => Ok(handle),
+                _ => Err(ReplayError::UndefinedResource { kind: $kind, id }),
+            }
+        }
+    };
+}
+
+/// Error returned when a trace cannot be replayed.
+#[derive(Clone, PartialEq, Debug)]
+pub enum ReplayError {
+    /// An operation references a resource that the trace does not define, or that the trace defines
+    /// as a different kind of resource.
+    UndefinedResource { kind: &'static str, id: TraceId },
+    /// A command is not valid in the context in which it was recorded.
+    InvalidCommand {
+        command: TraceCommand,
+        context: &'static str,
+    },
+    /// A command sequence ends while a compute pass or render pass is still open.
+    UnfinishedPass,
+    /// The device failed to create a resource described by the trace.
+    CreationFailed {
+        kind: &'static str,
+        id: TraceId,
+        message: String,
+    },
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::UndefinedResource { kind, id } => {
+                write!(f, "trace does not define {} `{}`", kind, id)
+            }
+            ReplayError::InvalidCommand { command, context } => {
+                write!(f, "command `{:?}` is not valid {}", command, context)
+            }
+            ReplayError::UnfinishedPass => write!(f, "trace ends with an unfinished pass"),
+            ReplayError::CreationFailed { kind, id, message } => {
+                write!(f, "failed to create {} `{}`: {}", kind, id, message)
+            }
+        }
+    }
+}
+
+impl Error for ReplayError {}
+
+/// Re-executes recorded operations against a device.
+///
+/// Resources are created with the wrapped native driver directly, such that replaying a trace does
+/// not itself get recorded by a capture that is in progress.
+pub struct Replayer<'a> {
+    device: &'a inner::DeviceHandle,
+    queue: &'a inner::QueueHandle,
+    resources: HashMap<TraceId, ReplayResource>,
+}
+
+impl<'a> Replayer<'a> {
+    pub fn new(device: &'a DeviceHandle, queue: &'a QueueHandle) -> Self {
+        Replayer {
+            device: &device.inner,
+            queue: &queue.inner,
+            resources: HashMap::new(),
+        }
+    }
+
+    replay_resource_getter!(buffer, Buffer, inner::BufferHandle, "buffer");
+    replay_resource_getter!(texture, Texture, inner::TextureHandle, "texture");
+    replay_resource_getter!(
+        texture_view,
+        TextureView,
+        <inner::Driver as driver::Driver>::TextureView,
+        "texture view"
+    );
+    replay_resource_getter!(sampler, Sampler, inner::SamplerHandle, "sampler");
+    replay_resource_getter!(
+        bind_group_layout,
+        BindGroupLayout,
+        inner::BindGroupLayoutHandle,
+        "bind group layout"
+    );
+    replay_resource_getter!(
+        pipeline_layout,
+        PipelineLayout,
+        inner::PipelineLayoutHandle,
+        "pipeline layout"
+    );
+    replay_resource_getter!(bind_group, BindGroup, inner::BindGroupHandle, "bind group");
+    replay_resource_getter!(query_set, QuerySet, inner::QuerySetHandle, "query set");
+    replay_resource_getter!(
+        shader_module,
+        ShaderModule,
+        inner::ShaderModuleHandle,
+        "shader module"
+    );
+    replay_resource_getter!(
+        compute_pipeline,
+        ComputePipeline,
+        inner::ComputePipelineHandle,
+        "compute pipeline"
+    );
+    replay_resource_getter!(
+        render_pipeline,
+        RenderPipeline,
+        inner::RenderPipelineHandle,
+        "render pipeline"
+    );
+    replay_resource_getter!(
+        render_bundle,
+        RenderBundle,
+        inner::RenderBundleHandle,
+        "render bundle"
+    );
+
+    pub fn replay(&mut self, op: &TraceOp) -> Result<(), ReplayError> {
+        match op {
+            TraceOp::CreateBuffer { id, size, usage } => {
+                let handle = self
+                    .device
+                    .create_buffer(&BufferDescriptor {
+                        size: *size,
+                        usage_flags: FlagSet::new_truncated(*usage),
+                        mapped_at_creation: false,
+                    })
+                    .map_err(|err| ReplayError::CreationFailed {
+                        kind: "buffer",
+                        id: *id,
+                        message: err.to_string(),
+                    })?;
+
+                self.resources.insert(*id, ReplayResource::Buffer(handle));
+            }
+            TraceOp::CreateTexture {
+                id,
+                size,
+                mipmap_levels,
+                sample_count,
+                dimensions,
+                format,
+                usage,
+                view_formats,
+            } => {
+                let handle = self
+                    .device
+                    .create_texture(&TextureDescriptor {
+                        size: *size,
+                        mipmap_levels: *mipmap_levels,
+                        sample_count: *sample_count,
+                        dimensions: *dimensions,
+                        format: *format,
+                        usage_flags: FlagSet::new_truncated(*usage),
+                        view_formats,
+                    })
+                    .map_err(|err| ReplayError::CreationFailed {
+                        kind: "texture",
+                        id: *id,
+                        message: err.to_string(),
+                    })?;
+
+                self.resources.insert(*id, ReplayResource::Texture(handle));
+            }
+            TraceOp::CreateTextureView {
+                id,
+                texture,
+                format,
+                dimensions,
+                aspect,
+                mip_levels,
+                layers,
+            } => {
+                let handle = self
+                    .texture(*texture)?
+                    .texture_view(&TextureViewDescriptor {
+                        format: *format,
+                        dimensions: *dimensions,
+                        aspect: *aspect,
+                        mip_levels: mip_levels.clone(),
+                        layers: layers.clone(),
+                    });
+
+                self.resources
+                    .insert(*id, ReplayResource::TextureView(handle));
+            }
+            TraceOp::CreateSampler { id, descriptor } => {
+                let handle = self.device.create_sampler(&SamplerDescriptor {
+                    address_mode_u: descriptor.address_mode_u,
+                    address_mode_v: descriptor.address_mode_v,
+                    address_mode_w: descriptor.address_mode_w,
+                    magnification_filter: descriptor.magnification_filter,
+                    minification_filter: descriptor.minification_filter,
+                    mipmap_filter: descriptor.mipmap_filter,
+                    lod_clamp: descriptor.lod_clamp.0..=descriptor.lod_clamp.1,
+                    max_anisotropy: descriptor.max_anisotropy,
+                    compare: descriptor.compare,
+                });
+
+                self.resources.insert(*id, ReplayResource::Sampler(handle));
+            }
+            TraceOp::CreateBindGroupLayout { id, entries } => {
+                let handle = self
+                    .device
+                    .create_bind_group_layout(BindGroupLayoutDescriptor {
+                        entries: entries.iter().map(|e| BindGroupLayoutEntry {
+                            binding: e.binding,
+                            binding_type: e.binding_type,
+                            visibility: FlagSet::new_truncated(e.visibility),
+                        }),
+                    });
+
+                self.resources
+                    .insert(*id, ReplayResource::BindGroupLayout(handle));
+            }
+            TraceOp::CreatePipelineLayout {
+                id,
+                bind_group_layouts,
+            } => {
+                let bind_group_layouts = bind_group_layouts
+                    .iter()
+                    .map(|l| self.bind_group_layout(*l)?)
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                let handle = self
+                    .device
+                    .create_pipeline_layout(PipelineLayoutDescriptor {
+                        bind_group_layouts: bind_group_layouts.into_iter(),
+                    });
+
+                self.resources
+                    .insert(*id, ReplayResource::PipelineLayout(handle));
+            }
+            TraceOp::CreateBindGroup {
+                id,
+                layout,
+                entries,
+            } => {
+                let entries = entries
+                    .iter()
+                    .map(|(binding, resource)| {
+                        let resource = match resource {
+                            TraceBindingResource::Buffer {
+                                buffer,
+                                offset,
+                                size,
+                            } => BindingResource::BufferBinding(
+                                self.buffer(*buffer)?.binding(*offset, *size),
+                            ),
+                            TraceBindingResource::TextureView(view) => {
+                                BindingResource::TextureView(self.texture_view(*view)?.clone())
+                            }
+                            TraceBindingResource::Sampler(sampler) => {
+                                BindingResource::Sampler(self.sampler(*sampler)?)
+                            }
+                        };
+
+                        Ok(BindGroupEntry {
+                            binding: *binding,
+                            resource,
+                        })
+                    })
+                    .collect::<Result<Vec<_>, ReplayError>>()?;
+
+                let handle = self.device.create_bind_group(BindGroupDescriptor {
+                    layout: self.bind_group_layout(*layout)?,
+                    entries: entries.into_iter(),
+                });
+
+                self.resources
+                    .insert(*id, ReplayResource::BindGroup(handle));
+            }
+            TraceOp::CreateQuerySet {
+                id,
+                query_type,
+                len,
+            } => {
+                let handle = self.device.create_query_set(&QuerySetDescriptor {
+                    query_type: *query_type,
+                    len: *len,
+                });
+
+                self.resources.insert(*id, ReplayResource::QuerySet(handle));
+            }
+            TraceOp::CreateShaderModule { id, source } => {
+                let handle = self.device.create_shader_module(source);
+
+                self.resources
+                    .insert(*id, ReplayResource::ShaderModule(handle));
+            }
+            TraceOp::CreateComputePipeline { id, layout, stage } => {
+                let handle = self
+                    .device
+                    .create_compute_pipeline(&ComputePipelineDescriptor {
+                        layout: self.pipeline_layout(*layout)?,
+                        shader_module: self.shader_module(stage.shader_module)?,
+                        entry_point: &stage.entry_point,
+                        constants: &stage.constants,
+                    })
+                    .map_err(|err| ReplayError::CreationFailed {
+                        kind: "compute pipeline",
+                        id: *id,
+                        message: err.to_string(),
+                    })?;
+
+                self.resources
+                    .insert(*id, ReplayResource::ComputePipeline(handle));
+            }
+            TraceOp::CreateRenderPipeline {
+                id,
+                layout,
+                primitive_state,
+                vertex_stage,
+                vertex_buffer_layouts,
+                depth_stencil_state,
+                fragment_stage,
+                multisample_state,
+                multiview,
+            } => {
+                let vertex_buffer_layouts: Vec<VertexBufferLayout> = vertex_buffer_layouts
+                    .iter()
+                    .map(|l| VertexBufferLayout {
+                        array_stride: l.array_stride,
+                        step_mode: l.step_mode,
+                        attributes: l.attributes.as_slice().into(),
+                    })
+                    .collect();
+
+                let targets: Vec<ColorTargetState> = fragment_stage
+                    .iter()
+                    .flat_map(|(_, targets)| targets)
+                    .map(|t| ColorTargetState {
+                        format: t.format,
+                        blend: t.blend,
+                        write_mask: FlagSet::new_truncated(t.write_mask),
+                    })
+                    .collect();
+
+                let fragment_module = fragment_stage
+                    .as_ref()
+                    .map(|(stage, _)| self.shader_module(stage.shader_module)?)
+                    .transpose()?;
+
+                let handle = self
+                    .device
+                    .create_render_pipeline(&RenderPipelineDescriptor {
+                        layout: self.pipeline_layout(*layout)?,
+                        primitive_state,
+                        vertex_state: VertexState {
+                            shader_module: self.shader_module(vertex_stage.shader_module)?,
+                            entry_point: &vertex_stage.entry_point,
+                            constants: &vertex_stage.constants,
+                            vertex_buffer_layouts: &vertex_buffer_layouts,
+                        },
+                        depth_stencil_state: depth_stencil_state.as_ref(),
+                        fragment_state: fragment_stage.as_ref().zip(fragment_module).map(
+                            |((stage, _), shader_module)| FragmentState {
+                                shader_module,
+                                entry_point: &stage.entry_point,
+                                constants: &stage.constants,
+                                targets: &targets,
+                            },
+                        ),
+                        multisample_state: multisample_state.as_ref(),
+                        multiview: *multiview,
+                    })
+                    .map_err(|err| ReplayError::CreationFailed {
+                        kind: "render pipeline",
+                        id: *id,
+                        message: err.to_string(),
+                    })?;
+
+                self.resources
+                    .insert(*id, ReplayResource::RenderPipeline(handle));
+            }
+            TraceOp::CreateRenderBundle {
+                id,
+                color_formats,
+                depth_stencil_format,
+                sample_count,
+                depth_read_only,
+                stencil_read_only,
+                commands,
+            } => {
+                let mut encoder =
+                    self.device
+                        .create_render_bundle_encoder(&RenderBundleEncoderDescriptor {
+                            color_formats,
+                            depth_stencil_format: *depth_stencil_format,
+                            sample_count: *sample_count,
+                            depth_read_only: *depth_read_only,
+                            stencil_read_only: *stencil_read_only,
+                        });
+
+                for command in commands {
+                    if !self.encode_render_command(&mut encoder, command)? {
+                        return Err(ReplayError::InvalidCommand {
+                            command: command.clone(),
+                            context: "in a render bundle",
+                        });
+                    }
+                }
+
+                let handle = encoder.finish();
+
+                self.resources
+                    .insert(*id, ReplayResource::RenderBundle(handle));
+            }
+            TraceOp::DestroyBuffer { buffer } => {
+                self.buffer(*buffer)?.destroy();
+            }
+            TraceOp::DestroyTexture { texture } => {
+                self.texture(*texture)?.destroy();
+            }
+            TraceOp::WriteBuffer {
+                buffer,
+                offset,
+                data,
+            } => {
+                let zeroed;
+                let bytes = match &data.bytes {
+                    Some(bytes) => bytes.as_slice(),
+                    None => {
+                        zeroed = vec![0; data.len];
+
+                        zeroed.as_slice()
+                    }
+                };
+
+                self.queue.write_buffer(WriteBufferOperation {
+                    buffer_handle: self.buffer(*buffer)?,
+                    offset: *offset,
+                    data: bytes,
+                });
+            }
+            TraceOp::WriteTexture {
+                texture,
+                offset,
+                bytes_per_row,
+                rows_per_image,
+                extent,
+                data,
+            } => {
+                let zeroed;
+                let bytes = match &data.bytes {
+                    Some(bytes) => bytes.as_slice(),
+                    None => {
+                        zeroed = vec![0; data.len];
+
+                        zeroed.as_slice()
+                    }
+                };
+
+                self.queue.write_texture(WriteTextureOperation {
+                    image_copy_texture: self.image_copy_texture(texture)?,
+                    image_data_layout: ImageDataLayout {
+                        offset: *offset,
+                        bytes_per_row: *bytes_per_row,
+                        rows_per_image: *rows_per_image,
+                    },
+                    extent: *extent,
+                    data: bytes,
+                });
+            }
+            TraceOp::Submit { commands } => {
+                let command_buffer = self.encode_commands(commands)?;
+
+                self.queue.submit(&command_buffer);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn image_copy_buffer(
+        &self,
+        op: &TraceImageCopyBuffer,
+    ) -> Result<ImageCopyBuffer<inner::Driver>, ReplayError> {
+        Ok(ImageCopyBuffer {
+            buffer_handle: self.buffer(op.buffer)?,
+            offset: op.offset,
+            size: op.size,
+            bytes_per_block: op.bytes_per_block,
+            blocks_per_row: op.blocks_per_row,
+            rows_per_image: op.rows_per_image,
+        })
+    }
+
+    fn image_copy_texture(
+        &self,
+        op: &TraceImageCopyTexture,
+    ) -> Result<ImageCopyTexture<inner::Driver>, ReplayError> {
+        Ok(ImageCopyTexture {
+            texture_handle: self.texture(op.texture)?,
+            mip_level: op.mip_level,
+            origin: op.origin,
+            aspect: op.aspect,
+        })
+    }
+
+    fn encode_commands(
+        &self,
+        commands: &[TraceCommand],
+    ) -> Result<inner::CommandBufferHandle, ReplayError> {
+        let mut encoder = self.device.create_command_encoder();
+        let mut compute_pass: Option<inner::ComputePassEncoderHandle> = None;
+        let mut render_pass: Option<inner::RenderPassEncoderHandle> = None;
+
+        for command in commands {
+            if let Some(pass) = compute_pass.as_mut() {
+                match command {
+                    TraceCommand::SetComputePipeline(pipeline) => {
+                        ComputePassEncoder::set_pipeline(pass, self.compute_pipeline(*pipeline)?);
+                    }
+                    TraceCommand::SetBindGroup { index, bind_group } => {
+                        pass.set_bind_group(*index, self.bind_group(*bind_group)?);
+                    }
+                    TraceCommand::DispatchWorkgroups(x, y, z) => {
+                        pass.dispatch_workgroups(*x, *y, *z);
+                    }
+                    TraceCommand::DispatchWorkgroupsIndirect { buffer, offset } => {
+                        pass.dispatch_workgroups_indirect(self.buffer(*buffer)?, *offset);
+                    }
+                    TraceCommand::BeginPipelineStatisticsQuery { query_set, index } => {
+                        pass.begin_pipeline_statistics_query(self.query_set(*query_set)?, *index);
+                    }
+                    TraceCommand::EndPipelineStatisticsQuery => {
+                        pass.end_pipeline_statistics_query();
+                    }
+                    TraceCommand::EndComputePass => {
+                        compute_pass.take().unwrap().end();
+                    }
+                    command => {
+                        return Err(ReplayError::InvalidCommand {
+                            command: command.clone(),
+                            context: "in a compute pass",
+                        })
+                    }
+                }
+
+                continue;
+            }
+
+            if let Some(pass) = render_pass.as_mut() {
+                if self.encode_render_command(pass, command)? {
+                    continue;
+                }
+
+                match command {
+                    TraceCommand::SetViewport(viewport) => pass.set_viewport(viewport),
+                    TraceCommand::SetScissorRect(scissor_rect) => {
+                        pass.set_scissor_rect(scissor_rect)
+                    }
+                    TraceCommand::SetBlendConstant(blend_constant) => {
+                        pass.set_blend_constant(blend_constant)
+                    }
+                    TraceCommand::SetStencilReference(reference) => {
+                        pass.set_stencil_reference(*reference)
+                    }
+                    TraceCommand::BeginOcclusionQuery(index) => pass.begin_occlusion_query(*index),
+                    TraceCommand::EndOcclusionQuery => pass.end_occlusion_query(),
+                    TraceCommand::BeginPipelineStatisticsQuery { query_set, index } => {
+                        pass.begin_pipeline_statistics_query(self.query_set(*query_set)?, *index);
+                    }
+                    TraceCommand::EndPipelineStatisticsQuery => {
+                        pass.end_pipeline_statistics_query();
+                    }
+                    TraceCommand::MultiDrawIndexedIndirectCount {
+                        buffer,
+                        offset,
+                        count_buffer,
+                        count_offset,
+                        max_count,
+                    } => pass.multi_draw_indexed_indirect_count(MultiDrawIndexedIndirectCount {
+                        buffer_handle: self.buffer(*buffer)?,
+                        offset: *offset,
+                        count_buffer_handle: self.buffer(*count_buffer)?,
+                        count_offset: *count_offset,
+                        max_count: *max_count,
+                    }),
+                    TraceCommand::ExecuteBundles(bundles) => {
+                        let mut bundle_encoder = pass.execute_bundles();
+
+                        for bundle in bundles {
+                            bundle_encoder.push_bundle(self.render_bundle(*bundle)?);
+                        }
+
+                        bundle_encoder.finish();
+                    }
+                    TraceCommand::EndRenderPass => {
+                        render_pass.take().unwrap().end();
+                    }
+                    command => {
+                        return Err(ReplayError::InvalidCommand {
+                            command: command.clone(),
+                            context: "in a render pass",
+                        })
+                    }
+                }
+
+                continue;
+            }
+
+            match command {
+                TraceCommand::CopyBufferToBuffer {
+                    source,
+                    source_offset,
+                    destination,
+                    destination_offset,
+                    size,
+                } => encoder.copy_buffer_to_buffer(CopyBufferToBuffer {
+                    source: self.buffer(*source)?,
+                    source_offset: *source_offset,
+                    destination: self.buffer(*destination)?,
+                    destination_offset: *destination_offset,
+                    size: *size,
+                }),
+                TraceCommand::CopyBufferToTexture {
+                    source,
+                    destination,
+                    copy_size,
+                } => encoder.copy_buffer_to_texture(CopyBufferToTexture {
+                    source: self.image_copy_buffer(source)?,
+                    destination: self.image_copy_texture(destination)?,
+                    copy_size: *copy_size,
+                }),
+                TraceCommand::CopyTextureToBuffer {
+                    source,
+                    destination,
+                    copy_size,
+                } => encoder.copy_texture_to_buffer(CopyTextureToBuffer {
+                    source: self.image_copy_texture(source)?,
+                    destination: self.image_copy_buffer(destination)?,
+                    copy_size: *copy_size,
+                }),
+                TraceCommand::CopyTextureToTexture {
+                    source,
+                    destination,
+                    copy_size,
+                } => encoder.copy_texture_to_texture(CopyTextureToTexture {
+                    source: self.image_copy_texture(source)?,
+                    destination: self.image_copy_texture(destination)?,
+                    copy_size: *copy_size,
+                }),
+                TraceCommand::ClearBuffer { buffer, range } => encoder.clear_buffer(ClearBuffer {
+                    buffer: self.buffer(*buffer)?,
+                    range: range.clone(),
+                }),
+                TraceCommand::WriteTimestamp { query_set, index } => {
+                    encoder.write_timestamp(self.query_set(*query_set)?, *index)
+                }
+                TraceCommand::ResolveQuerySet {
+                    query_set,
+                    query_range,
+                    destination,
+                    destination_offset,
+                } => encoder.resolve_query_set(ResolveQuerySet {
+                    query_set: self.query_set(*query_set)?,
+                    query_range: query_range.clone(),
+                    destination: self.buffer(*destination)?,
+                    destination_offset: *destination_offset,
+                }),
+                TraceCommand::BeginComputePass => {
+                    compute_pass = Some(encoder.begin_compute_pass());
+                }
+                TraceCommand::BeginRenderPass {
+                    color_attachments,
+                    depth_stencil_attachment,
+                    occlusion_query_set,
+                } => {
+                    let color_attachments = color_attachments
+                        .iter()
+                        .map(|a| {
+                            a.as_ref()
+                                .map(|a| {
+                                    let resolve_target = a
+                                        .resolve_target
+                                        .map(|r| self.texture_view(r).cloned())
+                                        .transpose()?;
+
+                                    Ok(RenderPassColorAttachment {
+                                        view: self.texture_view(a.view)?.clone(),
+                                        resolve_target,
+                                        load_op: a.load_op,
+                                        store_op: a.store_op,
+                                    })
+                                })
+                                .transpose()
+                        })
+                        .collect::<Result<Vec<_>, ReplayError>>()?;
+
+                    let depth_stencil_attachment = depth_stencil_attachment
+                        .as_ref()
+                        .map(|a| -> Result<_, ReplayError> {
+                            Ok(RenderPassDepthStencilAttachment {
+                                view: self.texture_view(a.view)?.clone(),
+                                depth_operations: a.depth_operations.map(|(load_op, store_op)| {
+                                    DepthStencilOperations { load_op, store_op }
+                                }),
+                                stencil_operations: a.stencil_operations.map(
+                                    |(load_op, store_op)| DepthStencilOperations {
+                                        load_op,
+                                        store_op,
+                                    },
+                                ),
+                            })
+                        })
+                        .transpose()?;
+
+                    let occlusion_query_set =
+                        occlusion_query_set.map(|q| self.query_set(q)).transpose()?;
+
+                    let pass = encoder.begin_render_pass(RenderPassDescriptor {
+                        color_attachments: color_attachments.into_iter(),
+                        depth_stencil_attachment,
+                        occlusion_query_set,
+                    });
+
+                    render_pass = Some(pass);
+                }
+                command => {
+                    return Err(ReplayError::InvalidCommand {
+                        command: command.clone(),
+                        context: "outside of a pass",
+                    })
+                }
+            }
+        }
+
+        if compute_pass.is_some() || render_pass.is_some() {
+            return Err(ReplayError::UnfinishedPass);
+        }
+
+        Ok(encoder.finish())
+    }
+
+    // Returns `Ok(false)` if the command is not a command that is shared by render passes and render
+    // bundles.
+    fn encode_render_command<E>(
+        &self,
+        encoder: &mut E,
+        command: &TraceCommand,
+    ) -> Result<bool, ReplayError>
+    where
+        E: RenderEncoder<inner::Driver>,
+    {
+        match command {
+            TraceCommand::SetRenderPipeline(pipeline) => {
+                RenderEncoder::set_pipeline(encoder, self.render_pipeline(*pipeline)?);
+            }
+            TraceCommand::SetBindGroup { index, bind_group } => {
+                encoder.set_bind_group(*index, self.bind_group(*bind_group)?);
+            }
+            TraceCommand::SetIndexBuffer {
+                buffer,
+                index_format,
+                range,
+            } => encoder.set_index_buffer(SetIndexBuffer {
+                buffer_handle: self.buffer(*buffer)?,
+                index_format: *index_format,
+                range: range.clone(),
+            }),
+            TraceCommand::SetVertexBuffer {
+                slot,
+                buffer,
+                range,
+            } => encoder.set_vertex_buffer(SetVertexBuffer {
+                slot: *slot,
+                buffer_handle: self.buffer(*buffer)?,
+                range: range.clone(),
+            }),
+            TraceCommand::Draw(op) => encoder.draw(*op),
+            TraceCommand::DrawIndexed(op) => encoder.draw_indexed(*op),
+            TraceCommand::DrawIndirect { buffer, offset } => {
+                encoder.draw_indirect(self.buffer(*buffer)?, *offset)
+            }
+            TraceCommand::DrawIndexedIndirect { buffer, offset } => {
+                encoder.draw_indexed_indirect(self.buffer(*buffer)?, *offset)
+            }
+            _ => return Ok(false),
+        }
+
+        Ok(true)
+    }
+}
@@ -15,24 +15,25 @@ use wasm_bindgen_futures::JsFuture;
 use web_sys::GpuSupportedFeatures;
 
 use crate::adapter::{Feature, Limits};
-use crate::buffer::MapError;
+use crate::buffer::{CreateBufferError, MapError};
 use crate::command::{BlendConstant, Draw, DrawIndexed, ScissorRect, Viewport};
-use crate::device::DeviceDescriptor;
+use crate::device::{DeviceDescriptor, PipelineCreationError};
 use crate::driver::{
     Adapter, BindGroupDescriptor, BindGroupEntry, BindGroupLayoutDescriptor, BindGroupLayoutEntry,
     BindingResource, BindingType, Buffer, BufferBindingType, BufferDescriptor, ClearBuffer,
     ColorTargetState, CommandEncoder, ComputePassEncoder, ComputePipelineDescriptor,
     CopyBufferToBuffer, CopyBufferToTexture, CopyTextureToBuffer, CopyTextureToTexture,
     DepthStencilOperations, DepthStencilState, Device, ExecuteRenderBundlesEncoder, FragmentState,
-    ImageCopyBuffer, ImageCopyTexture, ImageDataLayout, MapMode, MultisampleState,
-    PipelineLayoutDescriptor, PrimitiveState, PrimitiveTopology, ProgrammablePassEncoder,
-    QuerySetDescriptor, QueryType, Queue, RenderBundleEncoder, RenderBundleEncoderDescriptor,
-    RenderEncoder, RenderPassColorAttachment, RenderPassDepthStencilAttachment,
-    RenderPassDescriptor, RenderPassEncoder, RenderPipelineDescriptor, ResolveQuerySet,
-    SamplerBindingType, SamplerDescriptor, SetIndexBuffer, SetVertexBuffer, StencilFaceState,
-    StencilOperation, StorageTextureAccess, Texture, TextureAspect, TextureDescriptor,
-    TextureDimensions, TextureSampleType, TextureViewDescriptor, TextureViewDimension, VertexState,
-    WriteBufferOperation, WriteTextureOperation,
+    ImageCopyBuffer, ImageCopyTexture, ImageDataLayout, MapMode, MultiDrawIndexedIndirectCount,
+    MultisampleState, PipelineLayoutDescriptor, PrimitiveState, PrimitiveTopology,
+    ProgrammablePassEncoder, QuerySetDescriptor, QueryType, Queue, RenderBundleEncoder,
+    RenderBundleEncoderDescriptor, RenderEncoder, RenderPassColorAttachment,
+    RenderPassDepthStencilAttachment, RenderPassDescriptor, RenderPassEncoder,
+    RenderPipelineDescriptor, ResolveQuerySet, SamplerBindingType, SamplerDescriptor,
+    SetIndexBuffer, SetVertexBuffer, StencilFaceState, StencilOperation, StorageTextureAccess,
+    Texture, TextureAspect, TextureDescriptor, TextureDimensions, TextureSampleType,
+    TextureViewDescriptor, TextureViewDimension, VertexState, WriteBufferOperation,
+    WriteTextureOperation,
 };
 use crate::render_pipeline::{
     BlendComponent, BlendFactor, BlendState, CullMode, FrontFace, IndexFormat, VertexFormat,
@@ -41,6 +42,7 @@ use crate::render_pipeline::{
 use crate::render_target::{LoadOp, StoreOp};
 use crate::sampler::{AddressMode, FilterMode};
 use crate::texture::format::TextureFormatId;
+use crate::texture::CreateTextureError;
 use crate::{driver, CompareFunction};
 
 pub struct Driver;
@@ -178,7 +180,10 @@ impl Device<Driver> for DeviceHandle {
 
     type CreateRenderPipelineAsync = CreateRenderPipelineAsync;
 
-    fn create_buffer(&self, descriptor: &BufferDescriptor) -> BufferHandle {
+    fn create_buffer(
+        &self,
+        descriptor: &BufferDescriptor,
+    ) -> Result<BufferHandle, CreateBufferError> {
         let BufferDescriptor {
             size,
             usage_flags,
@@ -193,10 +198,14 @@ impl Device<Driver> for DeviceHandle {
 
         let inner = self.inner.create_buffer(&desc);
 
-        BufferHandle { inner }
+        // Errors are reported asynchronously as uncaptured device errors.
+        Ok(BufferHandle { inner })
     }
 
-    fn create_texture(&self, descriptor: &TextureDescriptor) -> TextureHandle {
+    fn create_texture(
+        &self,
+        descriptor: &TextureDescriptor,
+    ) -> Result<TextureHandle, CreateTextureError> {
         let TextureDescriptor {
             size,
             mipmap_levels,
@@ -226,7 +235,8 @@ impl Device<Driver> for DeviceHandle {
 
         let inner = self.inner.create_texture(&desc);
 
-        TextureHandle { inner }
+        // Errors are reported asynchronously as uncaptured device errors.
+        Ok(TextureHandle { inner })
     }
 
     fn create_sampler(&self, descriptor: &SamplerDescriptor) -> SamplerHandle {
@@ -367,11 +377,12 @@ impl Device<Driver> for DeviceHandle {
     fn create_compute_pipeline(
         &self,
         descriptor: &ComputePipelineDescriptor<Driver>,
-    ) -> ComputePipelineHandle {
+    ) -> Result<ComputePipelineHandle, PipelineCreationError> {
         let desc = compute_pipeline_descriptor_to_web_sys(descriptor);
         let inner = self.inner.create_compute_pipeline(&desc);
 
-        ComputePipelineHandle { inner }
+        // Errors are reported asynchronously as uncaptured device errors.
+        Ok(ComputePipelineHandle { inner })
     }
 
     fn create_compute_pipeline_async(
@@ -389,11 +400,12 @@ impl Device<Driver> for DeviceHandle {
     fn create_render_pipeline(
         &self,
         descriptor: &RenderPipelineDescriptor<Driver>,
-    ) -> RenderPipelineHandle {
+    ) -> Result<RenderPipelineHandle, PipelineCreationError> {
         let desc = render_pipeline_descriptor_to_web_sys(descriptor);
         let inner = self.inner.create_render_pipeline(&desc);
 
-        RenderPipelineHandle { inner }
+        // Errors are reported asynchronously as uncaptured device errors.
+        Ok(RenderPipelineHandle { inner })
     }
 
     fn create_render_pipeline_async(
@@ -461,7 +473,20 @@ impl Future for Map {
             .inner
             .poll(cx)
             .map_ok(|_| ())
-            .map_err(|_| MapError)
+            .map_err(map_error)
+    }
+}
+
+// The promise returned by `mapAsync` rejects with a `DOMException`: an `AbortError` if the buffer
+// was unmapped or destroyed before the mapping resolved, or an `OperationError` if the mapping
+// failed (e.g. because of a validation error or because the device was lost).
+fn map_error(err: JsValue) -> MapError {
+    let err: web_sys::DomException = err.unchecked_into();
+
+    match err.name().as_str() {
+        "AbortError" => MapError::Aborted,
+        "OperationError" => MapError::Validation(err.message()),
+        _ => MapError::Other(err.message()),
     }
 }
 
@@ -510,7 +535,7 @@ pub struct BufferBinding {
 
 #[derive(Clone)]
 pub struct BufferHandle {
-    inner: web_sys::GpuBuffer,
+    pub(crate) inner: web_sys::GpuBuffer,
 }
 
 impl Buffer<Driver> for BufferHandle {
@@ -599,6 +624,10 @@ impl Buffer<Driver> for BufferHandle {
 
         BufferBinding { inner }
     }
+
+    fn destroy(&self) {
+        self.inner.destroy();
+    }
 }
 
 #[derive(Clone)]
@@ -636,6 +665,10 @@ impl Texture<Driver> for TextureHandle {
 
         TextureView { inner }
     }
+
+    fn destroy(&self) {
+        self.inner.destroy();
+    }
 }
 
 #[derive(Clone)]
@@ -684,6 +717,10 @@ pub struct QueueHandle {
 }
 
 impl Queue<Driver> for QueueHandle {
+    type OnSubmittedWorkDone = OnSubmittedWorkDone;
+
+    type SubmissionIndex = ();
+
     fn submit(&self, command_buffer: &CommandBufferHandle) {
         let array = js_sys::Array::new();
 
@@ -692,6 +729,12 @@ impl Queue<Driver> for QueueHandle {
         self.inner.submit(array.as_ref());
     }
 
+    fn on_submitted_work_done(&self) -> OnSubmittedWorkDone {
+        OnSubmittedWorkDone {
+            inner: self.inner.on_submitted_work_done().into(),
+        }
+    }
+
     fn write_buffer(&self, operation: WriteBufferOperation<Driver>) {
         let WriteBufferOperation {
             buffer_handle,
@@ -719,6 +762,25 @@ impl Queue<Driver> for QueueHandle {
                 &size_3d_to_web_sys(extent),
             );
     }
+
+    fn timestamp_period(&self) -> f32 {
+        // WebGPU resolves timestamp queries in nanoseconds.
+        1.0
+    }
+}
+
+pub struct OnSubmittedWorkDone {
+    inner: JsFuture,
+}
+
+impl Future for OnSubmittedWorkDone {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.get_mut().inner).poll(cx).map(|result| {
+            result.expect("waiting for submitted work should not fail");
+        })
+    }
 }
 
 #[derive(Clone)]
@@ -731,15 +793,15 @@ pub struct CreateComputePipelineAsync {
 }
 
 impl Future for CreateComputePipelineAsync {
-    type Output = ComputePipelineHandle;
+    type Output = Result<ComputePipelineHandle, PipelineCreationError>;
 
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         Pin::new(&mut self.get_mut().inner).poll(cx).map(|result| {
-            let inner = result.expect("pipeline creation should not fail");
-
-            ComputePipelineHandle {
-                inner: inner.unchecked_into(),
-            }
+            result
+                .map(|inner| ComputePipelineHandle {
+                    inner: inner.unchecked_into(),
+                })
+                .map_err(pipeline_creation_error)
         })
     }
 }
@@ -754,19 +816,30 @@ pub struct CreateRenderPipelineAsync {
 }
 
 impl Future for CreateRenderPipelineAsync {
-    type Output = RenderPipelineHandle;
+    type Output = Result<RenderPipelineHandle, PipelineCreationError>;
 
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         Pin::new(&mut self.get_mut().inner).poll(cx).map(|result| {
-            let inner = result.expect("pipeline creation should not fail");
-
-            RenderPipelineHandle {
-                inner: inner.unchecked_into(),
-            }
+            result
+                .map(|inner| RenderPipelineHandle {
+                    inner: inner.unchecked_into(),
+                })
+                .map_err(pipeline_creation_error)
         })
     }
 }
 
+// The promise returned by `createComputePipelineAsync`/`createRenderPipelineAsync` rejects with a
+// `GPUPipelineError`, which is a `DOMException`.
+fn pipeline_creation_error(err: JsValue) -> PipelineCreationError {
+    let err: web_sys::DomException = err.unchecked_into();
+
+    PipelineCreationError {
+        message: err.message(),
+        entry_point: None,
+    }
+}
+
 #[derive(Clone)]
 pub struct CommandEncoderHandle {
     inner: web_sys::GpuCommandEncoder,
@@ -941,6 +1014,14 @@ impl ComputePassEncoder<Driver> for ComputePassEncoderHandle {
             .dispatch_workgroups_indirect_with_u32(&buffer_handle.inner, offset as u32);
     }
 
+    fn begin_pipeline_statistics_query(&mut self, _query_set: &QuerySetHandle, _query_index: u32) {
+        unsupported_pipeline_statistics()
+    }
+
+    fn end_pipeline_statistics_query(&mut self) {
+        unsupported_pipeline_statistics()
+    }
+
     fn end(self) {
         self.inner.end();
     }
@@ -1103,6 +1184,27 @@ impl RenderPassEncoder<Driver> for RenderPassEncoderHandle {
         self.inner.end_occlusion_query();
     }
 
+    fn begin_pipeline_statistics_query(&mut self, _query_set: &QuerySetHandle, _query_index: u32) {
+        unsupported_pipeline_statistics()
+    }
+
+    fn end_pipeline_statistics_query(&mut self) {
+        unsupported_pipeline_statistics()
+    }
+
+    fn multi_draw_indexed_indirect_count(&mut self, op: MultiDrawIndexedIndirectCount<Driver>) {
+        // WebGPU has no indirect draw count; emulate it by issuing `max_count` indirect draws and
+        // ignoring the count buffer.
+        let stride = mem::size_of::<DrawIndexed>();
+
+        for i in 0..op.max_count as usize {
+            self.inner.draw_indexed_indirect_with_u32(
+                &op.buffer_handle.inner,
+                (op.offset + i * stride) as u32,
+            );
+        }
+    }
+
     fn execute_bundles(&mut self) -> ExecuteRenderBundlesEncoderHandle {
         ExecuteRenderBundlesEncoderHandle {
             inner: &self.inner,
@@ -1494,6 +1596,7 @@ pub fn texture_format_to_web_sys(texture_format: &TextureFormatId) -> web_sys::G
         TextureFormatId::bgra8unorm_srgb => web_sys::GpuTextureFormat::Bgra8unormSrgb,
         TextureFormatId::rgb9e5ufloat => web_sys::GpuTextureFormat::Rgb9e5ufloat,
         TextureFormatId::rgb10a2unorm => web_sys::GpuTextureFormat::Rgb10a2unorm,
+        TextureFormatId::rgb10a2uint => web_sys::GpuTextureFormat::Rgb10a2uint,
         TextureFormatId::rg11b10ufloat => web_sys::GpuTextureFormat::Rg11b10ufloat,
         TextureFormatId::rg32uint => web_sys::GpuTextureFormat::Rg32uint,
         TextureFormatId::rg32sint => web_sys::GpuTextureFormat::Rg32sint,
@@ -1501,6 +1604,15 @@ pub fn texture_format_to_web_sys(texture_format: &TextureFormatId) -> web_sys::G
         TextureFormatId::rgba16uint => web_sys::GpuTextureFormat::Rgba16uint,
         TextureFormatId::rgba16sint => web_sys::GpuTextureFormat::Rgba16sint,
         TextureFormatId::rgba16float => web_sys::GpuTextureFormat::Rgba16float,
+        #[cfg(feature = "norm16")]
+        TextureFormatId::r16unorm
+        | TextureFormatId::r16snorm
+        | TextureFormatId::rg16unorm
+        | TextureFormatId::rg16snorm
+        | TextureFormatId::rgba16unorm
+        | TextureFormatId::rgba16snorm => {
+            panic!("16-bit normalized texture formats are not supported on the web")
+        }
         TextureFormatId::rgba32uint => web_sys::GpuTextureFormat::Rgba32uint,
         TextureFormatId::rgba32sint => web_sys::GpuTextureFormat::Rgba32sint,
         TextureFormatId::rgba32float => web_sys::GpuTextureFormat::Rgba32float,
@@ -1593,6 +1705,7 @@ pub fn texture_format_to_str(texture_format: &TextureFormatId) -> &str {
         TextureFormatId::bgra8unorm_srgb => "bgra8unorm-srgb",
         TextureFormatId::rgb9e5ufloat => "rgb9e5ufloat",
         TextureFormatId::rgb10a2unorm => "rgb10a2unorm",
+        TextureFormatId::rgb10a2uint => "rgb10a2uint",
         TextureFormatId::rg11b10ufloat => "rg11b10ufloat",
         TextureFormatId::rg32uint => "rg32uint",
         TextureFormatId::rg32sint => "rg32sint",
@@ -1600,6 +1713,15 @@ pub fn texture_format_to_str(texture_format: &TextureFormatId) -> &str {
         TextureFormatId::rgba16uint => "rgba16uint",
         TextureFormatId::rgba16sint => "rgba16sint",
         TextureFormatId::rgba16float => "rgba16float",
+        #[cfg(feature = "norm16")]
+        TextureFormatId::r16unorm
+        | TextureFormatId::r16snorm
+        | TextureFormatId::rg16unorm
+        | TextureFormatId::rg16snorm
+        | TextureFormatId::rgba16unorm
+        | TextureFormatId::rgba16snorm => {
+            panic!("16-bit normalized texture formats are not supported on the web")
+        }
         TextureFormatId::rgba32uint => "rgba32uint",
         TextureFormatId::rgba32sint => "rgba32sint",
         TextureFormatId::rgba32float => "rgba32float",
@@ -2061,8 +2183,13 @@ pub fn render_pipeline_descriptor_to_web_sys(
         depth_stencil_state,
         fragment_state,
         multisample_state,
+        multiview,
     } = descriptor;
 
+    if multiview.is_some() {
+        panic!("multiview rendering is not supported on the web");
+    }
+
     let mut desc = web_sys::GpuRenderPipelineDescriptor::new(
         layout.inner.as_ref(),
         &vertex_state_to_web_sys(vertex_state),
@@ -2173,9 +2300,14 @@ pub fn query_type_to_web_sys(query_type: &QueryType) -> web_sys::GpuQueryType {
     match query_type {
         QueryType::Occlusion => web_sys::GpuQueryType::Occlusion,
         QueryType::Timestamp => web_sys::GpuQueryType::Timestamp,
+        QueryType::PipelineStatistics(_) => unsupported_pipeline_statistics(),
     }
 }
 
+fn unsupported_pipeline_statistics() -> ! {
+    panic!("pipeline statistics queries are not supported on the web")
+}
+
 pub fn features_to_web_sys(features: &FlagSet<Feature>) -> js_sys::Array {
     let array = js_sys::Array::new();
 
@@ -2219,6 +2351,10 @@ pub fn features_to_web_sys(features: &FlagSet<Feature>) -> js_sys::Array {
         array.push(&JsValue::from("bgra8unorm-storage"));
     }
 
+    if features.contains(Feature::Subgroups) {
+        array.push(&JsValue::from("subgroups"));
+    }
+
     array
 }
 
@@ -2274,6 +2410,10 @@ pub fn features_from_web_sys(raw: &GpuSupportedFeatures) -> FlagSet<Feature> {
         features |= Feature::Bgra8UNormStorage;
     }
 
+    if js_sys::Reflect::has(raw.as_ref(), &JsValue::from("subgroups")).unwrap_or(false) {
+        features |= Feature::Subgroups;
+    }
+
     features
 }
 
@@ -2311,9 +2451,21 @@ pub fn limits_from_web_sys(limits: &web_sys::GpuSupportedLimits) -> Limits {
         max_compute_workgroup_size_y: limits.max_compute_workgroup_size_y(),
         max_compute_workgroup_size_z: limits.max_compute_workgroup_size_z(),
         max_compute_workgroups_per_dimension: limits.max_compute_workgroups_per_dimension(),
+        min_subgroup_size: optional_limit(limits, "minSubgroupSize"),
+        max_subgroup_size: optional_limit(limits, "maxSubgroupSize"),
     }
 }
 
+/// Reads a limit that is not part of the core WebGPU specification (and thus not exposed by
+/// `web_sys`); `0` if the browser does not report it.
+fn optional_limit(limits: &web_sys::GpuSupportedLimits, name: &str) -> u32 {
+    js_sys::Reflect::get(limits.as_ref(), &JsValue::from(name))
+        .ok()
+        .and_then(|value| value.as_f64())
+        .map(|value| value as u32)
+        .unwrap_or(0)
+}
+
 #[wasm_bindgen(module = "/src/js_support.js")]
 extern "C" {
     #[wasm_bindgen(js_name = __empa_js_copy_buffer_to_memory)]
@@ -0,0 +1,122 @@
+use std::collections::VecDeque;
+use std::error::Error;
+use std::fmt;
+
+use crate::device::Queue;
+use crate::driver;
+use crate::driver::{Driver, Dvr, Queue as _};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct InvalidFrameLatency(u32);
+
+impl fmt::Display for InvalidFrameLatency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "`{}` is not a valid frame latency; must be in the range `1..={}`",
+            self.0,
+            FrameLatency::MAX
+        )
+    }
+}
+
+impl Error for InvalidFrameLatency {}
+
+/// The maximum number of frames that may be queued up for presentation before the CPU waits for
+/// the GPU to catch up.
+///
+/// Lower values reduce input latency, higher values give the GPU more slack to avoid stalls. The
+/// default is `2`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct FrameLatency(u32);
+
+impl FrameLatency {
+    pub const MAX: u32 = 16;
+
+    pub const ONE: FrameLatency = FrameLatency(1);
+
+    pub const TWO: FrameLatency = FrameLatency(2);
+
+    pub const THREE: FrameLatency = FrameLatency(3);
+
+    pub fn new(frames: u32) -> Result<Self, InvalidFrameLatency> {
+        if frames >= 1 && frames <= Self::MAX {
+            Ok(FrameLatency(frames))
+        } else {
+            Err(InvalidFrameLatency(frames))
+        }
+    }
+
+    pub fn get(&self) -> u32 {
+        self.0
+    }
+}
+
+impl Default for FrameLatency {
+    fn default() -> Self {
+        FrameLatency::TWO
+    }
+}
+
+impl TryFrom<u32> for FrameLatency {
+    type Error = InvalidFrameLatency;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        FrameLatency::new(value)
+    }
+}
+
+type OnSubmittedWorkDone =
+    <<Dvr as Driver>::QueueHandle as driver::Queue<Dvr>>::OnSubmittedWorkDone;
+
+/// Caps the number of frames in flight to a [FrameLatency].
+///
+/// Presentation engines differ in how (and whether) they honor a desired maximum frame latency;
+/// the frame pacer enforces the limit on the CPU side by tracking the completion of submitted work,
+/// so that the behavior is consistent across platforms.
+///
+/// Call [wait](FramePacer::wait) before acquiring the next frame's texture, and call
+/// [frame_submitted](FramePacer::frame_submitted) after submitting the frame's final command
+/// buffer.
+pub struct FramePacer {
+    queue: Queue,
+    frame_latency: FrameLatency,
+    in_flight: VecDeque<OnSubmittedWorkDone>,
+}
+
+impl FramePacer {
+    pub fn new(queue: Queue, frame_latency: FrameLatency) -> Self {
+        FramePacer {
+            queue,
+            frame_latency,
+            in_flight: VecDeque::with_capacity(frame_latency.get() as usize),
+        }
+    }
+
+    pub fn frame_latency(&self) -> FrameLatency {
+        self.frame_latency
+    }
+
+    /// The number of submitted frames for which the GPU work has not yet been observed to complete.
+    pub fn frames_in_flight(&self) -> usize {
+        self.in_flight.len()
+    }
+
+    /// Marks the end of a frame; all work submitted to the queue up to this point is considered part
+    /// of the frame.
+    pub fn frame_submitted(&mut self) {
+        self.in_flight
+            .push_back(self.queue.handle.on_submitted_work_done());
+    }
+
+    /// Waits until fewer than [frame_latency](FramePacer::frame_latency) frames are in flight.
+    pub async fn wait(&mut self) {
+        let max = self.frame_latency.get() as usize;
+
+        while self.in_flight.len() >= max {
+            if let Some(work_done) = self.in_flight.pop_front() {
+                work_done.await;
+            }
+        }
+    }
+}
@@ -0,0 +1,359 @@
+//! Reloads shaders from disk during development, without restarting the application.
+//!
+//! A [ShaderWatcher] watches the WGSL files that pipelines were built from. When a file changes,
+//! the watcher re-parses it, verifies that its interface is compatible with the original shader,
+//! and rebuilds the pipelines that use it. Pipelines are accessed through a [Reloadable] handle;
+//! once a rebuilt pipeline is ready, it replaces the previous pipeline behind that handle.
+//!
+//! ```ignore
+//! const SHADER: ShaderSource = shader_source!("shader.wgsl");
+//!
+//! let mut watcher = ShaderWatcher::new(&device);
+//!
+//! let pipeline = watcher
+//!     .watch("src/shader.wgsl", &SHADER, move |shader| {
+//!         device.create_compute_pipeline(
+//!             &ComputePipelineDescriptorBuilder::begin()
+//!                 .layout(&pipeline_layout)
+//!                 .compute(ComputeStageBuilder::begin(shader, "main").finish())
+//!                 .finish(),
+//!         )
+//!     })
+//!     .await?;
+//!
+//! loop {
+//!     for error in watcher.poll() {
+//!         eprintln!("{}", error);
+//!     }
+//!
+//!     encoder = encoder
+//!         .begin_compute_pass()
+//!         .set_pipeline(&*pipeline.get())
+//!         ...
+//! }
+//! ```
+use std::cell::RefCell;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::future::Future;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::SystemTime;
+
+use empa_reflect::ShaderSource as DynamicShaderSource;
+use futures::task::noop_waker_ref;
+
+use crate::device::{Device, PipelineCreationError};
+use crate::shader_module::{
+    constant_identifier, ParseError, ShaderModule, ShaderSource, ShaderSourceInternal,
+};
+
+/// A handle to a pipeline that may be replaced by a [ShaderWatcher] when its shader is reloaded.
+///
+/// Obtain the current pipeline with [get](Reloadable::get) each time it is used to record
+/// commands; a pipeline obtained earlier remains valid, but will not reflect later reloads.
+pub struct Reloadable<P> {
+    current: Rc<RefCell<Rc<P>>>,
+}
+
+impl<P> Reloadable<P> {
+    /// Returns the most recently built pipeline.
+    pub fn get(&self) -> Rc<P> {
+        self.current.borrow().clone()
+    }
+
+    fn replace(&self, pipeline: P) {
+        *self.current.borrow_mut() = Rc::new(pipeline);
+    }
+}
+
+impl<P> Clone for Reloadable<P> {
+    fn clone(&self) -> Self {
+        Reloadable {
+            current: self.current.clone(),
+        }
+    }
+}
+
+type PendingRebuild = Pin<Box<dyn Future<Output = Result<(), PipelineCreationError>>>>;
+
+struct Watch {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    meta: ShaderSourceInternal,
+    reflection: Arc<DynamicShaderSource>,
+    rebuild: Box<dyn Fn(&ShaderModule) -> PendingRebuild>,
+    pending: Option<PendingRebuild>,
+}
+
+impl Watch {
+    fn reload(&mut self, device: &Device) -> Result<(), ShaderReloadErrorKind> {
+        let raw = fs::read_to_string(&self.path).map_err(ShaderReloadErrorKind::Io)?;
+        let source = ShaderSource::parse(raw).map_err(ShaderReloadErrorKind::Parse)?;
+
+        let reloaded = match &source.inner {
+            ShaderSourceInternal::Dynamic(reloaded) => reloaded,
+            _ => unreachable!(),
+        };
+
+        check_compatibility(&self.reflection, reloaded)
+            .map_err(ShaderReloadErrorKind::Incompatible)?;
+
+        // The reloaded module keeps the meta data of the original shader, so that pipeline
+        // descriptors are validated exactly as they were for the original pipeline.
+        let module = ShaderModule::with_meta(device, reloaded.raw_str(), self.meta.clone());
+
+        // A newer version of the shader supersedes a rebuild that is still in progress.
+        self.pending = Some((self.rebuild)(&module));
+
+        Ok(())
+    }
+}
+
+/// Watches WGSL files and rebuilds the pipelines that use them when they change.
+///
+/// The watcher does not spawn any threads: file changes are detected and rebuilds progress only
+/// when [poll](ShaderWatcher::poll) is called, typically once per frame.
+pub struct ShaderWatcher {
+    device: Device,
+    watches: Vec<Watch>,
+}
+
+impl ShaderWatcher {
+    pub fn new(device: &Device) -> Self {
+        ShaderWatcher {
+            device: device.clone(),
+            watches: Vec::new(),
+        }
+    }
+
+    /// Builds a pipeline for the given `source` with the `build` function, and rebuilds it
+    /// whenever the WGSL file at the given `path` changes.
+    ///
+    /// The `source` should be the source that was loaded from the file at `path` (e.g. with
+    /// [shader_source](crate::shader_module::shader_source)). A reloaded shader is only used if
+    /// its interface is compatible with the `source`: it may only use resource bindings that
+    /// `source` also declares, with the same binding types, and each entry point of the `source`
+    /// must still exist with the same stage, inputs, outputs and workgroup size.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `source` is an [unparsed](ShaderSource::unparsed) source.
+    pub async fn watch<P, F, Fut>(
+        &mut self,
+        path: impl AsRef<Path>,
+        source: &ShaderSource,
+        build: F,
+    ) -> Result<Reloadable<P>, PipelineCreationError>
+    where
+        P: 'static,
+        F: Fn(&ShaderModule) -> Fut + 'static,
+        Fut: Future<Output = Result<P, PipelineCreationError>> + 'static,
+    {
+        let path = path.as_ref().to_path_buf();
+        let meta = source.inner.clone();
+        let reflection = reflect(&meta);
+        let modified = modified_time(&path);
+
+        let module = self.device.create_shader_module(source);
+        let pipeline = build(&module).await?;
+
+        let reloadable = Reloadable {
+            current: Rc::new(RefCell::new(Rc::new(pipeline))),
+        };
+
+        let target = reloadable.clone();
+
+        let rebuild = Box::new(move |module: &ShaderModule| {
+            let target = target.clone();
+            let future = build(module);
+
+            Box::pin(async move {
+                target.replace(future.await?);
+
+                Ok(())
+            }) as PendingRebuild
+        });
+
+        self.watches.push(Watch {
+            path,
+            modified,
+            meta,
+            reflection,
+            rebuild,
+            pending: None,
+        });
+
+        Ok(reloadable)
+    }
+
+    /// Checks the watched files for changes, starts rebuilding the pipelines for any changed
+    /// files, and swaps in rebuilt pipelines that have finished.
+    ///
+    /// Returns the errors that occurred while reloading; the previous pipeline remains in use for
+    /// any shader that failed to reload.
+    pub fn poll(&mut self) -> Vec<ShaderReloadError> {
+        let mut errors = Vec::new();
+        let mut cx = Context::from_waker(noop_waker_ref());
+
+        for watch in &mut self.watches {
+            // Editors may briefly remove a file while saving it; only act once it is readable.
+            if let Some(modified) = modified_time(&watch.path) {
+                if watch.modified != Some(modified) {
+                    watch.modified = Some(modified);
+
+                    if let Err(kind) = watch.reload(&self.device) {
+                        errors.push(ShaderReloadError {
+                            path: watch.path.clone(),
+                            kind,
+                        });
+                    }
+                }
+            }
+
+            if let Some(pending) = &mut watch.pending {
+                if let Poll::Ready(result) = pending.as_mut().poll(&mut cx) {
+                    watch.pending = None;
+
+                    if let Err(err) = result {
+                        errors.push(ShaderReloadError {
+                            path: watch.path.clone(),
+                            kind: ShaderReloadErrorKind::PipelineCreation(err),
+                        });
+                    }
+                }
+            }
+        }
+
+        errors
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+fn reflect(source: &ShaderSourceInternal) -> Arc<DynamicShaderSource> {
+    match source {
+        ShaderSourceInternal::Static(source) => Arc::new(
+            DynamicShaderSource::parse(source.source.to_string())
+                .expect("a static shader source should be valid"),
+        ),
+        ShaderSourceInternal::Dynamic(source) => source.clone(),
+        ShaderSourceInternal::Unparsed(_) => panic!("cannot hot-reload an unparsed shader source"),
+    }
+}
+
+fn check_compatibility(
+    original: &DynamicShaderSource,
+    reloaded: &DynamicShaderSource,
+) -> Result<(), String> {
+    for binding in reloaded.resource_bindings() {
+        if !original.resource_bindings().contains(binding) {
+            return Err(format!(
+                "binding `{}` in group `{}` does not match a binding in the original shader",
+                binding.binding(),
+                binding.group()
+            ));
+        }
+    }
+
+    for entry_point in original.entry_points() {
+        let reloaded_entry_point = if let Some(entry_point) = reloaded
+            .entry_points()
+            .iter()
+            .find(|e| e.name() == entry_point.name())
+        {
+            entry_point
+        } else {
+            return Err(format!("entry point `{}` is missing", entry_point.name()));
+        };
+
+        if reloaded_entry_point.stage() != entry_point.stage()
+            || reloaded_entry_point.input_bindings() != entry_point.input_bindings()
+            || reloaded_entry_point.output_bindings() != entry_point.output_bindings()
+            || reloaded_entry_point.workgroup_size() != entry_point.workgroup_size()
+        {
+            return Err(format!(
+                "the interface of entry point `{}` does not match the original shader",
+                entry_point.name()
+            ));
+        }
+    }
+
+    for constant in reloaded.constants() {
+        if constant.required() && !original.constants().contains(constant) {
+            return Err(format!(
+                "required pipeline constant `{}` is not declared by the original shader",
+                constant_identifier(constant.identifier())
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// The reason a [ShaderWatcher] failed to reload a shader.
+#[derive(Debug)]
+pub enum ShaderReloadErrorKind {
+    /// The shader file could not be read.
+    Io(io::Error),
+
+    /// The shader file does not contain valid WGSL.
+    Parse(ParseError),
+
+    /// The interface of the reloaded shader is not compatible with the original shader.
+    Incompatible(String),
+
+    /// The device failed to create a pipeline for the reloaded shader.
+    PipelineCreation(PipelineCreationError),
+}
+
+impl fmt::Display for ShaderReloadErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShaderReloadErrorKind::Io(err) => write!(f, "could not read the file: {}", err),
+            ShaderReloadErrorKind::Parse(err) => write!(f, "could not parse the file: {}", err),
+            ShaderReloadErrorKind::Incompatible(reason) => {
+                write!(f, "incompatible with the original shader: {}", reason)
+            }
+            ShaderReloadErrorKind::PipelineCreation(err) => fmt::Display::fmt(err, f),
+        }
+    }
+}
+
+/// Signals that a [ShaderWatcher] failed to reload the shader at [path](ShaderReloadError::path).
+#[derive(Debug)]
+pub struct ShaderReloadError {
+    path: PathBuf,
+    kind: ShaderReloadErrorKind,
+}
+
+impl ShaderReloadError {
+    /// The path of the shader file that failed to reload.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn kind(&self) -> &ShaderReloadErrorKind {
+        &self.kind
+    }
+}
+
+impl fmt::Display for ShaderReloadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "failed to reload shader `{}`: {}",
+            self.path.display(),
+            self.kind
+        )
+    }
+}
+
+impl Error for ShaderReloadError {}
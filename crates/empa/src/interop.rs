@@ -0,0 +1,171 @@
+//! Escape hatches for integrating with other code that uses the same device.
+//!
+//! On the web, the underlying `web_sys` objects are exposed. On native targets, the `wgpu-core`
+//! IDs are exposed together with the [Global](wgc::global::Global) that owns them; the `wgc`
+//! (`wgpu-core`) and `wgt` (`wgpu-types`) crates are re-exported so that the IDs can be used with
+//! matching versions.
+
+#[cfg(not(feature = "web"))]
+mod native {
+    use std::sync::Arc;
+
+    use wgc::global::Global;
+    use wgc::id::{BufferId, DeviceId, QueueId, TextureId};
+
+    use crate::buffer::Buffer;
+    use crate::device::{Device, Queue};
+    use crate::texture::{Texture1D, Texture2D, Texture3D, TextureMultisampled2D};
+
+    pub use wgc;
+    pub use wgt;
+
+    /// The raw `wgpu-core` IDs for a [Device] and its primary queue.
+    pub struct RawDeviceIds {
+        pub global: Arc<Global>,
+        pub device: DeviceId,
+        pub queue: QueueId,
+    }
+
+    impl Device {
+        /// Returns the raw `wgpu-core` IDs for this device.
+        ///
+        /// # Safety
+        ///
+        /// The IDs must not be used to drop or destroy the device, or any resources created by empa.
+        /// Resources created with the IDs are not tracked by empa; commands that use them must not
+        /// invalidate the state empa's typed wrappers rely on (e.g. unmapping a buffer empa has
+        /// mapped).
+        pub unsafe fn raw_ids(&self) -> RawDeviceIds {
+            RawDeviceIds {
+                global: self.device_handle().global().clone(),
+                device: self.device_handle().id(),
+                queue: self.primary_queue_handle().id(),
+            }
+        }
+    }
+
+    impl Queue {
+        /// Returns the raw `wgpu-core` ID for this queue.
+        ///
+        /// # Safety
+        ///
+        /// See [Device::raw_ids].
+        pub unsafe fn raw_id(&self) -> QueueId {
+            self.handle.id()
+        }
+    }
+
+    impl<T, U> Buffer<T, U>
+    where
+        T: ?Sized,
+    {
+        /// Returns the raw `wgpu-core` ID for this buffer.
+        ///
+        /// # Safety
+        ///
+        /// The ID must not be used to drop, destroy, map or unmap the buffer. See also
+        /// [Device::raw_ids].
+        pub unsafe fn raw_id(&self) -> BufferId {
+            self.internal.handle.id()
+        }
+    }
+
+    impl<F, U> Texture1D<F, U> {
+        /// Returns the raw `wgpu-core` ID for this texture.
+        ///
+        /// # Safety
+        ///
+        /// The ID must not be used to drop or destroy the texture. See also [Device::raw_ids].
+        pub unsafe fn raw_id(&self) -> TextureId {
+            self.handle.id()
+        }
+    }
+
+    impl<F, U> Texture2D<F, U> {
+        /// Returns the raw `wgpu-core` ID for this texture.
+        ///
+        /// # Safety
+        ///
+        /// The ID must not be used to drop or destroy the texture. See also [Device::raw_ids].
+        pub unsafe fn raw_id(&self) -> TextureId {
+            self.handle.id()
+        }
+    }
+
+    impl<F, U> Texture3D<F, U> {
+        /// Returns the raw `wgpu-core` ID for this texture.
+        ///
+        /// # Safety
+        ///
+        /// The ID must not be used to drop or destroy the texture. See also [Device::raw_ids].
+        pub unsafe fn raw_id(&self) -> TextureId {
+            self.handle.id()
+        }
+    }
+
+    impl<F, U, const SAMPLES: u8> TextureMultisampled2D<F, U, SAMPLES> {
+        /// Returns the raw `wgpu-core` ID for this texture.
+        ///
+        /// # Safety
+        ///
+        /// The ID must not be used to drop or destroy the texture. See also [Device::raw_ids].
+        pub unsafe fn raw_id(&self) -> TextureId {
+            self.handle.id()
+        }
+    }
+}
+
+#[cfg(not(feature = "web"))]
+pub use self::native::*;
+
+#[cfg(feature = "web")]
+mod web {
+    use crate::buffer::Buffer;
+    use crate::device::{Device, Queue};
+    use crate::texture::{Texture1D, Texture2D, Texture3D, TextureMultisampled2D};
+
+    impl Device {
+        pub fn as_web_sys(&self) -> &web_sys::GpuDevice {
+            &self.device_handle().inner
+        }
+    }
+
+    impl Queue {
+        pub fn as_web_sys(&self) -> &web_sys::GpuQueue {
+            &self.handle.inner
+        }
+    }
+
+    impl<T, U> Buffer<T, U>
+    where
+        T: ?Sized,
+    {
+        pub fn as_web_sys(&self) -> &web_sys::GpuBuffer {
+            &self.internal.handle.inner
+        }
+    }
+
+    impl<F, U> Texture1D<F, U> {
+        pub fn as_web_sys(&self) -> &web_sys::GpuTexture {
+            &self.handle.inner
+        }
+    }
+
+    impl<F, U> Texture2D<F, U> {
+        pub fn as_web_sys(&self) -> &web_sys::GpuTexture {
+            &self.handle.inner
+        }
+    }
+
+    impl<F, U> Texture3D<F, U> {
+        pub fn as_web_sys(&self) -> &web_sys::GpuTexture {
+            &self.handle.inner
+        }
+    }
+
+    impl<F, U, const SAMPLES: u8> TextureMultisampled2D<F, U, SAMPLES> {
+        pub fn as_web_sys(&self) -> &web_sys::GpuTexture {
+            &self.handle.inner
+        }
+    }
+}
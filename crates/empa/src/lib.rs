@@ -1,4 +1,12 @@
-#![feature(new_uninit)]
+// The driver layer always includes the render and compute plumbing; without the `render` or
+// `compute` feature, parts of it are not reachable from the public API.
+#![cfg_attr(
+    not(all(feature = "render", feature = "compute")),
+    allow(dead_code, unused_imports)
+)]
+
+// Allows the `empa` derive and proc-macros to be used inside this crate.
+extern crate self as empa;
 
 mod driver;
 
@@ -8,28 +16,63 @@ pub use compare_function::CompareFunction;
 pub mod abi;
 pub mod access_mode;
 pub mod adapter;
+pub mod app;
 pub mod buffer;
 pub mod command;
+#[cfg(feature = "compute")]
 pub mod compute_pipeline;
+#[cfg(not(feature = "compute"))]
+mod compute_pipeline;
 pub mod device;
+pub mod frame_pacing;
+#[cfg(all(
+    feature = "hot_reload",
+    feature = "compute",
+    not(any(feature = "web", feature = "noop"))
+))]
+pub mod hot_reload;
+#[cfg(all(feature = "interop", not(any(feature = "noop", feature = "trace"))))]
+pub mod interop;
+#[cfg(feature = "render")]
+pub mod material;
+pub mod memory;
 pub mod pipeline_constants;
+pub mod pool;
 pub mod query;
+#[cfg(feature = "render")]
 pub mod render_pipeline;
+#[cfg(not(feature = "render"))]
+mod render_pipeline;
+#[cfg(feature = "render")]
 pub mod render_target;
+#[cfg(not(feature = "render"))]
+mod render_target;
 pub mod resource_binding;
 pub mod sampler;
 pub mod shader_module;
 pub mod texture;
 pub mod type_flag;
+#[cfg(feature = "usage_validation")]
+mod usage_validation;
+pub mod util;
 
-#[cfg(all(feature = "web", feature = "arwa"))]
+#[cfg(all(feature = "web", feature = "arwa", not(feature = "noop")))]
 pub mod arwa;
 
-#[cfg(not(feature = "web"))]
+#[cfg(not(any(feature = "web", feature = "noop")))]
 pub mod native;
 
+#[cfg(feature = "noop")]
+pub mod noop;
+
+#[cfg(all(feature = "trace", not(any(feature = "web", feature = "noop"))))]
+pub mod trace;
+
+#[cfg(not(feature = "web"))]
+pub mod testing;
+
 #[doc(hidden)]
 pub struct Untyped {}
 
 #[doc(hidden)]
-pub use memoffset::offset_of;
+pub use core::mem::offset_of;
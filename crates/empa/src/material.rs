@@ -0,0 +1,226 @@
+//! Materials: bundles of a shader module, pipeline constants and bound resources.
+//!
+//! A [Material] combines a reference to a [ShaderModule], a pipeline constants value and a bind
+//! group created from a [Resources] instance. Materials that share a resources layout are rendered
+//! with [MaterialPipelines], which creates a render pipeline for each combination of a material and
+//! a render layout the first time that combination is used, and caches it for subsequent uses:
+//!
+//! ```ignore
+//! let mut pipelines = MaterialPipelines::new(&device, &bind_group_layout);
+//!
+//! let material = Material::new(&device, shader.clone(), constants, &bind_group_layout, resources);
+//!
+//! encoder = pipelines
+//!     .bind(encoder, &material, |cx| {
+//!         RenderPipelineDescriptorBuilder::begin()
+//!             .layout(cx.pipeline_layout)
+//!             .vertex(VertexStageBuilder::begin(cx.shader_module, "vert_main")
+//!                 .pipeline_constants(cx.constants)
+//!                 .vertex_layout::<MyVertex>()
+//!                 .finish())
+//!             .fragment(FragmentStageBuilder::begin(cx.shader_module, "frag_main")
+//!                 .pipeline_constants(cx.constants)
+//!                 .color_outputs(ColorOutput {
+//!                     format: rgba8unorm,
+//!                     write_mask: ColorWrite::All,
+//!                 })
+//!                 .finish())
+//!             .finish()
+//!     })
+//!     .set_vertex_buffers(&vertices)
+//!     .draw(Draw { ... });
+//! ```
+
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::marker;
+use std::sync::Arc;
+
+use atomic_counter::AtomicCounter;
+
+use crate::command::{RenderStateEncoder, ResourceBindingCommandEncoder};
+use crate::device::{Device, ID_GEN};
+use crate::render_pipeline::{RenderPipeline, RenderPipelineDescriptor};
+use crate::render_target::RenderLayoutCompatible;
+use crate::resource_binding::{
+    BindGroup, BindGroupLayout, PipelineLayout, Resources, TypedBindGroupLayout,
+};
+use crate::shader_module::ShaderModule;
+
+/// A shader module, a pipeline constants value of type `C`, and a bind group with layout `L`.
+///
+/// The bind group is bound to group `0` of the pipelines created for the material by
+/// [MaterialPipelines].
+pub struct Material<C, L> {
+    id: usize,
+    shader_module: Arc<ShaderModule>,
+    constants: C,
+    bind_group: BindGroup<L>,
+}
+
+impl<C, L> Material<C, L>
+where
+    L: TypedBindGroupLayout,
+{
+    /// Creates a new material for the `shader_module` and `constants`, that binds the given
+    /// `resources`.
+    pub fn new<R>(
+        device: &Device,
+        shader_module: Arc<ShaderModule>,
+        constants: C,
+        layout: &BindGroupLayout<L>,
+        resources: R,
+    ) -> Self
+    where
+        R: Resources<Layout = L>,
+    {
+        Material {
+            id: ID_GEN.get(),
+            shader_module,
+            constants,
+            bind_group: device.create_bind_group(layout, resources),
+        }
+    }
+
+    /// Replaces the resources bound by this material.
+    ///
+    /// The pipelines cached for this material remain valid, as they do not depend on the
+    /// resources.
+    pub fn set_resources<R>(&mut self, device: &Device, layout: &BindGroupLayout<L>, resources: R)
+    where
+        R: Resources<Layout = L>,
+    {
+        self.bind_group = device.create_bind_group(layout, resources);
+    }
+}
+
+impl<C, L> Material<C, L> {
+    /// The shader module used by this material's pipelines.
+    pub fn shader_module(&self) -> &ShaderModule {
+        &self.shader_module
+    }
+
+    /// The pipeline constants used by this material's pipelines.
+    pub fn constants(&self) -> &C {
+        &self.constants
+    }
+
+    /// The bind group for this material's resources.
+    pub fn bind_group(&self) -> &BindGroup<L> {
+        &self.bind_group
+    }
+}
+
+type WithMaterial<'a, E, RT, T, V, I, L> = <<E as RenderStateEncoder<RT>>::WithPipeline<
+    RenderPipeline<T, V, I, (L,)>,
+> as ResourceBindingCommandEncoder>::WithResources<&'a BindGroup<L>>;
+
+/// The inputs for building the render pipeline for a [Material], see [MaterialPipelines::bind].
+pub struct MaterialPipelineContext<'a, C, L> {
+    /// The material's shader module.
+    pub shader_module: &'a ShaderModule,
+
+    /// The material's pipeline constants.
+    pub constants: &'a C,
+
+    /// The pipeline layout the pipeline must use.
+    pub pipeline_layout: &'a PipelineLayout<(L,)>,
+}
+
+/// Creates and caches render pipelines for [Material]s with resources layout `L`, that render
+/// vertices of type `V` with indices of type `I`.
+///
+/// A pipeline is created for each combination of a material and the render layout of the
+/// pipeline, the first time that combination is [bound](MaterialPipelines::bind).
+pub struct MaterialPipelines<C, L, V, I> {
+    device: Device,
+    pipeline_layout: PipelineLayout<(L,)>,
+    pipelines: HashMap<(usize, TypeId), Box<dyn Any>>,
+    _marker: marker::PhantomData<*const (C, V, I)>,
+}
+
+impl<C, L, V, I> MaterialPipelines<C, L, V, I>
+where
+    L: TypedBindGroupLayout + 'static,
+    V: 'static,
+    I: 'static,
+{
+    /// Creates a new pipeline cache for materials with the given `bind_group_layout`.
+    pub fn new(device: &Device, bind_group_layout: &BindGroupLayout<L>) -> Self {
+        MaterialPipelines {
+            device: device.clone(),
+            pipeline_layout: device.create_pipeline_layout(bind_group_layout),
+            pipelines: HashMap::new(),
+            _marker: Default::default(),
+        }
+    }
+
+    /// The pipeline layout used by all pipelines in this cache.
+    pub fn pipeline_layout(&self) -> &PipelineLayout<(L,)> {
+        &self.pipeline_layout
+    }
+
+    /// Returns the pipeline with render layout `T` for the `material`.
+    ///
+    /// If no such pipeline is cached yet, `build` is called to create the descriptor for the
+    /// pipeline, and the pipeline is created and cached.
+    ///
+    /// # Panics
+    ///
+    /// Panics if creating the pipeline fails.
+    pub fn pipeline<T, F>(
+        &mut self,
+        material: &Material<C, L>,
+        build: F,
+    ) -> &RenderPipeline<T, V, I, (L,)>
+    where
+        T: 'static,
+        F: FnOnce(MaterialPipelineContext<C, L>) -> RenderPipelineDescriptor<T, V, I, (L,)>,
+    {
+        let key = (material.id, TypeId::of::<T>());
+
+        let pipeline = self.pipelines.entry(key).or_insert_with(|| {
+            let descriptor = build(MaterialPipelineContext {
+                shader_module: &material.shader_module,
+                constants: &material.constants,
+                pipeline_layout: &self.pipeline_layout,
+            });
+
+            let pipeline = self
+                .device
+                .create_render_pipeline_sync(&descriptor)
+                .expect("failed to create the pipeline for the material");
+
+            Box::new(pipeline)
+        });
+
+        pipeline.downcast_ref().unwrap()
+    }
+
+    /// Sets the pipeline and bind group for the `material` on the `encoder`.
+    ///
+    /// The pipeline is obtained as with [pipeline](MaterialPipelines::pipeline).
+    pub fn bind<'a, E, RT, T, F>(
+        &mut self,
+        encoder: E,
+        material: &'a Material<C, L>,
+        build: F,
+    ) -> WithMaterial<'a, E, RT, T, V, I, L>
+    where
+        E: RenderStateEncoder<RT>,
+        E::WithPipeline<RenderPipeline<T, V, I, (L,)>>: ResourceBindingCommandEncoder,
+        T: RenderLayoutCompatible<RT> + 'static,
+        F: FnOnce(MaterialPipelineContext<C, L>) -> RenderPipelineDescriptor<T, V, I, (L,)>,
+    {
+        let pipeline = self.pipeline(material, build);
+
+        encoder
+            .set_pipeline(pipeline)
+            .set_bind_groups(&material.bind_group)
+    }
+
+    /// Removes the pipelines cached for the `material`, e.g. when the material is no longer used.
+    pub fn evict(&mut self, material: &Material<C, L>) {
+        self.pipelines.retain(|(id, _), _| *id != material.id);
+    }
+}
@@ -0,0 +1,223 @@
+//! Memory usage reporting.
+//!
+//! See [Device::report_memory_usage](crate::device::Device::report_memory_usage).
+
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use flagset::FlagSet;
+
+use crate::driver::{BufferUsage, TextureUsage};
+
+/// Totals (in bytes) of memory allocated for buffers, grouped by usage flag.
+///
+/// A buffer counts towards every usage flag it was created with, so the categories overlap.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub struct BufferUsageTotals {
+    pub map_read: u64,
+    pub map_write: u64,
+    pub copy_src: u64,
+    pub copy_dst: u64,
+    pub index: u64,
+    pub vertex: u64,
+    pub uniform: u64,
+    pub storage: u64,
+    pub indirect: u64,
+    pub query_resolve: u64,
+}
+
+/// Totals (in bytes) of memory allocated for textures, grouped by usage flag.
+///
+/// A texture counts towards every usage flag it was created with, so the categories overlap.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub struct TextureUsageTotals {
+    pub copy_src: u64,
+    pub copy_dst: u64,
+    pub texture_binding: u64,
+    pub storage_binding: u64,
+    pub render_attachment: u64,
+}
+
+/// A snapshot of the memory allocated for the resources created with a device.
+///
+/// Sizes are those requested by empa's typed layer; texture sizes are estimated from the format,
+/// size, mipmap levels and sample count. The actual amount of memory the driver allocates may be
+/// larger due to alignment and padding.
+#[derive(Debug)]
+pub struct MemoryReport {
+    /// The number of live buffers.
+    pub buffer_count: usize,
+
+    /// The total size in bytes of all live buffers.
+    pub buffer_bytes: u64,
+
+    /// The buffer totals grouped by usage flag.
+    pub buffer_bytes_by_usage: BufferUsageTotals,
+
+    /// The number of live textures.
+    pub texture_count: usize,
+
+    /// The estimated total size in bytes of all live textures.
+    pub texture_bytes: u64,
+
+    /// The texture totals grouped by usage flag.
+    pub texture_bytes_by_usage: TextureUsageTotals,
+
+    /// The internal resource report generated by `wgpu-core`.
+    #[cfg(not(any(feature = "web", feature = "noop")))]
+    pub native_report: wgc::global::GlobalReport,
+}
+
+const BUFFER_USAGES: [BufferUsage; 10] = [
+    BufferUsage::MapRead,
+    BufferUsage::MapWrite,
+    BufferUsage::CopySrc,
+    BufferUsage::CopyDst,
+    BufferUsage::Index,
+    BufferUsage::Vertex,
+    BufferUsage::Uniform,
+    BufferUsage::Storage,
+    BufferUsage::Indirect,
+    BufferUsage::QueryResolve,
+];
+
+const TEXTURE_USAGES: [TextureUsage; 5] = [
+    TextureUsage::CopySrc,
+    TextureUsage::CopyDst,
+    TextureUsage::TextureBinding,
+    TextureUsage::StorageBinding,
+    TextureUsage::RenderAttachment,
+];
+
+#[derive(Default)]
+pub(crate) struct MemoryTracker {
+    buffer_count: AtomicUsize,
+    buffer_bytes: AtomicU64,
+    buffer_bytes_by_usage: [AtomicU64; 10],
+    texture_count: AtomicUsize,
+    texture_bytes: AtomicU64,
+    texture_bytes_by_usage: [AtomicU64; 5],
+}
+
+impl MemoryTracker {
+    pub(crate) fn track_buffer(
+        self: &Arc<Self>,
+        size: u64,
+        usage: FlagSet<BufferUsage>,
+    ) -> MemoryAllocation {
+        self.buffer_count.fetch_add(1, Ordering::Relaxed);
+        self.buffer_bytes.fetch_add(size, Ordering::Relaxed);
+
+        for (i, flag) in BUFFER_USAGES.iter().enumerate() {
+            if usage.contains(*flag) {
+                self.buffer_bytes_by_usage[i].fetch_add(size, Ordering::Relaxed);
+            }
+        }
+
+        MemoryAllocation {
+            tracker: self.clone(),
+            kind: AllocationKind::Buffer(usage),
+            size,
+        }
+    }
+
+    pub(crate) fn track_texture(
+        self: &Arc<Self>,
+        size: u64,
+        usage: FlagSet<TextureUsage>,
+    ) -> MemoryAllocation {
+        self.texture_count.fetch_add(1, Ordering::Relaxed);
+        self.texture_bytes.fetch_add(size, Ordering::Relaxed);
+
+        for (i, flag) in TEXTURE_USAGES.iter().enumerate() {
+            if usage.contains(*flag) {
+                self.texture_bytes_by_usage[i].fetch_add(size, Ordering::Relaxed);
+            }
+        }
+
+        MemoryAllocation {
+            tracker: self.clone(),
+            kind: AllocationKind::Texture(usage),
+            size,
+        }
+    }
+
+    pub(crate) fn buffer_totals(&self) -> (usize, u64, BufferUsageTotals) {
+        let by_usage = |i: usize| self.buffer_bytes_by_usage[i].load(Ordering::Relaxed);
+
+        (
+            self.buffer_count.load(Ordering::Relaxed),
+            self.buffer_bytes.load(Ordering::Relaxed),
+            BufferUsageTotals {
+                map_read: by_usage(0),
+                map_write: by_usage(1),
+                copy_src: by_usage(2),
+                copy_dst: by_usage(3),
+                index: by_usage(4),
+                vertex: by_usage(5),
+                uniform: by_usage(6),
+                storage: by_usage(7),
+                indirect: by_usage(8),
+                query_resolve: by_usage(9),
+            },
+        )
+    }
+
+    pub(crate) fn texture_totals(&self) -> (usize, u64, TextureUsageTotals) {
+        let by_usage = |i: usize| self.texture_bytes_by_usage[i].load(Ordering::Relaxed);
+
+        (
+            self.texture_count.load(Ordering::Relaxed),
+            self.texture_bytes.load(Ordering::Relaxed),
+            TextureUsageTotals {
+                copy_src: by_usage(0),
+                copy_dst: by_usage(1),
+                texture_binding: by_usage(2),
+                storage_binding: by_usage(3),
+                render_attachment: by_usage(4),
+            },
+        )
+    }
+}
+
+enum AllocationKind {
+    Buffer(FlagSet<BufferUsage>),
+    Texture(FlagSet<TextureUsage>),
+}
+
+/// Removes a resource's memory from the device's totals when dropped.
+pub(crate) struct MemoryAllocation {
+    tracker: Arc<MemoryTracker>,
+    kind: AllocationKind,
+    size: u64,
+}
+
+impl Drop for MemoryAllocation {
+    fn drop(&mut self) {
+        let tracker = &self.tracker;
+        let size = self.size;
+
+        match self.kind {
+            AllocationKind::Buffer(usage) => {
+                tracker.buffer_count.fetch_sub(1, Ordering::Relaxed);
+                tracker.buffer_bytes.fetch_sub(size, Ordering::Relaxed);
+
+                for (i, flag) in BUFFER_USAGES.iter().enumerate() {
+                    if usage.contains(*flag) {
+                        tracker.buffer_bytes_by_usage[i].fetch_sub(size, Ordering::Relaxed);
+                    }
+                }
+            }
+            AllocationKind::Texture(usage) => {
+                tracker.texture_count.fetch_sub(1, Ordering::Relaxed);
+                tracker.texture_bytes.fetch_sub(size, Ordering::Relaxed);
+
+                for (i, flag) in TEXTURE_USAGES.iter().enumerate() {
+                    if usage.contains(*flag) {
+                        tracker.texture_bytes_by_usage[i].fetch_sub(size, Ordering::Relaxed);
+                    }
+                }
+            }
+        }
+    }
+}
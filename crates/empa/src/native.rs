@@ -1,11 +1,13 @@
 use std::fmt::Debug;
+use std::future::Future;
 use std::ops::Deref;
 use std::path::PathBuf;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::{error, fmt};
 
 use arrayvec::ArrayVec;
 use flagset::{flags, FlagSet};
+use futures::channel::oneshot;
 use raw_window_handle::{
     HandleError, HasDisplayHandle, HasWindowHandle, RawDisplayHandle, RawWindowHandle,
 };
@@ -16,11 +18,15 @@ use wgc::present::SurfaceOutput;
 use wgt::SurfaceStatus;
 
 use crate::adapter::Adapter;
+use crate::buffer::{Buffer, BufferUsages};
 use crate::device::Device;
 use crate::driver::native::{texture_format_to_wgc, texture_usage_to_wgc};
-use crate::texture::format::{TextureFormat, TextureFormatId, ViewFormats};
-use crate::texture::Texture2D;
-use crate::{driver, texture};
+use crate::frame_pacing::{FrameLatency, FramePacer};
+use crate::texture::format::{
+    ImageCopyToBufferFormat, ImageData, TextureFormat, TextureFormatId, ViewFormats,
+};
+use crate::texture::{ImageCopySize3D, ImageDataLayout, Texture2D};
+use crate::{buffer, driver, texture};
 
 flags! {
     pub enum Backend: u32 {
@@ -51,10 +57,19 @@ pub enum Dx12ShaderCompiler {
     },
 }
 
+/// Configures the backends and debugging flags for an [Instance].
+///
+/// ```ignore
+/// let instance = Instance::new(InstanceDescriptor {
+///     backends: Backend::Vulkan.into(),
+///     flags: InstanceFlag::Debug | InstanceFlag::Validation,
+///     ..Default::default()
+/// });
+/// ```
 pub struct InstanceDescriptor<B, F> {
     pub backends: B,
     pub flags: F,
-    pub dx12_shader_comiler: Dx12ShaderCompiler,
+    pub dx12_shader_compiler: Dx12ShaderCompiler,
 }
 
 impl Default for InstanceDescriptor<FlagSet<Backend>, FlagSet<InstanceFlag>> {
@@ -62,9 +77,67 @@ impl Default for InstanceDescriptor<FlagSet<Backend>, FlagSet<InstanceFlag>> {
         InstanceDescriptor {
             backends: Backend::All.into(),
             flags: InstanceFlag::None.into(),
-            dx12_shader_comiler: Default::default(),
+            dx12_shader_compiler: Default::default(),
+        }
+    }
+}
+
+/// Typed debugging and validation options that map onto [InstanceFlag]s.
+///
+/// Backend validation can be expensive; [DebugOptions::debug_builds] enables it only when
+/// `debug_assertions` are enabled:
+///
+/// ```ignore
+/// let instance = Instance::new(InstanceDescriptor {
+///     flags: DebugOptions::debug_builds().to_instance_flags(),
+///     ..Default::default()
+/// });
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct DebugOptions {
+    /// Enables the backend's validation layers (e.g. the Vulkan validation layers or the D3D12
+    /// debug layer), if they are installed.
+    pub validation: bool,
+
+    /// Enables GPU-based validation on backends that support it (currently D3D12 and Vulkan).
+    ///
+    /// Implies [validation](DebugOptions::validation). Very expensive.
+    pub gpu_based_validation: bool,
+
+    /// Passes debug labels and markers on to the backend, for use in graphics debuggers.
+    pub debug_labels: bool,
+}
+
+impl DebugOptions {
+    /// Enables validation and debug labels if `debug_assertions` are enabled, otherwise disables
+    /// all debug options.
+    pub fn debug_builds() -> Self {
+        let enabled = cfg!(debug_assertions);
+
+        DebugOptions {
+            validation: enabled,
+            gpu_based_validation: false,
+            debug_labels: enabled,
         }
     }
+
+    pub fn to_instance_flags(&self) -> FlagSet<InstanceFlag> {
+        let mut flags = FlagSet::from(InstanceFlag::None);
+
+        if self.validation || self.gpu_based_validation {
+            flags |= InstanceFlag::Validation;
+        }
+
+        if self.gpu_based_validation {
+            flags |= InstanceFlag::GpuBasedValidation;
+        }
+
+        if self.debug_labels {
+            flags |= InstanceFlag::Debug;
+        }
+
+        flags
+    }
 }
 
 pub struct RawSurfaceHandles {
@@ -84,6 +157,27 @@ pub struct AdapterOptions<'a, 'b> {
     pub power_preference: PowerPreference,
     pub force_fallback_adapter: bool,
     pub compatible_surface: Option<&'a Surface<'b>>,
+
+    /// The backends from which an adapter may be selected.
+    ///
+    /// Only backends that were also requested when the [Instance] was created are considered.
+    pub backends: FlagSet<Backend>,
+}
+
+impl<'a, 'b> AdapterOptions<'a, 'b> {
+    /// Restricts adapter selection to the given `backends`.
+    ///
+    /// ```ignore
+    /// let adapter = instance.get_adapter(AdapterOptions::default().with_backend(Backend::Vulkan))?;
+    /// ```
+    pub fn with_backend<B>(mut self, backends: B) -> Self
+    where
+        B: Into<FlagSet<Backend>>,
+    {
+        self.backends = backends.into();
+
+        self
+    }
 }
 
 impl Default for AdapterOptions<'_, '_> {
@@ -92,6 +186,7 @@ impl Default for AdapterOptions<'_, '_> {
             power_preference: Default::default(),
             force_fallback_adapter: false,
             compatible_surface: None,
+            backends: Backend::All.into(),
         }
     }
 }
@@ -141,7 +236,7 @@ impl Instance {
             wgt::InstanceDescriptor {
                 backends: backends_to_wgc(requested_backends),
                 flags: instance_flags_to_wgc(descriptor.flags.into()),
-                dx12_shader_compiler: dx12_shader_compiler_to_wgc(descriptor.dx12_shader_comiler),
+                dx12_shader_compiler: dx12_shader_compiler_to_wgc(descriptor.dx12_shader_compiler),
                 gles_minor_version: Default::default(),
             },
         );
@@ -151,6 +246,14 @@ impl Instance {
         }
     }
 
+    /// Creates an instance for all enabled backends with the given `debug_options`.
+    pub fn with_debug_options(debug_options: DebugOptions) -> Self {
+        Instance::new(InstanceDescriptor {
+            flags: debug_options.to_instance_flags(),
+            ..Default::default()
+        })
+    }
+
     pub fn create_surface<'a, T>(&self, window_handle: T) -> Result<Surface<'a>, CreateSurfaceError>
     where
         T: WindowHandle + 'a,
@@ -208,10 +311,15 @@ impl Instance {
         self.global
             .request_adapter(
                 &descriptor,
-                wgc::instance::AdapterInputs::Mask(wgt::Backends::all(), |_| None),
+                wgc::instance::AdapterInputs::Mask(backends_to_wgc(options.backends), |_| None),
             )
             .map(|id| {
-                Adapter::from_handle(driver::native::AdapterHandle::new(self.global.clone(), id))
+                let handle = driver::native::AdapterHandle::new(self.global.clone(), id);
+
+                #[cfg(feature = "trace")]
+                let handle = driver::trace::AdapterHandle::new(handle);
+
+                Adapter::from_handle(handle)
             })
             .map_err(|inner| GetAdapterError { inner })
     }
@@ -308,7 +416,7 @@ pub struct SurfaceConfiguration<F, U, V> {
     pub width: u32,
     pub height: u32,
     pub present_mode: PresentMode,
-    pub desired_maximum_frame_latency: u32,
+    pub desired_maximum_frame_latency: FrameLatency,
     pub alpha_mode: AlphaMode,
     pub view_formats: V,
 }
@@ -330,7 +438,7 @@ impl<'a> Surface<'a> {
         U: texture::UsageFlags,
         V: ViewFormats<F>,
     {
-        let err = gfx_select!(device.device_handle.id() => self.global.surface_configure(self.id, device.device_handle.id(), &surface_configuration_to_wgc(config)));
+        let err = gfx_select!(device.device_handle().id() => self.global.surface_configure(self.id, device.device_handle().id(), &surface_configuration_to_wgc(config)));
 
         if let Some(err) = err {
             panic!("{}", err);
@@ -347,21 +455,32 @@ impl<'a> Surface<'a> {
             view_formats: config.view_formats.formats().collect(),
             _format: config.format,
             usage: config.usage,
+            capture: Default::default(),
         }
     }
 }
 
+type FrameCaptureCallback<F, U> = Box<dyn FnOnce(&Texture2D<F, U>) + Send>;
+
+struct CapturedFrame {
+    buffer: Buffer<[[u8; 4]], BufferUsages!(MapRead | CopyDst)>,
+    layout: ImageDataLayout,
+    width: u32,
+    height: u32,
+}
+
 pub struct ConfiguredSurface<'a, F, U> {
     device: Device,
     surface: Surface<'a>,
     width: u32,
     height: u32,
     present_mode: PresentMode,
-    desired_maximum_frame_latency: u32,
+    desired_maximum_frame_latency: FrameLatency,
     alpha_mode: AlphaMode,
     view_formats: ArrayVec<TextureFormatId, 8>,
     _format: F,
     usage: U,
+    capture: Arc<Mutex<Option<FrameCaptureCallback<F, U>>>>,
 }
 
 impl<'a, F, U> ConfiguredSurface<'a, F, U>
@@ -382,13 +501,13 @@ where
 
         let view_formats = view_formats.iter().map(texture_format_to_wgc).collect();
 
-        let err = gfx_select!(device.device_handle.id() => surface.global.surface_configure(surface.id, device.device_handle.id(), &wgt::SurfaceConfiguration {
+        let err = gfx_select!(device.device_handle().id() => surface.global.surface_configure(surface.id, device.device_handle().id(), &wgt::SurfaceConfiguration {
             usage: texture_usage_to_wgc(&U::FLAG_SET),
             format: texture_format_to_wgc(&F::FORMAT_ID),
             width,
             height,
             present_mode: present_mode_to_wgc(present_mode),
-            desired_maximum_frame_latency: *desired_maximum_frame_latency,
+            desired_maximum_frame_latency: desired_maximum_frame_latency.get(),
             alpha_mode: alpha_mode_to_wgc(alpha_mode),
             view_formats,
         }));
@@ -401,9 +520,20 @@ where
         self.height = height;
     }
 
+    pub fn desired_maximum_frame_latency(&self) -> FrameLatency {
+        self.desired_maximum_frame_latency
+    }
+
+    /// Returns a [FramePacer] that caps the number of frames in flight to the
+    /// [desired_maximum_frame_latency](SurfaceConfiguration::desired_maximum_frame_latency) this
+    /// surface was configured with.
+    pub fn frame_pacer(&self) -> FramePacer {
+        FramePacer::new(self.device.queue(), self.desired_maximum_frame_latency)
+    }
+
     pub fn get_current_texture(&self) -> Result<SurfaceTexture<F, U>, SurfaceError> {
         let surface = &self.surface;
-        let res = gfx_select!(self.device.device_handle.id() => surface.global.surface_get_current_texture(self.surface.id, None));
+        let res = gfx_select!(self.device.device_handle().id() => surface.global.surface_get_current_texture(self.surface.id, None));
 
         match res {
             Ok(SurfaceOutput { status, texture_id }) => {
@@ -421,11 +551,28 @@ where
                     return Err(SurfaceError::Lost);
                 };
 
+                let handle = driver::native::TextureHandle::swap_chain(
+                    self.surface.global.clone(),
+                    texture_id,
+                );
+
+                #[cfg(feature = "trace")]
+                let handle = driver::trace::TextureHandle::external(
+                    self.device.device_handle(),
+                    handle,
+                    &driver::TextureDescriptor {
+                        size: (self.width, self.height, 1),
+                        mipmap_levels: 1,
+                        sample_count: 1,
+                        dimensions: driver::TextureDimensions::Two,
+                        format: F::FORMAT_ID,
+                        usage_flags: U::FLAG_SET,
+                        view_formats: self.view_formats.as_slice(),
+                    },
+                );
+
                 let texture = Texture2D::from_swap_chain_texture(
-                    driver::native::TextureHandle::swap_chain(
-                        self.surface.global.clone(),
-                        texture_id,
-                    ),
+                    handle,
                     self.width,
                     self.height,
                     self.view_formats.as_slice(),
@@ -437,12 +584,114 @@ where
                     surface_id: surface.id,
                     texture,
                     suboptimal,
+                    capture: self.capture.clone(),
                 })
             }
             Err(err) => panic!("{}", err),
         }
     }
 
+    /// Captures the contents of the next frame that is [presented](SurfaceTexture::present) for
+    /// this surface.
+    ///
+    /// The returned future resolves to the frame's pixel data as tightly packed rows of RGBA8
+    /// texels (4 bytes per texel, `width * height * 4` bytes in total), starting at the top-left
+    /// corner. For `bgra8unorm` surfaces, the red and blue channels are swapped to produce RGBA
+    /// data.
+    ///
+    /// The surface must have been configured with a `CopySrc` usage. If a capture was already
+    /// pending, the previous request is replaced and its future will panic.
+    ///
+    /// # Panics
+    ///
+    /// The returned future panics if the surface is dropped before a frame is presented, or if the
+    /// capture buffer could not be mapped.
+    pub fn capture_next_frame(&self) -> impl Future<Output = Vec<u8>>
+    where
+        F: ImageCopyToBufferFormat + 'static,
+        U: texture::CopySrc + 'static,
+        [u8; 4]: ImageData<F>,
+    {
+        let (sender, receiver) = oneshot::channel();
+        let device = self.device.clone();
+
+        let callback: FrameCaptureCallback<F, U> = Box::new(move |texture: &Texture2D<F, U>| {
+            let width = texture.width();
+            let height = texture.height();
+
+            let layout = ImageDataLayout::padded::<F>(ImageCopySize3D {
+                width,
+                height,
+                depth_or_layers: 1,
+            });
+
+            // The buffer is only read after the texture was copied into it.
+            let buffer = device.create_slice_buffer_uninit(
+                layout.required_len(1),
+                buffer::Usages::map_read().and_copy_dst(),
+            );
+            let buffer = unsafe { buffer.assume_init() };
+
+            let command_buffer = device
+                .create_command_encoder()
+                .image_copy_texture_to_buffer(
+                    texture.image_copy_to_buffer_src(0),
+                    buffer.image_copy_dst(layout),
+                )
+                .finish();
+
+            device.queue().submit(command_buffer);
+
+            let _ = sender.send(CapturedFrame {
+                buffer,
+                layout,
+                width,
+                height,
+            });
+        });
+
+        *self.capture.lock().unwrap() = Some(callback);
+
+        let swap_red_blue = matches!(
+            F::FORMAT_ID,
+            TextureFormatId::bgra8unorm | TextureFormatId::bgra8unorm_srgb
+        );
+
+        async move {
+            let CapturedFrame {
+                buffer,
+                layout,
+                width,
+                height,
+            } = receiver
+                .await
+                .expect("the capture request was dropped before a frame was presented");
+
+            buffer
+                .map_read()
+                .await
+                .unwrap_or_else(|err| panic!("{}", err));
+
+            let mapped = buffer.mapped();
+            let row_len = layout.blocks_per_row as usize;
+            let width = width as usize;
+
+            let mut pixels = Vec::with_capacity(width * height as usize * 4);
+
+            for row in mapped.chunks(row_len) {
+                for &[a, b, c, d] in &row[..width] {
+                    if swap_red_blue {
+                        pixels.extend_from_slice(&[c, b, a, d]);
+                    } else {
+                        pixels.extend_from_slice(&[a, b, c, d]);
+                    }
+                }
+            }
+
+            pixels
+        }
+    }
+
     pub fn unconfigure(self) -> Surface<'a> {
         self.surface
     }
@@ -460,6 +709,7 @@ pub struct SurfaceTexture<F, U> {
     surface_id: SurfaceId,
     texture: Texture2D<F, U>,
     suboptimal: bool,
+    capture: Arc<Mutex<Option<FrameCaptureCallback<F, U>>>>,
 }
 
 impl<F, U> SurfaceTexture<F, U> {
@@ -468,6 +718,12 @@ impl<F, U> SurfaceTexture<F, U> {
     }
 
     pub fn present(self) {
+        let capture = self.capture.lock().unwrap().take();
+
+        if let Some(capture) = capture {
+            capture(&self.texture);
+        }
+
         let res =
             gfx_select!(self.texture.handle.id() => self.global.surface_present(self.surface_id));
 
@@ -583,7 +839,7 @@ where
         width: surface_configuration.width,
         height: surface_configuration.height,
         present_mode: present_mode_to_wgc(&surface_configuration.present_mode),
-        desired_maximum_frame_latency: surface_configuration.desired_maximum_frame_latency,
+        desired_maximum_frame_latency: surface_configuration.desired_maximum_frame_latency.get(),
         alpha_mode: alpha_mode_to_wgc(&surface_configuration.alpha_mode),
         view_formats,
     }
@@ -0,0 +1,213 @@
+//! A driver that does not use a GPU, but instead records all operations into an inspectable log.
+//!
+//! Enabling the `noop` feature replaces the native or web driver with this driver. This allows
+//! unit-testing code that creates resources and encodes commands (e.g. which bind groups are set,
+//! dispatch sizes, copy ranges) on machines without a GPU:
+//!
+//! ```ignore
+//! let device = empa::noop::adapter()
+//!     .request_device(&DeviceDescriptor::default())
+//!     .await?;
+//! let log = empa::noop::operation_log(&device);
+//!
+//! // Create resources and submit commands...
+//!
+//! for operation in log.take() {
+//!     if let Operation::Submit { commands, .. } = operation {
+//!         assert!(commands.contains(&Command::DispatchWorkgroups { x: 16, y: 1, z: 1 }));
+//!     }
+//! }
+//! ```
+//!
+//! No GPU work is executed: buffers are backed by host memory that only reflects data written on
+//! the host (when mapped, or with [Queue::write_buffer](crate::device::Queue::write_buffer)); GPU
+//! commands such as copies and dispatches do not modify it. Shaders are not compiled or validated.
+//!
+//! The driver is selected at compile time, not at runtime: while the `noop` feature is enabled,
+//! every device in the build uses this driver, and the `native`, `web` and `trace` modules are not
+//! available. Because Cargo unifies features across a build, enabling `noop` from any crate in the
+//! dependency graph disables the GPU driver for all of them. Enable it only in builds that are used
+//! for testing, e.g. from a crate's `[dev-dependencies]`, and not from a library's regular
+//! dependencies.
+
+use std::sync::{Arc, Mutex};
+
+use flagset::FlagSet;
+
+use crate::adapter::{Adapter, Feature, Limits};
+use crate::buffer::Buffer;
+use crate::device::Device;
+use crate::driver::noop::AdapterHandle;
+use crate::texture::{Texture1D, Texture2D, Texture3D};
+
+pub use crate::driver::noop::{Command, Operation, ResourceId};
+
+/// Returns an adapter that supports all features and the default limits.
+pub fn adapter() -> Adapter {
+    adapter_with(FlagSet::full(), Limits::default())
+}
+
+/// Returns an adapter that reports the given `features` and `limits` as supported.
+pub fn adapter_with(features: FlagSet<Feature>, limits: Limits) -> Adapter {
+    Adapter::from_handle(AdapterHandle::new(features, limits))
+}
+
+/// Returns the operation log for the `device`.
+pub fn operation_log(device: &Device) -> OperationLog {
+    OperationLog {
+        inner: device.device_handle().log().clone(),
+    }
+}
+
+/// Returns the [ResourceId] the `buffer` was assigned by its device.
+pub fn buffer_id<T, U>(buffer: &Buffer<T, U>) -> ResourceId
+where
+    T: ?Sized,
+{
+    buffer.internal.handle.id()
+}
+
+/// Returns the [ResourceId] the `texture` was assigned by its device.
+pub fn texture_1d_id<F, U>(texture: &Texture1D<F, U>) -> ResourceId {
+    texture.handle.id()
+}
+
+/// Returns the [ResourceId] the `texture` was assigned by its device.
+pub fn texture_2d_id<F, U>(texture: &Texture2D<F, U>) -> ResourceId {
+    texture.handle.id()
+}
+
+/// Returns the [ResourceId] the `texture` was assigned by its device.
+pub fn texture_3d_id<F, U>(texture: &Texture3D<F, U>) -> ResourceId {
+    texture.handle.id()
+}
+
+/// The log of operations recorded by a device's queue.
+#[derive(Clone)]
+pub struct OperationLog {
+    inner: Arc<Mutex<Vec<Operation>>>,
+}
+
+impl OperationLog {
+    /// Returns a copy of all operations recorded so far.
+    pub fn operations(&self) -> Vec<Operation> {
+        self.inner.lock().unwrap().clone()
+    }
+
+    /// Removes and returns all operations recorded so far.
+    pub fn take(&self) -> Vec<Operation> {
+        std::mem::take(&mut *self.inner.lock().unwrap())
+    }
+
+    /// Removes all operations recorded so far.
+    pub fn clear(&self) {
+        self.inner.lock().unwrap().clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use futures::executor::block_on;
+
+    use crate::buffer;
+    use crate::device::DeviceDescriptor;
+
+    fn device() -> Device {
+        block_on(adapter().request_device(&DeviceDescriptor::default())).unwrap()
+    }
+
+    #[test]
+    fn write_buffer() {
+        let device = device();
+        let log = operation_log(&device);
+
+        let buffer: Buffer<[u32], _> =
+            device.create_buffer(vec![0u32; 4], buffer::Usages::copy_dst());
+
+        device
+            .queue()
+            .write_buffer_slice(buffer.view().get(1..3).unwrap(), &[1, 2]);
+
+        assert_eq!(
+            log.take(),
+            vec![Operation::WriteBuffer {
+                buffer: buffer_id(&buffer),
+                offset: 4,
+                data: [1u32, 2].iter().flat_map(|v| v.to_ne_bytes()).collect(),
+            }]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds the maximum buffer size")]
+    fn create_buffer_with_overflowing_size() {
+        let device = device();
+
+        let _: Buffer<[u64], _> =
+            device.create_buffer_with(usize::MAX, |i| i as u64, buffer::Usages::copy_dst());
+    }
+
+    #[test]
+    fn map_read() {
+        let device = device();
+
+        let buffer: Buffer<[u32], _> =
+            device.create_buffer(vec![1u32, 2, 3, 4], buffer::Usages::map_read());
+
+        block_on(buffer.map_read()).unwrap();
+
+        assert_eq!(&*buffer.mapped(), &[1, 2, 3, 4]);
+
+        buffer.unmap();
+    }
+
+    #[test]
+    fn encode_commands() {
+        let device = device();
+        let log = operation_log(&device);
+
+        let source: Buffer<[u32], _> =
+            device.create_buffer(vec![1u32, 2, 3, 4], buffer::Usages::copy_src());
+        let destination: Buffer<[u32], _> =
+            device.create_buffer(vec![0u32; 4], buffer::Usages::copy_dst());
+
+        let command_buffer = device
+            .create_command_encoder()
+            .copy_buffer_to_buffer_slice(source.view(), destination.view())
+            .clear_buffer_slice(destination.view().get(2..).unwrap())
+            .begin_compute_pass()
+            .end()
+            .finish();
+
+        device.queue().submit(command_buffer);
+
+        let operations = log.take();
+
+        assert_eq!(operations.len(), 1);
+
+        let Operation::Submit { commands, .. } = &operations[0] else {
+            panic!("expected a submit operation");
+        };
+
+        assert_eq!(
+            commands.as_slice(),
+            &[
+                Command::CopyBufferToBuffer {
+                    source: buffer_id(&source),
+                    source_offset: 0,
+                    destination: buffer_id(&destination),
+                    destination_offset: 0,
+                    size: 16,
+                },
+                Command::ClearBuffer {
+                    buffer: buffer_id(&destination),
+                    range: 8..16,
+                },
+                Command::BeginComputePass,
+                Command::EndComputePass,
+            ]
+        );
+    }
+}
@@ -0,0 +1,288 @@
+//! Pools that recycle transient buffers and textures across frames.
+//!
+//! Resources that are only needed for the duration of a frame (readback buffers, intermediate
+//! render targets, ping-pong textures) can be acquired from a [BufferPool] or [TexturePool] rather
+//! than created anew every frame. An acquired resource is returned as a [Pooled] guard; when the
+//! guard is dropped, the resource is released back to the pool. A released resource only becomes
+//! available for reuse after the GPU has finished the work of the frame in which it was released:
+//! call [frame_submitted](BufferPool::frame_submitted) after submitting a frame's final command
+//! buffer to mark the end of the frame.
+//!
+//! ```ignore
+//! let readback_pool = BufferPool::new(&device, buffer::Usages::copy_dst().and_map_read());
+//!
+//! loop {
+//!     let readback = readback_pool.acquire(1024);
+//!
+//!     // Encode and submit commands that use the readback buffer...
+//!
+//!     drop(readback);
+//!
+//!     readback_pool.frame_submitted();
+//! }
+//! ```
+
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::hash::Hash;
+use std::ops::Deref;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::Context;
+
+use futures::task::noop_waker_ref;
+
+use crate::buffer::{Buffer, ValidUsageFlags};
+use crate::device::Device;
+use crate::texture::format::{Texture2DFormat, ViewFormats};
+use crate::texture::{MipmapLevels, SupportedUsages, Texture2D, Texture2DDescriptor};
+
+type Fence = Pin<Box<dyn Future<Output = ()>>>;
+
+struct PoolState<K, R> {
+    available: HashMap<K, Vec<R>>,
+    released: Vec<(K, R)>,
+    in_flight: VecDeque<(Fence, Vec<(K, R)>)>,
+}
+
+impl<K, R> PoolState<K, R>
+where
+    K: Hash + Eq,
+{
+    fn new() -> Self {
+        PoolState {
+            available: HashMap::new(),
+            released: Vec::new(),
+            in_flight: VecDeque::new(),
+        }
+    }
+
+    // Moves the resources of all frames for which the GPU work has completed to the available set.
+    fn reclaim(&mut self) {
+        let mut cx = Context::from_waker(noop_waker_ref());
+
+        while let Some((fence, _)) = self.in_flight.front_mut() {
+            if fence.as_mut().poll(&mut cx).is_pending() {
+                break;
+            }
+
+            let (_, resources) = self.in_flight.pop_front().unwrap();
+
+            for (key, resource) in resources {
+                self.available.entry(key).or_default().push(resource);
+            }
+        }
+    }
+}
+
+struct Pool<K, R> {
+    device: Device,
+    state: Arc<Mutex<PoolState<K, R>>>,
+}
+
+impl<K, R> Pool<K, R>
+where
+    K: Hash + Eq + Clone + 'static,
+    R: 'static,
+{
+    fn new(device: &Device) -> Self {
+        Pool {
+            device: device.clone(),
+            state: Arc::new(Mutex::new(PoolState::new())),
+        }
+    }
+
+    fn acquire<F>(&self, key: K, create: F) -> Pooled<R>
+    where
+        F: FnOnce() -> R,
+    {
+        let recycled = {
+            let mut state = self.state.lock().unwrap();
+
+            state.reclaim();
+            state.available.get_mut(&key).and_then(|r| r.pop())
+        };
+
+        let resource = recycled.unwrap_or_else(create);
+        let state = Arc::downgrade(&self.state);
+
+        Pooled {
+            resource: Some(resource),
+            release: Some(Box::new(move |resource| {
+                // If the pool was dropped, the resource is simply dropped.
+                if let Some(state) = state.upgrade() {
+                    state.lock().unwrap().released.push((key, resource));
+                }
+            })),
+        }
+    }
+
+    fn frame_submitted(&self) {
+        let mut state = self.state.lock().unwrap();
+
+        if !state.released.is_empty() {
+            let released = std::mem::take(&mut state.released);
+            let fence = Box::pin(self.device.queue().on_submitted_work_done());
+
+            state.in_flight.push_back((fence, released));
+        }
+
+        state.reclaim();
+    }
+
+    fn available_len(&self) -> usize {
+        let mut state = self.state.lock().unwrap();
+
+        state.reclaim();
+        state.available.values().map(|r| r.len()).sum()
+    }
+
+    fn clear(&self) {
+        self.state.lock().unwrap().available.clear();
+    }
+}
+
+/// A resource acquired from a [BufferPool] or [TexturePool].
+///
+/// Dereferences to the resource. The resource is released back to its pool when the guard is
+/// dropped.
+pub struct Pooled<R> {
+    resource: Option<R>,
+    release: Option<Box<dyn FnOnce(R)>>,
+}
+
+impl<R> Pooled<R> {
+    /// Detaches the resource from its pool; the resource will not be released back to the pool.
+    pub fn into_inner(mut self) -> R {
+        self.release = None;
+
+        self.resource.take().unwrap()
+    }
+}
+
+impl<R> Deref for Pooled<R> {
+    type Target = R;
+
+    fn deref(&self) -> &R {
+        self.resource.as_ref().unwrap()
+    }
+}
+
+impl<R> Drop for Pooled<R> {
+    fn drop(&mut self) {
+        if let (Some(resource), Some(release)) = (self.resource.take(), self.release.take()) {
+            release(resource);
+        }
+    }
+}
+
+/// Recycles slice buffers of element type `T` with usage `U`, keyed by length.
+///
+/// See the [module documentation](self) for details.
+pub struct BufferPool<T, U> {
+    pool: Pool<usize, Buffer<[T], U>>,
+    usage: U,
+}
+
+impl<T, U> BufferPool<T, U>
+where
+    T: Copy + Default + 'static,
+    U: ValidUsageFlags + 'static,
+{
+    pub fn new(device: &Device, usage: U) -> Self {
+        BufferPool {
+            pool: Pool::new(device),
+            usage,
+        }
+    }
+
+    /// Acquires a buffer of `len` elements.
+    ///
+    /// If no recycled buffer of this length is available, a new buffer is created with all
+    /// elements initialized to `T::default()`. Note that a recycled buffer retains the contents it
+    /// had when it was released.
+    pub fn acquire(&self, len: usize) -> Pooled<Buffer<[T], U>> {
+        self.pool.acquire(len, || {
+            self.pool
+                .device
+                .create_buffer(vec![T::default(); len], self.usage)
+        })
+    }
+
+    /// Marks the end of a frame; buffers released since the previous call become available for
+    /// reuse once the GPU work submitted up to this point has completed.
+    pub fn frame_submitted(&self) {
+        self.pool.frame_submitted();
+    }
+
+    /// The number of buffers that are currently available for reuse.
+    pub fn available_len(&self) -> usize {
+        self.pool.available_len()
+    }
+
+    /// Drops all buffers that are currently available for reuse.
+    pub fn clear(&self) {
+        self.pool.clear();
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct Texture2DKey {
+    width: u32,
+    height: u32,
+    layers: u32,
+    mipmap_levels: MipmapLevels,
+}
+
+/// Recycles 2D textures of format `F` with usage `U`, keyed by size, layer count and mipmap level
+/// count.
+///
+/// See the [module documentation](self) for details.
+pub struct TexturePool<F, U> {
+    pool: Pool<Texture2DKey, Texture2D<F, U>>,
+}
+
+impl<F, U> TexturePool<F, U>
+where
+    F: Texture2DFormat + 'static,
+    U: SupportedUsages<F> + 'static,
+    (): ViewFormats<F>,
+{
+    pub fn new(device: &Device) -> Self {
+        TexturePool {
+            pool: Pool::new(device),
+        }
+    }
+
+    /// Acquires a texture that matches the `descriptor`.
+    ///
+    /// If no recycled texture matches, a new texture is created. Note that a recycled texture
+    /// retains the contents it had when it was released.
+    pub fn acquire(&self, descriptor: &Texture2DDescriptor<F, U, ()>) -> Pooled<Texture2D<F, U>> {
+        let key = Texture2DKey {
+            width: descriptor.width,
+            height: descriptor.height,
+            layers: descriptor.layers,
+            mipmap_levels: descriptor.mipmap_levels,
+        };
+
+        self.pool
+            .acquire(key, || self.pool.device.create_texture_2d(descriptor))
+    }
+
+    /// Marks the end of a frame; textures released since the previous call become available for
+    /// reuse once the GPU work submitted up to this point has completed.
+    pub fn frame_submitted(&self) {
+        self.pool.frame_submitted();
+    }
+
+    /// The number of textures that are currently available for reuse.
+    pub fn available_len(&self) -> usize {
+        self.pool.available_len()
+    }
+
+    /// Drops all textures that are currently available for reuse.
+    pub fn clear(&self) {
+        self.pool.clear();
+    }
+}
@@ -1,5 +1,18 @@
+use std::future::Future;
+use std::ops::Range;
+
+use crate::buffer;
+use crate::buffer::Buffer;
+use crate::command::CommandEncoder;
 use crate::device::Device;
-use crate::driver::{Device as _, Driver, Dvr, QuerySetDescriptor, QueryType};
+use crate::driver::{Device as _, Driver, Dvr, QuerySetDescriptor, QueryType, Queue as _};
+
+#[cfg(not(any(feature = "web", feature = "noop")))]
+pub use crate::driver::PipelineStatisticsTypes;
+
+/// The alignment in bytes required of the offset into a buffer at which query results are
+/// resolved.
+pub const QUERY_RESOLVE_ALIGNMENT: usize = 256;
 
 pub struct OcclusionQuerySet {
     pub(crate) handle: <Dvr as Driver>::QuerySetHandle,
@@ -10,10 +23,12 @@ impl OcclusionQuerySet {
     pub(crate) fn new(device: &Device, len: usize) -> Self {
         assert!(len < 8192, "query set len must be less than `8192`");
 
-        let handle = device.device_handle.create_query_set(&QuerySetDescriptor {
-            query_type: QueryType::Occlusion,
-            len,
-        });
+        let handle = device
+            .device_handle()
+            .create_query_set(&QuerySetDescriptor {
+                query_type: QueryType::Occlusion,
+                len,
+            });
 
         OcclusionQuerySet { handle, len }
     }
@@ -21,6 +36,24 @@ impl OcclusionQuerySet {
     pub fn len(&self) -> usize {
         self.len
     }
+
+    /// Resolves the queries in the given `range` and reads the results back to the CPU.
+    ///
+    /// Allocates the intermediate resolve and readback buffers internally. Each result is the
+    /// number of samples that passed the depth and stencil tests for the corresponding query.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `range` is out of bounds.
+    pub fn read_results(
+        &self,
+        device: &Device,
+        range: Range<usize>,
+    ) -> impl Future<Output = Vec<u64>> {
+        assert!(range.end <= self.len, "range out of bounds");
+
+        read_query_results(device, self, range)
+    }
 }
 
 pub struct TimestampQuerySet {
@@ -32,10 +65,12 @@ impl TimestampQuerySet {
     pub(crate) fn new(device: &Device, len: usize) -> Self {
         assert!(len < 8192, "query set len must be less than `8192`");
 
-        let handle = device.device_handle.create_query_set(&QuerySetDescriptor {
-            query_type: QueryType::Timestamp,
-            len,
-        });
+        let handle = device
+            .device_handle()
+            .create_query_set(&QuerySetDescriptor {
+                query_type: QueryType::Timestamp,
+                len,
+            });
 
         TimestampQuerySet { handle, len }
     }
@@ -43,4 +78,297 @@ impl TimestampQuerySet {
     pub fn len(&self) -> usize {
         self.len
     }
+
+    /// Resolves the queries in the given `range` and reads the results back to the CPU.
+    ///
+    /// Allocates the intermediate resolve and readback buffers internally. Results are
+    /// timestamps in nanoseconds: the raw tick values the device resolves are scaled by the
+    /// queue's timestamp period, which is not always `1` on native devices.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `range` is out of bounds.
+    pub fn read_results(
+        &self,
+        device: &Device,
+        range: Range<usize>,
+    ) -> impl Future<Output = Vec<u64>> {
+        assert!(range.end <= self.len, "range out of bounds");
+
+        let period = device.primary_queue_handle().timestamp_period() as f64;
+        let results = read_query_results(device, self, range);
+
+        async move {
+            results
+                .await
+                .into_iter()
+                .map(|ticks| (ticks as f64 * period) as u64)
+                .collect()
+        }
+    }
+}
+
+/// A set of queries that collect statistics about the work done by the pipeline between the
+/// beginning and the end of the query (e.g. the number of fragment shader invocations).
+///
+/// Only available on native targets; requires
+/// [Feature::PipelineStatisticsQuery](crate::adapter::Feature).
+///
+/// ```ignore
+/// let query_set = device.create_pipeline_statistics_query_set(
+///     1,
+///     PipelineStatisticsTypes {
+///         compute_shader_invocations: true,
+///         ..Default::default()
+///     },
+/// );
+///
+/// let encoder = device
+///     .create_command_encoder()
+///     .begin_compute_pass()
+///     .begin_pipeline_statistics_query(&query_set, 0)
+///     .set_pipeline(&pipeline)
+///     .set_bind_groups(&bind_groups)
+///     .dispatch_workgroups(DispatchWorkgroups { count_x: 64, count_y: 1, count_z: 1 })
+///     .end_pipeline_statistics_query()
+///     .end();
+///
+/// device.queue().submit(encoder.finish());
+///
+/// let results = query_set.read_results(&device, 0..1).await;
+///
+/// println!("{:?}", results[0].compute_shader_invocations);
+/// ```
+#[cfg(not(any(feature = "web", feature = "noop")))]
+pub struct PipelineStatisticsQuerySet {
+    pub(crate) handle: <Dvr as Driver>::QuerySetHandle,
+    len: usize,
+    statistics: PipelineStatisticsTypes,
+}
+
+#[cfg(not(any(feature = "web", feature = "noop")))]
+impl PipelineStatisticsQuerySet {
+    pub(crate) fn new(device: &Device, len: usize, statistics: PipelineStatisticsTypes) -> Self {
+        assert!(len < 8192, "query set len must be less than `8192`");
+        assert!(
+            statistics.count() > 0,
+            "a pipeline statistics query set must collect at least one statistic"
+        );
+
+        let handle = device
+            .device_handle()
+            .create_query_set(&QuerySetDescriptor {
+                query_type: QueryType::PipelineStatistics(statistics),
+                len,
+            });
+
+        PipelineStatisticsQuerySet {
+            handle,
+            len,
+            statistics,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// The statistics that the queries in this set collect.
+    pub fn statistics(&self) -> PipelineStatisticsTypes {
+        self.statistics
+    }
+
+    /// Decodes the raw `values` that were resolved from this query set (see
+    /// [CommandEncoder::resolve_pipeline_statistics_query_set]) into a [PipelineStatistics] for
+    /// each query.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `values` is not a multiple of the number of
+    /// [statistics](PipelineStatisticsQuerySet::statistics) per query.
+    pub fn decode(&self, values: &[u64]) -> Vec<PipelineStatistics> {
+        let count = self.statistics.count();
+
+        assert!(
+            values.len() % count == 0,
+            "the number of values (`{}`) must be a multiple of the number of statistics per query \
+             (`{}`)",
+            values.len(),
+            count
+        );
+
+        values
+            .chunks_exact(count)
+            .map(|chunk| PipelineStatistics::decode(&self.statistics, chunk))
+            .collect()
+    }
+
+    /// Resolves the queries in the given `range` and reads the results back to the CPU.
+    ///
+    /// Allocates the intermediate resolve and readback buffers internally.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `range` is out of bounds.
+    pub fn read_results(
+        &self,
+        device: &Device,
+        range: Range<usize>,
+    ) -> impl Future<Output = Vec<PipelineStatistics>> {
+        assert!(range.end <= self.len, "range out of bounds");
+
+        let statistics = self.statistics;
+        let values = read_query_results(device, self, range);
+
+        async move {
+            values
+                .await
+                .chunks_exact(statistics.count())
+                .map(|chunk| PipelineStatistics::decode(&statistics, chunk))
+                .collect()
+        }
+    }
+}
+
+/// The result of a query in a [PipelineStatisticsQuerySet].
+///
+/// Statistics that the query set does not collect are `None`.
+#[cfg(not(any(feature = "web", feature = "noop")))]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct PipelineStatistics {
+    pub vertex_shader_invocations: Option<u64>,
+    pub clipper_invocations: Option<u64>,
+    pub clipper_primitives_out: Option<u64>,
+    pub fragment_shader_invocations: Option<u64>,
+    pub compute_shader_invocations: Option<u64>,
+}
+
+#[cfg(not(any(feature = "web", feature = "noop")))]
+impl PipelineStatistics {
+    fn decode(statistics: &PipelineStatisticsTypes, values: &[u64]) -> Self {
+        let mut values = values.iter().copied();
+        let mut next = |selected: bool| if selected { values.next() } else { None };
+
+        PipelineStatistics {
+            vertex_shader_invocations: next(statistics.vertex_shader_invocations),
+            clipper_invocations: next(statistics.clipper_invocations),
+            clipper_primitives_out: next(statistics.clipper_primitives_out),
+            fragment_shader_invocations: next(statistics.fragment_shader_invocations),
+            compute_shader_invocations: next(statistics.compute_shader_invocations),
+        }
+    }
+}
+
+trait ResolveQueries {
+    /// The number of `u64` values each query resolves to.
+    fn values_per_query(&self) -> usize {
+        1
+    }
+
+    fn resolve<U>(
+        &self,
+        encoder: CommandEncoder,
+        offset: usize,
+        view: buffer::View<[u64], U>,
+    ) -> CommandEncoder
+    where
+        U: buffer::QueryResolve;
+}
+
+impl ResolveQueries for OcclusionQuerySet {
+    fn resolve<U>(
+        &self,
+        encoder: CommandEncoder,
+        offset: usize,
+        view: buffer::View<[u64], U>,
+    ) -> CommandEncoder
+    where
+        U: buffer::QueryResolve,
+    {
+        encoder.resolve_occlusion_query_set(self, offset, view)
+    }
+}
+
+impl ResolveQueries for TimestampQuerySet {
+    fn resolve<U>(
+        &self,
+        encoder: CommandEncoder,
+        offset: usize,
+        view: buffer::View<[u64], U>,
+    ) -> CommandEncoder
+    where
+        U: buffer::QueryResolve,
+    {
+        encoder.resolve_timestamp_query_set(self, offset, view)
+    }
+}
+
+#[cfg(not(any(feature = "web", feature = "noop")))]
+impl ResolveQueries for PipelineStatisticsQuerySet {
+    fn values_per_query(&self) -> usize {
+        self.statistics.count()
+    }
+
+    fn resolve<U>(
+        &self,
+        encoder: CommandEncoder,
+        offset: usize,
+        view: buffer::View<[u64], U>,
+    ) -> CommandEncoder
+    where
+        U: buffer::QueryResolve,
+    {
+        encoder.resolve_pipeline_statistics_query_set(self, offset, view)
+    }
+}
+
+fn read_query_results<Q>(
+    device: &Device,
+    query_set: &Q,
+    range: Range<usize>,
+) -> impl Future<Output = Vec<u64>>
+where
+    Q: ResolveQueries,
+{
+    let result_len = range.len() * query_set.values_per_query();
+    let len = result_len.max(1);
+
+    // The resolve buffer is written at offset `0`, which satisfies the 256-byte alignment
+    // requirement for query resolve destinations.
+    // WebGPU initializes buffers to zero, which is a valid `u64`.
+    let resolve_buffer: Buffer<[u64], _> = unsafe {
+        device
+            .create_slice_buffer_uninit(len, buffer::Usages::query_resolve().and_copy_src())
+            .assume_init()
+    };
+    let readback_buffer: Buffer<[u64], _> = unsafe {
+        device
+            .create_slice_buffer_uninit(len, buffer::Usages::map_read().and_copy_dst())
+            .assume_init()
+    };
+
+    let mut encoder = device.create_command_encoder();
+
+    if !range.is_empty() {
+        encoder = query_set.resolve(encoder, range.start, resolve_buffer.view());
+    }
+
+    let command_buffer = encoder
+        .copy_buffer_to_buffer_slice(resolve_buffer.view(), readback_buffer.view())
+        .finish();
+
+    device.queue().submit(command_buffer);
+
+    async move {
+        readback_buffer
+            .map_read()
+            .await
+            .expect("failed to map the query readback buffer");
+
+        let results = readback_buffer.mapped()[..result_len].to_vec();
+
+        readback_buffer.unmap();
+
+        results
+    }
 }
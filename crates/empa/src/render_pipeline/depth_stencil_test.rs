@@ -1,7 +1,8 @@
 use std::marker;
 
-use crate::driver::{DepthStencilState, StencilFaceState, StencilOperation};
-use crate::render_target::ReadOnly;
+use crate::driver::DepthStencilState;
+pub use crate::driver::{StencilFaceState, StencilOperation};
+use crate::render_target::{DepthConvention, ReadOnly};
 use crate::texture::format::{
     depth16unorm, depth24plus, depth24plus_stencil8, depth32float, depth32float_stencil8, stencil8,
     DepthStencilTestFormat,
@@ -47,8 +48,67 @@ impl StencilTest for ReadOnly<depth24plus_stencil8> {}
 impl StencilTest for ReadOnly<depth32float_stencil8> {}
 impl StencilTest for ReadOnly<stencil8> {}
 
+impl StencilFaceState {
+    /// A stencil face state that writes the stencil reference value to every fragment that passes
+    /// the depth test, e.g. to mark the area covered by a mask or portal.
+    ///
+    /// The reference value is set on the render pass with
+    /// [set_stencil_reference](crate::command::RenderPassEncoder::set_stencil_reference).
+    pub const fn write_reference() -> Self {
+        StencilFaceState {
+            compare: CompareFunction::Always,
+            depth_fail_op: StencilOperation::Keep,
+            fail_op: StencilOperation::Keep,
+            pass_op: StencilOperation::Replace,
+        }
+    }
+
+    /// A stencil face state that only passes fragments for which the `compare` function between
+    /// the stencil reference value and the stored stencil value succeeds, without modifying the
+    /// stored stencil values.
+    ///
+    /// For example, `StencilFaceState::test_reference(CompareFunction::Equal)` only renders
+    /// fragments inside an area that was previously marked with
+    /// [write_reference](StencilFaceState::write_reference).
+    pub const fn test_reference(compare: CompareFunction) -> Self {
+        StencilFaceState {
+            compare,
+            depth_fail_op: StencilOperation::Keep,
+            fail_op: StencilOperation::Keep,
+            pass_op: StencilOperation::Keep,
+        }
+    }
+}
+
+/// Configures the depth and stencil tests for a render pipeline.
+///
+/// The stencil methods are only available for formats with a stencil aspect. Stencil masking
+/// typically uses two pipelines: one that marks an area of the stencil buffer, and one that only
+/// renders inside (or outside) that area:
+///
+/// ```ignore
+/// // Writes the stencil reference value wherever the portal is drawn, without writing color.
+/// let mask_test = DepthStencilTest::read_write::<depth24plus_stencil8>()
+///     .stencil(StencilFaceState::write_reference());
+///
+/// // Only renders fragments where the stored stencil value equals the reference value.
+/// let masked_test = DepthStencilTest::read_write::<depth24plus_stencil8>()
+///     .stencil(StencilFaceState::test_reference(CompareFunction::Equal))
+///     .stencil_write_mask(0);
+///
+/// encoder = encoder
+///     .begin_render_pass(...)
+///     .set_stencil_reference(1)
+///     .set_pipeline(&mask_pipeline)
+///     ... // Draw the portal geometry
+///     .set_pipeline(&masked_pipeline)
+///     ... // Draw the scene behind the portal
+///     .end();
+/// ```
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct DepthStencilTest<F> {
     pub(crate) inner: DepthStencilState,
+    #[cfg_attr(feature = "serde", serde(skip))]
     _marker: marker::PhantomData<*const F>,
 }
 
@@ -126,6 +186,14 @@ where
         self
     }
 
+    /// Sets the depth compare function that matches the given depth `convention`.
+    ///
+    /// Fragments pass the depth test if they are closer to the viewer than the current depth value
+    /// (see [DepthConvention::depth_compare]).
+    pub fn depth_convention(self, convention: DepthConvention) -> Self {
+        self.depth_compare(convention.depth_compare())
+    }
+
     pub fn depth_bias(mut self, depth_bias: i32) -> Self {
         self.inner.depth_bias = depth_bias;
 
@@ -149,27 +217,42 @@ impl<F> DepthStencilTest<F>
 where
     F: StencilTest,
 {
+    /// Sets the mask applied to both the stored stencil value and the reference value before the
+    /// stencil comparison.
     pub fn stencil_read_mask(mut self, stencil_read_mask: u32) -> Self {
         self.inner.stencil_read_mask = stencil_read_mask;
 
         self
     }
 
+    /// Sets the mask that determines which bits of the stored stencil value may be modified.
+    ///
+    /// A mask of `0` disables stencil writes.
     pub fn stencil_write_mask(mut self, stencil_write_mask: u32) -> Self {
         self.inner.stencil_write_mask = stencil_write_mask;
 
         self
     }
 
+    /// Sets the stencil state for front-facing primitives.
     pub fn stencil_front(mut self, stencil_front: StencilFaceState) -> Self {
         self.inner.stencil_front = stencil_front;
 
         self
     }
 
+    /// Sets the stencil state for back-facing primitives.
     pub fn stencil_back(mut self, stencil_back: StencilFaceState) -> Self {
         self.inner.stencil_back = stencil_back;
 
         self
     }
+
+    /// Sets the same stencil state for both front-facing and back-facing primitives.
+    pub fn stencil(mut self, stencil: StencilFaceState) -> Self {
+        self.inner.stencil_front = stencil;
+        self.inner.stencil_back = stencil;
+
+        self
+    }
 }
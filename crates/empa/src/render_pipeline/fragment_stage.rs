@@ -7,7 +7,9 @@ use flagset::{flags, FlagSet};
 use crate::driver::{ColorTargetState, Driver, Dvr};
 use crate::pipeline_constants::PipelineConstants;
 use crate::render_target::TypedColorLayout;
-use crate::shader_module::{ShaderModule, ShaderSourceInternal};
+use crate::shader_module::{
+    EntryPointName, Fragment, InterStageVariable, ShaderModule, ShaderSourceInternal,
+};
 use crate::texture::format::{Blendable, ColorRenderable};
 
 flags! {
@@ -21,7 +23,14 @@ flags! {
     }
 }
 
+/// The set of color channels a color output writes to.
+///
+/// Combine [ColorWrite] channels with `|` (e.g. `ColorWrite::Red | ColorWrite::Alpha`); use
+/// `ColorWriteMask::default()` to disable all writes. The same mask type is used on all targets.
+pub type ColorWriteMask = FlagSet<ColorWrite>;
+
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BlendFactor {
     Zero,
     One,
@@ -39,6 +48,7 @@ pub enum BlendFactor {
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BlendComponent {
     Add {
         src_factor: BlendFactor,
@@ -66,24 +76,32 @@ impl Default for BlendComponent {
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BlendState {
     pub color: BlendComponent,
     pub alpha: BlendComponent,
 }
 
+/// A color output without blending; the fragment's color replaces the attachment's value.
+///
+/// Required for formats that cannot be blended, such as integer formats.
 pub struct ColorOutput<F, W>
 where
     F: ColorRenderable,
-    W: Into<FlagSet<ColorWrite>> + Copy,
+    W: Into<ColorWriteMask> + Copy,
 {
     pub format: F,
     pub write_mask: W,
 }
 
+/// A color output that blends the fragment's color with the attachment's value.
+///
+/// Only available for [Blendable] formats; integer formats and 32-bit floating point formats are
+/// rejected at compile time.
 pub struct BlendedColorOutput<F, W>
 where
     F: Blendable,
-    W: Into<FlagSet<ColorWrite>> + Copy,
+    W: Into<ColorWriteMask> + Copy,
 {
     pub format: F,
     pub blend_state: BlendState,
@@ -103,13 +121,13 @@ pub trait TypedColorOutput: typed_color_output_seal::Seal {
 impl<F, W> typed_color_output_seal::Seal for ColorOutput<F, W>
 where
     F: ColorRenderable,
-    W: Into<FlagSet<ColorWrite>> + Copy,
+    W: Into<ColorWriteMask> + Copy,
 {
 }
 impl<F, W> TypedColorOutput for ColorOutput<F, W>
 where
     F: ColorRenderable,
-    W: Into<FlagSet<ColorWrite>> + Copy,
+    W: Into<ColorWriteMask> + Copy,
 {
     type Format = F;
 
@@ -125,13 +143,13 @@ where
 impl<F, W> typed_color_output_seal::Seal for BlendedColorOutput<F, W>
 where
     F: Blendable,
-    W: Into<FlagSet<ColorWrite>> + Copy,
+    W: Into<ColorWriteMask> + Copy,
 {
 }
 impl<F, W> TypedColorOutput for BlendedColorOutput<F, W>
 where
     F: Blendable,
-    W: Into<FlagSet<ColorWrite>> + Copy,
+    W: Into<ColorWriteMask> + Copy,
 {
     type Format = F;
 
@@ -177,6 +195,17 @@ macro_rules! impl_typed_color_outputs {
     }
 }
 
+impl typed_color_outputs_seal::Seal for () {}
+impl TypedColorOutputs for () {
+    type Layout = ();
+
+    type Targets = <[ColorTargetState; 0] as IntoIterator>::IntoIter;
+
+    fn targets(&self) -> Self::Targets {
+        [].into_iter()
+    }
+}
+
 impl_typed_color_outputs!(1, C0);
 impl_typed_color_outputs!(2, C0, C1);
 impl_typed_color_outputs!(3, C0, C1, C2);
@@ -186,11 +215,28 @@ impl_typed_color_outputs!(6, C0, C1, C2, C3, C4, C5);
 impl_typed_color_outputs!(7, C0, C1, C2, C3, C4, C5, C6);
 impl_typed_color_outputs!(8, C0, C1, C2, C3, C4, C5, C6, C7);
 
+impl<C, const N: usize> typed_color_outputs_seal::Seal for [C; N] where C: TypedColorOutput {}
+impl<C, const N: usize> TypedColorOutputs for [C; N]
+where
+    C: TypedColorOutput,
+{
+    type Layout = [C::Format; N];
+
+    type Targets = <[ColorTargetState; N] as IntoIterator>::IntoIter;
+
+    fn targets(&self) -> Self::Targets {
+        self.each_ref()
+            .map(|output| output.to_color_target_state())
+            .into_iter()
+    }
+}
+
 pub(crate) struct FragmentState {
     pub(crate) shader_module: <Dvr as Driver>::ShaderModuleHandle,
     pub(crate) entry_point: String,
     pub(crate) constants: HashMap<String, f64>,
     pub(crate) targets: Vec<ColorTargetState>,
+    pub(crate) inputs: Vec<InterStageVariable>,
 }
 
 pub struct FragmentStage<O> {
@@ -206,7 +252,16 @@ pub struct FragmentStageBuilder<O> {
 }
 
 impl FragmentStageBuilder<()> {
-    pub fn begin(shader_module: &ShaderModule, entry_point: &str) -> Self {
+    /// Begins building a fragment stage for the `entry_point` in the `shader_module`.
+    ///
+    /// The `entry_point` may be an entry point name, or a typed
+    /// [FragmentEntryPoint](crate::shader_module::FragmentEntryPoint) declared with the
+    /// [shader_entry_points](crate::shader_module::shader_entry_points) macro.
+    pub fn begin<E>(shader_module: &ShaderModule, entry_point: E) -> Self
+    where
+        E: EntryPointName<Fragment>,
+    {
+        let entry_point = entry_point.entry_point_name();
         let shader_meta = shader_module.meta.clone();
 
         let entry_index = shader_meta
@@ -219,6 +274,12 @@ impl FragmentStageBuilder<()> {
             "entry point is not a fragment stage"
         );
 
+        let inputs = shader_meta
+            .entry_point_input_bindings(entry_index)
+            .unwrap()
+            .map(|binding| binding.to_inter_stage_variable())
+            .collect();
+
         FragmentStageBuilder {
             inner: FragmentStage {
                 fragment_state: FragmentState {
@@ -226,6 +287,7 @@ impl FragmentStageBuilder<()> {
                     entry_point: entry_point.to_string(),
                     constants: Default::default(),
                     targets: vec![],
+                    inputs,
                 },
                 shader_meta,
                 entry_index,
@@ -235,6 +297,18 @@ impl FragmentStageBuilder<()> {
         }
     }
 
+    /// Specifies the color outputs of the fragment stage.
+    ///
+    /// The `color_outputs` may be a single [ColorOutput] or [BlendedColorOutput], a tuple of up to
+    /// `8` outputs, or an array of up to `8` outputs of the same type. An array of outputs produces
+    /// an array layout (e.g. `[rgba8unorm; 4]`), which must be matched by an array of attachments
+    /// in the render target.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the shader writes to a location for which no color output is declared, if the
+    /// shader output's component type (float, signed or unsigned integer) does not match the
+    /// output format, or if the shader output has fewer components than the output format.
     pub fn color_outputs<O: TypedColorOutputs>(
         mut self,
         color_outputs: O,
@@ -251,42 +325,42 @@ impl FragmentStageBuilder<()> {
             let location = binding.location();
             let binding_type = binding.binding_type();
 
-            if let Some(format) = layout.get(location as usize) {
-                // TODO: it's not clear from the spec what it means for a format to be compatible
-                // with an output. Assuming for now that compatibility is solely about the main
-                // component type (float, half-float, uint, sint) and not the number of components
-                // (as this is how it works in OpenGL); needs confirmation.
-                if binding_type.is_float() && !format.is_float() {
-                    panic!(
-                        "shader expects a float format binding for location `{}`",
-                        location
-                    );
-                }
-
-                if binding_type.is_half_float() && !format.is_half_float() {
-                    panic!(
-                        "shader expects a half-float format binding for location `{}`",
-                        location
-                    );
-                }
-
-                if binding_type.is_signed_integer() && !format.is_signed_integer() {
-                    panic!(
-                        "shader expects a signed integer format binding for location `{}`",
-                        location
-                    );
-                }
-
-                if binding_type.is_unsigned_integer() && !format.is_unsigned_integer() {
-                    panic!(
-                        "shader expects an unsigned integer format binding for location `{}`",
-                        location
-                    );
-                }
+            let Some(format) = layout.get(location as usize) else {
+                panic!(
+                    "the shader writes to color output location `{}`, but only `{}` color \
+                    outputs were declared",
+                    location,
+                    layout.len()
+                );
+            };
+
+            // Float and half-float shader outputs may write to any format with a float component
+            // type (including normalized formats); integer outputs must match the signedness of
+            // the format exactly.
+            let type_matches = if binding_type.is_float() || binding_type.is_half_float() {
+                format.is_float() || format.is_half_float()
+            } else if binding_type.is_signed_integer() {
+                format.is_signed_integer()
             } else {
+                format.is_unsigned_integer()
+            };
+
+            if !type_matches {
+                panic!(
+                    "the shader output type `{:?}` at location `{}` is not compatible with the \
+                    `{:?}` color output format",
+                    binding_type, location, format
+                );
+            }
+
+            if binding_type.component_count() < format.component_count() {
                 panic!(
-                    "shader expects an output binding for location `{}`",
-                    location
+                    "the shader output at location `{}` has `{}` components, but the `{:?}` \
+                    color output format requires at least `{}` components",
+                    location,
+                    binding_type.component_count(),
+                    format,
+                    format.component_count()
                 );
             }
         }
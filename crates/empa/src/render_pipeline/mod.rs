@@ -7,6 +7,11 @@ pub use self::fragment_stage::*;
 mod multisample_state;
 pub use self::multisample_state::*;
 
+#[cfg(not(feature = "web"))]
+mod multiview_state;
+#[cfg(not(feature = "web"))]
+pub use self::multiview_state::*;
+
 mod primitive_assembly;
 pub use self::primitive_assembly::*;
 
@@ -1,19 +1,20 @@
 use crate::driver;
+use crate::render_target::SampleCount;
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct MultisampleState<const SAMPLES: u8> {
     pub(crate) inner: driver::MultisampleState,
 }
 
 impl<const SAMPLES: u8> MultisampleState<SAMPLES> {
     pub fn new() -> Self {
-        if SAMPLES <= 1 {
-            panic!("sample count must be more than `1`");
-        }
+        #[allow(clippy::let_unit_value)]
+        let _ = SampleCount::<SAMPLES>::ASSERT_SUPPORTED;
 
         MultisampleState {
             inner: driver::MultisampleState {
                 count: SAMPLES as u32,
-                mask: 0xFFFFFFF,
+                mask: 0xFFFFFFFF,
                 alpha_to_coverage_enabled: false,
             },
         }
@@ -0,0 +1,23 @@
+use std::num::NonZeroU32;
+
+/// Configures a render pipeline to render to `VIEWS` array layers in a single draw.
+///
+/// The view index is available to shaders through the `view_index` builtin. Pipelines created with
+/// a multiview state may only be used with a
+/// [MultiviewRenderTarget](crate::render_target::MultiviewRenderTarget) with a matching view
+/// count. Requires the [Feature::Multiview](crate::adapter::Feature) device feature.
+pub struct MultiviewState<const VIEWS: u32> {
+    pub(crate) views: NonZeroU32,
+}
+
+impl<const VIEWS: u32> MultiviewState<VIEWS> {
+    pub fn new() -> Self {
+        if VIEWS <= 1 {
+            panic!("view count must be more than `1`");
+        }
+
+        MultiviewState {
+            views: NonZeroU32::new(VIEWS).unwrap(),
+        }
+    }
+}
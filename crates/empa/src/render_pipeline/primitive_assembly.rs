@@ -3,12 +3,14 @@ use std::marker;
 use crate::driver::{PrimitiveState, PrimitiveTopology};
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum IndexFormat {
     U16,
     U32,
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum FrontFace {
     Clockwise,
     CounterClockwise,
@@ -21,6 +23,7 @@ impl Default for FrontFace {
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CullMode {
     Front,
     Back,
@@ -77,7 +80,13 @@ impl IndexData for u32 {
     const FORMAT: IndexFormat = IndexFormat::U32;
 }
 
-pub trait PipelineIndexFormatCompatible<I>: IndexData
+/// Marks index data of which the format ([u16] or [u32]) is only known at runtime.
+///
+/// Index buffers with dynamic index data (e.g. [MeshIndices](crate::util::MeshIndices)) can only
+/// be used with pipelines that accept [IndexAny].
+pub struct DynamicIndexData {}
+
+pub trait PipelineIndexFormatCompatible<I>
 where
     I: PipelineIndexFormat,
 {
@@ -89,9 +98,12 @@ impl PipelineIndexFormatCompatible<Index32> for u32 {}
 
 impl PipelineIndexFormatCompatible<IndexAny> for u16 {}
 impl PipelineIndexFormatCompatible<IndexAny> for u32 {}
+impl PipelineIndexFormatCompatible<IndexAny> for DynamicIndexData {}
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct PrimitiveAssembly<I> {
     pub(crate) inner: PrimitiveState,
+    #[cfg_attr(feature = "serde", serde(skip))]
     _marker: marker::PhantomData<*const I>,
 }
 
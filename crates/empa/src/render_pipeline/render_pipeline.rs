@@ -1,19 +1,27 @@
 use std::borrow::Borrow;
 use std::future::Future;
 use std::marker;
+use std::num::NonZeroU32;
 
 use atomic_counter::AtomicCounter;
 use futures::FutureExt;
 
-use crate::device::{Device, ID_GEN};
+use crate::device::{Device, PipelineCreationError, ID_GEN};
 use crate::driver;
 use crate::driver::{Device as _, Driver, Dvr, PrimitiveState, PrimitiveTopology, ShaderStage};
+#[cfg(not(feature = "web"))]
+use crate::render_pipeline::MultiviewState;
 use crate::render_pipeline::{
     DepthStencilTest, FragmentStage, FragmentState, FrontFace, IndexAny, MultisampleState,
     PipelineIndexFormat, PrimitiveAssembly, TypedVertexLayout, VertexStage, VertexState,
 };
-use crate::render_target::{MultisampleRenderLayout, RenderLayout, TypedMultisampleColorLayout};
+#[cfg(not(feature = "web"))]
+use crate::render_target::MultiviewRenderLayout;
+use crate::render_target::{
+    MultisampleRenderLayout, RenderLayout, TypedColorLayout, TypedMultisampleColorLayout,
+};
 use crate::resource_binding::{PipelineLayout, TypedPipelineLayout};
+use crate::shader_module::validate_inter_stage_interface;
 
 pub struct RenderPipeline<O, V, I, R> {
     pub(crate) handle: <Dvr as Driver>::RenderPipelineHandle,
@@ -25,34 +33,36 @@ impl<O, V, I, R> RenderPipeline<O, V, I, R> {
     pub(crate) fn new_sync(
         device: &Device,
         descriptor: &RenderPipelineDescriptor<O, V, I, R>,
-    ) -> Self {
-        let id = ID_GEN.get();
+    ) -> Result<Self, PipelineCreationError> {
         let handle = device
-            .device_handle
-            .create_render_pipeline(&descriptor.to_driver());
+            .device_handle()
+            .create_render_pipeline(&descriptor.to_driver())?;
+        let id = ID_GEN.get();
 
-        RenderPipeline {
+        Ok(RenderPipeline {
             handle,
             id,
             _marker: Default::default(),
-        }
+        })
     }
 
     pub(crate) fn new_async(
         device: &Device,
         descriptor: &RenderPipelineDescriptor<O, V, I, R>,
-    ) -> impl Future<Output = Self> {
+    ) -> impl Future<Output = Result<Self, PipelineCreationError>> {
         device
-            .device_handle
+            .device_handle()
             .create_render_pipeline_async(&descriptor.to_driver())
-            .map(|handle| {
-                let id = ID_GEN.get();
-
-                RenderPipeline {
-                    handle,
-                    id,
-                    _marker: Default::default(),
-                }
+            .map(|result| {
+                result.map(|handle| {
+                    let id = ID_GEN.get();
+
+                    RenderPipeline {
+                        handle,
+                        id,
+                        _marker: Default::default(),
+                    }
+                })
             })
     }
 
@@ -68,6 +78,7 @@ pub struct RenderPipelineDescriptor<O, V, I, R> {
     fragment_state: Option<FragmentState>,
     depth_stencil_state: Option<driver::DepthStencilState>,
     multisample_state: Option<driver::MultisampleState>,
+    multiview: Option<NonZeroU32>,
     _marker: marker::PhantomData<(*const O, *const V, *const I, *const R)>,
 }
 
@@ -90,6 +101,7 @@ impl<O, V, I, R> RenderPipelineDescriptor<O, V, I, R> {
                 targets: &f.targets,
             }),
             multisample_state: self.multisample_state.as_ref(),
+            multiview: self.multiview,
         }
     }
 }
@@ -108,6 +120,7 @@ pub struct RenderPipelineDescriptorBuilder<
     primitive_state: driver::PrimitiveState,
     depth_stencil_state: Option<driver::DepthStencilState>,
     multisample_state: Option<driver::MultisampleState>,
+    multiview: Option<NonZeroU32>,
     _marker: marker::PhantomData<(
         Multisample,
         Layout,
@@ -141,6 +154,7 @@ impl
             },
             depth_stencil_state: None,
             multisample_state: None,
+            multiview: None,
             _marker: Default::default(),
         }
     }
@@ -158,6 +172,7 @@ impl<M, L, V, F, D, P> RenderPipelineDescriptorBuilder<M, L, V, F, D, P> {
             primitive_state: self.primitive_state,
             depth_stencil_state: Some(depth_stencil_test.inner),
             multisample_state: self.multisample_state,
+            multiview: self.multiview,
             _marker: Default::default(),
         }
     }
@@ -176,6 +191,7 @@ impl<M, L, V, F, D, P> RenderPipelineDescriptorBuilder<M, L, V, F, D, P> {
             primitive_state: primitive_assembly.inner,
             depth_stencil_state: self.depth_stencil_state,
             multisample_state: self.multisample_state,
+            multiview: self.multiview,
             _marker: Default::default(),
         }
     }
@@ -193,6 +209,7 @@ impl<M, D, P> RenderPipelineDescriptorBuilder<M, (), (), (), D, P> {
             primitive_state: self.primitive_state,
             depth_stencil_state: self.depth_stencil_state,
             multisample_state: self.multisample_state,
+            multiview: self.multiview,
             _marker: Default::default(),
         }
     }
@@ -210,6 +227,24 @@ impl<L, V, D, P> RenderPipelineDescriptorBuilder<(), L, V, (), D, P> {
             primitive_state: self.primitive_state,
             depth_stencil_state: self.depth_stencil_state,
             multisample_state: Some(multisample_state.inner),
+            multiview: self.multiview,
+            _marker: Default::default(),
+        }
+    }
+
+    #[cfg(not(feature = "web"))]
+    pub fn multiview<const VIEWS: u32>(
+        self,
+        multiview_state: MultiviewState<VIEWS>,
+    ) -> RenderPipelineDescriptorBuilder<MultiviewState<VIEWS>, L, V, (), D, P> {
+        RenderPipelineDescriptorBuilder {
+            vertex_state: self.vertex_state,
+            fragment_state: self.fragment_state,
+            layout: self.layout,
+            primitive_state: self.primitive_state,
+            depth_stencil_state: self.depth_stencil_state,
+            multisample_state: self.multisample_state,
+            multiview: Some(multiview_state.views),
             _marker: Default::default(),
         }
     }
@@ -255,14 +290,25 @@ where
                 );
             }
 
+            if let Err(mismatch) = entry
+                .binding_type
+                .check_buffer_layout(&resource_binding.binding_type)
+            {
+                panic!(
+                    "the buffer layout for binding `{}` in group `{}` does not match the shader \
+                     layout {}",
+                    resource_binding.binding, resource_binding.group, mismatch
+                );
+            }
+
             if entry.binding_type != resource_binding.binding_type {
                 panic!(
-                    "the binding type for binding `{}` in group `{}` does not match the shader \
-                type (shader: {:#?}, actual: {:#?})",
+                    "binding `{}` in group `{}` {}",
                     resource_binding.binding,
                     resource_binding.group,
-                    &resource_binding.binding_type,
-                    &entry.binding_type
+                    entry
+                        .binding_type
+                        .describe_mismatch(&resource_binding.binding_type)
                 )
             }
         }
@@ -274,6 +320,7 @@ where
             primitive_state: self.primitive_state,
             depth_stencil_state: self.depth_stencil_state,
             multisample_state: self.multisample_state,
+            multiview: self.multiview,
             _marker: Default::default(),
         }
     }
@@ -319,8 +366,26 @@ where
                 );
             }
 
+            if let Err(mismatch) = entry
+                .binding_type
+                .check_buffer_layout(&resource_binding.binding_type)
+            {
+                panic!(
+                    "the buffer layout for binding `{}` in group `{}` does not match the shader \
+                     layout {}",
+                    resource_binding.binding, resource_binding.group, mismatch
+                );
+            }
+
             if entry.binding_type != resource_binding.binding_type {
-                panic!("the binding type for binding `{}` in group `{}` does not match the shader type", resource_binding.binding, resource_binding.group)
+                panic!(
+                    "binding `{}` in group `{}` {}",
+                    resource_binding.binding,
+                    resource_binding.group,
+                    entry
+                        .binding_type
+                        .describe_mismatch(&resource_binding.binding_type)
+                )
             }
         }
 
@@ -331,6 +396,7 @@ where
             primitive_state: self.primitive_state,
             depth_stencil_state: self.depth_stencil_state,
             multisample_state: self.multisample_state,
+            multiview: self.multiview,
             _marker: Default::default(),
         }
     }
@@ -375,6 +441,48 @@ where
     }
 }
 
+impl<L, V, F, D, P, const SAMPLES: u8>
+    RenderPipelineDescriptorBuilder<MultisampleState<SAMPLES>, L, V, F, D, P>
+{
+    /// Sets the mask that determines which samples are written to.
+    ///
+    /// Sample `i` is written only if bit `i` of the mask is set. Defaults to all samples.
+    pub fn sample_mask(mut self, mask: u32) -> Self {
+        if let Some(state) = self.multisample_state.as_mut() {
+            state.mask = mask;
+        }
+
+        self
+    }
+}
+
+impl<L, V, Color, D, P, const SAMPLES: u8>
+    RenderPipelineDescriptorBuilder<MultisampleState<SAMPLES>, L, V, FragmentStage<Color>, D, P>
+{
+    /// Enables or disables alpha-to-coverage.
+    ///
+    /// When enabled, the alpha channel of the fragment's first color output is used to generate
+    /// an additional sample coverage mask. Only available once a fragment stage has been
+    /// specified.
+    pub fn alpha_to_coverage(mut self, enabled: bool) -> Self {
+        let has_targets = self
+            .fragment_state
+            .as_ref()
+            .map(|f| !f.targets.is_empty())
+            .unwrap_or(false);
+
+        if enabled && !has_targets {
+            panic!("alpha-to-coverage requires the fragment stage to output at least one color");
+        }
+
+        if let Some(state) = self.multisample_state.as_mut() {
+            state.alpha_to_coverage_enabled = enabled;
+        }
+
+        self
+    }
+}
+
 impl<Layout, Vertex, Color, DepthStencil, Index>
     RenderPipelineDescriptorBuilder<
         (),
@@ -388,13 +496,50 @@ impl<Layout, Vertex, Color, DepthStencil, Index>
     pub fn finish(
         self,
     ) -> RenderPipelineDescriptor<RenderLayout<Color, DepthStencil>, Vertex, Index, Layout> {
+        let vertex_state = self.vertex_state.unwrap();
+
+        validate_stage_interface(&vertex_state, self.fragment_state.as_ref());
+
         RenderPipelineDescriptor {
-            vertex_state: self.vertex_state.unwrap(),
+            vertex_state,
             layout: self.layout.unwrap(),
             primitive_state: self.primitive_state,
             fragment_state: self.fragment_state,
             depth_stencil_state: self.depth_stencil_state,
             multisample_state: self.multisample_state,
+            multiview: self.multiview,
+            _marker: Default::default(),
+        }
+    }
+}
+
+impl<Layout, Vertex, DepthStencil, Index>
+    RenderPipelineDescriptorBuilder<
+        (),
+        PipelineLayout<Layout>,
+        VertexStage<Vertex>,
+        (),
+        DepthStencilTest<DepthStencil>,
+        PrimitiveAssembly<Index>,
+    >
+{
+    /// Finishes a pipeline without a fragment stage that only writes to a depth-stencil
+    /// attachment, e.g. for rendering a shadow map.
+    ///
+    /// The pipeline is compatible with render targets that have no color attachments (`color:
+    /// ()`); use a [fragment](Self::fragment) stage with `()` color outputs instead if the
+    /// depth-only pass needs to discard fragments (e.g. for alpha-tested geometry).
+    pub fn finish(
+        self,
+    ) -> RenderPipelineDescriptor<RenderLayout<(), DepthStencil>, Vertex, Index, Layout> {
+        RenderPipelineDescriptor {
+            vertex_state: self.vertex_state.unwrap(),
+            layout: self.layout.unwrap(),
+            primitive_state: self.primitive_state,
+            fragment_state: None,
+            depth_stencil_state: self.depth_stencil_state,
+            multisample_state: self.multisample_state,
+            multiview: self.multiview,
             _marker: Default::default(),
         }
     }
@@ -417,15 +562,155 @@ impl<Layout, Vertex, Color, DepthStencil, Index, const SAMPLES: u8>
         Vertex,
         Index,
         Layout,
+    > {
+        let vertex_state = self.vertex_state.unwrap();
+
+        validate_stage_interface(&vertex_state, self.fragment_state.as_ref());
+
+        RenderPipelineDescriptor {
+            vertex_state,
+            layout: self.layout.unwrap(),
+            primitive_state: self.primitive_state,
+            fragment_state: self.fragment_state,
+            depth_stencil_state: self.depth_stencil_state,
+            multisample_state: self.multisample_state,
+            multiview: self.multiview,
+            _marker: Default::default(),
+        }
+    }
+}
+
+impl<Layout, Vertex, DepthStencil, Index, const SAMPLES: u8>
+    RenderPipelineDescriptorBuilder<
+        MultisampleState<SAMPLES>,
+        PipelineLayout<Layout>,
+        VertexStage<Vertex>,
+        (),
+        DepthStencilTest<DepthStencil>,
+        PrimitiveAssembly<Index>,
+    >
+{
+    /// Finishes a multisample pipeline without a fragment stage that only writes to a
+    /// depth-stencil attachment.
+    pub fn finish(
+        self,
+    ) -> RenderPipelineDescriptor<
+        MultisampleRenderLayout<(), DepthStencil, SAMPLES>,
+        Vertex,
+        Index,
+        Layout,
     > {
         RenderPipelineDescriptor {
             vertex_state: self.vertex_state.unwrap(),
             layout: self.layout.unwrap(),
             primitive_state: self.primitive_state,
+            fragment_state: None,
+            depth_stencil_state: self.depth_stencil_state,
+            multisample_state: self.multisample_state,
+            multiview: self.multiview,
+            _marker: Default::default(),
+        }
+    }
+}
+
+#[cfg(not(feature = "web"))]
+impl<Layout, V, D, P, const VIEWS: u32>
+    RenderPipelineDescriptorBuilder<MultiviewState<VIEWS>, PipelineLayout<Layout>, V, (), D, P>
+where
+    Layout: TypedPipelineLayout,
+{
+    pub fn fragment<ColorLayout: TypedColorLayout>(
+        self,
+        fragment_stage: FragmentStage<ColorLayout>,
+    ) -> RenderPipelineDescriptorBuilder<
+        MultiviewState<VIEWS>,
+        PipelineLayout<Layout>,
+        V,
+        FragmentStage<ColorLayout>,
+        D,
+        P,
+    > {
+        self.fragment_internal(fragment_stage)
+    }
+}
+
+#[cfg(not(feature = "web"))]
+impl<Layout, Vertex, Color, DepthStencil, Index, const VIEWS: u32>
+    RenderPipelineDescriptorBuilder<
+        MultiviewState<VIEWS>,
+        PipelineLayout<Layout>,
+        VertexStage<Vertex>,
+        FragmentStage<Color>,
+        DepthStencilTest<DepthStencil>,
+        PrimitiveAssembly<Index>,
+    >
+{
+    pub fn finish(
+        self,
+    ) -> RenderPipelineDescriptor<
+        MultiviewRenderLayout<Color, DepthStencil, VIEWS>,
+        Vertex,
+        Index,
+        Layout,
+    > {
+        let vertex_state = self.vertex_state.unwrap();
+
+        validate_stage_interface(&vertex_state, self.fragment_state.as_ref());
+
+        RenderPipelineDescriptor {
+            vertex_state,
+            layout: self.layout.unwrap(),
+            primitive_state: self.primitive_state,
             fragment_state: self.fragment_state,
             depth_stencil_state: self.depth_stencil_state,
             multisample_state: self.multisample_state,
+            multiview: self.multiview,
+            _marker: Default::default(),
+        }
+    }
+}
+
+#[cfg(not(feature = "web"))]
+impl<Layout, Vertex, DepthStencil, Index, const VIEWS: u32>
+    RenderPipelineDescriptorBuilder<
+        MultiviewState<VIEWS>,
+        PipelineLayout<Layout>,
+        VertexStage<Vertex>,
+        (),
+        DepthStencilTest<DepthStencil>,
+        PrimitiveAssembly<Index>,
+    >
+{
+    /// Finishes a multiview pipeline without a fragment stage that only writes to a
+    /// depth-stencil attachment.
+    pub fn finish(
+        self,
+    ) -> RenderPipelineDescriptor<
+        MultiviewRenderLayout<(), DepthStencil, VIEWS>,
+        Vertex,
+        Index,
+        Layout,
+    > {
+        RenderPipelineDescriptor {
+            vertex_state: self.vertex_state.unwrap(),
+            layout: self.layout.unwrap(),
+            primitive_state: self.primitive_state,
+            fragment_state: None,
+            depth_stencil_state: self.depth_stencil_state,
+            multisample_state: self.multisample_state,
+            multiview: self.multiview,
             _marker: Default::default(),
         }
     }
 }
+
+fn validate_stage_interface(vertex_state: &VertexState, fragment_state: Option<&FragmentState>) {
+    if let Some(fragment_state) = fragment_state {
+        validate_inter_stage_interface(
+            &vertex_state.entry_point,
+            &vertex_state.outputs,
+            &fragment_state.entry_point,
+            &fragment_state.inputs,
+        );
+    }
+}
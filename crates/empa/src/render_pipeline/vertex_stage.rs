@@ -8,13 +8,16 @@ use crate::driver::{Driver, Dvr};
 use crate::pipeline_constants::PipelineConstants;
 use crate::render_pipeline::vertex_attribute::vertex_format_is_compatible;
 use crate::render_pipeline::{TypedVertexLayout, VertexBufferLayout};
-use crate::shader_module::{ShaderModule, ShaderSourceInternal};
+use crate::shader_module::{
+    EntryPointName, InterStageVariable, ShaderModule, ShaderSourceInternal, Vertex,
+};
 
 pub(crate) struct VertexState {
     pub(crate) shader_module: <Dvr as Driver>::ShaderModuleHandle,
     pub(crate) entry_point: String,
     pub(crate) constants: HashMap<String, f64>,
     pub(crate) vertex_buffer_layouts: Cow<'static, [VertexBufferLayout<'static>]>,
+    pub(crate) outputs: Vec<InterStageVariable>,
 }
 
 pub struct VertexStage<V> {
@@ -30,7 +33,16 @@ pub struct VertexStageBuilder<V> {
 }
 
 impl VertexStageBuilder<()> {
-    pub fn begin(shader_module: &ShaderModule, entry_point: &str) -> Self {
+    /// Begins building a vertex stage for the `entry_point` in the `shader_module`.
+    ///
+    /// The `entry_point` may be an entry point name, or a typed
+    /// [VertexEntryPoint](crate::shader_module::VertexEntryPoint) declared with the
+    /// [shader_entry_points](crate::shader_module::shader_entry_points) macro.
+    pub fn begin<E>(shader_module: &ShaderModule, entry_point: E) -> Self
+    where
+        E: EntryPointName<Vertex>,
+    {
+        let entry_point = entry_point.entry_point_name();
         let shader_meta = shader_module.meta.clone();
 
         let entry_index = shader_meta
@@ -43,6 +55,12 @@ impl VertexStageBuilder<()> {
             "entry point is not a vertex stage"
         );
 
+        let outputs = shader_meta
+            .entry_point_output_bindings(entry_index)
+            .unwrap()
+            .map(|binding| binding.to_inter_stage_variable())
+            .collect();
+
         VertexStageBuilder {
             inner: VertexStage {
                 vertex_state: VertexState {
@@ -50,6 +68,7 @@ impl VertexStageBuilder<()> {
                     entry_point: entry_point.to_string(),
                     constants: Default::default(),
                     vertex_buffer_layouts: Cow::Owned(Vec::new()),
+                    outputs,
                 },
                 shader_meta,
                 entry_index,
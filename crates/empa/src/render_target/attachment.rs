@@ -2,13 +2,17 @@ use crate::driver::{
     DepthStencilOperations, RenderPassColorAttachment, RenderPassDepthStencilAttachment,
 };
 use crate::render_target::{
-    ColorTargetEncoding, DepthStencilTargetEncoding, DepthValue, LoadOp, StoreOp, TypedColorLayout,
+    ColorAttachmentCount, ColorTargetEncoding, DepthStencilTargetEncoding, DepthValue, LoadOp,
+    ReadOnly, StoreOp, TypedColorLayout, TypedDepthStencilLayout,
 };
 use crate::texture::format::{
-    ColorRenderable, CombinedDepthStencilRenderable, DepthRenderable, DepthStencilRenderable,
+    ColorRenderable, CombinedDepthStencilRenderable, DepthRenderable, DepthSamplable,
     FloatRenderable, SignedIntegerRenderable, StencilRenderable, UnsignedIntegerRenderable,
 };
-use crate::texture::AttachableImage;
+use crate::texture::{
+    AttachableImage, AttachableImageDescriptor, RenderAttachment, Sampled2DDepth, Texture2D,
+    TextureBinding, View2DDescriptor,
+};
 
 mod color_targets_seal {
     pub trait Seal {}
@@ -53,6 +57,24 @@ impl_color_targets!(6, A0, A1, A2, A3, A4, A5);
 impl_color_targets!(7, A0, A1, A2, A3, A4, A5, A6);
 impl_color_targets!(8, A0, A1, A2, A3, A4, A5, A6, A7);
 
+impl<A, const N: usize> color_targets_seal::Seal for [A; N] where A: ColorTarget {}
+impl<A, const N: usize> ColorTargets for [A; N]
+where
+    A: ColorTarget,
+{
+    type Layout = [A::Format; N];
+    type Encodings<'a>
+        = [ColorTargetEncoding<'a>; N]
+    where
+        Self: 'a;
+
+    fn encodings<'a>(&'a self) -> Self::Encodings<'a> {
+        let _ = ColorAttachmentCount::<N>::ASSERT_SUPPORTED;
+
+        self.each_ref().map(|target| target.to_encoding())
+    }
+}
+
 mod color_target_seal {
     pub trait Seal {}
 }
@@ -63,6 +85,11 @@ pub trait ColorTarget: color_target_seal::Seal {
     fn to_encoding(&self) -> ColorTargetEncoding;
 }
 
+/// A color attachment for a format that stores floating point or normalized values.
+///
+/// The attachment is cleared to raw `f32` values; see
+/// [ClearColor](crate::render_target::ClearColor) for clearing to a color independent of the
+/// format's encoding.
 pub struct FloatAttachment<'a, F>
 where
     F: FloatRenderable,
@@ -95,11 +122,17 @@ where
             }),
             width: image.width,
             height: image.height,
+            layers: image.layers,
             _marker: Default::default(),
         }
     }
 }
 
+/// A color attachment for a format that stores signed integer values (e.g. `r32sint`).
+///
+/// The attachment is cleared to `i32` values. Integer formats cannot be blended: a pipeline must
+/// output to the attachment with a [ColorOutput](crate::render_pipeline::ColorOutput) rather than
+/// a [BlendedColorOutput](crate::render_pipeline::BlendedColorOutput).
 pub struct SignedIntegerAttachment<'a, F>
 where
     F: SignedIntegerRenderable,
@@ -135,11 +168,18 @@ where
             }),
             width: image.width,
             height: image.height,
+            layers: image.layers,
             _marker: Default::default(),
         }
     }
 }
 
+/// A color attachment for a format that stores unsigned integer values (e.g. `r32uint`), such as
+/// an object ID buffer for picking.
+///
+/// The attachment is cleared to `u32` values. Integer formats cannot be blended: a pipeline must
+/// output to the attachment with a [ColorOutput](crate::render_pipeline::ColorOutput) rather than
+/// a [BlendedColorOutput](crate::render_pipeline::BlendedColorOutput).
 pub struct UnsignedIntegerAttachment<'a, F>
 where
     F: UnsignedIntegerRenderable,
@@ -175,6 +215,7 @@ where
             }),
             width: image.width,
             height: image.height,
+            layers: image.layers,
             _marker: Default::default(),
         }
     }
@@ -185,11 +226,17 @@ mod depth_stencil_target_seal {
 }
 
 pub trait DepthStencilTarget: depth_stencil_target_seal::Seal {
-    type Format: DepthStencilRenderable;
+    type Format: TypedDepthStencilLayout;
 
     fn to_encoding(&self) -> DepthStencilTargetEncoding;
 }
 
+/// Shorthand for [SignedIntegerAttachment].
+pub type IntAttachment<'a, F> = SignedIntegerAttachment<'a, F>;
+
+/// Shorthand for [UnsignedIntegerAttachment].
+pub type UintAttachment<'a, F> = UnsignedIntegerAttachment<'a, F>;
+
 pub struct DepthStencilAttachment<'a, F>
 where
     F: CombinedDepthStencilRenderable,
@@ -234,6 +281,7 @@ where
             }),
             width: image.width,
             height: image.height,
+            layers: image.layers,
             _marker: Default::default(),
         }
     }
@@ -267,6 +315,7 @@ where
             }),
             width: image.width,
             height: image.height,
+            layers: image.layers,
             _marker: Default::default(),
         }
     }
@@ -281,6 +330,56 @@ where
     pub store_op: StoreOp,
 }
 
+impl<'a, F> DepthAttachment<'a, F>
+where
+    F: DepthRenderable,
+{
+    /// Creates a depth attachment for the base mipmap level of the given `layer` of a layered
+    /// `texture`.
+    ///
+    /// Allows rendering to the layers of a single depth texture one pass at a time, e.g. one pass
+    /// per cascade of a cascaded shadow map. The texture may then be bound as a whole with
+    /// [Texture2D::sampled_array_depth].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `layer` is out of bounds.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// for cascade in 0..CASCADE_COUNT {
+    ///     encoder = encoder
+    ///         .begin_render_pass(RenderPassDescriptor::new(&RenderTarget {
+    ///             color: (),
+    ///             depth_stencil: DepthAttachment::layer(
+    ///                 &shadow_maps,
+    ///                 cascade,
+    ///                 LoadOp::Clear(DepthValue::ONE),
+    ///                 StoreOp::Store,
+    ///             ),
+    ///         }))
+    ///         .execute_bundle(&cascade_bundles[cascade as usize])
+    ///         .end();
+    /// }
+    /// ```
+    pub fn layer<U>(
+        texture: &'a Texture2D<F, U>,
+        layer: u32,
+        load_op: LoadOp<DepthValue>,
+        store_op: StoreOp,
+    ) -> Self
+    where
+        U: RenderAttachment,
+    {
+        DepthAttachment {
+            image: texture.attachable_image_layer(layer),
+            load_op,
+            store_op,
+        }
+    }
+}
+
 impl<'a, F> depth_stencil_target_seal::Seal for DepthAttachment<'a, F> where F: DepthRenderable {}
 impl<'a, F> DepthStencilTarget for DepthAttachment<'a, F>
 where
@@ -306,6 +405,7 @@ where
             }),
             width: image.width,
             height: image.height,
+            layers: image.layers,
             _marker: Default::default(),
         }
     }
@@ -339,6 +439,112 @@ where
             }),
             width: image.width,
             height: image.height,
+            layers: image.layers,
+            _marker: Default::default(),
+        }
+    }
+}
+
+/// A depth attachment that is only read during the render pass.
+///
+/// Unlike [ReadOnlyDepthAttachment], the render layout of a pass with this attachment records the
+/// depth aspect as read-only (`ReadOnly<F>`): only pipelines created with
+/// [DepthStencilTest::read_only] may be set, and only render bundles recorded with
+/// [depth_stencil_format_read_only] may be executed.
+///
+/// Because the pass does not write to the depth texture, WebGPU's usage scope rules allow the same
+/// texture to also be bound as a sampled texture in the pass, see [with_sampled_view].
+///
+/// [DepthStencilTest::read_only]: crate::render_pipeline::DepthStencilTest::read_only
+/// [depth_stencil_format_read_only]: crate::command::RenderBundleEncoderDescriptor::depth_stencil_format_read_only
+/// [with_sampled_view]: DepthReadOnlyAttachment::with_sampled_view
+pub struct DepthReadOnlyAttachment<'a, F>
+where
+    F: DepthRenderable,
+{
+    pub image: AttachableImage<'a, F>,
+}
+
+impl<'a, F> DepthReadOnlyAttachment<'a, F>
+where
+    F: DepthRenderable + DepthSamplable,
+{
+    /// Creates a read-only depth attachment for the image of the `texture` selected by the
+    /// `descriptor`, together with a sampled view of that same image.
+    ///
+    /// A texture that is used as a depth attachment may only be sampled in the same render pass if
+    /// the attachment is read-only; binding the sampled view in a pass that writes to the texture
+    /// is a validation error. This pairs the two such that the sampled view can only be obtained
+    /// for a read-only attachment, e.g. for soft particles that fade out near the scene's depth
+    /// after a depth pre-pass.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `descriptor`'s layer or mipmap level is out of bounds.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let (depth_attachment, scene_depth) = DepthReadOnlyAttachment::with_sampled_view(
+    ///     &depth_texture,
+    ///     &AttachableImageDescriptor::default(),
+    /// );
+    ///
+    /// let particle_bind_group = device.create_bind_group(
+    ///     &particle_bind_group_layout,
+    ///     ParticleResources { scene_depth, ... },
+    /// );
+    ///
+    /// encoder = encoder
+    ///     .begin_render_pass(RenderPassDescriptor::new(&RenderTarget {
+    ///         color: FloatAttachment { ... },
+    ///         depth_stencil: depth_attachment,
+    ///     }))
+    ///     .set_pipeline(&particle_pipeline) // Uses `DepthStencilTest::read_only`
+    ///     .set_bind_groups(&particle_bind_group)
+    ///     ...
+    ///     .end();
+    /// ```
+    pub fn with_sampled_view<U>(
+        texture: &'a Texture2D<F, U>,
+        descriptor: &AttachableImageDescriptor,
+    ) -> (Self, Sampled2DDepth<'a>)
+    where
+        U: RenderAttachment + TextureBinding,
+    {
+        let image = texture.attachable_image(descriptor);
+        let sampled = texture.sampled_depth(&View2DDescriptor {
+            layer: descriptor.layer,
+            base_mipmap_level: descriptor.mipmap_level,
+            mipmap_level_count: Some(1),
+        });
+
+        (DepthReadOnlyAttachment { image }, sampled)
+    }
+}
+
+impl<'a, F> depth_stencil_target_seal::Seal for DepthReadOnlyAttachment<'a, F> where
+    F: DepthRenderable
+{
+}
+impl<'a, F> DepthStencilTarget for DepthReadOnlyAttachment<'a, F>
+where
+    F: DepthRenderable,
+{
+    type Format = ReadOnly<F>;
+
+    fn to_encoding(&self) -> DepthStencilTargetEncoding {
+        let DepthReadOnlyAttachment { image } = self;
+
+        DepthStencilTargetEncoding {
+            inner: Some(RenderPassDepthStencilAttachment {
+                view: image.inner.clone(),
+                depth_operations: None,
+                stencil_operations: None,
+            }),
+            width: image.width,
+            height: image.height,
+            layers: image.layers,
             _marker: Default::default(),
         }
     }
@@ -378,6 +584,7 @@ where
             }),
             width: image.width,
             height: image.height,
+            layers: image.layers,
             _marker: Default::default(),
         }
     }
@@ -411,6 +618,7 @@ where
             }),
             width: image.width,
             height: image.height,
+            layers: image.layers,
             _marker: Default::default(),
         }
     }
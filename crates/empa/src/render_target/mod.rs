@@ -13,6 +13,8 @@ use std::{fmt, marker};
 
 pub use self::render_target::*;
 use crate::driver::{Dvr, RenderPassColorAttachment, RenderPassDepthStencilAttachment};
+use crate::texture::format::{TextureFormat, TextureFormatId};
+use crate::CompareFunction;
 
 #[derive(Clone, Copy, PartialEq, Debug)]
 pub struct InvalidDepthValue(f32);
@@ -50,12 +52,196 @@ impl TryFrom<f32> for DepthValue {
     }
 }
 
+/// A color that a color attachment is cleared to, independent of the attachment's encoding.
+///
+/// The raw clear value of a [FloatAttachment] is stored as-is, so the same value results in a
+/// different displayed color for an sRGB format (which encodes the value as it is written) than
+/// for a non-sRGB format. A [ClearColor] is encoded for the attachment's format with
+/// [encode](Self::encode), such that the attachment displays the same color in either case.
+///
+/// # Example
+///
+/// ```ignore
+/// let attachment = FloatAttachment {
+///     image: frame.attachable_image(&AttachableImageDescriptor::default()),
+///     load_op: LoadOp::Clear(ClearColor::from_srgb8(100, 149, 237, 255)).encode::<bgra8unorm>(),
+///     store_op: StoreOp::Store,
+/// };
+/// ```
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ClearColor {
+    linear: [f32; 4],
+}
+
+impl ClearColor {
+    pub const TRANSPARENT: ClearColor = ClearColor::from_linear(0.0, 0.0, 0.0, 0.0);
+
+    pub const BLACK: ClearColor = ClearColor::from_linear(0.0, 0.0, 0.0, 1.0);
+
+    pub const WHITE: ClearColor = ClearColor::from_linear(1.0, 1.0, 1.0, 1.0);
+
+    /// Creates a clear color from linear color components and a (linear) alpha value.
+    pub const fn from_linear(r: f32, g: f32, b: f32, a: f32) -> Self {
+        ClearColor {
+            linear: [r, g, b, a],
+        }
+    }
+
+    /// Creates a clear color from sRGB-encoded color components in the range `0.0..=1.0` and a
+    /// (linear) alpha value.
+    pub fn from_srgb(r: f32, g: f32, b: f32, a: f32) -> Self {
+        ClearColor {
+            linear: [srgb_to_linear(r), srgb_to_linear(g), srgb_to_linear(b), a],
+        }
+    }
+
+    /// Creates a clear color from 8-bit sRGB-encoded color components and an 8-bit (linear) alpha
+    /// value, e.g. as picked from a color picker.
+    pub fn from_srgb8(r: u8, g: u8, b: u8, a: u8) -> Self {
+        ClearColor::from_srgb(
+            r as f32 / 255.0,
+            g as f32 / 255.0,
+            b as f32 / 255.0,
+            a as f32 / 255.0,
+        )
+    }
+
+    /// Returns the linear color components and alpha value.
+    pub fn to_linear(&self) -> [f32; 4] {
+        self.linear
+    }
+
+    /// Returns the sRGB-encoded color components and the (linear) alpha value.
+    pub fn to_srgb(&self) -> [f32; 4] {
+        let [r, g, b, a] = self.linear;
+
+        [linear_to_srgb(r), linear_to_srgb(g), linear_to_srgb(b), a]
+    }
+
+    /// Returns the raw clear value for an attachment of format `F`.
+    ///
+    /// Returns the sRGB-encoded color for normalized formats that are not sRGB formats (such as
+    /// `bgra8unorm`), as these are displayed as sRGB-encoded color. Returns the linear color for
+    /// sRGB formats (which encode the value when it is written) and for floating point formats
+    /// (which are assumed to hold linear color, as is the case for HDR render targets).
+    pub fn encode<F>(&self) -> [f32; 4]
+    where
+        F: TextureFormat,
+    {
+        if stores_display_encoded_color(F::FORMAT_ID) {
+            self.to_srgb()
+        } else {
+            self.to_linear()
+        }
+    }
+}
+
+fn srgb_to_linear(value: f32) -> f32 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(value: f32) -> f32 {
+    if value <= 0.0031308 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+fn stores_display_encoded_color(format: TextureFormatId) -> bool {
+    match format {
+        TextureFormatId::r8unorm
+        | TextureFormatId::rg8unorm
+        | TextureFormatId::rgba8unorm
+        | TextureFormatId::bgra8unorm
+        | TextureFormatId::rgb10a2unorm => true,
+        #[cfg(feature = "norm16")]
+        TextureFormatId::r16unorm | TextureFormatId::rg16unorm | TextureFormatId::rgba16unorm => {
+            true
+        }
+        _ => false,
+    }
+}
+
+/// The mapping of view-space distance to depth values used by a renderer.
+///
+/// With [DepthConvention::Standard], the near plane maps to a depth of `0.0` and the far plane
+/// maps to `1.0`; with [DepthConvention::ReverseZ], this mapping is reversed, which distributes
+/// the floating point precision of the depth buffer far more evenly over the view distance
+/// (particularly with a `depth32float` depth buffer).
+///
+/// The convention must be applied consistently to the depth test of the pipeline (see
+/// [DepthStencilTest::depth_convention](crate::render_pipeline::DepthStencilTest::depth_convention)),
+/// to the clear value of the depth attachment (see [DepthConvention::clear_value]) and to the
+/// projection matrix.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
+pub enum DepthConvention {
+    #[default]
+    Standard,
+    ReverseZ,
+}
+
+impl DepthConvention {
+    /// The compare function for a depth test that passes for fragments that are closer to the
+    /// viewer than the current depth value.
+    pub const fn depth_compare(&self) -> CompareFunction {
+        match self {
+            DepthConvention::Standard => CompareFunction::Less,
+            DepthConvention::ReverseZ => CompareFunction::Greater,
+        }
+    }
+
+    /// The compare function for a depth test that also passes for fragments at the same depth as
+    /// the current depth value, e.g. for a pass that re-renders geometry after a depth pre-pass.
+    pub const fn depth_compare_or_equal(&self) -> CompareFunction {
+        match self {
+            DepthConvention::Standard => CompareFunction::LessEqual,
+            DepthConvention::ReverseZ => CompareFunction::GreaterEqual,
+        }
+    }
+
+    /// The depth value of the far plane, which a depth attachment should be cleared to.
+    pub const fn clear_value(&self) -> DepthValue {
+        match self {
+            DepthConvention::Standard => DepthValue::ONE,
+            DepthConvention::ReverseZ => DepthValue::ZERO,
+        }
+    }
+
+    /// The depth value of the near plane.
+    pub const fn near_value(&self) -> DepthValue {
+        match self {
+            DepthConvention::Standard => DepthValue::ZERO,
+            DepthConvention::ReverseZ => DepthValue::ONE,
+        }
+    }
+}
+
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum LoadOp<T> {
     Load,
     Clear(T),
 }
 
+impl LoadOp<ClearColor> {
+    /// Encodes the clear color for an attachment of format `F`; see [ClearColor::encode].
+    pub fn encode<F>(&self) -> LoadOp<[f32; 4]>
+    where
+        F: TextureFormat,
+    {
+        match self {
+            LoadOp::Load => LoadOp::Load,
+            LoadOp::Clear(color) => LoadOp::Clear(color.encode::<F>()),
+        }
+    }
+}
+
 impl LoadOp<[f32; 4]> {
     pub(crate) fn to_4xf64(&self) -> LoadOp<[f64; 4]> {
         match self {
@@ -99,6 +285,7 @@ impl LoadOp<DepthValue> {
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum StoreOp {
     Store,
     Discard,
@@ -108,6 +295,7 @@ pub struct ColorTargetEncoding<'a> {
     pub(crate) inner: Option<RenderPassColorAttachment<Dvr>>,
     pub(crate) width: u32,
     pub(crate) height: u32,
+    pub(crate) layers: u32,
     _marker: marker::PhantomData<&'a ()>,
 }
 
@@ -115,5 +303,6 @@ pub struct DepthStencilTargetEncoding<'a> {
     pub(crate) inner: Option<RenderPassDepthStencilAttachment<Dvr>>,
     pub(crate) width: u32,
     pub(crate) height: u32,
+    pub(crate) layers: u32,
     _marker: marker::PhantomData<&'a ()>,
 }
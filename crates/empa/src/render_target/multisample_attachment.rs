@@ -2,8 +2,8 @@ use crate::driver::{
     DepthStencilOperations, RenderPassColorAttachment, RenderPassDepthStencilAttachment,
 };
 use crate::render_target::{
-    ColorTargetEncoding, DepthStencilTargetEncoding, DepthValue, LoadOp, StoreOp,
-    TypedMultisampleColorLayout,
+    ColorAttachmentCount, ColorTargetEncoding, DepthStencilTargetEncoding, DepthValue, LoadOp,
+    StoreOp, TypedMultisampleColorLayout,
 };
 use crate::texture::format::{
     CombinedDepthStencilRenderable, DepthRenderable, DepthStencilRenderable,
@@ -57,6 +57,27 @@ impl_multisample_color_targets!(6, A0, A1, A2, A3, A4, A5);
 impl_multisample_color_targets!(7, A0, A1, A2, A3, A4, A5, A6);
 impl_multisample_color_targets!(8, A0, A1, A2, A3, A4, A5, A6, A7);
 
+impl<A, const N: usize, const SAMPLES: u8> multisample_color_targets_seal::Seal<SAMPLES> for [A; N] where
+    A: MultisampleColorTarget<SAMPLES>
+{
+}
+impl<A, const N: usize, const SAMPLES: u8> MultisampleColorTargets<SAMPLES> for [A; N]
+where
+    A: MultisampleColorTarget<SAMPLES>,
+{
+    type Layout = [A::Format; N];
+    type Encodings<'a>
+        = [ColorTargetEncoding<'a>; N]
+    where
+        Self: 'a;
+
+    fn encodings<'a>(&'a self) -> Self::Encodings<'a> {
+        let _ = ColorAttachmentCount::<N>::ASSERT_SUPPORTED;
+
+        self.each_ref().map(|target| target.to_encoding())
+    }
+}
+
 mod multisample_color_target_seal {
     pub trait Seal {}
 }
@@ -105,6 +126,7 @@ where
             }),
             width: image.width,
             height: image.height,
+            layers: 1,
             _marker: Default::default(),
         }
     }
@@ -154,6 +176,7 @@ where
             }),
             width: image.width,
             height: image.height,
+            layers: 1,
             _marker: Default::default(),
         }
     }
@@ -197,6 +220,7 @@ where
             }),
             width: image.width,
             height: image.height,
+            layers: 1,
             _marker: Default::default(),
         }
     }
@@ -240,6 +264,7 @@ where
             }),
             width: image.width,
             height: image.height,
+            layers: 1,
             _marker: Default::default(),
         }
     }
@@ -304,6 +329,7 @@ where
             }),
             width: image.width,
             height: image.height,
+            layers: 1,
             _marker: Default::default(),
         }
     }
@@ -340,6 +366,7 @@ where
             }),
             width: image.width,
             height: image.height,
+            layers: 1,
             _marker: Default::default(),
         }
     }
@@ -385,6 +412,7 @@ where
             }),
             width: image.width,
             height: image.height,
+            layers: 1,
             _marker: Default::default(),
         }
     }
@@ -421,6 +449,7 @@ where
             }),
             width: image.width,
             height: image.height,
+            layers: 1,
             _marker: Default::default(),
         }
     }
@@ -466,6 +495,7 @@ where
             }),
             width: image.width,
             height: image.height,
+            layers: 1,
             _marker: Default::default(),
         }
     }
@@ -502,6 +532,7 @@ where
             }),
             width: image.width,
             height: image.height,
+            layers: 1,
             _marker: Default::default(),
         }
     }
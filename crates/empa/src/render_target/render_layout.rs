@@ -1,4 +1,5 @@
 use std::marker;
+use std::num::NonZeroU32;
 
 use crate::texture::format::{
     ColorRenderable, DepthStencilRenderable, MultisampleColorRenderable, TextureFormatId,
@@ -8,6 +9,7 @@ pub struct RenderLayoutDescriptor<'a> {
     pub color_layout: &'a [TextureFormatId],
     pub depth_stencil_layout: Option<DepthStencilLayout>,
     pub samples: u8,
+    pub multiview: Option<NonZeroU32>,
 }
 
 pub struct RenderLayout<C, Ds> {
@@ -18,6 +20,40 @@ pub struct MultisampleRenderLayout<C, Ds, const SAMPLES: u8> {
     _marker: marker::PhantomData<(C, Ds)>,
 }
 
+pub(crate) struct SampleCount<const SAMPLES: u8>;
+
+impl<const SAMPLES: u8> SampleCount<SAMPLES> {
+    /// Fails to compile when `SAMPLES` is not a supported sample count.
+    ///
+    /// WebGPU only supports a sample count of `4`; native targets additionally support `8` for
+    /// formats that the adapter reports as 8x multisample capable.
+    pub(crate) const ASSERT_SUPPORTED: () = assert!(
+        SAMPLES == 4 || (!cfg!(feature = "web") && SAMPLES == 8),
+        "unsupported multisample sample count"
+    );
+}
+
+pub(crate) struct ColorAttachmentCount<const N: usize>;
+
+impl<const N: usize> ColorAttachmentCount<N> {
+    /// Fails to compile when `N` is not a supported number of color attachments.
+    ///
+    /// WebGPU guarantees support for at least `8` color attachments; larger arrays are rejected
+    /// so that layouts are portable across devices.
+    pub(crate) const ASSERT_SUPPORTED: () = assert!(
+        N >= 1 && N <= 8,
+        "the number of color attachments must be between `1` and `8`"
+    );
+}
+
+/// Layout for a render target that renders to `VIEWS` array layers simultaneously.
+///
+/// Only available on native targets; requires [Feature::Multiview](crate::adapter::Feature).
+#[cfg(not(feature = "web"))]
+pub struct MultiviewRenderLayout<C, Ds, const VIEWS: u32> {
+    _marker: marker::PhantomData<(C, Ds)>,
+}
+
 mod typed_color_layout_seal {
     pub trait Seal {}
 }
@@ -52,6 +88,18 @@ impl_typed_color_layout!(C0, C1, C2, C3, C4, C5);
 impl_typed_color_layout!(C0, C1, C2, C3, C4, C5, C6);
 impl_typed_color_layout!(C0, C1, C2, C3, C4, C5, C6, C7);
 
+impl<C, const N: usize> typed_color_layout_seal::Seal for [C; N] where C: ColorRenderable {}
+impl<C, const N: usize> TypedColorLayout for [C; N]
+where
+    C: ColorRenderable,
+{
+    const COLOR_FORMATS: &'static [TextureFormatId] = {
+        let _ = ColorAttachmentCount::<N>::ASSERT_SUPPORTED;
+
+        &[C::FORMAT_ID; N]
+    };
+}
+
 mod typed_multisample_color_layout_seal {
     pub trait Seal {}
 }
@@ -86,6 +134,21 @@ impl_typed_multisample_color_layout!(C0, C1, C2, C3, C4, C5);
 impl_typed_multisample_color_layout!(C0, C1, C2, C3, C4, C5, C6);
 impl_typed_multisample_color_layout!(C0, C1, C2, C3, C4, C5, C6, C7);
 
+impl<C, const N: usize> typed_multisample_color_layout_seal::Seal for [C; N] where
+    C: MultisampleColorRenderable
+{
+}
+impl<C, const N: usize> TypedMultisampleColorLayout for [C; N]
+where
+    C: MultisampleColorRenderable,
+{
+    const COLOR_FORMATS: &'static [TextureFormatId] = {
+        let _ = ColorAttachmentCount::<N>::ASSERT_SUPPORTED;
+
+        &[C::FORMAT_ID; N]
+    };
+}
+
 mod typed_depth_stencil_layout_seal {
     pub trait Seal {}
 }
@@ -155,6 +218,7 @@ where
         color_layout: C::COLOR_FORMATS,
         depth_stencil_layout: Ds::LAYOUT,
         samples: 1,
+        multiview: None,
     };
 }
 
@@ -174,6 +238,28 @@ where
         color_layout: C::COLOR_FORMATS,
         depth_stencil_layout: Ds::LAYOUT,
         samples: SAMPLES,
+        multiview: None,
+    };
+}
+
+#[cfg(not(feature = "web"))]
+impl<C, Ds, const VIEWS: u32> typed_render_layout_seal::Seal for MultiviewRenderLayout<C, Ds, VIEWS>
+where
+    C: TypedColorLayout,
+    Ds: TypedDepthStencilLayout,
+{
+}
+#[cfg(not(feature = "web"))]
+impl<C, Ds, const VIEWS: u32> TypedRenderLayout for MultiviewRenderLayout<C, Ds, VIEWS>
+where
+    C: TypedColorLayout,
+    Ds: TypedDepthStencilLayout,
+{
+    const LAYOUT: RenderLayoutDescriptor<'static> = RenderLayoutDescriptor {
+        color_layout: C::COLOR_FORMATS,
+        depth_stencil_layout: Ds::LAYOUT,
+        samples: 1,
+        multiview: NonZeroU32::new(VIEWS),
     };
 }
 
@@ -202,3 +288,28 @@ impl<C, Ds0, Ds1> RenderLayoutCompatible<RenderLayout<C, Ds0>> for RenderLayout<
     Ds1: depth_stencil_layout_compatible_seal::DepthStencilLayoutCompatible<Ds0>
 {
 }
+
+impl<C, Ds, const SAMPLES: u8> render_layout_compatible_seal::Seal
+    for MultisampleRenderLayout<C, Ds, SAMPLES>
+{
+}
+impl<C, Ds0, Ds1, const SAMPLES: u8>
+    RenderLayoutCompatible<MultisampleRenderLayout<C, Ds0, SAMPLES>>
+    for MultisampleRenderLayout<C, Ds1, SAMPLES>
+where
+    Ds1: depth_stencil_layout_compatible_seal::DepthStencilLayoutCompatible<Ds0>,
+{
+}
+
+#[cfg(not(feature = "web"))]
+impl<C, Ds, const VIEWS: u32> render_layout_compatible_seal::Seal
+    for MultiviewRenderLayout<C, Ds, VIEWS>
+{
+}
+#[cfg(not(feature = "web"))]
+impl<C, Ds0, Ds1, const VIEWS: u32> RenderLayoutCompatible<MultiviewRenderLayout<C, Ds0, VIEWS>>
+    for MultiviewRenderLayout<C, Ds1, VIEWS>
+where
+    Ds1: depth_stencil_layout_compatible_seal::DepthStencilLayoutCompatible<Ds0>,
+{
+}
@@ -1,6 +1,8 @@
 use crate::render_target::multisample_attachment::{
     MultisampleColorTargets, MultisampleDepthStencilTarget,
 };
+#[cfg(not(feature = "web"))]
+use crate::render_target::MultiviewRenderLayout;
 use crate::render_target::{
     ColorTargetEncoding, ColorTargets, DepthStencilTarget, DepthStencilTargetEncoding,
     MultisampleRenderLayout, RenderLayout, TypedRenderLayout,
@@ -40,7 +42,10 @@ where
 {
     type RenderLayout = RenderLayout<C::Layout, Ds::Format>;
 
-    type ColorTargetEncodings<'a> = C::Encodings<'a> where Self: 'a;
+    type ColorTargetEncodings<'a>
+        = C::Encodings<'a>
+    where
+        Self: 'a;
 
     fn color_target_encodings<'a>(&'a self) -> Self::ColorTargetEncodings<'a> {
         self.color.encodings()
@@ -58,7 +63,10 @@ where
 {
     type RenderLayout = RenderLayout<C::Layout, ()>;
 
-    type ColorTargetEncodings<'a> = C::Encodings<'a> where C: 'a;
+    type ColorTargetEncodings<'a>
+        = C::Encodings<'a>
+    where
+        C: 'a;
 
     fn color_target_encodings<'a>(&'a self) -> Self::ColorTargetEncodings<'a> {
         self.color.encodings()
@@ -69,6 +77,7 @@ where
             inner: None,
             width: 0,
             height: 0,
+            layers: 0,
             _marker: Default::default(),
         }
     }
@@ -81,7 +90,10 @@ where
 {
     type RenderLayout = RenderLayout<(), Ds::Format>;
 
-    type ColorTargetEncodings<'a> = [ColorTargetEncoding<'a>; 0] where Ds: 'a;
+    type ColorTargetEncodings<'a>
+        = [ColorTargetEncoding<'a>; 0]
+    where
+        Ds: 'a;
 
     fn color_target_encodings<'a>(&'a self) -> Self::ColorTargetEncodings<'a> {
         []
@@ -111,7 +123,10 @@ where
 {
     type RenderLayout = MultisampleRenderLayout<C::Layout, Ds::Format, SAMPLES>;
 
-    type ColorTargetEncodings<'a> = C::Encodings<'a> where Self: 'a;
+    type ColorTargetEncodings<'a>
+        = C::Encodings<'a>
+    where
+        Self: 'a;
 
     fn color_target_encodings<'a>(&'a self) -> Self::ColorTargetEncodings<'a> {
         self.color.encodings()
@@ -134,7 +149,10 @@ where
 {
     type RenderLayout = MultisampleRenderLayout<C::Layout, (), SAMPLES>;
 
-    type ColorTargetEncodings<'a> = C::Encodings<'a> where C: 'a;
+    type ColorTargetEncodings<'a>
+        = C::Encodings<'a>
+    where
+        C: 'a;
 
     fn color_target_encodings<'a>(&'a self) -> Self::ColorTargetEncodings<'a> {
         self.color.encodings()
@@ -145,6 +163,7 @@ where
             inner: None,
             width: 0,
             height: 0,
+            layers: 0,
             _marker: Default::default(),
         }
     }
@@ -162,7 +181,108 @@ where
 {
     type RenderLayout = MultisampleRenderLayout<(), Ds::Format, SAMPLES>;
 
-    type ColorTargetEncodings<'a> = [ColorTargetEncoding<'a>; 0] where Ds: 'a;
+    type ColorTargetEncodings<'a>
+        = [ColorTargetEncoding<'a>; 0]
+    where
+        Ds: 'a;
+
+    fn color_target_encodings<'a>(&'a self) -> Self::ColorTargetEncodings<'a> {
+        []
+    }
+
+    fn depth_stencil_target_encoding(&self) -> DepthStencilTargetEncoding {
+        self.depth_stencil.to_encoding()
+    }
+}
+
+/// A render target that renders to `VIEWS` array layers simultaneously.
+///
+/// The attachments must be created with
+/// [Texture2D::attachable_image_multiview](crate::texture::Texture2D::attachable_image_multiview)
+/// with a matching view count.
+#[cfg(not(feature = "web"))]
+pub struct MultiviewRenderTarget<C, Ds, const VIEWS: u32> {
+    pub color: C,
+    pub depth_stencil: Ds,
+}
+
+#[cfg(not(feature = "web"))]
+impl<C, Ds, const VIEWS: u32> valid_render_target_seal::Seal for MultiviewRenderTarget<C, Ds, VIEWS>
+where
+    C: ColorTargets,
+    Ds: DepthStencilTarget,
+{
+}
+#[cfg(not(feature = "web"))]
+impl<C, Ds, const VIEWS: u32> ValidRenderTarget for MultiviewRenderTarget<C, Ds, VIEWS>
+where
+    C: ColorTargets,
+    Ds: DepthStencilTarget,
+{
+    type RenderLayout = MultiviewRenderLayout<C::Layout, Ds::Format, VIEWS>;
+
+    type ColorTargetEncodings<'a>
+        = C::Encodings<'a>
+    where
+        Self: 'a;
+
+    fn color_target_encodings<'a>(&'a self) -> Self::ColorTargetEncodings<'a> {
+        self.color.encodings()
+    }
+
+    fn depth_stencil_target_encoding(&self) -> DepthStencilTargetEncoding {
+        self.depth_stencil.to_encoding()
+    }
+}
+
+#[cfg(not(feature = "web"))]
+impl<C, const VIEWS: u32> valid_render_target_seal::Seal for MultiviewRenderTarget<C, (), VIEWS> where
+    C: ColorTargets
+{
+}
+#[cfg(not(feature = "web"))]
+impl<C, const VIEWS: u32> ValidRenderTarget for MultiviewRenderTarget<C, (), VIEWS>
+where
+    C: ColorTargets,
+{
+    type RenderLayout = MultiviewRenderLayout<C::Layout, (), VIEWS>;
+
+    type ColorTargetEncodings<'a>
+        = C::Encodings<'a>
+    where
+        C: 'a;
+
+    fn color_target_encodings<'a>(&'a self) -> Self::ColorTargetEncodings<'a> {
+        self.color.encodings()
+    }
+
+    fn depth_stencil_target_encoding(&self) -> DepthStencilTargetEncoding {
+        DepthStencilTargetEncoding {
+            inner: None,
+            width: 0,
+            height: 0,
+            layers: 0,
+            _marker: Default::default(),
+        }
+    }
+}
+
+#[cfg(not(feature = "web"))]
+impl<Ds, const VIEWS: u32> valid_render_target_seal::Seal for MultiviewRenderTarget<(), Ds, VIEWS> where
+    Ds: DepthStencilTarget
+{
+}
+#[cfg(not(feature = "web"))]
+impl<Ds, const VIEWS: u32> ValidRenderTarget for MultiviewRenderTarget<(), Ds, VIEWS>
+where
+    Ds: DepthStencilTarget,
+{
+    type RenderLayout = MultiviewRenderLayout<(), Ds::Format, VIEWS>;
+
+    type ColorTargetEncodings<'a>
+        = [ColorTargetEncoding<'a>; 0]
+    where
+        Ds: 'a;
 
     fn color_target_encodings<'a>(&'a self) -> Self::ColorTargetEncodings<'a> {
         []
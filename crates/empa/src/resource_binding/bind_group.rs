@@ -1,4 +1,7 @@
-use std::marker;
+use std::error::Error;
+#[cfg(feature = "usage_validation")]
+use std::sync::Arc;
+use std::{fmt, marker};
 
 use atomic_counter::AtomicCounter;
 
@@ -10,7 +13,10 @@ use crate::driver::{BindGroupDescriptor, BindingResource, Device as _, Driver, D
 use crate::resource_binding::typed_bind_group_entry::{
     f32_unfiltered, ShaderStages, TypedSlotBinding,
 };
-use crate::resource_binding::{typed_bind_group_entry, BindGroupLayout, TypedBindGroupLayout};
+use crate::resource_binding::{
+    typed_bind_group_entry, BindGroupLayout, BindGroupLayoutMismatch, BindingType,
+    TypedBindGroupLayout,
+};
 use crate::sampler::{ComparisonSampler, NonFilteringSampler, Sampler};
 use crate::texture::format::Storable;
 use crate::texture::{
@@ -25,11 +31,15 @@ use crate::texture::{
     SampledCubeUnsignedInteger, Storage1D, Storage2D, Storage2DArray, Storage3D,
 };
 use crate::type_flag::O;
+#[cfg(feature = "usage_validation")]
+use crate::usage_validation;
 use crate::{abi, driver};
 
 pub struct BindGroup<T> {
     handle: <Dvr as Driver>::BindGroupHandle,
     id: usize,
+    #[cfg(feature = "usage_validation")]
+    buffer_usages: Arc<[usage_validation::BufferUsage]>,
     _marker: marker::PhantomData<*const T>,
 }
 
@@ -42,26 +52,84 @@ where
         R: Resources<Layout = T>,
     {
         let id = ID_GEN.get();
-        let handle = device.device_handle.create_bind_group(BindGroupDescriptor {
-            layout: &layout.handle,
-            entries: resources
-                .to_entries()
-                .as_ref()
-                .iter()
-                .map(|e| driver::BindGroupEntry {
+        let entries = resources.to_entries();
+
+        for entry in entries.as_ref() {
+            if let Some(buffer_range) = &entry.resource.buffer_range {
+                buffer_range.validate(entry.binding, device);
+            }
+        }
+
+        let handle = device
+            .device_handle()
+            .create_bind_group(BindGroupDescriptor {
+                layout: &layout.handle,
+                entries: entries.as_ref().iter().map(|e| driver::BindGroupEntry {
                     binding: e.binding,
                     resource: e.resource.inner.clone(),
                 }),
-        });
+            });
+
+        #[cfg(feature = "usage_validation")]
+        let buffer_usages: Arc<[usage_validation::BufferUsage]> = entries
+            .as_ref()
+            .iter()
+            .filter_map(|entry| entry.resource.buffer_range.as_ref())
+            .map(|buffer_range| buffer_range.usage())
+            .collect();
+
+        #[cfg(feature = "usage_validation")]
+        usage_validation::register_bind_group(id, &buffer_usages);
 
         BindGroup {
             handle,
             id,
+            #[cfg(feature = "usage_validation")]
+            buffer_usages,
             _marker: Default::default(),
         }
     }
 }
 
+impl<T> BindGroup<T>
+where
+    T: TypedBindGroupLayout,
+{
+    /// Reinterprets this bind group as a bind group for layout `O`, so that it may be used with
+    /// pipelines that were created with a different but compatible bind group layout.
+    ///
+    /// See also [BindGroupLayout::is_compatible_with].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the layout of this bind group is not compatible with layout `O`. The panic
+    /// message identifies the binding at which the layouts differ.
+    pub fn cast<O>(&self) -> BindGroup<O>
+    where
+        O: TypedBindGroupLayout,
+    {
+        self.try_cast()
+            .unwrap_or_else(|mismatch| panic!("bind group layout mismatch: {}", mismatch))
+    }
+
+    /// Same as [cast](BindGroup::cast), but returns an error if the layout of this bind group
+    /// is not compatible with layout `O`.
+    pub fn try_cast<O>(&self) -> Result<BindGroup<O>, BindGroupLayoutMismatch>
+    where
+        O: TypedBindGroupLayout,
+    {
+        BindGroupLayout::<T>::check_compatible_with::<O>()?;
+
+        Ok(BindGroup {
+            handle: self.handle.clone(),
+            id: self.id,
+            #[cfg(feature = "usage_validation")]
+            buffer_usages: self.buffer_usages.clone(),
+            _marker: Default::default(),
+        })
+    }
+}
+
 impl<T> BindGroup<T> {
     pub fn to_encoding(&self) -> BindGroupEncoding {
         BindGroupEncoding {
@@ -79,6 +147,10 @@ pub struct BindGroupEntry<'a> {
 pub unsafe trait Resources {
     type Layout: TypedBindGroupLayout;
 
+    /// The names of the fields that provide the resources, indexed by binding; used to name the
+    /// fields in [ResourcesMismatch] errors.
+    const BINDING_NAMES: &'static [Option<&'static str>] = &[];
+
     type ToEntries<'a>: AsRef<[BindGroupEntry<'a>]>
     where
         Self: 'a;
@@ -86,6 +158,80 @@ pub unsafe trait Resources {
     fn to_entries<'a>(&'a self) -> Self::ToEntries<'a>;
 }
 
+/// Describes the first binding at which a [Resources] type does not match the resource bindings a
+/// shader declares for a bind group, see
+/// [ShaderSource::check_resources](crate::shader_module::ShaderSource::check_resources).
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ResourcesMismatch {
+    /// The shader declares the binding, but the resources do not provide it.
+    MissingBinding { binding: u32 },
+
+    /// The resources provide the binding with a different binding type than the shader declares
+    /// (e.g. a 2D texture where the shader expects a 2D array texture).
+    BindingType {
+        binding: u32,
+        field: Option<&'static str>,
+        expected: BindingType,
+        actual: BindingType,
+    },
+
+    /// The resources provide the buffer binding with a different memory layout than the shader
+    /// declares.
+    BufferLayout {
+        binding: u32,
+        field: Option<&'static str>,
+        mismatch: abi::LayoutMismatch,
+    },
+}
+
+impl ResourcesMismatch {
+    /// The index of the binding at which the resources do not match the shader.
+    pub fn binding(&self) -> u32 {
+        match self {
+            ResourcesMismatch::MissingBinding { binding } => *binding,
+            ResourcesMismatch::BindingType { binding, .. } => *binding,
+            ResourcesMismatch::BufferLayout { binding, .. } => *binding,
+        }
+    }
+
+    /// The name of the field that provides the mismatched binding, if known.
+    pub fn field(&self) -> Option<&'static str> {
+        match self {
+            ResourcesMismatch::MissingBinding { .. } => None,
+            ResourcesMismatch::BindingType { field, .. } => *field,
+            ResourcesMismatch::BufferLayout { field, .. } => *field,
+        }
+    }
+}
+
+impl fmt::Display for ResourcesMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let ResourcesMismatch::MissingBinding { binding } = self {
+            return write!(f, "the shader expects a resource for binding `{}`", binding);
+        }
+
+        if let Some(field) = self.field() {
+            write!(f, "field `{}` (binding `{}`) ", field, self.binding())?;
+        } else {
+            write!(f, "binding `{}` ", self.binding())?;
+        }
+
+        match self {
+            ResourcesMismatch::BindingType {
+                expected, actual, ..
+            } => f.write_str(&actual.describe_mismatch(expected)),
+            ResourcesMismatch::BufferLayout { mismatch, .. } => write!(
+                f,
+                "does not match the buffer layout the shader expects: {}",
+                mismatch
+            ),
+            ResourcesMismatch::MissingBinding { .. } => unreachable!(),
+        }
+    }
+}
+
+impl Error for ResourcesMismatch {}
+
 pub unsafe trait Resource {
     type Binding: TypedSlotBinding;
 
@@ -94,11 +240,91 @@ pub unsafe trait Resource {
 
 pub struct ResourceEncoding<'a> {
     pub(crate) inner: BindingResource<'a, Dvr>,
+    pub(crate) buffer_range: Option<BufferBindingRange>,
 }
 
 impl<'a> From<BindingResource<'a, Dvr>> for ResourceEncoding<'a> {
     fn from(inner: BindingResource<'a, Dvr>) -> Self {
-        ResourceEncoding { inner }
+        ResourceEncoding {
+            inner,
+            buffer_range: None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum BufferBindingKind {
+    Uniform,
+    ReadOnlyStorage,
+    Storage,
+}
+
+/// The region of a buffer that is bound by a buffer resource binding.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) struct BufferBindingRange {
+    #[cfg(feature = "usage_validation")]
+    pub(crate) buffer_id: usize,
+    pub(crate) kind: BufferBindingKind,
+    pub(crate) offset: usize,
+    pub(crate) size: usize,
+}
+
+impl BufferBindingRange {
+    #[cfg(feature = "usage_validation")]
+    fn usage(&self) -> usage_validation::BufferUsage {
+        let kind = match self.kind {
+            BufferBindingKind::Uniform => usage_validation::BufferUsageKind::Uniform,
+            BufferBindingKind::ReadOnlyStorage => {
+                usage_validation::BufferUsageKind::ReadOnlyStorage
+            }
+            BufferBindingKind::Storage => usage_validation::BufferUsageKind::Storage,
+        };
+
+        usage_validation::BufferUsage {
+            buffer_id: self.buffer_id,
+            kind,
+        }
+    }
+
+    fn validate(&self, binding: u32, device: &Device) {
+        let limits = device.limits();
+
+        let (kind, min_offset_alignment, max_binding_size) = match self.kind {
+            BufferBindingKind::Uniform => (
+                "uniform",
+                limits.min_uniform_buffer_offset_alignment,
+                limits.max_uniform_buffer_binding_size,
+            ),
+            BufferBindingKind::ReadOnlyStorage | BufferBindingKind::Storage => (
+                "storage",
+                limits.min_storage_buffer_offset_alignment,
+                limits.max_storage_buffer_binding_size,
+            ),
+        };
+
+        if self.offset % min_offset_alignment as usize != 0 {
+            panic!(
+                "the offset (`{}`) of the {} buffer region for binding `{}` must be a multiple of \
+                 the device's minimum {} buffer offset alignment (`{}`)",
+                self.offset, kind, binding, kind, min_offset_alignment
+            );
+        }
+
+        if self.size as u64 > max_binding_size {
+            panic!(
+                "the size (`{}`) of the {} buffer region for binding `{}` exceeds the device's \
+                 maximum {} buffer binding size (`{}`)",
+                self.size, kind, binding, kind, max_binding_size
+            );
+        }
+
+        if self.kind == BufferBindingKind::Storage && self.size % 4 != 0 {
+            panic!(
+                "the size (`{}`) of the storage buffer region for binding `{}` must be a multiple \
+                 of `4`",
+                self.size, binding
+            );
+        }
     }
 }
 
@@ -412,7 +638,16 @@ where
     type Binding = typed_bind_group_entry::Uniform<T, ShaderStages<O, O, O>>;
 
     fn to_encoding(&self) -> ResourceEncoding {
-        BindingResource::BufferBinding(self.inner.clone()).into()
+        ResourceEncoding {
+            inner: BindingResource::BufferBinding(self.inner.clone()),
+            buffer_range: Some(BufferBindingRange {
+                #[cfg(feature = "usage_validation")]
+                buffer_id: self.buffer_id,
+                kind: BufferBindingKind::Uniform,
+                offset: self.offset,
+                size: self.size,
+            }),
+        }
     }
 }
 
@@ -423,7 +658,16 @@ where
     type Binding = typed_bind_group_entry::Storage<T, Read, ShaderStages<O, O, O>>;
 
     fn to_encoding(&self) -> ResourceEncoding {
-        BindingResource::BufferBinding(self.inner.clone()).into()
+        ResourceEncoding {
+            inner: BindingResource::BufferBinding(self.inner.clone()),
+            buffer_range: Some(BufferBindingRange {
+                #[cfg(feature = "usage_validation")]
+                buffer_id: self.buffer_id,
+                kind: BufferBindingKind::ReadOnlyStorage,
+                offset: self.offset,
+                size: self.size,
+            }),
+        }
     }
 }
 
@@ -434,6 +678,15 @@ where
     type Binding = typed_bind_group_entry::Storage<T, ReadWrite, ShaderStages<O, O, O>>;
 
     fn to_encoding(&self) -> ResourceEncoding {
-        BindingResource::BufferBinding(self.inner.clone()).into()
+        ResourceEncoding {
+            inner: BindingResource::BufferBinding(self.inner.clone()),
+            buffer_range: Some(BufferBindingRange {
+                #[cfg(feature = "usage_validation")]
+                buffer_id: self.buffer_id,
+                kind: BufferBindingKind::Storage,
+                offset: self.offset,
+                size: self.size,
+            }),
+        }
     }
 }
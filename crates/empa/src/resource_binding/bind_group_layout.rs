@@ -1,8 +1,10 @@
-use std::marker;
+use std::error::Error;
+use std::{cmp, fmt, marker};
 
 use flagset::FlagSet;
 
-use crate::abi::MemoryUnit;
+use crate::abi;
+use crate::abi::{LayoutMismatch, MemoryUnit};
 use crate::device::Device;
 use crate::driver::{
     BufferBindingType, Device as _, Driver, Dvr, SamplerBindingType, ShaderStage,
@@ -38,7 +40,7 @@ impl<T> BindGroupLayout<T> {
             });
 
         let handle = device
-            .device_handle
+            .device_handle()
             .create_bind_group_layout(driver::BindGroupLayoutDescriptor { entries });
 
         BindGroupLayout {
@@ -67,12 +69,150 @@ where
     pub(crate) fn typed(device: &Device) -> Self {
         BindGroupLayout::new(device, T::BIND_GROUP_LAYOUT)
     }
+
+    /// Whether bind groups created for this layout may be used where a bind group for layout `O`
+    /// is expected.
+    ///
+    /// Two layouts are compatible if they declare the same bindings, with the same binding types
+    /// and visibility.
+    pub fn is_compatible_with<O>() -> bool
+    where
+        O: TypedBindGroupLayout,
+    {
+        Self::check_compatible_with::<O>().is_ok()
+    }
+
+    /// Same as [is_compatible_with](BindGroupLayout::is_compatible_with), but returns the first
+    /// binding at which the layouts differ if they are not compatible.
+    pub fn check_compatible_with<O>() -> Result<(), BindGroupLayoutMismatch>
+    where
+        O: TypedBindGroupLayout,
+    {
+        compare_layouts(O::BIND_GROUP_LAYOUT, T::BIND_GROUP_LAYOUT)
+    }
+}
+
+pub(crate) fn compare_layouts(
+    expected: &[Option<BindGroupLayoutEntry>],
+    actual: &[Option<BindGroupLayoutEntry>],
+) -> Result<(), BindGroupLayoutMismatch> {
+    let len = cmp::max(expected.len(), actual.len());
+
+    for i in 0..len {
+        let binding = i as u32;
+        let expected = expected.get(i).copied().flatten();
+        let actual = actual.get(i).copied().flatten();
+
+        match (expected, actual) {
+            (Some(expected), Some(actual)) => {
+                if expected.binding_type != actual.binding_type {
+                    return Err(BindGroupLayoutMismatch::BindingType {
+                        binding,
+                        expected: expected.binding_type,
+                        actual: actual.binding_type,
+                    });
+                }
+
+                if expected.visibility != actual.visibility {
+                    return Err(BindGroupLayoutMismatch::Visibility {
+                        binding,
+                        expected: expected.visibility,
+                        actual: actual.visibility,
+                    });
+                }
+            }
+            (Some(_), None) => return Err(BindGroupLayoutMismatch::MissingBinding { binding }),
+            (None, Some(_)) => return Err(BindGroupLayoutMismatch::UnexpectedBinding { binding }),
+            (None, None) => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// Describes the first binding at which a bind group layout differs from the layout that was
+/// expected.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum BindGroupLayoutMismatch {
+    /// The expected layout declares the binding, but the actual layout does not.
+    MissingBinding { binding: u32 },
+
+    /// The actual layout declares the binding, but the expected layout does not.
+    UnexpectedBinding { binding: u32 },
+
+    /// Both layouts declare the binding, but with different binding types.
+    BindingType {
+        binding: u32,
+        expected: BindingType,
+        actual: BindingType,
+    },
+
+    /// Both layouts declare the binding, but with different shader stage visibility.
+    Visibility {
+        binding: u32,
+        expected: FlagSet<ShaderStage>,
+        actual: FlagSet<ShaderStage>,
+    },
+}
+
+impl BindGroupLayoutMismatch {
+    /// The index of the binding at which the layouts differ.
+    pub fn binding(&self) -> u32 {
+        match self {
+            BindGroupLayoutMismatch::MissingBinding { binding } => *binding,
+            BindGroupLayoutMismatch::UnexpectedBinding { binding } => *binding,
+            BindGroupLayoutMismatch::BindingType { binding, .. } => *binding,
+            BindGroupLayoutMismatch::Visibility { binding, .. } => *binding,
+        }
+    }
+}
+
+impl fmt::Display for BindGroupLayoutMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BindGroupLayoutMismatch::MissingBinding { binding } => {
+                write!(f, "expected a binding at index `{}`", binding)
+            }
+            BindGroupLayoutMismatch::UnexpectedBinding { binding } => {
+                write!(f, "did not expect a binding at index `{}`", binding)
+            }
+            BindGroupLayoutMismatch::BindingType {
+                binding,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "binding type mismatch at index `{}` (expected: {:?}, actual: {:?})",
+                binding, expected, actual
+            ),
+            BindGroupLayoutMismatch::Visibility {
+                binding,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "visibility mismatch at index `{}` (expected: {:?}, actual: {:?})",
+                binding, expected, actual
+            ),
+        }
+    }
 }
 
+impl Error for BindGroupLayoutMismatch {}
+
 pub unsafe trait TypedBindGroupLayout {
     const BIND_GROUP_LAYOUT: &'static [Option<BindGroupLayoutEntry>];
 }
 
+/// The layout of an empty bind group.
+///
+/// Can be used as a placeholder in a [TypedPipelineLayout](crate::resource_binding::TypedPipelineLayout)
+/// for a group index that is not used by a pipeline's shaders, e.g. `(Group0Layout, (), Group2Layout)`
+/// for shaders that only use `@group(0)` and `@group(2)`.
+unsafe impl TypedBindGroupLayout for () {
+    const BIND_GROUP_LAYOUT: &'static [Option<BindGroupLayoutEntry>] = &[];
+}
+
 macro_rules! impl_typed_bind_group_layout {
     ($($binding:ident),*) => {
         #[allow(unused_parens)]
@@ -163,6 +303,7 @@ impl_typed_bind_group_layout!(
     B21, B22, B23, B24, B25, B26, B27, B28, B29, B30, B31
 );
 
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub struct BindGroupLayoutEntry {
     pub visibility: FlagSet<ShaderStage>,
     pub binding_type: BindingType,
@@ -195,6 +336,61 @@ pub enum BindingType {
 }
 
 impl BindingType {
+    /// The dimensionality of a texture binding, or `None` if this is not a texture binding.
+    pub(crate) fn texture_dimension(&self) -> Option<&'static str> {
+        match self {
+            BindingType::Texture1D(_) | BindingType::StorageTexture1D(_) => Some("1D"),
+            BindingType::Texture2D(_)
+            | BindingType::TextureDepth2D
+            | BindingType::StorageTexture2D(_) => Some("2D"),
+            BindingType::Texture3D(_) | BindingType::StorageTexture3D(_) => Some("3D"),
+            BindingType::Texture2DArray(_)
+            | BindingType::TextureDepth2DArray
+            | BindingType::StorageTexture2DArray(_) => Some("2D array"),
+            BindingType::TextureCube(_) | BindingType::TextureDepthCube => Some("cube"),
+            BindingType::TextureCubeArray(_) | BindingType::TextureDepthCubeArray => {
+                Some("cube array")
+            }
+            BindingType::TextureMultisampled2D(_) | BindingType::TextureDepthMultisampled2D => {
+                Some("multisampled 2D")
+            }
+            _ => None,
+        }
+    }
+
+    /// Describes how this binding type differs from the binding type the `shader` expects, for use
+    /// in error messages, e.g. "is a 2D texture (`texture_2d<f32>`), but the shader expects a 2D
+    /// array texture (`texture_2d_array<f32>`)".
+    pub(crate) fn describe_mismatch(&self, shader: &BindingType) -> String {
+        match (self.texture_dimension(), shader.texture_dimension()) {
+            (Some(actual), Some(expected)) if actual != expected => format!(
+                "is a {} texture (`{}`), but the shader expects a {} texture (`{}`)",
+                actual, self, expected, shader
+            ),
+            _ => format!(
+                "is a `{}` binding, but the shader expects a `{}` binding",
+                self, shader
+            ),
+        }
+    }
+
+    /// Checks that the memory layout of a buffer binding matches the layout the `shader` expects.
+    ///
+    /// Returns `Ok` if either binding is not a buffer binding, or if the bindings are buffer
+    /// bindings of different kinds; such mismatches are reported by comparing the binding types.
+    pub(crate) fn check_buffer_layout(&self, shader: &BindingType) -> Result<(), LayoutMismatch> {
+        match (shader, self) {
+            (BindingType::Uniform(expected), BindingType::Uniform(actual)) => {
+                abi::check_memory_units(expected.0, actual.0)
+            }
+            (BindingType::Storage(expected), BindingType::Storage(actual))
+            | (BindingType::ReadOnlyStorage(expected), BindingType::ReadOnlyStorage(actual)) => {
+                abi::check_unsized_buffer_layouts(expected, actual)
+            }
+            _ => Ok(()),
+        }
+    }
+
     fn to_driver(&self) -> driver::BindingType {
         match self {
             BindingType::Texture1D(texel_type) => driver::BindingType::Texture {
@@ -297,6 +493,44 @@ impl BindingType {
     }
 }
 
+/// Formats the binding type as the WGSL type of the corresponding shader variable, e.g.
+/// `texture_2d_array<f32>` or `var<uniform>`.
+impl fmt::Display for BindingType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BindingType::Texture1D(texel_type) => write!(f, "texture_1d<{}>", texel_type),
+            BindingType::Texture2D(texel_type) => write!(f, "texture_2d<{}>", texel_type),
+            BindingType::Texture3D(texel_type) => write!(f, "texture_3d<{}>", texel_type),
+            BindingType::Texture2DArray(texel_type) => {
+                write!(f, "texture_2d_array<{}>", texel_type)
+            }
+            BindingType::TextureCube(texel_type) => write!(f, "texture_cube<{}>", texel_type),
+            BindingType::TextureCubeArray(texel_type) => {
+                write!(f, "texture_cube_array<{}>", texel_type)
+            }
+            BindingType::TextureMultisampled2D(texel_type) => {
+                write!(f, "texture_multisampled_2d<{}>", texel_type)
+            }
+            BindingType::TextureDepth2D => f.write_str("texture_depth_2d"),
+            BindingType::TextureDepth2DArray => f.write_str("texture_depth_2d_array"),
+            BindingType::TextureDepthCube => f.write_str("texture_depth_cube"),
+            BindingType::TextureDepthCubeArray => f.write_str("texture_depth_cube_array"),
+            BindingType::TextureDepthMultisampled2D => f.write_str("texture_depth_multisampled_2d"),
+            BindingType::StorageTexture1D(_) => f.write_str("texture_storage_1d"),
+            BindingType::StorageTexture2D(_) => f.write_str("texture_storage_2d"),
+            BindingType::StorageTexture2DArray(_) => f.write_str("texture_storage_2d_array"),
+            BindingType::StorageTexture3D(_) => f.write_str("texture_storage_3d"),
+            BindingType::FilteringSampler | BindingType::NonFilteringSampler => {
+                f.write_str("sampler")
+            }
+            BindingType::ComparisonSampler => f.write_str("sampler_comparison"),
+            BindingType::Uniform(_) => f.write_str("var<uniform>"),
+            BindingType::Storage(_) => f.write_str("var<storage, read_write>"),
+            BindingType::ReadOnlyStorage(_) => f.write_str("var<storage, read>"),
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 pub enum TexelType {
     Float,
@@ -305,6 +539,16 @@ pub enum TexelType {
     UnsignedInteger,
 }
 
+impl fmt::Display for TexelType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TexelType::Float | TexelType::UnfilterableFloat => f.write_str("f32"),
+            TexelType::SignedInteger => f.write_str("i32"),
+            TexelType::UnsignedInteger => f.write_str("u32"),
+        }
+    }
+}
+
 impl TexelType {
     pub(crate) fn to_driver(&self) -> driver::TextureSampleType {
         match self {
@@ -1,3 +1,4 @@
+use std::fmt::Write;
 use std::marker;
 
 use crate::device::Device;
@@ -22,7 +23,7 @@ where
         let bind_group_layouts = bind_group_layouts.encodings().into_iter().map(|l| l.handle);
 
         let handle = device
-            .device_handle
+            .device_handle()
             .create_pipeline_layout(PipelineLayoutDescriptor { bind_group_layouts });
 
         PipelineLayout {
@@ -30,12 +31,46 @@ where
             _marker: Default::default(),
         }
     }
+
+    /// Returns a human-readable description of the bindings in each bind group of this layout,
+    /// including their binding types and visibility.
+    ///
+    /// Intended as a debugging aid, e.g. when a bind group does not match a pipeline:
+    ///
+    /// ```ignore
+    /// println!("{}", pipeline_layout.describe());
+    /// ```
+    pub fn describe(&self) -> String {
+        let mut description = String::new();
+
+        for (group, entries) in T::BIND_GROUP_LAYOUTS.iter().enumerate() {
+            writeln!(description, "group {}:", group).unwrap();
+
+            for (binding, entry) in entries.iter().enumerate() {
+                if let Some(entry) = entry {
+                    writeln!(
+                        description,
+                        "    binding {}: {:?}, visible to {:?}",
+                        binding, entry.binding_type, entry.visibility
+                    )
+                    .unwrap();
+                }
+            }
+        }
+
+        description
+    }
 }
 
 mod typed_pipeline_layout_seal {
     pub trait Seal {}
 }
 
+/// The bind group layouts of a pipeline layout, as a tuple of up to 4 [TypedBindGroupLayout]s.
+///
+/// Use `()` as a placeholder for group indices that the pipeline's shaders do not use; create the
+/// pipeline layout with an empty bind group layout (`device.create_bind_group_layout::<()>()`) for
+/// those indices, and skip them with `()` when setting bind groups.
 pub trait TypedPipelineLayout: typed_pipeline_layout_seal::Seal {
     const BIND_GROUP_LAYOUTS: &'static [&'static [Option<BindGroupLayoutEntry>]];
 }
@@ -1,45 +1,70 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
 use std::ops::RangeInclusive;
+use std::sync::Mutex;
 
 use crate::device::Device;
 use crate::driver::{Device as _, Driver, Dvr};
 use crate::{driver, CompareFunction};
 
-#[derive(Clone, Copy, PartialEq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum AddressMode {
     ClampToEdge,
     Repeat,
     MirrorRepeat,
 }
 
-#[derive(Clone, Copy, PartialEq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum FilterMode {
     Nearest,
     Linear,
 }
 
+#[derive(Clone)]
 pub struct Sampler {
     pub(crate) handle: <Dvr as Driver>::SamplerHandle,
 }
 
 impl Sampler {
     pub(crate) fn new(device: &Device, descriptor: &SamplerDescriptor) -> Self {
-        let handle = device.device_handle.create_sampler(&descriptor.to_driver());
+        let handle = device
+            .device_handle()
+            .create_sampler(&descriptor.to_driver());
 
         Sampler { handle }
     }
 
-    pub(crate) fn anisotropic(device: &Device, descriptor: &AnisotropicSamplerDescriptor) -> Self {
+    pub(crate) fn cached(device: &Device, descriptor: &SamplerDescriptor) -> Self {
+        let handle = device
+            .sampler_cache()
+            .get_or_create(device, &descriptor.to_driver());
+
+        Sampler { handle }
+    }
+
+    pub(crate) fn anisotropic(
+        device: &Device,
+        descriptor: &AnisotropicSamplerDescriptor,
+    ) -> Result<Self, AnisotropicSamplerError> {
         if descriptor.max_anisotropy <= 1 {
-            panic!("`max_anisotropy` must be set to a value greater than `1`")
+            return Err(AnisotropicSamplerError::MaxAnisotropyTooLow(
+                descriptor.max_anisotropy,
+            ));
         }
 
-        let handle = device.device_handle.create_sampler(&descriptor.to_driver());
+        let handle = device
+            .device_handle()
+            .create_sampler(&descriptor.to_driver());
 
-        Sampler { handle }
+        Ok(Sampler { handle })
     }
 }
 
 #[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SamplerDescriptor {
     pub address_mode_u: AddressMode,
     pub address_mode_v: AddressMode,
@@ -51,6 +76,59 @@ pub struct SamplerDescriptor {
 }
 
 impl SamplerDescriptor {
+    /// Clamps to the edge in all directions and uses linear filtering for magnification,
+    /// minification and mipmap selection.
+    pub const LINEAR_CLAMP: SamplerDescriptor = SamplerDescriptor {
+        address_mode_u: AddressMode::ClampToEdge,
+        address_mode_v: AddressMode::ClampToEdge,
+        address_mode_w: AddressMode::ClampToEdge,
+        magnification_filter: FilterMode::Linear,
+        minification_filter: FilterMode::Linear,
+        mipmap_filter: FilterMode::Linear,
+        lod_clamp: 0.0..=32.0,
+    };
+
+    /// Repeats in all directions and uses nearest filtering for magnification, minification and
+    /// mipmap selection.
+    pub const NEAREST_REPEAT: SamplerDescriptor = SamplerDescriptor {
+        address_mode_u: AddressMode::Repeat,
+        address_mode_v: AddressMode::Repeat,
+        address_mode_w: AddressMode::Repeat,
+        magnification_filter: FilterMode::Nearest,
+        minification_filter: FilterMode::Nearest,
+        mipmap_filter: FilterMode::Nearest,
+        lod_clamp: 0.0..=32.0,
+    };
+
+    /// Converts this descriptor into an [AnisotropicSamplerDescriptor] with the given
+    /// `max_anisotropy`, keeping the address modes and LOD clamp.
+    ///
+    /// WebGPU requires all filters of an anisotropic sampler to be linear; returns an error if
+    /// this descriptor uses a nearest filter, or if `max_anisotropy` is not greater than `1`.
+    pub fn anisotropic(
+        &self,
+        max_anisotropy: u16,
+    ) -> Result<AnisotropicSamplerDescriptor, AnisotropicSamplerError> {
+        if max_anisotropy <= 1 {
+            return Err(AnisotropicSamplerError::MaxAnisotropyTooLow(max_anisotropy));
+        }
+
+        if self.magnification_filter != FilterMode::Linear
+            || self.minification_filter != FilterMode::Linear
+            || self.mipmap_filter != FilterMode::Linear
+        {
+            return Err(AnisotropicSamplerError::NonLinearFilter);
+        }
+
+        Ok(AnisotropicSamplerDescriptor {
+            max_anisotropy,
+            address_mode_u: self.address_mode_u,
+            address_mode_v: self.address_mode_v,
+            address_mode_w: self.address_mode_w,
+            lod_clamp: self.lod_clamp.clone(),
+        })
+    }
+
     fn to_driver(&self) -> driver::SamplerDescriptor {
         let SamplerDescriptor {
             address_mode_u,
@@ -90,6 +168,7 @@ impl Default for SamplerDescriptor {
 }
 
 #[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AnisotropicSamplerDescriptor {
     pub max_anisotropy: u16,
     pub address_mode_u: AddressMode,
@@ -99,6 +178,15 @@ pub struct AnisotropicSamplerDescriptor {
 }
 
 impl AnisotropicSamplerDescriptor {
+    /// Repeats in all directions and uses trilinear filtering with a maximum anisotropy of `16`.
+    pub const TRILINEAR_ANISO16: AnisotropicSamplerDescriptor = AnisotropicSamplerDescriptor {
+        max_anisotropy: 16,
+        address_mode_u: AddressMode::Repeat,
+        address_mode_v: AddressMode::Repeat,
+        address_mode_w: AddressMode::Repeat,
+        lod_clamp: 0.0..=32.0,
+    };
+
     fn to_driver(&self) -> driver::SamplerDescriptor {
         let AnisotropicSamplerDescriptor {
             max_anisotropy,
@@ -134,19 +222,49 @@ impl Default for AnisotropicSamplerDescriptor {
     }
 }
 
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AnisotropicSamplerError {
+    /// The `max_anisotropy` is not greater than `1`.
+    MaxAnisotropyTooLow(u16),
+    /// One of the filters is not [FilterMode::Linear].
+    NonLinearFilter,
+}
+
+impl fmt::Display for AnisotropicSamplerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnisotropicSamplerError::MaxAnisotropyTooLow(max_anisotropy) => write!(
+                f,
+                "`max_anisotropy` must be greater than `1` (got `{}`)",
+                max_anisotropy
+            ),
+            AnisotropicSamplerError::NonLinearFilter => write!(
+                f,
+                "anisotropic samplers require linear magnification, minification and mipmap \
+                filters"
+            ),
+        }
+    }
+}
+
+impl Error for AnisotropicSamplerError {}
+
 pub struct ComparisonSampler {
     pub(crate) handle: <Dvr as Driver>::SamplerHandle,
 }
 
 impl ComparisonSampler {
     pub(crate) fn new(device: &Device, descriptor: &ComparisonSamplerDescriptor) -> Self {
-        let handle = device.device_handle.create_sampler(&descriptor.to_driver());
+        let handle = device
+            .device_handle()
+            .create_sampler(&descriptor.to_driver());
 
         ComparisonSampler { handle }
     }
 }
 
 #[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ComparisonSamplerDescriptor {
     pub compare: CompareFunction,
     pub address_mode_u: AddressMode,
@@ -209,13 +327,16 @@ pub struct NonFilteringSampler {
 
 impl NonFilteringSampler {
     pub(crate) fn new(device: &Device, descriptor: &NonFilteringSamplerDescriptor) -> Self {
-        let handle = device.device_handle.create_sampler(&descriptor.to_driver());
+        let handle = device
+            .device_handle()
+            .create_sampler(&descriptor.to_driver());
 
         NonFilteringSampler { handle }
     }
 }
 
 #[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NonFilteringSamplerDescriptor {
     pub address_mode_u: AddressMode,
     pub address_mode_v: AddressMode,
@@ -256,3 +377,56 @@ impl Default for NonFilteringSamplerDescriptor {
         }
     }
 }
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct SamplerKey {
+    address_modes: [AddressMode; 3],
+    filters: [FilterMode; 3],
+    lod_clamp: (u32, u32),
+    max_anisotropy: u16,
+    compare: Option<CompareFunction>,
+}
+
+impl SamplerKey {
+    fn new(descriptor: &driver::SamplerDescriptor) -> Self {
+        SamplerKey {
+            address_modes: [
+                descriptor.address_mode_u,
+                descriptor.address_mode_v,
+                descriptor.address_mode_w,
+            ],
+            filters: [
+                descriptor.magnification_filter,
+                descriptor.minification_filter,
+                descriptor.mipmap_filter,
+            ],
+            lod_clamp: (
+                descriptor.lod_clamp.start().to_bits(),
+                descriptor.lod_clamp.end().to_bits(),
+            ),
+            max_anisotropy: descriptor.max_anisotropy,
+            compare: descriptor.compare,
+        }
+    }
+}
+
+// Samplers are immutable, so samplers created from equal descriptors can be shared.
+#[derive(Default)]
+pub(crate) struct SamplerCache {
+    samplers: Mutex<HashMap<SamplerKey, <Dvr as Driver>::SamplerHandle>>,
+}
+
+impl SamplerCache {
+    fn get_or_create(
+        &self,
+        device: &Device,
+        descriptor: &driver::SamplerDescriptor,
+    ) -> <Dvr as Driver>::SamplerHandle {
+        self.samplers
+            .lock()
+            .unwrap()
+            .entry(SamplerKey::new(descriptor))
+            .or_insert_with(|| device.device_handle().create_sampler(descriptor))
+            .clone()
+    }
+}
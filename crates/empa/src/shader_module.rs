@@ -1,18 +1,18 @@
 use std::collections::HashMap;
 use std::sync::Arc;
-use std::{fmt, slice};
+use std::{fmt, marker, slice};
 
-pub use empa_macros::shader_source;
+pub use empa_macros::{shader_entry_points, shader_source};
 use empa_reflect::{
     ConstantIdentifier, ConstantType, EntryPointBinding as DynamicEntryPointBinding,
-    EntryPointBindingType, ParseError as DynamicParseError, ShaderSource as DynamicShaderSource,
-    ShaderStage,
+    ParseError as DynamicParseError, ShaderSource as DynamicShaderSource, ShaderStage,
 };
+pub use empa_reflect::{EntryPointBindingType, Interpolation, Sampling};
 
 use crate::device::Device;
 use crate::driver::{Device as _, Driver, Dvr};
 use crate::pipeline_constants::{PipelineConstantIdentifier, PipelineConstants};
-use crate::resource_binding::BindingType;
+use crate::resource_binding::{BindingType, Resources, ResourcesMismatch, TypedBindGroupLayout};
 
 /// Internal type for `shader_source` macro.
 #[doc(hidden)]
@@ -131,6 +131,26 @@ pub enum StaticSampling {
     Sample,
 }
 
+impl StaticInterpolation {
+    pub fn to_interpolation(&self) -> Interpolation {
+        match self {
+            StaticInterpolation::Perspective => Interpolation::Perspective,
+            StaticInterpolation::Linear => Interpolation::Linear,
+            StaticInterpolation::Flat => Interpolation::Flat,
+        }
+    }
+}
+
+impl StaticSampling {
+    pub fn to_sampling(&self) -> Sampling {
+        match self {
+            StaticSampling::Center => Sampling::Center,
+            StaticSampling::Centroid => Sampling::Centroid,
+            StaticSampling::Sample => Sampling::Sample,
+        }
+    }
+}
+
 /// Internal type for `shader_source` macro.
 #[doc(hidden)]
 #[derive(Clone, Copy, PartialEq, Debug)]
@@ -149,6 +169,24 @@ pub struct StaticEntryPoint {
     pub stage: StaticShaderStage,
     pub input_bindings: &'static [StaticEntryPointBinding],
     pub output_bindings: &'static [StaticEntryPointBinding],
+    pub workgroup_size: [u32; 3],
+    pub workgroup_variables: &'static [StaticWorkgroupVariable],
+    pub uses_subgroups: bool,
+}
+
+/// Internal type for `shader_source` macro.
+#[doc(hidden)]
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct StaticWorkgroupVariable {
+    pub name: Option<&'static str>,
+    pub size: u32,
+}
+
+/// A variable in the `workgroup` address space that is used by a compute entry point.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub(crate) struct WorkgroupVariable {
+    pub(crate) name: Option<String>,
+    pub(crate) size: u32,
 }
 
 #[derive(Clone)]
@@ -201,7 +239,25 @@ impl ShaderSourceInternal {
                 .enumerate()
                 .find(|(_, e)| e.name() == name)
                 .map(|(index, _)| index),
-            ShaderSourceInternal::Unparsed(_) => unimplemented!()
+            ShaderSourceInternal::Unparsed(_) => None
+        }
+    }
+
+    pub(crate) fn entry_point_count(&self) -> usize {
+        match self {
+            ShaderSourceInternal::Static(source) => source.entry_points.len(),
+            ShaderSourceInternal::Dynamic(source) => source.entry_points().len(),
+            ShaderSourceInternal::Unparsed(_) => 0,
+        }
+    }
+
+    pub(crate) fn entry_point_name(&self, index: usize) -> Option<&str> {
+        match self {
+            ShaderSourceInternal::Static(source) => source.entry_points.get(index).map(|e| e.name),
+            ShaderSourceInternal::Dynamic(source) => {
+                source.entry_points().get(index).map(|e| e.name())
+            }
+            ShaderSourceInternal::Unparsed(_) => None,
         }
     }
 
@@ -211,7 +267,58 @@ impl ShaderSourceInternal {
             ShaderSourceInternal::Dynamic(source) => {
                 source.entry_points().get(index).map(|e| e.stage())
             }
-            ShaderSourceInternal::Unparsed(_) => unimplemented!()
+            ShaderSourceInternal::Unparsed(_) => None
+        }
+    }
+
+    pub(crate) fn entry_point_workgroup_size(&self, index: usize) -> Option<[u32; 3]> {
+        match self {
+            ShaderSourceInternal::Static(source) => {
+                source.entry_points.get(index).map(|e| e.workgroup_size)
+            }
+            ShaderSourceInternal::Dynamic(source) => {
+                source.entry_points().get(index).map(|e| e.workgroup_size())
+            }
+            ShaderSourceInternal::Unparsed(_) => None,
+        }
+    }
+
+    pub(crate) fn entry_point_workgroup_variables(
+        &self,
+        index: usize,
+    ) -> Option<Vec<WorkgroupVariable>> {
+        match self {
+            ShaderSourceInternal::Static(source) => source.entry_points.get(index).map(|e| {
+                e.workgroup_variables
+                    .iter()
+                    .map(|v| WorkgroupVariable {
+                        name: v.name.map(|n| n.to_string()),
+                        size: v.size,
+                    })
+                    .collect()
+            }),
+            ShaderSourceInternal::Dynamic(source) => source.entry_points().get(index).map(|e| {
+                e.workgroup_variables()
+                    .iter()
+                    .map(|v| WorkgroupVariable {
+                        name: v.name().map(|n| n.to_string()),
+                        size: v.size(),
+                    })
+                    .collect()
+            }),
+            ShaderSourceInternal::Unparsed(_) => None,
+        }
+    }
+
+    pub(crate) fn entry_point_uses_subgroups(&self, index: usize) -> Option<bool> {
+        match self {
+            ShaderSourceInternal::Static(source) => {
+                source.entry_points.get(index).map(|e| e.uses_subgroups)
+            }
+            ShaderSourceInternal::Dynamic(source) => {
+                source.entry_points().get(index).map(|e| e.uses_subgroups())
+            }
+            ShaderSourceInternal::Unparsed(_) => None,
         }
     }
 
@@ -225,7 +332,7 @@ impl ShaderSourceInternal {
                 .entry_points()
                 .get(index)
                 .map(|e| EntryPointBindings::Dynamic(e.input_bindings().iter())),
-            ShaderSourceInternal::Unparsed(_) => unimplemented!()
+            ShaderSourceInternal::Unparsed(_) => None
         }
     }
 
@@ -239,7 +346,7 @@ impl ShaderSourceInternal {
                 .entry_points()
                 .get(index)
                 .map(|e| EntryPointBindings::Dynamic(e.output_bindings().iter())),
-            ShaderSourceInternal::Unparsed(_) => unimplemented!()
+            ShaderSourceInternal::Unparsed(_) => None
         }
     }
 
@@ -280,12 +387,11 @@ impl ShaderSourceInternal {
             }
             ShaderSourceInternal::Dynamic(s) => {
                 for constant in s.constants() {
-                    let identifier = match constant.identifier() {
-                        ConstantIdentifier::Number(n) => PipelineConstantIdentifier::Number(*n),
-                        ConstantIdentifier::Name(n) => PipelineConstantIdentifier::Name(n),
-                    };
-
-                    add_constant(identifier, constant.constant_type(), constant.required());
+                    add_constant(
+                        constant_identifier(constant.identifier()),
+                        constant.constant_type(),
+                        constant.required(),
+                    );
                 }
             }
             ShaderSourceInternal::Unparsed(_) => {
@@ -297,6 +403,13 @@ impl ShaderSourceInternal {
     }
 }
 
+pub(crate) fn constant_identifier(identifier: &ConstantIdentifier) -> PipelineConstantIdentifier {
+    match identifier {
+        ConstantIdentifier::Number(n) => PipelineConstantIdentifier::Number(*n),
+        ConstantIdentifier::Name(n) => PipelineConstantIdentifier::Name(n),
+    }
+}
+
 pub(crate) enum EntryPointBindings<'a> {
     Static(slice::Iter<'a, StaticEntryPointBinding>),
     Dynamic(slice::Iter<'a, DynamicEntryPointBinding>),
@@ -332,6 +445,103 @@ impl EntryPointBinding<'_> {
             EntryPointBinding::Dynamic(b) => b.binding_type(),
         }
     }
+
+    pub(crate) fn interpolation(&self) -> Option<Interpolation> {
+        match self {
+            EntryPointBinding::Static(b) => b.interpolation.map(|i| i.to_interpolation()),
+            EntryPointBinding::Dynamic(b) => b.interpolation(),
+        }
+    }
+
+    pub(crate) fn sampling(&self) -> Option<Sampling> {
+        match self {
+            EntryPointBinding::Static(b) => b.sampling.map(|s| s.to_sampling()),
+            EntryPointBinding::Dynamic(b) => b.sampling(),
+        }
+    }
+
+    /// Resolves this binding to an inter-stage variable, applying the WGSL default interpolation
+    /// and sampling where none were declared explicitly.
+    pub(crate) fn to_inter_stage_variable(&self) -> InterStageVariable {
+        let binding_type = self.binding_type();
+
+        let interpolation = self.interpolation().unwrap_or_else(|| {
+            if binding_type.is_float() || binding_type.is_half_float() {
+                Interpolation::Perspective
+            } else {
+                Interpolation::Flat
+            }
+        });
+
+        let sampling = if interpolation == Interpolation::Flat {
+            None
+        } else {
+            Some(self.sampling().unwrap_or(Sampling::Center))
+        };
+
+        InterStageVariable {
+            location: self.location(),
+            binding_type,
+            interpolation,
+            sampling,
+        }
+    }
+}
+
+/// A user-defined variable passed between shader stages (a vertex output or a fragment input).
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub(crate) struct InterStageVariable {
+    pub(crate) location: u32,
+    pub(crate) binding_type: EntryPointBindingType,
+    pub(crate) interpolation: Interpolation,
+    pub(crate) sampling: Option<Sampling>,
+}
+
+/// Checks that every input of the fragment entry point is matched by an output of the vertex
+/// entry point with the same type, interpolation and sampling.
+///
+/// Panics with a message that names both entry points and the offending location otherwise.
+pub(crate) fn validate_inter_stage_interface(
+    vertex_entry_point: &str,
+    vertex_outputs: &[InterStageVariable],
+    fragment_entry_point: &str,
+    fragment_inputs: &[InterStageVariable],
+) {
+    for input in fragment_inputs {
+        let Some(output) = vertex_outputs.iter().find(|o| o.location == input.location) else {
+            panic!(
+                "fragment entry point `{}` reads location `{}`, but vertex entry point `{}` does \
+                not write to that location",
+                fragment_entry_point, input.location, vertex_entry_point
+            );
+        };
+
+        if output.binding_type != input.binding_type {
+            panic!(
+                "location `{}` has type `{:?}` in vertex entry point `{}`, but type `{:?}` in \
+                fragment entry point `{}`",
+                input.location,
+                output.binding_type,
+                vertex_entry_point,
+                input.binding_type,
+                fragment_entry_point
+            );
+        }
+
+        if output.interpolation != input.interpolation || output.sampling != input.sampling {
+            panic!(
+                "location `{}` uses interpolation `{:?}` (sampling `{:?}`) in vertex entry point \
+                `{}`, but interpolation `{:?}` (sampling `{:?}`) in fragment entry point `{}`",
+                input.location,
+                output.interpolation,
+                output.sampling,
+                vertex_entry_point,
+                input.interpolation,
+                input.sampling,
+                fragment_entry_point
+            );
+        }
+    }
 }
 
 pub struct ParseError {
@@ -351,7 +561,7 @@ impl fmt::Display for ParseError {
 }
 
 pub struct ShaderSource {
-    inner: ShaderSourceInternal,
+    pub(crate) inner: ShaderSourceInternal,
 }
 
 impl ShaderSource {
@@ -376,6 +586,228 @@ impl ShaderSource {
             inner: ShaderSourceInternal::Unparsed(Arc::new(raw))
         }
     }
+
+    /// Combines the given WGSL `sources` into a single shader source.
+    ///
+    /// This allows shared code (e.g. math, noise or lighting functions) to live in a separate
+    /// source that is combined with the sources that declare the entry points for a specific pass:
+    ///
+    /// ```ignore
+    /// const LIGHTING: ShaderSource = shader_source!("lighting.wgsl");
+    /// const FORWARD_PASS: ShaderSource = shader_source!("forward_pass.wgsl");
+    ///
+    /// let source = ShaderSource::concat(&[&LIGHTING, &FORWARD_PASS])?;
+    /// let shader = device.create_shader_module(&source);
+    /// ```
+    ///
+    /// The sources are concatenated in the given order and the result is parsed and reflected
+    /// like a source created with [parse](ShaderSource::parse); declarations may reference
+    /// declarations from any of the other sources. To combine sources at compile time instead, use
+    /// `#include` directives with the `shader_source!` macro.
+    ///
+    /// Returns an error if the combined source fails to parse; this includes the case where more
+    /// than one source declares an item with the same name. Note that the locations in the error
+    /// refer to the combined source.
+    pub fn concat(sources: &[&ShaderSource]) -> Result<Self, ParseError> {
+        let mut raw = String::new();
+
+        for source in sources {
+            raw.push_str(source.inner.source());
+
+            if !raw.ends_with('\n') {
+                raw.push('\n');
+            }
+        }
+
+        ShaderSource::parse(raw)
+    }
+
+    /// The WGSL source text.
+    pub fn raw_str(&self) -> &str {
+        self.inner.source()
+    }
+
+    /// Checks the resources `R` against the resource bindings this shader declares for the bind
+    /// `group`.
+    ///
+    /// Pipeline creation panics if a bind group layout does not match the shader, but only reports
+    /// the group and binding index. This check reports the same mismatches (other than visibility),
+    /// but names the field of `R` that provides the mismatched binding, along with the binding type
+    /// the shader expects, e.g. when a [Sampled2DFloat](crate::texture::Sampled2DFloat) field is
+    /// bound where the shader expects a `texture_2d_array<f32>`. This allows checking resources
+    /// early, e.g. in a test or a debug assertion:
+    ///
+    /// ```ignore
+    /// debug_assert_eq!(SHADER.check_resources::<ObjectResources>(0), Ok(()));
+    /// ```
+    ///
+    /// Only sources created with the `shader_source!` macro (or with `empa-build`) carry the
+    /// reflection data for this check; for other sources this always returns `Ok`.
+    pub fn check_resources<R: Resources>(&self, group: u32) -> Result<(), ResourcesMismatch> {
+        let ShaderSourceInternal::Static(source) = &self.inner else {
+            return Ok(());
+        };
+
+        let layout = R::Layout::BIND_GROUP_LAYOUT;
+
+        for resource_binding in source.resource_bindings {
+            if resource_binding.group != group {
+                continue;
+            }
+
+            let binding = resource_binding.binding;
+
+            let Some(Some(entry)) = layout.get(binding as usize) else {
+                return Err(ResourcesMismatch::MissingBinding { binding });
+            };
+
+            let field = R::BINDING_NAMES.get(binding as usize).copied().flatten();
+
+            if let Err(mismatch) = entry
+                .binding_type
+                .check_buffer_layout(&resource_binding.binding_type)
+            {
+                return Err(ResourcesMismatch::BufferLayout {
+                    binding,
+                    field,
+                    mismatch,
+                });
+            }
+
+            if entry.binding_type != resource_binding.binding_type {
+                return Err(ResourcesMismatch::BindingType {
+                    binding,
+                    field,
+                    expected: resource_binding.binding_type,
+                    actual: entry.binding_type,
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Marker type for entry points of the vertex stage.
+pub enum Vertex {}
+
+/// Marker type for entry points of the fragment stage.
+pub enum Fragment {}
+
+/// Marker type for entry points of the compute stage.
+pub enum Compute {}
+
+/// A typed handle for an entry point of the shader stage `Stage`.
+///
+/// Typed entry points are declared with the [shader_entry_points!] macro. Unlike an entry point
+/// name string, a typed entry point can only be passed to the builder for the matching stage, so
+/// that e.g. using a fragment entry point for a vertex stage is a compile-time error.
+pub struct EntryPoint<Stage> {
+    name: &'static str,
+    _marker: marker::PhantomData<Stage>,
+}
+
+impl<Stage> EntryPoint<Stage> {
+    /// Internal function for `shader_entry_points` macro.
+    #[doc(hidden)]
+    pub const fn new_unchecked(name: &'static str) -> Self {
+        EntryPoint {
+            name,
+            _marker: marker::PhantomData,
+        }
+    }
+
+    /// The name of the entry point.
+    pub const fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+impl<Stage> Clone for EntryPoint<Stage> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<Stage> Copy for EntryPoint<Stage> {}
+
+impl<Stage> fmt::Debug for EntryPoint<Stage> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("EntryPoint").field(&self.name).finish()
+    }
+}
+
+pub type VertexEntryPoint = EntryPoint<Vertex>;
+
+pub type FragmentEntryPoint = EntryPoint<Fragment>;
+
+pub type ComputeEntryPoint = EntryPoint<Compute>;
+
+mod entry_point_name_seal {
+    pub trait Seal<Stage> {}
+}
+
+/// Identifies an entry point for the shader stage `Stage`: either an entry point name, or a typed
+/// [EntryPoint] for the same stage.
+pub trait EntryPointName<Stage>: entry_point_name_seal::Seal<Stage> {
+    #[doc(hidden)]
+    fn entry_point_name(&self) -> &str;
+}
+
+impl<Stage> entry_point_name_seal::Seal<Stage> for &'_ str {}
+impl<Stage> EntryPointName<Stage> for &'_ str {
+    fn entry_point_name(&self) -> &str {
+        self
+    }
+}
+
+impl<Stage> entry_point_name_seal::Seal<Stage> for EntryPoint<Stage> {}
+impl<Stage> EntryPointName<Stage> for EntryPoint<Stage> {
+    fn entry_point_name(&self) -> &str {
+        self.name
+    }
+}
+
+/// A user-defined input or output of a shader entry point, as reflected from the shader source.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct EntryPointIoBinding {
+    location: u32,
+    binding_type: EntryPointBindingType,
+    interpolation: Option<Interpolation>,
+    sampling: Option<Sampling>,
+}
+
+impl EntryPointIoBinding {
+    /// The `@location` of the input or output.
+    pub fn location(&self) -> u32 {
+        self.location
+    }
+
+    /// The WGSL type of the input or output.
+    pub fn binding_type(&self) -> EntryPointBindingType {
+        self.binding_type
+    }
+
+    /// The interpolation type declared with the `@interpolate` attribute, if any.
+    pub fn interpolation(&self) -> Option<Interpolation> {
+        self.interpolation
+    }
+
+    /// The interpolation sampling declared with the `@interpolate` attribute, if any.
+    pub fn sampling(&self) -> Option<Sampling> {
+        self.sampling
+    }
+}
+
+impl From<EntryPointBinding<'_>> for EntryPointIoBinding {
+    fn from(binding: EntryPointBinding) -> Self {
+        EntryPointIoBinding {
+            location: binding.location(),
+            binding_type: binding.binding_type(),
+            interpolation: binding.interpolation(),
+            sampling: binding.sampling(),
+        }
+    }
 }
 
 pub struct ShaderModule {
@@ -386,7 +818,7 @@ pub struct ShaderModule {
 impl ShaderModule {
     pub(crate) fn new(device: &Device, source: &ShaderSource) -> Self {
         let handle = device
-            .device_handle
+            .device_handle()
             .create_shader_module(source.inner.source());
 
         ShaderModule {
@@ -394,4 +826,91 @@ impl ShaderModule {
             meta: source.inner.clone(),
         }
     }
+
+    /// Creates a shader module from the `raw` source that carries the given `meta` data, rather
+    /// than meta data for the `raw` source itself.
+    ///
+    /// The caller must ensure that the `raw` source is interface-compatible with the `meta` data.
+    #[cfg(all(feature = "hot_reload", not(any(feature = "web", feature = "noop"))))]
+    pub(crate) fn with_meta(device: &Device, raw: &str, meta: ShaderSourceInternal) -> Self {
+        let handle = device.device_handle().create_shader_module(raw);
+
+        ShaderModule { handle, meta }
+    }
+
+    /// Returns the user-defined inputs of the `entry_point`.
+    ///
+    /// Returns `None` if the shader module does not declare an entry point with the given name, or
+    /// if the shader module was created from an [unparsed](ShaderSource::unparsed) source.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// for input in shader.entry_point_inputs("frag_main").unwrap() {
+    ///     println!("{}: {:?}", input.location(), input.interpolation());
+    /// }
+    /// ```
+    pub fn entry_point_inputs<E, S>(&self, entry_point: E) -> Option<Vec<EntryPointIoBinding>>
+    where
+        E: EntryPointName<S>,
+    {
+        let index = self.resolve_entry_point(entry_point.entry_point_name())?;
+        let bindings = self.meta.entry_point_input_bindings(index)?;
+
+        Some(bindings.map(|binding| binding.into()).collect())
+    }
+
+    /// Returns the user-defined outputs of the `entry_point`.
+    ///
+    /// Returns `None` if the shader module does not declare an entry point with the given name, or
+    /// if the shader module was created from an [unparsed](ShaderSource::unparsed) source.
+    pub fn entry_point_outputs<E, S>(&self, entry_point: E) -> Option<Vec<EntryPointIoBinding>>
+    where
+        E: EntryPointName<S>,
+    {
+        let index = self.resolve_entry_point(entry_point.entry_point_name())?;
+        let bindings = self.meta.entry_point_output_bindings(index)?;
+
+        Some(bindings.map(|binding| binding.into()).collect())
+    }
+
+    /// Returns the names of the compute entry points in the shader module, in declaration order.
+    ///
+    /// Returns an empty iterator if the shader module was created from an
+    /// [unparsed](ShaderSource::unparsed) source.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// for name in shader.compute_entry_points() {
+    ///     println!("kernel: {}", name);
+    /// }
+    /// ```
+    pub fn compute_entry_points(&self) -> impl Iterator<Item = &str> + '_ {
+        (0..self.meta.entry_point_count())
+            .filter(|index| self.meta.entry_point_stage(*index) == Some(ShaderStage::Compute))
+            .filter_map(|index| self.meta.entry_point_name(index))
+    }
+
+    fn resolve_entry_point(&self, name: &str) -> Option<usize> {
+        self.meta.resolve_entry_point_index(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unparsed_source_has_no_reflection() {
+        let source = ShaderSource::unparsed("@compute @workgroup_size(1) fn main() {}".to_string());
+        let meta = &source.inner;
+
+        assert_eq!(meta.entry_point_count(), 0);
+        assert_eq!(meta.resolve_entry_point_index("main"), None);
+        assert_eq!(meta.entry_point_name(0), None);
+        assert_eq!(meta.entry_point_stage(0), None);
+        assert!(meta.entry_point_input_bindings(0).is_none());
+        assert!(meta.entry_point_output_bindings(0).is_none());
+    }
 }
@@ -0,0 +1,296 @@
+//! Helpers for writing image-based regression tests for pipelines on the native backend.
+//!
+//! Render to an offscreen image with [render_to_image] and compare the result against a reference
+//! image with [compare_images]:
+//!
+//! ```ignore
+//! let actual = render_to_image::<rgba8unorm, _>(&device, 64, 64, |encoder, image| {
+//!     encoder
+//!         .begin_render_pass(RenderPassDescriptor::new(&RenderTarget {
+//!             color: FloatAttachment {
+//!                 image,
+//!                 load_op: LoadOp::Clear([0.0; 4]),
+//!                 store_op: StoreOp::Store,
+//!             },
+//!             depth_stencil: (),
+//!         }))
+//!         // ...
+//!         .end()
+//! })
+//! .await;
+//!
+//! compare_images::<rgba8unorm>(64, 64, &expected, &actual, 1).unwrap();
+//! ```
+
+use std::error::Error;
+use std::fmt;
+
+use crate::buffer::Buffer;
+use crate::command::CommandEncoder;
+use crate::device::Device;
+use crate::texture::format::{
+    ImageBufferDataFormat, ImageCopyToBufferFormat, Renderable, Texture2DFormat,
+};
+use crate::texture::{
+    AttachableImage, ImageCopySize3D, ImageDataLayout, MipmapLevels, Texture2DDescriptor,
+};
+use crate::{buffer, texture};
+
+/// Renders to an offscreen image of format `F` and returns its contents.
+///
+/// The `encode` function receives a command encoder and an attachable image for the offscreen
+/// image, and must return the command encoder after recording its commands. The resulting image
+/// data is returned with tightly packed rows (no row padding), starting with the top row.
+///
+/// # Panics
+///
+/// Panics if the image data cannot be read back.
+pub async fn render_to_image<F, E>(device: &Device, width: u32, height: u32, encode: E) -> Vec<u8>
+where
+    F: Texture2DFormat + Renderable + ImageCopyToBufferFormat + Default,
+    E: FnOnce(CommandEncoder, AttachableImage<F>) -> CommandEncoder,
+{
+    let texture = device.create_texture_2d(&Texture2DDescriptor {
+        format: F::default(),
+        usage: texture::Usages::render_attachment().and_copy_src(),
+        view_formats: (),
+        width,
+        height,
+        layers: 1,
+        mipmap_levels: MipmapLevels::Partial(1),
+    });
+
+    let layout = ImageDataLayout::padded::<F>(ImageCopySize3D {
+        width,
+        height,
+        depth_or_layers: 1,
+    });
+    let bytes_per_block = F::BYTES_PER_BLOCK as usize;
+    let padded_len = layout.required_len(1) * bytes_per_block;
+
+    let readback: Buffer<[u8], _> = device.create_buffer(
+        vec![0u8; padded_len],
+        buffer::Usages::copy_dst().and_map_read(),
+    );
+
+    let encoder = encode(
+        device.create_command_encoder(),
+        texture.attachable_image(&Default::default()),
+    );
+
+    let command_buffer = encoder
+        .image_copy_texture_to_buffer_raw(
+            texture.image_copy_to_buffer_src(0),
+            readback.image_copy_dst_raw(layout.to_byte_layout(F::BYTES_PER_BLOCK)),
+        )
+        .finish();
+
+    device.queue().submit(command_buffer);
+
+    readback
+        .map_read()
+        .await
+        .expect("failed to map the readback buffer");
+
+    let row_len = width as usize * bytes_per_block;
+    let padded_row_len = layout.blocks_per_row as usize * bytes_per_block;
+    let mut data = Vec::with_capacity(row_len * height as usize);
+
+    {
+        let mapped = readback.mapped();
+
+        for row in mapped.chunks(padded_row_len).take(height as usize) {
+            data.extend_from_slice(&row[..row_len]);
+        }
+    }
+
+    readback.unmap();
+
+    data
+}
+
+/// Error returned by [compare_images] if the images differ by more than the tolerance.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ImageMismatch {
+    /// The number of pixels for which at least one channel exceeds the tolerance.
+    pub mismatched_pixels: usize,
+    /// The largest difference found in any channel.
+    pub max_difference: u8,
+    /// The x coordinate of the first mismatched pixel.
+    pub x: u32,
+    /// The y coordinate of the first mismatched pixel.
+    pub y: u32,
+    /// The channel (byte index within the pixel) of the first mismatch.
+    pub channel: u32,
+    pub expected: u8,
+    pub actual: u8,
+}
+
+impl fmt::Display for ImageMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} pixel(s) differ (max difference `{}`); first mismatch at ({}, {}) channel `{}`: \
+             expected `{}`, found `{}`",
+            self.mismatched_pixels,
+            self.max_difference,
+            self.x,
+            self.y,
+            self.channel,
+            self.expected,
+            self.actual
+        )
+    }
+}
+
+impl Error for ImageMismatch {}
+
+/// Compares two images of format `F` with tightly packed rows (as returned by [render_to_image]).
+///
+/// Every channel byte of `actual` may differ from the corresponding byte in `expected` by at most
+/// `tolerance`.
+///
+/// # Panics
+///
+/// Panics if the length of either image does not match `width * height` pixels of format `F`.
+pub fn compare_images<F>(
+    width: u32,
+    height: u32,
+    expected: &[u8],
+    actual: &[u8],
+    tolerance: u8,
+) -> Result<(), ImageMismatch>
+where
+    F: ImageBufferDataFormat,
+{
+    let bytes_per_pixel = F::BYTES_PER_BLOCK as usize;
+    let len = width as usize * height as usize * bytes_per_pixel;
+
+    assert_eq!(
+        expected.len(),
+        len,
+        "the length of `expected` does not match the image size"
+    );
+    assert_eq!(
+        actual.len(),
+        len,
+        "the length of `actual` does not match the image size"
+    );
+
+    let mut mismatch: Option<ImageMismatch> = None;
+
+    for (index, (expected_pixel, actual_pixel)) in expected
+        .chunks(bytes_per_pixel)
+        .zip(actual.chunks(bytes_per_pixel))
+        .enumerate()
+    {
+        let mut pixel_mismatched = false;
+
+        for (channel, (e, a)) in expected_pixel.iter().zip(actual_pixel).enumerate() {
+            let difference = e.abs_diff(*a);
+
+            if difference <= tolerance {
+                continue;
+            }
+
+            if let Some(mismatch) = mismatch.as_mut() {
+                mismatch.max_difference = mismatch.max_difference.max(difference);
+            } else {
+                mismatch = Some(ImageMismatch {
+                    mismatched_pixels: 0,
+                    max_difference: difference,
+                    x: (index % width as usize) as u32,
+                    y: (index / width as usize) as u32,
+                    channel: channel as u32,
+                    expected: *e,
+                    actual: *a,
+                });
+            }
+
+            pixel_mismatched = true;
+        }
+
+        if pixel_mismatched {
+            if let Some(mismatch) = mismatch.as_mut() {
+                mismatch.mismatched_pixels += 1;
+            }
+        }
+    }
+
+    match mismatch {
+        Some(mismatch) => Err(mismatch),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::texture::format::{r8unorm, rgba8unorm};
+
+    #[test]
+    fn identical_images_match() {
+        let image = [10, 20, 30, 255, 40, 50, 60, 255];
+
+        assert_eq!(
+            compare_images::<rgba8unorm>(2, 1, &image, &image, 0),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn differences_within_tolerance_match() {
+        let expected = [10, 20, 30, 255];
+        let actual = [12, 18, 30, 254];
+
+        assert_eq!(
+            compare_images::<rgba8unorm>(1, 1, &expected, &actual, 2),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn reports_first_mismatch() {
+        let expected = [0, 0, 0, 0, 0, 0];
+        let actual = [0, 0, 9, 0, 5, 0];
+
+        assert_eq!(
+            compare_images::<r8unorm>(3, 2, &expected, &actual, 1),
+            Err(ImageMismatch {
+                mismatched_pixels: 2,
+                max_difference: 9,
+                x: 2,
+                y: 0,
+                channel: 0,
+                expected: 0,
+                actual: 9,
+            })
+        );
+    }
+
+    #[test]
+    fn counts_pixels_not_channels() {
+        let expected = [0, 0, 0, 0, 0, 0, 0, 0];
+        let actual = [0, 0, 0, 0, 7, 8, 0, 0];
+
+        assert_eq!(
+            compare_images::<rgba8unorm>(2, 1, &expected, &actual, 0),
+            Err(ImageMismatch {
+                mismatched_pixels: 1,
+                max_difference: 8,
+                x: 1,
+                y: 0,
+                channel: 0,
+                expected: 0,
+                actual: 7,
+            })
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "the length of `actual` does not match the image size")]
+    fn image_size_mismatch() {
+        let _ = compare_images::<rgba8unorm>(1, 1, &[0; 4], &[0; 8], 0);
+    }
+}
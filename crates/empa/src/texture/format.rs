@@ -3,6 +3,7 @@
 use std::iter;
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(non_camel_case_types)]
 pub enum TextureFormatId {
     r8unorm,
@@ -31,6 +32,7 @@ pub enum TextureFormatId {
     bgra8unorm_srgb,
     rgb9e5ufloat,
     rgb10a2unorm,
+    rgb10a2uint,
     rg11b10ufloat,
     rg32uint,
     rg32sint,
@@ -38,6 +40,18 @@ pub enum TextureFormatId {
     rgba16uint,
     rgba16sint,
     rgba16float,
+    #[cfg(feature = "norm16")]
+    r16unorm,
+    #[cfg(feature = "norm16")]
+    r16snorm,
+    #[cfg(feature = "norm16")]
+    rg16unorm,
+    #[cfg(feature = "norm16")]
+    rg16snorm,
+    #[cfg(feature = "norm16")]
+    rgba16unorm,
+    #[cfg(feature = "norm16")]
+    rgba16snorm,
     rgba32uint,
     rgba32sint,
     rgba32float,
@@ -102,6 +116,126 @@ pub enum TextureFormatId {
 }
 
 impl TextureFormatId {
+    /// The width and height in texels of a single block of this format.
+    pub fn block_dimensions(&self) -> [u32; 2] {
+        use TextureFormatId::*;
+
+        match self {
+            bc1_rgba_unorm
+            | bc1_rgba_unorm_srgb
+            | bc2_rgba_unorm
+            | bc2_rgba_unorm_srgb
+            | bc3_rgba_unorm
+            | bc3_rgba_unorm_srgb
+            | bc4_r_unorm
+            | bc4_r_snorm
+            | bc5_rg_unorm
+            | bc5_rg_snorm
+            | bc6h_rgb_ufloat
+            | bc6h_rgb_float
+            | bc7_rgba_unorm
+            | bc7_rgba_unorm_srgb
+            | etc2_rgb8unorm
+            | etc2_rgb8unorm_srgb
+            | etc2_rgb8a1unorm
+            | etc2_rgb8a1unorm_srgb
+            | etc2_rgba8unorm
+            | etc2_rgba8unorm_srgb
+            | eac_r11unorm
+            | eac_r11snorm
+            | eac_rg11unorm
+            | eac_rg11snorm
+            | astc_4x4_unorm
+            | astc_4x4_unorm_srgb => [4, 4],
+            astc_5x4_unorm | astc_5x4_unorm_srgb => [5, 4],
+            astc_5x5_unorm | astc_5x5_unorm_srgb => [5, 5],
+            astc_6x5_unorm | astc_6x5_unorm_srgb => [6, 5],
+            astc_6x6_unorm | astc_6x6_unorm_srgb => [6, 6],
+            astc_8x5_unorm | astc_8x5_unorm_srgb => [8, 5],
+            astc_8x6_unorm | astc_8x6_unorm_srgb => [8, 6],
+            astc_8x8_unorm | astc_8x8_unorm_srgb => [8, 8],
+            astc_10x5_unorm | astc_10x5_unorm_srgb => [10, 5],
+            astc_10x6_unorm | astc_10x6_unorm_srgb => [10, 6],
+            astc_10x8_unorm | astc_10x8_unorm_srgb => [10, 8],
+            astc_10x10_unorm | astc_10x10_unorm_srgb => [10, 10],
+            astc_12x10_unorm | astc_12x10_unorm_srgb => [12, 10],
+            astc_12x12_unorm | astc_12x12_unorm_srgb => [12, 12],
+            _ => [1, 1],
+        }
+    }
+
+    /// The size in bytes of a single block of this format.
+    ///
+    /// For depth-stencil formats without a defined memory layout, this is an estimate.
+    pub fn bytes_per_block(&self) -> u32 {
+        use TextureFormatId::*;
+
+        match self {
+            r8unorm | r8snorm | r8uint | r8sint | stencil8 => 1,
+            r16uint | r16sint | r16float | rg8unorm | rg8snorm | rg8uint | rg8sint
+            | depth16unorm => 2,
+            r32uint | r32sint | r32float | rg16uint | rg16sint | rg16float | rgba8unorm
+            | rgba8unorm_srgb | rgba8snorm | rgba8uint | rgba8sint | bgra8unorm
+            | bgra8unorm_srgb | rgb9e5ufloat | rgb10a2unorm | rgb10a2uint | rg11b10ufloat
+            | depth24plus | depth24plus_stencil8 | depth32float => 4,
+            rg32uint
+            | rg32sint
+            | rg32float
+            | rgba16uint
+            | rgba16sint
+            | rgba16float
+            | depth32float_stencil8
+            | bc1_rgba_unorm
+            | bc1_rgba_unorm_srgb
+            | bc4_r_unorm
+            | bc4_r_snorm
+            | etc2_rgb8unorm
+            | etc2_rgb8unorm_srgb
+            | etc2_rgb8a1unorm
+            | etc2_rgb8a1unorm_srgb
+            | eac_r11unorm
+            | eac_r11snorm => 8,
+            #[cfg(feature = "norm16")]
+            r16unorm | r16snorm => 2,
+            #[cfg(feature = "norm16")]
+            rg16unorm | rg16snorm => 4,
+            #[cfg(feature = "norm16")]
+            rgba16unorm | rgba16snorm => 8,
+            _ => 16,
+        }
+    }
+
+    /// Whether this is a block-compressed format (a BC, ETC2, EAC or ASTC format).
+    pub fn is_compressed(&self) -> bool {
+        self.block_dimensions() != [1, 1]
+    }
+
+    /// The number of color components of this format, or `0` for depth-stencil formats.
+    ///
+    /// Only meaningful for color-renderable and depth-stencil formats.
+    pub(crate) fn component_count(&self) -> u32 {
+        use TextureFormatId::*;
+
+        match self {
+            r8unorm | r8snorm | r8uint | r8sint | r16uint | r16sint | r16float | r32uint
+            | r32sint | r32float => 1,
+            rg8unorm | rg8snorm | rg8uint | rg8sint | rg16uint | rg16sint | rg16float
+            | rg32uint | rg32sint | rg32float => 2,
+            rg11b10ufloat | rgb9e5ufloat => 3,
+            #[cfg(feature = "norm16")]
+            r16unorm | r16snorm => 1,
+            #[cfg(feature = "norm16")]
+            rg16unorm | rg16snorm => 2,
+            stencil8
+            | depth16unorm
+            | depth24plus
+            | depth24plus_stencil8
+            | depth32float
+            | depth32float_stencil8 => 0,
+            _ => 4,
+        }
+    }
+
     pub(crate) fn is_float(&self) -> bool {
         match self {
             TextureFormatId::r8unorm
@@ -116,8 +250,16 @@ impl TextureFormatId {
             | TextureFormatId::bgra8unorm_srgb
             | TextureFormatId::rgb9e5ufloat
             | TextureFormatId::rgb10a2unorm
+            | TextureFormatId::rg11b10ufloat
             | TextureFormatId::rg32float
             | TextureFormatId::rgba32float => true,
+            #[cfg(feature = "norm16")]
+            TextureFormatId::r16unorm
+            | TextureFormatId::r16snorm
+            | TextureFormatId::rg16unorm
+            | TextureFormatId::rg16snorm
+            | TextureFormatId::rgba16unorm
+            | TextureFormatId::rgba16snorm => true,
             _ => false,
         }
     }
@@ -156,7 +298,8 @@ impl TextureFormatId {
             | TextureFormatId::rgba8uint
             | TextureFormatId::rg32uint
             | TextureFormatId::rgba16uint
-            | TextureFormatId::rgba32uint => true,
+            | TextureFormatId::rgba32uint
+            | TextureFormatId::rgb10a2uint => true,
             _ => false,
         }
     }
@@ -174,7 +317,7 @@ pub trait TextureFormat: texture_format_seal::Seal {
 
 macro_rules! typed_texture_format {
     ($format:ident, $block_width:literal, $block_height:literal) => {
-        #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+        #[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
         pub struct $format;
 
         impl texture_format_seal::Seal for $format {}
@@ -215,6 +358,7 @@ typed_texture_format!(bgra8unorm);
 typed_texture_format!(bgra8unorm_srgb);
 typed_texture_format!(rgb9e5ufloat);
 typed_texture_format!(rgb10a2unorm);
+typed_texture_format!(rgb10a2uint);
 typed_texture_format!(rg11b10ufloat);
 typed_texture_format!(rg32uint);
 typed_texture_format!(rg32sint);
@@ -315,6 +459,7 @@ impl Texture1DFormat for bgra8unorm {}
 impl Texture1DFormat for bgra8unorm_srgb {}
 impl Texture1DFormat for rgb9e5ufloat {}
 impl Texture1DFormat for rgb10a2unorm {}
+impl Texture1DFormat for rgb10a2uint {}
 impl Texture1DFormat for rg11b10ufloat {}
 impl Texture1DFormat for rg32uint {}
 impl Texture1DFormat for rg32sint {}
@@ -354,6 +499,7 @@ impl Texture2DFormat for bgra8unorm {}
 impl Texture2DFormat for bgra8unorm_srgb {}
 impl Texture2DFormat for rgb9e5ufloat {}
 impl Texture2DFormat for rgb10a2unorm {}
+impl Texture2DFormat for rgb10a2uint {}
 impl Texture2DFormat for rg11b10ufloat {}
 impl Texture2DFormat for rg32uint {}
 impl Texture2DFormat for rg32sint {}
@@ -451,6 +597,7 @@ impl Texture3DFormat for bgra8unorm {}
 impl Texture3DFormat for bgra8unorm_srgb {}
 impl Texture3DFormat for rgb9e5ufloat {}
 impl Texture3DFormat for rgb10a2unorm {}
+impl Texture3DFormat for rgb10a2uint {}
 impl Texture3DFormat for rg11b10ufloat {}
 impl Texture3DFormat for rg32uint {}
 impl Texture3DFormat for rg32sint {}
@@ -462,6 +609,11 @@ impl Texture3DFormat for rgba32uint {}
 impl Texture3DFormat for rgba32sint {}
 impl Texture3DFormat for rgba32float {}
 
+/// Marker trait for formats that can be sampled as floating point values with a filtering
+/// sampler.
+///
+/// Textures with these formats can be bound as `texture_2d<f32>` (and the other sampled texture
+/// types) and may be sampled with linear filtering.
 pub trait FloatSamplable: TextureFormat {}
 
 impl FloatSamplable for r8unorm {}
@@ -532,6 +684,8 @@ impl FloatSamplable for astc_12x10_unorm_srgb {}
 impl FloatSamplable for astc_12x12_unorm {}
 impl FloatSamplable for astc_12x12_unorm_srgb {}
 
+/// Marker trait for formats that can be sampled as floating point values with a non-filtering
+/// sampler.
 pub trait UnfilteredFloatSamplable: TextureFormat {}
 
 impl UnfilteredFloatSamplable for r8unorm {}
@@ -605,6 +759,7 @@ impl UnfilteredFloatSamplable for astc_12x10_unorm_srgb {}
 impl UnfilteredFloatSamplable for astc_12x12_unorm {}
 impl UnfilteredFloatSamplable for astc_12x12_unorm_srgb {}
 
+/// Marker trait for formats that can be sampled as signed integer values (`i32`).
 pub trait SignedIntegerSamplable: TextureFormat {}
 
 impl SignedIntegerSamplable for r8sint {}
@@ -617,6 +772,7 @@ impl SignedIntegerSamplable for r32sint {}
 impl SignedIntegerSamplable for rg32sint {}
 impl SignedIntegerSamplable for rgba32sint {}
 
+/// Marker trait for formats that can be sampled as unsigned integer values (`u32`).
 pub trait UnsignedIntegerSamplable: TextureFormat {}
 
 impl UnsignedIntegerSamplable for r8uint {}
@@ -628,7 +784,9 @@ impl UnsignedIntegerSamplable for rgba16uint {}
 impl UnsignedIntegerSamplable for r32uint {}
 impl UnsignedIntegerSamplable for rg32uint {}
 impl UnsignedIntegerSamplable for rgba32uint {}
+impl UnsignedIntegerSamplable for rgb10a2uint {}
 
+/// Marker trait for formats that can be sampled as depth values, e.g. with a comparison sampler.
 pub trait DepthSamplable: TextureFormat {}
 
 impl DepthSamplable for depth16unorm {}
@@ -660,6 +818,10 @@ impl DepthStencilTestFormat for depth24plus_stencil8 {}
 impl DepthStencilTestFormat for depth32float_stencil8 {}
 impl DepthStencilTestFormat for stencil8 {}
 
+/// Marker trait for formats that can be used for storage textures.
+///
+/// Only textures with these formats can be created with the
+/// [StorageBinding](crate::texture::StorageBinding) usage.
 pub trait Storable: TextureFormat {}
 
 impl Storable for rgba8unorm {}
@@ -679,6 +841,10 @@ impl Storable for rgba32uint {}
 impl Storable for rgba32sint {}
 impl Storable for rgba32float {}
 
+/// Marker trait for formats that can be used as render attachments.
+///
+/// Only textures with these formats can be created with the
+/// [RenderAttachment](crate::texture::RenderAttachment) usage.
 pub trait Renderable: TextureFormat {}
 
 impl Renderable for r8unorm {}
@@ -711,6 +877,7 @@ impl Renderable for rgba16uint {}
 impl Renderable for r32uint {}
 impl Renderable for rg32uint {}
 impl Renderable for rgba32uint {}
+impl Renderable for rgb10a2uint {}
 impl Renderable for stencil8 {}
 impl Renderable for depth16unorm {}
 impl Renderable for depth24plus {}
@@ -718,6 +885,7 @@ impl Renderable for depth24plus_stencil8 {}
 impl Renderable for depth32float {}
 impl Renderable for depth32float_stencil8 {}
 
+/// Marker trait for formats that can be used as color attachments.
 pub trait ColorRenderable: Renderable {}
 
 impl ColorRenderable for r8unorm {}
@@ -750,7 +918,9 @@ impl ColorRenderable for rgba16uint {}
 impl ColorRenderable for r32uint {}
 impl ColorRenderable for rg32uint {}
 impl ColorRenderable for rgba32uint {}
+impl ColorRenderable for rgb10a2uint {}
 
+/// Marker trait for color formats to which a fragment shader writes floating point values.
 pub trait FloatRenderable: ColorRenderable {}
 
 impl FloatRenderable for r8unorm {}
@@ -766,6 +936,7 @@ impl FloatRenderable for r32float {}
 impl FloatRenderable for rgba32float {}
 impl FloatRenderable for rgb10a2unorm {}
 
+/// Marker trait for color formats to which a fragment shader writes signed integer values.
 pub trait SignedIntegerRenderable: ColorRenderable {}
 
 impl SignedIntegerRenderable for r8sint {}
@@ -778,6 +949,7 @@ impl SignedIntegerRenderable for r32sint {}
 impl SignedIntegerRenderable for rg32sint {}
 impl SignedIntegerRenderable for rgba32sint {}
 
+/// Marker trait for color formats to which a fragment shader writes unsigned integer values.
 pub trait UnsignedIntegerRenderable: ColorRenderable {}
 
 impl UnsignedIntegerRenderable for r8uint {}
@@ -789,6 +961,7 @@ impl UnsignedIntegerRenderable for rgba16uint {}
 impl UnsignedIntegerRenderable for r32uint {}
 impl UnsignedIntegerRenderable for rg32uint {}
 impl UnsignedIntegerRenderable for rgba32uint {}
+impl UnsignedIntegerRenderable for rgb10a2uint {}
 
 pub trait DepthStencilRenderable: Renderable {
     const HAS_DEPTH_COMPONENT: bool;
@@ -836,6 +1009,7 @@ pub trait StencilRenderable: DepthStencilRenderable {}
 
 impl StencilRenderable for stencil8 {}
 
+/// Marker trait for formats that can be used for multisampled textures.
 pub trait MultisampleFormat: TextureFormat {}
 
 impl MultisampleFormat for r8unorm {}
@@ -860,6 +1034,7 @@ impl MultisampleFormat for rg16uint {}
 impl MultisampleFormat for rg16sint {}
 impl MultisampleFormat for rg16float {}
 impl MultisampleFormat for rgba16uint {}
+impl MultisampleFormat for rgb10a2uint {}
 impl MultisampleFormat for rgba16sint {}
 impl MultisampleFormat for rgba16float {}
 impl MultisampleFormat for r32float {}
@@ -893,6 +1068,7 @@ impl MultisampleColorRenderable for rg16uint {}
 impl MultisampleColorRenderable for rg16sint {}
 impl MultisampleColorRenderable for rg16float {}
 impl MultisampleColorRenderable for rgba16uint {}
+impl MultisampleColorRenderable for rgb10a2uint {}
 impl MultisampleColorRenderable for rgba16sint {}
 impl MultisampleColorRenderable for rgba16float {}
 impl MultisampleColorRenderable for r32float {}
@@ -929,7 +1105,9 @@ impl MultisampleUnsignedIntegerRenderable for rgba8uint {}
 impl MultisampleUnsignedIntegerRenderable for r16uint {}
 impl MultisampleUnsignedIntegerRenderable for rg16uint {}
 impl MultisampleUnsignedIntegerRenderable for rgba16uint {}
+impl MultisampleUnsignedIntegerRenderable for rgb10a2uint {}
 
+/// Marker trait for multisampled color formats that can be resolved into a single-sampled texture.
 pub trait Resolvable: MultisampleColorRenderable {}
 
 impl Resolvable for r8unorm {}
@@ -943,6 +1121,7 @@ impl Resolvable for rg16float {}
 impl Resolvable for rgba16float {}
 impl Resolvable for rgb10a2unorm {}
 
+/// Marker trait for color formats that support blending.
 pub trait Blendable: ColorRenderable {}
 
 impl Blendable for r8unorm {}
@@ -1002,6 +1181,7 @@ impl_buffer_data_format!(bgra8unorm, 4);
 impl_buffer_data_format!(bgra8unorm_srgb, 4);
 impl_buffer_data_format!(rgb9e5ufloat, 4);
 impl_buffer_data_format!(rgb10a2unorm, 4);
+impl_buffer_data_format!(rgb10a2uint, 4);
 impl_buffer_data_format!(rg11b10ufloat, 4);
 impl_buffer_data_format!(rg32uint, 8);
 impl_buffer_data_format!(rg32sint, 8);
@@ -1068,6 +1248,66 @@ impl_buffer_data_format!(astc_12x10_unorm_srgb, 16);
 impl_buffer_data_format!(astc_12x12_unorm, 16);
 impl_buffer_data_format!(astc_12x12_unorm_srgb, 16);
 
+/// Marker trait for block-compressed formats (the BC, ETC2, EAC and ASTC formats).
+///
+/// Data for these formats is supplied as raw bytes, where each block of
+/// [BLOCK_SIZE](TextureFormat::BLOCK_SIZE) texels occupies
+/// [BYTES_PER_BLOCK](ImageBufferDataFormat::BYTES_PER_BLOCK) bytes.
+pub trait CompressedFormat: ImageCopyFromBufferFormat {}
+
+impl CompressedFormat for bc1_rgba_unorm {}
+impl CompressedFormat for bc1_rgba_unorm_srgb {}
+impl CompressedFormat for bc2_rgba_unorm {}
+impl CompressedFormat for bc2_rgba_unorm_srgb {}
+impl CompressedFormat for bc3_rgba_unorm {}
+impl CompressedFormat for bc3_rgba_unorm_srgb {}
+impl CompressedFormat for bc4_r_unorm {}
+impl CompressedFormat for bc4_r_snorm {}
+impl CompressedFormat for bc5_rg_unorm {}
+impl CompressedFormat for bc5_rg_snorm {}
+impl CompressedFormat for bc6h_rgb_ufloat {}
+impl CompressedFormat for bc6h_rgb_float {}
+impl CompressedFormat for bc7_rgba_unorm {}
+impl CompressedFormat for bc7_rgba_unorm_srgb {}
+impl CompressedFormat for etc2_rgb8unorm {}
+impl CompressedFormat for etc2_rgb8unorm_srgb {}
+impl CompressedFormat for etc2_rgb8a1unorm {}
+impl CompressedFormat for etc2_rgb8a1unorm_srgb {}
+impl CompressedFormat for etc2_rgba8unorm {}
+impl CompressedFormat for etc2_rgba8unorm_srgb {}
+impl CompressedFormat for eac_r11unorm {}
+impl CompressedFormat for eac_r11snorm {}
+impl CompressedFormat for eac_rg11unorm {}
+impl CompressedFormat for eac_rg11snorm {}
+impl CompressedFormat for astc_4x4_unorm {}
+impl CompressedFormat for astc_4x4_unorm_srgb {}
+impl CompressedFormat for astc_5x4_unorm {}
+impl CompressedFormat for astc_5x4_unorm_srgb {}
+impl CompressedFormat for astc_5x5_unorm {}
+impl CompressedFormat for astc_5x5_unorm_srgb {}
+impl CompressedFormat for astc_6x5_unorm {}
+impl CompressedFormat for astc_6x5_unorm_srgb {}
+impl CompressedFormat for astc_6x6_unorm {}
+impl CompressedFormat for astc_6x6_unorm_srgb {}
+impl CompressedFormat for astc_8x5_unorm {}
+impl CompressedFormat for astc_8x5_unorm_srgb {}
+impl CompressedFormat for astc_8x6_unorm {}
+impl CompressedFormat for astc_8x6_unorm_srgb {}
+impl CompressedFormat for astc_8x8_unorm {}
+impl CompressedFormat for astc_8x8_unorm_srgb {}
+impl CompressedFormat for astc_10x5_unorm {}
+impl CompressedFormat for astc_10x5_unorm_srgb {}
+impl CompressedFormat for astc_10x6_unorm {}
+impl CompressedFormat for astc_10x6_unorm_srgb {}
+impl CompressedFormat for astc_10x8_unorm {}
+impl CompressedFormat for astc_10x8_unorm_srgb {}
+impl CompressedFormat for astc_10x10_unorm {}
+impl CompressedFormat for astc_10x10_unorm_srgb {}
+impl CompressedFormat for astc_12x10_unorm {}
+impl CompressedFormat for astc_12x10_unorm_srgb {}
+impl CompressedFormat for astc_12x12_unorm {}
+impl CompressedFormat for astc_12x12_unorm_srgb {}
+
 /// Marker trait for types that can be copied from a texture to a buffer.
 pub trait ImageCopyToBufferFormat: ImageBufferDataFormat {}
 
@@ -1097,6 +1337,7 @@ impl ImageCopyToBufferFormat for bgra8unorm {}
 impl ImageCopyToBufferFormat for bgra8unorm_srgb {}
 impl ImageCopyToBufferFormat for rgb9e5ufloat {}
 impl ImageCopyToBufferFormat for rgb10a2unorm {}
+impl ImageCopyToBufferFormat for rgb10a2uint {}
 impl ImageCopyToBufferFormat for rg11b10ufloat {}
 impl ImageCopyToBufferFormat for rg32uint {}
 impl ImageCopyToBufferFormat for rg32sint {}
@@ -1192,6 +1433,7 @@ impl ImageCopyFromBufferFormat for bgra8unorm {}
 impl ImageCopyFromBufferFormat for bgra8unorm_srgb {}
 impl ImageCopyFromBufferFormat for rgb9e5ufloat {}
 impl ImageCopyFromBufferFormat for rgb10a2unorm {}
+impl ImageCopyFromBufferFormat for rgb10a2uint {}
 impl ImageCopyFromBufferFormat for rg11b10ufloat {}
 impl ImageCopyFromBufferFormat for rg32uint {}
 impl ImageCopyFromBufferFormat for rg32sint {}
@@ -1286,6 +1528,7 @@ impl ImageCopyTextureFormat for bgra8unorm {}
 impl ImageCopyTextureFormat for bgra8unorm_srgb {}
 impl ImageCopyTextureFormat for rgb9e5ufloat {}
 impl ImageCopyTextureFormat for rgb10a2unorm {}
+impl ImageCopyTextureFormat for rgb10a2uint {}
 impl ImageCopyTextureFormat for rg11b10ufloat {}
 impl ImageCopyTextureFormat for rg32uint {}
 impl ImageCopyTextureFormat for rg32sint {}
@@ -1353,6 +1596,51 @@ impl ImageCopyTextureFormat for astc_12x10_unorm_srgb {}
 impl ImageCopyTextureFormat for astc_12x12_unorm {}
 impl ImageCopyTextureFormat for astc_12x12_unorm_srgb {}
 
+/// Marker trait for formats that a texture with format `F` can be copied to with a texture to
+/// texture copy operation.
+///
+/// Formats are always copy-compatible with themselves. Additionally, formats that only differ in
+/// whether or not they are sRGB-encoded (e.g. [rgba8unorm] and [rgba8unorm_srgb]) are
+/// copy-compatible.
+pub trait CopyCompatible<F>: ImageCopyTextureFormat
+where
+    F: ImageCopyTextureFormat,
+{
+}
+
+impl<F> CopyCompatible<F> for F where F: ImageCopyTextureFormat {}
+
+macro_rules! impl_srgb_copy_compatible {
+    ($linear:ident, $srgb:ident) => {
+        impl CopyCompatible<$srgb> for $linear {}
+        impl CopyCompatible<$linear> for $srgb {}
+    };
+}
+
+impl_srgb_copy_compatible!(rgba8unorm, rgba8unorm_srgb);
+impl_srgb_copy_compatible!(bgra8unorm, bgra8unorm_srgb);
+impl_srgb_copy_compatible!(bc1_rgba_unorm, bc1_rgba_unorm_srgb);
+impl_srgb_copy_compatible!(bc2_rgba_unorm, bc2_rgba_unorm_srgb);
+impl_srgb_copy_compatible!(bc3_rgba_unorm, bc3_rgba_unorm_srgb);
+impl_srgb_copy_compatible!(bc7_rgba_unorm, bc7_rgba_unorm_srgb);
+impl_srgb_copy_compatible!(etc2_rgb8unorm, etc2_rgb8unorm_srgb);
+impl_srgb_copy_compatible!(etc2_rgb8a1unorm, etc2_rgb8a1unorm_srgb);
+impl_srgb_copy_compatible!(etc2_rgba8unorm, etc2_rgba8unorm_srgb);
+impl_srgb_copy_compatible!(astc_4x4_unorm, astc_4x4_unorm_srgb);
+impl_srgb_copy_compatible!(astc_5x4_unorm, astc_5x4_unorm_srgb);
+impl_srgb_copy_compatible!(astc_5x5_unorm, astc_5x5_unorm_srgb);
+impl_srgb_copy_compatible!(astc_6x5_unorm, astc_6x5_unorm_srgb);
+impl_srgb_copy_compatible!(astc_6x6_unorm, astc_6x6_unorm_srgb);
+impl_srgb_copy_compatible!(astc_8x5_unorm, astc_8x5_unorm_srgb);
+impl_srgb_copy_compatible!(astc_8x6_unorm, astc_8x6_unorm_srgb);
+impl_srgb_copy_compatible!(astc_8x8_unorm, astc_8x8_unorm_srgb);
+impl_srgb_copy_compatible!(astc_10x5_unorm, astc_10x5_unorm_srgb);
+impl_srgb_copy_compatible!(astc_10x6_unorm, astc_10x6_unorm_srgb);
+impl_srgb_copy_compatible!(astc_10x8_unorm, astc_10x8_unorm_srgb);
+impl_srgb_copy_compatible!(astc_10x10_unorm, astc_10x10_unorm_srgb);
+impl_srgb_copy_compatible!(astc_12x10_unorm, astc_12x10_unorm_srgb);
+impl_srgb_copy_compatible!(astc_12x12_unorm, astc_12x12_unorm_srgb);
+
 /// Marker trait for formats that can be used in sub-image copy operations.
 ///
 /// Not all copyable formats (see [ImageCopyToBufferFormat], [ImageCopyFromBufferFormat],
@@ -1386,6 +1674,7 @@ impl SubImageCopyFormat for bgra8unorm {}
 impl SubImageCopyFormat for bgra8unorm_srgb {}
 impl SubImageCopyFormat for rgb9e5ufloat {}
 impl SubImageCopyFormat for rgb10a2unorm {}
+impl SubImageCopyFormat for rgb10a2uint {}
 impl SubImageCopyFormat for rg11b10ufloat {}
 impl SubImageCopyFormat for rg32uint {}
 impl SubImageCopyFormat for rg32sint {}
@@ -1547,3 +1836,8 @@ unsafe impl ImageData<rgba32float> for [f32; 4] {}
 unsafe impl ImageData<stencil8> for u8 {}
 unsafe impl ImageData<depth16unorm> for u16 {}
 unsafe impl ImageData<depth32float> for f32 {}
+
+#[cfg(feature = "norm16")]
+mod norm16;
+#[cfg(feature = "norm16")]
+pub use self::norm16::*;
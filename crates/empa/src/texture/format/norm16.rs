@@ -0,0 +1,91 @@
+//! The 16-bit normalized formats.
+//!
+//! These formats are not part of the WebGPU specification; they are only available on native
+//! targets, on devices that enable [Feature::TextureFormat16BitNorm](crate::adapter::Feature).
+//! Textures with these formats can be sampled with filtering and copied to and from buffers.
+
+use super::*;
+
+typed_texture_format!(r16unorm);
+typed_texture_format!(r16snorm);
+typed_texture_format!(rg16unorm);
+typed_texture_format!(rg16snorm);
+typed_texture_format!(rgba16unorm);
+typed_texture_format!(rgba16snorm);
+
+impl Texture1DFormat for r16unorm {}
+impl Texture1DFormat for r16snorm {}
+impl Texture1DFormat for rg16unorm {}
+impl Texture1DFormat for rg16snorm {}
+impl Texture1DFormat for rgba16unorm {}
+impl Texture1DFormat for rgba16snorm {}
+
+impl Texture2DFormat for r16unorm {}
+impl Texture2DFormat for r16snorm {}
+impl Texture2DFormat for rg16unorm {}
+impl Texture2DFormat for rg16snorm {}
+impl Texture2DFormat for rgba16unorm {}
+impl Texture2DFormat for rgba16snorm {}
+
+impl Texture3DFormat for r16unorm {}
+impl Texture3DFormat for r16snorm {}
+impl Texture3DFormat for rg16unorm {}
+impl Texture3DFormat for rg16snorm {}
+impl Texture3DFormat for rgba16unorm {}
+impl Texture3DFormat for rgba16snorm {}
+
+impl FloatSamplable for r16unorm {}
+impl FloatSamplable for r16snorm {}
+impl FloatSamplable for rg16unorm {}
+impl FloatSamplable for rg16snorm {}
+impl FloatSamplable for rgba16unorm {}
+impl FloatSamplable for rgba16snorm {}
+
+impl UnfilteredFloatSamplable for r16unorm {}
+impl UnfilteredFloatSamplable for r16snorm {}
+impl UnfilteredFloatSamplable for rg16unorm {}
+impl UnfilteredFloatSamplable for rg16snorm {}
+impl UnfilteredFloatSamplable for rgba16unorm {}
+impl UnfilteredFloatSamplable for rgba16snorm {}
+
+impl_buffer_data_format!(r16unorm, 2);
+impl_buffer_data_format!(r16snorm, 2);
+impl_buffer_data_format!(rg16unorm, 4);
+impl_buffer_data_format!(rg16snorm, 4);
+impl_buffer_data_format!(rgba16unorm, 8);
+impl_buffer_data_format!(rgba16snorm, 8);
+
+impl ImageCopyToBufferFormat for r16unorm {}
+impl ImageCopyToBufferFormat for r16snorm {}
+impl ImageCopyToBufferFormat for rg16unorm {}
+impl ImageCopyToBufferFormat for rg16snorm {}
+impl ImageCopyToBufferFormat for rgba16unorm {}
+impl ImageCopyToBufferFormat for rgba16snorm {}
+
+impl ImageCopyFromBufferFormat for r16unorm {}
+impl ImageCopyFromBufferFormat for r16snorm {}
+impl ImageCopyFromBufferFormat for rg16unorm {}
+impl ImageCopyFromBufferFormat for rg16snorm {}
+impl ImageCopyFromBufferFormat for rgba16unorm {}
+impl ImageCopyFromBufferFormat for rgba16snorm {}
+
+impl ImageCopyTextureFormat for r16unorm {}
+impl ImageCopyTextureFormat for r16snorm {}
+impl ImageCopyTextureFormat for rg16unorm {}
+impl ImageCopyTextureFormat for rg16snorm {}
+impl ImageCopyTextureFormat for rgba16unorm {}
+impl ImageCopyTextureFormat for rgba16snorm {}
+
+impl SubImageCopyFormat for r16unorm {}
+impl SubImageCopyFormat for r16snorm {}
+impl SubImageCopyFormat for rg16unorm {}
+impl SubImageCopyFormat for rg16snorm {}
+impl SubImageCopyFormat for rgba16unorm {}
+impl SubImageCopyFormat for rgba16snorm {}
+
+unsafe impl ImageData<r16unorm> for u16 {}
+unsafe impl ImageData<r16snorm> for i16 {}
+unsafe impl ImageData<rg16unorm> for [u16; 2] {}
+unsafe impl ImageData<rg16snorm> for [i16; 2] {}
+unsafe impl ImageData<rgba16unorm> for [u16; 4] {}
+unsafe impl ImageData<rgba16snorm> for [i16; 4] {}
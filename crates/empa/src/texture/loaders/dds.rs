@@ -0,0 +1,385 @@
+use crate::texture::format::TextureFormatId;
+use crate::texture::loaders::{
+    image_size, read_bytes, read_u32, validate_mipmap_levels, LoadError, TextureData,
+};
+
+const MAGIC: u32 = 0x2053_4444;
+
+const HEADER_SIZE: u32 = 124;
+
+const DX10_HEADER_OFFSET: usize = 128;
+
+const DDPF_FOURCC: u32 = 0x4;
+
+const DDPF_RGB: u32 = 0x40;
+
+const DDSCAPS2_CUBEMAP: u32 = 0x200;
+
+const DDSCAPS2_VOLUME: u32 = 0x20_0000;
+
+const DDS_RESOURCE_MISC_TEXTURECUBE: u32 = 0x4;
+
+const DDS_DIMENSION_TEXTURE2D: u32 = 3;
+
+const fn four_cc(code: &[u8; 4]) -> u32 {
+    u32::from_le_bytes(*code)
+}
+
+/// Parses the contents of a DDS file.
+///
+/// Supports both legacy DDS files with a FourCC or RGBA8 pixel format and DDS files with a DX10
+/// extension header. Volume textures are not supported.
+pub fn parse_dds(bytes: &[u8]) -> Result<TextureData, LoadError> {
+    if read_u32(bytes, 0)? != MAGIC {
+        return Err(LoadError::InvalidIdentifier);
+    }
+
+    if read_u32(bytes, 4)? != HEADER_SIZE {
+        return Err(LoadError::InvalidIdentifier);
+    }
+
+    let height = read_u32(bytes, 12)?;
+    let width = read_u32(bytes, 16)?;
+    let mip_map_count = read_u32(bytes, 28)?;
+    let pixel_format_flags = read_u32(bytes, 80)?;
+    let four_cc_code = read_u32(bytes, 84)?;
+    let caps2 = read_u32(bytes, 112)?;
+
+    if caps2 & DDSCAPS2_VOLUME != 0 {
+        return Err(LoadError::Unsupported("volume textures"));
+    }
+
+    if width == 0 || height == 0 {
+        return Err(LoadError::InvalidDimensions);
+    }
+
+    let faces = if caps2 & DDSCAPS2_CUBEMAP != 0 { 6 } else { 1 };
+
+    let (format, layers, data_offset) = if pixel_format_flags & DDPF_FOURCC != 0
+        && four_cc_code == four_cc(b"DX10")
+    {
+        let dxgi_format = read_u32(bytes, DX10_HEADER_OFFSET)?;
+        let resource_dimension = read_u32(bytes, DX10_HEADER_OFFSET + 4)?;
+        let misc_flag = read_u32(bytes, DX10_HEADER_OFFSET + 8)?;
+        let array_size = read_u32(bytes, DX10_HEADER_OFFSET + 12)?;
+
+        if resource_dimension != DDS_DIMENSION_TEXTURE2D {
+            return Err(LoadError::Unsupported("non-2D textures"));
+        }
+
+        let format = dxgi_format_to_format_id(dxgi_format).ok_or(LoadError::UnsupportedFormat)?;
+        let faces = if misc_flag & DDS_RESOURCE_MISC_TEXTURECUBE != 0 {
+            6
+        } else {
+            1
+        };
+
+        let layers = array_size
+            .max(1)
+            .checked_mul(faces)
+            .ok_or(LoadError::InvalidDimensions)?;
+
+        (format, layers, DX10_HEADER_OFFSET + 20)
+    } else if pixel_format_flags & DDPF_FOURCC != 0 {
+        let format = four_cc_to_format_id(four_cc_code).ok_or(LoadError::UnsupportedFormat)?;
+
+        (format, faces, DX10_HEADER_OFFSET)
+    } else if pixel_format_flags & DDPF_RGB != 0 {
+        let format = legacy_rgb_format_id(bytes)?.ok_or(LoadError::UnsupportedFormat)?;
+
+        (format, faces, DX10_HEADER_OFFSET)
+    } else {
+        return Err(LoadError::UnsupportedFormat);
+    };
+
+    let mipmap_levels = mip_map_count.max(1);
+
+    validate_mipmap_levels(width, height, mipmap_levels)?;
+
+    // DDS stores the complete mipmap chain of each layer consecutively, whereas [TextureData]
+    // orders images by mipmap level first, so we collect the images per layer and then interleave.
+    // Don't preallocate based on the layer count: it is read from the file and has not yet been
+    // validated against the size of the data.
+    let mut layer_images = Vec::new();
+    let mut offset = data_offset;
+
+    for _ in 0..layers {
+        let mut chain = Vec::with_capacity(mipmap_levels as usize);
+
+        for level in 0..mipmap_levels {
+            let size = image_size(format, width, height, level)?;
+            let image = read_bytes(bytes, offset, size)?;
+
+            chain.push(image);
+            offset += size;
+        }
+
+        layer_images.push(chain);
+    }
+
+    let mut images = Vec::with_capacity(layer_images.len() * mipmap_levels as usize);
+
+    for level in 0..mipmap_levels as usize {
+        for chain in &layer_images {
+            images.push(chain[level]);
+        }
+    }
+
+    Ok(TextureData {
+        format,
+        width,
+        height,
+        layers,
+        mipmap_levels,
+        images,
+    })
+}
+
+fn four_cc_to_format_id(code: u32) -> Option<TextureFormatId> {
+    let format = if code == four_cc(b"DXT1") {
+        TextureFormatId::bc1_rgba_unorm
+    } else if code == four_cc(b"DXT2") || code == four_cc(b"DXT3") {
+        TextureFormatId::bc2_rgba_unorm
+    } else if code == four_cc(b"DXT4") || code == four_cc(b"DXT5") {
+        TextureFormatId::bc3_rgba_unorm
+    } else if code == four_cc(b"ATI1") || code == four_cc(b"BC4U") {
+        TextureFormatId::bc4_r_unorm
+    } else if code == four_cc(b"BC4S") {
+        TextureFormatId::bc4_r_snorm
+    } else if code == four_cc(b"ATI2") || code == four_cc(b"BC5U") {
+        TextureFormatId::bc5_rg_unorm
+    } else if code == four_cc(b"BC5S") {
+        TextureFormatId::bc5_rg_snorm
+    } else {
+        return None;
+    };
+
+    Some(format)
+}
+
+fn legacy_rgb_format_id(bytes: &[u8]) -> Result<Option<TextureFormatId>, LoadError> {
+    let bit_count = read_u32(bytes, 88)?;
+    let r_mask = read_u32(bytes, 92)?;
+    let g_mask = read_u32(bytes, 96)?;
+    let b_mask = read_u32(bytes, 100)?;
+
+    if bit_count != 32 || g_mask != 0x0000_ff00 {
+        return Ok(None);
+    }
+
+    let format = match (r_mask, b_mask) {
+        (0x0000_00ff, 0x00ff_0000) => Some(TextureFormatId::rgba8unorm),
+        (0x00ff_0000, 0x0000_00ff) => Some(TextureFormatId::bgra8unorm),
+        _ => None,
+    };
+
+    Ok(format)
+}
+
+fn dxgi_format_to_format_id(dxgi_format: u32) -> Option<TextureFormatId> {
+    use TextureFormatId::*;
+
+    let format = match dxgi_format {
+        2 => rgba32float,
+        3 => rgba32uint,
+        4 => rgba32sint,
+        10 => rgba16float,
+        #[cfg(feature = "norm16")]
+        11 => rgba16unorm,
+        12 => rgba16uint,
+        #[cfg(feature = "norm16")]
+        13 => rgba16snorm,
+        14 => rgba16sint,
+        16 => rg32float,
+        17 => rg32uint,
+        18 => rg32sint,
+        24 => rgb10a2unorm,
+        25 => rgb10a2uint,
+        26 => rg11b10ufloat,
+        28 => rgba8unorm,
+        29 => rgba8unorm_srgb,
+        30 => rgba8uint,
+        31 => rgba8snorm,
+        32 => rgba8sint,
+        34 => rg16float,
+        #[cfg(feature = "norm16")]
+        35 => rg16unorm,
+        36 => rg16uint,
+        #[cfg(feature = "norm16")]
+        37 => rg16snorm,
+        38 => rg16sint,
+        41 => r32float,
+        42 => r32uint,
+        43 => r32sint,
+        49 => rg8unorm,
+        50 => rg8uint,
+        51 => rg8snorm,
+        52 => rg8sint,
+        54 => r16float,
+        #[cfg(feature = "norm16")]
+        56 => r16unorm,
+        57 => r16uint,
+        #[cfg(feature = "norm16")]
+        58 => r16snorm,
+        59 => r16sint,
+        61 => r8unorm,
+        62 => r8uint,
+        63 => r8snorm,
+        64 => r8sint,
+        67 => rgb9e5ufloat,
+        71 => bc1_rgba_unorm,
+        72 => bc1_rgba_unorm_srgb,
+        74 => bc2_rgba_unorm,
+        75 => bc2_rgba_unorm_srgb,
+        77 => bc3_rgba_unorm,
+        78 => bc3_rgba_unorm_srgb,
+        80 => bc4_r_unorm,
+        81 => bc4_r_snorm,
+        83 => bc5_rg_unorm,
+        84 => bc5_rg_snorm,
+        87 => bgra8unorm,
+        91 => bgra8unorm_srgb,
+        95 => bc6h_rgb_ufloat,
+        96 => bc6h_rgb_float,
+        98 => bc7_rgba_unorm,
+        99 => bc7_rgba_unorm_srgb,
+        _ => return None,
+    };
+
+    Some(format)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DXGI_FORMAT_R8G8B8A8_UNORM: u32 = 28;
+
+    /// Builds the header of a legacy DDS file with an RGBA8 pixel format.
+    fn dds_header(width: u32, height: u32, mip_map_count: u32) -> Vec<u8> {
+        let mut bytes = vec![0; DX10_HEADER_OFFSET];
+
+        write_u32(&mut bytes, 0, MAGIC);
+        write_u32(&mut bytes, 4, HEADER_SIZE);
+        write_u32(&mut bytes, 12, height);
+        write_u32(&mut bytes, 16, width);
+        write_u32(&mut bytes, 28, mip_map_count);
+        write_u32(&mut bytes, 80, DDPF_RGB);
+        write_u32(&mut bytes, 88, 32);
+        write_u32(&mut bytes, 92, 0x0000_00ff);
+        write_u32(&mut bytes, 96, 0x0000_ff00);
+        write_u32(&mut bytes, 100, 0x00ff_0000);
+
+        bytes
+    }
+
+    /// Builds the headers of a DDS file with a DX10 extension header for an `rgba8unorm` texture.
+    fn dx10_header(width: u32, height: u32, array_size: u32, misc_flag: u32) -> Vec<u8> {
+        let mut bytes = dds_header(width, height, 1);
+
+        write_u32(&mut bytes, 80, DDPF_FOURCC);
+        write_u32(&mut bytes, 84, four_cc(b"DX10"));
+
+        for value in [
+            DXGI_FORMAT_R8G8B8A8_UNORM,
+            DDS_DIMENSION_TEXTURE2D,
+            misc_flag,
+            array_size,
+            0,
+        ] {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+
+        bytes
+    }
+
+    fn write_u32(bytes: &mut [u8], offset: usize, value: u32) {
+        bytes[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+    }
+
+    #[test]
+    fn parses_mipmap_chain() {
+        let mut bytes = dds_header(2, 2, 2);
+
+        bytes.extend_from_slice(&[1; 16]);
+        bytes.extend_from_slice(&[2; 4]);
+
+        let data = parse_dds(&bytes).unwrap();
+
+        assert_eq!(data.format(), TextureFormatId::rgba8unorm);
+        assert_eq!(data.mipmap_levels(), 2);
+        assert_eq!(data.image(0, 0), Some(&[1; 16][..]));
+        assert_eq!(data.image(1, 0), Some(&[2; 4][..]));
+    }
+
+    #[test]
+    fn rejects_invalid_magic() {
+        let mut bytes = dds_header(2, 2, 1);
+
+        bytes.extend_from_slice(&[0; 16]);
+        write_u32(&mut bytes, 0, 0);
+
+        assert_eq!(parse_dds(&bytes).unwrap_err(), LoadError::InvalidIdentifier);
+    }
+
+    #[test]
+    fn rejects_truncated_header() {
+        let bytes = dds_header(2, 2, 1);
+
+        assert_eq!(
+            parse_dds(&bytes[..60]).unwrap_err(),
+            LoadError::UnexpectedEnd
+        );
+    }
+
+    #[test]
+    fn rejects_truncated_dx10_header() {
+        let bytes = dx10_header(2, 2, 1, 0);
+
+        assert_eq!(
+            parse_dds(&bytes[..DX10_HEADER_OFFSET + 8]).unwrap_err(),
+            LoadError::UnexpectedEnd
+        );
+    }
+
+    #[test]
+    fn rejects_truncated_image_data() {
+        let mut bytes = dds_header(2, 2, 2);
+
+        bytes.extend_from_slice(&[0; 16]);
+
+        assert_eq!(parse_dds(&bytes).unwrap_err(), LoadError::UnexpectedEnd);
+    }
+
+    #[test]
+    fn rejects_too_many_mipmap_levels() {
+        let mut bytes = dds_header(1, 1, 32);
+
+        bytes.extend_from_slice(&[0; 4]);
+
+        assert_eq!(parse_dds(&bytes).unwrap_err(), LoadError::InvalidDimensions);
+    }
+
+    #[test]
+    fn rejects_overflowing_image_size() {
+        let bytes = dds_header(u32::MAX, u32::MAX, 1);
+
+        assert_eq!(parse_dds(&bytes).unwrap_err(), LoadError::UnexpectedEnd);
+    }
+
+    #[test]
+    fn rejects_overflowing_layer_count() {
+        let bytes = dx10_header(2, 2, u32::MAX, DDS_RESOURCE_MISC_TEXTURECUBE);
+
+        assert_eq!(parse_dds(&bytes).unwrap_err(), LoadError::InvalidDimensions);
+    }
+
+    #[test]
+    fn rejects_layer_count_exceeding_data() {
+        let mut bytes = dx10_header(2, 2, u32::MAX, 0);
+
+        bytes.extend_from_slice(&[0; 16]);
+
+        assert_eq!(parse_dds(&bytes).unwrap_err(), LoadError::UnexpectedEnd);
+    }
+}
@@ -0,0 +1,386 @@
+use crate::texture::format::TextureFormatId;
+use crate::texture::loaders::{
+    image_size, read_bytes, read_u32, read_u64, validate_mipmap_levels, LoadError, TextureData,
+};
+
+const IDENTIFIER: [u8; 12] = [
+    0xAB, 0x4B, 0x54, 0x58, 0x20, 0x32, 0x30, 0xBB, 0x0D, 0x0A, 0x1A, 0x0A,
+];
+
+const LEVEL_INDEX_OFFSET: usize = 80;
+
+const LEVEL_INDEX_ENTRY_SIZE: usize = 24;
+
+/// Parses the contents of a KTX2 file.
+///
+/// Supercompressed KTX2 files (e.g. Basis Universal or Zstandard) and 3D textures are not
+/// supported.
+pub fn parse_ktx2(bytes: &[u8]) -> Result<TextureData, LoadError> {
+    if bytes.len() < IDENTIFIER.len() {
+        return Err(LoadError::UnexpectedEnd);
+    }
+
+    if bytes[0..12] != IDENTIFIER {
+        return Err(LoadError::InvalidIdentifier);
+    }
+
+    let vk_format = read_u32(bytes, 12)?;
+    let width = read_u32(bytes, 20)?;
+    let height = read_u32(bytes, 24)?;
+    let depth = read_u32(bytes, 28)?;
+    let layer_count = read_u32(bytes, 32)?;
+    let face_count = read_u32(bytes, 36)?;
+    let level_count = read_u32(bytes, 40)?;
+    let supercompression_scheme = read_u32(bytes, 44)?;
+
+    if supercompression_scheme != 0 {
+        return Err(LoadError::Unsupported("supercompression"));
+    }
+
+    if depth > 1 {
+        return Err(LoadError::Unsupported("3D textures"));
+    }
+
+    if width == 0 || height == 0 {
+        return Err(LoadError::Unsupported("1D textures"));
+    }
+
+    if face_count != 1 && face_count != 6 {
+        return Err(LoadError::InvalidDimensions);
+    }
+
+    let format = vk_format_to_format_id(vk_format).ok_or(LoadError::UnsupportedFormat)?;
+
+    let layers = layer_count
+        .max(1)
+        .checked_mul(face_count)
+        .ok_or(LoadError::InvalidDimensions)?;
+
+    // A level count of `0` indicates that the consumer should generate the mipmap chain, the file
+    // only contains the base level.
+    let mipmap_levels = level_count.max(1);
+
+    validate_mipmap_levels(width, height, mipmap_levels)?;
+
+    // Don't preallocate based on the layer count: it is read from the file and has not yet been
+    // validated against the size of the data.
+    let mut images = Vec::new();
+
+    for level in 0..mipmap_levels as usize {
+        let entry_offset = LEVEL_INDEX_OFFSET + level * LEVEL_INDEX_ENTRY_SIZE;
+
+        let byte_offset = read_u64(bytes, entry_offset)?;
+        let byte_length = read_u64(bytes, entry_offset + 8)?;
+
+        let byte_offset = usize::try_from(byte_offset).map_err(|_| LoadError::UnexpectedEnd)?;
+        let byte_length = usize::try_from(byte_length).map_err(|_| LoadError::UnexpectedEnd)?;
+
+        let level_data = read_bytes(bytes, byte_offset, byte_length)?;
+
+        let image_size = image_size(format, width, height, level as u32)?;
+        let expected_length = image_size
+            .checked_mul(layers as usize)
+            .ok_or(LoadError::UnexpectedEnd)?;
+
+        if level_data.len() < expected_length {
+            return Err(LoadError::UnexpectedEnd);
+        }
+
+        if level_data.len() > expected_length {
+            return Err(LoadError::InvalidDimensions);
+        }
+
+        images.extend(level_data.chunks_exact(image_size));
+    }
+
+    Ok(TextureData {
+        format,
+        width,
+        height,
+        layers,
+        mipmap_levels,
+        images,
+    })
+}
+
+fn vk_format_to_format_id(vk_format: u32) -> Option<TextureFormatId> {
+    use TextureFormatId::*;
+
+    let format = match vk_format {
+        9 => r8unorm,
+        10 => r8snorm,
+        13 => r8uint,
+        14 => r8sint,
+        16 => rg8unorm,
+        17 => rg8snorm,
+        20 => rg8uint,
+        21 => rg8sint,
+        37 => rgba8unorm,
+        38 => rgba8snorm,
+        41 => rgba8uint,
+        42 => rgba8sint,
+        43 => rgba8unorm_srgb,
+        44 => bgra8unorm,
+        50 => bgra8unorm_srgb,
+        64 => rgb10a2unorm,
+        68 => rgb10a2uint,
+        #[cfg(feature = "norm16")]
+        70 => r16unorm,
+        #[cfg(feature = "norm16")]
+        71 => r16snorm,
+        74 => r16uint,
+        75 => r16sint,
+        76 => r16float,
+        #[cfg(feature = "norm16")]
+        77 => rg16unorm,
+        #[cfg(feature = "norm16")]
+        78 => rg16snorm,
+        81 => rg16uint,
+        82 => rg16sint,
+        83 => rg16float,
+        #[cfg(feature = "norm16")]
+        91 => rgba16unorm,
+        #[cfg(feature = "norm16")]
+        92 => rgba16snorm,
+        95 => rgba16uint,
+        96 => rgba16sint,
+        97 => rgba16float,
+        98 => r32uint,
+        99 => r32sint,
+        100 => r32float,
+        101 => rg32uint,
+        102 => rg32sint,
+        103 => rg32float,
+        107 => rgba32uint,
+        108 => rgba32sint,
+        109 => rgba32float,
+        122 => rg11b10ufloat,
+        123 => rgb9e5ufloat,
+        133 => bc1_rgba_unorm,
+        134 => bc1_rgba_unorm_srgb,
+        135 => bc2_rgba_unorm,
+        136 => bc2_rgba_unorm_srgb,
+        137 => bc3_rgba_unorm,
+        138 => bc3_rgba_unorm_srgb,
+        139 => bc4_r_unorm,
+        140 => bc4_r_snorm,
+        141 => bc5_rg_unorm,
+        142 => bc5_rg_snorm,
+        143 => bc6h_rgb_ufloat,
+        144 => bc6h_rgb_float,
+        145 => bc7_rgba_unorm,
+        146 => bc7_rgba_unorm_srgb,
+        147 => etc2_rgb8unorm,
+        148 => etc2_rgb8unorm_srgb,
+        149 => etc2_rgb8a1unorm,
+        150 => etc2_rgb8a1unorm_srgb,
+        151 => etc2_rgba8unorm,
+        152 => etc2_rgba8unorm_srgb,
+        153 => eac_r11unorm,
+        154 => eac_r11snorm,
+        155 => eac_rg11unorm,
+        156 => eac_rg11snorm,
+        157 => astc_4x4_unorm,
+        158 => astc_4x4_unorm_srgb,
+        159 => astc_5x4_unorm,
+        160 => astc_5x4_unorm_srgb,
+        161 => astc_5x5_unorm,
+        162 => astc_5x5_unorm_srgb,
+        163 => astc_6x5_unorm,
+        164 => astc_6x5_unorm_srgb,
+        165 => astc_6x6_unorm,
+        166 => astc_6x6_unorm_srgb,
+        167 => astc_8x5_unorm,
+        168 => astc_8x5_unorm_srgb,
+        169 => astc_8x6_unorm,
+        170 => astc_8x6_unorm_srgb,
+        171 => astc_8x8_unorm,
+        172 => astc_8x8_unorm_srgb,
+        173 => astc_10x5_unorm,
+        174 => astc_10x5_unorm_srgb,
+        175 => astc_10x6_unorm,
+        176 => astc_10x6_unorm_srgb,
+        177 => astc_10x8_unorm,
+        178 => astc_10x8_unorm_srgb,
+        179 => astc_10x10_unorm,
+        180 => astc_10x10_unorm_srgb,
+        181 => astc_12x10_unorm,
+        182 => astc_12x10_unorm_srgb,
+        183 => astc_12x12_unorm,
+        184 => astc_12x12_unorm_srgb,
+        _ => return None,
+    };
+
+    Some(format)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VK_FORMAT_R8G8B8A8_UNORM: u32 = 37;
+
+    /// Builds a KTX2 file for an `rgba8unorm` texture with a level index entry for each of the
+    /// `levels`, given as `(byte_offset, byte_length)`, followed by the `data`.
+    fn ktx2(
+        width: u32,
+        height: u32,
+        level_count: u32,
+        levels: &[(u64, u64)],
+        data: &[u8],
+    ) -> Vec<u8> {
+        let mut bytes = IDENTIFIER.to_vec();
+
+        for value in [
+            VK_FORMAT_R8G8B8A8_UNORM,
+            1,
+            width,
+            height,
+            0,
+            0,
+            1,
+            level_count,
+            0,
+        ] {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+
+        bytes.resize(LEVEL_INDEX_OFFSET, 0);
+
+        for (byte_offset, byte_length) in levels {
+            bytes.extend_from_slice(&byte_offset.to_le_bytes());
+            bytes.extend_from_slice(&byte_length.to_le_bytes());
+            bytes.extend_from_slice(&byte_length.to_le_bytes());
+        }
+
+        bytes.extend_from_slice(data);
+
+        bytes
+    }
+
+    fn data_offset(level_count: usize) -> u64 {
+        (LEVEL_INDEX_OFFSET + level_count * LEVEL_INDEX_ENTRY_SIZE) as u64
+    }
+
+    #[test]
+    fn parses_single_level() {
+        let bytes = ktx2(2, 2, 1, &[(data_offset(1), 16)], &[7; 16]);
+        let data = parse_ktx2(&bytes).unwrap();
+
+        assert_eq!(data.format(), TextureFormatId::rgba8unorm);
+        assert_eq!(data.width(), 2);
+        assert_eq!(data.height(), 2);
+        assert_eq!(data.layers(), 1);
+        assert_eq!(data.mipmap_levels(), 1);
+        assert_eq!(data.image(0, 0), Some(&[7; 16][..]));
+    }
+
+    #[test]
+    fn rejects_truncated_identifier() {
+        assert_eq!(
+            parse_ktx2(&IDENTIFIER[..8]).unwrap_err(),
+            LoadError::UnexpectedEnd
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_identifier() {
+        let mut bytes = ktx2(2, 2, 1, &[(data_offset(1), 16)], &[0; 16]);
+
+        bytes[0] = 0;
+
+        assert_eq!(
+            parse_ktx2(&bytes).unwrap_err(),
+            LoadError::InvalidIdentifier
+        );
+    }
+
+    #[test]
+    fn rejects_truncated_header() {
+        let bytes = ktx2(2, 2, 1, &[(data_offset(1), 16)], &[0; 16]);
+
+        assert_eq!(
+            parse_ktx2(&bytes[..30]).unwrap_err(),
+            LoadError::UnexpectedEnd
+        );
+    }
+
+    #[test]
+    fn rejects_truncated_level_index() {
+        let bytes = ktx2(2, 2, 2, &[(data_offset(2), 16)], &[]);
+
+        assert_eq!(parse_ktx2(&bytes).unwrap_err(), LoadError::UnexpectedEnd);
+    }
+
+    #[test]
+    fn rejects_truncated_level_data() {
+        let bytes = ktx2(2, 2, 1, &[(data_offset(1), 16)], &[0; 8]);
+
+        assert_eq!(parse_ktx2(&bytes).unwrap_err(), LoadError::UnexpectedEnd);
+    }
+
+    #[test]
+    fn rejects_empty_level() {
+        let bytes = ktx2(2, 2, 1, &[(data_offset(1), 0)], &[]);
+
+        assert_eq!(parse_ktx2(&bytes).unwrap_err(), LoadError::UnexpectedEnd);
+    }
+
+    #[test]
+    fn rejects_level_shorter_than_format_size() {
+        // A 2x2 `rgba8unorm` level requires 16 bytes.
+        let bytes = ktx2(2, 2, 1, &[(data_offset(1), 8)], &[0; 16]);
+
+        assert_eq!(parse_ktx2(&bytes).unwrap_err(), LoadError::UnexpectedEnd);
+    }
+
+    #[test]
+    fn rejects_level_longer_than_format_size() {
+        let bytes = ktx2(2, 2, 1, &[(data_offset(1), 20)], &[0; 20]);
+
+        assert_eq!(
+            parse_ktx2(&bytes).unwrap_err(),
+            LoadError::InvalidDimensions
+        );
+    }
+
+    #[test]
+    fn rejects_truncated_mipmap_level() {
+        let offset = data_offset(2);
+        let bytes = ktx2(2, 2, 2, &[(offset, 16), (offset + 16, 2)], &[0; 18]);
+
+        assert_eq!(parse_ktx2(&bytes).unwrap_err(), LoadError::UnexpectedEnd);
+    }
+
+    #[test]
+    fn parses_mipmap_chain() {
+        let offset = data_offset(2);
+        let mut data = vec![1; 16];
+
+        data.extend_from_slice(&[2; 4]);
+
+        let bytes = ktx2(2, 2, 2, &[(offset, 16), (offset + 16, 4)], &data);
+        let data = parse_ktx2(&bytes).unwrap();
+
+        assert_eq!(data.mipmap_levels(), 2);
+        assert_eq!(data.image(0, 0), Some(&[1; 16][..]));
+        assert_eq!(data.image(1, 0), Some(&[2; 4][..]));
+    }
+
+    #[test]
+    fn rejects_overflowing_level_range() {
+        let bytes = ktx2(2, 2, 1, &[(u64::MAX - 4, 16)], &[0; 16]);
+
+        assert_eq!(parse_ktx2(&bytes).unwrap_err(), LoadError::UnexpectedEnd);
+    }
+
+    #[test]
+    fn rejects_too_many_levels() {
+        let bytes = ktx2(2, 2, 40, &[], &[]);
+
+        assert_eq!(
+            parse_ktx2(&bytes).unwrap_err(),
+            LoadError::InvalidDimensions
+        );
+    }
+}
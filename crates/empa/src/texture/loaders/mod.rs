@@ -0,0 +1,345 @@
+//! Loaders for textures stored in KTX2 and DDS container files.
+//!
+//! Parse a container with [parse_ktx2] or [parse_dds], then create and upload a texture from the
+//! parsed [TextureData] with [create_texture_2d]:
+//!
+//! ```ignore
+//! let data = parse_ktx2(&bytes)?;
+//! let texture = create_texture_2d(&device, &data, bc7_rgba_unorm_srgb, texture_usages)?;
+//! ```
+//!
+//! Only uncompressed (not supercompressed) 2D textures, 2D texture arrays and cube maps are
+//! supported. Cube maps are loaded as 2D textures with 6 layers per cube.
+
+use std::error::Error;
+use std::fmt;
+use std::ops::Rem;
+
+use crate::adapter::Feature;
+use crate::device::Device;
+use crate::texture::format::{
+    ImageCopyFromBufferFormat, SubImageCopyFormat, Texture2DFormat, TextureFormatId,
+};
+use crate::texture::{
+    CopyDst, ImageCopySize3D, ImageDataByteLayout, MipmapLevels, SubImageCopy2DDescriptor,
+    SupportedUsages, Texture2D, Texture2DDescriptor,
+};
+
+mod dds;
+pub use self::dds::parse_dds;
+
+mod ktx2;
+pub use self::ktx2::parse_ktx2;
+
+/// Texture data parsed from a container file.
+///
+/// Does not copy the image data; it borrows the image data from the container bytes.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TextureData<'a> {
+    pub(crate) format: TextureFormatId,
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+    pub(crate) layers: u32,
+    pub(crate) mipmap_levels: u32,
+    // Ordered by mipmap level first, then by layer.
+    pub(crate) images: Vec<&'a [u8]>,
+}
+
+impl<'a> TextureData<'a> {
+    /// The format of the texture data.
+    pub fn format(&self) -> TextureFormatId {
+        self.format
+    }
+
+    /// The width of the base mipmap level.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// The height of the base mipmap level.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// The number of layers; for cube maps this includes the 6 faces of each cube.
+    pub fn layers(&self) -> u32 {
+        self.layers
+    }
+
+    /// The number of mipmap levels contained in the texture data.
+    pub fn mipmap_levels(&self) -> u32 {
+        self.mipmap_levels
+    }
+
+    /// Returns the image data for the given `mipmap_level` and `layer`, or `None` if either is out
+    /// of bounds.
+    pub fn image(&self, mipmap_level: u32, layer: u32) -> Option<&'a [u8]> {
+        if mipmap_level >= self.mipmap_levels || layer >= self.layers {
+            return None;
+        }
+
+        Some(self.images[(mipmap_level * self.layers + layer) as usize])
+    }
+
+    /// The device feature that must be enabled to create a texture with this data's format, if
+    /// any.
+    pub fn required_feature(&self) -> Option<Feature> {
+        required_feature(self.format)
+    }
+}
+
+/// Creates a new texture from the given `data` and uploads all of its mipmap levels and layers.
+///
+/// Returns an error if `format` does not match the [TextureData::format], if the `device` does not
+/// have the feature enabled that is required for the format, or if the data's dimensions are not
+/// compatible with the format's block size.
+pub fn create_texture_2d<F, U>(
+    device: &Device,
+    data: &TextureData,
+    format: F,
+    usage: U,
+) -> Result<Texture2D<F, U>, LoadError>
+where
+    F: Texture2DFormat + ImageCopyFromBufferFormat + SubImageCopyFormat,
+    U: SupportedUsages<F> + CopyDst,
+{
+    if data.format != F::FORMAT_ID {
+        return Err(LoadError::FormatMismatch {
+            expected: F::FORMAT_ID,
+            found: data.format,
+        });
+    }
+
+    if let Some(feature) = data.required_feature() {
+        if !device.features().contains(feature) {
+            return Err(LoadError::MissingFeature(feature));
+        }
+    }
+
+    let [block_width, block_height] = F::BLOCK_SIZE;
+
+    if data.width.rem(block_width) != 0 || data.height.rem(block_height) != 0 {
+        return Err(LoadError::InvalidDimensions);
+    }
+
+    let mipmap_levels =
+        u8::try_from(data.mipmap_levels).map_err(|_| LoadError::InvalidDimensions)?;
+
+    let texture = device.create_texture_2d(&Texture2DDescriptor {
+        format,
+        usage,
+        view_formats: (),
+        width: data.width,
+        height: data.height,
+        layers: data.layers,
+        mipmap_levels: MipmapLevels::Partial(mipmap_levels),
+    });
+
+    let queue = device.queue();
+
+    for mipmap_level in 0..data.mipmap_levels {
+        let (width, height) = physical_level_size(data, mipmap_level, F::BLOCK_SIZE);
+        let layout = ImageDataByteLayout {
+            bytes_per_block: F::BYTES_PER_BLOCK,
+            blocks_per_row: width / block_width,
+            rows_per_image: height / block_height,
+        };
+
+        for layer in 0..data.layers {
+            let bytes = data.images[(mipmap_level * data.layers + layer) as usize];
+
+            queue.write_texture_sub_image_raw(
+                texture.sub_image_copy_from_buffer_dst(SubImageCopy2DDescriptor {
+                    mipmap_level: mipmap_level as u8,
+                    origin_x: 0,
+                    origin_y: 0,
+                    origin_layer: layer,
+                }),
+                bytes,
+                layout,
+                ImageCopySize3D {
+                    width,
+                    height,
+                    depth_or_layers: 1,
+                },
+            );
+        }
+    }
+
+    Ok(texture)
+}
+
+fn physical_level_size(data: &TextureData, mipmap_level: u32, block_size: [u32; 2]) -> (u32, u32) {
+    let [block_width, block_height] = block_size;
+
+    let width = (data.width >> mipmap_level).max(1);
+    let height = (data.height >> mipmap_level).max(1);
+
+    (
+        width.next_multiple_of(block_width),
+        height.next_multiple_of(block_height),
+    )
+}
+
+pub(crate) fn required_feature(format: TextureFormatId) -> Option<Feature> {
+    use TextureFormatId::*;
+
+    match format {
+        bc1_rgba_unorm | bc1_rgba_unorm_srgb | bc2_rgba_unorm | bc2_rgba_unorm_srgb
+        | bc3_rgba_unorm | bc3_rgba_unorm_srgb | bc4_r_unorm | bc4_r_snorm | bc5_rg_unorm
+        | bc5_rg_snorm | bc6h_rgb_ufloat | bc6h_rgb_float | bc7_rgba_unorm
+        | bc7_rgba_unorm_srgb => Some(Feature::TextureCompressionBc),
+        etc2_rgb8unorm
+        | etc2_rgb8unorm_srgb
+        | etc2_rgb8a1unorm
+        | etc2_rgb8a1unorm_srgb
+        | etc2_rgba8unorm
+        | etc2_rgba8unorm_srgb
+        | eac_r11unorm
+        | eac_r11snorm
+        | eac_rg11unorm
+        | eac_rg11snorm => Some(Feature::TextureComporessionEtc2),
+        astc_4x4_unorm
+        | astc_4x4_unorm_srgb
+        | astc_5x4_unorm
+        | astc_5x4_unorm_srgb
+        | astc_5x5_unorm
+        | astc_5x5_unorm_srgb
+        | astc_6x5_unorm
+        | astc_6x5_unorm_srgb
+        | astc_6x6_unorm
+        | astc_6x6_unorm_srgb
+        | astc_8x5_unorm
+        | astc_8x5_unorm_srgb
+        | astc_8x6_unorm
+        | astc_8x6_unorm_srgb
+        | astc_8x8_unorm
+        | astc_8x8_unorm_srgb
+        | astc_10x5_unorm
+        | astc_10x5_unorm_srgb
+        | astc_10x6_unorm
+        | astc_10x6_unorm_srgb
+        | astc_10x8_unorm
+        | astc_10x8_unorm_srgb
+        | astc_10x10_unorm
+        | astc_10x10_unorm_srgb
+        | astc_12x10_unorm
+        | astc_12x10_unorm_srgb
+        | astc_12x12_unorm
+        | astc_12x12_unorm_srgb => Some(Feature::TextureCompressionAstc),
+        #[cfg(feature = "norm16")]
+        r16unorm | r16snorm | rg16unorm | rg16snorm | rgba16unorm | rgba16snorm => {
+            Some(Feature::TextureFormat16BitNorm)
+        }
+        _ => None,
+    }
+}
+
+/// Returns [LoadError::InvalidDimensions] if a texture with the given `width` and `height` cannot
+/// have `mipmap_levels` levels, e.g. because the count exceeds the length of a full mipmap chain.
+pub(crate) fn validate_mipmap_levels(
+    width: u32,
+    height: u32,
+    mipmap_levels: u32,
+) -> Result<(), LoadError> {
+    let max_levels = u32::BITS - width.max(height).leading_zeros();
+
+    if mipmap_levels > max_levels {
+        return Err(LoadError::InvalidDimensions);
+    }
+
+    Ok(())
+}
+
+/// Returns the size in bytes of a single image of mipmap `level` of a texture with the given
+/// `width` and `height`, or [LoadError::UnexpectedEnd] if the size overflows.
+pub(crate) fn image_size(
+    format: TextureFormatId,
+    width: u32,
+    height: u32,
+    level: u32,
+) -> Result<usize, LoadError> {
+    let bytes_per_block = format.bytes_per_block();
+    let [block_width, block_height] = format.block_dimensions();
+
+    let level_width = (width >> level).max(1);
+    let level_height = (height >> level).max(1);
+
+    let blocks_per_row = level_width.div_ceil(block_width) as usize;
+    let rows = level_height.div_ceil(block_height) as usize;
+
+    (bytes_per_block as usize)
+        .checked_mul(blocks_per_row)
+        .and_then(|size| size.checked_mul(rows))
+        .ok_or(LoadError::UnexpectedEnd)
+}
+
+/// Returns the `size` bytes at `offset`, or [LoadError::UnexpectedEnd] if the range is out of
+/// bounds.
+pub(crate) fn read_bytes(bytes: &[u8], offset: usize, size: usize) -> Result<&[u8], LoadError> {
+    offset
+        .checked_add(size)
+        .and_then(|end| bytes.get(offset..end))
+        .ok_or(LoadError::UnexpectedEnd)
+}
+
+/// Reads a little-endian `u32` at `offset`, or returns [LoadError::UnexpectedEnd].
+pub(crate) fn read_u32(bytes: &[u8], offset: usize) -> Result<u32, LoadError> {
+    read_bytes(bytes, offset, 4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+/// Reads a little-endian `u64` at `offset`, or returns [LoadError::UnexpectedEnd].
+pub(crate) fn read_u64(bytes: &[u8], offset: usize) -> Result<u64, LoadError> {
+    let low = read_u32(bytes, offset)? as u64;
+    let high = read_u32(bytes, offset + 4)? as u64;
+
+    Ok(low | (high << 32))
+}
+
+/// Signals that an error occurred when loading texture data from a container file.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum LoadError {
+    /// The data does not start with the container's identifier.
+    InvalidIdentifier,
+    /// The data ended before all headers or image data could be read.
+    UnexpectedEnd,
+    /// The container's format is not supported.
+    UnsupportedFormat,
+    /// The container uses a feature that is not supported by the loader.
+    Unsupported(&'static str),
+    /// The texture dimensions are not valid for the format.
+    InvalidDimensions,
+    /// The data's format does not match the requested texture format.
+    FormatMismatch {
+        expected: TextureFormatId,
+        found: TextureFormatId,
+    },
+    /// The device does not have the feature enabled that is required for the data's format.
+    MissingFeature(Feature),
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::InvalidIdentifier => write!(f, "invalid container identifier"),
+            LoadError::UnexpectedEnd => write!(f, "unexpected end of data"),
+            LoadError::UnsupportedFormat => write!(f, "unsupported texture format"),
+            LoadError::Unsupported(feature) => {
+                write!(f, "unsupported container feature: {}", feature)
+            }
+            LoadError::InvalidDimensions => {
+                write!(f, "texture dimensions are not valid for the texture format")
+            }
+            LoadError::FormatMismatch { expected, found } => write!(
+                f,
+                "expected texture data with format `{:?}`, found `{:?}`",
+                expected, found
+            ),
+            LoadError::MissingFeature(feature) => {
+                write!(f, "the device feature `{:?}` is not enabled", feature)
+            }
+        }
+    }
+}
+
+impl Error for LoadError {}
@@ -21,6 +21,10 @@ pub use self::usage::*;
 
 pub mod format;
 
+#[cfg(feature = "loaders")]
+pub mod loaders;
+
+use std::cmp::max;
 use std::error::Error;
 use std::fmt;
 use std::ops::Rem;
@@ -28,8 +32,118 @@ use std::ops::Rem;
 use arrayvec::ArrayVec;
 pub use empa_macros::TextureUsages;
 
+use crate::device::Device;
 use crate::driver;
-use crate::texture::format::TextureFormatId;
+use crate::driver::{Device as _, Driver, Dvr};
+use crate::memory::MemoryAllocation;
+use crate::texture::format::{ImageBufferDataFormat, TextureFormatId};
+
+/// Signals that an error occurred when trying to create a texture.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum CreateTextureError {
+    /// The size of the texture exceeds the device's texture dimension limits.
+    ///
+    /// Both `size` and `max_size` are given as `(width, height, depth_or_layers)`.
+    SizeLimitExceeded {
+        size: (u32, u32, u32),
+        max_size: (u32, u32, u32),
+    },
+    /// The combination of usage flags is not supported for the texture's format.
+    InvalidUsage,
+    /// The texture's format requires a feature that is not enabled for the device.
+    UnsupportedFormat(TextureFormatId),
+    /// Any other error reported by the device.
+    Other(String),
+}
+
+impl fmt::Display for CreateTextureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CreateTextureError::SizeLimitExceeded { size, max_size } => write!(
+                f,
+                "texture size (`{:?}`) exceeds the maximum texture size (`{:?}`)",
+                size, max_size
+            ),
+            CreateTextureError::InvalidUsage => write!(
+                f,
+                "the combination of texture usages is not supported for the texture format"
+            ),
+            CreateTextureError::UnsupportedFormat(format) => write!(
+                f,
+                "texture format `{:?}` requires a feature that is not enabled",
+                format
+            ),
+            CreateTextureError::Other(message) => {
+                write!(f, "failed to create texture: {}", message)
+            }
+        }
+    }
+}
+
+impl Error for CreateTextureError {}
+
+// Validates the texture size against the device's limits before creating the texture, so that
+// exceeding a limit is reported consistently across drivers.
+pub(crate) fn create_texture_handle(
+    device: &Device,
+    descriptor: &driver::TextureDescriptor,
+) -> Result<(<Dvr as Driver>::TextureHandle, MemoryAllocation), CreateTextureError> {
+    let limits = device.limits();
+    let (width, height, depth_or_layers) = descriptor.size;
+
+    let max_size = match descriptor.dimensions {
+        driver::TextureDimensions::One => (limits.max_texture_dimension_1d, 1, 1),
+        driver::TextureDimensions::Two => (
+            limits.max_texture_dimension_2d,
+            limits.max_texture_dimension_2d,
+            limits.max_texture_array_layers,
+        ),
+        driver::TextureDimensions::Three => (
+            limits.max_texture_dimension_3d,
+            limits.max_texture_dimension_3d,
+            limits.max_texture_dimension_3d,
+        ),
+    };
+
+    if width > max_size.0 || height > max_size.1 || depth_or_layers > max_size.2 {
+        return Err(CreateTextureError::SizeLimitExceeded {
+            size: descriptor.size,
+            max_size,
+        });
+    }
+
+    let handle = device.device_handle().create_texture(descriptor)?;
+    let allocation = device
+        .memory_tracker()
+        .track_texture(estimate_texture_size(descriptor), descriptor.usage_flags);
+
+    Ok((handle, allocation))
+}
+
+fn estimate_texture_size(descriptor: &driver::TextureDescriptor) -> u64 {
+    let [block_width, block_height] = descriptor.format.block_dimensions();
+    let bytes_per_block = descriptor.format.bytes_per_block() as u64;
+    let (width, height, depth_or_layers) = descriptor.size;
+
+    let mut size = 0;
+
+    for level in 0..descriptor.mipmap_levels {
+        let level_width = max(width >> level, 1);
+        let level_height = max(height >> level, 1);
+        let level_depth = if descriptor.dimensions == driver::TextureDimensions::Three {
+            max(depth_or_layers >> level, 1)
+        } else {
+            max(depth_or_layers, 1)
+        };
+
+        let blocks_per_row = level_width.div_ceil(block_width) as u64;
+        let rows = level_height.div_ceil(block_height) as u64;
+
+        size += blocks_per_row * rows * level_depth as u64 * bytes_per_block;
+    }
+
+    size * descriptor.sample_count as u64
+}
 
 #[allow(unused)]
 enum FormatKind<F> {
@@ -74,6 +188,35 @@ pub struct ImageDataLayout {
 }
 
 impl ImageDataLayout {
+    /// Returns a layout for an image of the given `size` in a buffer that is copied to or from a
+    /// texture with format `F`.
+    ///
+    /// Rows are padded such that the number of bytes per row is a multiple of `256`, as required
+    /// for buffer-texture copies. Use [required_len](Self::required_len) to determine the number of
+    /// elements the buffer must contain.
+    pub fn padded<F>(size: ImageCopySize3D) -> Self
+    where
+        F: ImageBufferDataFormat,
+    {
+        let [block_width, block_height] = F::BLOCK_SIZE;
+
+        let width_in_blocks = (size.width + block_width - 1) / block_width;
+        let height_in_blocks = (size.height + block_height - 1) / block_height;
+
+        let bytes_per_row = (width_in_blocks * F::BYTES_PER_BLOCK).next_multiple_of(256);
+
+        ImageDataLayout {
+            blocks_per_row: bytes_per_row / F::BYTES_PER_BLOCK,
+            rows_per_image: height_in_blocks,
+        }
+    }
+
+    /// Returns the minimum number of blocks that image data with this layout must contain for the
+    /// given number of images (the copy depth or layer count).
+    pub fn required_len(&self, depth_or_layers: u32) -> usize {
+        self.blocks_per_row as usize * self.rows_per_image as usize * depth_or_layers as usize
+    }
+
     pub(crate) fn to_byte_layout(&self, bytes_per_block: u32) -> ImageDataByteLayout {
         let ImageDataLayout {
             blocks_per_row,
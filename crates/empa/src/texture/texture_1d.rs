@@ -9,32 +9,43 @@ use crate::driver::{
     Device as _, Driver, Dvr, Texture, TextureAspect, TextureDescriptor, TextureDimensions,
     TextureViewDescriptor, TextureViewDimension,
 };
+use crate::memory::MemoryAllocation;
 use crate::texture::format::{
     FloatSamplable, ImageCopyFromBufferFormat, ImageCopyTextureFormat, ImageCopyToBufferFormat,
     SignedIntegerSamplable, Storable, SubImageCopyFormat, Texture1DFormat, TextureFormatId,
     UnfilteredFloatSamplable, UnsignedIntegerSamplable, ViewFormat, ViewFormats,
 };
 use crate::texture::{
-    CopyDst, CopySrc, FormatKind, ImageCopyDst, ImageCopyFromTextureDst, ImageCopySrc,
-    ImageCopyTexture, ImageCopyToTextureSrc, StorageBinding, SubImageCopyDst,
-    SubImageCopyFromTextureDst, SubImageCopySrc, SubImageCopyToTextureSrc, TextureBinding,
-    UnsupportedViewFormat, UsageFlags,
+    create_texture_handle, CopyDst, CopySrc, CreateTextureError, FormatKind, ImageCopyDst,
+    ImageCopyFromTextureDst, ImageCopySrc, ImageCopyTexture, ImageCopyToTextureSrc, StorageBinding,
+    SubImageCopyDst, SubImageCopyFromTextureDst, SubImageCopySrc, SubImageCopyToTextureSrc,
+    SupportedUsages, TextureBinding, UnsupportedViewFormat, UsageFlags,
 };
 
+/// Describes a texture to be created.
+///
+/// With the `serde` feature, the descriptor can be (de)serialized; the `format`, `usage` and
+/// `view_formats` are determined by the descriptor's type and are not part of the serialized form.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Texture1DDescriptor<F, U, V>
 where
     F: Texture1DFormat,
     U: UsageFlags,
     V: ViewFormats<F>,
 {
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub format: F,
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub usage: U,
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub view_formats: V,
     pub size: u32,
 }
 
 pub struct Texture1D<F, Usage> {
-    handle: <Dvr as Driver>::TextureHandle,
+    pub(crate) handle: <Dvr as Driver>::TextureHandle,
+    #[allow(unused)]
+    allocation: MemoryAllocation,
     size: u32,
     view_formats: ArrayVec<TextureFormatId, 8>,
     usage: Usage,
@@ -49,7 +60,10 @@ where
     pub(crate) fn new<V: ViewFormats<F>>(
         device: &Device,
         descriptor: &Texture1DDescriptor<F, U, V>,
-    ) -> Self {
+    ) -> Result<Self, CreateTextureError>
+    where
+        U: SupportedUsages<F>,
+    {
         let Texture1DDescriptor {
             view_formats,
             size,
@@ -61,23 +75,27 @@ where
 
         let view_formats = view_formats.formats().collect::<ArrayVec<_, 8>>();
 
-        let handle = device.device_handle.create_texture(&TextureDescriptor {
-            size: (*size, 0, 0),
-            mipmap_levels: 1,
-            sample_count: 1,
-            dimensions: TextureDimensions::One,
-            format: F::FORMAT_ID,
-            usage_flags: U::FLAG_SET,
-            view_formats: view_formats.as_slice(),
-        });
-
-        Texture1D {
+        let (handle, allocation) = create_texture_handle(
+            device,
+            &TextureDescriptor {
+                size: (*size, 0, 0),
+                mipmap_levels: 1,
+                sample_count: 1,
+                dimensions: TextureDimensions::One,
+                format: F::FORMAT_ID,
+                usage_flags: U::FLAG_SET,
+                view_formats: view_formats.as_slice(),
+            },
+        )?;
+
+        Ok(Texture1D {
             handle,
+            allocation,
             size: *size,
             view_formats,
             usage: *usage,
             _format: FormatKind::Typed(Default::default()),
-        }
+        })
     }
 }
 
@@ -95,6 +113,15 @@ impl<F, U> Texture1D<F, U> {
         self.size
     }
 
+    /// Destroys the texture, immediately releasing its GPU memory.
+    ///
+    /// Any bind groups, views or recorded commands that still reference the texture become
+    /// invalid; submitting work that uses the texture after it was destroyed results in a
+    /// validation error.
+    pub fn destroy(self) {
+        self.handle.destroy();
+    }
+
     fn view_internal(&self, format: TextureFormatId) -> <Dvr as Driver>::TextureView {
         self.handle.texture_view(&TextureViewDescriptor {
             format,
@@ -4,34 +4,45 @@ use std::ops::Rem;
 
 use arrayvec::ArrayVec;
 
-use crate::device::Device;
+use crate::device::{Device, Queue};
 use crate::driver;
 use crate::driver::{
     Device as _, Driver, Dvr, Texture, TextureAspect, TextureDescriptor, TextureDimensions,
     TextureViewDescriptor, TextureViewDimension,
 };
+use crate::memory::MemoryAllocation;
 use crate::texture::format::{
-    DepthSamplable, DepthStencilFormat, FloatSamplable, ImageBufferDataFormat,
-    ImageCopyFromBufferFormat, ImageCopyTextureFormat, ImageCopyToBufferFormat, Renderable,
-    SignedIntegerSamplable, Storable, SubImageCopyFormat, Texture2DFormat, TextureFormat,
-    TextureFormatId, UnfilteredFloatSamplable, UnsignedIntegerSamplable, ViewFormat, ViewFormats,
+    CompressedFormat, DepthSamplable, DepthStencilFormat, FloatSamplable, ImageBufferDataFormat,
+    ImageCopyFromBufferFormat, ImageCopyTextureFormat, ImageCopyToBufferFormat, ImageData,
+    Renderable, SignedIntegerSamplable, Storable, SubImageCopyFormat, Texture2DFormat,
+    TextureFormat, TextureFormatId, UnfilteredFloatSamplable, UnsignedIntegerSamplable, ViewFormat,
+    ViewFormats,
 };
 use crate::texture::{
-    CopyDst, CopySrc, FormatKind, ImageCopyDst, ImageCopyFromTextureDst, ImageCopySrc,
-    ImageCopyTexture, ImageCopyToTextureSrc, MipmapLevels, RenderAttachment, StorageBinding,
-    SubImageCopyDst, SubImageCopyFromTextureDst, SubImageCopySrc, SubImageCopyToTextureSrc,
-    TextureBinding, UnsupportedViewFormat, UsageFlags,
+    create_texture_handle, CopyDst, CopySrc, CreateTextureError, FormatKind, ImageCopyDst,
+    ImageCopyFromTextureDst, ImageCopySize2D, ImageCopySize3D, ImageCopySrc, ImageCopyTexture,
+    ImageCopyToTextureSrc, ImageDataByteLayout, ImageDataLayout, MipmapLevels, RenderAttachment,
+    StorageBinding, SubImageCopyDst, SubImageCopyFromTextureDst, SubImageCopySrc,
+    SubImageCopyToTextureSrc, SupportedUsages, TextureBinding, UnsupportedViewFormat, UsageFlags,
 };
 
+/// Describes a texture to be created.
+///
+/// With the `serde` feature, the descriptor can be (de)serialized; the `format`, `usage` and
+/// `view_formats` are determined by the descriptor's type and are not part of the serialized form.
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Texture2DDescriptor<F, U, V>
 where
     F: Texture2DFormat,
     U: UsageFlags,
     V: ViewFormats<F>,
 {
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub format: F,
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub usage: U,
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub view_formats: V,
     pub width: u32,
     pub height: u32,
@@ -149,6 +160,9 @@ pub struct SubImageCopy2DDescriptor {
 
 pub struct Texture2D<F, Usage> {
     pub(crate) handle: <Dvr as Driver>::TextureHandle,
+    // Swap chain textures are not tracked.
+    #[allow(unused)]
+    allocation: Option<MemoryAllocation>,
     pub(crate) width: u32,
     pub(crate) height: u32,
     pub(crate) layers: u32,
@@ -174,6 +188,7 @@ where
 
         Texture2D {
             handle,
+            allocation: None,
             width,
             height,
             layers: 1,
@@ -193,7 +208,10 @@ where
     pub(crate) fn new<V: ViewFormats<F>>(
         device: &Device,
         descriptor: &Texture2DDescriptor<F, U, V>,
-    ) -> Self {
+    ) -> Result<Self, CreateTextureError>
+    where
+        U: SupportedUsages<F>,
+    {
         let Texture2DDescriptor {
             view_formats,
             width,
@@ -224,18 +242,22 @@ where
         let mip_level_count = mipmap_levels.to_u32(max(*width, *height));
         let view_formats = view_formats.formats().collect::<ArrayVec<_, 8>>();
 
-        let handle = device.device_handle.create_texture(&TextureDescriptor {
-            size: (*width, *height, *layers),
-            mipmap_levels: mip_level_count,
-            sample_count: 1,
-            dimensions: TextureDimensions::Two,
-            format: F::FORMAT_ID,
-            usage_flags: U::FLAG_SET,
-            view_formats: view_formats.as_slice(),
-        });
-
-        Texture2D {
+        let (handle, allocation) = create_texture_handle(
+            device,
+            &TextureDescriptor {
+                size: (*width, *height, *layers),
+                mipmap_levels: mip_level_count,
+                sample_count: 1,
+                dimensions: TextureDimensions::Two,
+                format: F::FORMAT_ID,
+                usage_flags: U::FLAG_SET,
+                view_formats: view_formats.as_slice(),
+            },
+        )?;
+
+        Ok(Texture2D {
             handle,
+            allocation: Some(allocation),
             width: *width,
             height: *height,
             layers: *layers,
@@ -243,7 +265,7 @@ where
             view_formats,
             usage: *usage,
             _format: FormatKind::Typed(Default::default()),
-        }
+        })
     }
 }
 
@@ -273,6 +295,29 @@ impl<F, U> Texture2D<F, U> {
         self.mip_level_count
     }
 
+    /// Destroys the texture, immediately releasing its GPU memory.
+    ///
+    /// Any bind groups, views or recorded commands that still reference the texture become
+    /// invalid; submitting work that uses the texture after it was destroyed results in a
+    /// validation error.
+    pub fn destroy(self) {
+        self.handle.destroy();
+    }
+
+    /// Returns the `(width, height)` of the given `mipmap_level`.
+    ///
+    /// Panics if the `mipmap_level` is out of bounds.
+    pub fn mipmap_level_size(&self, mipmap_level: u8) -> (u32, u32) {
+        assert!(
+            mipmap_level < self.mip_level_count,
+            "mipmap level out of bounds"
+        );
+
+        let level = mipmap_level as u32;
+
+        (max(self.width >> level, 1), max(self.height >> level, 1))
+    }
+
     fn view_2d_internal<'a>(
         &'a self,
         format: TextureFormatId,
@@ -1212,6 +1257,7 @@ impl<F, U> Texture2D<F, U> {
     fn attachable_image_internal<ViewedFormat>(
         &self,
         descriptor: &AttachableImageDescriptor,
+        layer_count: u32,
     ) -> AttachableImage<ViewedFormat>
     where
         ViewedFormat: Renderable,
@@ -1223,20 +1269,32 @@ impl<F, U> Texture2D<F, U> {
         } = *descriptor;
 
         assert!(layer < self.layers, "`layer` out of bounds");
+        assert!(
+            layer + layer_count <= self.layers,
+            "`layer + layer_count` must not exceed the texture's layer count"
+        );
         assert!(
             mipmap_level < self.mip_level_count,
-            "`base_mipmap_level` must not exceed the texture's mipmap level count"
+            "`mipmap_level` must not exceed the texture's mipmap level count"
         );
 
+        let (width, height) = self.mipmap_level_size(mipmap_level);
+
         let mip_levels_start = mipmap_level as u32;
         let mip_levels_end = mip_levels_start + 1;
 
         let layers_start = layer;
-        let layers_end = layers_start + 1;
+        let layers_end = layers_start + layer_count;
+
+        let dimensions = if layer_count == 1 {
+            TextureViewDimension::Two
+        } else {
+            TextureViewDimension::TwoArray
+        };
 
         let inner = self.handle.texture_view(&TextureViewDescriptor {
             format: ViewedFormat::FORMAT_ID,
-            dimensions: TextureViewDimension::Two,
+            dimensions,
             aspect: TextureAspect::All,
             mip_levels: mip_levels_start..mip_levels_end,
             layers: layers_start..layers_end,
@@ -1244,8 +1302,9 @@ impl<F, U> Texture2D<F, U> {
 
         AttachableImage {
             inner,
-            width: self.width,
-            height: self.height,
+            width,
+            height,
+            layers: layer_count,
             _marker: Default::default(),
         }
     }
@@ -1255,7 +1314,61 @@ impl<F, U> Texture2D<F, U> {
         F: Renderable,
         U: RenderAttachment,
     {
-        self.attachable_image_internal(descriptor)
+        self.attachable_image_internal(descriptor, 1)
+    }
+
+    /// Returns an attachable image for the given `mipmap_level` of the first layer.
+    ///
+    /// Panics if the `mipmap_level` is out of bounds.
+    pub fn attachable_image_mip(&self, mipmap_level: u8) -> AttachableImage<F>
+    where
+        F: Renderable,
+        U: RenderAttachment,
+    {
+        self.attachable_image_internal(
+            &AttachableImageDescriptor {
+                layer: 0,
+                mipmap_level,
+            },
+            1,
+        )
+    }
+
+    /// Returns an attachable image for the base mipmap level of the given `layer`.
+    ///
+    /// Panics if the `layer` is out of bounds.
+    pub fn attachable_image_layer(&self, layer: u32) -> AttachableImage<F>
+    where
+        F: Renderable,
+        U: RenderAttachment,
+    {
+        self.attachable_image_internal(
+            &AttachableImageDescriptor {
+                layer,
+                mipmap_level: 0,
+            },
+            1,
+        )
+    }
+
+    /// Returns an attachable image for multiview rendering that spans `VIEWS` layers, starting at
+    /// the `descriptor`'s layer.
+    ///
+    /// Must be used with a [MultiviewRenderTarget](crate::render_target::MultiviewRenderTarget)
+    /// that has a matching view count. Requires the [Multiview](crate::adapter::Feature::Multiview)
+    /// feature; not supported on the web.
+    #[cfg(not(feature = "web"))]
+    pub fn attachable_image_multiview<const VIEWS: u32>(
+        &self,
+        descriptor: &AttachableImageDescriptor,
+    ) -> AttachableImage<F>
+    where
+        F: Renderable,
+        U: RenderAttachment,
+    {
+        assert!(VIEWS > 0, "view count must be greater than `0`");
+
+        self.attachable_image_internal(descriptor, VIEWS)
     }
 
     pub fn try_as_attachable_image<ViewedFormat>(
@@ -1267,7 +1380,7 @@ impl<F, U> Texture2D<F, U> {
         U: RenderAttachment,
     {
         if self.view_formats.contains(&ViewedFormat::FORMAT_ID) {
-            Ok(self.attachable_image_internal(descriptor))
+            Ok(self.attachable_image_internal(descriptor, 1))
         } else {
             Err(UnsupportedViewFormat {
                 format: ViewedFormat::FORMAT_ID,
@@ -1423,6 +1536,9 @@ impl<F, U> Texture2D<F, U> {
             "mipmap level out of bounds"
         );
 
+        let [block_width, block_height] = block_size;
+        let (width, height) = self.mipmap_level_size(mipmap_level);
+
         let inner = driver::ImageCopyTexture {
             texture_handle: &self.handle,
             mip_level: mipmap_level as u32,
@@ -1430,10 +1546,12 @@ impl<F, U> Texture2D<F, U> {
             aspect,
         };
 
+        // The copy covers the physical size of the mipmap level, which for block-compressed
+        // formats is rounded up to a whole number of blocks.
         ImageCopyTexture {
             inner,
-            width: self.width,
-            height: self.height,
+            width: width.div_ceil(block_width) * block_width,
+            height: height.div_ceil(block_height) * block_height,
             depth_or_layers: self.layers,
             bytes_per_block,
             block_size,
@@ -1458,8 +1576,10 @@ impl<F, U> Texture2D<F, U> {
             mipmap_level < self.mip_level_count,
             "mipmap level out of bounds"
         );
-        assert!(origin_x < self.width, "`x` origin out of bounds");
-        assert!(origin_y < self.height, "`y` origin out of bounds");
+        let (width, height) = self.mipmap_level_size(mipmap_level);
+
+        assert!(origin_x < width, "`x` origin out of bounds");
+        assert!(origin_y < height, "`y` origin out of bounds");
         assert!(origin_layer < self.layers, "layer origin out of bounds");
 
         let [block_width, block_height] = block_size;
@@ -1660,6 +1780,131 @@ impl<F, U> Texture2D<F, U> {
             inner: self.sub_image_copy_internal(descriptor, 0, F::BLOCK_SIZE),
         }
     }
+
+    /// Writes `data` to all layers of the given `mipmap_level`.
+    ///
+    /// The `data` must be tightly packed: each row of texel blocks directly follows the previous
+    /// row, and each layer directly follows the previous layer. The [ImageDataLayout] is derived
+    /// from the size of the mipmap level:
+    ///
+    /// ```ignore
+    /// texture.write_mip(device.queue(), 0, &texels);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `mipmap_level` is out of bounds, or if the length of `data` does not match the
+    /// number of texel blocks in the mipmap level.
+    pub fn write_mip<T>(&self, queue: &Queue, mipmap_level: u8, data: &[T])
+    where
+        T: ImageData<F>,
+        F: ImageCopyFromBufferFormat,
+        U: CopyDst,
+    {
+        let (width, height) = self.mipmap_level_size(mipmap_level);
+        let [block_width, block_height] = F::BLOCK_SIZE;
+
+        let blocks_per_row = width.div_ceil(block_width);
+        let rows_per_image = height.div_ceil(block_height);
+        let expected_len = blocks_per_row as usize * rows_per_image as usize * self.layers as usize;
+
+        assert!(
+            data.len() == expected_len,
+            "data length `{}` does not match the size of mipmap level `{}` (expected `{}` blocks)",
+            data.len(),
+            mipmap_level,
+            expected_len
+        );
+
+        queue.write_texture(
+            self.image_copy_from_buffer_dst(mipmap_level),
+            data,
+            ImageDataLayout {
+                blocks_per_row,
+                rows_per_image,
+            },
+        );
+    }
+
+    /// Writes block-compressed `bytes` to all layers of the given `mipmap_level`.
+    ///
+    /// The `bytes` must be tightly packed blocks of the texture's [CompressedFormat], in the same
+    /// order as for [write_mip](Texture2D::write_mip). Each row of blocks covers
+    /// [BLOCK_SIZE](TextureFormat::BLOCK_SIZE) rows of texels; a mipmap level whose size is not
+    /// a multiple of the block size is rounded up to whole blocks.
+    ///
+    /// ```ignore
+    /// // A 256x256 `bc1_rgba_unorm` level consists of 64x64 blocks of 8 bytes each.
+    /// texture.write_compressed_mip(device.queue(), 0, &bytes[..64 * 64 * 8]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `mipmap_level` is out of bounds, or if the length of `bytes` does not match
+    /// the size of the mipmap level in bytes.
+    pub fn write_compressed_mip(&self, queue: &Queue, mipmap_level: u8, bytes: &[u8])
+    where
+        F: CompressedFormat,
+        U: CopyDst,
+    {
+        let (width, height) = self.mipmap_level_size(mipmap_level);
+        let [block_width, block_height] = F::BLOCK_SIZE;
+
+        let blocks_per_row = width.div_ceil(block_width);
+        let rows_per_image = height.div_ceil(block_height);
+        let expected_len = F::BYTES_PER_BLOCK as usize
+            * blocks_per_row as usize
+            * rows_per_image as usize
+            * self.layers as usize;
+
+        assert!(
+            bytes.len() == expected_len,
+            "data length `{}` does not match the size of mipmap level `{}` (expected `{}` bytes)",
+            bytes.len(),
+            mipmap_level,
+            expected_len
+        );
+
+        queue.write_texture_raw(
+            self.image_copy_from_buffer_dst(mipmap_level),
+            bytes,
+            ImageDataByteLayout {
+                bytes_per_block: F::BYTES_PER_BLOCK,
+                blocks_per_row,
+                rows_per_image,
+            },
+        );
+    }
+
+    /// Writes `data` to the region of size `size` that starts at the `origin`, see also
+    /// [AtlasAllocator](crate::util::AtlasAllocator).
+    pub fn write_region<T>(
+        &self,
+        queue: &Queue,
+        origin: SubImageCopy2DDescriptor,
+        size: ImageCopySize2D,
+        data: &[T],
+        layout: ImageDataLayout,
+    ) where
+        T: ImageData<F>,
+        F: ImageCopyFromBufferFormat + SubImageCopyFormat,
+        U: CopyDst,
+    {
+        let ImageCopySize2D { width, height } = size;
+
+        let size = ImageCopySize3D {
+            width,
+            height,
+            depth_or_layers: 1,
+        };
+
+        queue.write_texture_sub_image(
+            self.sub_image_copy_from_buffer_dst(origin),
+            data,
+            layout,
+            size,
+        );
+    }
 }
 
 #[derive(Clone)]
@@ -1799,5 +2044,6 @@ pub struct AttachableImage<'a, F> {
     pub(crate) inner: <Dvr as Driver>::TextureView,
     pub(crate) width: u32,
     pub(crate) height: u32,
+    pub(crate) layers: u32,
     _marker: marker::PhantomData<&'a F>,
 }
@@ -3,33 +3,43 @@ use std::marker;
 
 use arrayvec::ArrayVec;
 
-use crate::device::Device;
+use crate::device::{Device, Queue};
 use crate::driver;
 use crate::driver::{
     Device as _, Driver, Dvr, Texture, TextureAspect, TextureDescriptor, TextureDimensions,
     TextureViewDescriptor, TextureViewDimension,
 };
+use crate::memory::MemoryAllocation;
 use crate::texture::format::{
     FloatSamplable, ImageCopyFromBufferFormat, ImageCopyTextureFormat, ImageCopyToBufferFormat,
-    SignedIntegerSamplable, Storable, SubImageCopyFormat, Texture3DFormat, TextureFormatId,
-    UnfilteredFloatSamplable, UnsignedIntegerSamplable, ViewFormat, ViewFormats,
+    ImageData, SignedIntegerSamplable, Storable, SubImageCopyFormat, Texture3DFormat,
+    TextureFormatId, UnfilteredFloatSamplable, UnsignedIntegerSamplable, ViewFormat, ViewFormats,
 };
 use crate::texture::{
-    CopyDst, CopySrc, FormatKind, ImageCopyDst, ImageCopyFromTextureDst, ImageCopySrc,
-    ImageCopyTexture, ImageCopyToTextureSrc, MipmapLevels, StorageBinding, SubImageCopyDst,
-    SubImageCopyFromTextureDst, SubImageCopySrc, SubImageCopyToTextureSrc, TextureBinding,
-    UnsupportedViewFormat, UsageFlags,
+    create_texture_handle, CopyDst, CopySrc, CreateTextureError, FormatKind, ImageCopyDst,
+    ImageCopyFromTextureDst, ImageCopySize2D, ImageCopySize3D, ImageCopySrc, ImageCopyTexture,
+    ImageCopyToTextureSrc, ImageDataLayout, MipmapLevels, StorageBinding, SubImageCopyDst,
+    SubImageCopyFromTextureDst, SubImageCopySrc, SubImageCopyToTextureSrc, SupportedUsages,
+    TextureBinding, UnsupportedViewFormat, UsageFlags,
 };
 
+/// Describes a texture to be created.
+///
+/// With the `serde` feature, the descriptor can be (de)serialized; the `format`, `usage` and
+/// `view_formats` are determined by the descriptor's type and are not part of the serialized form.
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Texture3DDescriptor<F, U, V>
 where
     F: Texture3DFormat,
     U: UsageFlags,
     V: ViewFormats<F>,
 {
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub format: F,
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub usage: U,
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub view_formats: V,
     pub width: u32,
     pub height: u32,
@@ -61,7 +71,9 @@ pub struct SubImageCopy3DDescriptor {
 }
 
 pub struct Texture3D<F, Usage> {
-    handle: <Dvr as Driver>::TextureHandle,
+    pub(crate) handle: <Dvr as Driver>::TextureHandle,
+    #[allow(unused)]
+    allocation: MemoryAllocation,
     mip_level_count: u8,
     width: u32,
     height: u32,
@@ -79,7 +91,10 @@ where
     pub(crate) fn new<V: ViewFormats<F>>(
         device: &Device,
         descriptor: &Texture3DDescriptor<F, U, V>,
-    ) -> Self {
+    ) -> Result<Self, CreateTextureError>
+    where
+        U: SupportedUsages<F>,
+    {
         let Texture3DDescriptor {
             view_formats,
             width,
@@ -97,18 +112,22 @@ where
         let mipmap_levels = mipmap_levels.to_u32(max(max(*width, *height), *depth));
         let view_formats = view_formats.formats().collect::<ArrayVec<_, 8>>();
 
-        let handle = device.device_handle.create_texture(&TextureDescriptor {
-            size: (*width, *height, *depth),
-            mipmap_levels,
-            sample_count: 1,
-            dimensions: TextureDimensions::Three,
-            format: F::FORMAT_ID,
-            usage_flags: U::FLAG_SET,
-            view_formats: view_formats.as_slice(),
-        });
-
-        Texture3D {
+        let (handle, allocation) = create_texture_handle(
+            device,
+            &TextureDescriptor {
+                size: (*width, *height, *depth),
+                mipmap_levels,
+                sample_count: 1,
+                dimensions: TextureDimensions::Three,
+                format: F::FORMAT_ID,
+                usage_flags: U::FLAG_SET,
+                view_formats: view_formats.as_slice(),
+            },
+        )?;
+
+        Ok(Texture3D {
             handle,
+            allocation,
             width: *width,
             height: *height,
             depth: *depth,
@@ -116,7 +135,7 @@ where
             view_formats,
             usage: *usage,
             _format: FormatKind::Typed(Default::default()),
-        }
+        })
     }
 }
 
@@ -146,6 +165,33 @@ impl<F, U> Texture3D<F, U> {
         self.mip_level_count
     }
 
+    /// Destroys the texture, immediately releasing its GPU memory.
+    ///
+    /// Any bind groups, views or recorded commands that still reference the texture become
+    /// invalid; submitting work that uses the texture after it was destroyed results in a
+    /// validation error.
+    pub fn destroy(self) {
+        self.handle.destroy();
+    }
+
+    /// Returns the `(width, height, depth)` of the given `mipmap_level`.
+    ///
+    /// Panics if the `mipmap_level` is out of bounds.
+    pub fn mipmap_level_size(&self, mipmap_level: u8) -> (u32, u32, u32) {
+        assert!(
+            mipmap_level < self.mip_level_count,
+            "mipmap level out of bounds"
+        );
+
+        let level = mipmap_level as u32;
+
+        (
+            max(self.width >> level, 1),
+            max(self.height >> level, 1),
+            max(self.depth >> level, 1),
+        )
+    }
+
     fn view_internal(
         &self,
         format: TextureFormatId,
@@ -383,6 +429,8 @@ impl<F, U> Texture3D<F, U> {
             "mipmap level out of bounds"
         );
 
+        let (width, height, depth) = self.mipmap_level_size(mipmap_level);
+
         let inner = driver::ImageCopyTexture {
             texture_handle: &self.handle,
             mip_level: mipmap_level as u32,
@@ -392,9 +440,9 @@ impl<F, U> Texture3D<F, U> {
 
         ImageCopyTexture {
             inner,
-            width: self.width,
-            height: self.height,
-            depth_or_layers: self.depth,
+            width,
+            height,
+            depth_or_layers: depth,
             bytes_per_block,
             block_size,
             _marker: Default::default(),
@@ -418,9 +466,12 @@ impl<F, U> Texture3D<F, U> {
             mipmap_level < self.mip_level_count,
             "mipmap level out of bounds"
         );
-        assert!(origin_x < self.width, "`x` origin out of bounds");
-        assert!(origin_y < self.height, "`y` origin out of bounds");
-        assert!(origin_z < self.depth, "layer origin out of bounds");
+
+        let (width, height, depth) = self.mipmap_level_size(mipmap_level);
+
+        assert!(origin_x < width, "`x` origin out of bounds");
+        assert!(origin_y < height, "`y` origin out of bounds");
+        assert!(origin_z < depth, "`z` origin out of bounds");
 
         let inner = driver::ImageCopyTexture {
             texture_handle: &self.handle,
@@ -431,9 +482,9 @@ impl<F, U> Texture3D<F, U> {
 
         ImageCopyTexture {
             inner,
-            width: self.width,
-            height: self.height,
-            depth_or_layers: self.depth,
+            width,
+            height,
+            depth_or_layers: depth,
             bytes_per_block,
             block_size,
             _marker: Default::default(),
@@ -531,6 +582,106 @@ impl<F, U> Texture3D<F, U> {
             inner: self.sub_image_copy_internal(descriptor, 0, F::BLOCK_SIZE),
         }
     }
+
+    /// Writes `data` to the complete volume of the given `mipmap_level`.
+    ///
+    /// The `data` is expected to contain the volume's depth slices consecutively, with
+    /// `layout.rows_per_image` rows per slice.
+    pub fn write_volume<T>(
+        &self,
+        queue: &Queue,
+        mipmap_level: u8,
+        data: &[T],
+        layout: ImageDataLayout,
+    ) where
+        T: ImageData<F>,
+        F: ImageCopyFromBufferFormat,
+        U: CopyDst,
+    {
+        queue.write_texture(self.image_copy_from_buffer_dst(mipmap_level), data, layout);
+    }
+
+    /// Writes `data` to the box of size `size` that starts at the `origin`.
+    pub fn write_region<T>(
+        &self,
+        queue: &Queue,
+        origin: SubImageCopy3DDescriptor,
+        size: ImageCopySize3D,
+        data: &[T],
+        layout: ImageDataLayout,
+    ) where
+        T: ImageData<F>,
+        F: ImageCopyFromBufferFormat + SubImageCopyFormat,
+        U: CopyDst,
+    {
+        queue.write_texture_sub_image(
+            self.sub_image_copy_from_buffer_dst(origin),
+            data,
+            layout,
+            size,
+        );
+    }
+
+    /// Writes `data` to the single depth slice `z` of the given `mipmap_level`.
+    ///
+    /// Useful for uploading volume data one slice at a time, e.g. when the slices are generated or
+    /// decoded incrementally.
+    pub fn write_slice<T>(
+        &self,
+        queue: &Queue,
+        mipmap_level: u8,
+        z: u32,
+        data: &[T],
+        layout: ImageDataLayout,
+    ) where
+        T: ImageData<F>,
+        F: ImageCopyFromBufferFormat + SubImageCopyFormat,
+        U: CopyDst,
+    {
+        let (width, height, _) = self.mipmap_level_size(mipmap_level);
+
+        self.write_slice_region(
+            queue,
+            SubImageCopy3DDescriptor {
+                mipmap_level,
+                origin_x: 0,
+                origin_y: 0,
+                origin_z: z,
+            },
+            ImageCopySize2D { width, height },
+            data,
+            layout,
+        );
+    }
+
+    /// Writes `data` to the rectangle of size `size` that starts at the `origin`, within the single
+    /// depth slice `origin.origin_z`.
+    pub fn write_slice_region<T>(
+        &self,
+        queue: &Queue,
+        origin: SubImageCopy3DDescriptor,
+        size: ImageCopySize2D,
+        data: &[T],
+        layout: ImageDataLayout,
+    ) where
+        T: ImageData<F>,
+        F: ImageCopyFromBufferFormat + SubImageCopyFormat,
+        U: CopyDst,
+    {
+        let ImageCopySize2D { width, height } = size;
+
+        self.write_region(
+            queue,
+            origin,
+            ImageCopySize3D {
+                width,
+                height,
+                depth_or_layers: 1,
+            },
+            data,
+            layout,
+        );
+    }
 }
 
 /// View on a 3D texture that can be bound to a pipeline as a float sampled texture resource.
@@ -6,19 +6,25 @@ use crate::driver::{
     Device as _, Driver, Dvr, Texture, TextureAspect, TextureDescriptor, TextureDimensions,
     TextureViewDescriptor, TextureViewDimension,
 };
+use crate::memory::MemoryAllocation;
+use crate::render_target::SampleCount;
 use crate::texture::format::MultisampleFormat;
 use crate::texture::{
-    CopyDst, CopySrc, FormatKind, ImageCopyTexture, ImageCopyToTextureDstMultisample,
-    ImageCopyToTextureSrcMultisample, RenderAttachment, UsageFlags,
+    create_texture_handle, CopyDst, CopySrc, CreateTextureError, FormatKind, ImageCopyTexture,
+    ImageCopyToTextureDstMultisample, ImageCopyToTextureSrcMultisample, RenderAttachment,
+    UsageFlags,
 };
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TextureMultisampled2DDescriptor {
     pub width: u32,
     pub height: u32,
 }
 
 pub struct TextureMultisampled2D<F, Usage, const SAMPLES: u8> {
-    handle: <Dvr as Driver>::TextureHandle,
+    pub(crate) handle: <Dvr as Driver>::TextureHandle,
+    #[allow(unused)]
+    allocation: MemoryAllocation,
     width: u32,
     height: u32,
     _format: FormatKind<F>,
@@ -30,34 +36,48 @@ where
     F: MultisampleFormat,
     U: UsageFlags + RenderAttachment,
 {
-    pub(crate) fn new(device: &Device, descriptor: &TextureMultisampled2DDescriptor) -> Self {
-        assert!(
-            SAMPLES == 4,
-            "only a sample count of 4 is currently supported"
-        );
+    pub(crate) fn new(
+        device: &Device,
+        descriptor: &TextureMultisampled2DDescriptor,
+    ) -> Result<Self, CreateTextureError> {
+        #[allow(clippy::let_unit_value)]
+        let _ = SampleCount::<SAMPLES>::ASSERT_SUPPORTED;
 
         let TextureMultisampled2DDescriptor { width, height } = *descriptor;
 
         assert!(width > 0, "width must be greater than `0`");
         assert!(height > 0, "height must be greater than `0`");
 
-        let handle = device.device_handle.create_texture(&TextureDescriptor {
-            size: (width, height, 1),
-            mipmap_levels: 1,
-            sample_count: SAMPLES as u32,
-            dimensions: TextureDimensions::Two,
-            format: F::FORMAT_ID,
-            usage_flags: U::FLAG_SET,
-            view_formats: &[],
-        });
+        let (handle, allocation) = create_texture_handle(
+            device,
+            &TextureDescriptor {
+                size: (width, height, 1),
+                mipmap_levels: 1,
+                sample_count: SAMPLES as u32,
+                dimensions: TextureDimensions::Two,
+                format: F::FORMAT_ID,
+                usage_flags: U::FLAG_SET,
+                view_formats: &[],
+            },
+        )?;
 
-        TextureMultisampled2D {
+        Ok(TextureMultisampled2D {
             handle,
+            allocation,
             width,
             height,
             _format: FormatKind::Typed(Default::default()),
             _usage: Default::default(),
-        }
+        })
+    }
+
+    /// Destroys the texture, immediately releasing its GPU memory.
+    ///
+    /// Any bind groups, views or recorded commands that still reference the texture become
+    /// invalid; submitting work that uses the texture after it was destroyed results in a
+    /// validation error.
+    pub fn destroy(self) {
+        self.handle.destroy();
     }
 
     pub fn attachable_image(&self) -> AttachableMultisampledImage<F, SAMPLES> {
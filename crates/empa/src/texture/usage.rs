@@ -57,6 +57,21 @@ impl<
 {
 }
 
+impl<
+        RenderAttachment: TypeFlag,
+        StorageBinding: TypeFlag,
+        TextureBinding: TypeFlag,
+        CopyDst: TypeFlag,
+        CopySrc: TypeFlag,
+    > Default for Usages<RenderAttachment, StorageBinding, TextureBinding, CopyDst, CopySrc>
+{
+    fn default() -> Self {
+        Usages {
+            _marker: Default::default(),
+        }
+    }
+}
+
 impl<
         RenderAttachment: TypeFlag,
         StorageBinding: TypeFlag,
@@ -130,6 +145,40 @@ impl<
 {
 }
 
+mod format_capability {
+    use crate::texture::format::{Renderable, Storable};
+    use crate::type_flag::{O, X};
+
+    pub trait RenderAttachmentCapability<F> {}
+
+    impl<F> RenderAttachmentCapability<F> for O {}
+    impl<F: Renderable> RenderAttachmentCapability<F> for X {}
+
+    pub trait StorageBindingCapability<F> {}
+
+    impl<F> StorageBindingCapability<F> for O {}
+    impl<F: Storable> StorageBindingCapability<F> for X {}
+}
+
+/// Marker trait for usage flags that are supported by textures of format `F`.
+///
+/// Only [Renderable](crate::texture::format::Renderable) formats support the [RenderAttachment]
+/// usage, and only [Storable](crate::texture::format::Storable) formats support the
+/// [StorageBinding] usage. Creating a texture with a combination of format and usages
+/// that the format does not support is rejected at compile time.
+pub trait SupportedUsages<F>: UsageFlags {}
+
+impl<F, RenderAttachment, StorageBinding, TextureBinding, CopyDst, CopySrc> SupportedUsages<F>
+    for Usages<RenderAttachment, StorageBinding, TextureBinding, CopyDst, CopySrc>
+where
+    RenderAttachment: TypeFlag + format_capability::RenderAttachmentCapability<F>,
+    StorageBinding: TypeFlag + format_capability::StorageBindingCapability<F>,
+    TextureBinding: TypeFlag,
+    CopyDst: TypeFlag,
+    CopySrc: TypeFlag,
+{
+}
+
 mod copy_src_seal {
     pub trait Seal {}
 }
@@ -205,73 +254,73 @@ impl<U1: TypeFlag, U2: TypeFlag, U3: TypeFlag, U4: TypeFlag> RenderAttachment
 }
 
 impl Usages<O, O, O, O, O> {
-    pub fn render_attachment() -> Usages<X, O, O, O, O> {
+    pub const fn render_attachment() -> Usages<X, O, O, O, O> {
         Usages {
-            _marker: Default::default(),
+            _marker: std::marker::PhantomData,
         }
     }
 
-    pub fn storage_binding() -> Usages<O, X, O, O, O> {
+    pub const fn storage_binding() -> Usages<O, X, O, O, O> {
         Usages {
-            _marker: Default::default(),
+            _marker: std::marker::PhantomData,
         }
     }
 
-    pub fn texture_binding() -> Usages<O, O, X, O, O> {
+    pub const fn texture_binding() -> Usages<O, O, X, O, O> {
         Usages {
-            _marker: Default::default(),
+            _marker: std::marker::PhantomData,
         }
     }
 
-    pub fn copy_dst() -> Usages<O, O, O, X, O> {
+    pub const fn copy_dst() -> Usages<O, O, O, X, O> {
         Usages {
-            _marker: Default::default(),
+            _marker: std::marker::PhantomData,
         }
     }
 
-    pub fn copy_src() -> Usages<O, O, O, O, X> {
+    pub const fn copy_src() -> Usages<O, O, O, O, X> {
         Usages {
-            _marker: Default::default(),
+            _marker: std::marker::PhantomData,
         }
     }
 }
 
 impl<U1: TypeFlag, U2: TypeFlag, U3: TypeFlag, U4: TypeFlag> Usages<O, U1, U2, U3, U4> {
-    pub fn and_render_attachment(self) -> Usages<X, U1, U2, U3, U4> {
+    pub const fn and_render_attachment(self) -> Usages<X, U1, U2, U3, U4> {
         Usages {
-            _marker: Default::default(),
+            _marker: std::marker::PhantomData,
         }
     }
 }
 
 impl<U0: TypeFlag, U2: TypeFlag, U3: TypeFlag, U4: TypeFlag> Usages<U0, O, U2, U3, U4> {
-    pub fn and_storage_binding(self) -> Usages<U0, X, U2, U3, U4> {
+    pub const fn and_storage_binding(self) -> Usages<U0, X, U2, U3, U4> {
         Usages {
-            _marker: Default::default(),
+            _marker: std::marker::PhantomData,
         }
     }
 }
 
 impl<U0: TypeFlag, U1: TypeFlag, U3: TypeFlag, U4: TypeFlag> Usages<U0, U1, O, U3, U4> {
-    pub fn and_texture_binding(self) -> Usages<U0, U1, X, U3, U4> {
+    pub const fn and_texture_binding(self) -> Usages<U0, U1, X, U3, U4> {
         Usages {
-            _marker: Default::default(),
+            _marker: std::marker::PhantomData,
         }
     }
 }
 
 impl<U0: TypeFlag, U1: TypeFlag, U2: TypeFlag, U4: TypeFlag> Usages<U0, U1, U2, O, U4> {
-    pub fn and_copy_dst(self) -> Usages<U0, U1, U2, X, U4> {
+    pub const fn and_copy_dst(self) -> Usages<U0, U1, U2, X, U4> {
         Usages {
-            _marker: Default::default(),
+            _marker: std::marker::PhantomData,
         }
     }
 }
 
 impl<U0: TypeFlag, U1: TypeFlag, U2: TypeFlag, U3: TypeFlag> Usages<U0, U1, U2, U3, O> {
-    pub fn and_copy_src(self) -> Usages<U0, U1, U2, U3, X> {
+    pub const fn and_copy_src(self) -> Usages<U0, U1, U2, U3, X> {
         Usages {
-            _marker: Default::default(),
+            _marker: std::marker::PhantomData,
         }
     }
 }
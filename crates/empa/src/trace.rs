@@ -0,0 +1,208 @@
+//! Capturing the operations a device performs into a trace, and replaying traces.
+//!
+//! Enabling the `trace` feature wraps the native driver such that it keeps track of the resources
+//! a device creates and the operations submitted to its queue. A capture records these operations
+//! (resource IDs, descriptors, command streams and the data - or a hash of the data - written to
+//! buffers and textures) into a [Trace] that can be serialized and replayed against another
+//! device, e.g. to reproduce a rendering issue outside of the application that produced it:
+//!
+//! ```ignore
+//! empa::trace::start_capture(&device, CaptureOptions::default());
+//!
+//! // Create resources and submit commands...
+//!
+//! let trace = empa::trace::stop_capture(&device);
+//!
+//! std::fs::write("frame.trace.json", trace.to_json())?;
+//!
+//! // Later, possibly in a different process:
+//! let trace = Trace::from_json(&std::fs::read_to_string("frame.trace.json")?)?;
+//!
+//! empa::trace::replay(&device, &trace)?;
+//! ```
+//!
+//! When a capture starts, the creation of all resources that are alive at that time is included
+//! at the start of the trace, so that the trace is self-contained. Contents written to a buffer
+//! or texture before the capture started are not included; neither are the results of GPU work
+//! that executed before the capture started. Surface textures are replayed as regular textures
+//! with the same format, size and usage.
+
+use serde::{Deserialize, Serialize};
+
+use crate::device::Device;
+use crate::driver::trace::Replayer;
+
+pub use crate::driver::trace::{
+    CaptureOptions, ReplayError, TraceBindGroupLayoutEntry, TraceBindingResource,
+    TraceColorAttachment, TraceColorTargetState, TraceCommand, TraceData,
+    TraceDepthStencilAttachment, TraceId, TraceImageCopyBuffer, TraceImageCopyTexture,
+    TraceOp as Operation, TraceSamplerDescriptor, TraceShaderStage, TraceVertexBufferLayout,
+};
+
+/// A recorded sequence of device operations.
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct Trace {
+    pub operations: Vec<Operation>,
+}
+
+impl Trace {
+    /// Serializes the trace to JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("trace serialization cannot fail")
+    }
+
+    /// Deserializes a trace from JSON.
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+}
+
+/// Starts capturing the operations performed by the `device`.
+///
+/// # Panics
+///
+/// Panics if a capture is already in progress for the `device`.
+pub fn start_capture(device: &Device, options: CaptureOptions) {
+    device.device_handle().tracer().begin_capture(options);
+}
+
+/// Stops the capture that is in progress for the `device` and returns the captured trace.
+///
+/// # Panics
+///
+/// Panics if no capture is in progress for the `device`.
+pub fn stop_capture(device: &Device) -> Trace {
+    Trace {
+        operations: device.device_handle().tracer().end_capture(),
+    }
+}
+
+/// Re-executes the operations recorded in the `trace` on the `device`.
+///
+/// The resources created while replaying are dropped when replay finishes (after the submitted work
+/// was handed to the device's queue).
+///
+/// # Errors
+///
+/// Returns an error if the trace is malformed, e.g. when an operation references a resource the
+/// trace does not create or a command is recorded outside of a valid pass, or if the `device` fails
+/// to create a resource the trace describes. Operations that precede the failing operation will
+/// already have been replayed.
+pub fn replay(device: &Device, trace: &Trace) -> Result<(), ReplayError> {
+    let mut replayer = Replayer::new(device.device_handle(), device.primary_queue_handle());
+
+    for operation in &trace.operations {
+        replayer.replay(operation)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::buffer;
+    use crate::buffer::Buffer;
+    use crate::device::DeviceDescriptor;
+    use crate::native::Instance;
+
+    // Returns `None` if the environment does not provide an adapter, in which case the test is
+    // skipped.
+    fn request_device() -> Option<Device> {
+        let adapter = Instance::default().get_adapter(Default::default()).ok()?;
+
+        futures::executor::block_on(adapter.request_device(&DeviceDescriptor::default())).ok()
+    }
+
+    #[test]
+    fn record_and_replay() {
+        let Some(device) = request_device() else {
+            return;
+        };
+
+        start_capture(&device, CaptureOptions { include_data: true });
+
+        let source: Buffer<[u32], _> =
+            device.create_buffer(vec![1u32, 2, 3, 4], buffer::Usages::copy_src());
+        let destination: Buffer<[u32], _> =
+            device.create_buffer(vec![0u32; 4], buffer::Usages::copy_dst());
+
+        let command_buffer = device
+            .create_command_encoder()
+            .copy_buffer_to_buffer_slice(source.view(), destination.view())
+            .finish();
+
+        device.queue().submit(command_buffer);
+
+        let trace = stop_capture(&device);
+
+        assert!(trace
+            .operations
+            .iter()
+            .any(|op| matches!(op, Operation::Submit { .. })));
+
+        let trace = Trace::from_json(&trace.to_json()).unwrap();
+
+        let Some(replay_device) = request_device() else {
+            return;
+        };
+
+        assert_eq!(replay(&replay_device, &trace), Ok(()));
+    }
+
+    #[test]
+    fn undefined_resource() {
+        let Some(device) = request_device() else {
+            return;
+        };
+
+        let trace = Trace {
+            operations: vec![Operation::DestroyBuffer { buffer: 7 }],
+        };
+
+        assert_eq!(
+            replay(&device, &trace),
+            Err(ReplayError::UndefinedResource {
+                kind: "buffer",
+                id: 7
+            })
+        );
+    }
+
+    #[test]
+    fn command_outside_of_pass() {
+        let Some(device) = request_device() else {
+            return;
+        };
+
+        let trace = Trace {
+            operations: vec![Operation::Submit {
+                commands: vec![TraceCommand::EndRenderPass],
+            }],
+        };
+
+        assert_eq!(
+            replay(&device, &trace),
+            Err(ReplayError::InvalidCommand {
+                command: TraceCommand::EndRenderPass,
+                context: "outside of a pass",
+            })
+        );
+    }
+
+    #[test]
+    fn unfinished_pass() {
+        let Some(device) = request_device() else {
+            return;
+        };
+
+        let trace = Trace {
+            operations: vec![Operation::Submit {
+                commands: vec![TraceCommand::BeginComputePass],
+            }],
+        };
+
+        assert_eq!(replay(&device, &trace), Err(ReplayError::UnfinishedPass));
+    }
+}
@@ -0,0 +1,173 @@
+//! Runtime tracking of buffer usages within usage scopes, enabled with the `usage_validation`
+//! feature.
+//!
+//! WebGPU requires that a buffer that is bound as read-write storage has no other usage within the
+//! same usage scope: a render pass forms a single usage scope, and in a compute pass each dispatch
+//! forms its own usage scope. Breaking this rule invalidates the command buffer, which on the web
+//! is reported asynchronously and without naming the resources involved. The validator reports
+//! such conflicts as soon as the offending draw or dispatch is recorded, naming the buffers by
+//! their labels (see [Buffer::set_label](crate::buffer::Buffer::set_label)).
+//!
+//! Usages in different usage scopes (e.g. a compute pass that writes a buffer and a later render
+//! pass that reads it as a vertex buffer) are synchronized automatically and are not reported.
+//! Usages by render bundles are not tracked.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex, Weak};
+
+use lazy_static::lazy_static;
+
+lazy_static! {
+    static ref BUFFER_LABELS: Mutex<HashMap<usize, String>> = Mutex::new(HashMap::new());
+    static ref BIND_GROUP_USAGES: Mutex<HashMap<usize, Weak<[BufferUsage]>>> =
+        Mutex::new(HashMap::new());
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum BufferUsageKind {
+    Vertex,
+    Index,
+    Indirect,
+    Uniform,
+    ReadOnlyStorage,
+    Storage,
+}
+
+impl BufferUsageKind {
+    fn is_writable(&self) -> bool {
+        *self == BufferUsageKind::Storage
+    }
+}
+
+impl fmt::Display for BufferUsageKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BufferUsageKind::Vertex => f.write_str("vertex buffer"),
+            BufferUsageKind::Index => f.write_str("index buffer"),
+            BufferUsageKind::Indirect => f.write_str("indirect buffer"),
+            BufferUsageKind::Uniform => f.write_str("uniform buffer"),
+            BufferUsageKind::ReadOnlyStorage => f.write_str("read-only storage buffer"),
+            BufferUsageKind::Storage => f.write_str("read-write storage buffer"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) struct BufferUsage {
+    pub(crate) buffer_id: usize,
+    pub(crate) kind: BufferUsageKind,
+}
+
+pub(crate) fn set_buffer_label(buffer_id: usize, label: &str) {
+    BUFFER_LABELS
+        .lock()
+        .unwrap()
+        .insert(buffer_id, label.to_string());
+}
+
+pub(crate) fn remove_buffer_label(buffer_id: usize) {
+    BUFFER_LABELS.lock().unwrap().remove(&buffer_id);
+}
+
+fn buffer_name(buffer_id: usize) -> String {
+    if let Some(label) = BUFFER_LABELS.lock().unwrap().get(&buffer_id) {
+        format!("buffer `{}`", label)
+    } else {
+        format!("unlabeled buffer (id `{}`)", buffer_id)
+    }
+}
+
+/// Registers the buffer usages of a bind group, for as long as the bind group keeps the `usages`
+/// alive.
+pub(crate) fn register_bind_group(bind_group_id: usize, usages: &Arc<[BufferUsage]>) {
+    let mut registry = BIND_GROUP_USAGES.lock().unwrap();
+
+    // Prune the entries of dropped bind groups now and then, so that the registry does not grow
+    // with every bind group that was ever created.
+    if registry.len() % 256 == 255 {
+        registry.retain(|_, usages| usages.strong_count() > 0);
+    }
+
+    registry.insert(bind_group_id, Arc::downgrade(usages));
+}
+
+fn bind_group_usages(bind_group_id: usize) -> Option<Arc<[BufferUsage]>> {
+    BIND_GROUP_USAGES
+        .lock()
+        .unwrap()
+        .get(&bind_group_id)
+        .and_then(|usages| usages.upgrade())
+}
+
+fn warn(message: &str) {
+    #[cfg(feature = "web")]
+    web_sys::console::warn_1(&message.into());
+
+    #[cfg(not(feature = "web"))]
+    eprintln!("empa: {}", message);
+}
+
+/// Tracks the buffer usages in the current usage scope of a command encoder.
+pub(crate) struct UsageValidator {
+    scope: &'static str,
+    usages: HashMap<usize, Vec<BufferUsageKind>>,
+    reported: Vec<usize>,
+}
+
+impl UsageValidator {
+    pub(crate) fn new() -> Self {
+        UsageValidator {
+            scope: "",
+            usages: HashMap::new(),
+            reported: Vec::new(),
+        }
+    }
+
+    /// Begins a new usage scope, e.g. `"render pass"`; used in messages to describe the scope.
+    pub(crate) fn begin_scope(&mut self, scope: &'static str) {
+        self.scope = scope;
+        self.usages.clear();
+        self.reported.clear();
+    }
+
+    pub(crate) fn record_bind_groups(&mut self, bind_group_ids: &[Option<usize>]) {
+        for bind_group_id in bind_group_ids.iter().flatten() {
+            if let Some(usages) = bind_group_usages(*bind_group_id) {
+                for usage in usages.iter() {
+                    self.record(usage.buffer_id, usage.kind);
+                }
+            }
+        }
+    }
+
+    pub(crate) fn record(&mut self, buffer_id: usize, kind: BufferUsageKind) {
+        let kinds = self.usages.entry(buffer_id).or_default();
+
+        if !kinds.contains(&kind) {
+            kinds.push(kind);
+        }
+
+        if kinds.len() > 1
+            && kinds.iter().any(|kind| kind.is_writable())
+            && !self.reported.contains(&buffer_id)
+        {
+            let kinds = kinds
+                .iter()
+                .map(|kind| kind.to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            warn(&format!(
+                "{} is used as a {} within the same {}; a buffer that is bound as read-write \
+                 storage may not have any other usage within a usage scope, so the command buffer \
+                 will fail validation",
+                buffer_name(buffer_id),
+                kinds,
+                self.scope
+            ));
+
+            self.reported.push(buffer_id);
+        }
+    }
+}
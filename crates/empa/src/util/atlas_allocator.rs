@@ -0,0 +1,262 @@
+use crate::texture::{ImageCopySize2D, SubImageCopy2DDescriptor};
+
+/// A region of a texture atlas allocated by an [AtlasAllocator].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct AtlasRegion {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl AtlasRegion {
+    /// Returns the `[min_u, min_v, max_u, max_v]` texture coordinates of this region for an atlas
+    /// of size `atlas_width` by `atlas_height`.
+    pub fn uv_rect(&self, atlas_width: u32, atlas_height: u32) -> [f32; 4] {
+        let atlas_width = atlas_width as f32;
+        let atlas_height = atlas_height as f32;
+
+        [
+            self.x as f32 / atlas_width,
+            self.y as f32 / atlas_height,
+            (self.x + self.width) as f32 / atlas_width,
+            (self.y + self.height) as f32 / atlas_height,
+        ]
+    }
+
+    /// Returns a sub-image copy descriptor with an origin that corresponds to this region.
+    pub fn sub_image_copy_descriptor(
+        &self,
+        mipmap_level: u8,
+        layer: u32,
+    ) -> SubImageCopy2DDescriptor {
+        SubImageCopy2DDescriptor {
+            mipmap_level,
+            origin_x: self.x,
+            origin_y: self.y,
+            origin_layer: layer,
+        }
+    }
+
+    /// Returns the copy size of this region.
+    pub fn size(&self) -> ImageCopySize2D {
+        ImageCopySize2D {
+            width: self.width,
+            height: self.height,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Shelf {
+    y: u32,
+    height: u32,
+    cursor_x: u32,
+}
+
+/// Allocates rectangular regions in a texture atlas using a shelf packing strategy.
+///
+/// Regions are placed left-to-right on horizontal "shelves"; a new shelf is opened when a region
+/// does not fit on any existing shelf. Allocations can not be freed individually, call
+/// [clear](Self::clear) to reset the allocator.
+///
+/// # Example
+///
+/// ```ignore
+/// let mut allocator = AtlasAllocator::new(1024, 1024);
+///
+/// let region = allocator.allocate(32, 32).expect("atlas is full");
+///
+/// texture.write_region(&queue, region.sub_image_copy_descriptor(0, 0), region.size(), &data, layout);
+///
+/// let uv_rect = region.uv_rect(1024, 1024);
+/// ```
+#[derive(Clone, Debug)]
+pub struct AtlasAllocator {
+    width: u32,
+    height: u32,
+    alignment: [u32; 2],
+    shelves: Vec<Shelf>,
+}
+
+impl AtlasAllocator {
+    /// Creates a new allocator for an atlas of size `width` by `height`.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self::with_alignment(width, height, [1, 1])
+    }
+
+    /// Creates a new allocator for an atlas of size `width` by `height` that aligns all regions
+    /// to the given `alignment` (typically the block size of a compressed texture format).
+    ///
+    /// # Panics
+    ///
+    /// Panics if either alignment dimension is `0`.
+    pub fn with_alignment(width: u32, height: u32, alignment: [u32; 2]) -> Self {
+        let [align_x, align_y] = alignment;
+
+        assert!(
+            align_x != 0 && align_y != 0,
+            "alignment dimensions must not be `0`"
+        );
+
+        AtlasAllocator {
+            width,
+            height,
+            alignment,
+            shelves: Vec::new(),
+        }
+    }
+
+    /// The width of the atlas.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// The height of the atlas.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Allocates a region of size `width` by `height`, or returns `None` if the atlas does not
+    /// have enough space left.
+    pub fn allocate(&mut self, width: u32, height: u32) -> Option<AtlasRegion> {
+        let [align_x, align_y] = self.alignment;
+
+        if width == 0 || height == 0 {
+            return None;
+        }
+
+        let aligned_width = width.checked_next_multiple_of(align_x)?;
+        let aligned_height = height.checked_next_multiple_of(align_y)?;
+
+        if aligned_width > self.width || aligned_height > self.height {
+            return None;
+        }
+
+        // Pick the lowest shelf that fits, to keep wasted space within shelves small.
+        let mut best: Option<usize> = None;
+
+        for (index, shelf) in self.shelves.iter().enumerate() {
+            if shelf.height >= aligned_height && self.width - shelf.cursor_x >= aligned_width {
+                let is_better = best
+                    .map(|best| shelf.height < self.shelves[best].height)
+                    .unwrap_or(true);
+
+                if is_better {
+                    best = Some(index);
+                }
+            }
+        }
+
+        let index = if let Some(index) = best {
+            index
+        } else {
+            let y = self
+                .shelves
+                .last()
+                .map(|shelf| shelf.y + shelf.height)
+                .unwrap_or(0);
+
+            if self.height - y < aligned_height {
+                return None;
+            }
+
+            self.shelves.push(Shelf {
+                y,
+                height: aligned_height,
+                cursor_x: 0,
+            });
+
+            self.shelves.len() - 1
+        };
+
+        let shelf = &mut self.shelves[index];
+
+        let region = AtlasRegion {
+            x: shelf.cursor_x,
+            y: shelf.y,
+            width,
+            height,
+        };
+
+        shelf.cursor_x += aligned_width;
+
+        Some(region)
+    }
+
+    /// Frees all allocated regions.
+    pub fn clear(&mut self) {
+        self.shelves.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn region(x: u32, y: u32, width: u32, height: u32) -> AtlasRegion {
+        AtlasRegion {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    #[test]
+    fn packs_regions_onto_shelves() {
+        let mut allocator = AtlasAllocator::new(64, 64);
+
+        assert_eq!(allocator.allocate(32, 16), Some(region(0, 0, 32, 16)));
+        assert_eq!(allocator.allocate(32, 8), Some(region(32, 0, 32, 8)));
+
+        // The first shelf is full, so a new shelf is opened below it.
+        assert_eq!(allocator.allocate(16, 16), Some(region(0, 16, 16, 16)));
+    }
+
+    #[test]
+    fn picks_the_lowest_shelf_that_fits() {
+        let mut allocator = AtlasAllocator::new(64, 64);
+
+        allocator.allocate(48, 32).unwrap();
+        allocator.allocate(32, 8).unwrap();
+
+        // Both shelves have space for an 8 pixel high region; the 8 pixel high shelf wastes less.
+        assert_eq!(allocator.allocate(8, 8), Some(region(32, 32, 8, 8)));
+        // Only the first shelf is high enough for a 16 pixel high region.
+        assert_eq!(allocator.allocate(8, 16), Some(region(48, 0, 8, 16)));
+    }
+
+    #[test]
+    fn aligns_regions() {
+        let mut allocator = AtlasAllocator::with_alignment(64, 64, [4, 4]);
+
+        assert_eq!(allocator.allocate(5, 3), Some(region(0, 0, 5, 3)));
+        assert_eq!(allocator.allocate(2, 2), Some(region(8, 0, 2, 2)));
+        assert_eq!(allocator.allocate(4, 5), Some(region(0, 4, 4, 5)));
+    }
+
+    #[test]
+    fn fails_when_full() {
+        let mut allocator = AtlasAllocator::new(32, 32);
+
+        assert_eq!(allocator.allocate(33, 1), None);
+        assert_eq!(allocator.allocate(1, 33), None);
+        assert_eq!(allocator.allocate(0, 8), None);
+
+        assert!(allocator.allocate(32, 24).is_some());
+        assert_eq!(allocator.allocate(8, 16), None);
+        assert!(allocator.allocate(8, 8).is_some());
+
+        allocator.clear();
+
+        assert_eq!(allocator.allocate(8, 16), Some(region(0, 0, 8, 16)));
+    }
+
+    #[test]
+    fn overflowing_alignment() {
+        let mut allocator = AtlasAllocator::with_alignment(u32::MAX, u32::MAX, [4, 4]);
+
+        assert_eq!(allocator.allocate(u32::MAX, 1), None);
+    }
+}
@@ -0,0 +1,13 @@
+#include "./fullscreen.wgsl"
+
+@group(0) @binding(0)
+var src_texture: texture_2d<f32>;
+
+@group(0) @binding(1)
+var src_sampler: sampler;
+
+@fragment
+fn frag_main(@location(0) uv: vec2<f32>) -> @location(0) vec4<f32> {
+    // Sample the base level explicitly; the source may have mipmaps that were never written.
+    return textureSampleLevel(src_texture, src_sampler, uv, 0.0);
+}
@@ -0,0 +1,122 @@
+use crate::command::CommandEncoder;
+use crate::device::Device;
+use crate::render_pipeline::{ColorOutput, ColorWrite, FragmentStageBuilder};
+use crate::resource_binding;
+use crate::resource_binding::BindGroupLayout;
+use crate::sampler::{FilterMode, Sampler, SamplerDescriptor};
+use crate::shader_module::{shader_source, ShaderSource};
+use crate::texture::format::FloatRenderable;
+use crate::texture::{AttachableImage, Sampled2DFloat};
+use crate::util::fullscreen::{
+    create_fullscreen_pipeline, encode_fullscreen_pass, FullscreenPipeline,
+};
+
+const SHADER: ShaderSource = shader_source!("blit.wgsl");
+
+#[derive(resource_binding::Resources)]
+struct Resources<'a> {
+    #[resource(binding = 0, visibility = "FRAGMENT")]
+    src_texture: Sampled2DFloat<'a>,
+    #[resource(binding = 1, visibility = "FRAGMENT")]
+    src_sampler: &'a Sampler,
+}
+
+type ResourcesLayout = <Resources<'static> as resource_binding::Resources>::Layout;
+
+/// Copies the contents of a filterable float texture view to an image of format `F`, scaling the
+/// contents to fit the destination and converting between formats as needed.
+///
+/// Unlike the copy commands on [CommandEncoder], the source and destination may differ in size
+/// and format; the source is sampled across the entire destination image.
+///
+/// ```ignore
+/// let blitter = Blitter::new(&device, bgra8unorm).await;
+///
+/// encoder = blitter.blit(
+///     encoder,
+///     offscreen.sampled_float(&Default::default()),
+///     swapchain_image.attachable_image(),
+///     FilterMode::Linear,
+/// );
+/// ```
+pub struct Blitter<F>
+where
+    F: FloatRenderable,
+{
+    pipeline: FullscreenPipeline<F, ResourcesLayout>,
+    bind_group_layout: BindGroupLayout<ResourcesLayout>,
+    nearest_sampler: Sampler,
+    linear_sampler: Sampler,
+    device: Device,
+}
+
+impl<F> Blitter<F>
+where
+    F: FloatRenderable,
+{
+    /// Creates a new blitter that writes to images of the given `format`.
+    pub async fn new(device: &Device, format: F) -> Self {
+        let shader = device.create_shader_module(&SHADER);
+
+        let bind_group_layout = device.create_bind_group_layout::<ResourcesLayout>();
+        let pipeline_layout = device.create_pipeline_layout(&bind_group_layout);
+
+        let fragment = FragmentStageBuilder::begin(&shader, "frag_main")
+            .color_outputs(ColorOutput {
+                format,
+                write_mask: ColorWrite::All,
+            })
+            .finish();
+
+        let pipeline = create_fullscreen_pipeline(device, &shader, &pipeline_layout, fragment)
+            .await
+            .expect("the blit pipeline should be valid");
+
+        let nearest_sampler = device.create_sampler(&SamplerDescriptor {
+            magnification_filter: FilterMode::Nearest,
+            minification_filter: FilterMode::Nearest,
+            ..Default::default()
+        });
+
+        let linear_sampler = device.create_sampler(&SamplerDescriptor {
+            magnification_filter: FilterMode::Linear,
+            minification_filter: FilterMode::Linear,
+            ..Default::default()
+        });
+
+        Blitter {
+            pipeline,
+            bind_group_layout,
+            nearest_sampler,
+            linear_sampler,
+            device: device.clone(),
+        }
+    }
+
+    /// Records a render pass that copies the contents of the `src` view to the `dst` image,
+    /// sampling the source with the given `filter`.
+    ///
+    /// If the `src` view has multiple mipmap levels, only the base level is sampled.
+    pub fn blit(
+        &self,
+        encoder: CommandEncoder,
+        src: Sampled2DFloat,
+        dst: AttachableImage<F>,
+        filter: FilterMode,
+    ) -> CommandEncoder {
+        let sampler = match filter {
+            FilterMode::Nearest => &self.nearest_sampler,
+            FilterMode::Linear => &self.linear_sampler,
+        };
+
+        let bind_group = self.device.create_bind_group(
+            &self.bind_group_layout,
+            Resources {
+                src_texture: src,
+                src_sampler: sampler,
+            },
+        );
+
+        encode_fullscreen_pass(encoder, &self.pipeline, &bind_group, dst)
+    }
+}
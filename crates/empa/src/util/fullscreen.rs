@@ -0,0 +1,76 @@
+use crate::command::{
+    CommandEncoder, Draw, DrawCommandEncoder, RenderPassDescriptor, RenderStateEncoder,
+    ResourceBindingCommandEncoder,
+};
+use crate::device::{Device, PipelineCreationError};
+use crate::render_pipeline::{
+    FragmentStage, IndexAny, RenderPipeline, RenderPipelineDescriptorBuilder, VertexStageBuilder,
+};
+use crate::render_target::{FloatAttachment, LoadOp, RenderLayout, RenderTarget, StoreOp};
+use crate::resource_binding::{BindGroup, PipelineLayout, TypedBindGroupLayout};
+use crate::shader_module::ShaderModule;
+use crate::texture::format::FloatRenderable;
+use crate::texture::AttachableImage;
+
+/// A pipeline for a full-screen pass that renders to an image of format `F` and uses a single
+/// bind group with layout `B`.
+pub(crate) type FullscreenPipeline<F, B> = RenderPipeline<RenderLayout<F, ()>, (), IndexAny, (B,)>;
+
+/// Creates a full-screen pipeline from the `vert_main` entry point of the `shader` and the given
+/// `fragment` stage.
+///
+/// The `shader` must `#include "./fullscreen.wgsl"`, which defines a `vert_main` that draws a
+/// single triangle covering the entire viewport and passes its texture coordinates to the
+/// fragment stage at location `0`.
+pub(crate) async fn create_fullscreen_pipeline<F, B>(
+    device: &Device,
+    shader: &ShaderModule,
+    layout: &PipelineLayout<(B,)>,
+    fragment: FragmentStage<F>,
+) -> Result<FullscreenPipeline<F, B>, PipelineCreationError>
+where
+    F: FloatRenderable,
+    B: TypedBindGroupLayout,
+{
+    device
+        .create_render_pipeline(
+            &RenderPipelineDescriptorBuilder::begin()
+                .layout(layout)
+                .vertex(VertexStageBuilder::begin(shader, "vert_main").finish())
+                .fragment(fragment)
+                .finish(),
+        )
+        .await
+}
+
+/// Records a render pass that clears the `image` and then draws the full-screen triangle with the
+/// `pipeline` and `bind_group`.
+pub(crate) fn encode_fullscreen_pass<F, B>(
+    encoder: CommandEncoder,
+    pipeline: &FullscreenPipeline<F, B>,
+    bind_group: &BindGroup<B>,
+    image: AttachableImage<F>,
+) -> CommandEncoder
+where
+    F: FloatRenderable,
+    B: TypedBindGroupLayout,
+{
+    encoder
+        .begin_render_pass(RenderPassDescriptor::new(&RenderTarget {
+            color: FloatAttachment {
+                image,
+                load_op: LoadOp::Clear([0.0; 4]),
+                store_op: StoreOp::Store,
+            },
+            depth_stencil: (),
+        }))
+        .set_pipeline(pipeline)
+        .set_bind_groups(bind_group)
+        .draw(Draw {
+            vertex_count: 3,
+            instance_count: 1,
+            first_vertex: 0,
+            first_instance: 0,
+        })
+        .end()
+}
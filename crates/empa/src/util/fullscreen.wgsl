@@ -0,0 +1,17 @@
+struct VertexOut {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>
+}
+
+@vertex
+fn vert_main(@builtin(vertex_index) vertex_index: u32) -> VertexOut {
+    // A single triangle that covers the entire viewport.
+    let uv = vec2(f32((vertex_index << 1u) & 2u), f32(vertex_index & 2u));
+
+    var result = VertexOut();
+
+    result.position = vec4(uv.x * 2.0 - 1.0, 1.0 - uv.y * 2.0, 0.0, 1.0);
+    result.uv = uv;
+
+    return result;
+}
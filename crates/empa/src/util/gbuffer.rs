@@ -0,0 +1,276 @@
+use crate::device::Device;
+use crate::render_target::{
+    DepthAttachment, DepthValue, FloatAttachment, LoadOp, RenderLayout, RenderTarget, StoreOp,
+};
+use crate::texture::format::{
+    DepthRenderable, DepthSamplable, FloatRenderable, FloatSamplable, Renderable, Texture2DFormat,
+};
+use crate::texture::{
+    MipmapLevels, Sampled2DDepth, Sampled2DFloat, Texture2D, Texture2DDescriptor, TextureUsages,
+};
+use crate::{resource_binding, texture};
+
+/// The usages of the textures that make up a [GBuffer].
+pub type GBufferUsages = TextureUsages!(RenderAttachment | TextureBinding);
+
+/// The render layout of the geometry pass that writes to a [GBuffer].
+///
+/// Use this layout for the pipelines that render to the [GBuffer::render_target].
+pub type GBufferLayout<Albedo, Normal, Material, Depth> =
+    RenderLayout<(Albedo, Normal, Material), Depth>;
+
+/// The geometry pass render target of a [GBuffer].
+pub type GBufferRenderTarget<'a, Albedo, Normal, Material, Depth> = RenderTarget<
+    (
+        FloatAttachment<'a, Albedo>,
+        FloatAttachment<'a, Normal>,
+        FloatAttachment<'a, Material>,
+    ),
+    DepthAttachment<'a, Depth>,
+>;
+
+/// Settings for a [GBuffer].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct GBufferDescriptor<Albedo, Normal, Material, Depth> {
+    pub albedo: Albedo,
+    pub normal: Normal,
+    pub material: Material,
+    pub depth: Depth,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// The values the attachments of a [GBuffer] are cleared to at the start of the geometry pass.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct GBufferClearValues {
+    pub albedo: [f32; 4],
+    pub normal: [f32; 4],
+    pub material: [f32; 4],
+    pub depth: DepthValue,
+}
+
+impl Default for GBufferClearValues {
+    fn default() -> Self {
+        GBufferClearValues {
+            albedo: [0.0; 4],
+            normal: [0.0; 4],
+            material: [0.0; 4],
+            depth: DepthValue::ONE,
+        }
+    }
+}
+
+/// The [GBuffer] textures as resources for the lighting pass.
+///
+/// The textures are bound in the order `albedo` (binding `0`), `normal` (binding `1`), `material`
+/// (binding `2`) and `depth` (binding `3`), all visible to the fragment stage:
+///
+/// ```wgsl
+/// @group(0) @binding(0) var albedo: texture_2d<f32>;
+/// @group(0) @binding(1) var normal: texture_2d<f32>;
+/// @group(0) @binding(2) var material: texture_2d<f32>;
+/// @group(0) @binding(3) var depth: texture_depth_2d;
+/// ```
+///
+/// Use [GBufferResourcesLayout] to create the bind group layout for the lighting pass.
+#[derive(resource_binding::Resources)]
+pub struct GBufferResources<'a> {
+    #[resource(binding = 0, visibility = "FRAGMENT")]
+    pub albedo: Sampled2DFloat<'a>,
+    #[resource(binding = 1, visibility = "FRAGMENT")]
+    pub normal: Sampled2DFloat<'a>,
+    #[resource(binding = 2, visibility = "FRAGMENT")]
+    pub material: Sampled2DFloat<'a>,
+    #[resource(binding = 3, visibility = "FRAGMENT")]
+    pub depth: Sampled2DDepth<'a>,
+}
+
+/// The bind group layout for [GBufferResources].
+pub type GBufferResourcesLayout =
+    <GBufferResources<'static> as resource_binding::Resources>::Layout;
+
+/// The set of textures for deferred rendering with multiple render targets.
+///
+/// A geometry pass renders the scene's surface attributes into the albedo, normal and material
+/// textures (and the depth texture) through the [render_target](GBuffer::render_target); a
+/// lighting pass then samples these textures through the [resources](GBuffer::resources) to
+/// compute the final shading.
+///
+/// ```ignore
+/// let gbuffer = GBuffer::new(&device, GBufferDescriptor {
+///     albedo: rgba8unorm,
+///     normal: rgba16float,
+///     material: rgba8unorm,
+///     depth: depth24plus,
+///     width,
+///     height,
+/// });
+///
+/// // Geometry pass, with a pipeline for
+/// // `GBufferLayout<rgba8unorm, rgba16float, rgba8unorm, depth24plus>`
+/// encoder = encoder
+///     .begin_render_pass(RenderPassDescriptor::new(
+///         &gbuffer.render_target(&Default::default()),
+///     ))
+///     .set_pipeline(&geometry_pipeline)
+///     ...
+///     .end();
+///
+/// // Lighting pass
+/// let lighting_bind_group = device.create_bind_group(&lighting_layout, gbuffer.resources());
+///
+/// encoder = encoder
+///     .begin_render_pass(...)
+///     .set_pipeline(&lighting_pipeline)
+///     .set_bind_groups(&lighting_bind_group)
+///     ...
+///     .end();
+/// ```
+pub struct GBuffer<Albedo, Normal, Material, Depth> {
+    albedo: Texture2D<Albedo, GBufferUsages>,
+    normal: Texture2D<Normal, GBufferUsages>,
+    material: Texture2D<Material, GBufferUsages>,
+    depth: Texture2D<Depth, GBufferUsages>,
+}
+
+impl<Albedo, Normal, Material, Depth> GBuffer<Albedo, Normal, Material, Depth>
+where
+    Albedo: Texture2DFormat + FloatRenderable + FloatSamplable,
+    Normal: Texture2DFormat + FloatRenderable + FloatSamplable,
+    Material: Texture2DFormat + FloatRenderable + FloatSamplable,
+    Depth: Texture2DFormat + DepthRenderable + DepthSamplable,
+{
+    /// Creates the textures for a new [GBuffer] with the given `descriptor`.
+    pub fn new(
+        device: &Device,
+        descriptor: GBufferDescriptor<Albedo, Normal, Material, Depth>,
+    ) -> Self {
+        let GBufferDescriptor {
+            albedo,
+            normal,
+            material,
+            depth,
+            width,
+            height,
+        } = descriptor;
+
+        GBuffer {
+            albedo: create_texture(device, albedo, width, height),
+            normal: create_texture(device, normal, width, height),
+            material: create_texture(device, material, width, height),
+            depth: create_texture(device, depth, width, height),
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.albedo.width()
+    }
+
+    pub fn height(&self) -> u32 {
+        self.albedo.height()
+    }
+
+    pub fn albedo(&self) -> &Texture2D<Albedo, GBufferUsages> {
+        &self.albedo
+    }
+
+    pub fn normal(&self) -> &Texture2D<Normal, GBufferUsages> {
+        &self.normal
+    }
+
+    pub fn material(&self) -> &Texture2D<Material, GBufferUsages> {
+        &self.material
+    }
+
+    pub fn depth(&self) -> &Texture2D<Depth, GBufferUsages> {
+        &self.depth
+    }
+
+    /// Returns the render target for the geometry pass.
+    ///
+    /// All attachments are cleared to the `clear_values` at the start of the render pass.
+    pub fn render_target(
+        &self,
+        clear_values: &GBufferClearValues,
+    ) -> GBufferRenderTarget<Albedo, Normal, Material, Depth> {
+        RenderTarget {
+            color: (
+                FloatAttachment {
+                    image: self.albedo.attachable_image_mip(0),
+                    load_op: LoadOp::Clear(clear_values.albedo),
+                    store_op: StoreOp::Store,
+                },
+                FloatAttachment {
+                    image: self.normal.attachable_image_mip(0),
+                    load_op: LoadOp::Clear(clear_values.normal),
+                    store_op: StoreOp::Store,
+                },
+                FloatAttachment {
+                    image: self.material.attachable_image_mip(0),
+                    load_op: LoadOp::Clear(clear_values.material),
+                    store_op: StoreOp::Store,
+                },
+            ),
+            depth_stencil: DepthAttachment {
+                image: self.depth.attachable_image_mip(0),
+                load_op: LoadOp::Clear(clear_values.depth),
+                store_op: StoreOp::Store,
+            },
+        }
+    }
+
+    /// Returns the textures as resources for the lighting pass.
+    ///
+    /// See [GBufferResources] for the binding layout.
+    pub fn resources(&self) -> GBufferResources {
+        GBufferResources {
+            albedo: self.albedo.sampled_float(&Default::default()),
+            normal: self.normal.sampled_float(&Default::default()),
+            material: self.material.sampled_float(&Default::default()),
+            depth: self.depth.sampled_depth(&Default::default()),
+        }
+    }
+
+    /// Recreates the textures with the given `width` and `height`, e.g. after the window was
+    /// resized.
+    ///
+    /// Does nothing if the size did not change. Bind groups created from earlier
+    /// [resources](GBuffer::resources) continue to refer to the old textures and must be
+    /// recreated.
+    pub fn resize(&mut self, device: &Device, width: u32, height: u32)
+    where
+        Albedo: Default,
+        Normal: Default,
+        Material: Default,
+        Depth: Default,
+    {
+        if width == self.width() && height == self.height() {
+            return;
+        }
+
+        self.albedo = create_texture(device, Albedo::default(), width, height);
+        self.normal = create_texture(device, Normal::default(), width, height);
+        self.material = create_texture(device, Material::default(), width, height);
+        self.depth = create_texture(device, Depth::default(), width, height);
+    }
+}
+
+fn create_texture<F>(
+    device: &Device,
+    format: F,
+    width: u32,
+    height: u32,
+) -> Texture2D<F, GBufferUsages>
+where
+    F: Texture2DFormat + Renderable,
+{
+    device.create_texture_2d(&Texture2DDescriptor {
+        format,
+        usage: texture::Usages::render_attachment().and_texture_binding(),
+        view_formats: (),
+        width,
+        height,
+        layers: 1,
+        mipmap_levels: MipmapLevels::Partial(1),
+    })
+}
@@ -0,0 +1,359 @@
+use std::ops::Range;
+
+use crate::buffer;
+use crate::buffer::Buffer;
+use crate::command::{
+    Draw, DrawCommandEncoder, DrawIndexed, DrawIndexedCommandEncoder, IndexBuffer,
+    RenderStateEncoder, VertexBuffers,
+};
+use crate::render_pipeline::{IndexData, Vertex};
+use crate::util::MeshIndices;
+
+mod mesh_vertex_data_seal {
+    pub trait Seal {}
+}
+
+/// Vertex buffers that can be owned by a [Mesh]: a single vertex buffer, or a tuple of up to 4
+/// vertex buffers.
+pub trait MeshVertexData: mesh_vertex_data_seal::Seal {
+    type VertexBuffers<'a>: VertexBuffers
+    where
+        Self: 'a;
+
+    fn vertex_buffers(&self) -> Self::VertexBuffers<'_>;
+
+    /// The number of vertices in the first vertex buffer.
+    fn vertex_count(&self) -> usize;
+}
+
+impl<V, U> mesh_vertex_data_seal::Seal for Buffer<[V], U>
+where
+    V: Vertex,
+    U: buffer::Vertex,
+{
+}
+impl<V, U> MeshVertexData for Buffer<[V], U>
+where
+    V: Vertex,
+    U: buffer::Vertex,
+{
+    type VertexBuffers<'a>
+        = &'a Buffer<[V], U>
+    where
+        Self: 'a;
+
+    fn vertex_buffers(&self) -> Self::VertexBuffers<'_> {
+        self
+    }
+
+    fn vertex_count(&self) -> usize {
+        self.len()
+    }
+}
+
+macro_rules! impl_mesh_vertex_data {
+    ($($V:ident $U:ident $i:tt),*) => {
+        impl<$($V, $U),*> mesh_vertex_data_seal::Seal for ($(Buffer<[$V], $U>,)*)
+        where
+            $($V: Vertex, $U: buffer::Vertex),*
+        {
+        }
+        impl<$($V, $U),*> MeshVertexData for ($(Buffer<[$V], $U>,)*)
+        where
+            $($V: Vertex, $U: buffer::Vertex),*
+        {
+            type VertexBuffers<'a> = ($(&'a Buffer<[$V], $U>),*) where Self: 'a;
+
+            fn vertex_buffers(&self) -> Self::VertexBuffers<'_> {
+                ($(&self.$i),*)
+            }
+
+            fn vertex_count(&self) -> usize {
+                self.0.len()
+            }
+        }
+    }
+}
+
+impl_mesh_vertex_data!(V0 U0 0, V1 U1 1);
+impl_mesh_vertex_data!(V0 U0 0, V1 U1 1, V2 U2 2);
+impl_mesh_vertex_data!(V0 U0 0, V1 U1 1, V2 U2 2, V3 U3 3);
+
+mod mesh_index_data_seal {
+    pub trait Seal {}
+}
+
+/// Index buffers that can be owned by a [Mesh]: a `u16` or `u32` index buffer, or
+/// [MeshIndices].
+pub trait MeshIndexData: mesh_index_data_seal::Seal {
+    type IndexBuffer<'a>: IndexBuffer
+    where
+        Self: 'a;
+
+    fn index_buffer(&self) -> Self::IndexBuffer<'_>;
+
+    fn index_count(&self) -> usize;
+}
+
+impl<I, U> mesh_index_data_seal::Seal for Buffer<[I], U>
+where
+    I: IndexData,
+    U: buffer::Index,
+{
+}
+impl<I, U> MeshIndexData for Buffer<[I], U>
+where
+    I: IndexData,
+    U: buffer::Index,
+{
+    type IndexBuffer<'a>
+        = &'a Buffer<[I], U>
+    where
+        Self: 'a;
+
+    fn index_buffer(&self) -> Self::IndexBuffer<'_> {
+        self
+    }
+
+    fn index_count(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<U> mesh_index_data_seal::Seal for MeshIndices<U> where U: buffer::Index {}
+impl<U> MeshIndexData for MeshIndices<U>
+where
+    U: buffer::Index,
+{
+    type IndexBuffer<'a>
+        = &'a MeshIndices<U>
+    where
+        Self: 'a;
+
+    fn index_buffer(&self) -> Self::IndexBuffer<'_> {
+        self
+    }
+
+    fn index_count(&self) -> usize {
+        self.len()
+    }
+}
+
+/// A range of the vertices (for non-indexed meshes) or indices (for indexed meshes) of a [Mesh]
+/// that is drawn as a unit, e.g. a part of a model that uses a different material.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct SubMesh {
+    /// The first vertex or index of the sub-mesh.
+    pub first: u32,
+
+    /// The number of vertices or indices in the sub-mesh.
+    pub count: u32,
+
+    /// The value added to each index before fetching vertex data. Ignored for non-indexed meshes.
+    pub base_vertex: u32,
+}
+
+/// Bundles the vertex buffer(s) and optional index buffer of a mesh, along with its
+/// [sub-meshes](SubMesh).
+///
+/// A mesh without an index buffer (created with [Mesh::new]) is drawn with [Draw] commands; a mesh
+/// with an index buffer (created with [Mesh::indexed]) is drawn with [DrawIndexed] commands:
+///
+/// ```ignore
+/// let mesh = Mesh::indexed(vertex_buffer, index_buffer);
+///
+/// encoder = mesh.draw_indexed(encoder.set_pipeline(&pipeline), 0..1).end();
+/// ```
+pub struct Mesh<V, I = ()> {
+    vertex_data: V,
+    index_data: I,
+    sub_meshes: Vec<SubMesh>,
+}
+
+impl<V> Mesh<V>
+where
+    V: MeshVertexData,
+{
+    /// Creates a non-indexed mesh from the given `vertex_data`.
+    ///
+    /// The mesh initially has a single sub-mesh that covers all vertices.
+    pub fn new(vertex_data: V) -> Self {
+        let sub_meshes = vec![SubMesh {
+            first: 0,
+            count: vertex_data.vertex_count() as u32,
+            base_vertex: 0,
+        }];
+
+        Mesh {
+            vertex_data,
+            index_data: (),
+            sub_meshes,
+        }
+    }
+
+    /// Sets the vertex buffers for this mesh on the `encoder` and records a [Draw] command for
+    /// each of its sub-meshes, with the given range of `instances`.
+    pub fn draw<'a, E, T>(
+        &'a self,
+        encoder: E,
+        instances: Range<u32>,
+    ) -> E::WithVertexBuffers<V::VertexBuffers<'a>>
+    where
+        E: RenderStateEncoder<T>,
+        E::WithVertexBuffers<V::VertexBuffers<'a>>: DrawCommandEncoder,
+    {
+        let mut encoder = encoder.set_vertex_buffers(self.vertex_data.vertex_buffers());
+
+        for sub_mesh in &self.sub_meshes {
+            encoder = encoder.draw(Self::sub_mesh_draw(sub_mesh, instances.clone()));
+        }
+
+        encoder
+    }
+
+    /// Sets the vertex buffers for this mesh on the `encoder` and records a [Draw] command for
+    /// the sub-mesh at the given `index`, with the given range of `instances`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds for the mesh's sub-meshes.
+    pub fn draw_sub_mesh<'a, E, T>(
+        &'a self,
+        encoder: E,
+        index: usize,
+        instances: Range<u32>,
+    ) -> E::WithVertexBuffers<V::VertexBuffers<'a>>
+    where
+        E: RenderStateEncoder<T>,
+        E::WithVertexBuffers<V::VertexBuffers<'a>>: DrawCommandEncoder,
+    {
+        let sub_mesh = &self.sub_meshes[index];
+
+        encoder
+            .set_vertex_buffers(self.vertex_data.vertex_buffers())
+            .draw(Self::sub_mesh_draw(sub_mesh, instances))
+    }
+
+    fn sub_mesh_draw(sub_mesh: &SubMesh, instances: Range<u32>) -> Draw {
+        Draw {
+            vertex_count: sub_mesh.count,
+            instance_count: instances.len() as u32,
+            first_vertex: sub_mesh.first,
+            first_instance: instances.start,
+        }
+    }
+}
+
+impl<V, I> Mesh<V, I>
+where
+    V: MeshVertexData,
+    I: MeshIndexData,
+{
+    /// Creates an indexed mesh from the given `vertex_data` and `index_data`.
+    ///
+    /// The mesh initially has a single sub-mesh that covers all indices.
+    pub fn indexed(vertex_data: V, index_data: I) -> Self {
+        let sub_meshes = vec![SubMesh {
+            first: 0,
+            count: index_data.index_count() as u32,
+            base_vertex: 0,
+        }];
+
+        Mesh {
+            vertex_data,
+            index_data,
+            sub_meshes,
+        }
+    }
+
+    pub fn index_data(&self) -> &I {
+        &self.index_data
+    }
+
+    /// Sets the vertex buffers and index buffer for this mesh on the `encoder` and records a
+    /// [DrawIndexed] command for each of its sub-meshes, with the given range of `instances`.
+    #[allow(clippy::type_complexity)]
+    pub fn draw_indexed<'a, E, T>(
+        &'a self,
+        encoder: E,
+        instances: Range<u32>,
+    ) -> <E::WithVertexBuffers<V::VertexBuffers<'a>> as RenderStateEncoder<T>>::WithIndexBuffer<
+        I::IndexBuffer<'a>,
+    >
+    where
+        E: RenderStateEncoder<T>,
+        E::WithVertexBuffers<V::VertexBuffers<'a>>: RenderStateEncoder<T>,
+        <E::WithVertexBuffers<V::VertexBuffers<'a>> as RenderStateEncoder<T>>::WithIndexBuffer<
+            I::IndexBuffer<'a>,
+        >: DrawIndexedCommandEncoder,
+    {
+        let mut encoder = encoder
+            .set_vertex_buffers(self.vertex_data.vertex_buffers())
+            .set_index_buffer(self.index_data.index_buffer());
+
+        for sub_mesh in &self.sub_meshes {
+            encoder =
+                encoder.draw_indexed(Self::sub_mesh_draw_indexed(sub_mesh, instances.clone()));
+        }
+
+        encoder
+    }
+
+    /// Sets the vertex buffers and index buffer for this mesh on the `encoder` and records a
+    /// [DrawIndexed] command for the sub-mesh at the given `index`, with the given range of
+    /// `instances`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds for the mesh's sub-meshes.
+    #[allow(clippy::type_complexity)]
+    pub fn draw_indexed_sub_mesh<'a, E, T>(
+        &'a self,
+        encoder: E,
+        index: usize,
+        instances: Range<u32>,
+    ) -> <E::WithVertexBuffers<V::VertexBuffers<'a>> as RenderStateEncoder<T>>::WithIndexBuffer<
+        I::IndexBuffer<'a>,
+    >
+    where
+        E: RenderStateEncoder<T>,
+        E::WithVertexBuffers<V::VertexBuffers<'a>>: RenderStateEncoder<T>,
+        <E::WithVertexBuffers<V::VertexBuffers<'a>> as RenderStateEncoder<T>>::WithIndexBuffer<
+            I::IndexBuffer<'a>,
+        >: DrawIndexedCommandEncoder,
+    {
+        let sub_mesh = &self.sub_meshes[index];
+
+        encoder
+            .set_vertex_buffers(self.vertex_data.vertex_buffers())
+            .set_index_buffer(self.index_data.index_buffer())
+            .draw_indexed(Self::sub_mesh_draw_indexed(sub_mesh, instances))
+    }
+
+    fn sub_mesh_draw_indexed(sub_mesh: &SubMesh, instances: Range<u32>) -> DrawIndexed {
+        DrawIndexed {
+            index_count: sub_mesh.count,
+            instance_count: instances.len() as u32,
+            first_index: sub_mesh.first,
+            base_vertex: sub_mesh.base_vertex,
+            first_instance: instances.start,
+        }
+    }
+}
+
+impl<V, I> Mesh<V, I> {
+    /// Replaces the sub-meshes of this mesh.
+    pub fn with_sub_meshes(mut self, sub_meshes: Vec<SubMesh>) -> Self {
+        self.sub_meshes = sub_meshes;
+
+        self
+    }
+
+    pub fn vertex_data(&self) -> &V {
+        &self.vertex_data
+    }
+
+    pub fn sub_meshes(&self) -> &[SubMesh] {
+        &self.sub_meshes
+    }
+}
@@ -0,0 +1,160 @@
+use crate::buffer;
+use crate::buffer::Buffer;
+use crate::device::Device;
+use crate::render_pipeline::IndexFormat;
+
+pub(crate) enum MeshIndexBuffer<U> {
+    U16(Buffer<[u16], U>),
+    U32(Buffer<[u32], U>),
+}
+
+/// An index buffer that uses the smallest index format that can address all vertices of a mesh.
+///
+/// WebGPU only supports `u16` and `u32` index data. [MeshIndices::new] accepts indices of any
+/// unsigned integer type (including `u8`) and converts them on upload to `u16` if the mesh has
+/// fewer than `65535` vertices, or to `u32` otherwise. Note that `0xFFFF` is never used as a `u16`
+/// index value, as it is reserved as the primitive restart value for strip topologies.
+///
+/// A reference to [MeshIndices] can be used as the index buffer for pipelines that accept
+/// [IndexAny](crate::render_pipeline::IndexAny):
+///
+/// ```ignore
+/// let indices = MeshIndices::new(&device, &[0u8, 1, 2, 2, 1, 3], 4, buffer::Usages::index());
+///
+/// encoder = encoder
+///     .begin_render_pass(...)
+///     .set_pipeline(&pipeline)
+///     .set_vertex_buffers(&vertices)
+///     .set_index_buffer(&indices)
+///     .draw_indexed(DrawIndexed {
+///         index_count: indices.len() as u32,
+///         ...
+///     })
+///     .end();
+/// ```
+pub struct MeshIndices<U> {
+    pub(crate) buffer: MeshIndexBuffer<U>,
+    len: usize,
+}
+
+impl<U> MeshIndices<U>
+where
+    U: buffer::ValidUsageFlags,
+{
+    /// Creates a new index buffer with the given `usage` from the `indices` of a mesh with the
+    /// given `vertex_count`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the `indices` is not smaller than the `vertex_count`.
+    pub fn new<I>(device: &Device, indices: &[I], vertex_count: u32, usage: U) -> Self
+    where
+        I: Copy + Into<u32>,
+    {
+        for index in indices {
+            let index = (*index).into();
+
+            if index >= vertex_count {
+                panic!(
+                    "index `{}` is out of bounds for vertex count `{}`",
+                    index, vertex_count
+                );
+            }
+        }
+
+        let buffer = if vertex_count < u16::MAX as u32 {
+            MeshIndexBuffer::U16(device.create_buffer(u16_index_data(indices), usage))
+        } else {
+            let data: Vec<u32> = indices.iter().map(|i| (*i).into()).collect();
+
+            MeshIndexBuffer::U32(device.create_buffer(data, usage))
+        };
+
+        MeshIndices {
+            buffer,
+            len: indices.len(),
+        }
+    }
+}
+
+impl<U> MeshIndices<U> {
+    /// The index format that was selected for this index buffer.
+    pub fn format(&self) -> IndexFormat {
+        match &self.buffer {
+            MeshIndexBuffer::U16(_) => IndexFormat::U16,
+            MeshIndexBuffer::U32(_) => IndexFormat::U32,
+        }
+    }
+
+    /// The number of indices in this index buffer.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether this index buffer contains no indices.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The underlying buffer if the selected format is [IndexFormat::U16], `None` otherwise.
+    ///
+    /// If the number of indices is odd, the buffer holds an additional `0` padding index, see
+    /// [len](MeshIndices::len) for the number of indices.
+    pub fn as_u16(&self) -> Option<&Buffer<[u16], U>> {
+        if let MeshIndexBuffer::U16(buffer) = &self.buffer {
+            Some(buffer)
+        } else {
+            None
+        }
+    }
+
+    /// The underlying buffer if the selected format is [IndexFormat::U32], `None` otherwise.
+    pub fn as_u32(&self) -> Option<&Buffer<[u32], U>> {
+        if let MeshIndexBuffer::U32(buffer) = &self.buffer {
+            Some(buffer)
+        } else {
+            None
+        }
+    }
+}
+
+/// Converts the `indices` to `u16` index data, padded with a `0` index to an even length, as the
+/// size of a buffer that is mapped at creation must be a multiple of `4` bytes.
+fn u16_index_data<I>(indices: &[I]) -> Vec<u16>
+where
+    I: Copy + Into<u32>,
+{
+    let mut data: Vec<u16> = indices.iter().map(|i| (*i).into() as u16).collect();
+
+    if data.len() % 2 != 0 {
+        data.push(0);
+    }
+
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn u16_index_data_pads_odd_count() {
+        let data = u16_index_data(&[0u8, 1, 2]);
+
+        assert_eq!(data, vec![0, 1, 2, 0]);
+        assert_eq!(data.len() * 2 % 4, 0);
+    }
+
+    #[test]
+    fn u16_index_data_even_count() {
+        assert_eq!(
+            u16_index_data(&[0u32, 1, 2, 2, 1, 3]),
+            vec![0, 1, 2, 2, 1, 3]
+        );
+    }
+
+    #[test]
+    fn u16_index_data_empty() {
+        assert!(u16_index_data::<u16>(&[]).is_empty());
+    }
+}
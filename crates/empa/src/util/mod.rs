@@ -0,0 +1,51 @@
+mod atlas_allocator;
+pub use self::atlas_allocator::*;
+
+#[cfg(feature = "render")]
+mod blitter;
+#[cfg(feature = "render")]
+pub use self::blitter::*;
+
+#[cfg(feature = "render")]
+mod fullscreen;
+
+#[cfg(feature = "render")]
+mod gbuffer;
+#[cfg(feature = "render")]
+pub use self::gbuffer::*;
+
+#[cfg(feature = "render")]
+mod mesh;
+#[cfg(feature = "render")]
+pub use self::mesh::*;
+
+#[cfg(feature = "render")]
+mod mesh_indices;
+#[cfg(feature = "render")]
+pub use self::mesh_indices::*;
+
+#[cfg(all(feature = "render", feature = "compute"))]
+mod particle_system;
+#[cfg(all(feature = "render", feature = "compute"))]
+pub use self::particle_system::*;
+
+#[cfg(feature = "compute")]
+mod ping_pong;
+#[cfg(feature = "compute")]
+pub use self::ping_pong::*;
+
+#[cfg(feature = "render")]
+pub mod text;
+
+#[cfg(feature = "render")]
+mod tonemap;
+#[cfg(feature = "render")]
+pub use self::tonemap::*;
+
+mod uniform_ring;
+pub use self::uniform_ring::*;
+
+#[cfg(feature = "render")]
+mod vertex_pulling;
+#[cfg(feature = "render")]
+pub use self::vertex_pulling::*;
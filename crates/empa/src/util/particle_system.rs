@@ -0,0 +1,431 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::abi;
+use crate::access_mode::ReadWrite;
+use crate::buffer;
+use crate::buffer::{Buffer, BufferUsages, Storage, Uniform};
+use crate::command::{
+    CommandEncoder, DispatchWorkgroupsLinear, Draw, DrawCommandEncoder,
+    ResourceBindingCommandEncoder,
+};
+use crate::compute_pipeline::{
+    ComputePipeline, ComputePipelineDescriptorBuilder, ComputeStageBuilder,
+};
+use crate::device::{Device, PipelineCreationError};
+use crate::resource_binding;
+use crate::resource_binding::{
+    BindGroup, BindGroupLayout, PipelineLayout, Resources, TypedBindGroupLayout,
+};
+use crate::shader_module::{ParseError, ShaderModule, ShaderSource};
+use crate::util::{PingPong, PingPongBindGroups};
+
+const PRELUDE: &str = include_str!("particle_system.wgsl");
+
+const WORKGROUP_SIZE: u32 = 64;
+
+/// The buffer that holds the particles of a [ParticleSystem].
+pub type ParticleBuffer<P> = Buffer<[P], BufferUsages!(StorageBinding)>;
+
+/// The buffer that holds the indirect draw arguments of a [ParticleSystem].
+///
+/// The instance count is the number of live particles, as computed on the GPU.
+pub type ParticleCountBuffer = Buffer<Draw, BufferUsages!(Indirect | StorageBinding)>;
+
+type SimulationBuffer = Buffer<Simulation, BufferUsages!(UniformBinding | CopyDst)>;
+
+#[derive(abi::Sized, Clone, Copy)]
+#[repr(C)]
+struct Simulation {
+    time: f32,
+    delta_time: f32,
+    emit_count: u32,
+    capacity: u32,
+}
+
+#[derive(resource_binding::Resources)]
+struct SimulationResources<'a, P>
+where
+    P: abi::Sized + 'static,
+{
+    #[resource(binding = 0, visibility = "COMPUTE")]
+    simulation: Uniform<'a, Simulation>,
+    #[resource(binding = 1, visibility = "COMPUTE")]
+    particles_in: Storage<'a, [P]>,
+    #[resource(binding = 2, visibility = "COMPUTE")]
+    count_in: Storage<'a, Draw, ReadWrite>,
+    #[resource(binding = 3, visibility = "COMPUTE")]
+    particles_out: Storage<'a, [P], ReadWrite>,
+    #[resource(binding = 4, visibility = "COMPUTE")]
+    count_out: Storage<'a, Draw, ReadWrite>,
+}
+
+type SimulationLayout<P> = <SimulationResources<'static, P> as Resources>::Layout;
+
+struct ParticleState<P> {
+    particles: ParticleBuffer<P>,
+    count: ParticleCountBuffer,
+}
+
+/// Settings for a [ParticleSystem].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ParticleSystemDescriptor<'a> {
+    /// The maximum number of live particles.
+    pub capacity: u32,
+
+    /// The vertex count of the indirect draw command issued by [ParticleSystem::draw], e.g. `6`
+    /// for particles rendered as quads made from 2 triangles.
+    pub vertices_per_particle: u32,
+
+    /// WGSL that declares the `Particle` struct, which must match the memory layout of the
+    /// particle type, along with any helpers used by the other snippets.
+    pub particle_source: &'a str,
+
+    /// WGSL that declares `fn emit_particle(index: u32) -> Particle`, called to initialize each
+    /// emitted particle.
+    pub emit_source: &'a str,
+
+    /// WGSL that declares `fn update_particle(particle: ptr<function, Particle>) -> bool`, called
+    /// for each live particle on each step; a particle for which the function returns `false` is
+    /// removed.
+    pub update_source: &'a str,
+}
+
+/// The inputs for a single [ParticleSystem::simulate] step.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ParticleStep {
+    /// The simulation time, available to the snippets as `simulation.time`.
+    pub time: f32,
+
+    /// The time elapsed since the previous step, available to the snippets as
+    /// `simulation.delta_time`.
+    pub delta_time: f32,
+
+    /// The number of particles to emit; emission stops once the system is at capacity.
+    pub emit_count: u32,
+}
+
+async fn create_pipeline<P>(
+    device: &Device,
+    layout: &PipelineLayout<(SimulationLayout<P>,)>,
+    shader: &ShaderModule,
+    entry_point: &str,
+) -> Result<ComputePipeline<(SimulationLayout<P>,)>, ParticleSystemError>
+where
+    P: abi::Sized + 'static,
+{
+    device
+        .create_compute_pipeline(
+            &ComputePipelineDescriptorBuilder::begin()
+                .layout(layout)
+                .compute(ComputeStageBuilder::begin(shader, entry_point).finish())
+                .finish(),
+        )
+        .await
+        .map_err(ParticleSystemError::PipelineCreation)
+}
+
+/// Signals that a [ParticleSystem] could not be created from its WGSL snippets.
+#[derive(Debug)]
+pub enum ParticleSystemError {
+    /// The combined snippets are not valid WGSL.
+    Parse(ParseError),
+
+    /// The device failed to create one of the simulation pipelines.
+    PipelineCreation(PipelineCreationError),
+}
+
+impl fmt::Display for ParticleSystemError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParticleSystemError::Parse(err) => {
+                write!(f, "could not parse the particle shader: {}", err)
+            }
+            ParticleSystemError::PipelineCreation(err) => fmt::Display::fmt(err, f),
+        }
+    }
+}
+
+impl Error for ParticleSystemError {}
+
+/// A GPU particle simulation that stores particles of type `P` in a pair of storage buffers.
+///
+/// Each [simulate](ParticleSystem::simulate) step records a compute pass that runs the
+/// `update_particle` snippet for every live particle, compacts the surviving particles into the
+/// other buffer, and then appends newly emitted particles. The number of live particles never
+/// leaves the GPU: it is written directly into the instance count of an indirect draw command,
+/// which [draw](ParticleSystem::draw) records into a render pass.
+///
+/// ```ignore
+/// let mut particles = ParticleSystem::<MyParticle>::new(&device, &ParticleSystemDescriptor {
+///     capacity: 10_000,
+///     vertices_per_particle: 6,
+///     particle_source: PARTICLE_WGSL,
+///     emit_source: EMIT_WGSL,
+///     update_source: UPDATE_WGSL,
+/// })
+/// .await?;
+///
+/// let render_bind_groups = particles.render_bind_groups(&device, &layout, |particles| {
+///     RenderResources { particles }
+/// });
+///
+/// encoder = particles.simulate(encoder, &ParticleStep {
+///     time,
+///     delta_time,
+///     emit_count: 100,
+/// });
+///
+/// encoder = particles
+///     .draw(
+///         encoder
+///             .begin_render_pass(...)
+///             .set_pipeline(&pipeline)
+///             .set_bind_groups(render_bind_groups.get(&particles)),
+///     )
+///     .end();
+/// ```
+///
+/// The render pipeline's vertex stage reads the particle for the current instance from the
+/// storage buffer with `instance_index`.
+///
+/// # Synchronization
+///
+/// The step inputs are written to a uniform buffer with
+/// [Queue::write_buffer](crate::device::Queue::write_buffer) when
+/// [simulate](ParticleSystem::simulate) is called.
+/// Submit the command buffer that contains a step before recording the next step, otherwise both
+/// steps observe the inputs of the later step.
+pub struct ParticleSystem<P>
+where
+    P: abi::Sized + 'static,
+{
+    device: Device,
+    capacity: u32,
+    state: PingPong<ParticleState<P>>,
+    simulation: SimulationBuffer,
+    bind_groups: PingPongBindGroups<SimulationLayout<P>>,
+    reset_pipeline: ComputePipeline<(SimulationLayout<P>,)>,
+    update_pipeline: ComputePipeline<(SimulationLayout<P>,)>,
+    emit_pipeline: ComputePipeline<(SimulationLayout<P>,)>,
+}
+
+impl<P> ParticleSystem<P>
+where
+    P: abi::Sized + 'static,
+{
+    /// Creates a new particle system with no live particles.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `capacity` is `0`.
+    pub async fn new(
+        device: &Device,
+        descriptor: &ParticleSystemDescriptor<'_>,
+    ) -> Result<Self, ParticleSystemError> {
+        let ParticleSystemDescriptor {
+            capacity,
+            vertices_per_particle,
+            particle_source,
+            emit_source,
+            update_source,
+        } = *descriptor;
+
+        if capacity == 0 {
+            panic!("the particle system capacity must be greater than `0`");
+        }
+
+        let source = format!(
+            "{}\n{}\n{}\n{}",
+            particle_source, emit_source, update_source, PRELUDE
+        );
+        let source = ShaderSource::parse(source).map_err(ParticleSystemError::Parse)?;
+        let shader = device.create_shader_module(&source);
+
+        let bind_group_layout = device.create_bind_group_layout::<SimulationLayout<P>>();
+        let pipeline_layout = device.create_pipeline_layout(&bind_group_layout);
+
+        let reset_pipeline =
+            create_pipeline(device, &pipeline_layout, &shader, "particles_reset").await?;
+        let update_pipeline =
+            create_pipeline(device, &pipeline_layout, &shader, "particles_update").await?;
+        let emit_pipeline =
+            create_pipeline(device, &pipeline_layout, &shader, "particles_emit").await?;
+
+        let create_state = || {
+            // Particles are only read up to the live particle count, which starts at `0`.
+            let particles: Buffer<[P], _> = unsafe {
+                device
+                    .create_slice_buffer_uninit(
+                        capacity as usize,
+                        buffer::Usages::storage_binding(),
+                    )
+                    .assume_init()
+            };
+            let count = device.create_buffer(
+                Draw {
+                    vertex_count: vertices_per_particle,
+                    instance_count: 0,
+                    first_vertex: 0,
+                    first_instance: 0,
+                },
+                buffer::Usages::indirect().and_storage_binding(),
+            );
+
+            ParticleState { particles, count }
+        };
+
+        let state = PingPong::new(create_state(), create_state());
+
+        let simulation = device.create_buffer(
+            Simulation {
+                time: 0.0,
+                delta_time: 0.0,
+                emit_count: 0,
+                capacity,
+            },
+            buffer::Usages::uniform_binding().and_copy_dst(),
+        );
+
+        let bind_groups = state.bind_groups(device, &bind_group_layout, |front, back| {
+            SimulationResources {
+                simulation: simulation.uniform(),
+                particles_in: front.particles.storage(),
+                count_in: front.count.storage(),
+                particles_out: back.particles.storage(),
+                count_out: back.count.storage(),
+            }
+        });
+
+        Ok(ParticleSystem {
+            device: device.clone(),
+            capacity,
+            state,
+            simulation,
+            bind_groups,
+            reset_pipeline,
+            update_pipeline,
+            emit_pipeline,
+        })
+    }
+
+    /// The maximum number of live particles.
+    pub fn capacity(&self) -> u32 {
+        self.capacity
+    }
+
+    /// The buffer that holds the current particles.
+    ///
+    /// Only the first `n` particles are live, where `n` is the instance count in the
+    /// [count](ParticleSystem::count) buffer. The buffer changes with every
+    /// [simulate](ParticleSystem::simulate) step.
+    pub fn particles(&self) -> &ParticleBuffer<P> {
+        &self.state.front().particles
+    }
+
+    /// The buffer that holds the indirect draw arguments for the current particles.
+    ///
+    /// The buffer changes with every [simulate](ParticleSystem::simulate) step.
+    pub fn count(&self) -> &ParticleCountBuffer {
+        &self.state.front().count
+    }
+
+    /// Records a simulation step into the `encoder`.
+    ///
+    /// See the [type-level documentation](ParticleSystem) for synchronization requirements.
+    pub fn simulate(&mut self, encoder: CommandEncoder, step: &ParticleStep) -> CommandEncoder {
+        let ParticleStep {
+            time,
+            delta_time,
+            emit_count,
+        } = *step;
+
+        self.device.queue().write_buffer(
+            self.simulation.view(),
+            &Simulation {
+                time,
+                delta_time,
+                emit_count,
+                capacity: self.capacity,
+            },
+        );
+
+        let bind_group = self.bind_groups.get(&self.state);
+
+        let mut pass = encoder
+            .begin_compute_pass()
+            .set_pipeline(&self.reset_pipeline)
+            .set_bind_groups(bind_group)
+            .dispatch_workgroups_linear(DispatchWorkgroupsLinear { count: 1 })
+            .set_pipeline(&self.update_pipeline)
+            .set_bind_groups(bind_group)
+            .dispatch_workgroups_linear(DispatchWorkgroupsLinear {
+                count: self.capacity.div_ceil(WORKGROUP_SIZE),
+            })
+            .set_pipeline(&self.emit_pipeline)
+            .set_bind_groups(bind_group);
+
+        if emit_count > 0 {
+            pass = pass.dispatch_workgroups_linear(DispatchWorkgroupsLinear {
+                count: emit_count.min(self.capacity).div_ceil(WORKGROUP_SIZE),
+            });
+        }
+
+        let encoder = pass.end();
+
+        self.state.swap();
+
+        encoder
+    }
+
+    /// Creates the bind groups that make the current particles available to a render pipeline.
+    ///
+    /// The `resources` function is called once for each of the particle system's buffers. Use
+    /// [ParticleBindGroups::get] to select the bind group for the current particles.
+    pub fn render_bind_groups<'a, L, R, F>(
+        &'a self,
+        device: &Device,
+        layout: &BindGroupLayout<L>,
+        mut resources: F,
+    ) -> ParticleBindGroups<L>
+    where
+        L: TypedBindGroupLayout,
+        R: Resources<Layout = L>,
+        F: FnMut(Storage<'a, [P]>) -> R,
+    {
+        let bind_groups = self.state.bind_groups(device, layout, |front, _| {
+            resources(front.particles.storage())
+        });
+
+        ParticleBindGroups { bind_groups }
+    }
+
+    /// Records an indirect draw of the current particles into the `encoder`.
+    ///
+    /// Draws one instance for each live particle, with the vertex count set by
+    /// [ParticleSystemDescriptor::vertices_per_particle].
+    pub fn draw<E>(&self, encoder: E) -> E
+    where
+        E: DrawCommandEncoder,
+    {
+        encoder.draw_indirect(self.count().view())
+    }
+}
+
+/// The bind groups created by [ParticleSystem::render_bind_groups].
+pub struct ParticleBindGroups<L> {
+    bind_groups: PingPongBindGroups<L>,
+}
+
+impl<L> ParticleBindGroups<L> {
+    /// Returns the bind group for the current particles of the `particle_system`.
+    ///
+    /// The `particle_system` must be the particle system from which these bind groups were
+    /// created.
+    pub fn get<P>(&self, particle_system: &ParticleSystem<P>) -> &BindGroup<L>
+    where
+        P: abi::Sized + 'static,
+    {
+        self.bind_groups.get(&particle_system.state)
+    }
+}
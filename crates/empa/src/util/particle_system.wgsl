@@ -0,0 +1,73 @@
+struct ParticleSimulation {
+    time: f32,
+    delta_time: f32,
+    emit_count: u32,
+    capacity: u32,
+}
+
+// Matches the layout of the arguments of an indirect draw command, so that the number of live
+// particles can be used directly as the instance count.
+struct ParticleCount {
+    vertex_count: u32,
+    instance_count: atomic<u32>,
+    first_vertex: u32,
+    first_instance: u32,
+}
+
+@group(0) @binding(0)
+var<uniform> simulation: ParticleSimulation;
+
+@group(0) @binding(1)
+var<storage, read> particles_in: array<Particle>;
+
+@group(0) @binding(2)
+var<storage, read_write> count_in: ParticleCount;
+
+@group(0) @binding(3)
+var<storage, read_write> particles_out: array<Particle>;
+
+@group(0) @binding(4)
+var<storage, read_write> count_out: ParticleCount;
+
+@compute @workgroup_size(1)
+fn particles_reset() {
+    atomicStore(&count_out.instance_count, 0u);
+}
+
+@compute @workgroup_size(64)
+fn particles_update(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let index = global_id.x;
+
+    if index >= atomicLoad(&count_in.instance_count) {
+        return;
+    }
+
+    var particle = particles_in[index];
+
+    if update_particle(&particle) {
+        let slot = atomicAdd(&count_out.instance_count, 1u);
+
+        particles_out[slot] = particle;
+    }
+}
+
+@compute @workgroup_size(64)
+fn particles_emit(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let index = global_id.x;
+
+    if index >= simulation.emit_count {
+        return;
+    }
+
+    let slot = atomicAdd(&count_out.instance_count, 1u);
+
+    // Once the count reaches the capacity, every invocation that fails to claim a slot undoes its
+    // own increment, so the count settles at the capacity.
+    if slot >= simulation.capacity {
+        atomicSub(&count_out.instance_count, 1u);
+
+        return;
+    }
+
+    particles_out[slot] = emit_particle(index);
+}
@@ -0,0 +1,113 @@
+use crate::device::Device;
+use crate::resource_binding::{BindGroup, BindGroupLayout, Resources, TypedBindGroupLayout};
+
+/// Holds a pair of resources (e.g. buffers or textures) for iterative algorithms that read from
+/// one resource and write to the other on each step, then swap their roles.
+///
+/// The resource that is read from is the [front](PingPong::front), the resource that is written to
+/// is the [back](PingPong::back). Call [swap](PingPong::swap) after each step.
+///
+/// ```ignore
+/// let state = PingPong::new(
+///     device.create_buffer(initial.clone(), buffer::Usages::storage_binding()),
+///     device.create_buffer(initial, buffer::Usages::storage_binding()),
+/// );
+///
+/// let bind_groups = state.bind_groups(&device, &layout, |front, back| Resources {
+///     input: front.storage(),
+///     output: back.storage(),
+/// });
+///
+/// for _ in 0..steps {
+///     encoder = encoder
+///         .begin_compute_pass()
+///         .set_pipeline(&pipeline)
+///         .set_bind_groups(bind_groups.get(&state))
+///         .dispatch_workgroups(dispatch)
+///         .end();
+///
+///     state.swap();
+/// }
+/// ```
+pub struct PingPong<T> {
+    resources: [T; 2],
+    front: usize,
+}
+
+impl<T> PingPong<T> {
+    /// Creates a new pair with `front` as the initial front resource and `back` as the initial
+    /// back resource.
+    pub fn new(front: T, back: T) -> Self {
+        PingPong {
+            resources: [front, back],
+            front: 0,
+        }
+    }
+
+    /// The resource that is currently read from.
+    pub fn front(&self) -> &T {
+        &self.resources[self.front]
+    }
+
+    /// The resource that is currently written to.
+    pub fn back(&self) -> &T {
+        &self.resources[1 - self.front]
+    }
+
+    /// Swaps the roles of the front and back resources.
+    pub fn swap(&mut self) {
+        self.front = 1 - self.front;
+    }
+
+    /// Creates the bind groups for both orientations of the pair.
+    ///
+    /// The `resources` function is called once for each orientation with the front and back
+    /// resources of that orientation. Use [PingPongBindGroups::get] to select the bind group for
+    /// the pair's current orientation.
+    pub fn bind_groups<'a, L, R, F>(
+        &'a self,
+        device: &Device,
+        layout: &BindGroupLayout<L>,
+        mut resources: F,
+    ) -> PingPongBindGroups<L>
+    where
+        L: TypedBindGroupLayout,
+        R: Resources<Layout = L>,
+        F: FnMut(&'a T, &'a T) -> R,
+    {
+        let [a, b] = &self.resources;
+
+        // Index `0` holds the bind group for the orientation in which resource `0` is the front.
+        let bind_groups = [
+            device.create_bind_group(layout, resources(a, b)),
+            device.create_bind_group(layout, resources(b, a)),
+        ];
+
+        PingPongBindGroups { bind_groups }
+    }
+
+    /// Returns the pair as `(front, back)`.
+    pub fn into_inner(self) -> (T, T) {
+        let [a, b] = self.resources;
+
+        if self.front == 0 {
+            (a, b)
+        } else {
+            (b, a)
+        }
+    }
+}
+
+/// The pair of bind groups created by [PingPong::bind_groups].
+pub struct PingPongBindGroups<L> {
+    bind_groups: [BindGroup<L>; 2],
+}
+
+impl<L> PingPongBindGroups<L> {
+    /// Returns the bind group for the current orientation of the `ping_pong` pair.
+    ///
+    /// The `ping_pong` pair must be the pair from which these bind groups were created.
+    pub fn get<T>(&self, ping_pong: &PingPong<T>) -> &BindGroup<L> {
+        &self.bind_groups[ping_pong.front]
+    }
+}
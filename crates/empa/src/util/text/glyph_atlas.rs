@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+
+use crate::device::{Device, Queue};
+use crate::texture;
+use crate::texture::format::r8unorm;
+use crate::texture::{
+    ImageDataLayout, MipmapLevels, Texture2D, Texture2DDescriptor, TextureUsages,
+};
+use crate::util::{AtlasAllocator, AtlasRegion};
+
+/// A dynamic texture atlas for glyph coverage masks.
+///
+/// Glyphs are identified by a user-defined `u32` key (e.g. a `char` code point, or a glyph index
+/// combined with a font size).
+pub struct GlyphAtlas {
+    texture: Texture2D<r8unorm, TextureUsages!(TextureBinding | CopyDst)>,
+    allocator: AtlasAllocator,
+    glyphs: HashMap<u32, AtlasRegion>,
+}
+
+impl GlyphAtlas {
+    pub fn new(device: &Device, width: u32, height: u32) -> Self {
+        let texture = device.create_texture_2d(&Texture2DDescriptor {
+            format: r8unorm,
+            usage: texture::Usages::texture_binding().and_copy_dst(),
+            view_formats: (),
+            width,
+            height,
+            layers: 1,
+            mipmap_levels: MipmapLevels::Partial(1),
+        });
+
+        GlyphAtlas {
+            texture,
+            allocator: AtlasAllocator::new(width, height),
+            glyphs: HashMap::new(),
+        }
+    }
+
+    pub fn texture(&self) -> &Texture2D<r8unorm, TextureUsages!(TextureBinding | CopyDst)> {
+        &self.texture
+    }
+
+    pub fn width(&self) -> u32 {
+        self.allocator.width()
+    }
+
+    pub fn height(&self) -> u32 {
+        self.allocator.height()
+    }
+
+    pub fn get(&self, key: u32) -> Option<AtlasRegion> {
+        self.glyphs.get(&key).copied()
+    }
+
+    /// Adds a glyph with the given `coverage` mask (one byte per texel, tightly packed rows) to the
+    /// atlas and returns its region.
+    ///
+    /// If a glyph with the `key` already exists, returns its existing region without uploading
+    /// the `coverage` data. Returns `None` if the atlas has no space left for the glyph.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `coverage` contains fewer than `width * height` values.
+    pub fn insert(
+        &mut self,
+        queue: &Queue,
+        key: u32,
+        width: u32,
+        height: u32,
+        coverage: &[u8],
+    ) -> Option<AtlasRegion> {
+        if let Some(region) = self.glyphs.get(&key) {
+            return Some(*region);
+        }
+
+        assert!(
+            coverage.len() >= (width * height) as usize,
+            "coverage must contain at least `width * height` values"
+        );
+
+        // Allocate with a 1 texel gap on the right and bottom, to avoid bleeding between glyphs when
+        // sampling with linear filtering.
+        let allocation = self
+            .allocator
+            .allocate(width.checked_add(1)?, height.checked_add(1)?)?;
+        let region = AtlasRegion {
+            x: allocation.x,
+            y: allocation.y,
+            width,
+            height,
+        };
+
+        if width > 0 && height > 0 {
+            self.texture.write_region(
+                queue,
+                region.sub_image_copy_descriptor(0, 0),
+                region.size(),
+                coverage,
+                ImageDataLayout {
+                    blocks_per_row: width,
+                    rows_per_image: height,
+                },
+            );
+        }
+
+        self.glyphs.insert(key, region);
+
+        Some(region)
+    }
+
+    /// Removes all glyphs from the atlas.
+    ///
+    /// Does not clear the texture data; glyphs inserted afterwards overwrite the old data.
+    pub fn clear(&mut self) {
+        self.allocator.clear();
+        self.glyphs.clear();
+    }
+}
@@ -0,0 +1,11 @@
+//! Minimal building blocks for drawing text: a [GlyphAtlas] that stores rasterized glyph coverage
+//! in an `r8unorm` texture, and a [TextRenderer] that draws batches of glyph quads.
+//!
+//! Glyph rasterization is left to the user (e.g. with a font rasterization crate, or with a
+//! hand-made bitmap font).
+
+mod glyph_atlas;
+pub use self::glyph_atlas::*;
+
+mod text_renderer;
+pub use self::text_renderer::*;
@@ -0,0 +1,46 @@
+struct GlyphIn {
+    @location(0) rect: vec4<f32>,
+    @location(1) uv_rect: vec4<f32>,
+    @location(2) color: vec4<f32>
+}
+
+struct VertexOut {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+    @location(1) color: vec4<f32>
+}
+
+@group(0) @binding(0)
+var glyph_atlas: texture_2d<f32>;
+
+@group(0) @binding(1)
+var glyph_sampler: sampler;
+
+@vertex
+fn vert_main(@builtin(vertex_index) vertex_index: u32, glyph: GlyphIn) -> VertexOut {
+    var corners = array<vec2<f32>, 6>(
+        vec2(0.0, 0.0),
+        vec2(1.0, 0.0),
+        vec2(0.0, 1.0),
+        vec2(0.0, 1.0),
+        vec2(1.0, 0.0),
+        vec2(1.0, 1.0)
+    );
+
+    let corner = corners[vertex_index];
+
+    var result = VertexOut();
+
+    result.position = vec4(mix(glyph.rect.xy, glyph.rect.zw, corner), 0.0, 1.0);
+    result.uv = mix(glyph.uv_rect.xy, glyph.uv_rect.zw, corner);
+    result.color = glyph.color;
+
+    return result;
+}
+
+@fragment
+fn frag_main(@location(0) uv: vec2<f32>, @location(1) color: vec4<f32>) -> @location(0) vec4<f32> {
+    let coverage = textureSample(glyph_atlas, glyph_sampler, uv).r;
+
+    return vec4(color.rgb, color.a * coverage);
+}
@@ -0,0 +1,244 @@
+use crate::buffer::{Buffer, BufferUsages};
+use crate::command::{
+    CommandEncoder, Draw, DrawCommandEncoder, RenderPassDescriptor, RenderStateEncoder,
+    ResourceBindingCommandEncoder,
+};
+use crate::device::Device;
+use crate::render_pipeline::{
+    BlendComponent, BlendFactor, BlendState, BlendedColorOutput, ColorWrite, FragmentStageBuilder,
+    IndexAny, RenderPipeline, RenderPipelineDescriptorBuilder, VertexStageBuilder,
+};
+use crate::render_target::{FloatAttachment, LoadOp, RenderLayout, RenderTarget, StoreOp};
+use crate::resource_binding::BindGroup;
+use crate::sampler::{FilterMode, Sampler, SamplerDescriptor};
+use crate::shader_module::{shader_source, ShaderSource};
+use crate::texture::format::{Blendable, FloatRenderable};
+use crate::texture::{AttachableImage, Sampled2DFloat};
+use crate::util::text::GlyphAtlas;
+use crate::util::AtlasRegion;
+use crate::{buffer, render_pipeline, resource_binding};
+
+const SHADER: ShaderSource = shader_source!("text.wgsl");
+
+/// A single glyph instance, as consumed by the [TextRenderer] pipeline.
+#[derive(render_pipeline::Vertex, Clone, Copy, Default)]
+#[vertex_per_instance]
+pub struct GlyphQuad {
+    /// The quad's rectangle in normalized device coordinates as `[min_x, min_y, max_x, max_y]`.
+    #[vertex_attribute(location = 0, format = "float32x4")]
+    pub rect: [f32; 4],
+    /// The quad's glyph atlas UV rectangle as `[min_u, min_v, max_u, max_v]`.
+    #[vertex_attribute(location = 1, format = "float32x4")]
+    pub uv_rect: [f32; 4],
+    #[vertex_attribute(location = 2, format = "unorm8x4")]
+    pub color: [u8; 4],
+}
+
+#[derive(resource_binding::Resources)]
+struct Resources<'a> {
+    #[resource(binding = 0, visibility = "FRAGMENT")]
+    glyph_atlas: Sampled2DFloat<'a>,
+    #[resource(binding = 1, visibility = "FRAGMENT")]
+    glyph_sampler: &'a Sampler,
+}
+
+type ResourcesLayout = <Resources<'static> as resource_binding::Resources>::Layout;
+
+type InstanceBuffer = Buffer<[GlyphQuad], BufferUsages!(Vertex | CopyDst)>;
+
+const MIN_CAPACITY: usize = 64;
+
+/// Draws batches of glyph quads that sample coverage from a [GlyphAtlas].
+///
+/// Queue glyphs with [queue_glyph](TextRenderer::queue_glyph) or
+/// [queue_region](TextRenderer::queue_region), then record the draw with
+/// [encode](TextRenderer::encode). Glyphs are alpha-blended over the existing contents of the
+/// target image.
+pub struct TextRenderer<F>
+where
+    F: Blendable + FloatRenderable,
+{
+    device: Device,
+    pipeline: RenderPipeline<RenderLayout<F, ()>, GlyphQuad, IndexAny, (ResourcesLayout,)>,
+    bind_group: BindGroup<ResourcesLayout>,
+    instance_buffer: InstanceBuffer,
+    atlas_width: u32,
+    atlas_height: u32,
+    quads: Vec<([f32; 4], [f32; 4], [u8; 4])>,
+}
+
+impl<F> TextRenderer<F>
+where
+    F: Blendable + FloatRenderable,
+{
+    /// Creates a new renderer that samples from the given `atlas` and renders to images of the
+    /// given `format`.
+    pub async fn new(device: &Device, atlas: &GlyphAtlas, format: F) -> Self {
+        let shader = device.create_shader_module(&SHADER);
+
+        let bind_group_layout = device.create_bind_group_layout::<ResourcesLayout>();
+        let pipeline_layout = device.create_pipeline_layout(&bind_group_layout);
+
+        let pipeline = device
+            .create_render_pipeline(
+                &RenderPipelineDescriptorBuilder::begin()
+                    .layout(&pipeline_layout)
+                    .vertex(
+                        VertexStageBuilder::begin(&shader, "vert_main")
+                            .vertex_layout::<GlyphQuad>()
+                            .finish(),
+                    )
+                    .fragment(
+                        FragmentStageBuilder::begin(&shader, "frag_main")
+                            .color_outputs(BlendedColorOutput {
+                                format,
+                                blend_state: BlendState {
+                                    color: BlendComponent::Add {
+                                        src_factor: BlendFactor::SrcAlpha,
+                                        dst_factor: BlendFactor::OneMinusSrcAlpha,
+                                    },
+                                    alpha: BlendComponent::Add {
+                                        src_factor: BlendFactor::One,
+                                        dst_factor: BlendFactor::OneMinusSrcAlpha,
+                                    },
+                                },
+                                write_mask: ColorWrite::All,
+                            })
+                            .finish(),
+                    )
+                    .finish(),
+            )
+            .await
+            .expect("the text renderer pipeline should be valid");
+
+        let sampler = device.create_sampler(&SamplerDescriptor {
+            magnification_filter: FilterMode::Linear,
+            minification_filter: FilterMode::Linear,
+            ..Default::default()
+        });
+
+        let bind_group = device.create_bind_group(
+            &bind_group_layout,
+            Resources {
+                glyph_atlas: atlas.texture().sampled_float(&Default::default()),
+                glyph_sampler: &sampler,
+            },
+        );
+
+        let instance_buffer = create_instance_buffer(device, MIN_CAPACITY);
+
+        TextRenderer {
+            device: device.clone(),
+            pipeline,
+            bind_group,
+            instance_buffer,
+            atlas_width: atlas.width(),
+            atlas_height: atlas.height(),
+            quads: Vec::new(),
+        }
+    }
+
+    /// Queues a glyph quad.
+    ///
+    /// The `rect` is specified in pixels relative to the top-left corner of the target image as
+    /// `[min_x, min_y, max_x, max_y]`; the `uv_rect` is specified in normalized atlas coordinates
+    /// as `[min_u, min_v, max_u, max_v]` (see [AtlasRegion::uv_rect]).
+    pub fn queue_glyph(&mut self, rect: [f32; 4], uv_rect: [f32; 4], color: [u8; 4]) {
+        self.quads.push((rect, uv_rect, color));
+    }
+
+    /// Queues a glyph quad that displays the atlas `region` unscaled, with its top-left corner at
+    /// pixel position `x`, `y`.
+    pub fn queue_region(&mut self, x: f32, y: f32, region: AtlasRegion, color: [u8; 4]) {
+        let rect = [x, y, x + region.width as f32, y + region.height as f32];
+        let uv_rect = region.uv_rect(self.atlas_width, self.atlas_height);
+
+        self.queue_glyph(rect, uv_rect, color);
+    }
+
+    /// The number of glyph quads currently queued.
+    pub fn queued_len(&self) -> usize {
+        self.quads.len()
+    }
+
+    /// Discards all queued glyph quads.
+    pub fn clear(&mut self) {
+        self.quads.clear();
+    }
+
+    /// Records a render pass that draws all queued glyph quads to the `image` and clears the
+    /// queue.
+    ///
+    /// Note that the glyph instance data is uploaded with a queue write, which takes effect before
+    /// any command buffer submitted afterwards executes. Encoding for multiple images before a
+    /// single submit will therefore draw the last batch to all images.
+    pub fn encode(&mut self, encoder: CommandEncoder, image: AttachableImage<F>) -> CommandEncoder {
+        if self.quads.is_empty() {
+            return encoder;
+        }
+
+        let width = image.width as f32;
+        let height = image.height as f32;
+
+        let to_ndc_x = |x: f32| x / width * 2.0 - 1.0;
+        let to_ndc_y = |y: f32| 1.0 - y / height * 2.0;
+
+        let data: Vec<GlyphQuad> = self
+            .quads
+            .drain(..)
+            .map(|(rect, uv_rect, color)| {
+                let [min_x, min_y, max_x, max_y] = rect;
+
+                GlyphQuad {
+                    rect: [
+                        to_ndc_x(min_x),
+                        to_ndc_y(min_y),
+                        to_ndc_x(max_x),
+                        to_ndc_y(max_y),
+                    ],
+                    uv_rect,
+                    color,
+                }
+            })
+            .collect();
+
+        let len = data.len();
+
+        if self.instance_buffer.len() < len {
+            let capacity = len.next_power_of_two().max(MIN_CAPACITY);
+
+            self.instance_buffer = create_instance_buffer(&self.device, capacity);
+        }
+
+        let instances = self.instance_buffer.get(0..len).unwrap();
+
+        self.device.queue().write_buffer_slice(instances, &data);
+
+        encoder
+            .begin_render_pass(RenderPassDescriptor::new(&RenderTarget {
+                color: FloatAttachment {
+                    image,
+                    load_op: LoadOp::Load,
+                    store_op: StoreOp::Store,
+                },
+                depth_stencil: (),
+            }))
+            .set_pipeline(&self.pipeline)
+            .set_vertex_buffers(instances)
+            .set_bind_groups(&self.bind_group)
+            .draw(Draw {
+                vertex_count: 6,
+                instance_count: len as u32,
+                first_vertex: 0,
+                first_instance: 0,
+            })
+            .end()
+    }
+}
+
+fn create_instance_buffer(device: &Device, capacity: usize) -> InstanceBuffer {
+    device.create_buffer(
+        vec![GlyphQuad::default(); capacity],
+        buffer::Usages::vertex().and_copy_dst(),
+    )
+}
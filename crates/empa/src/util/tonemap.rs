@@ -0,0 +1,192 @@
+use crate::command::CommandEncoder;
+use crate::device::Device;
+use crate::render_pipeline::{ColorOutput, ColorWrite, FragmentStageBuilder};
+use crate::resource_binding::{BindGroup, BindGroupLayout};
+use crate::sampler::{FilterMode, Sampler, SamplerDescriptor};
+use crate::shader_module::{shader_source, ShaderSource};
+use crate::texture::format::{rgba16float, FloatRenderable};
+use crate::texture::{
+    AttachableImage, MipmapLevels, Sampled2DFloat, Texture2D, Texture2DDescriptor, TextureUsages,
+};
+use crate::util::fullscreen::{
+    create_fullscreen_pipeline, encode_fullscreen_pass, FullscreenPipeline,
+};
+use crate::{pipeline_constants, resource_binding, texture};
+
+const SHADER: ShaderSource = shader_source!("tonemap.wgsl");
+
+/// An offscreen `rgba16float` render target for high dynamic range rendering that can be resolved
+/// to a displayable image with a [Tonemapper].
+pub type HdrTarget = Texture2D<rgba16float, TextureUsages!(RenderAttachment | TextureBinding)>;
+
+/// Creates a new [HdrTarget] of the given `width` and `height`.
+pub fn create_hdr_target(device: &Device, width: u32, height: u32) -> HdrTarget {
+    device.create_texture_2d(&Texture2DDescriptor {
+        format: rgba16float,
+        usage: texture::Usages::render_attachment().and_texture_binding(),
+        view_formats: (),
+        width,
+        height,
+        layers: 1,
+        mipmap_levels: MipmapLevels::Partial(1),
+    })
+}
+
+/// The curve a [Tonemapper] uses to map high dynamic range color values into the `[0, 1]` range.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
+pub enum TonemapOperator {
+    /// The simple Reinhard operator, `color / (color + 1)`.
+    #[default]
+    Reinhard,
+    /// An approximation of the ACES filmic curve.
+    Aces,
+}
+
+#[derive(pipeline_constants::PipelineConstants)]
+struct Constants {
+    #[constant_id(0)]
+    operator: u32,
+    #[constant_id(1)]
+    exposure: f32,
+}
+
+#[derive(resource_binding::Resources)]
+struct Resources<'a> {
+    #[resource(binding = 0, visibility = "FRAGMENT")]
+    hdr_texture: Sampled2DFloat<'a>,
+    #[resource(binding = 1, visibility = "FRAGMENT")]
+    hdr_sampler: &'a Sampler,
+}
+
+type ResourcesLayout = <Resources<'static> as resource_binding::Resources>::Layout;
+
+/// Settings for a [Tonemapper].
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct TonemapperDescriptor<F> {
+    /// The format of the images the tonemapper renders to, typically the swapchain format.
+    pub format: F,
+
+    /// The tonemapping curve.
+    pub operator: TonemapOperator,
+
+    /// A factor by which HDR color values are scaled before the tonemapping curve is applied.
+    pub exposure: f32,
+}
+
+/// A bind group that makes an [HdrTarget] available to a [Tonemapper].
+///
+/// See [Tonemapper::source].
+pub struct TonemapSource {
+    bind_group: BindGroup<ResourcesLayout>,
+}
+
+/// A full-screen pass that samples an [HdrTarget] and writes the tonemapped result to a low
+/// dynamic range image (e.g. the current swapchain image).
+///
+/// The tonemapping operator and exposure are baked into the pipeline as pipeline constants;
+/// create a separate tonemapper for each configuration.
+///
+/// ```ignore
+/// let hdr_target = create_hdr_target(&device, width, height);
+/// let tonemapper = Tonemapper::new(&device, TonemapperDescriptor {
+///     format: bgra8unorm,
+///     operator: TonemapOperator::Aces,
+///     exposure: 1.0,
+/// })
+/// .await;
+/// let source = tonemapper.source(&hdr_target);
+///
+/// // First render the scene to the `hdr_target`, then resolve it to the swapchain image:
+/// encoder = encoder
+///     .begin_render_pass(...) // Renders to `hdr_target.attachable_image(...)`
+///     ...
+///     .end();
+///
+/// encoder = tonemapper.encode(encoder, &source, swapchain_image.attachable_image());
+/// ```
+pub struct Tonemapper<F>
+where
+    F: FloatRenderable,
+{
+    pipeline: FullscreenPipeline<F, ResourcesLayout>,
+    bind_group_layout: BindGroupLayout<ResourcesLayout>,
+    sampler: Sampler,
+    device: Device,
+}
+
+impl<F> Tonemapper<F>
+where
+    F: FloatRenderable,
+{
+    /// Creates a new tonemapper with the given `descriptor`.
+    pub async fn new(device: &Device, descriptor: TonemapperDescriptor<F>) -> Self {
+        let TonemapperDescriptor {
+            format,
+            operator,
+            exposure,
+        } = descriptor;
+
+        let shader = device.create_shader_module(&SHADER);
+
+        let bind_group_layout = device.create_bind_group_layout::<ResourcesLayout>();
+        let pipeline_layout = device.create_pipeline_layout(&bind_group_layout);
+
+        let operator = match operator {
+            TonemapOperator::Reinhard => 0,
+            TonemapOperator::Aces => 1,
+        };
+
+        let fragment = FragmentStageBuilder::begin(&shader, "frag_main")
+            .pipeline_constants(&Constants { operator, exposure })
+            .color_outputs(ColorOutput {
+                format,
+                write_mask: ColorWrite::All,
+            })
+            .finish();
+
+        let pipeline = create_fullscreen_pipeline(device, &shader, &pipeline_layout, fragment)
+            .await
+            .expect("the tonemap pipeline should be valid");
+
+        let sampler = device.create_sampler(&SamplerDescriptor {
+            magnification_filter: FilterMode::Linear,
+            minification_filter: FilterMode::Linear,
+            ..Default::default()
+        });
+
+        Tonemapper {
+            pipeline,
+            bind_group_layout,
+            sampler,
+            device: device.clone(),
+        }
+    }
+
+    /// Creates a [TonemapSource] for the given `hdr_target`.
+    ///
+    /// The source may be reused for as long as the `hdr_target` remains the same (e.g. until the
+    /// target is recreated because the window was resized).
+    pub fn source(&self, hdr_target: &HdrTarget) -> TonemapSource {
+        let bind_group = self.device.create_bind_group(
+            &self.bind_group_layout,
+            Resources {
+                hdr_texture: hdr_target.sampled_float(&Default::default()),
+                hdr_sampler: &self.sampler,
+            },
+        );
+
+        TonemapSource { bind_group }
+    }
+
+    /// Records a render pass that writes the tonemapped contents of the `source` to the `image`.
+    ///
+    /// The `source` is stretched to cover the entire `image`.
+    pub fn encode(
+        &self,
+        encoder: CommandEncoder,
+        source: &TonemapSource,
+        image: AttachableImage<F>,
+    ) -> CommandEncoder {
+        encode_fullscreen_pass(encoder, &self.pipeline, &source.bind_group, image)
+    }
+}
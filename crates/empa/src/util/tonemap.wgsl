@@ -0,0 +1,42 @@
+#include "./fullscreen.wgsl"
+
+@id(0) override tonemap_operator: u32 = 0u;
+
+@id(1) override exposure: f32 = 1.0;
+
+@group(0) @binding(0)
+var hdr_texture: texture_2d<f32>;
+
+@group(0) @binding(1)
+var hdr_sampler: sampler;
+
+fn reinhard(color: vec3<f32>) -> vec3<f32> {
+    return color / (color + vec3(1.0));
+}
+
+// Krzysztof Narkowicz's fit of the ACES filmic curve.
+fn aces(color: vec3<f32>) -> vec3<f32> {
+    let a = 2.51;
+    let b = 0.03;
+    let c = 2.43;
+    let d = 0.59;
+    let e = 0.14;
+
+    return clamp((color * (a * color + b)) / (color * (c * color + d) + e), vec3(0.0), vec3(1.0));
+}
+
+@fragment
+fn frag_main(@location(0) uv: vec2<f32>) -> @location(0) vec4<f32> {
+    let hdr = textureSample(hdr_texture, hdr_sampler, uv);
+    let color = hdr.rgb * exposure;
+
+    var mapped: vec3<f32>;
+
+    if tonemap_operator == 1u {
+        mapped = aces(color);
+    } else {
+        mapped = reinhard(color);
+    }
+
+    return vec4(mapped, hdr.a);
+}
@@ -0,0 +1,156 @@
+use crate::abi;
+use crate::buffer;
+use crate::buffer::{Buffer, BufferUsages, Uniform};
+use crate::device::{Device, Queue};
+use crate::frame_pacing::FrameLatency;
+use crate::resource_binding::{BindGroup, BindGroupLayout, Resources, TypedBindGroupLayout};
+
+/// The usages of the buffers in a [UniformRing].
+pub type UniformRingUsages = BufferUsages!(UniformBinding | CopyDst);
+
+/// Holds a copy of a uniform value for each frame that may be in flight, so that the data for the
+/// next frame can be written while the GPU may still be reading the data of earlier frames.
+///
+/// Write the frame's data with [write](UniformRing::write), bind the
+/// [current](UniformRing::current) buffer (or one of the bind groups created with
+/// [bind_groups](UniformRing::bind_groups)), and call [advance](UniformRing::advance) once the
+/// frame has been submitted:
+///
+/// ```ignore
+/// let mut uniforms = UniformRing::new(&device, frame_pacer.frame_latency(), Uniforms::default());
+///
+/// let bind_groups = uniforms.bind_groups(&device, &layout, |buffer| Resources {
+///     uniforms: buffer.uniform(),
+/// });
+///
+/// loop {
+///     frame_pacer.wait().await;
+///
+///     uniforms.write(&queue, &frame_uniforms);
+///
+///     let encoder = device
+///         .create_command_encoder()
+///         .begin_render_pass(...)
+///         .set_pipeline(&pipeline)
+///         .set_bind_groups(bind_groups.get(&uniforms))
+///         ...
+///         .end();
+///
+///     queue.submit(encoder.finish());
+///     frame_pacer.frame_submitted();
+///
+///     uniforms.advance();
+/// }
+/// ```
+///
+/// # Synchronization
+///
+/// On all backends, [Queue::write_buffer] is ordered with the other operations on the queue: a
+/// write never affects work that was submitted before it, so using a single buffer for all frames
+/// is never unsound. It may however stall: with the native backend, a write to a buffer that is
+/// still being read by an earlier submission must wait for that submission to finish reading
+/// before the staged data can be copied; in the browser, the WebGPU implementation must make the
+/// same guarantee and may serialize the copy in the same way. Rotating through a buffer per frame
+/// in flight removes this dependency, provided the ring holds at least as many buffers as there
+/// may be frames in flight (e.g. when the frames are paced with a
+/// [FramePacer](crate::frame_pacing::FramePacer) with the same [FrameLatency]).
+///
+/// The ring does not track the completion of submitted work itself; if more frames than the ring's
+/// [size](UniformRing::size) are in flight, writes are still correct, but may stall as described
+/// above.
+pub struct UniformRing<T> {
+    buffers: Vec<Buffer<T, UniformRingUsages>>,
+    current: usize,
+}
+
+impl<T> UniformRing<T>
+where
+    T: abi::Sized + Copy + 'static,
+{
+    /// Creates a new ring with a buffer for each frame that may be in flight with the given
+    /// `frame_latency`, each initialized to `initial`.
+    pub fn new(device: &Device, frame_latency: FrameLatency, initial: T) -> Self {
+        let buffers = (0..frame_latency.get())
+            .map(|_| {
+                device.create_buffer(initial, buffer::Usages::uniform_binding().and_copy_dst())
+            })
+            .collect();
+
+        UniformRing {
+            buffers,
+            current: 0,
+        }
+    }
+
+    /// The number of buffers in the ring.
+    pub fn size(&self) -> usize {
+        self.buffers.len()
+    }
+
+    /// The index of the [current](UniformRing::current) buffer in the ring.
+    pub fn index(&self) -> usize {
+        self.current
+    }
+
+    /// The buffer for the current frame.
+    pub fn current(&self) -> &Buffer<T, UniformRingUsages> {
+        &self.buffers[self.current]
+    }
+
+    /// The buffer for the current frame as a uniform resource.
+    pub fn uniform(&self) -> Uniform<T> {
+        self.current().uniform()
+    }
+
+    /// Writes the `data` for the current frame to the [current](UniformRing::current) buffer.
+    pub fn write(&self, queue: &Queue, data: &T) {
+        queue.write_buffer(self.current().view(), data);
+    }
+
+    /// Rotates the ring to the next buffer; call this once per frame after the frame's work was
+    /// submitted.
+    pub fn advance(&mut self) {
+        self.current = (self.current + 1) % self.buffers.len();
+    }
+
+    /// Creates a bind group for each buffer in the ring.
+    ///
+    /// The `resources` function is called once for each buffer. Use [UniformRingBindGroups::get]
+    /// to select the bind group for the ring's current buffer; rebinding a different bind group
+    /// each frame is cheap compared to recreating it. Bind group creation does not yet support
+    /// dynamic offsets; once it does, the ring may instead use a single bind group.
+    pub fn bind_groups<'a, L, R, F>(
+        &'a self,
+        device: &Device,
+        layout: &BindGroupLayout<L>,
+        resources: F,
+    ) -> UniformRingBindGroups<L>
+    where
+        L: TypedBindGroupLayout,
+        R: Resources<Layout = L>,
+        F: FnMut(&'a Buffer<T, UniformRingUsages>) -> R,
+    {
+        let bind_groups = self
+            .buffers
+            .iter()
+            .map(resources)
+            .map(|resources| device.create_bind_group(layout, resources))
+            .collect();
+
+        UniformRingBindGroups { bind_groups }
+    }
+}
+
+/// The bind groups created by [UniformRing::bind_groups].
+pub struct UniformRingBindGroups<L> {
+    bind_groups: Vec<BindGroup<L>>,
+}
+
+impl<L> UniformRingBindGroups<L> {
+    /// Returns the bind group for the current buffer of the `ring`.
+    ///
+    /// The `ring` must be the ring from which these bind groups were created.
+    pub fn get<T>(&self, ring: &UniformRing<T>) -> &BindGroup<L> {
+        &self.bind_groups[ring.current]
+    }
+}
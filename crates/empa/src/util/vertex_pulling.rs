@@ -0,0 +1,402 @@
+use std::fmt::Write;
+
+use crate::render_pipeline::{Vertex, VertexAttribute, VertexFormat};
+
+/// Names and bindings used by the WGSL generated by [vertex_pulling_source].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct VertexPullingDescriptor<'a> {
+    /// The name of the generated struct that holds the attributes of a single vertex.
+    pub struct_name: &'a str,
+
+    /// The name of the generated fetch function.
+    pub fetch_fn_name: &'a str,
+
+    /// The name of the generated storage buffer variable.
+    pub storage_name: &'a str,
+
+    /// The bind group index of the storage buffer variable.
+    pub group: u32,
+
+    /// The binding index of the storage buffer variable.
+    pub binding: u32,
+}
+
+/// Generates a WGSL snippet for fetching vertices of type `V` from a storage buffer.
+///
+/// The snippet declares:
+///
+/// - a struct with one member for each of `V`'s vertex attributes, named `location_{n}` after
+///   the attribute's shader location, with the type the attribute would have as a vertex shader
+///   input;
+/// - a read-only `array<u32>` storage buffer variable at the given group and binding;
+/// - a fetch function that takes a vertex index and returns the attributes of that vertex.
+///
+/// Bind the buffer that holds the vertex data with
+/// [Buffer::vertex_pulling_storage](crate::buffer::Buffer::vertex_pulling_storage), so that the
+/// same `#[derive(Vertex)]` type can be used with both classic vertex buffers and vertex pulling:
+///
+/// ```ignore
+/// let snippet = vertex_pulling_source::<MyVertex>(&VertexPullingDescriptor {
+///     struct_name: "MyVertex",
+///     fetch_fn_name: "fetch_vertex",
+///     storage_name: "vertices",
+///     group: 0,
+///     binding: 0,
+/// });
+///
+/// let shader = device.create_shader_module(&ShaderSource::parse(snippet + MAIN_SOURCE)?);
+///
+/// #[derive(empa::resource_binding::Resources)]
+/// struct Resources<'a> {
+///     #[resource(binding = 0, visibility = "VERTEX")]
+///     vertices: Storage<'a, [u32]>,
+/// }
+///
+/// let resources = Resources {
+///     vertices: vertex_buffer.vertex_pulling_storage(),
+/// };
+/// ```
+///
+/// # Panics
+///
+/// Panics if the vertex stride of `V` is not a multiple of `4`.
+pub fn vertex_pulling_source<V>(descriptor: &VertexPullingDescriptor) -> String
+where
+    V: Vertex,
+{
+    let VertexPullingDescriptor {
+        struct_name,
+        fetch_fn_name,
+        storage_name,
+        group,
+        binding,
+    } = *descriptor;
+
+    let layout = V::LAYOUT;
+
+    if layout.array_stride % 4 != 0 {
+        panic!(
+            "vertex stride `{}` must be a multiple of `4` for vertex pulling",
+            layout.array_stride
+        );
+    }
+
+    let stride_in_words = layout.array_stride / 4;
+
+    let mut source = String::new();
+
+    writeln!(source, "struct {} {{", struct_name).unwrap();
+
+    for attribute in layout.attributes.iter() {
+        writeln!(
+            source,
+            "    location_{}: {},",
+            attribute.shader_location,
+            wgsl_type(attribute.format)
+        )
+        .unwrap();
+    }
+
+    writeln!(source, "}}\n").unwrap();
+
+    writeln!(
+        source,
+        "@group({}) @binding({})\nvar<storage, read> {}: array<u32>;\n",
+        group, binding, storage_name
+    )
+    .unwrap();
+
+    writeln!(
+        source,
+        "fn {}(index: u32) -> {} {{",
+        fetch_fn_name, struct_name
+    )
+    .unwrap();
+    writeln!(source, "    let base = index * {}u;\n", stride_in_words).unwrap();
+    writeln!(source, "    var result: {};\n", struct_name).unwrap();
+
+    for attribute in layout.attributes.iter() {
+        writeln!(
+            source,
+            "    result.location_{} = {};",
+            attribute.shader_location,
+            fetch_expression(attribute, storage_name)
+        )
+        .unwrap();
+    }
+
+    writeln!(source, "\n    return result;\n}}").unwrap();
+
+    source
+}
+
+fn wgsl_type(format: VertexFormat) -> &'static str {
+    match format {
+        VertexFormat::uint8x2 | VertexFormat::uint16x2 | VertexFormat::uint32x2 => "vec2<u32>",
+        VertexFormat::uint8x4 | VertexFormat::uint16x4 | VertexFormat::uint32x4 => "vec4<u32>",
+        VertexFormat::sint8x2 | VertexFormat::sint16x2 | VertexFormat::sint32x2 => "vec2<i32>",
+        VertexFormat::sint8x4 | VertexFormat::sint16x4 | VertexFormat::sint32x4 => "vec4<i32>",
+        VertexFormat::unorm8x2
+        | VertexFormat::snorm8x2
+        | VertexFormat::unorm16x2
+        | VertexFormat::snorm16x2
+        | VertexFormat::float16x2
+        | VertexFormat::float32x2 => "vec2<f32>",
+        VertexFormat::unorm8x4
+        | VertexFormat::snorm8x4
+        | VertexFormat::unorm16x4
+        | VertexFormat::snorm16x4
+        | VertexFormat::float16x4
+        | VertexFormat::float32x4 => "vec4<f32>",
+        VertexFormat::float32 => "f32",
+        VertexFormat::float32x3 => "vec3<f32>",
+        VertexFormat::uint32 => "u32",
+        VertexFormat::uint32x3 => "vec3<u32>",
+        VertexFormat::sint32 => "i32",
+        VertexFormat::sint32x3 => "vec3<i32>",
+    }
+}
+
+fn fetch_expression(attribute: &VertexAttribute, storage_name: &str) -> String {
+    let first_word = attribute.offset / 4;
+
+    let word = |i: usize| format!("{}[base + {}u]", storage_name, first_word + i);
+
+    // Two-byte formats only need to be 2-byte aligned, so they may start halfway into a word.
+    let half_word = || {
+        format!(
+            "extractBits({}, {}u, 16u)",
+            word(0),
+            (attribute.offset % 4) * 8
+        )
+    };
+
+    let components_2x8 = |value: String, ty: &str| {
+        format!(
+            "vec2<{ty}>(extractBits({v}, 0u, 8u), extractBits({v}, 8u, 8u))",
+            ty = ty,
+            v = value
+        )
+    };
+
+    let components_4x8 = |value: String, ty: &str| {
+        format!(
+            "vec4<{ty}>(extractBits({v}, 0u, 8u), extractBits({v}, 8u, 8u), \
+            extractBits({v}, 16u, 8u), extractBits({v}, 24u, 8u))",
+            ty = ty,
+            v = value
+        )
+    };
+
+    let components_2x16 = |value: String, ty: &str| {
+        format!(
+            "vec2<{ty}>(extractBits({v}, 0u, 16u), extractBits({v}, 16u, 16u))",
+            ty = ty,
+            v = value
+        )
+    };
+
+    let words = |count: usize| (0..count).map(&word).collect::<Vec<_>>().join(", ");
+
+    match attribute.format {
+        VertexFormat::uint8x2 => components_2x8(half_word(), "u32"),
+        VertexFormat::uint8x4 => components_4x8(word(0), "u32"),
+        VertexFormat::sint8x2 => components_2x8(format!("bitcast<i32>({})", half_word()), "i32"),
+        VertexFormat::sint8x4 => components_4x8(format!("bitcast<i32>({})", word(0)), "i32"),
+        VertexFormat::unorm8x2 => format!("unpack4x8unorm({}).xy", half_word()),
+        VertexFormat::unorm8x4 => format!("unpack4x8unorm({})", word(0)),
+        VertexFormat::snorm8x2 => format!("unpack4x8snorm({}).xy", half_word()),
+        VertexFormat::snorm8x4 => format!("unpack4x8snorm({})", word(0)),
+        VertexFormat::uint16x2 => components_2x16(word(0), "u32"),
+        VertexFormat::uint16x4 => format!(
+            "vec4<u32>({}, {})",
+            components_2x16(word(0), "u32"),
+            components_2x16(word(1), "u32")
+        ),
+        VertexFormat::sint16x2 => components_2x16(format!("bitcast<i32>({})", word(0)), "i32"),
+        VertexFormat::sint16x4 => format!(
+            "vec4<i32>({}, {})",
+            components_2x16(format!("bitcast<i32>({})", word(0)), "i32"),
+            components_2x16(format!("bitcast<i32>({})", word(1)), "i32")
+        ),
+        VertexFormat::unorm16x2 => format!("unpack2x16unorm({})", word(0)),
+        VertexFormat::unorm16x4 => format!(
+            "vec4<f32>(unpack2x16unorm({}), unpack2x16unorm({}))",
+            word(0),
+            word(1)
+        ),
+        VertexFormat::snorm16x2 => format!("unpack2x16snorm({})", word(0)),
+        VertexFormat::snorm16x4 => format!(
+            "vec4<f32>(unpack2x16snorm({}), unpack2x16snorm({}))",
+            word(0),
+            word(1)
+        ),
+        VertexFormat::float16x2 => format!("unpack2x16float({})", word(0)),
+        VertexFormat::float16x4 => format!(
+            "vec4<f32>(unpack2x16float({}), unpack2x16float({}))",
+            word(0),
+            word(1)
+        ),
+        VertexFormat::float32 => format!("bitcast<f32>({})", word(0)),
+        VertexFormat::float32x2 => format!("bitcast<vec2<f32>>(vec2<u32>({}))", words(2)),
+        VertexFormat::float32x3 => format!("bitcast<vec3<f32>>(vec3<u32>({}))", words(3)),
+        VertexFormat::float32x4 => format!("bitcast<vec4<f32>>(vec4<u32>({}))", words(4)),
+        VertexFormat::uint32 => word(0),
+        VertexFormat::uint32x2 => format!("vec2<u32>({})", words(2)),
+        VertexFormat::uint32x3 => format!("vec3<u32>({})", words(3)),
+        VertexFormat::uint32x4 => format!("vec4<u32>({})", words(4)),
+        VertexFormat::sint32 => format!("bitcast<i32>({})", word(0)),
+        VertexFormat::sint32x2 => format!("bitcast<vec2<i32>>(vec2<u32>({}))", words(2)),
+        VertexFormat::sint32x3 => format!("bitcast<vec3<i32>>(vec3<u32>({}))", words(3)),
+        VertexFormat::sint32x4 => format!("bitcast<vec4<i32>>(vec4<u32>({}))", words(4)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // The vertex types below only exist for their layouts; their fields are never read.
+    #![allow(dead_code)]
+
+    use naga::valid::{Capabilities, ValidationFlags, Validator};
+
+    use super::*;
+    use crate::render_pipeline;
+
+    #[derive(render_pipeline::Vertex, Clone, Copy)]
+    #[repr(C)]
+    struct MeshVertex {
+        #[vertex_attribute(location = 0, format = "float32x3")]
+        position: [f32; 3],
+        #[vertex_attribute(location = 1, format = "float32x3")]
+        normal: [f32; 3],
+        #[vertex_attribute(location = 2, format = "float32x2")]
+        uv: [f32; 2],
+        #[vertex_attribute(location = 3, format = "unorm8x4")]
+        color: [u8; 4],
+    }
+
+    #[derive(render_pipeline::Vertex, Clone, Copy)]
+    #[repr(C)]
+    struct Packed8Vertex {
+        #[vertex_attribute(location = 0, format = "uint8x2")]
+        a: [u8; 2],
+        #[vertex_attribute(location = 1, format = "sint8x2")]
+        b: [i8; 2],
+        #[vertex_attribute(location = 2, format = "unorm8x2")]
+        c: [u8; 2],
+        #[vertex_attribute(location = 3, format = "snorm8x2")]
+        d: [i8; 2],
+        #[vertex_attribute(location = 4, format = "uint8x4")]
+        e: [u8; 4],
+        #[vertex_attribute(location = 5, format = "sint8x4")]
+        f: [i8; 4],
+        #[vertex_attribute(location = 6, format = "snorm8x4")]
+        g: [i8; 4],
+    }
+
+    #[derive(render_pipeline::Vertex, Clone, Copy)]
+    #[repr(C)]
+    struct Packed16Vertex {
+        #[vertex_attribute(location = 0, format = "uint16x2")]
+        a: [u16; 2],
+        #[vertex_attribute(location = 1, format = "sint16x2")]
+        b: [i16; 2],
+        #[vertex_attribute(location = 2, format = "unorm16x2")]
+        c: [u16; 2],
+        #[vertex_attribute(location = 3, format = "snorm16x2")]
+        d: [i16; 2],
+        #[vertex_attribute(location = 4, format = "uint16x4")]
+        e: [u16; 4],
+        #[vertex_attribute(location = 5, format = "sint16x4")]
+        f: [i16; 4],
+        #[vertex_attribute(location = 6, format = "unorm16x4")]
+        g: [u16; 4],
+        #[vertex_attribute(location = 7, format = "snorm16x4")]
+        h: [i16; 4],
+    }
+
+    #[derive(render_pipeline::Vertex, Clone, Copy)]
+    #[repr(C)]
+    struct Word32Vertex {
+        #[vertex_attribute(location = 0, format = "uint32")]
+        a: u32,
+        #[vertex_attribute(location = 1, format = "uint32x2")]
+        b: [u32; 2],
+        #[vertex_attribute(location = 2, format = "uint32x3")]
+        c: [u32; 3],
+        #[vertex_attribute(location = 3, format = "uint32x4")]
+        d: [u32; 4],
+        #[vertex_attribute(location = 4, format = "sint32")]
+        e: i32,
+        #[vertex_attribute(location = 5, format = "sint32x2")]
+        f: [i32; 2],
+        #[vertex_attribute(location = 6, format = "sint32x3")]
+        g: [i32; 3],
+        #[vertex_attribute(location = 7, format = "sint32x4")]
+        h: [i32; 4],
+        #[vertex_attribute(location = 8, format = "float32")]
+        i: f32,
+        #[vertex_attribute(location = 9, format = "float32x4")]
+        j: [f32; 4],
+    }
+
+    #[derive(render_pipeline::Vertex, Clone, Copy)]
+    #[repr(C)]
+    struct UnalignedVertex {
+        #[vertex_attribute(location = 0, format = "uint8x2")]
+        a: [u8; 2],
+    }
+
+    const DESCRIPTOR: VertexPullingDescriptor = VertexPullingDescriptor {
+        struct_name: "PulledVertex",
+        fetch_fn_name: "fetch_vertex",
+        storage_name: "vertices",
+        group: 1,
+        binding: 2,
+    };
+
+    fn validate(source: &str) {
+        let module = naga::front::wgsl::parse_str(source)
+            .unwrap_or_else(|err| panic!("{}\n{}", err.emit_to_string(source), source));
+
+        Validator::new(ValidationFlags::all(), Capabilities::empty())
+            .validate(&module)
+            .unwrap_or_else(|err| panic!("{:?}\n{}", err, source));
+    }
+
+    #[test]
+    fn mesh_vertex_source_is_valid() {
+        let source = vertex_pulling_source::<MeshVertex>(&DESCRIPTOR);
+
+        validate(&source);
+
+        assert!(source.contains("@group(1) @binding(2)\nvar<storage, read> vertices: array<u32>;"));
+        assert!(source.contains("let base = index * 9u;"));
+    }
+
+    #[test]
+    fn packed_8_bit_vertex_source_is_valid() {
+        let source = vertex_pulling_source::<Packed8Vertex>(&DESCRIPTOR);
+
+        validate(&source);
+
+        // The second 2-byte attribute starts halfway into the first word.
+        assert!(source.contains("extractBits(vertices[base + 0u], 16u, 16u)"));
+    }
+
+    #[test]
+    fn packed_16_bit_vertex_source_is_valid() {
+        validate(&vertex_pulling_source::<Packed16Vertex>(&DESCRIPTOR));
+    }
+
+    #[test]
+    fn word_32_bit_vertex_source_is_valid() {
+        validate(&vertex_pulling_source::<Word32Vertex>(&DESCRIPTOR));
+    }
+
+    #[test]
+    #[should_panic(expected = "must be a multiple of `4`")]
+    fn unaligned_stride() {
+        vertex_pulling_source::<UnalignedVertex>(&DESCRIPTOR);
+    }
+}
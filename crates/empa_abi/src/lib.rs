@@ -0,0 +1,855 @@
+//! Rust types that match the memory layout of WGSL types, and descriptions of these memory
+//! layouts.
+//!
+//! This crate does not depend on `std` or on the rest of the GPU stack, so that tooling (e.g. an
+//! asset baker that precomputes buffer layouts) can share these types with the `empa` crate, which
+//! re-exports them from its `abi` module.
+#![no_std]
+
+extern crate alloc;
+
+use core::error::Error;
+use core::{cmp, fmt, mem, ops};
+
+pub mod reflect;
+
+pub unsafe trait Sized {
+    const LAYOUT: &'static [MemoryUnit];
+
+    #[doc(hidden)]
+    const IS_STRUCT: bool = false;
+
+    #[doc(hidden)]
+    const IS_UNIFORM_ARRAY: bool = false;
+}
+
+pub unsafe trait Unsized {
+    const SIZED_HEAD_LAYOUT: &'static [MemoryUnit];
+
+    const UNSIZED_TAIL_LAYOUT: Option<&'static [MemoryUnit]>;
+}
+
+unsafe impl<T> Unsized for T
+where
+    T: Sized,
+{
+    const SIZED_HEAD_LAYOUT: &'static [MemoryUnit] = T::LAYOUT;
+    const UNSIZED_TAIL_LAYOUT: Option<&'static [MemoryUnit]> = None;
+}
+
+unsafe impl<T> Unsized for [T]
+where
+    T: Sized,
+{
+    const SIZED_HEAD_LAYOUT: &'static [MemoryUnit] = &[];
+    const UNSIZED_TAIL_LAYOUT: Option<&'static [MemoryUnit]> = Some(T::LAYOUT);
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct MemoryUnit {
+    pub offset: usize,
+    pub layout: MemoryUnitLayout,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MemoryUnitLayout {
+    Float,
+    FloatVector2,
+    FloatVector3,
+    FloatVector4,
+    Integer,
+    IntegerVector2,
+    IntegerVector3,
+    IntegerVector4,
+    UnsignedInteger,
+    UnsignedIntegerVector2,
+    UnsignedIntegerVector3,
+    UnsignedIntegerVector4,
+    Matrix2x2,
+    Matrix2x3,
+    Matrix2x4,
+    Matrix3x2,
+    Matrix3x3,
+    Matrix3x4,
+    Matrix4x2,
+    Matrix4x3,
+    Matrix4x4,
+    Array {
+        units: &'static [MemoryUnit],
+        stride: usize,
+        len: usize,
+    },
+}
+
+impl MemoryUnitLayout {
+    /// The size in bytes of a value with this layout according to the WGSL memory layout rules.
+    ///
+    /// Note that this may be smaller than the size of the corresponding Rust type: e.g. a
+    /// `vec3<f32>` occupies `12` bytes in WGSL, but [Vec3] is padded to `16` bytes.
+    pub const fn wgsl_size(&self) -> usize {
+        match self {
+            MemoryUnitLayout::Float
+            | MemoryUnitLayout::Integer
+            | MemoryUnitLayout::UnsignedInteger => 4,
+            MemoryUnitLayout::FloatVector2
+            | MemoryUnitLayout::IntegerVector2
+            | MemoryUnitLayout::UnsignedIntegerVector2 => 8,
+            MemoryUnitLayout::FloatVector3
+            | MemoryUnitLayout::IntegerVector3
+            | MemoryUnitLayout::UnsignedIntegerVector3 => 12,
+            MemoryUnitLayout::FloatVector4
+            | MemoryUnitLayout::IntegerVector4
+            | MemoryUnitLayout::UnsignedIntegerVector4 => 16,
+            MemoryUnitLayout::Matrix2x2 => 16,
+            MemoryUnitLayout::Matrix2x3 => 32,
+            MemoryUnitLayout::Matrix2x4 => 32,
+            MemoryUnitLayout::Matrix3x2 => 24,
+            MemoryUnitLayout::Matrix3x3 => 48,
+            MemoryUnitLayout::Matrix3x4 => 48,
+            MemoryUnitLayout::Matrix4x2 => 32,
+            MemoryUnitLayout::Matrix4x3 => 64,
+            MemoryUnitLayout::Matrix4x4 => 64,
+            MemoryUnitLayout::Array { stride, len, .. } => *stride * *len,
+        }
+    }
+
+    /// The alignment in bytes of a value with this layout according to the WGSL memory layout
+    /// rules.
+    pub const fn wgsl_align(&self) -> usize {
+        match self {
+            MemoryUnitLayout::Float
+            | MemoryUnitLayout::Integer
+            | MemoryUnitLayout::UnsignedInteger => 4,
+            MemoryUnitLayout::FloatVector2
+            | MemoryUnitLayout::IntegerVector2
+            | MemoryUnitLayout::UnsignedIntegerVector2
+            | MemoryUnitLayout::Matrix2x2
+            | MemoryUnitLayout::Matrix3x2
+            | MemoryUnitLayout::Matrix4x2 => 8,
+            MemoryUnitLayout::Array { units, .. } => wgsl_align(units),
+            _ => 16,
+        }
+    }
+}
+
+/// The alignment in bytes of a type with the given memory `units` according to the WGSL memory
+/// layout rules (the largest alignment of any of the units).
+pub const fn wgsl_align(units: &[MemoryUnit]) -> usize {
+    let mut align = 1;
+    let mut i = 0;
+
+    while i < units.len() {
+        let unit_align = units[i].layout.wgsl_align();
+
+        if unit_align > align {
+            align = unit_align;
+        }
+
+        i += 1;
+    }
+
+    align
+}
+
+/// The alignment in bytes of `T` when used as a struct member according to the WGSL memory layout
+/// rules.
+///
+/// This is the [alignment](wgsl_align) of the type's memory units, except for an [ArrayOf], which
+/// follows the rules of the uniform address space and is aligned to at least `16` bytes.
+///
+/// A struct type is aligned to the alignment of its members, as in the storage address space. The
+/// uniform address space additionally aligns struct members to `16` bytes; WGSL requires this
+/// alignment to be explicit (with `@align(16)` or padding members), so the Rust type needs the same
+/// explicit padding.
+#[doc(hidden)]
+pub const fn wgsl_align_of<T>() -> usize
+where
+    T: Sized,
+{
+    let align = wgsl_align(T::LAYOUT);
+
+    if T::IS_UNIFORM_ARRAY {
+        round_up(16, align)
+    } else {
+        align
+    }
+}
+
+/// The size in bytes of `T` according to the WGSL memory layout rules.
+///
+/// For a struct type this is the end of its last member rounded up to the struct's
+/// [alignment](wgsl_align); for other types this is the size of the type itself, e.g. `12` for a
+/// [Vec3] (where the Rust type is padded to `16` bytes).
+pub const fn wgsl_size_of<T>() -> usize
+where
+    T: Sized,
+{
+    let units = T::LAYOUT;
+    let mut end = 0;
+    let mut i = 0;
+
+    while i < units.len() {
+        let unit_end = units[i].offset + units[i].layout.wgsl_size();
+
+        if unit_end > end {
+            end = unit_end;
+        }
+
+        i += 1;
+    }
+
+    if T::IS_STRUCT {
+        round_up(wgsl_align(units), end)
+    } else {
+        end
+    }
+}
+
+#[doc(hidden)]
+pub const fn round_up(align: usize, value: usize) -> usize {
+    value.div_ceil(align) * align
+}
+
+unsafe impl<T, const N: usize> Sized for [T; N]
+where
+    T: Sized,
+{
+    const LAYOUT: &'static [MemoryUnit] = &[MemoryUnit {
+        offset: 0,
+        layout: MemoryUnitLayout::Array {
+            units: T::LAYOUT,
+            stride: mem::size_of::<T>(),
+            len: N,
+        },
+    }];
+}
+
+/// A fixed-size array of `N` elements of type `T` in which each element is padded to a multiple of
+/// 16 bytes, corresponding to a WGSL `array<T, N>` in the uniform address space.
+///
+/// WGSL requires the stride of an array in the uniform address space to be a multiple of 16 bytes,
+/// whereas the stride of a Rust `[T; N]` is the size of `T`. For example, the elements of a WGSL
+/// `array<f32, 4>` in a uniform buffer are 16 bytes apart, but the elements of a Rust `[f32; 4]`
+/// are only 4 bytes apart. `ArrayOf` aligns each element to 16 bytes, so that its stride matches
+/// the WGSL stride:
+///
+/// ```ignore
+/// #[derive(abi::Sized, Clone, Copy)]
+/// #[repr(C)]
+/// struct Light {
+///     position: abi::Vec3<f32>,
+///     intensity: f32,
+/// }
+///
+/// #[derive(abi::Sized, Clone, Copy)]
+/// #[repr(C)]
+/// struct Uniforms {
+///     lights: abi::ArrayOf<Light, 4>,
+///     weights: abi::ArrayOf<f32, 4>,
+/// }
+/// ```
+///
+/// An `ArrayOf` may also be used for a storage buffer if the WGSL size of `T` is a multiple of 16
+/// bytes (e.g. a struct that contains a `vec4<f32>`); otherwise use a Rust array, as arrays in the
+/// storage address space only have to be aligned to the alignment of their element type. Pipeline
+/// creation reports a mismatch between the strides (see [check_layout]).
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[repr(C)]
+pub struct ArrayOf<T, const N: usize>([ArrayElement<T>; N]);
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[repr(C, align(16))]
+struct ArrayElement<T>(T);
+
+impl<T, const N: usize> ArrayOf<T, N> {
+    /// Creates a new array from the given `elements`.
+    pub fn new(elements: [T; N]) -> Self {
+        ArrayOf(elements.map(ArrayElement))
+    }
+
+    /// The number of elements in the array.
+    pub const fn len(&self) -> usize {
+        N
+    }
+
+    /// Whether the array has no elements.
+    pub const fn is_empty(&self) -> bool {
+        N == 0
+    }
+
+    /// Returns a reference to the element at the given `index`, or `None` if the `index` is out
+    /// of bounds.
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.0.get(index).map(|element| &element.0)
+    }
+
+    /// Returns a mutable reference to the element at the given `index`, or `None` if the `index`
+    /// is out of bounds.
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        self.0.get_mut(index).map(|element| &mut element.0)
+    }
+
+    /// Returns an iterator over the elements of the array.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.0.iter().map(|element| &element.0)
+    }
+
+    /// Returns an iterator that allows modifying the elements of the array.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.0.iter_mut().map(|element| &mut element.0)
+    }
+
+    /// Returns the elements of the array without padding.
+    pub fn into_inner(self) -> [T; N] {
+        self.0.map(|element| element.0)
+    }
+}
+
+impl<T, const N: usize> From<[T; N]> for ArrayOf<T, N> {
+    fn from(elements: [T; N]) -> Self {
+        ArrayOf::new(elements)
+    }
+}
+
+impl<T, const N: usize> Default for ArrayOf<T, N>
+where
+    T: Default,
+{
+    fn default() -> Self {
+        ArrayOf(core::array::from_fn(|_| ArrayElement(T::default())))
+    }
+}
+
+impl<T, const N: usize> ops::Index<usize> for ArrayOf<T, N> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        &self.0[index].0
+    }
+}
+
+impl<T, const N: usize> ops::IndexMut<usize> for ArrayOf<T, N> {
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        &mut self.0[index].0
+    }
+}
+
+#[cfg(feature = "bytemuck")]
+unsafe impl<T, const N: usize> bytemuck::Zeroable for ArrayOf<T, N> where T: bytemuck::Zeroable {}
+
+unsafe impl<T, const N: usize> Sized for ArrayOf<T, N>
+where
+    T: Sized,
+{
+    const LAYOUT: &'static [MemoryUnit] = &[MemoryUnit {
+        offset: 0,
+        layout: MemoryUnitLayout::Array {
+            units: T::LAYOUT,
+            stride: mem::size_of::<ArrayElement<T>>(),
+            len: N,
+        },
+    }];
+
+    const IS_UNIFORM_ARRAY: bool = true;
+}
+
+unsafe impl Sized for f32 {
+    const LAYOUT: &'static [MemoryUnit] = &[MemoryUnit {
+        offset: 0,
+        layout: MemoryUnitLayout::Float,
+    }];
+}
+
+unsafe impl Sized for i32 {
+    const LAYOUT: &'static [MemoryUnit] = &[MemoryUnit {
+        offset: 0,
+        layout: MemoryUnitLayout::Integer,
+    }];
+}
+
+unsafe impl Sized for u32 {
+    const LAYOUT: &'static [MemoryUnit] = &[MemoryUnit {
+        offset: 0,
+        layout: MemoryUnitLayout::UnsignedInteger,
+    }];
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[repr(C, align(8))]
+pub struct Vec2<T>(pub T, pub T);
+
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for Vec2<u32> {}
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for Vec2<i32> {}
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for Vec2<f32> {}
+
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Pod for Vec2<u32> {}
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Pod for Vec2<i32> {}
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Pod for Vec2<f32> {}
+
+unsafe impl Sized for Vec2<f32> {
+    const LAYOUT: &'static [MemoryUnit] = &[MemoryUnit {
+        offset: 0,
+        layout: MemoryUnitLayout::FloatVector2,
+    }];
+}
+
+unsafe impl Sized for Vec2<i32> {
+    const LAYOUT: &'static [MemoryUnit] = &[MemoryUnit {
+        offset: 0,
+        layout: MemoryUnitLayout::IntegerVector2,
+    }];
+}
+
+unsafe impl Sized for Vec2<u32> {
+    const LAYOUT: &'static [MemoryUnit] = &[MemoryUnit {
+        offset: 0,
+        layout: MemoryUnitLayout::UnsignedIntegerVector2,
+    }];
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[repr(C, align(16))]
+pub struct Vec3<T>(pub T, pub T, pub T);
+
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for Vec3<u32> {}
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for Vec3<i32> {}
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for Vec3<f32> {}
+
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Pod for Vec3<u32> {}
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Pod for Vec3<i32> {}
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Pod for Vec3<f32> {}
+
+unsafe impl Sized for Vec3<f32> {
+    const LAYOUT: &'static [MemoryUnit] = &[MemoryUnit {
+        offset: 0,
+        layout: MemoryUnitLayout::FloatVector3,
+    }];
+}
+
+unsafe impl Sized for Vec3<i32> {
+    const LAYOUT: &'static [MemoryUnit] = &[MemoryUnit {
+        offset: 0,
+        layout: MemoryUnitLayout::IntegerVector3,
+    }];
+}
+
+unsafe impl Sized for Vec3<u32> {
+    const LAYOUT: &'static [MemoryUnit] = &[MemoryUnit {
+        offset: 0,
+        layout: MemoryUnitLayout::UnsignedIntegerVector3,
+    }];
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[repr(C, align(16))]
+pub struct Vec4<T>(pub T, pub T, pub T, pub T);
+
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for Vec4<u32> {}
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for Vec4<i32> {}
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for Vec4<f32> {}
+
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Pod for Vec4<u32> {}
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Pod for Vec4<i32> {}
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Pod for Vec4<f32> {}
+
+unsafe impl Sized for Vec4<f32> {
+    const LAYOUT: &'static [MemoryUnit] = &[MemoryUnit {
+        offset: 0,
+        layout: MemoryUnitLayout::FloatVector4,
+    }];
+}
+
+unsafe impl Sized for Vec4<i32> {
+    const LAYOUT: &'static [MemoryUnit] = &[MemoryUnit {
+        offset: 0,
+        layout: MemoryUnitLayout::IntegerVector4,
+    }];
+}
+
+unsafe impl Sized for Vec4<u32> {
+    const LAYOUT: &'static [MemoryUnit] = &[MemoryUnit {
+        offset: 0,
+        layout: MemoryUnitLayout::UnsignedIntegerVector4,
+    }];
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[repr(C)]
+pub struct Mat2x2(pub Vec2<f32>, pub Vec2<f32>);
+
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for Mat2x2 {}
+
+unsafe impl Sized for Mat2x2 {
+    const LAYOUT: &'static [MemoryUnit] = &[MemoryUnit {
+        offset: 0,
+        layout: MemoryUnitLayout::Matrix2x2,
+    }];
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[repr(C)]
+pub struct Mat2x3(pub Vec3<f32>, pub Vec3<f32>);
+
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for Mat2x3 {}
+
+unsafe impl Sized for Mat2x3 {
+    const LAYOUT: &'static [MemoryUnit] = &[MemoryUnit {
+        offset: 0,
+        layout: MemoryUnitLayout::Matrix2x3,
+    }];
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[repr(C)]
+pub struct Mat2x4(pub Vec4<f32>, pub Vec4<f32>);
+
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for Mat2x4 {}
+
+unsafe impl Sized for Mat2x4 {
+    const LAYOUT: &'static [MemoryUnit] = &[MemoryUnit {
+        offset: 0,
+        layout: MemoryUnitLayout::Matrix2x4,
+    }];
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[repr(C)]
+pub struct Mat3x2(pub Vec2<f32>, pub Vec2<f32>, pub Vec2<f32>);
+
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for Mat3x2 {}
+
+unsafe impl Sized for Mat3x2 {
+    const LAYOUT: &'static [MemoryUnit] = &[MemoryUnit {
+        offset: 0,
+        layout: MemoryUnitLayout::Matrix3x2,
+    }];
+}
+
+/// A 3x3 matrix of `f32` values, corresponding to a WGSL `mat3x3<f32>`, stored as 3 columns.
+///
+/// In WGSL, the columns of a `mat3x3<f32>` are 16 bytes apart: each column is followed by 4 bytes
+/// of padding, for a total size of 48 bytes. The columns are [Vec3] values, which are aligned to
+/// 16 bytes, so this type includes the same padding. Note that this differs from a tightly packed
+/// `[f32; 9]` (such as the data of glam's `Mat3`): copying 9 consecutive `f32` values into the
+/// buffer would shift the second and third columns, which typically shows up as a sheared normal
+/// matrix. Use [Mat3x3::from_cols_padded] for data that is already padded.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[repr(C)]
+pub struct Mat3x3(pub Vec3<f32>, pub Vec3<f32>, pub Vec3<f32>);
+
+impl Mat3x3 {
+    /// Creates a matrix from column-major `data` in which each column is followed by a padding
+    /// value, matching the memory layout of a WGSL `mat3x3<f32>`.
+    ///
+    /// The padding values (`data[3]`, `data[7]` and `data[11]`) are ignored.
+    ///
+    /// ```ignore
+    /// let normal_matrix = Mat3x3::from_cols_padded([
+    ///     1.0, 0.0, 0.0, 0.0,
+    ///     0.0, 1.0, 0.0, 0.0,
+    ///     0.0, 0.0, 1.0, 0.0,
+    /// ]);
+    /// ```
+    pub const fn from_cols_padded(data: [f32; 12]) -> Self {
+        Mat3x3(
+            Vec3(data[0], data[1], data[2]),
+            Vec3(data[4], data[5], data[6]),
+            Vec3(data[8], data[9], data[10]),
+        )
+    }
+}
+
+const _: () = assert!(mem::size_of::<Mat3x3>() == 48 && mem::align_of::<Mat3x3>() == 16);
+
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for Mat3x3 {}
+
+unsafe impl Sized for Mat3x3 {
+    const LAYOUT: &'static [MemoryUnit] = &[MemoryUnit {
+        offset: 0,
+        layout: MemoryUnitLayout::Matrix3x3,
+    }];
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[repr(C)]
+pub struct Mat3x4(pub Vec4<f32>, pub Vec4<f32>, pub Vec4<f32>);
+
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for Mat3x4 {}
+
+unsafe impl Sized for Mat3x4 {
+    const LAYOUT: &'static [MemoryUnit] = &[MemoryUnit {
+        offset: 0,
+        layout: MemoryUnitLayout::Matrix3x4,
+    }];
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[repr(C)]
+pub struct Mat4x2(pub Vec2<f32>, pub Vec2<f32>, pub Vec2<f32>, pub Vec2<f32>);
+
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for Mat4x2 {}
+
+unsafe impl Sized for Mat4x2 {
+    const LAYOUT: &'static [MemoryUnit] = &[MemoryUnit {
+        offset: 0,
+        layout: MemoryUnitLayout::Matrix4x2,
+    }];
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[repr(C)]
+pub struct Mat4x3(pub Vec3<f32>, pub Vec3<f32>, pub Vec3<f32>, pub Vec3<f32>);
+
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for Mat4x3 {}
+
+unsafe impl Sized for Mat4x3 {
+    const LAYOUT: &'static [MemoryUnit] = &[MemoryUnit {
+        offset: 0,
+        layout: MemoryUnitLayout::Matrix4x3,
+    }];
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[repr(C)]
+pub struct Mat4x4(pub Vec4<f32>, pub Vec4<f32>, pub Vec4<f32>, pub Vec4<f32>);
+
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for Mat4x4 {}
+
+unsafe impl Sized for Mat4x4 {
+    const LAYOUT: &'static [MemoryUnit] = &[MemoryUnit {
+        offset: 0,
+        layout: MemoryUnitLayout::Matrix4x4,
+    }];
+}
+
+/// Signals that the memory layout of a Rust type does not match the memory layout a shader expects
+/// for a buffer binding.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct LayoutMismatch {
+    offset: usize,
+    expected: Option<MemoryUnitLayout>,
+    actual: Option<MemoryUnitLayout>,
+}
+
+impl LayoutMismatch {
+    /// The offset in bytes at which the layouts first differ.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// The memory unit the shader expects at the [offset](LayoutMismatch::offset), if any.
+    pub fn expected(&self) -> Option<MemoryUnitLayout> {
+        self.expected
+    }
+
+    /// The memory unit the Rust type has at the [offset](LayoutMismatch::offset), if any.
+    pub fn actual(&self) -> Option<MemoryUnitLayout> {
+        self.actual
+    }
+}
+
+impl fmt::Display for LayoutMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "at offset `{}`: ", self.offset)?;
+
+        match (&self.expected, &self.actual) {
+            (Some(expected), Some(actual)) => write!(
+                f,
+                "the shader expects `{}`, but the Rust type has `{}`",
+                WgslName(expected),
+                WgslName(actual)
+            ),
+            (Some(expected), None) => write!(
+                f,
+                "the shader expects `{}`, but the Rust type has no further members",
+                WgslName(expected)
+            ),
+            (None, Some(actual)) => write!(
+                f,
+                "the shader expects no further members, but the Rust type has `{}`",
+                WgslName(actual)
+            ),
+            (None, None) => write!(f, "the layouts do not match"),
+        }
+    }
+}
+
+impl Error for LayoutMismatch {}
+
+struct WgslName<'a>(&'a MemoryUnitLayout);
+
+impl fmt::Display for WgslName<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self.0 {
+            MemoryUnitLayout::Float => "f32",
+            MemoryUnitLayout::FloatVector2 => "vec2<f32>",
+            MemoryUnitLayout::FloatVector3 => "vec3<f32>",
+            MemoryUnitLayout::FloatVector4 => "vec4<f32>",
+            MemoryUnitLayout::Integer => "i32",
+            MemoryUnitLayout::IntegerVector2 => "vec2<i32>",
+            MemoryUnitLayout::IntegerVector3 => "vec3<i32>",
+            MemoryUnitLayout::IntegerVector4 => "vec4<i32>",
+            MemoryUnitLayout::UnsignedInteger => "u32",
+            MemoryUnitLayout::UnsignedIntegerVector2 => "vec2<u32>",
+            MemoryUnitLayout::UnsignedIntegerVector3 => "vec3<u32>",
+            MemoryUnitLayout::UnsignedIntegerVector4 => "vec4<u32>",
+            MemoryUnitLayout::Matrix2x2 => "mat2x2<f32>",
+            MemoryUnitLayout::Matrix2x3 => "mat2x3<f32>",
+            MemoryUnitLayout::Matrix2x4 => "mat2x4<f32>",
+            MemoryUnitLayout::Matrix3x2 => "mat3x2<f32>",
+            MemoryUnitLayout::Matrix3x3 => "mat3x3<f32>",
+            MemoryUnitLayout::Matrix3x4 => "mat3x4<f32>",
+            MemoryUnitLayout::Matrix4x2 => "mat4x2<f32>",
+            MemoryUnitLayout::Matrix4x3 => "mat4x3<f32>",
+            MemoryUnitLayout::Matrix4x4 => "mat4x4<f32>",
+            MemoryUnitLayout::Array { stride, len, .. } => {
+                return write!(f, "array<_, {}> (stride {})", len, stride);
+            }
+        };
+
+        f.write_str(name)
+    }
+}
+
+/// Checks that the `actual` memory units match the `expected` memory units.
+///
+/// Returns the first [LayoutMismatch] if the memory units differ in their offsets or layouts.
+pub fn check_memory_units(
+    expected: &[MemoryUnit],
+    actual: &[MemoryUnit],
+) -> Result<(), LayoutMismatch> {
+    check_memory_units_at(expected, actual, 0)
+}
+
+/// Checks that the `actual` memory units of an (optionally) runtime-sized type match the `expected`
+/// memory units.
+///
+/// The memory units of the sized heads are compared as with [check_memory_units]; the unsized
+/// tails (the element layouts of the runtime-sized arrays at the end of the types, if any) are
+/// compared at the offset at which the expected sized head ends.
+pub fn check_unsized_memory_units(
+    expected_head: &[MemoryUnit],
+    expected_tail: Option<&'static [MemoryUnit]>,
+    actual_head: &[MemoryUnit],
+    actual_tail: Option<&'static [MemoryUnit]>,
+) -> Result<(), LayoutMismatch> {
+    check_memory_units_at(expected_head, actual_head, 0)?;
+
+    let tail_offset = expected_head
+        .last()
+        .map(|unit| unit.offset + unit.layout.wgsl_size())
+        .unwrap_or(0);
+
+    match (expected_tail, actual_tail) {
+        (Some(expected), Some(actual)) => check_memory_units_at(expected, actual, tail_offset),
+        (None, None) => Ok(()),
+        (expected, actual) => Err(LayoutMismatch {
+            offset: tail_offset,
+            expected: expected.map(|units| MemoryUnitLayout::Array {
+                units,
+                stride: 0,
+                len: 0,
+            }),
+            actual: actual.map(|units| MemoryUnitLayout::Array {
+                units,
+                stride: 0,
+                len: 0,
+            }),
+        }),
+    }
+}
+
+fn check_memory_units_at(
+    expected: &[MemoryUnit],
+    actual: &[MemoryUnit],
+    base_offset: usize,
+) -> Result<(), LayoutMismatch> {
+    let len = cmp::max(expected.len(), actual.len());
+
+    for i in 0..len {
+        let expected_unit = expected.get(i);
+        let actual_unit = actual.get(i);
+
+        let offset = base_offset
+            + expected_unit
+                .or(actual_unit)
+                .map(|unit| unit.offset)
+                .unwrap_or(0);
+
+        let mismatch = || LayoutMismatch {
+            offset,
+            expected: expected_unit.map(|unit| unit.layout),
+            actual: actual_unit.map(|unit| unit.layout),
+        };
+
+        match (expected_unit, actual_unit) {
+            (Some(expected_unit), Some(actual_unit)) => {
+                if expected_unit.offset != actual_unit.offset {
+                    return Err(LayoutMismatch {
+                        offset: base_offset + cmp::min(expected_unit.offset, actual_unit.offset),
+                        expected: (expected_unit.offset <= actual_unit.offset)
+                            .then_some(expected_unit.layout),
+                        actual: (actual_unit.offset <= expected_unit.offset)
+                            .then_some(actual_unit.layout),
+                    });
+                }
+
+                match (expected_unit.layout, actual_unit.layout) {
+                    (
+                        MemoryUnitLayout::Array {
+                            units: expected_units,
+                            stride: expected_stride,
+                            len: expected_len,
+                        },
+                        MemoryUnitLayout::Array {
+                            units: actual_units,
+                            stride: actual_stride,
+                            len: actual_len,
+                        },
+                    ) => {
+                        if expected_stride != actual_stride || expected_len != actual_len {
+                            return Err(mismatch());
+                        }
+
+                        check_memory_units_at(expected_units, actual_units, offset)?;
+                    }
+                    (expected_layout, actual_layout) => {
+                        if expected_layout != actual_layout {
+                            return Err(mismatch());
+                        }
+                    }
+                }
+            }
+            _ => return Err(mismatch()),
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,66 @@
+//! Owned descriptions of the memory layouts of WGSL types, as produced by shader reflection.
+//!
+//! Unlike [MemoryUnitLayout](crate::MemoryUnitLayout), the layouts in this module own their
+//! array element layouts and describe arrays of scalars, vectors and matrices with dedicated
+//! variants, which is how the `empa-reflect` crate reports the layouts of shader bindings.
+
+use alloc::vec::Vec;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MemoryUnit {
+    pub offset: usize,
+    pub layout: MemoryUnitLayout,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MemoryUnitLayout {
+    Float,
+    FloatArray(usize),
+    FloatVector2,
+    FloatVector2Array(usize),
+    FloatVector3,
+    FloatVector3Array(usize),
+    FloatVector4,
+    FloatVector4Array(usize),
+    Integer,
+    IntegerArray(usize),
+    IntegerVector2,
+    IntegerVector2Array(usize),
+    IntegerVector3,
+    IntegerVector3Array(usize),
+    IntegerVector4,
+    IntegerVector4Array(usize),
+    UnsignedInteger,
+    UnsignedIntegerArray(usize),
+    UnsignedIntegerVector2,
+    UnsignedIntegerVector2Array(usize),
+    UnsignedIntegerVector3,
+    UnsignedIntegerVector3Array(usize),
+    UnsignedIntegerVector4,
+    UnsignedIntegerVector4Array(usize),
+    Matrix2x2,
+    Matrix2x2Array(usize),
+    Matrix2x3,
+    Matrix2x3Array(usize),
+    Matrix2x4,
+    Matrix2x4Array(usize),
+    Matrix3x2,
+    Matrix3x2Array(usize),
+    Matrix3x3,
+    Matrix3x3Array(usize),
+    Matrix3x4,
+    Matrix3x4Array(usize),
+    Matrix4x2,
+    Matrix4x2Array(usize),
+    Matrix4x3,
+    Matrix4x3Array(usize),
+    Matrix4x4,
+    Matrix4x4Array(usize),
+    ComplexArray {
+        units: Vec<MemoryUnit>,
+        stride: usize,
+        len: usize,
+    },
+}
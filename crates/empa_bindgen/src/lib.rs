@@ -0,0 +1,782 @@
+//! Generates Rust bindings for WGSL shaders.
+//!
+//! For projects that prefer generating code in a build script (or ahead of time with the
+//! `empa-bindgen` command line tool) over the `shader_source!` and derive macros, [generate] emits
+//! a Rust module for a WGSL shader that contains:
+//!
+//! - an `empa::abi::Sized` struct for every struct that is used in a uniform or storage buffer
+//!   binding;
+//! - a `Resources` struct for every bind group, named after the group (e.g. `Group0Resources`),
+//!   along with an alias for its layout (e.g. `Group0Layout`); the visibility of each resource is
+//!   derived from the entry points that use it;
+//! - a `Vertex` struct for the inputs of every vertex entry point, named after the entry point
+//!   (e.g. `VsMainVertex` for `vs_main`);
+//! - a typed constant for every entry point, named after the entry point in upper snake case (e.g.
+//!   `VS_MAIN`).
+//!
+//! The generated code refers to all items by their full `empa` paths, so that it may be included
+//! in any module:
+//!
+//! ```ignore
+//! // build.rs
+//! let source = fs::read_to_string("src/shader.wgsl")?;
+//! let bindings = empa_bindgen::generate(&source)?;
+//!
+//! fs::write(Path::new(&env::var("OUT_DIR")?).join("shader.rs"), bindings)?;
+//!
+//! // main.rs
+//! mod shader {
+//!     include!(concat!(env!("OUT_DIR"), "/shader.rs"));
+//! }
+//! ```
+//!
+//! WGSL structs that cannot be represented by an equivalent Rust struct (e.g. a struct in which a
+//! `vec3<f32>` is directly followed by an `f32`, as Rust pads an `abi::Vec3` to 16 bytes) are
+//! reported as errors; reorder the members, or add explicit padding with `@size` or `@align`
+//! attributes. A storage buffer that contains a runtime-sized array is bound as a slice of
+//! the array's element type; its struct may not contain other members. The shader source is not
+//! preprocessed: `#include` directives are not supported.
+
+use std::collections::{BTreeMap, HashSet};
+use std::error::Error;
+use std::fmt;
+use std::fmt::Write;
+
+use empa_reflect::{
+    BindingType, EntryPointBindingType, ShaderSource, ShaderStage, StorageTextureFormat, TexelType,
+};
+use naga::proc::{IndexableLength, Layouter};
+use naga::valid::{Capabilities, ModuleInfo, ValidationFlags, Validator};
+use naga::{Handle, Module, ScalarKind, Type, TypeInner};
+
+/// Error returned by [generate] when no bindings can be generated for a shader.
+#[derive(Clone, Debug)]
+pub struct GenerateError {
+    message: String,
+}
+
+impl GenerateError {
+    fn new(message: String) -> Self {
+        GenerateError { message }
+    }
+
+    /// A description of the error; for parse and validation errors this includes an annotated
+    /// snippet of the shader source.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for GenerateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl Error for GenerateError {}
+
+/// Parses and validates the WGSL `source` and generates Rust bindings for it.
+///
+/// See the [crate] documentation for a description of the generated items.
+pub fn generate(source: &str) -> Result<String, GenerateError> {
+    let shader_source = ShaderSource::parse(source.to_string())
+        .map_err(|err| GenerateError::new(err.emit_to_string(source)))?;
+
+    let mut validator = Validator::new(
+        ValidationFlags::all() & !(ValidationFlags::EXPRESSIONS | ValidationFlags::BLOCKS),
+        Capabilities::all(),
+    );
+
+    let info = validator
+        .validate(shader_source.module())
+        .map_err(|err| GenerateError::new(err.emit_to_string(source)))?;
+
+    let mut layouter = Layouter::default();
+
+    layouter.update(shader_source.module().to_ctx()).unwrap();
+
+    let mut generator = Generator {
+        shader_source: &shader_source,
+        module: shader_source.module(),
+        info,
+        layouter,
+        output: String::new(),
+    };
+
+    generator.generate_structs()?;
+    generator.generate_resources()?;
+    generator.generate_vertex_inputs()?;
+    generator.generate_entry_points();
+
+    Ok(generator.output)
+}
+
+/// What a buffer binding contains: a sized type, or a runtime-sized array of elements.
+enum BufferContents {
+    Sized(Handle<Type>),
+    Slice(Handle<Type>),
+}
+
+/// A Rust type that represents a WGSL type, along with its size and alignment in Rust.
+struct RustType {
+    name: String,
+    size: u32,
+    align: u32,
+    is_padded_array: bool,
+}
+
+struct Generator<'a> {
+    shader_source: &'a ShaderSource,
+    module: &'a Module,
+    info: ModuleInfo,
+    layouter: Layouter,
+    output: String,
+}
+
+impl Generator<'_> {
+    fn generate_structs(&mut self) -> Result<(), GenerateError> {
+        let mut structs = HashSet::new();
+
+        for (_, global) in self.module.global_variables.iter() {
+            if let naga::AddressSpace::Uniform | naga::AddressSpace::Storage { .. } = global.space {
+                let name = global.name.as_deref().unwrap_or("");
+
+                match self.buffer_contents(name, global.ty)? {
+                    BufferContents::Sized(ty) | BufferContents::Slice(ty) => {
+                        self.collect_structs(ty, &mut structs)
+                    }
+                }
+            }
+        }
+
+        // Iterate the type arena rather than the set, so that the structs are generated in
+        // declaration order.
+        for (handle, ty) in self.module.types.iter() {
+            if let TypeInner::Struct { members, .. } = &ty.inner {
+                if structs.contains(&handle) {
+                    self.generate_struct(ty.name.as_deref().unwrap_or(""), members)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn collect_structs(&self, ty: Handle<Type>, structs: &mut HashSet<Handle<Type>>) {
+        match &self.module.types[ty].inner {
+            TypeInner::Array { base, .. } => self.collect_structs(*base, structs),
+            TypeInner::Struct { members, .. } => {
+                if structs.insert(ty) {
+                    for member in members {
+                        self.collect_structs(member.ty, structs);
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn generate_struct(
+        &mut self,
+        name: &str,
+        members: &[naga::StructMember],
+    ) -> Result<(), GenerateError> {
+        let mut fields = Vec::with_capacity(members.len());
+
+        for (i, member) in members.iter().enumerate() {
+            let member_name = member.name.as_deref().unwrap_or("");
+            let context = format!("member `{}` of struct `{}`", member_name, name);
+            let rust_type = self.rust_type(member.ty, &context)?;
+
+            // The `abi::Sized` derive checks that each field directly follows the previous field
+            // in WGSL; if the WGSL struct adds explicit padding, the previous field must be
+            // annotated with the size it is padded to.
+            let pad_to = members.get(i + 1).and_then(|next| {
+                let natural_offset = self.natural_offset(member, next, &context).ok()?;
+
+                (next.offset != natural_offset).then(|| next.offset - member.offset)
+            });
+
+            fields.push((field_ident(member_name), rust_type.name, pad_to));
+        }
+
+        // Validates that the Rust struct has the same layout as the WGSL struct.
+        self.struct_layout(name, members)?;
+
+        writeln!(self.output, "#[derive(empa::abi::Sized, Clone, Copy)]").unwrap();
+        writeln!(self.output, "#[repr(C)]").unwrap();
+        writeln!(self.output, "pub struct {} {{", name).unwrap();
+
+        for (ident, ty, pad_to) in fields {
+            if let Some(pad_to) = pad_to {
+                writeln!(self.output, "    #[abi(pad_to = {})]", pad_to).unwrap();
+            }
+
+            writeln!(self.output, "    pub {}: {},", ident, ty).unwrap();
+        }
+
+        writeln!(self.output, "}}\n").unwrap();
+
+        Ok(())
+    }
+
+    /// The offset at which `next` would follow `member` in WGSL without explicit padding.
+    fn natural_offset(
+        &self,
+        member: &naga::StructMember,
+        next: &naga::StructMember,
+        context: &str,
+    ) -> Result<u32, GenerateError> {
+        let end = member.offset + self.layouter[member.ty].size;
+        let offset = self.layouter[next.ty].alignment.round_up(end);
+
+        if self.rust_type(next.ty, context)?.is_padded_array {
+            Ok(round_up(16, offset))
+        } else {
+            Ok(offset)
+        }
+    }
+
+    /// Returns the size and alignment of the Rust struct for the WGSL struct with the given
+    /// `members`, or an error if a member would not have the same offset as in WGSL.
+    fn struct_layout(
+        &self,
+        name: &str,
+        members: &[naga::StructMember],
+    ) -> Result<(u32, u32), GenerateError> {
+        let mut offset = 0;
+        let mut align = 1;
+
+        for member in members {
+            let member_name = member.name.as_deref().unwrap_or("");
+            let context = format!("member `{}` of struct `{}`", member_name, name);
+            let rust_type = self.rust_type(member.ty, &context)?;
+
+            offset = round_up(rust_type.align, offset);
+
+            if offset != member.offset {
+                return Err(GenerateError::new(format!(
+                    "{} is at offset `{}` in WGSL, but would be at offset `{}` in Rust (e.g. \
+                     because a `vec3` is padded to 16 bytes in Rust, but only occupies 12 bytes in \
+                     WGSL); reorder the members or add explicit padding with `@size` or `@align`",
+                    context, member.offset, offset
+                )));
+            }
+
+            offset += rust_type.size;
+            align = align.max(rust_type.align);
+        }
+
+        Ok((round_up(align, offset), align))
+    }
+
+    fn rust_type(&self, ty: Handle<Type>, context: &str) -> Result<RustType, GenerateError> {
+        let unsupported = || {
+            GenerateError::new(format!(
+                "{} has a type that cannot be represented in Rust (only `f32`, `i32` and `u32` \
+                 based types are supported)",
+                context
+            ))
+        };
+
+        let scalar_name = |scalar: &naga::Scalar| match (scalar.kind, scalar.width) {
+            (ScalarKind::Float, 4) => Ok("f32"),
+            (ScalarKind::Sint, 4) => Ok("i32"),
+            (ScalarKind::Uint, 4) => Ok("u32"),
+            _ => Err(unsupported()),
+        };
+
+        let rust_type = match &self.module.types[ty].inner {
+            TypeInner::Scalar(scalar) | TypeInner::Atomic(scalar) => RustType {
+                name: scalar_name(scalar)?.to_string(),
+                size: 4,
+                align: 4,
+                is_padded_array: false,
+            },
+            TypeInner::Vector { size, scalar } => {
+                let len = *size as u32;
+                let size = if len == 2 { 8 } else { 16 };
+
+                RustType {
+                    name: format!("empa::abi::Vec{}<{}>", len, scalar_name(scalar)?),
+                    size,
+                    align: size,
+                    is_padded_array: false,
+                }
+            }
+            TypeInner::Matrix {
+                columns,
+                rows,
+                scalar,
+            } => {
+                if scalar_name(scalar)? != "f32" {
+                    return Err(unsupported());
+                }
+
+                let columns = *columns as u32;
+                let rows = *rows as u32;
+                let column_size = if rows == 2 { 8 } else { 16 };
+
+                RustType {
+                    name: format!("empa::abi::Mat{}x{}", columns, rows),
+                    size: columns * column_size,
+                    align: column_size,
+                    is_padded_array: false,
+                }
+            }
+            TypeInner::Array { base, size, stride } => {
+                let len = match size.to_indexable_length(self.module) {
+                    Ok(IndexableLength::Known(len)) => len,
+                    _ => {
+                        return Err(GenerateError::new(format!(
+                            "{} is a runtime-sized array, which is only supported as the only \
+                             member of a storage buffer struct",
+                            context
+                        )))
+                    }
+                };
+
+                let element = self.rust_type(*base, context)?;
+
+                if *stride == element.size {
+                    RustType {
+                        name: format!("[{}; {}]", element.name, len),
+                        size: element.size * len,
+                        align: element.align,
+                        is_padded_array: false,
+                    }
+                } else if *stride == round_up(16, element.size) {
+                    RustType {
+                        name: format!("empa::abi::ArrayOf<{}, {}>", element.name, len),
+                        size: stride * len,
+                        align: element.align.max(16),
+                        is_padded_array: true,
+                    }
+                } else {
+                    return Err(GenerateError::new(format!(
+                        "{} is an array with a stride of `{}` bytes in WGSL, but its element type \
+                         has a size of `{}` bytes in Rust",
+                        context, stride, element.size
+                    )));
+                }
+            }
+            TypeInner::Struct { members, .. } => {
+                let name = self.module.types[ty].name.as_deref().unwrap_or("");
+                let (size, align) = self.struct_layout(name, members)?;
+
+                RustType {
+                    name: name.to_string(),
+                    size,
+                    align,
+                    is_padded_array: false,
+                }
+            }
+            _ => return Err(unsupported()),
+        };
+
+        Ok(rust_type)
+    }
+
+    fn buffer_contents(
+        &self,
+        name: &str,
+        ty: Handle<Type>,
+    ) -> Result<BufferContents, GenerateError> {
+        let runtime_array_element = |ty: Handle<Type>| match &self.module.types[ty].inner {
+            TypeInner::Array { base, size, .. } => match size.to_indexable_length(self.module) {
+                Ok(IndexableLength::Dynamic) => Some(*base),
+                _ => None,
+            },
+            _ => None,
+        };
+
+        if let Some(element) = runtime_array_element(ty) {
+            return Ok(BufferContents::Slice(element));
+        }
+
+        if let TypeInner::Struct { members, .. } = &self.module.types[ty].inner {
+            if let Some(element) = members.last().and_then(|m| runtime_array_element(m.ty)) {
+                if members.len() > 1 {
+                    return Err(GenerateError::new(format!(
+                        "binding `{}` contains a runtime-sized array that follows other struct \
+                         members; move the array into a struct of its own, as only runtime-sized \
+                         arrays without a sized head can be bound",
+                        name
+                    )));
+                }
+
+                return Ok(BufferContents::Slice(element));
+            }
+        }
+
+        Ok(BufferContents::Sized(ty))
+    }
+
+    fn generate_resources(&mut self) -> Result<(), GenerateError> {
+        let mut groups: BTreeMap<u32, Vec<(u32, String, String, String)>> = BTreeMap::new();
+
+        for (handle, global) in self.module.global_variables.iter() {
+            let Some(resource_binding) = &global.binding else {
+                continue;
+            };
+
+            let name = global.name.as_deref().unwrap_or("");
+            let binding_type = self
+                .shader_source
+                .resource_bindings()
+                .iter()
+                .find(|b| {
+                    b.group() == resource_binding.group && b.binding() == resource_binding.binding
+                })
+                .map(|b| b.binding_type())
+                .unwrap();
+
+            let ty = self.resource_type(name, global.ty, binding_type)?;
+            let visibility = self.visibility(handle);
+
+            groups.entry(resource_binding.group).or_default().push((
+                resource_binding.binding,
+                field_ident(name),
+                ty,
+                visibility,
+            ));
+        }
+
+        for (group, mut resources) in groups {
+            resources.sort_by_key(|(binding, ..)| *binding);
+
+            writeln!(self.output, "#[derive(empa::resource_binding::Resources)]").unwrap();
+            writeln!(self.output, "pub struct Group{}Resources<'a> {{", group).unwrap();
+
+            for (binding, ident, ty, visibility) in resources {
+                writeln!(
+                    self.output,
+                    "    #[resource(binding = {}, visibility = \"{}\")]",
+                    binding, visibility
+                )
+                .unwrap();
+                writeln!(self.output, "    pub {}: {},", ident, ty).unwrap();
+            }
+
+            writeln!(self.output, "}}\n").unwrap();
+            writeln!(
+                self.output,
+                "pub type Group{0}Layout = <Group{0}Resources<'static> as \
+                 empa::resource_binding::Resources>::Layout;\n",
+                group
+            )
+            .unwrap();
+        }
+
+        Ok(())
+    }
+
+    fn resource_type(
+        &self,
+        name: &str,
+        ty: Handle<Type>,
+        binding_type: &BindingType,
+    ) -> Result<String, GenerateError> {
+        let sampled = |dimension: &str, texel_type: &TexelType| {
+            let texel_type = match texel_type {
+                TexelType::Float => "Float",
+                TexelType::UnfilterableFloat => "UnfilteredFloat",
+                TexelType::Integer => "SignedInteger",
+                TexelType::UnsignedInteger => "UnsignedInteger",
+            };
+
+            format!("empa::texture::Sampled{}{}<'a>", dimension, texel_type)
+        };
+
+        let storage = |dimension: &str, format: &StorageTextureFormat| {
+            format!(
+                "empa::texture::Storage{}<'a, empa::texture::format::{:?}>",
+                dimension, format
+            )
+        };
+
+        let buffer_contents = || -> Result<String, GenerateError> {
+            match self.buffer_contents(name, ty)? {
+                BufferContents::Sized(ty) => {
+                    Ok(self.rust_type(ty, &format!("binding `{}`", name))?.name)
+                }
+                BufferContents::Slice(element) => {
+                    let context = format!("the element type of binding `{}`", name);
+                    let element_type = self.rust_type(element, &context)?;
+                    let stride = self.layouter[element].to_stride();
+
+                    if element_type.size != stride {
+                        return Err(GenerateError::new(format!(
+                            "{} has a stride of `{}` bytes in WGSL, but a size of `{}` bytes in \
+                             Rust",
+                            context, stride, element_type.size
+                        )));
+                    }
+
+                    Ok(format!("[{}]", element_type.name))
+                }
+            }
+        };
+
+        let resource_type = match binding_type {
+            BindingType::Texture1D(texel_type) => sampled("1D", texel_type),
+            BindingType::Texture2D(texel_type) => sampled("2D", texel_type),
+            BindingType::Texture3D(texel_type) => sampled("3D", texel_type),
+            BindingType::Texture2DArray(texel_type) => sampled("2DArray", texel_type),
+            BindingType::TextureCube(texel_type) => sampled("Cube", texel_type),
+            BindingType::TextureCubeArray(texel_type) => sampled("CubeArray", texel_type),
+            BindingType::TextureDepth2D => "empa::texture::Sampled2DDepth<'a>".to_string(),
+            BindingType::TextureDepth2DArray => {
+                "empa::texture::Sampled2DArrayDepth<'a>".to_string()
+            }
+            BindingType::TextureDepthCube => "empa::texture::SampledCubeDepth<'a>".to_string(),
+            BindingType::TextureDepthCubeArray => {
+                "empa::texture::SampledCubeArrayDepth<'a>".to_string()
+            }
+            BindingType::TextureMultisampled2D(_) | BindingType::TextureDepthMultisampled2D => {
+                return Err(GenerateError::new(format!(
+                    "binding `{}` is a multisampled texture, which cannot be bound with a \
+                     `Resources` struct",
+                    name
+                )))
+            }
+            BindingType::StorageTexture1D(format) => storage("1D", format),
+            BindingType::StorageTexture2D(format) => storage("2D", format),
+            BindingType::StorageTexture2DArray(format) => storage("2DArray", format),
+            BindingType::StorageTexture3D(format) => storage("3D", format),
+            BindingType::FilteringSampler => "&'a empa::sampler::Sampler".to_string(),
+            BindingType::NonFilteringSampler => {
+                "&'a empa::sampler::NonFilteringSampler".to_string()
+            }
+            BindingType::ComparisonSampler => "&'a empa::sampler::ComparisonSampler".to_string(),
+            BindingType::Uniform(_) => format!("empa::buffer::Uniform<'a, {}>", buffer_contents()?),
+            BindingType::ReadOnlyStorage(_) => {
+                format!("empa::buffer::Storage<'a, {}>", buffer_contents()?)
+            }
+            BindingType::Storage(_) => format!(
+                "empa::buffer::Storage<'a, {}, empa::access_mode::ReadWrite>",
+                buffer_contents()?
+            ),
+        };
+
+        Ok(resource_type)
+    }
+
+    /// The shader stages of the entry points that use the `global`; if no entry point uses it,
+    /// the stages of all entry points.
+    fn visibility(&self, global: Handle<naga::GlobalVariable>) -> String {
+        let mut vertex = false;
+        let mut fragment = false;
+        let mut compute = false;
+
+        for (i, entry_point) in self.module.entry_points.iter().enumerate() {
+            if !self.info.get_entry_point(i)[global].is_empty() {
+                match ShaderStage::from(&entry_point.stage) {
+                    ShaderStage::Vertex => vertex = true,
+                    ShaderStage::Fragment => fragment = true,
+                    ShaderStage::Compute => compute = true,
+                }
+            }
+        }
+
+        if !(vertex || fragment || compute) {
+            for entry_point in self.shader_source.entry_points() {
+                match entry_point.stage() {
+                    ShaderStage::Vertex => vertex = true,
+                    ShaderStage::Fragment => fragment = true,
+                    ShaderStage::Compute => compute = true,
+                }
+            }
+        }
+
+        let stages = [
+            (vertex, "VERTEX"),
+            (fragment, "FRAGMENT"),
+            (compute, "COMPUTE"),
+        ];
+
+        let visibility = stages
+            .iter()
+            .filter(|(visible, _)| *visible)
+            .map(|(_, stage)| *stage)
+            .collect::<Vec<_>>()
+            .join("|");
+
+        if visibility.is_empty() {
+            "VERTEX|FRAGMENT|COMPUTE".to_string()
+        } else {
+            visibility
+        }
+    }
+
+    fn generate_vertex_inputs(&mut self) -> Result<(), GenerateError> {
+        for entry_point in self.module.entry_points.iter() {
+            if entry_point.stage != naga::ShaderStage::Vertex {
+                continue;
+            }
+
+            let mut attributes = Vec::new();
+
+            for argument in entry_point.function.arguments.iter() {
+                match &argument.binding {
+                    Some(naga::Binding::Location { location, .. }) => {
+                        attributes.push((*location, argument.name.clone(), argument.ty));
+                    }
+                    Some(_) => (),
+                    None => {
+                        if let TypeInner::Struct { members, .. } =
+                            &self.module.types[argument.ty].inner
+                        {
+                            for member in members {
+                                if let Some(naga::Binding::Location { location, .. }) =
+                                    &member.binding
+                                {
+                                    attributes.push((*location, member.name.clone(), member.ty));
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            if attributes.is_empty() {
+                continue;
+            }
+
+            attributes.sort_by_key(|(location, ..)| *location);
+
+            let struct_name = format!("{}Vertex", to_upper_camel_case(&entry_point.name));
+
+            writeln!(
+                self.output,
+                "#[derive(empa::render_pipeline::Vertex, Clone, Copy)]"
+            )
+            .unwrap();
+            writeln!(self.output, "pub struct {} {{", struct_name).unwrap();
+
+            for (location, name, ty) in attributes {
+                let name = name.unwrap_or_else(|| format!("attribute_{}", location));
+                let (rust_type, format) =
+                    EntryPointBindingType::try_from(&self.module.types[ty].inner)
+                        .ok()
+                        .and_then(vertex_attribute_type)
+                        .ok_or_else(|| {
+                            GenerateError::new(format!(
+                        "input `{}` of vertex entry point `{}` has a type that is not supported \
+                         as a vertex attribute",
+                        name, entry_point.name
+                    ))
+                        })?;
+
+                writeln!(
+                    self.output,
+                    "    #[vertex_attribute(location = {}, format = \"{}\")]",
+                    location, format
+                )
+                .unwrap();
+                writeln!(
+                    self.output,
+                    "    pub {}: {},",
+                    field_ident(&name),
+                    rust_type
+                )
+                .unwrap();
+            }
+
+            writeln!(self.output, "}}\n").unwrap();
+        }
+
+        Ok(())
+    }
+
+    fn generate_entry_points(&mut self) {
+        for entry_point in self.shader_source.entry_points() {
+            let ty = match entry_point.stage() {
+                ShaderStage::Vertex => "empa::shader_module::VertexEntryPoint",
+                ShaderStage::Fragment => "empa::shader_module::FragmentEntryPoint",
+                ShaderStage::Compute => "empa::shader_module::ComputeEntryPoint",
+            };
+
+            writeln!(
+                self.output,
+                "pub const {}: {} = {}::new_unchecked(\"{}\");",
+                to_upper_snake_case(entry_point.name()),
+                ty,
+                ty,
+                entry_point.name()
+            )
+            .unwrap();
+        }
+    }
+}
+
+fn vertex_attribute_type(
+    binding_type: EntryPointBindingType,
+) -> Option<(&'static str, &'static str)> {
+    match binding_type {
+        EntryPointBindingType::SignedInteger => Some(("i32", "sint32")),
+        EntryPointBindingType::SignedIntegerVector2 => Some(("[i32; 2]", "sint32x2")),
+        EntryPointBindingType::SignedIntegerVector3 => Some(("[i32; 3]", "sint32x3")),
+        EntryPointBindingType::SignedIntegerVector4 => Some(("[i32; 4]", "sint32x4")),
+        EntryPointBindingType::UnsignedInteger => Some(("u32", "uint32")),
+        EntryPointBindingType::UnsignedIntegerVector2 => Some(("[u32; 2]", "uint32x2")),
+        EntryPointBindingType::UnsignedIntegerVector3 => Some(("[u32; 3]", "uint32x3")),
+        EntryPointBindingType::UnsignedIntegerVector4 => Some(("[u32; 4]", "uint32x4")),
+        EntryPointBindingType::Float => Some(("f32", "float32")),
+        EntryPointBindingType::FloatVector2 => Some(("[f32; 2]", "float32x2")),
+        EntryPointBindingType::FloatVector3 => Some(("[f32; 3]", "float32x3")),
+        EntryPointBindingType::FloatVector4 => Some(("[f32; 4]", "float32x4")),
+        _ => None,
+    }
+}
+
+fn round_up(align: u32, value: u32) -> u32 {
+    (value + align - 1) / align * align
+}
+
+/// Escapes WGSL identifiers that are Rust keywords.
+fn field_ident(name: &str) -> String {
+    const KEYWORDS: &[&str] = &[
+        "as", "async", "await", "box", "break", "const", "continue", "crate", "dyn", "else",
+        "enum", "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod",
+        "move", "mut", "pub", "ref", "return", "static", "struct", "trait", "true", "type",
+        "unsafe", "use", "where", "while", "yield",
+    ];
+
+    if KEYWORDS.contains(&name) {
+        format!("r#{}", name)
+    } else {
+        name.to_string()
+    }
+}
+
+fn to_upper_snake_case(name: &str) -> String {
+    let mut result = String::with_capacity(name.len());
+    let mut previous_is_lowercase = false;
+
+    for c in name.chars() {
+        if c.is_uppercase() && previous_is_lowercase {
+            result.push('_');
+        }
+
+        previous_is_lowercase = c.is_lowercase() || c.is_ascii_digit();
+        result.extend(c.to_uppercase());
+    }
+
+    result
+}
+
+fn to_upper_camel_case(name: &str) -> String {
+    let mut result = String::with_capacity(name.len());
+
+    for part in name.split('_') {
+        let mut chars = part.chars();
+
+        if let Some(first) = chars.next() {
+            result.extend(first.to_uppercase());
+            result.push_str(chars.as_str());
+        }
+    }
+
+    result
+}
@@ -0,0 +1,135 @@
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::{env, fs};
+
+const USAGE: &str = "\
+Generates Rust bindings for WGSL shaders for use with the empa crate.
+
+Usage: empa-bindgen [--output <FILE>] <SHADER>...
+
+When given multiple shaders, the bindings for each shader are wrapped in a module named after the
+shader's file name.
+
+Options:
+  -o, --output <FILE>  Write the bindings to FILE instead of to the standard output
+  -h, --help           Print this help message";
+
+fn main() -> ExitCode {
+    let mut inputs = Vec::new();
+    let mut output = None;
+    let mut args = env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+
+                return ExitCode::SUCCESS;
+            }
+            "-o" | "--output" => match args.next() {
+                Some(path) => output = Some(PathBuf::from(path)),
+                None => return usage_error("`--output` requires a file path"),
+            },
+            arg if arg.starts_with('-') => {
+                return usage_error(&format!("unknown option `{}`", arg));
+            }
+            _ => inputs.push(PathBuf::from(arg)),
+        }
+    }
+
+    if inputs.is_empty() {
+        return usage_error("no shaders given");
+    }
+
+    let mut bindings = String::from("// Generated by empa-bindgen; do not edit.\n\n");
+
+    for input in inputs.iter() {
+        let generated = match generate(input) {
+            Ok(generated) => generated,
+            Err(message) => {
+                eprintln!("error: {}", message);
+
+                return ExitCode::FAILURE;
+            }
+        };
+
+        if inputs.len() == 1 {
+            bindings.push_str(&generated);
+        } else {
+            bindings.push_str(&format!("pub mod {} {{\n", module_name(input)));
+
+            for line in generated.lines() {
+                if !line.is_empty() {
+                    bindings.push_str("    ");
+                    bindings.push_str(line);
+                }
+
+                bindings.push('\n');
+            }
+
+            bindings.push_str("}\n\n");
+        }
+    }
+
+    let result = match &output {
+        Some(path) => fs::write(path, bindings.trim_end().to_string() + "\n")
+            .map_err(|err| format!("failed to write `{}`: {}", path.display(), err)),
+        None => {
+            println!("{}", bindings.trim_end());
+
+            Ok(())
+        }
+    };
+
+    if let Err(message) = result {
+        eprintln!("error: {}", message);
+
+        return ExitCode::FAILURE;
+    }
+
+    ExitCode::SUCCESS
+}
+
+fn generate(path: &Path) -> Result<String, String> {
+    let source = fs::read_to_string(path)
+        .map_err(|err| format!("failed to read `{}`: {}", path.display(), err))?;
+
+    empa_bindgen::generate(&source).map_err(|err| {
+        format!(
+            "failed to generate bindings for `{}`:\n{}",
+            path.display(),
+            err
+        )
+    })
+}
+
+/// Derives a module name from the file name of a shader, e.g. `shadow_map` for `shadow-map.wgsl`.
+fn module_name(path: &Path) -> String {
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let mut name: String = stem
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+
+    if !name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+        name.insert(0, '_');
+    }
+
+    name
+}
+
+fn usage_error(message: &str) -> ExitCode {
+    eprintln!("error: {}\n\n{}", message, USAGE);
+
+    ExitCode::from(2)
+}
@@ -0,0 +1,288 @@
+//! Build script support for embedding WGSL shaders.
+//!
+//! The `shader_source!` macro embeds a shader that exists in the crate's source tree when the crate
+//! is compiled. This crate covers the cases the macro cannot: shaders that are themselves generated
+//! by a build script (e.g. assembled from templates), and Rust bindings for a shader's structs, bind
+//! groups and vertex inputs generated with `empa-bindgen` (see [Shader::bindings]), which the
+//! application then uses as regular Rust types. Compiling a shader in a build script resolves its
+//! `#include` directives, parses and validates it (failing the build on errors, just like the
+//! macro), and generates a Rust file in `OUT_DIR` that embeds the source with `include_str!`, along
+//! with the same reflection data that the macro generates:
+//!
+//! ```ignore
+//! // build.rs
+//! fn main() {
+//!     empa_build::compile_shader("src/shader.wgsl").unwrap();
+//! }
+//!
+//! // main.rs
+//! mod shader {
+//!     include!(concat!(env!("OUT_DIR"), "/shader.rs"));
+//! }
+//!
+//! let shader = device.create_shader_module(&shader::SHADER);
+//! let vertex_stage = VertexStageBuilder::begin(&shader, shader::VS_MAIN)
+//!     .vertex_layout::<Vertex>()
+//!     .finish();
+//! ```
+//!
+//! The generated file declares a `SHADER` constant for the `ShaderSource` and a typed constant for
+//! each entry point, named after the entry point in upper snake case (as with
+//! `shader_entry_points!`). Paths are relative to the directory that contains the crate's
+//! `Cargo.toml`, which is also the base path for resolving `#include` directives. The build script
+//! is rerun when any of the files that make up the shader change.
+
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use empa_reflect::{tokens, ShaderSource};
+use include_preprocessor::{
+    preprocess, Error as IppError, OutputSink, SearchPaths, SourceMappedChunk, SourceTracker,
+};
+use naga::valid::{Capabilities, ValidationFlags, Validator};
+use quote::quote;
+
+/// Error returned when a shader fails to compile.
+///
+/// Its [Debug](fmt::Debug) output is the same as its [Display](fmt::Display) output, so that
+/// diagnostics remain readable when a build script unwraps the result.
+#[derive(Clone)]
+pub struct BuildError {
+    message: String,
+}
+
+impl BuildError {
+    fn new(message: String) -> Self {
+        BuildError { message }
+    }
+
+    /// A description of the error; for parse and validation errors this includes an annotated
+    /// snippet of the shader source.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl fmt::Debug for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl Error for BuildError {}
+
+/// Compiles the shader at the `path` with the default options, see [Shader].
+///
+/// Returns the path of the generated Rust file.
+pub fn compile_shader<P: AsRef<Path>>(path: P) -> Result<PathBuf, BuildError> {
+    Shader::new(path).compile()
+}
+
+/// Configures the compilation of a shader in a build script.
+pub struct Shader {
+    path: PathBuf,
+    name: Option<String>,
+    bindings: bool,
+}
+
+impl Shader {
+    /// Creates a new shader compilation for the WGSL file at the `path`, relative to the directory
+    /// that contains the crate's `Cargo.toml`.
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Shader {
+            path: path.as_ref().to_path_buf(),
+            name: None,
+            bindings: false,
+        }
+    }
+
+    /// Sets the name of the generated file (`{name}.rs` in `OUT_DIR`).
+    ///
+    /// Defaults to the file name of the shader without its extension; set a name when compiling
+    /// multiple shaders with the same file name.
+    pub fn name(mut self, name: &str) -> Self {
+        self.name = Some(name.to_string());
+
+        self
+    }
+
+    /// Whether to also generate Rust types for the shader's structs, bind groups and vertex inputs
+    /// with `empa-bindgen`.
+    ///
+    /// Defaults to `false`.
+    pub fn bindings(mut self, bindings: bool) -> Self {
+        self.bindings = bindings;
+
+        self
+    }
+
+    /// Compiles the shader and writes the generated Rust file to `OUT_DIR`.
+    ///
+    /// Returns the path of the generated file. Must be called from a build script.
+    pub fn compile(self) -> Result<PathBuf, BuildError> {
+        let manifest_dir = env_path("CARGO_MANIFEST_DIR")?;
+        let out_dir = env_path("OUT_DIR")?;
+        let path = manifest_dir.join(&self.path);
+
+        let name = match self.name {
+            Some(name) => name,
+            None => path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .ok_or_else(|| {
+                    BuildError::new(format!("`{}` is not a file path", path.display()))
+                })?,
+        };
+
+        let mut search_paths = SearchPaths::new();
+
+        search_paths.push_base_path(manifest_dir.clone());
+
+        let mut tracked_paths = TrackedPaths(Vec::new());
+
+        let output = preprocess(
+            path.clone(),
+            search_paths,
+            Output(String::new()),
+            &mut tracked_paths,
+        )
+        .map_err(|err| preprocess_error(&path, err))?;
+
+        for tracked_path in tracked_paths.0.iter() {
+            println!("cargo:rerun-if-changed={}", tracked_path.display());
+        }
+
+        let source = output.0;
+
+        let shader_source = ShaderSource::parse(source.clone()).map_err(|err| {
+            BuildError::new(format!(
+                "failed to parse `{}`:\n{}",
+                path.display(),
+                err.emit_to_string(&source)
+            ))
+        })?;
+
+        let mut validator = Validator::new(
+            ValidationFlags::all() & !(ValidationFlags::EXPRESSIONS | ValidationFlags::BLOCKS),
+            Capabilities::all(),
+        );
+
+        if let Err(err) = validator.validate(shader_source.module()) {
+            return Err(BuildError::new(format!(
+                "failed to validate `{}`:\n{}",
+                path.display(),
+                err.emit_to_string(&source)
+            )));
+        }
+
+        // Embed the preprocessed source, as it may have been assembled from multiple files.
+        let source_path = out_dir.join(format!("{}.wgsl", name));
+
+        write(&source_path, &source)?;
+
+        let source_path = source_path.to_string_lossy().to_string();
+        let shader_source_tokens =
+            tokens::shader_source_tokens(&shader_source, quote!(include_str!(#source_path)));
+
+        let mut generated = format!(
+            "// Generated by empa-build from `{}`; do not edit.\n\n",
+            self.path.display()
+        );
+
+        generated.push_str(
+            &quote! {
+                pub const SHADER: empa::shader_module::ShaderSource = #shader_source_tokens;
+            }
+            .to_string(),
+        );
+        generated.push_str("\n\n");
+
+        if self.bindings {
+            // The bindings include the entry point constants.
+            let bindings = empa_bindgen::generate(&source).map_err(|err| {
+                BuildError::new(format!(
+                    "failed to generate bindings for `{}`:\n{}",
+                    path.display(),
+                    err
+                ))
+            })?;
+
+            generated.push_str(&bindings);
+        } else {
+            generated.push_str(&tokens::entry_point_constants_tokens(&shader_source).to_string());
+            generated.push('\n');
+        }
+
+        let generated_path = out_dir.join(format!("{}.rs", name));
+
+        write(&generated_path, &generated)?;
+
+        Ok(generated_path)
+    }
+}
+
+fn env_path(name: &str) -> Result<PathBuf, BuildError> {
+    std::env::var_os(name).map(PathBuf::from).ok_or_else(|| {
+        BuildError::new(format!(
+            "environment variable `{}` is not set; shaders must be compiled from a build script",
+            name
+        ))
+    })
+}
+
+fn write(path: &Path, contents: &str) -> Result<(), BuildError> {
+    fs::write(path, contents)
+        .map_err(|err| BuildError::new(format!("failed to write `{}`: {}", path.display(), err)))
+}
+
+fn preprocess_error(path: &Path, error: IppError) -> BuildError {
+    let message = match error {
+        IppError::FileNotFound(error) => format!(
+            "{}:{}: could not find file `{}`",
+            error.source_file().display(),
+            error.line_number() + 1,
+            error.included_path().display()
+        ),
+        IppError::IO(error) => error.to_string(),
+        IppError::Parse(error) => format!(
+            "{}:{}: {}",
+            error.source_file().display(),
+            error.line_number() + 1,
+            error.message()
+        ),
+    };
+
+    BuildError::new(format!(
+        "failed to preprocess `{}`:\n{}",
+        path.display(),
+        message
+    ))
+}
+
+struct Output(String);
+
+impl OutputSink for Output {
+    fn sink(&mut self, chunk: &str) {
+        self.0.push_str(chunk);
+    }
+
+    fn sink_source_mapped(&mut self, source_mapped_chunk: SourceMappedChunk) {
+        self.0.push_str(source_mapped_chunk.text());
+    }
+}
+
+struct TrackedPaths(Vec<PathBuf>);
+
+impl SourceTracker for TrackedPaths {
+    fn track(&mut self, path: &Path, _source: &str) {
+        self.0.push(path.to_path_buf());
+    }
+}
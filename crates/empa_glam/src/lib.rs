@@ -1,4 +1,6 @@
 use empa::abi;
+use empa::render_target::DepthConvention;
+use glam::f32::{Mat4, Vec4};
 
 pub trait ToAbi {
     type Abi: abi::Sized;
@@ -22,6 +24,14 @@ impl ToAbi for glam::f32::Vec3 {
     }
 }
 
+impl ToAbi for glam::f32::Vec3A {
+    type Abi = abi::Vec3<f32>;
+
+    fn to_abi(&self) -> Self::Abi {
+        abi::Vec3(self.x, self.y, self.z)
+    }
+}
+
 impl ToAbi for glam::f32::Vec4 {
     type Abi = abi::Vec4<f32>;
 
@@ -98,6 +108,18 @@ impl ToAbi for glam::f32::Mat3 {
     }
 }
 
+impl ToAbi for glam::f32::Mat3A {
+    type Abi = abi::Mat3x3;
+
+    fn to_abi(&self) -> Self::Abi {
+        abi::Mat3x3(
+            self.x_axis.to_abi(),
+            self.y_axis.to_abi(),
+            self.z_axis.to_abi(),
+        )
+    }
+}
+
 impl ToAbi for glam::f32::Mat4 {
     type Abi = abi::Mat4x4;
 
@@ -110,3 +132,43 @@ impl ToAbi for glam::f32::Mat4 {
         )
     }
 }
+
+/// Adapts a projection matrix that maps the near plane to a depth of `0.0` and the far plane to a
+/// depth of `1.0` (such as [Mat4::perspective_rh]) to the given depth `convention`.
+///
+/// Returns the `projection` unchanged for [DepthConvention::Standard]; for
+/// [DepthConvention::ReverseZ] the resulting matrix maps the near plane to `1.0` and the far plane
+/// to `0.0`.
+///
+/// ```ignore
+/// let convention = DepthConvention::ReverseZ;
+/// let projection = adapt_projection(Mat4::perspective_rh(fov_y, aspect, near, far), convention);
+///
+/// let depth_stencil_test = DepthStencilTest::read_write::<depth32float>()
+///     .depth_convention(convention);
+///
+/// let depth_attachment = DepthAttachment {
+///     image: depth_texture.attachable_image(),
+///     load_op: LoadOp::Clear(convention.clear_value()),
+///     store_op: StoreOp::Store,
+/// };
+/// ```
+///
+/// Note that glam also provides reverse-Z projections directly (e.g.
+/// [Mat4::perspective_infinite_reverse_rh]).
+pub fn adapt_projection(projection: Mat4, convention: DepthConvention) -> Mat4 {
+    match convention {
+        DepthConvention::Standard => projection,
+        DepthConvention::ReverseZ => {
+            // Maps clip-space `z` to `w - z`, which maps normalized depth `d` to `1.0 - d`.
+            let reverse = Mat4::from_cols(
+                Vec4::X,
+                Vec4::Y,
+                Vec4::new(0.0, 0.0, -1.0, 0.0),
+                Vec4::new(0.0, 0.0, 1.0, 1.0),
+            );
+
+            reverse * projection
+        }
+    }
+}
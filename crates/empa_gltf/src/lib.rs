@@ -0,0 +1,575 @@
+//! Loads glTF 2.0 meshes, materials and textures into `empa` buffers and textures.
+//!
+//! Vertex data is interleaved into a single vertex buffer per mesh primitive. The vertex type is
+//! supplied by the user by implementing [GltfVertex] for a `#[derive(Vertex, Clone, Copy)]` type,
+//! or [DefaultVertex] may be used:
+//!
+//! ```no_run
+//! # fn load(device: &empa::device::Device) -> Result<(), empa_gltf::LoadError> {
+//! use empa_gltf::DefaultVertex;
+//!
+//! let scene = empa_gltf::import::<DefaultVertex>(device, "model.gltf")?;
+//!
+//! for mesh in &scene.meshes {
+//!     for primitive in &mesh.primitives {
+//!         // Draw `primitive.mesh` with a pipeline that uses `DefaultVertex` and `IndexAny`...
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Only primitives with a triangle list topology are supported. Primitives without index data
+//! are given sequential indices, and all index data is converted to the smallest index format that
+//! can address the primitive's vertices (see [MeshIndices]).
+
+use std::path::Path;
+use std::{error, fmt};
+
+use empa::buffer;
+use empa::buffer::{Buffer, BufferUsages};
+use empa::device::Device;
+use empa::render_pipeline::Vertex;
+use empa::texture;
+use empa::texture::format::{rgba8unorm, rgba8unorm_srgb};
+use empa::texture::{ImageDataLayout, MipmapLevels, Texture2D, Texture2DDescriptor, TextureUsages};
+use empa::util::{Mesh, MeshIndices};
+use gltf::image::Format;
+use gltf::mesh::Mode;
+
+/// The usage flags of the vertex buffers created by the loader.
+pub type VertexUsage = BufferUsages!(Vertex);
+
+/// The usage flags of the index buffers created by the loader.
+pub type IndexUsage = BufferUsages!(Index);
+
+/// The usage flags of the textures created by the loader.
+pub type ImageUsage = TextureUsages!(TextureBinding | CopyDst);
+
+/// A texture loaded from a glTF image.
+///
+/// Images are loaded as `rgba8unorm` textures that may also be viewed as `rgba8unorm_srgb` (e.g.
+/// with [Texture2D::try_as_sampled_float]). Base color and emissive images store sRGB encoded
+/// color data, and should be viewed as `rgba8unorm_srgb`.
+pub type Image = Texture2D<rgba8unorm, ImageUsage>;
+
+/// The vertex attribute values of a single vertex, as read from a glTF mesh primitive.
+///
+/// Attributes that are not present in the primitive are `None`. All attribute values are
+/// converted to `f32` values; normalized integer attribute values are converted to the `[0, 1]`
+/// range.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct VertexAttributes {
+    pub position: [f32; 3],
+    pub normal: Option<[f32; 3]>,
+    pub tangent: Option<[f32; 4]>,
+    pub tex_coord_0: Option<[f32; 2]>,
+    pub tex_coord_1: Option<[f32; 2]>,
+    pub color_0: Option<[f32; 4]>,
+}
+
+/// A vertex type that can be constructed from glTF vertex attributes.
+pub trait GltfVertex: Vertex {
+    fn from_attributes(attributes: &VertexAttributes) -> Self;
+}
+
+/// A vertex type with a position, a normal and a texture coordinate.
+///
+/// Missing normals and texture coordinates default to zero vectors.
+#[derive(empa::render_pipeline::Vertex, Clone, Copy, PartialEq, Default, Debug)]
+pub struct DefaultVertex {
+    #[vertex_attribute(location = 0, format = "float32x3")]
+    pub position: [f32; 3],
+    #[vertex_attribute(location = 1, format = "float32x3")]
+    pub normal: [f32; 3],
+    #[vertex_attribute(location = 2, format = "float32x2")]
+    pub tex_coord: [f32; 2],
+}
+
+impl GltfVertex for DefaultVertex {
+    fn from_attributes(attributes: &VertexAttributes) -> Self {
+        DefaultVertex {
+            position: attributes.position,
+            normal: attributes.normal.unwrap_or_default(),
+            tex_coord: attributes.tex_coord_0.unwrap_or_default(),
+        }
+    }
+}
+
+/// The metallic-roughness material parameters of a glTF material.
+///
+/// Texture references are indices into [Scene::images].
+#[derive(Clone, PartialEq, Debug)]
+pub struct Material {
+    pub name: Option<String>,
+    pub base_color_factor: [f32; 4],
+    pub base_color_image: Option<usize>,
+    pub metallic_factor: f32,
+    pub roughness_factor: f32,
+    pub metallic_roughness_image: Option<usize>,
+    pub normal_image: Option<usize>,
+    pub occlusion_image: Option<usize>,
+    pub emissive_factor: [f32; 3],
+    pub emissive_image: Option<usize>,
+    pub double_sided: bool,
+}
+
+/// A part of a [GltfMesh] that is drawn with a single material.
+pub struct Primitive<V> {
+    pub mesh: Mesh<Buffer<[V], VertexUsage>, MeshIndices<IndexUsage>>,
+
+    /// The index of the primitive's material in [Scene::materials], if any.
+    pub material: Option<usize>,
+}
+
+pub struct GltfMesh<V> {
+    pub name: Option<String>,
+    pub primitives: Vec<Primitive<V>>,
+}
+
+/// The meshes, materials and images loaded from a glTF asset.
+///
+/// Meshes, materials and images keep the order (and therefore the indices) of the glTF document.
+pub struct Scene<V> {
+    pub meshes: Vec<GltfMesh<V>>,
+    pub materials: Vec<Material>,
+    pub images: Vec<Image>,
+}
+
+#[derive(Debug)]
+pub enum LoadError {
+    /// The asset could not be read or parsed.
+    Gltf(gltf::Error),
+
+    /// A mesh primitive has a topology other than a triangle list.
+    UnsupportedPrimitiveMode { mesh: usize, mode: Mode },
+
+    /// A mesh primitive has no position attribute.
+    MissingPositions { mesh: usize },
+
+    /// An image uses a pixel format other than 8-bit per channel R, RG, RGB or RGBA.
+    UnsupportedImageFormat { image: usize, format: Format },
+
+    /// The data for a buffer is missing or shorter than the buffer's declared length.
+    MissingBufferData { buffer: usize },
+
+    /// A mesh primitive contains an index that is not smaller than its vertex count.
+    IndexOutOfBounds {
+        mesh: usize,
+        index: u32,
+        vertex_count: u32,
+    },
+
+    /// The pixel data of an image does not match the image's dimensions.
+    InvalidImageData { image: usize },
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::Gltf(err) => err.fmt(f),
+            LoadError::UnsupportedPrimitiveMode { mesh, mode } => write!(
+                f,
+                "mesh `{}` contains a primitive with unsupported mode `{:?}`",
+                mesh, mode
+            ),
+            LoadError::MissingPositions { mesh } => {
+                write!(f, "mesh `{}` contains a primitive without positions", mesh)
+            }
+            LoadError::UnsupportedImageFormat { image, format } => {
+                write!(f, "image `{}` has unsupported format `{:?}`", image, format)
+            }
+            LoadError::MissingBufferData { buffer } => {
+                write!(
+                    f,
+                    "the data for buffer `{}` is missing or too short",
+                    buffer
+                )
+            }
+            LoadError::IndexOutOfBounds {
+                mesh,
+                index,
+                vertex_count,
+            } => write!(
+                f,
+                "mesh `{}` contains index `{}`, which is out of bounds for vertex count `{}`",
+                mesh, index, vertex_count
+            ),
+            LoadError::InvalidImageData { image } => {
+                write!(
+                    f,
+                    "the pixel data of image `{}` does not match its size",
+                    image
+                )
+            }
+        }
+    }
+}
+
+impl error::Error for LoadError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            LoadError::Gltf(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<gltf::Error> for LoadError {
+    fn from(err: gltf::Error) -> Self {
+        LoadError::Gltf(err)
+    }
+}
+
+/// Imports a glTF asset from the file system, including any external buffer and image files.
+pub fn import<V>(device: &Device, path: impl AsRef<Path>) -> Result<Scene<V>, LoadError>
+where
+    V: GltfVertex + Copy + 'static,
+{
+    let (document, buffers, images) = gltf::import(path)?;
+
+    load(device, &document, &buffers, &images)
+}
+
+/// Imports a glTF asset from a slice of bytes, e.g. the contents of a `.glb` file.
+///
+/// External buffers and images are resolved relative to the current working directory.
+pub fn import_slice<V>(device: &Device, bytes: &[u8]) -> Result<Scene<V>, LoadError>
+where
+    V: GltfVertex + Copy + 'static,
+{
+    let (document, buffers, images) = gltf::import_slice(bytes)?;
+
+    load(device, &document, &buffers, &images)
+}
+
+/// Loads the meshes, materials and images of an already imported glTF `document`.
+///
+/// The `buffers` and `images` must hold the data for the document's buffers and images, in
+/// document order (as returned by e.g. [gltf::import]).
+pub fn load<V>(
+    device: &Device,
+    document: &gltf::Document,
+    buffers: &[gltf::buffer::Data],
+    images: &[gltf::image::Data],
+) -> Result<Scene<V>, LoadError>
+where
+    V: GltfVertex + Copy + 'static,
+{
+    check_buffers(document, buffers)?;
+
+    let meshes = document
+        .meshes()
+        .map(|mesh| load_mesh(device, &mesh, buffers))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let materials = document.materials().map(|m| load_material(&m)).collect();
+
+    let images = images
+        .iter()
+        .enumerate()
+        .map(|(index, image)| load_image(device, index, image))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Scene {
+        meshes,
+        materials,
+        images,
+    })
+}
+
+fn check_buffers(
+    document: &gltf::Document,
+    buffers: &[gltf::buffer::Data],
+) -> Result<(), LoadError> {
+    for buffer in document.buffers() {
+        let index = buffer.index();
+
+        match buffers.get(index) {
+            Some(data) if data.len() >= buffer.length() => (),
+            _ => return Err(LoadError::MissingBufferData { buffer: index }),
+        }
+    }
+
+    Ok(())
+}
+
+fn load_mesh<V>(
+    device: &Device,
+    mesh: &gltf::Mesh,
+    buffers: &[gltf::buffer::Data],
+) -> Result<GltfMesh<V>, LoadError>
+where
+    V: GltfVertex + Copy + 'static,
+{
+    let mut primitives = Vec::new();
+
+    for primitive in mesh.primitives() {
+        let PrimitiveData {
+            vertices,
+            indices,
+            vertex_count,
+        } = read_primitive(mesh, &primitive, buffers)?;
+
+        let vertex_buffer = device.create_buffer(vertices, buffer::Usages::vertex());
+        let index_buffer =
+            MeshIndices::new(device, &indices, vertex_count, buffer::Usages::index());
+
+        primitives.push(Primitive {
+            mesh: Mesh::indexed(vertex_buffer, index_buffer),
+            material: primitive.material().index(),
+        });
+    }
+
+    Ok(GltfMesh {
+        name: mesh.name().map(|n| n.to_string()),
+        primitives,
+    })
+}
+
+struct PrimitiveData<V> {
+    vertices: Vec<V>,
+    indices: Vec<u32>,
+    vertex_count: u32,
+}
+
+fn read_primitive<V>(
+    mesh: &gltf::Mesh,
+    primitive: &gltf::Primitive,
+    buffers: &[gltf::buffer::Data],
+) -> Result<PrimitiveData<V>, LoadError>
+where
+    V: GltfVertex,
+{
+    let mode = primitive.mode();
+
+    if mode != Mode::Triangles {
+        return Err(LoadError::UnsupportedPrimitiveMode {
+            mesh: mesh.index(),
+            mode,
+        });
+    }
+
+    let reader = primitive.reader(|buffer| buffers.get(buffer.index()).map(|data| &data[..]));
+
+    let positions: Vec<[f32; 3]> = if let Some(positions) = reader.read_positions() {
+        positions.collect()
+    } else {
+        return Err(LoadError::MissingPositions { mesh: mesh.index() });
+    };
+
+    let mut normals = reader.read_normals();
+    let mut tangents = reader.read_tangents();
+    let mut tex_coords_0 = reader.read_tex_coords(0).map(|t| t.into_f32());
+    let mut tex_coords_1 = reader.read_tex_coords(1).map(|t| t.into_f32());
+    let mut colors_0 = reader.read_colors(0).map(|c| c.into_rgba_f32());
+
+    let vertices: Vec<V> = positions
+        .iter()
+        .map(|position| {
+            V::from_attributes(&VertexAttributes {
+                position: *position,
+                normal: normals.as_mut().and_then(|n| n.next()),
+                tangent: tangents.as_mut().and_then(|t| t.next()),
+                tex_coord_0: tex_coords_0.as_mut().and_then(|t| t.next()),
+                tex_coord_1: tex_coords_1.as_mut().and_then(|t| t.next()),
+                color_0: colors_0.as_mut().and_then(|c| c.next()),
+            })
+        })
+        .collect();
+
+    let vertex_count = vertices.len() as u32;
+
+    let indices: Vec<u32> = if let Some(indices) = reader.read_indices() {
+        indices.into_u32().collect()
+    } else {
+        (0..vertex_count).collect()
+    };
+
+    if let Some(&index) = indices.iter().find(|&&index| index >= vertex_count) {
+        return Err(LoadError::IndexOutOfBounds {
+            mesh: mesh.index(),
+            index,
+            vertex_count,
+        });
+    }
+
+    Ok(PrimitiveData {
+        vertices,
+        indices,
+        vertex_count,
+    })
+}
+
+fn load_material(material: &gltf::Material) -> Material {
+    let pbr = material.pbr_metallic_roughness();
+
+    Material {
+        name: material.name().map(|n| n.to_string()),
+        base_color_factor: pbr.base_color_factor(),
+        base_color_image: pbr
+            .base_color_texture()
+            .map(|info| info.texture().source().index()),
+        metallic_factor: pbr.metallic_factor(),
+        roughness_factor: pbr.roughness_factor(),
+        metallic_roughness_image: pbr
+            .metallic_roughness_texture()
+            .map(|info| info.texture().source().index()),
+        normal_image: material
+            .normal_texture()
+            .map(|normal| normal.texture().source().index()),
+        occlusion_image: material
+            .occlusion_texture()
+            .map(|occlusion| occlusion.texture().source().index()),
+        emissive_factor: material.emissive_factor(),
+        emissive_image: material
+            .emissive_texture()
+            .map(|info| info.texture().source().index()),
+        double_sided: material.double_sided(),
+    }
+}
+
+fn load_image(
+    device: &Device,
+    index: usize,
+    image: &gltf::image::Data,
+) -> Result<Image, LoadError> {
+    let pixels = &image.pixels;
+
+    let channels = match image.format {
+        Format::R8 => 1,
+        Format::R8G8 => 2,
+        Format::R8G8B8 => 3,
+        Format::R8G8B8A8 => 4,
+        format => {
+            return Err(LoadError::UnsupportedImageFormat {
+                image: index,
+                format,
+            })
+        }
+    };
+
+    if pixels.len() != image.width as usize * image.height as usize * channels {
+        return Err(LoadError::InvalidImageData { image: index });
+    }
+
+    let texels: Vec<[u8; 4]> = pixels
+        .chunks_exact(channels)
+        .map(|p| match *p {
+            [r] => [r, r, r, 255],
+            [r, g] => [r, g, 0, 255],
+            [r, g, b] => [r, g, b, 255],
+            [r, g, b, a] => [r, g, b, a],
+            _ => unreachable!(),
+        })
+        .collect();
+
+    let texture = device.create_texture_2d(&Texture2DDescriptor {
+        format: rgba8unorm,
+        usage: texture::Usages::texture_binding().and_copy_dst(),
+        view_formats: (rgba8unorm_srgb,),
+        width: image.width,
+        height: image.height,
+        layers: 1,
+        mipmap_levels: MipmapLevels::Partial(1),
+    });
+
+    device.queue().write_texture(
+        texture.image_copy_from_buffer_dst(0),
+        &texels,
+        ImageDataLayout {
+            blocks_per_row: image.width,
+            rows_per_image: image.height,
+        },
+    );
+
+    Ok(texture)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A single triangle with `u16` indices, with the buffer embedded as a data URI.
+    const TRIANGLE: &str = r#"{
+        "asset": { "version": "2.0" },
+        "buffers": [{ "byteLength": 44, "uri": "data:application/octet-stream;base64,DATA" }],
+        "bufferViews": [
+            { "buffer": 0, "byteOffset": 0, "byteLength": 36 },
+            { "buffer": 0, "byteOffset": 36, "byteLength": 6 }
+        ],
+        "accessors": [
+            {
+                "bufferView": 0,
+                "componentType": 5126,
+                "count": 3,
+                "type": "VEC3",
+                "min": [0.0, 0.0, 0.0],
+                "max": [1.0, 1.0, 0.0]
+            },
+            { "bufferView": 1, "componentType": 5123, "count": 3, "type": "SCALAR" }
+        ],
+        "meshes": [{ "primitives": [{ "attributes": { "POSITION": 0 }, "indices": 1 }] }]
+    }"#;
+
+    // Positions `[0, 0, 0]`, `[1, 0, 0]` and `[0, 1, 0]`, followed by indices `[0, 1, 2]`.
+    const VALID_DATA: &str = "AAAAAAAAAAAAAAAAAACAPwAAAAAAAAAAAAAAAAAAgD8AAAAAAAABAAIAAAA=";
+
+    // Positions as above, followed by indices `[0, 1, 5]`.
+    const OUT_OF_BOUNDS_DATA: &str = "AAAAAAAAAAAAAAAAAACAPwAAAAAAAAAAAAAAAAAAgD8AAAAAAAABAAUAAAA=";
+
+    fn import_triangle(data: &str) -> (gltf::Document, Vec<gltf::buffer::Data>) {
+        let source = TRIANGLE.replace("DATA", data);
+        let (document, buffers, _) = gltf::import_slice(source.as_bytes()).unwrap();
+
+        (document, buffers)
+    }
+
+    fn read_first_primitive(
+        document: &gltf::Document,
+        buffers: &[gltf::buffer::Data],
+    ) -> Result<PrimitiveData<DefaultVertex>, LoadError> {
+        let mesh = document.meshes().next().unwrap();
+        let primitive = mesh.primitives().next().unwrap();
+
+        read_primitive(&mesh, &primitive, buffers)
+    }
+
+    #[test]
+    fn read_triangle() {
+        let (document, buffers) = import_triangle(VALID_DATA);
+
+        check_buffers(&document, &buffers).unwrap();
+
+        let data = read_first_primitive(&document, &buffers).unwrap();
+
+        assert_eq!(data.vertex_count, 3);
+        assert_eq!(data.indices, vec![0, 1, 2]);
+        assert_eq!(data.vertices[1].position, [1.0, 0.0, 0.0]);
+        assert_eq!(data.vertices[2].normal, [0.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn missing_buffer_data() {
+        let (document, _) = import_triangle(VALID_DATA);
+
+        assert!(matches!(
+            check_buffers(&document, &[]),
+            Err(LoadError::MissingBufferData { buffer: 0 })
+        ));
+    }
+
+    #[test]
+    fn index_out_of_bounds() {
+        let (document, buffers) = import_triangle(OUT_OF_BOUNDS_DATA);
+
+        assert!(matches!(
+            read_first_primitive(&document, &buffers),
+            Err(LoadError::IndexOutOfBounds {
+                mesh: 0,
+                index: 5,
+                vertex_count: 3
+            })
+        ));
+    }
+}
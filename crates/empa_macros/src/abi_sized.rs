@@ -1,30 +1,86 @@
 use proc_macro2::TokenStream;
 use quote::{quote, quote_spanned, ToTokens};
 use syn::spanned::Spanned;
-use syn::{Data, DeriveInput};
+use syn::{Attribute, Data, DeriveInput, Field, Lit, Meta, NestedMeta};
+
+use crate::error_log::ErrorLog;
 
 pub fn expand_derive_sized(input: &DeriveInput) -> Result<TokenStream, String> {
     if let Data::Struct(data) = &input.data {
         let mod_path = quote!(empa::abi);
         let struct_name = &input.ident;
-
-        let recurse_len = data.fields.iter().map(|field| {
+        let mut log = ErrorLog::new();
+
+        if !input.attrs.iter().any(is_repr_c) {
+            log.log_error(format!(
+                "`Sized` can only be derived for a `#[repr(C)]` struct; add `#[repr(C)]` to `{}` so \
+                 that its fields are laid out in declaration order, as in WGSL.",
+                struct_name
+            ));
+        }
+
+        let fields: Vec<AbiField> = data
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(position, field)| AbiField::from_ast(field, position, &mut log))
+            .collect();
+
+        log.compile()?;
+
+        let recurse_len = fields.iter().map(|field| {
             let ty = &field.ty;
-            let span = field.span();
+            let span = field.span;
 
             quote_spanned! {span=>
                 <#ty as #mod_path::Sized>::LAYOUT.len()
             }
         });
 
-        let recurse_array = data.fields.iter().enumerate().map(|(position, field)| {
+        let recurse_check = fields.windows(2).map(|pair| {
+            let previous = &pair[0];
+            let field = &pair[1];
+
+            let previous_ident = &previous.ident;
+            let previous_ty = &previous.ty;
+            let ident = &field.ident;
             let ty = &field.ty;
-            let ident = field
-                .ident
-                .clone()
-                .map(|i| i.into_token_stream())
-                .unwrap_or(syn::Index::from(position).into_token_stream());
-            let span = field.span();
+            let span = field.span;
+
+            let previous_size = if let Some(pad_to) = previous.pad_to {
+                quote! {
+                    #mod_path::round_up(#pad_to, #mod_path::wgsl_size_of::<#previous_ty>())
+                }
+            } else {
+                quote!(#mod_path::wgsl_size_of::<#previous_ty>())
+            };
+
+            let message = format!(
+                "field `{}` of `{}` does not have the same offset as in WGSL: in Rust, the \
+                 previous field `{}` is followed by padding that WGSL would use for `{}` (e.g. a \
+                 `Vec3` is padded to 16 bytes in Rust, but only occupies 12 bytes in WGSL); add \
+                 the padding explicitly to the WGSL struct and annotate `{}` with \
+                 `#[abi(pad_to = 16)]` (or the size it is padded to), or reorder the fields",
+                field.name, struct_name, previous.name, field.name, previous.name
+            );
+
+            quote_spanned! {span=>
+                let previous_end = empa::offset_of!(#struct_name, #previous_ident) + #previous_size;
+                let expected_offset = #mod_path::round_up(
+                    #mod_path::wgsl_align_of::<#ty>(),
+                    previous_end,
+                );
+
+                if empa::offset_of!(#struct_name, #ident) != expected_offset {
+                    panic!(#message);
+                }
+            }
+        });
+
+        let recurse_array = fields.iter().map(|field| {
+            let ty = &field.ty;
+            let ident = &field.ident;
+            let span = field.span;
 
             quote_spanned! {span=>
                 let base_offset = empa::offset_of!(#struct_name, #ident);
@@ -53,6 +109,8 @@ pub fn expand_derive_sized(input: &DeriveInput) -> Result<TokenStream, String> {
                 const LAYOUT: &'static [#mod_path::MemoryUnit] = &{
                     const LEN: usize = #(#recurse_len)+*;
 
+                    #(#recurse_check)*
+
                     // Initialize array with temporary values;
                     let mut array = [#mod_path::MemoryUnit {
                         offset: 0,
@@ -65,7 +123,21 @@ pub fn expand_derive_sized(input: &DeriveInput) -> Result<TokenStream, String> {
 
                     array
                 };
+
+                const IS_STRUCT: bool = true;
+            }
+        };
+
+        // Evaluate the layout eagerly for non-generic structs, so that layout mismatches are
+        // reported where the struct is declared, rather than where the layout is first used.
+        let layout_check = if input.generics.params.is_empty() {
+            quote! {
+                const _: () = {
+                    let _layout = <#struct_name as #mod_path::Sized>::LAYOUT;
+                };
             }
+        } else {
+            TokenStream::new()
         };
 
         let generated = quote! {
@@ -76,6 +148,8 @@ pub fn expand_derive_sized(input: &DeriveInput) -> Result<TokenStream, String> {
                 #[allow(rust_2018_idioms)]
 
                 #impl_block
+
+                #layout_check
             };
         };
 
@@ -84,3 +158,91 @@ pub fn expand_derive_sized(input: &DeriveInput) -> Result<TokenStream, String> {
         Err("`Sized` can only be derived for a struct.".to_string())
     }
 }
+
+fn is_repr_c(attribute: &Attribute) -> bool {
+    if !attribute.path.is_ident("repr") {
+        return false;
+    }
+
+    if let Ok(Meta::List(list)) = attribute.parse_meta() {
+        list.nested.iter().any(|nested| {
+            if let NestedMeta::Meta(Meta::Path(path)) = nested {
+                path.is_ident("C")
+            } else {
+                false
+            }
+        })
+    } else {
+        false
+    }
+}
+
+struct AbiField {
+    ident: TokenStream,
+    name: String,
+    ty: syn::Type,
+    span: proc_macro2::Span,
+    pad_to: Option<usize>,
+}
+
+impl AbiField {
+    fn from_ast(ast: &Field, position: usize, log: &mut ErrorLog) -> Self {
+        let ident = ast
+            .ident
+            .clone()
+            .map(|i| i.into_token_stream())
+            .unwrap_or(syn::Index::from(position).into_token_stream());
+        let name = ast
+            .ident
+            .clone()
+            .map(|i| i.to_string())
+            .unwrap_or(position.to_string());
+
+        let mut pad_to = None;
+
+        for attr in ast.attrs.iter().filter(|a| a.path.is_ident("abi")) {
+            let meta_items: Vec<NestedMeta> = match attr.parse_meta() {
+                Ok(Meta::List(meta)) => meta.nested.iter().cloned().collect(),
+                _ => {
+                    log.log_error(format!("Malformed #[abi] attribute for field `{}`.", name));
+
+                    Vec::new()
+                }
+            };
+
+            for meta_item in meta_items.into_iter() {
+                match meta_item {
+                    NestedMeta::Meta(Meta::NameValue(m)) if m.path.is_ident("pad_to") => {
+                        let value = if let Lit::Int(i) = &m.lit {
+                            i.base10_parse::<usize>().ok()
+                        } else {
+                            None
+                        };
+
+                        match value {
+                            Some(value) if value > 0 && value % 4 == 0 => pad_to = Some(value),
+                            _ => log.log_error(format!(
+                                "Malformed #[abi] attribute for field `{}`: expected `pad_to` to \
+                                 be a positive multiple of `4`.",
+                                name
+                            )),
+                        }
+                    }
+                    _ => log.log_error(format!(
+                        "Malformed #[abi] attribute for field `{}`: unrecognized option `{}`.",
+                        name,
+                        meta_item.into_token_stream()
+                    )),
+                }
+            }
+        }
+
+        AbiField {
+            ident,
+            name,
+            ty: ast.ty.clone(),
+            span: ast.span(),
+            pad_to,
+        }
+    }
+}
@@ -1,5 +1,3 @@
-#![feature(proc_macro_span, track_path)]
-
 mod abi_sized;
 mod buffer_usage;
 mod error_log;
@@ -14,12 +12,33 @@ use proc_macro2;
 use quote::quote;
 use syn::{parse_macro_input, DeriveInput};
 
+/// Expands to the `empa::buffer::Usages` type with the given usage flags enabled, for use in type
+/// positions such as struct fields:
+///
+/// ```ignore
+/// struct AppState {
+///     uniforms: Buffer<Uniforms, BufferUsages!(UniformBinding | CopyDst)>,
+/// }
+/// ```
+///
+/// Valid flags are: `QueryResolve`, `Indirect`, `StorageBinding`, `UniformBinding`, `Vertex`,
+/// `Index`, `CopyDst`, `CopySrc`, `MapWrite`, `MapRead`.
 #[proc_macro]
 #[allow(nonstandard_style)]
 pub fn BufferUsages(input: TokenStream) -> TokenStream {
     buffer_usage::expand_buffer_usages(input)
 }
 
+/// Expands to the `empa::texture::Usages` type with the given usage flags enabled, for use in type
+/// positions such as struct fields:
+///
+/// ```ignore
+/// struct AppState {
+///     depth_texture: Texture2D<depth24plus, TextureUsages!(RenderAttachment | CopyDst)>,
+/// }
+/// ```
+///
+/// Valid flags are: `RenderAttachment`, `StorageBinding`, `TextureBinding`, `CopyDst`, `CopySrc`.
 #[proc_macro]
 #[allow(nonstandard_style)]
 pub fn TextureUsages(input: TokenStream) -> TokenStream {
@@ -31,6 +50,27 @@ pub fn shader_source(input: TokenStream) -> TokenStream {
     shader_source::expand_shader_source(input)
 }
 
+/// Declares a typed constant for each entry point in a WGSL shader source.
+///
+/// Takes the same path as [shader_source!]; the constants are named after the entry points in
+/// upper snake case:
+///
+/// ```ignore
+/// const SHADER: ShaderSource = shader_source!("shader.wgsl");
+///
+/// mod entry_points {
+///     empa::shader_module::shader_entry_points!("shader.wgsl");
+/// }
+///
+/// let vertex_stage = VertexStageBuilder::begin(&shader, entry_points::VERT_MAIN)
+///     .vertex_layout::<Vertex>()
+///     .finish();
+/// ```
+#[proc_macro]
+pub fn shader_entry_points(input: TokenStream) -> TokenStream {
+    shader_source::expand_shader_entry_points(input)
+}
+
 #[proc_macro_derive(PipelineConstants, attributes(constant_id))]
 pub fn derive_pipeline_constants(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
@@ -49,7 +89,7 @@ pub fn derive_resources(input: TokenStream) -> TokenStream {
         .into()
 }
 
-#[proc_macro_derive(Sized)]
+#[proc_macro_derive(Sized, attributes(abi))]
 pub fn derive_sized(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
 
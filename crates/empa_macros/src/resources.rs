@@ -97,6 +97,16 @@ pub fn expand_derive_resources(input: &DeriveInput) -> Result<TokenStream, Strin
             entries.push(tokens);
         }
 
+        let binding_names = (0..=max_binding).map(|i| {
+            if let Some(field) = resource_fields.get(&i) {
+                let name = &field.name;
+
+                quote!(Some(#name))
+            } else {
+                quote!(None)
+            }
+        });
+
         let iter_len = max_binding + 1;
         let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
 
@@ -105,6 +115,8 @@ pub fn expand_derive_resources(input: &DeriveInput) -> Result<TokenStream, Strin
             unsafe impl #impl_generics #mod_path::Resources for #struct_name #ty_generics #where_clause {
                 type Layout = (#(#bindings,)*);
 
+                const BINDING_NAMES: &'static [Option<&'static str>] = &[#(#binding_names),*];
+
                 type ToEntries<'__a> = [#mod_path::BindGroupEntry<'__a>; #iter_len] where Self: '__a;
 
                 fn to_entries<'__a>(&'__a self) -> Self::ToEntries<'__a> {
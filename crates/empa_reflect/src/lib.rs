@@ -1,11 +1,15 @@
 use std::convert::TryFrom;
 use std::ops::Deref;
 
+pub use empa_abi::reflect::{MemoryUnit, MemoryUnitLayout};
 use naga::front::wgsl;
 use naga::proc::IndexableLength;
 use naga::{AddressSpace, Module, Override, ScalarKind};
 pub use wgsl::ParseError;
 
+#[cfg(feature = "tokens")]
+pub mod tokens;
+
 #[derive(Clone, Debug)]
 pub struct ShaderSource {
     source: String,
@@ -32,20 +36,35 @@ impl ShaderSource {
             }
         }
 
-        let constants = module.overrides.iter().map(|(_, c)| {
-            let ty = module.types.get_handle(c.ty).unwrap();
+        let constants = module
+            .overrides
+            .iter()
+            .map(|(_, c)| {
+                let ty = module.types.get_handle(c.ty).unwrap();
 
-            Constant {
-                identifier: ConstantIdentifier::from_naga(c),
-                constant_type: ConstantType::from_naga(ty),
-                required: c.init.is_none(),
-            }
-        }).collect();
+                Constant {
+                    identifier: ConstantIdentifier::from_naga(c),
+                    constant_type: ConstantType::from_naga(ty),
+                    required: c.init.is_none(),
+                }
+            })
+            .collect();
+
+        let mut layouter = naga::proc::Layouter::default();
+
+        layouter.update(module.to_ctx()).unwrap();
 
         let mut entry_points = Vec::new();
 
-        for entry_point in module.entry_points.iter() {
-            entry_points.push(EntryPoint::try_from_naga(&module, entry_point).unwrap());
+        for naga_entry_point in module.entry_points.iter() {
+            let mut entry_point = EntryPoint::try_from_naga(&module, naga_entry_point).unwrap();
+
+            if entry_point.stage == ShaderStage::Compute {
+                entry_point.workgroup_variables =
+                    resolve_workgroup_variables(&module, &layouter, &naga_entry_point.function);
+            }
+
+            entry_points.push(entry_point);
         }
 
         Ok(ShaderSource {
@@ -79,6 +98,7 @@ impl ShaderSource {
 }
 
 #[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ConstantIdentifier {
     Number(u32),
     Name(String),
@@ -89,12 +109,18 @@ impl ConstantIdentifier {
         if let Some(id) = value.id {
             ConstantIdentifier::Number(id as u32)
         } else {
-            ConstantIdentifier::Name(value.name.clone().expect("override constant should have name or ID"))
+            ConstantIdentifier::Name(
+                value
+                    .name
+                    .clone()
+                    .expect("override constant should have name or ID"),
+            )
         }
     }
 }
 
 #[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Constant {
     identifier: ConstantIdentifier,
     constant_type: ConstantType,
@@ -116,6 +142,7 @@ impl Constant {
 }
 
 #[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ConstantType {
     Float,
     Bool,
@@ -131,7 +158,7 @@ impl ConstantType {
                 ScalarKind::Uint => ConstantType::UnsignedInteger,
                 ScalarKind::Float => ConstantType::Float,
                 ScalarKind::Bool => ConstantType::Bool,
-                _ => unreachable!("constant type must be concrete")
+                _ => unreachable!("constant type must be concrete"),
             }
         } else {
             unreachable!("constant type must be scalar");
@@ -140,6 +167,7 @@ impl ConstantType {
 }
 
 #[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ShaderStage {
     Vertex,
     Fragment,
@@ -157,6 +185,7 @@ impl From<&'_ naga::ShaderStage> for ShaderStage {
 }
 
 #[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ShaderResourceBinding {
     group: u32,
     binding: u32,
@@ -178,6 +207,7 @@ impl ShaderResourceBinding {
 }
 
 #[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BindingType {
     Texture1D(TexelType),
     Texture2D(TexelType),
@@ -366,6 +396,7 @@ impl BindingType {
 }
 
 #[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TexelType {
     Float,
     UnfilterableFloat,
@@ -390,6 +421,7 @@ impl TryFrom<naga::ScalarKind> for TexelType {
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[allow(non_camel_case_types)]
 pub enum StorageTextureFormat {
     rgba8unorm,
@@ -437,6 +469,7 @@ impl TryFrom<naga::StorageFormat> for StorageTextureFormat {
 }
 
 #[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SizedBufferLayout(Vec<MemoryUnit>);
 
 impl SizedBufferLayout {
@@ -462,6 +495,7 @@ impl SizedBufferLayout {
 }
 
 #[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UnsizedBufferLayout {
     sized_head: Vec<MemoryUnit>,
     unsized_tail: Option<Vec<MemoryUnit>>,
@@ -493,11 +527,15 @@ impl UnsizedBufferLayout {
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EntryPoint {
     name: String,
     stage: ShaderStage,
     input_bindings: Vec<EntryPointBinding>,
     output_bindings: Vec<EntryPointBinding>,
+    workgroup_size: [u32; 3],
+    workgroup_variables: Vec<WorkgroupVariable>,
+    uses_subgroups: bool,
 }
 
 impl EntryPoint {
@@ -516,6 +554,53 @@ impl EntryPoint {
     pub fn output_bindings(&self) -> &[EntryPointBinding] {
         &self.output_bindings
     }
+
+    /// The workgroup size declared for a compute entry point; `[0, 0, 0]` for other stages.
+    pub fn workgroup_size(&self) -> [u32; 3] {
+        self.workgroup_size
+    }
+
+    /// The `workgroup` address space variables that are used by a compute entry point (directly
+    /// or through the functions it calls); empty for other stages.
+    pub fn workgroup_variables(&self) -> &[WorkgroupVariable] {
+        &self.workgroup_variables
+    }
+
+    /// The total amount of workgroup storage in bytes that a compute entry point uses, as counted
+    /// against the `max_compute_workgroup_storage_size` limit.
+    ///
+    /// The size of each variable is rounded up to a multiple of `16` bytes.
+    pub fn workgroup_storage_size(&self) -> u32 {
+        self.workgroup_variables
+            .iter()
+            .map(|variable| variable.size.div_ceil(16) * 16)
+            .sum()
+    }
+
+    /// Whether the entry point uses subgroup built-in values or subgroup operations (directly or
+    /// through the functions it calls), which require the device's subgroups feature.
+    pub fn uses_subgroups(&self) -> bool {
+        self.uses_subgroups
+    }
+}
+
+/// A variable in the `workgroup` address space.
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WorkgroupVariable {
+    name: Option<String>,
+    size: u32,
+}
+
+impl WorkgroupVariable {
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// The size of the variable in bytes.
+    pub fn size(&self) -> u32 {
+        self.size
+    }
 }
 
 impl EntryPoint {
@@ -595,11 +680,15 @@ impl EntryPoint {
             stage: ShaderStage::from(&entry_point.stage),
             input_bindings,
             output_bindings,
+            workgroup_size: entry_point.workgroup_size,
+            workgroup_variables: Vec::new(),
+            uses_subgroups: resolve_uses_subgroups(module, entry_point),
         })
     }
 }
 
 #[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EntryPointBinding {
     location: u32,
     binding_type: EntryPointBindingType,
@@ -642,6 +731,7 @@ impl EntryPointBinding {
 }
 
 #[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum EntryPointBindingType {
     SignedInteger,
     SignedIntegerVector2,
@@ -662,6 +752,28 @@ pub enum EntryPointBindingType {
 }
 
 impl EntryPointBindingType {
+    /// The number of scalar components of the binding type (`1` for scalar types).
+    pub fn component_count(&self) -> u32 {
+        match self {
+            EntryPointBindingType::SignedInteger
+            | EntryPointBindingType::UnsignedInteger
+            | EntryPointBindingType::Float
+            | EntryPointBindingType::HalfFloat => 1,
+            EntryPointBindingType::SignedIntegerVector2
+            | EntryPointBindingType::UnsignedIntegerVector2
+            | EntryPointBindingType::FloatVector2
+            | EntryPointBindingType::HalfFloatVector2 => 2,
+            EntryPointBindingType::SignedIntegerVector3
+            | EntryPointBindingType::UnsignedIntegerVector3
+            | EntryPointBindingType::FloatVector3
+            | EntryPointBindingType::HalfFloatVector3 => 3,
+            EntryPointBindingType::SignedIntegerVector4
+            | EntryPointBindingType::UnsignedIntegerVector4
+            | EntryPointBindingType::FloatVector4
+            | EntryPointBindingType::HalfFloatVector4 => 4,
+        }
+    }
+
     pub fn is_float(&self) -> bool {
         match self {
             EntryPointBindingType::Float
@@ -808,6 +920,7 @@ impl TryFrom<&'_ naga::TypeInner> for EntryPointBindingType {
 }
 
 #[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Interpolation {
     Perspective,
     Linear,
@@ -825,6 +938,7 @@ impl From<naga::Interpolation> for Interpolation {
 }
 
 #[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Sampling {
     Center,
     Centroid,
@@ -841,61 +955,150 @@ impl From<naga::Sampling> for Sampling {
     }
 }
 
-#[derive(Clone, PartialEq, Eq, Debug)]
-pub struct MemoryUnit {
-    pub offset: usize,
-    pub layout: MemoryUnitLayout,
+fn resolve_workgroup_variables(
+    module: &Module,
+    layouter: &naga::proc::Layouter,
+    function: &naga::Function,
+) -> Vec<WorkgroupVariable> {
+    fn collect_calls(block: &naga::Block, sink: &mut Vec<naga::Handle<naga::Function>>) {
+        for statement in block.iter() {
+            match statement {
+                naga::Statement::Block(block) => collect_calls(block, sink),
+                naga::Statement::If { accept, reject, .. } => {
+                    collect_calls(accept, sink);
+                    collect_calls(reject, sink);
+                }
+                naga::Statement::Switch { cases, .. } => {
+                    for case in cases {
+                        collect_calls(&case.body, sink);
+                    }
+                }
+                naga::Statement::Loop {
+                    body, continuing, ..
+                } => {
+                    collect_calls(body, sink);
+                    collect_calls(continuing, sink);
+                }
+                naga::Statement::Call { function, .. } => {
+                    if !sink.contains(function) {
+                        sink.push(*function);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn collect_globals(
+        module: &Module,
+        function: &naga::Function,
+        visited: &mut Vec<naga::Handle<naga::Function>>,
+        sink: &mut Vec<naga::Handle<naga::GlobalVariable>>,
+    ) {
+        for (_, expression) in function.expressions.iter() {
+            if let naga::Expression::GlobalVariable(handle) = expression {
+                let global = &module.global_variables[*handle];
+
+                if global.space == AddressSpace::WorkGroup && !sink.contains(handle) {
+                    sink.push(*handle);
+                }
+            }
+        }
+
+        let mut calls = Vec::new();
+
+        collect_calls(&function.body, &mut calls);
+
+        for call in calls {
+            if !visited.contains(&call) {
+                visited.push(call);
+
+                collect_globals(module, &module.functions[call], visited, sink);
+            }
+        }
+    }
+
+    let mut globals = Vec::new();
+
+    collect_globals(module, function, &mut Vec::new(), &mut globals);
+
+    globals
+        .into_iter()
+        .map(|handle| {
+            let global = &module.global_variables[handle];
+
+            WorkgroupVariable {
+                name: global.name.clone(),
+                size: layouter[global.ty].size,
+            }
+        })
+        .collect()
 }
 
-#[derive(Clone, PartialEq, Eq, Debug)]
-pub enum MemoryUnitLayout {
-    Float,
-    FloatArray(usize),
-    FloatVector2,
-    FloatVector2Array(usize),
-    FloatVector3,
-    FloatVector3Array(usize),
-    FloatVector4,
-    FloatVector4Array(usize),
-    Integer,
-    IntegerArray(usize),
-    IntegerVector2,
-    IntegerVector2Array(usize),
-    IntegerVector3,
-    IntegerVector3Array(usize),
-    IntegerVector4,
-    IntegerVector4Array(usize),
-    UnsignedInteger,
-    UnsignedIntegerArray(usize),
-    UnsignedIntegerVector2,
-    UnsignedIntegerVector2Array(usize),
-    UnsignedIntegerVector3,
-    UnsignedIntegerVector3Array(usize),
-    UnsignedIntegerVector4,
-    UnsignedIntegerVector4Array(usize),
-    Matrix2x2,
-    Matrix2x2Array(usize),
-    Matrix2x3,
-    Matrix2x3Array(usize),
-    Matrix2x4,
-    Matrix2x4Array(usize),
-    Matrix3x2,
-    Matrix3x2Array(usize),
-    Matrix3x3,
-    Matrix3x3Array(usize),
-    Matrix3x4,
-    Matrix3x4Array(usize),
-    Matrix4x2,
-    Matrix4x2Array(usize),
-    Matrix4x3,
-    Matrix4x3Array(usize),
-    Matrix4x4,
-    Matrix4x4Array(usize),
-    ComplexArray {
-        units: Vec<MemoryUnit>,
-        stride: usize,
-        len: usize,
-    },
+fn resolve_uses_subgroups(module: &Module, entry_point: &naga::EntryPoint) -> bool {
+    fn is_subgroup_builtin(binding: Option<&naga::Binding>) -> bool {
+        matches!(
+            binding,
+            Some(naga::Binding::BuiltIn(
+                naga::BuiltIn::NumSubgroups
+                    | naga::BuiltIn::SubgroupId
+                    | naga::BuiltIn::SubgroupSize
+                    | naga::BuiltIn::SubgroupInvocationId
+            ))
+        )
+    }
+
+    fn block_uses_subgroups(
+        module: &Module,
+        block: &naga::Block,
+        visited: &mut Vec<naga::Handle<naga::Function>>,
+    ) -> bool {
+        block.iter().any(|statement| match statement {
+            naga::Statement::SubgroupBallot { .. }
+            | naga::Statement::SubgroupGather { .. }
+            | naga::Statement::SubgroupCollectiveOperation { .. } => true,
+            naga::Statement::Block(block) => block_uses_subgroups(module, block, visited),
+            naga::Statement::If { accept, reject, .. } => {
+                block_uses_subgroups(module, accept, visited)
+                    || block_uses_subgroups(module, reject, visited)
+            }
+            naga::Statement::Switch { cases, .. } => cases
+                .iter()
+                .any(|case| block_uses_subgroups(module, &case.body, visited)),
+            naga::Statement::Loop {
+                body, continuing, ..
+            } => {
+                block_uses_subgroups(module, body, visited)
+                    || block_uses_subgroups(module, continuing, visited)
+            }
+            naga::Statement::Call { function, .. } => {
+                if visited.contains(function) {
+                    false
+                } else {
+                    visited.push(*function);
+
+                    block_uses_subgroups(module, &module.functions[*function].body, visited)
+                }
+            }
+            _ => false,
+        })
+    }
+
+    let uses_builtin = entry_point.function.arguments.iter().any(|argument| {
+        if is_subgroup_builtin(argument.binding.as_ref()) {
+            return true;
+        }
+
+        if let naga::TypeInner::Struct { members, .. } = &module.types[argument.ty].inner {
+            members
+                .iter()
+                .any(|member| is_subgroup_builtin(member.binding.as_ref()))
+        } else {
+            false
+        }
+    });
+
+    uses_builtin || block_uses_subgroups(module, &entry_point.function.body, &mut Vec::new())
 }
 
 fn collect_units(
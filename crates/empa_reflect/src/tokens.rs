@@ -0,0 +1,803 @@
+//! Generates the tokens of the static shader reflection data that the empa crate embeds for a
+//! [ShaderSource], enabled with the `tokens` feature.
+//!
+//! Shared by the `shader_source!` and `shader_entry_points!` macros and by build script code
+//! generation, so that all of these embed the same reflection data.
+
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::quote;
+
+use crate::{
+    BindingType, ConstantIdentifier, ConstantType, EntryPointBinding, EntryPointBindingType,
+    Interpolation, MemoryUnit, MemoryUnitLayout, Sampling, ShaderSource, ShaderStage,
+    SizedBufferLayout, StorageTextureFormat, TexelType, UnsizedBufferLayout,
+};
+
+/// Generates an expression that evaluates to an `empa::shader_module::ShaderSource` for the
+/// `shader_source`.
+///
+/// The `source` tokens must be an expression that evaluates to the `&'static str` source text of
+/// the `shader_source`, e.g. a string literal or an `include_str!` invocation.
+pub fn shader_source_tokens(shader_source: &ShaderSource, source: TokenStream) -> TokenStream {
+    let mod_path = quote!(empa::shader_module);
+
+    let resource_bindings = shader_source.resource_bindings().iter().map(|b| {
+        let group = b.group();
+        let binding = b.binding();
+        let binding_type = binding_type_tokens(b.binding_type());
+
+        quote! {
+            #mod_path::StaticResourceBinding {
+                group: #group,
+                binding: #binding,
+                binding_type: #binding_type
+            }
+        }
+    });
+
+    let constants = shader_source.constants().iter().map(|c| {
+        let identifier = match c.identifier() {
+            ConstantIdentifier::Number(n) => {
+                quote!(empa::pipeline_constants::PipelineConstantIdentifier::Number(#n))
+            }
+            ConstantIdentifier::Name(n) => {
+                quote!(empa::pipeline_constants::PipelineConstantIdentifier::Name(#n))
+            }
+        };
+        let constant_type = constant_type_tokens(c.constant_type());
+        let required = c.required();
+
+        quote! {
+            #mod_path::StaticConstantDescriptor {
+                identifier: #identifier,
+                constant_type: #constant_type,
+                required: #required,
+            }
+        }
+    });
+
+    let entry_points = shader_source.entry_points().iter().map(|e| {
+        let name = e.name();
+        let stage = shader_stage_tokens(e.stage());
+        let input_bindings = e.input_bindings().iter().map(entry_point_binding_tokens);
+        let output_bindings = e.output_bindings().iter().map(entry_point_binding_tokens);
+        let [workgroup_size_x, workgroup_size_y, workgroup_size_z] = e.workgroup_size();
+        let uses_subgroups = e.uses_subgroups();
+        let workgroup_variables = e.workgroup_variables().iter().map(|v| {
+            let name = match v.name() {
+                Some(name) => quote!(Some(#name)),
+                None => quote!(None),
+            };
+            let size = v.size();
+
+            quote! {
+                #mod_path::StaticWorkgroupVariable {
+                    name: #name,
+                    size: #size,
+                }
+            }
+        });
+
+        quote! {
+            #mod_path::StaticEntryPoint {
+                name: #name,
+                stage: #stage,
+                input_bindings: &[#(#input_bindings),*],
+                output_bindings: &[#(#output_bindings),*],
+                workgroup_size: [#workgroup_size_x, #workgroup_size_y, #workgroup_size_z],
+                workgroup_variables: &[#(#workgroup_variables),*],
+                uses_subgroups: #uses_subgroups,
+            }
+        }
+    });
+
+    quote! {
+        #mod_path::ShaderSource::from_static(#mod_path::StaticShaderSource {
+            source: #source,
+            resource_bindings: &[#(#resource_bindings),*],
+            constants: &[#(#constants),*],
+            entry_points: &[#(#entry_points),*]
+        })
+    }
+}
+
+/// Generates a typed constant for each entry point in the `shader_source`, named after the entry
+/// point in upper snake case.
+pub fn entry_point_constants_tokens(shader_source: &ShaderSource) -> TokenStream {
+    let mod_path = quote!(empa::shader_module);
+
+    let entry_points = shader_source.entry_points().iter().map(|e| {
+        let name = e.name();
+        let ident = Ident::new(&to_upper_snake_case(name), Span::call_site());
+        let ty = match e.stage() {
+            ShaderStage::Vertex => quote!(#mod_path::VertexEntryPoint),
+            ShaderStage::Fragment => quote!(#mod_path::FragmentEntryPoint),
+            ShaderStage::Compute => quote!(#mod_path::ComputeEntryPoint),
+        };
+
+        quote! {
+            pub const #ident: #ty = #ty::new_unchecked(#name);
+        }
+    });
+
+    quote! {
+        #(#entry_points)*
+    }
+}
+
+fn to_upper_snake_case(name: &str) -> String {
+    let mut result = String::with_capacity(name.len());
+    let mut previous_is_lowercase = false;
+
+    for c in name.chars() {
+        if c.is_uppercase() && previous_is_lowercase {
+            result.push('_');
+        }
+
+        previous_is_lowercase = c.is_lowercase() || c.is_ascii_digit();
+        result.extend(c.to_uppercase());
+    }
+
+    result
+}
+
+fn binding_type_tokens(binding_type: &BindingType) -> TokenStream {
+    let mod_path = quote!(empa::resource_binding);
+
+    match binding_type {
+        BindingType::Texture1D(texel_type) => {
+            let texel_type = texel_type_tokens(*texel_type);
+
+            quote!(#mod_path::BindingType::Texture1D(#texel_type))
+        }
+        BindingType::Texture2D(texel_type) => {
+            let texel_type = texel_type_tokens(*texel_type);
+
+            quote!(#mod_path::BindingType::Texture2D(#texel_type))
+        }
+        BindingType::Texture3D(texel_type) => {
+            let texel_type = texel_type_tokens(*texel_type);
+
+            quote!(#mod_path::BindingType::Texture3D(#texel_type))
+        }
+        BindingType::Texture2DArray(texel_type) => {
+            let texel_type = texel_type_tokens(*texel_type);
+
+            quote!(#mod_path::BindingType::Texture2DArray(#texel_type))
+        }
+        BindingType::TextureCube(texel_type) => {
+            let texel_type = texel_type_tokens(*texel_type);
+
+            quote!(#mod_path::BindingType::TextureCube(#texel_type))
+        }
+        BindingType::TextureCubeArray(texel_type) => {
+            let texel_type = texel_type_tokens(*texel_type);
+
+            quote!(#mod_path::BindingType::TextureCubeArray(#texel_type))
+        }
+        BindingType::TextureMultisampled2D(texel_type) => {
+            let texel_type = texel_type_tokens(*texel_type);
+
+            quote!(#mod_path::BindingType::TextureMultisampled2D(#texel_type))
+        }
+        BindingType::TextureDepth2D => {
+            quote!(#mod_path::BindingType::TextureDepth2D)
+        }
+        BindingType::TextureDepth2DArray => {
+            quote!(#mod_path::BindingType::TextureDepth2DArray)
+        }
+        BindingType::TextureDepthCube => {
+            quote!(#mod_path::BindingType::TextureDepthCube)
+        }
+        BindingType::TextureDepthCubeArray => {
+            quote!(#mod_path::BindingType::TextureDepthCubeArray)
+        }
+        BindingType::TextureDepthMultisampled2D => {
+            quote!(#mod_path::BindingType::TextureDepthMultisampled2D)
+        }
+        BindingType::StorageTexture1D(storage_format) => {
+            let storage_format = storage_format_tokens(*storage_format);
+
+            quote!(#mod_path::BindingType::StorageTexture1D(#storage_format))
+        }
+        BindingType::StorageTexture2D(storage_format) => {
+            let storage_format = storage_format_tokens(*storage_format);
+
+            quote!(#mod_path::BindingType::StorageTexture2D(#storage_format))
+        }
+        BindingType::StorageTexture2DArray(storage_format) => {
+            let storage_format = storage_format_tokens(*storage_format);
+
+            quote!(#mod_path::BindingType::StorageTexture2DArray(#storage_format))
+        }
+        BindingType::StorageTexture3D(storage_format) => {
+            let storage_format = storage_format_tokens(*storage_format);
+
+            quote!(#mod_path::BindingType::StorageTexture3D(#storage_format))
+        }
+        BindingType::FilteringSampler => {
+            quote!(#mod_path::BindingType::FilteringSampler)
+        }
+        BindingType::NonFilteringSampler => {
+            quote!(#mod_path::BindingType::NonFilteringSampler)
+        }
+        BindingType::ComparisonSampler => {
+            quote!(#mod_path::BindingType::ComparisonSampler)
+        }
+        BindingType::Uniform(layout) => {
+            let layout = sized_buffer_layout_tokens(layout);
+
+            quote!(#mod_path::BindingType::Uniform(#layout))
+        }
+        BindingType::Storage(layout) => {
+            let layout = unsized_buffer_layout_tokens(layout);
+
+            quote!(#mod_path::BindingType::Storage(#layout))
+        }
+        BindingType::ReadOnlyStorage(layout) => {
+            let layout = unsized_buffer_layout_tokens(layout);
+
+            quote!(#mod_path::BindingType::ReadOnlyStorage(#layout))
+        }
+    }
+}
+
+fn texel_type_tokens(texel_type: TexelType) -> TokenStream {
+    let mod_path = quote!(empa::resource_binding);
+
+    match texel_type {
+        TexelType::Float => {
+            quote!(#mod_path::TexelType::Float)
+        }
+        TexelType::UnfilterableFloat => {
+            quote!(#mod_path::TexelType::UnfilterableFloat)
+        }
+        TexelType::Integer => {
+            quote!(#mod_path::TexelType::Integer)
+        }
+        TexelType::UnsignedInteger => {
+            quote!(#mod_path::TexelType::UnsignedInteger)
+        }
+    }
+}
+
+fn storage_format_tokens(storage_format: StorageTextureFormat) -> TokenStream {
+    let mod_path = quote!(empa::texture::format);
+
+    match storage_format {
+        StorageTextureFormat::rgba8unorm => {
+            quote!(<#mod_path::rgba8unorm as #mod_path::TextureFormat>::FORMAT_ID)
+        }
+        StorageTextureFormat::rgba8snorm => {
+            quote!(<#mod_path::rgba8snorm as #mod_path::TextureFormat>::FORMAT_ID)
+        }
+        StorageTextureFormat::rgba8uint => {
+            quote!(<#mod_path::rgba8uint as #mod_path::TextureFormat>::FORMAT_ID)
+        }
+        StorageTextureFormat::rgba8sint => {
+            quote!(<#mod_path::rgba8sint as #mod_path::TextureFormat>::FORMAT_ID)
+        }
+        StorageTextureFormat::rgba16uint => {
+            quote!(<#mod_path::rgba16uint as #mod_path::TextureFormat>::FORMAT_ID)
+        }
+        StorageTextureFormat::rgba16sint => {
+            quote!(<#mod_path::rgba16sint as #mod_path::TextureFormat>::FORMAT_ID)
+        }
+        StorageTextureFormat::rgba16float => {
+            quote!(<#mod_path::rgba16float as #mod_path::TextureFormat>::FORMAT_ID)
+        }
+        StorageTextureFormat::r32uint => {
+            quote!(<#mod_path::r32uint as #mod_path::TextureFormat>::FORMAT_ID)
+        }
+        StorageTextureFormat::r32sint => {
+            quote!(<#mod_path::r32sint as #mod_path::TextureFormat>::FORMAT_ID)
+        }
+        StorageTextureFormat::r32float => {
+            quote!(<#mod_path::r32float as #mod_path::TextureFormat>::FORMAT_ID)
+        }
+        StorageTextureFormat::rg32uint => {
+            quote!(<#mod_path::rg32uint as #mod_path::TextureFormat>::FORMAT_ID)
+        }
+        StorageTextureFormat::rg32sint => {
+            quote!(<#mod_path::rg32sint as #mod_path::TextureFormat>::FORMAT_ID)
+        }
+        StorageTextureFormat::rg32float => {
+            quote!(<#mod_path::rg32float as #mod_path::TextureFormat>::FORMAT_ID)
+        }
+        StorageTextureFormat::rgba32uint => {
+            quote!(<#mod_path::rgba32uint as #mod_path::TextureFormat>::FORMAT_ID)
+        }
+        StorageTextureFormat::rgba32sint => {
+            quote!(<#mod_path::rgba32sint as #mod_path::TextureFormat>::FORMAT_ID)
+        }
+        StorageTextureFormat::rgba32float => {
+            quote!(<#mod_path::rgba32float as #mod_path::TextureFormat>::FORMAT_ID)
+        }
+    }
+}
+
+fn sized_buffer_layout_tokens(layout: &SizedBufferLayout) -> TokenStream {
+    let recurse = layout.memory_units().iter().map(|u| memory_unit_tokens(u));
+
+    let tokens = quote! {
+        empa::resource_binding::SizedBufferLayout(&[#(#recurse),*])
+    };
+
+    tokens
+}
+
+fn unsized_buffer_layout_tokens(layout: &UnsizedBufferLayout) -> TokenStream {
+    let head_recurse = layout.sized_head().iter().map(|u| memory_unit_tokens(u));
+
+    let tail = if let Some(layout) = layout.unsized_tail() {
+        let recurse = layout.iter().map(|u| memory_unit_tokens(u));
+
+        quote! {
+            Some(&[#(#recurse),*])
+        }
+    } else {
+        quote!(None)
+    };
+
+    quote! {
+        empa::resource_binding::UnsizedBufferLayout {
+            sized_head: &[#(#head_recurse),*],
+            unsized_tail: #tail
+        }
+    }
+}
+
+fn memory_unit_tokens(memory_unit: &MemoryUnit) -> TokenStream {
+    let offset = memory_unit.offset;
+    let layout = memory_unit_layout_tokens(&memory_unit.layout);
+
+    quote! {
+        empa::abi::MemoryUnit {
+            offset: #offset,
+            layout: #layout
+        }
+    }
+}
+
+fn memory_unit_layout_tokens(memory_unit_layout: &MemoryUnitLayout) -> TokenStream {
+    let mod_path = quote!(empa::abi);
+
+    match memory_unit_layout {
+        MemoryUnitLayout::Float => {
+            quote!(#mod_path::MemoryUnitLayout::Float)
+        }
+        MemoryUnitLayout::FloatArray(len) => {
+            quote!(#mod_path::MemoryUnitLayout::Array {
+                units: &[#mod_path::MemoryUnit {
+                    offset: 0,
+                    layout: #mod_path::MemoryUnitLayout::Float
+                }],
+                stride: 4,
+                len: #len
+            })
+        }
+        MemoryUnitLayout::FloatVector2 => {
+            quote!(#mod_path::MemoryUnitLayout::FloatVector2)
+        }
+        MemoryUnitLayout::FloatVector2Array(len) => {
+            quote!(#mod_path::MemoryUnitLayout::Array {
+                units: &[#mod_path::MemoryUnit {
+                    offset: 0,
+                    layout: #mod_path::MemoryUnitLayout::FloatVector2
+                }],
+                stride: 8,
+                len: #len
+            })
+        }
+        MemoryUnitLayout::FloatVector3 => {
+            quote!(#mod_path::MemoryUnitLayout::FloatVector3)
+        }
+        MemoryUnitLayout::FloatVector3Array(len) => {
+            quote!(#mod_path::MemoryUnitLayout::Array {
+                units: &[#mod_path::MemoryUnit {
+                    offset: 0,
+                    layout: #mod_path::MemoryUnitLayout::FloatVector3
+                }],
+                stride: 16,
+                len: #len
+            })
+        }
+        MemoryUnitLayout::FloatVector4 => {
+            quote!(#mod_path::MemoryUnitLayout::FloatVector4)
+        }
+        MemoryUnitLayout::FloatVector4Array(len) => {
+            quote!(#mod_path::MemoryUnitLayout::Array {
+                units: &[#mod_path::MemoryUnit {
+                    offset: 0,
+                    layout: #mod_path::MemoryUnitLayout::FloatVector4
+                }],
+                stride: 16,
+                len: #len
+            })
+        }
+        MemoryUnitLayout::Integer => {
+            quote!(#mod_path::MemoryUnitLayout::Integer)
+        }
+        MemoryUnitLayout::IntegerArray(len) => {
+            quote!(#mod_path::MemoryUnitLayout::Array {
+                units: &[#mod_path::MemoryUnit {
+                    offset: 0,
+                    layout: #mod_path::MemoryUnitLayout::Integer
+                }],
+                stride: 4,
+                len: #len
+            })
+        }
+        MemoryUnitLayout::IntegerVector2 => {
+            quote!(#mod_path::MemoryUnitLayout::IntegerVector2)
+        }
+        MemoryUnitLayout::IntegerVector2Array(len) => {
+            quote!(#mod_path::MemoryUnitLayout::Array {
+                units: &[#mod_path::MemoryUnit {
+                    offset: 0,
+                    layout: #mod_path::MemoryUnitLayout::IntegerVector2
+                }],
+                stride: 8,
+                len: #len
+            })
+        }
+        MemoryUnitLayout::IntegerVector3 => {
+            quote!(#mod_path::MemoryUnitLayout::IntegerVector3)
+        }
+        MemoryUnitLayout::IntegerVector3Array(len) => {
+            quote!(#mod_path::MemoryUnitLayout::Array {
+                units: &[#mod_path::MemoryUnit {
+                    offset: 0,
+                    layout: #mod_path::MemoryUnitLayout::IntegerVector3
+                }],
+                stride: 16,
+                len: #len
+            })
+        }
+        MemoryUnitLayout::IntegerVector4 => {
+            quote!(#mod_path::MemoryUnitLayout::IntegerVector4)
+        }
+        MemoryUnitLayout::IntegerVector4Array(len) => {
+            quote!(#mod_path::MemoryUnitLayout::Array {
+                units: &[#mod_path::MemoryUnit {
+                    offset: 0,
+                    layout: #mod_path::MemoryUnitLayout::IntegerVector4
+                }],
+                stride: 16,
+                len: #len
+            })
+        }
+        MemoryUnitLayout::UnsignedInteger => {
+            quote!(#mod_path::MemoryUnitLayout::UnsignedInteger)
+        }
+        MemoryUnitLayout::UnsignedIntegerArray(len) => {
+            quote!(#mod_path::MemoryUnitLayout::Array {
+                units: &[#mod_path::MemoryUnit {
+                    offset: 0,
+                    layout: #mod_path::MemoryUnitLayout::UnsignedInteger
+                }],
+                stride: 4,
+                len: #len
+            })
+        }
+        MemoryUnitLayout::UnsignedIntegerVector2 => {
+            quote!(#mod_path::MemoryUnitLayout::UnsignedIntegerVector2)
+        }
+        MemoryUnitLayout::UnsignedIntegerVector2Array(len) => {
+            quote!(#mod_path::MemoryUnitLayout::Array {
+                units: &[#mod_path::MemoryUnit {
+                    offset: 0,
+                    layout: #mod_path::MemoryUnitLayout::UnsignedIntegerVector2
+                }],
+                stride: 8,
+                len: #len
+            })
+        }
+        MemoryUnitLayout::UnsignedIntegerVector3 => {
+            quote!(#mod_path::MemoryUnitLayout::UnsignedIntegerVector3)
+        }
+        MemoryUnitLayout::UnsignedIntegerVector3Array(len) => {
+            quote!(#mod_path::MemoryUnitLayout::Array {
+                units: &[#mod_path::MemoryUnit {
+                    offset: 0,
+                    layout: #mod_path::MemoryUnitLayout::UnsignedIntegerVector3
+                }],
+                stride: 16,
+                len: #len
+            })
+        }
+        MemoryUnitLayout::UnsignedIntegerVector4 => {
+            quote!(#mod_path::MemoryUnitLayout::UnsignedIntegerVector4)
+        }
+        MemoryUnitLayout::UnsignedIntegerVector4Array(len) => {
+            quote!(#mod_path::MemoryUnitLayout::Array {
+                units: &[#mod_path::MemoryUnit {
+                    offset: 0,
+                    layout: #mod_path::MemoryUnitLayout::UnsignedIntegerVector4
+                }],
+                stride: 16,
+                len: #len
+            })
+        }
+        MemoryUnitLayout::Matrix2x2 => {
+            quote!(#mod_path::MemoryUnitLayout::Matrix2x2)
+        }
+        MemoryUnitLayout::Matrix2x2Array(len) => {
+            quote!(#mod_path::MemoryUnitLayout::Array {
+                units: &[#mod_path::MemoryUnit {
+                    offset: 0,
+                    layout: #mod_path::MemoryUnitLayout::Matrix2x2
+                }],
+                stride: 16,
+                len: #len
+            })
+        }
+        MemoryUnitLayout::Matrix2x3 => {
+            quote!(#mod_path::MemoryUnitLayout::Matrix2x3)
+        }
+        MemoryUnitLayout::Matrix2x3Array(len) => {
+            quote!(#mod_path::MemoryUnitLayout::Array {
+                units: &[#mod_path::MemoryUnit {
+                    offset: 0,
+                    layout: #mod_path::MemoryUnitLayout::Matrix2x3
+                }],
+                stride: 32,
+                len: #len
+            })
+        }
+        MemoryUnitLayout::Matrix2x4 => {
+            quote!(#mod_path::MemoryUnitLayout::Matrix2x4)
+        }
+        MemoryUnitLayout::Matrix2x4Array(len) => {
+            quote!(#mod_path::MemoryUnitLayout::Array {
+                units: &[#mod_path::MemoryUnit {
+                    offset: 0,
+                    layout: #mod_path::MemoryUnitLayout::Matrix2x4
+                }],
+                stride: 32,
+                len: #len
+            })
+        }
+        MemoryUnitLayout::Matrix3x2 => {
+            quote!(#mod_path::MemoryUnitLayout::Matrix3x2)
+        }
+        MemoryUnitLayout::Matrix3x2Array(len) => {
+            quote!(#mod_path::MemoryUnitLayout::Array {
+                units: &[#mod_path::MemoryUnit {
+                    offset: 0,
+                    layout: #mod_path::MemoryUnitLayout::Matrix3x2
+                }],
+                stride: 24,
+                len: #len
+            })
+        }
+        MemoryUnitLayout::Matrix3x3 => {
+            quote!(#mod_path::MemoryUnitLayout::Matrix3x3)
+        }
+        MemoryUnitLayout::Matrix3x3Array(len) => {
+            quote!(#mod_path::MemoryUnitLayout::Array {
+                units: &[#mod_path::MemoryUnit {
+                    offset: 0,
+                    layout: #mod_path::MemoryUnitLayout::Matrix3x3
+                }],
+                stride: 48,
+                len: #len
+            })
+        }
+        MemoryUnitLayout::Matrix3x4 => {
+            quote!(#mod_path::MemoryUnitLayout::Matrix3x4)
+        }
+        MemoryUnitLayout::Matrix3x4Array(len) => {
+            quote!(#mod_path::MemoryUnitLayout::Array {
+                units: &[#mod_path::MemoryUnit {
+                    offset: 0,
+                    layout: #mod_path::MemoryUnitLayout::Matrix3x4
+                }],
+                stride: 48,
+                len: #len
+            })
+        }
+        MemoryUnitLayout::Matrix4x2 => {
+            quote!(#mod_path::MemoryUnitLayout::Matrix4x2)
+        }
+        MemoryUnitLayout::Matrix4x2Array(len) => {
+            quote!(#mod_path::MemoryUnitLayout::Array {
+                units: &[#mod_path::MemoryUnit {
+                    offset: 0,
+                    layout: #mod_path::MemoryUnitLayout::Matrix4x2
+                }],
+                stride: 32,
+                len: #len
+            })
+        }
+        MemoryUnitLayout::Matrix4x3 => {
+            quote!(#mod_path::MemoryUnitLayout::Matrix4x3)
+        }
+        MemoryUnitLayout::Matrix4x3Array(len) => {
+            quote!(#mod_path::MemoryUnitLayout::Array {
+                units: &[#mod_path::MemoryUnit {
+                    offset: 0,
+                    layout: #mod_path::MemoryUnitLayout::Matrix4x3
+                }],
+                stride: 64,
+                len: #len
+            })
+        }
+        MemoryUnitLayout::Matrix4x4 => {
+            quote!(#mod_path::MemoryUnitLayout::Matrix4x4)
+        }
+        MemoryUnitLayout::Matrix4x4Array(len) => {
+            quote!(#mod_path::MemoryUnitLayout::Array {
+                units: &[#mod_path::MemoryUnit {
+                    offset: 0,
+                    layout: #mod_path::MemoryUnitLayout::Matrix4x4
+                }],
+                stride: 64,
+                len: #len
+            })
+        }
+        MemoryUnitLayout::ComplexArray { units, stride, len } => {
+            let recurse = units.iter().map(|unit| memory_unit_tokens(unit));
+
+            quote!(#mod_path::MemoryUnitLayout::Array {
+                units: &[#(#recurse),*],
+                stride: #stride,
+                len: #len,
+            })
+        }
+    }
+}
+
+fn constant_type_tokens(constant_type: ConstantType) -> TokenStream {
+    let mod_path = quote!(empa::shader_module);
+
+    match constant_type {
+        ConstantType::Float => {
+            quote!(#mod_path::StaticConstantType::Float)
+        }
+        ConstantType::Bool => {
+            quote!(#mod_path::StaticConstantType::Bool)
+        }
+        ConstantType::SignedInteger => {
+            quote!(#mod_path::StaticConstantType::SignedInteger)
+        }
+        ConstantType::UnsignedInteger => {
+            quote!(#mod_path::StaticConstantType::UnsignedInteger)
+        }
+    }
+}
+
+fn shader_stage_tokens(shader_stage: ShaderStage) -> TokenStream {
+    let mod_path = quote!(empa::shader_module);
+
+    match shader_stage {
+        ShaderStage::Vertex => {
+            quote!(#mod_path::StaticShaderStage::Vertex)
+        }
+        ShaderStage::Fragment => {
+            quote!(#mod_path::StaticShaderStage::Fragment)
+        }
+        ShaderStage::Compute => {
+            quote!(#mod_path::StaticShaderStage::Compute)
+        }
+    }
+}
+
+fn entry_point_binding_tokens(entry_point_binding: &EntryPointBinding) -> TokenStream {
+    let mod_path = quote!(empa::shader_module);
+
+    let location = entry_point_binding.location();
+    let binding_type = entry_point_binding_type_tokens(entry_point_binding.binding_type());
+
+    let interpolation = if let Some(interpolation) = entry_point_binding.interpolation() {
+        let interpolation = interpolation_tokens(interpolation);
+
+        quote!(Some(#interpolation))
+    } else {
+        quote!(None)
+    };
+
+    let sampling = if let Some(sampling) = entry_point_binding.sampling() {
+        let sampling = sampling_tokens(sampling);
+
+        quote!(Some(#sampling))
+    } else {
+        quote!(None)
+    };
+
+    quote! {
+        #mod_path::StaticEntryPointBinding {
+            location: #location,
+            binding_type: #binding_type,
+            interpolation: #interpolation,
+            sampling: #sampling,
+        }
+    }
+}
+
+fn entry_point_binding_type_tokens(binding_type: EntryPointBindingType) -> TokenStream {
+    let mod_path = quote!(empa::shader_module);
+
+    match binding_type {
+        EntryPointBindingType::SignedInteger => {
+            quote!(#mod_path::StaticEntryPointBindingType::SignedInteger)
+        }
+        EntryPointBindingType::SignedIntegerVector2 => {
+            quote!(#mod_path::StaticEntryPointBindingType::SignedIntegerVector2)
+        }
+        EntryPointBindingType::SignedIntegerVector3 => {
+            quote!(#mod_path::StaticEntryPointBindingType::SignedIntegerVector3)
+        }
+        EntryPointBindingType::SignedIntegerVector4 => {
+            quote!(#mod_path::StaticEntryPointBindingType::SignedIntegerVector4)
+        }
+        EntryPointBindingType::UnsignedInteger => {
+            quote!(#mod_path::StaticEntryPointBindingType::UnsignedInteger)
+        }
+        EntryPointBindingType::UnsignedIntegerVector2 => {
+            quote!(#mod_path::StaticEntryPointBindingType::UnsignedIntegerVector2)
+        }
+        EntryPointBindingType::UnsignedIntegerVector3 => {
+            quote!(#mod_path::StaticEntryPointBindingType::UnsignedIntegerVector3)
+        }
+        EntryPointBindingType::UnsignedIntegerVector4 => {
+            quote!(#mod_path::StaticEntryPointBindingType::UnsignedIntegerVector4)
+        }
+        EntryPointBindingType::Float => {
+            quote!(#mod_path::StaticEntryPointBindingType::Float)
+        }
+        EntryPointBindingType::FloatVector2 => {
+            quote!(#mod_path::StaticEntryPointBindingType::FloatVector2)
+        }
+        EntryPointBindingType::FloatVector3 => {
+            quote!(#mod_path::StaticEntryPointBindingType::FloatVector3)
+        }
+        EntryPointBindingType::FloatVector4 => {
+            quote!(#mod_path::StaticEntryPointBindingType::FloatVector4)
+        }
+        EntryPointBindingType::HalfFloat => {
+            quote!(#mod_path::StaticEntryPointBindingType::HalfFloat)
+        }
+        EntryPointBindingType::HalfFloatVector2 => {
+            quote!(#mod_path::StaticEntryPointBindingType::HalfFloatVector2)
+        }
+        EntryPointBindingType::HalfFloatVector3 => {
+            quote!(#mod_path::StaticEntryPointBindingType::HalfFloatVector3)
+        }
+        EntryPointBindingType::HalfFloatVector4 => {
+            quote!(#mod_path::StaticEntryPointBindingType::HalfFloatVector4)
+        }
+    }
+}
+
+fn interpolation_tokens(interpolation: Interpolation) -> TokenStream {
+    let mod_path = quote!(empa::shader_module);
+
+    match interpolation {
+        Interpolation::Perspective => {
+            quote!(#mod_path::StaticInterpolation::Perspective)
+        }
+        Interpolation::Linear => {
+            quote!(#mod_path::StaticInterpolation::Linear)
+        }
+        Interpolation::Flat => {
+            quote!(#mod_path::StaticInterpolation::Flat)
+        }
+    }
+}
+
+fn sampling_tokens(sampling: Sampling) -> TokenStream {
+    let mod_path = quote!(empa::shader_module);
+
+    match sampling {
+        Sampling::Center => {
+            quote!(#mod_path::StaticSampling::Center)
+        }
+        Sampling::Centroid => {
+            quote!(#mod_path::StaticSampling::Centroid)
+        }
+        Sampling::Sample => {
+            quote!(#mod_path::StaticSampling::Sample)
+        }
+    }
+}
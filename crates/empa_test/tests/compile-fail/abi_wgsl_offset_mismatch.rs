@@ -0,0 +1,13 @@
+use empa::abi;
+
+// In WGSL, `intensity` directly follows the 12 bytes of `position` at offset `12`, but in Rust
+// `abi::Vec3` is padded to 16 bytes.
+#[derive(abi::Sized, Clone, Copy)]
+#[repr(C)]
+struct Light {
+    position: abi::Vec3<f32>,
+    intensity: f32,
+    //~^ ERROR E0080
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use empa::render_pipeline::{BlendState, BlendedColorOutput, ColorWrite};
+use empa::texture::format::r32uint;
+
+fn main() {
+    let blend_state = BlendState {
+        color: Default::default(),
+        alpha: Default::default(),
+    };
+
+    let _output = BlendedColorOutput { format: r32uint, blend_state, write_mask: ColorWrite::All };
+    //~^ ERROR the trait bound `r32uint: Blendable` is not satisfied
+}
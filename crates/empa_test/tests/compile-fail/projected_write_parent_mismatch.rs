@@ -0,0 +1,22 @@
+use empa::buffer;
+use empa::buffer::{projection, BufferUsages};
+use empa::device::Queue;
+
+#[derive(Clone, Copy)]
+struct A {
+    flags: u16,
+}
+
+#[derive(Clone, Copy)]
+struct B {
+    flags: u16,
+}
+
+fn write_flags(queue: &Queue, view: buffer::View<A, BufferUsages!(CopyDst)>, data: &A) {
+    queue.write_buffer_projected(view, projection!(B => flags), data);
+    //~^ ERROR mismatched types
+}
+
+fn main() {
+    let _ = write_flags;
+}
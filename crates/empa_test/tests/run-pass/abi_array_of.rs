@@ -0,0 +1,28 @@
+use empa::abi;
+
+#[derive(abi::Sized, Clone, Copy, PartialEq, Debug)]
+#[repr(C)]
+struct Light {
+    position: abi::Vec3<f32>,
+    intensity: f32,
+}
+
+// Corresponds to the WGSL struct (in the uniform address space):
+//
+// struct Uniforms {
+//     light_count: u32,
+//     lights: array<Light, 4>,
+//     weights: array<f32, 4>,
+// }
+#[derive(abi::Sized, Clone, Copy, PartialEq, Debug)]
+#[repr(C)]
+struct Uniforms {
+    light_count: u32,
+    lights: abi::ArrayOf<Light, 4>,
+    weights: abi::ArrayOf<f32, 4>,
+}
+
+const _: () = assert!(empa::offset_of!(Uniforms, lights) == 16);
+const _: () = assert!(empa::offset_of!(Uniforms, weights) == 80);
+
+fn main() {}
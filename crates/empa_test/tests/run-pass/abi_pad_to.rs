@@ -0,0 +1,26 @@
+use empa::abi;
+
+// Corresponds to the WGSL struct:
+//
+// struct Light {
+//     position: vec3<f32>,
+//     _padding: f32,
+//     intensity: f32,
+// }
+#[derive(abi::Sized, Clone, Copy, PartialEq, Debug)]
+#[repr(C)]
+struct Light {
+    #[abi(pad_to = 16)]
+    position: abi::Vec3<f32>,
+    intensity: f32,
+}
+
+// A `vec3` followed by a `vec4` needs no explicit padding, as WGSL aligns the `vec4` to 16 bytes.
+#[derive(abi::Sized, Clone, Copy, PartialEq, Debug)]
+#[repr(C)]
+struct Material {
+    albedo: abi::Vec3<f32>,
+    emission: abi::Vec4<f32>,
+}
+
+fn main() {}
@@ -0,0 +1,39 @@
+use empa::abi;
+
+#[derive(abi::Sized, Clone, Copy, PartialEq, Debug)]
+#[repr(C)]
+struct Inner {
+    value: f32,
+}
+
+// Corresponds to the WGSL struct below, which is valid in the uniform address space: a struct
+// member is aligned to 16 bytes there, and is followed by at least `roundUp(16, SizeOf(Inner))`
+// bytes before the next member.
+//
+// struct Uniforms {
+//     scale: f32,
+//     _padding_0: f32,
+//     _padding_1: vec2<f32>,
+//     inner: Inner,
+//     _padding_2: f32,
+//     _padding_3: vec2<f32>,
+//     weight: f32,
+// }
+#[derive(abi::Sized, Clone, Copy, PartialEq, Debug)]
+#[repr(C)]
+struct Uniforms {
+    scale: f32,
+    _padding_0: f32,
+    _padding_1: abi::Vec2<f32>,
+    inner: Inner,
+    _padding_2: f32,
+    _padding_3: abi::Vec2<f32>,
+    weight: f32,
+}
+
+const _: () = {
+    assert!(std::mem::offset_of!(Uniforms, inner) == 16);
+    assert!(std::mem::offset_of!(Uniforms, weight) == 32);
+};
+
+fn main() {}
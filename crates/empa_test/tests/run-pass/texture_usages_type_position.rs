@@ -0,0 +1,16 @@
+use empa::texture;
+use empa::texture::TextureUsages;
+use empa::type_flag::{O, X};
+
+struct Target {
+    usage: TextureUsages!(RenderAttachment | CopyDst),
+}
+
+const USAGE: TextureUsages!(RenderAttachment | CopyDst) =
+    texture::Usages::render_attachment().and_copy_dst();
+
+fn main() {
+    let explicit: texture::Usages<X, O, O, X, O> = USAGE;
+
+    let _target = Target { usage: explicit };
+}
@@ -0,0 +1,425 @@
+//! Integration between `empa` and `winit`.
+//!
+//! Implement [EmpaApp] for your application state and hand it to [run]. The runner creates the
+//! window, instance, adapter, device and surface, keeps the surface configured to the window's
+//! size, and calls [EmpaApp::frame] for every redraw.
+//!
+//! Applications that should also run on the web can instead implement [empa::app::App] and be run
+//! with [run_app].
+//!
+//! ```no_run
+//! use std::error::Error;
+//!
+//! use empa_winit::{Context, EmpaApp, RunnerConfig, SurfaceTexture};
+//!
+//! struct MyApp;
+//!
+//! impl EmpaApp for MyApp {
+//!     async fn init(context: &Context) -> Result<Self, Box<dyn Error>> {
+//!         Ok(MyApp)
+//!     }
+//!
+//!     fn frame(&mut self, context: &Context, frame: &SurfaceTexture) {
+//!         // Record and submit commands that render to `frame`...
+//!     }
+//! }
+//!
+//! fn main() {
+//!     empa_winit::run::<MyApp>(RunnerConfig::default()).unwrap();
+//! }
+//! ```
+
+use std::error::Error;
+use std::fmt;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Instant;
+
+use empa::adapter::{Adapter, Feature, Limits};
+use empa::app::{App, AppContext, Frame};
+use empa::device::{Device, DeviceDescriptor};
+use empa::frame_pacing::{FrameLatency, FramePacer};
+use empa::native::{
+    AdapterOptions, AlphaMode, Backend, ConfiguredSurface, Instance, InstanceDescriptor,
+    InstanceFlag, PowerPreference, PresentMode, SurfaceConfiguration, SurfaceError,
+};
+use empa::texture;
+use empa::texture::format::bgra8unorm;
+use empa::texture::TextureUsages;
+use flagset::FlagSet;
+use winit::application::ApplicationHandler;
+use winit::error::{EventLoopError, OsError};
+use winit::event::WindowEvent;
+use winit::event_loop::{ActiveEventLoop, ControlFlow, EventLoop};
+use winit::window::{Window, WindowAttributes, WindowId};
+
+/// The texture format used for the window surface.
+pub type SurfaceFormat = bgra8unorm;
+
+/// The usage flags used for the window surface.
+pub type SurfaceUsage = TextureUsages!(RenderAttachment);
+
+pub type SurfaceTexture = empa::native::SurfaceTexture<SurfaceFormat, SurfaceUsage>;
+
+/// Application callbacks driven by the [run] loop.
+pub trait EmpaApp: Sized {
+    /// Called once after the window and device have been created.
+    fn init(context: &Context) -> impl Future<Output = Result<Self, Box<dyn Error>>>;
+
+    /// Called after the surface was resized to match the window's new size.
+    fn resize(&mut self, context: &Context, width: u32, height: u32) {
+        let _ = (context, width, height);
+    }
+
+    /// Called for every redraw with the surface texture for the current frame.
+    ///
+    /// The frame is presented after this returns; all commands that render to the frame must have
+    /// been submitted by then.
+    fn frame(&mut self, context: &Context, frame: &SurfaceTexture);
+
+    /// Called for every window event, before the runner's own handling of the event.
+    fn window_event(&mut self, context: &Context, event: &WindowEvent) {
+        let _ = (context, event);
+    }
+}
+
+pub struct RunnerConfig {
+    pub window_attributes: WindowAttributes,
+    pub backends: FlagSet<Backend>,
+    pub instance_flags: FlagSet<InstanceFlag>,
+    pub power_preference: PowerPreference,
+    pub required_features: FlagSet<Feature>,
+    pub required_limits: Limits,
+    pub present_mode: PresentMode,
+    pub alpha_mode: AlphaMode,
+    pub frame_latency: FrameLatency,
+}
+
+impl Default for RunnerConfig {
+    fn default() -> Self {
+        RunnerConfig {
+            window_attributes: Window::default_attributes(),
+            backends: Backend::All.into(),
+            instance_flags: InstanceFlag::None.into(),
+            power_preference: Default::default(),
+            required_features: Feature::None.into(),
+            required_limits: Default::default(),
+            present_mode: Default::default(),
+            alpha_mode: Default::default(),
+            frame_latency: Default::default(),
+        }
+    }
+}
+
+/// The objects the runner sets up, exposed for use by the application.
+pub struct Context {
+    instance: Instance,
+    adapter: Adapter,
+    device: Device,
+    window: Arc<Window>,
+}
+
+impl Context {
+    pub fn instance(&self) -> &Instance {
+        &self.instance
+    }
+
+    pub fn adapter(&self) -> &Adapter {
+        &self.adapter
+    }
+
+    pub fn device(&self) -> &Device {
+        &self.device
+    }
+
+    pub fn window(&self) -> &Arc<Window> {
+        &self.window
+    }
+}
+
+/// Error returned by [run] and [run_app].
+#[derive(Debug)]
+pub enum RunError {
+    /// The event loop could not be created or failed while running.
+    EventLoop(EventLoopError),
+    /// The window could not be created.
+    Window(OsError),
+    /// Setting up the instance, adapter, device or surface failed, or [EmpaApp::init] returned an
+    /// error.
+    Init(Box<dyn Error>),
+}
+
+impl From<EventLoopError> for RunError {
+    fn from(err: EventLoopError) -> Self {
+        RunError::EventLoop(err)
+    }
+}
+
+impl fmt::Display for RunError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RunError::EventLoop(err) => write!(f, "event loop error: {}", err),
+            RunError::Window(err) => write!(f, "failed to create window: {}", err),
+            RunError::Init(err) => write!(f, "failed to initialize application: {}", err),
+        }
+    }
+}
+
+impl Error for RunError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            RunError::EventLoop(err) => Some(err),
+            RunError::Window(err) => Some(err),
+            RunError::Init(err) => Some(err.as_ref()),
+        }
+    }
+}
+
+/// Runs the application until its window is closed.
+///
+/// # Errors
+///
+/// Returns an error if the window, instance, adapter, device or surface could not be set up, or
+/// if [EmpaApp::init] fails. The event loop exits when setup fails.
+pub fn run<A: EmpaApp>(config: RunnerConfig) -> Result<(), RunError> {
+    let event_loop = EventLoop::new()?;
+
+    event_loop.set_control_flow(ControlFlow::Poll);
+
+    let mut runner = Runner::<A> {
+        config,
+        state: None,
+        error: None,
+    };
+
+    event_loop.run_app(&mut runner)?;
+
+    match runner.error {
+        Some(err) => Err(err),
+        None => Ok(()),
+    }
+}
+
+/// Runs a target-independent [App] until its window is closed.
+///
+/// See [empa::app] for running the same application on the web.
+pub fn run_app<A: App>(config: RunnerConfig) -> Result<(), RunError> {
+    run::<AppAdapter<A>>(config)
+}
+
+/// Drives an [App] with the [EmpaApp] callbacks.
+struct AppAdapter<A> {
+    app_context: AppContext,
+    app: A,
+    start: Instant,
+    last_frame: Option<Instant>,
+}
+
+impl<A> EmpaApp for AppAdapter<A>
+where
+    A: App,
+{
+    async fn init(context: &Context) -> Result<Self, Box<dyn Error>> {
+        let size = context.window.inner_size();
+        let app_context = AppContext::new(
+            context.device.clone(),
+            size.width.max(1),
+            size.height.max(1),
+        );
+        let app = A::init(&app_context).await?;
+
+        Ok(AppAdapter {
+            app_context,
+            app,
+            start: Instant::now(),
+            last_frame: None,
+        })
+    }
+
+    fn resize(&mut self, _context: &Context, width: u32, height: u32) {
+        self.app_context.set_size(width, height);
+        self.app.resize(&self.app_context, width, height);
+    }
+
+    fn frame(&mut self, _context: &Context, frame: &SurfaceTexture) {
+        let now = Instant::now();
+        let delta_time = self
+            .last_frame
+            .map(|last_frame| (now - last_frame).as_secs_f64() * 1000.0)
+            .unwrap_or(0.0);
+
+        self.last_frame = Some(now);
+
+        self.app.frame(
+            &self.app_context,
+            &Frame {
+                texture: frame,
+                time: (now - self.start).as_secs_f64() * 1000.0,
+                delta_time,
+            },
+        );
+    }
+}
+
+struct RunnerState<A> {
+    context: Context,
+    surface: ConfiguredSurface<'static, SurfaceFormat, SurfaceUsage>,
+    frame_pacer: FramePacer,
+    app: A,
+}
+
+impl<A> RunnerState<A>
+where
+    A: EmpaApp,
+{
+    async fn init(window: Window, config: &RunnerConfig) -> Result<Self, Box<dyn Error>> {
+        let mut size = window.inner_size();
+
+        size.width = size.width.max(1);
+        size.height = size.height.max(1);
+
+        let window = Arc::new(window);
+        let instance = Instance::new(InstanceDescriptor {
+            backends: config.backends,
+            flags: config.instance_flags,
+            dx12_shader_compiler: Default::default(),
+        });
+        let surface = instance.create_surface(window.clone())?;
+        let adapter = instance.get_adapter(AdapterOptions {
+            power_preference: config.power_preference,
+            force_fallback_adapter: false,
+            compatible_surface: Some(&surface),
+            backends: config.backends,
+        })?;
+
+        let device = adapter
+            .request_device(&DeviceDescriptor {
+                required_features: config.required_features,
+                required_limits: config.required_limits,
+            })
+            .await?;
+
+        let surface = surface.configure(
+            &device,
+            &SurfaceConfiguration {
+                format: bgra8unorm,
+                usage: texture::Usages::render_attachment(),
+                width: size.width,
+                height: size.height,
+                present_mode: config.present_mode,
+                desired_maximum_frame_latency: config.frame_latency,
+                alpha_mode: config.alpha_mode,
+                view_formats: (),
+            },
+        );
+
+        let frame_pacer = surface.frame_pacer();
+
+        let context = Context {
+            instance,
+            adapter,
+            device,
+            window,
+        };
+
+        let app = A::init(&context).await?;
+
+        Ok(RunnerState {
+            context,
+            surface,
+            frame_pacer,
+            app,
+        })
+    }
+
+    fn resize(&mut self, width: u32, height: u32) {
+        let width = width.max(1);
+        let height = height.max(1);
+
+        self.surface.resize(width, height);
+        self.app.resize(&self.context, width, height);
+    }
+
+    fn redraw(&mut self) {
+        pollster::block_on(self.frame_pacer.wait());
+
+        let frame = match self.surface.get_current_texture() {
+            Ok(frame) => frame,
+            Err(SurfaceError::Timeout) => return,
+            Err(SurfaceError::Outdated) | Err(SurfaceError::Lost) => {
+                let size = self.context.window.inner_size();
+
+                self.resize(size.width, size.height);
+
+                return;
+            }
+        };
+
+        self.app.frame(&self.context, &frame);
+        self.frame_pacer.frame_submitted();
+
+        frame.present();
+    }
+}
+
+struct Runner<A> {
+    config: RunnerConfig,
+    state: Option<RunnerState<A>>,
+    error: Option<RunError>,
+}
+
+impl<A> ApplicationHandler for Runner<A>
+where
+    A: EmpaApp,
+{
+    fn resumed(&mut self, event_loop: &ActiveEventLoop) {
+        if self.state.is_some() || self.error.is_some() {
+            return;
+        }
+
+        let window = match event_loop.create_window(self.config.window_attributes.clone()) {
+            Ok(window) => window,
+            Err(err) => {
+                self.error = Some(RunError::Window(err));
+                event_loop.exit();
+
+                return;
+            }
+        };
+
+        match pollster::block_on(RunnerState::init(window, &self.config)) {
+            Ok(state) => self.state = Some(state),
+            Err(err) => {
+                self.error = Some(RunError::Init(err));
+                event_loop.exit();
+            }
+        }
+    }
+
+    fn window_event(
+        &mut self,
+        event_loop: &ActiveEventLoop,
+        _window_id: WindowId,
+        event: WindowEvent,
+    ) {
+        let Some(state) = self.state.as_mut() else {
+            return;
+        };
+
+        state.app.window_event(&state.context, &event);
+
+        match event {
+            WindowEvent::CloseRequested => {
+                event_loop.exit();
+            }
+            WindowEvent::Resized(size) => {
+                state.resize(size.width, size.height);
+            }
+            WindowEvent::RedrawRequested => {
+                state.redraw();
+                state.context.window.request_redraw();
+            }
+            _ => (),
+        }
+    }
+}
@@ -0,0 +1,172 @@
+//! The animated triangle example, written once against [empa::app] and run on both native and web
+//! targets.
+
+use std::error::Error;
+
+use empa::app::{App, AppContext, Frame, FrameFormat};
+use empa::buffer;
+use empa::buffer::{Buffer, BufferUsages, Uniform};
+use empa::command::{
+    Draw, DrawCommandEncoder, RenderPassDescriptor, RenderStateEncoder,
+    ResourceBindingCommandEncoder,
+};
+use empa::render_pipeline::{
+    ColorOutput, ColorWrite, FragmentStageBuilder, IndexAny, RenderPipeline,
+    RenderPipelineDescriptorBuilder, VertexStageBuilder,
+};
+use empa::render_target::{FloatAttachment, LoadOp, RenderLayout, RenderTarget, StoreOp};
+use empa::resource_binding::BindGroup;
+use empa::shader_module::{shader_source, ShaderSource};
+use empa::texture::format::bgra8unorm;
+use empa::texture::AttachableImageDescriptor;
+
+#[derive(empa::render_pipeline::Vertex, Clone, Copy)]
+struct Vertex {
+    #[vertex_attribute(location = 0, format = "float32x2")]
+    position: [f32; 2],
+    #[vertex_attribute(location = 1, format = "unorm8x4")]
+    color: [u8; 4],
+}
+
+#[derive(empa::resource_binding::Resources)]
+struct Resources<'a> {
+    #[resource(binding = 0, visibility = "VERTEX|FRAGMENT")]
+    uniform_buffer: Uniform<'a, f32>,
+}
+
+type ResourceLayout = <Resources<'static> as empa::resource_binding::Resources>::Layout;
+
+const SHADER: ShaderSource = shader_source!("shader.wgsl");
+
+struct AnimatedTriangle {
+    pipeline: RenderPipeline<RenderLayout<FrameFormat, ()>, Vertex, IndexAny, (ResourceLayout,)>,
+    uniform_buffer: Buffer<f32, BufferUsages!(UniformBinding | CopyDst)>,
+    bind_group: BindGroup<ResourceLayout>,
+    vertex_buffer: Buffer<[Vertex], BufferUsages!(Vertex)>,
+}
+
+impl App for AnimatedTriangle {
+    async fn init(context: &AppContext) -> Result<Self, Box<dyn Error>> {
+        let device = context.device();
+
+        let shader = device.create_shader_module(&SHADER);
+
+        let bind_group_layout = device.create_bind_group_layout::<ResourceLayout>();
+        let pipeline_layout = device.create_pipeline_layout(&bind_group_layout);
+
+        let pipeline = device
+            .create_render_pipeline(
+                &RenderPipelineDescriptorBuilder::begin()
+                    .layout(&pipeline_layout)
+                    .vertex(
+                        VertexStageBuilder::begin(&shader, "vert_main")
+                            .vertex_layout::<Vertex>()
+                            .finish(),
+                    )
+                    .fragment(
+                        FragmentStageBuilder::begin(&shader, "frag_main")
+                            .color_outputs(ColorOutput {
+                                format: bgra8unorm,
+                                write_mask: ColorWrite::All,
+                            })
+                            .finish(),
+                    )
+                    .finish(),
+            )
+            .await?;
+
+        let uniform_buffer =
+            device.create_buffer(1.0, buffer::Usages::uniform_binding().and_copy_dst());
+        let bind_group = device.create_bind_group(
+            &bind_group_layout,
+            Resources {
+                uniform_buffer: uniform_buffer.uniform(),
+            },
+        );
+
+        let vertex_data = [
+            Vertex {
+                position: [0.0, 0.5],
+                color: [255, 0, 0, 255],
+            },
+            Vertex {
+                position: [-0.5, -0.5],
+                color: [0, 255, 0, 255],
+            },
+            Vertex {
+                position: [0.5, -0.5],
+                color: [0, 0, 255, 255],
+            },
+        ];
+
+        let vertex_buffer = device.create_buffer(vertex_data, buffer::Usages::vertex());
+
+        Ok(AnimatedTriangle {
+            pipeline,
+            uniform_buffer,
+            bind_group,
+            vertex_buffer,
+        })
+    }
+
+    fn frame(&mut self, context: &AppContext, frame: &Frame) {
+        let device = context.device();
+        let queue = device.queue();
+
+        queue.write_buffer(
+            self.uniform_buffer.view(),
+            &f32::sin(frame.time as f32 * 0.001),
+        );
+
+        let command_buffer = device
+            .create_command_encoder()
+            .begin_render_pass(RenderPassDescriptor::new(&RenderTarget {
+                color: FloatAttachment {
+                    image: frame
+                        .texture
+                        .attachable_image(&AttachableImageDescriptor::default()),
+                    load_op: LoadOp::Clear([0.0; 4]),
+                    store_op: StoreOp::Store,
+                },
+                depth_stencil: (),
+            }))
+            .set_pipeline(&self.pipeline)
+            .set_bind_groups(&self.bind_group)
+            .set_vertex_buffers(&self.vertex_buffer)
+            .draw(Draw {
+                vertex_count: self.vertex_buffer.len() as u32,
+                instance_count: 1,
+                first_vertex: 0,
+                first_instance: 0,
+            })
+            .end()
+            .finish();
+
+        queue.submit(command_buffer);
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn main() {
+    empa_winit::run_app::<AnimatedTriangle>(Default::default()).unwrap();
+}
+
+#[cfg(target_arch = "wasm32")]
+fn main() {
+    use arwa::dom::{selector, ParentNode};
+    use arwa::html::HtmlCanvasElement;
+    use arwa::window::window;
+
+    let canvas: HtmlCanvasElement = window()
+        .document()
+        .query_selector(&selector!("#canvas"))
+        .expect("canvas not found")
+        .try_into()
+        .expect("not a canvas element");
+
+    arwa::spawn_local(async move {
+        empa::app::run_canvas::<AnimatedTriangle>(canvas)
+            .await
+            .unwrap();
+    });
+}
@@ -50,6 +50,7 @@ impl AppState {
             power_preference: PowerPreference::default(),
             force_fallback_adapter: false,
             compatible_surface: Some(&surface),
+            ..Default::default()
         })?;
 
         let device = adapter.request_device(&DeviceDescriptor::default()).await?;
@@ -77,7 +78,7 @@ impl AppState {
                     )
                     .finish(),
             )
-            .await;
+            .await?;
 
         let vertex_data = [
             Vertex {
@@ -105,7 +106,7 @@ impl AppState {
                 width: size.width,
                 height: size.height,
                 present_mode: Default::default(),
-                desired_maximum_frame_latency: 0,
+                desired_maximum_frame_latency: Default::default(),
                 alpha_mode: Default::default(),
                 view_formats: (),
             },
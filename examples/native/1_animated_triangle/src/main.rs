@@ -68,6 +68,7 @@ impl AppState {
             power_preference: PowerPreference::default(),
             force_fallback_adapter: false,
             compatible_surface: Some(&surface),
+            ..Default::default()
         })?;
 
         let device = adapter.request_device(&DeviceDescriptor::default()).await?;
@@ -96,7 +97,7 @@ impl AppState {
                     )
                     .finish(),
             )
-            .await;
+            .await?;
 
         let uniform_buffer =
             device.create_buffer(1.0, buffer::Usages::uniform_binding().and_copy_dst());
@@ -133,7 +134,7 @@ impl AppState {
                 width: size.width,
                 height: size.height,
                 present_mode: Default::default(),
-                desired_maximum_frame_latency: 0,
+                desired_maximum_frame_latency: Default::default(),
                 alpha_mode: Default::default(),
                 view_formats: (),
             },
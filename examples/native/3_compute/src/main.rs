@@ -44,7 +44,7 @@ async fn run() -> Result<(), Box<dyn Error>> {
                 .compute(ComputeStageBuilder::begin(&shader, "main").finish())
                 .finish(),
         )
-        .await;
+        .await?;
 
     let data: Vec<u32> = (0..1024).collect();
 
@@ -38,6 +38,7 @@ struct Vertex {
 }
 
 #[derive(empa::abi::Sized, Clone, Copy)]
+#[repr(C)]
 struct Uniforms {
     model: abi::Mat4x4,
     view: abi::Mat4x4,
@@ -110,7 +111,7 @@ impl AppState {
                     )
                     .finish(),
             )
-            .await;
+            .await?;
 
         let vertex_data = [
             Vertex {
@@ -231,7 +232,7 @@ impl AppState {
                 width: size.width,
                 height: size.height,
                 present_mode: Default::default(),
-                desired_maximum_frame_latency: 0,
+                desired_maximum_frame_latency: Default::default(),
                 alpha_mode: Default::default(),
                 view_formats: (),
             },
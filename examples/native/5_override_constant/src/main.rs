@@ -58,7 +58,7 @@ async fn run() -> Result<(), Box<dyn Error>> {
                 )
                 .finish(),
         )
-        .await;
+        .await?;
 
     let data: Vec<u32> = (0..1024).collect();
 
@@ -0,0 +1,559 @@
+use std::error::Error;
+use std::f32::consts::PI;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use empa::buffer::{Buffer, BufferUsages, Uniform};
+use empa::command::{
+    DrawIndexed, DrawIndexedCommandEncoder, RenderBundle, RenderBundleEncoderDescriptor,
+    RenderPassDescriptor, RenderStateEncoder, ResourceBindingCommandEncoder,
+};
+use empa::device::{Device, DeviceDescriptor};
+use empa::native::{AdapterOptions, ConfiguredSurface, Instance, SurfaceConfiguration};
+use empa::render_pipeline::{
+    ColorOutput, ColorWrite, DepthStencilTest, FragmentStageBuilder, IndexAny, RenderPipeline,
+    RenderPipelineDescriptorBuilder, VertexStageBuilder,
+};
+use empa::render_target::{
+    DepthAttachment, DepthValue, FloatAttachment, LoadOp, RenderLayout, RenderTarget, StoreOp,
+};
+use empa::resource_binding::BindGroup;
+use empa::sampler::{ComparisonSampler, ComparisonSamplerDescriptor, FilterMode};
+use empa::shader_module::{shader_source, ShaderSource};
+use empa::texture::format::{bgra8unorm, depth24plus, depth32float};
+use empa::texture::{
+    AttachableImageDescriptor, MipmapLevels, Sampled2DDepth, Texture2D, Texture2DDescriptor,
+    TextureUsages,
+};
+use empa::{abi, buffer, texture, CompareFunction};
+use empa_glam::ToAbi;
+use glam::f32::{Mat4, Vec3};
+use winit::application::ApplicationHandler;
+use winit::event::WindowEvent;
+use winit::event_loop::{ActiveEventLoop, ControlFlow, EventLoop};
+use winit::window::{Window, WindowId};
+
+const SHADOW_MAP_SIZE: u32 = 1024;
+
+#[derive(empa::render_pipeline::Vertex, Clone, Copy)]
+struct Vertex {
+    #[vertex_attribute(location = 0, format = "float32x4")]
+    position: [f32; 4],
+    #[vertex_attribute(location = 1, format = "unorm8x4")]
+    color: [u8; 4],
+}
+
+#[derive(empa::abi::Sized, Clone, Copy)]
+#[repr(C)]
+struct Uniforms {
+    model: abi::Mat4x4,
+    view_projection: abi::Mat4x4,
+    light_view_projection: abi::Mat4x4,
+}
+
+#[derive(empa::resource_binding::Resources)]
+struct ObjectResources<'a> {
+    #[resource(binding = 0, visibility = "VERTEX")]
+    uniform_buffer: Uniform<'a, Uniforms>,
+}
+
+type ObjectLayout = <ObjectResources<'static> as empa::resource_binding::Resources>::Layout;
+
+#[derive(empa::resource_binding::Resources)]
+struct ShadowResources<'a> {
+    #[resource(binding = 0, visibility = "FRAGMENT")]
+    shadow_map: Sampled2DDepth<'a>,
+    #[resource(binding = 1, visibility = "FRAGMENT")]
+    shadow_sampler: &'a ComparisonSampler,
+}
+
+type ShadowLayout = <ShadowResources<'static> as empa::resource_binding::Resources>::Layout;
+
+type UniformBuffer = Buffer<Uniforms, BufferUsages!(UniformBinding | CopyDst)>;
+
+const SHADOW_SHADER: ShaderSource = shader_source!("shadow.wgsl");
+
+const SCENE_SHADER: ShaderSource = shader_source!("scene.wgsl");
+
+struct AppState {
+    device: Device,
+    light_view_projection: Mat4,
+    view_projection: Mat4,
+    cube_uniforms: UniformBuffer,
+    plane_uniforms: UniformBuffer,
+    cube_bind_group: BindGroup<ObjectLayout>,
+    plane_bind_group: BindGroup<ObjectLayout>,
+    shadow_bind_group: BindGroup<ShadowLayout>,
+    vertex_buffer: Buffer<[Vertex], BufferUsages!(Vertex)>,
+    index_buffer: Buffer<[u16], BufferUsages!(Index)>,
+    shadow_map: Texture2D<depth32float, TextureUsages!(RenderAttachment | TextureBinding)>,
+    shadow_bundle: RenderBundle<RenderLayout<(), depth32float>>,
+    scene_pipeline: RenderPipeline<
+        RenderLayout<bgra8unorm, depth24plus>,
+        Vertex,
+        IndexAny,
+        (ObjectLayout, ShadowLayout),
+    >,
+    depth_texture: Texture2D<depth24plus, TextureUsages!(RenderAttachment)>,
+    window: Arc<Window>,
+    surface: ConfiguredSurface<'static, bgra8unorm, TextureUsages!(RenderAttachment)>,
+}
+
+fn camera_view_projection(width: u32, height: u32) -> Mat4 {
+    let view = Mat4::look_at_rh(
+        Vec3::new(0.0, 15.0, 40.0),
+        Vec3::new(0.0, -5.0, 0.0),
+        Vec3::Y,
+    );
+    let projection = Mat4::perspective_rh(0.3 * PI, width as f32 / height as f32, 1.0, 100.0);
+
+    projection * view
+}
+
+impl AppState {
+    async fn init(window: Window) -> Result<Self, Box<dyn Error>> {
+        let mut size = window.inner_size();
+
+        size.width = size.width.max(1);
+        size.height = size.height.max(1);
+
+        let window = Arc::new(window);
+        let instance = Instance::default();
+        let surface = instance.create_surface(window.clone())?;
+        let adapter = instance.get_adapter(AdapterOptions {
+            compatible_surface: Some(&surface),
+            ..Default::default()
+        })?;
+
+        let device = adapter.request_device(&DeviceDescriptor::default()).await?;
+
+        let shadow_shader = device.create_shader_module(&SHADOW_SHADER);
+        let scene_shader = device.create_shader_module(&SCENE_SHADER);
+
+        let object_layout = device.create_bind_group_layout::<ObjectLayout>();
+        let shadow_layout = device.create_bind_group_layout::<ShadowLayout>();
+
+        let shadow_pipeline_layout = device.create_pipeline_layout(&object_layout);
+        let scene_pipeline_layout = device.create_pipeline_layout((&object_layout, &shadow_layout));
+
+        // A depth-only pipeline: there is no fragment stage, so the pipeline's render layout has
+        // no color formats.
+        let shadow_pipeline = device
+            .create_render_pipeline(
+                &RenderPipelineDescriptorBuilder::begin()
+                    .layout(&shadow_pipeline_layout)
+                    .vertex(
+                        VertexStageBuilder::begin(&shadow_shader, "vert_main")
+                            .vertex_layout::<Vertex>()
+                            .finish(),
+                    )
+                    .depth_stencil_test(
+                        DepthStencilTest::read_write::<depth32float>()
+                            .depth_compare(CompareFunction::LessEqual),
+                    )
+                    .finish(),
+            )
+            .await?;
+
+        let scene_pipeline = device
+            .create_render_pipeline(
+                &RenderPipelineDescriptorBuilder::begin()
+                    .layout(&scene_pipeline_layout)
+                    .vertex(
+                        VertexStageBuilder::begin(&scene_shader, "vert_main")
+                            .vertex_layout::<Vertex>()
+                            .finish(),
+                    )
+                    .fragment(
+                        FragmentStageBuilder::begin(&scene_shader, "frag_main")
+                            .color_outputs(ColorOutput {
+                                format: bgra8unorm,
+                                write_mask: ColorWrite::All,
+                            })
+                            .finish(),
+                    )
+                    .depth_stencil_test(
+                        DepthStencilTest::read_write::<depth24plus>()
+                            .depth_compare(CompareFunction::LessEqual),
+                    )
+                    .finish(),
+            )
+            .await?;
+
+        let cube_color = [200, 80, 80, 255];
+        let plane_color = [220, 220, 220, 255];
+
+        let vertex_data = [
+            // Cube
+            Vertex {
+                position: [-5.0, -5.0, -5.0, 1.0],
+                color: cube_color,
+            },
+            Vertex {
+                position: [5.0, -5.0, -5.0, 1.0],
+                color: cube_color,
+            },
+            Vertex {
+                position: [-5.0, 5.0, -5.0, 1.0],
+                color: cube_color,
+            },
+            Vertex {
+                position: [5.0, 5.0, -5.0, 1.0],
+                color: cube_color,
+            },
+            Vertex {
+                position: [-5.0, -5.0, 5.0, 1.0],
+                color: cube_color,
+            },
+            Vertex {
+                position: [5.0, -5.0, 5.0, 1.0],
+                color: cube_color,
+            },
+            Vertex {
+                position: [-5.0, 5.0, 5.0, 1.0],
+                color: cube_color,
+            },
+            Vertex {
+                position: [5.0, 5.0, 5.0, 1.0],
+                color: cube_color,
+            },
+            // Ground plane
+            Vertex {
+                position: [-25.0, -12.0, -25.0, 1.0],
+                color: plane_color,
+            },
+            Vertex {
+                position: [25.0, -12.0, -25.0, 1.0],
+                color: plane_color,
+            },
+            Vertex {
+                position: [25.0, -12.0, 25.0, 1.0],
+                color: plane_color,
+            },
+            Vertex {
+                position: [-25.0, -12.0, 25.0, 1.0],
+                color: plane_color,
+            },
+        ];
+
+        let vertex_buffer = device.create_buffer(vertex_data, buffer::Usages::vertex());
+
+        let index_data: Vec<u16> = vec![
+            0, 2, 1, // Back
+            1, 2, 3, //
+            0, 6, 2, // Left
+            0, 4, 6, //
+            1, 3, 7, // Right
+            1, 7, 5, //
+            2, 7, 3, // Top
+            2, 6, 7, //
+            0, 1, 5, // Bottom
+            0, 5, 4, //
+            4, 5, 7, // Front
+            6, 4, 7, //
+            8, 10, 9, // Ground plane
+            8, 11, 10, //
+        ];
+
+        let index_buffer = device.create_buffer(index_data, buffer::Usages::index());
+
+        let light_view = Mat4::look_at_rh(Vec3::new(15.0, 30.0, 15.0), Vec3::ZERO, Vec3::Y);
+        let light_projection = Mat4::orthographic_rh(-30.0, 30.0, -30.0, 30.0, 1.0, 80.0);
+        let light_view_projection = light_projection * light_view;
+        let view_projection = camera_view_projection(size.width, size.height);
+
+        let uniforms = Uniforms {
+            model: Mat4::IDENTITY.to_abi(),
+            view_projection: view_projection.to_abi(),
+            light_view_projection: light_view_projection.to_abi(),
+        };
+
+        let cube_uniforms: UniformBuffer =
+            device.create_buffer(uniforms, buffer::Usages::uniform_binding().and_copy_dst());
+        let plane_uniforms: UniformBuffer =
+            device.create_buffer(uniforms, buffer::Usages::uniform_binding().and_copy_dst());
+
+        let cube_bind_group = device.create_bind_group(
+            &object_layout,
+            ObjectResources {
+                uniform_buffer: cube_uniforms.uniform(),
+            },
+        );
+        let plane_bind_group = device.create_bind_group(
+            &object_layout,
+            ObjectResources {
+                uniform_buffer: plane_uniforms.uniform(),
+            },
+        );
+
+        let shadow_map = device.create_texture_2d(&Texture2DDescriptor {
+            format: depth32float,
+            usage: texture::Usages::render_attachment().and_texture_binding(),
+            view_formats: (),
+            width: SHADOW_MAP_SIZE,
+            height: SHADOW_MAP_SIZE,
+            layers: 1,
+            mipmap_levels: MipmapLevels::Partial(1),
+        });
+
+        let shadow_sampler = device.create_comparison_sampler(&ComparisonSamplerDescriptor {
+            compare: CompareFunction::LessEqual,
+            magnification_filter: FilterMode::Linear,
+            minification_filter: FilterMode::Linear,
+            ..Default::default()
+        });
+
+        let shadow_bind_group = device.create_bind_group(
+            &shadow_layout,
+            ShadowResources {
+                shadow_map: shadow_map.sampled_depth(&Default::default()),
+                shadow_sampler: &shadow_sampler,
+            },
+        );
+
+        // A render bundle without color formats, for use in the depth-only shadow pass.
+        let shadow_bundle = device
+            .create_render_bundle_encoder(
+                &RenderBundleEncoderDescriptor::new::<()>().depth_stencil_format::<depth32float>(),
+            )
+            .set_pipeline(&shadow_pipeline)
+            .set_vertex_buffers(&vertex_buffer)
+            .set_index_buffer(&index_buffer)
+            .set_bind_groups(&cube_bind_group)
+            .draw_indexed(DrawIndexed {
+                index_count: 36,
+                instance_count: 1,
+                first_index: 0,
+                first_instance: 0,
+                base_vertex: 0,
+            })
+            .set_bind_groups(&plane_bind_group)
+            .draw_indexed(DrawIndexed {
+                index_count: 6,
+                instance_count: 1,
+                first_index: 36,
+                first_instance: 0,
+                base_vertex: 0,
+            })
+            .finish();
+
+        let depth_texture = device.create_texture_2d(&Texture2DDescriptor {
+            format: depth24plus,
+            usage: texture::Usages::render_attachment(),
+            view_formats: (),
+            width: size.width,
+            height: size.height,
+            layers: 1,
+            mipmap_levels: MipmapLevels::Partial(1),
+        });
+
+        let surface = surface.configure(
+            &device,
+            &SurfaceConfiguration {
+                format: bgra8unorm,
+                usage: texture::Usages::render_attachment(),
+                width: size.width,
+                height: size.height,
+                present_mode: Default::default(),
+                desired_maximum_frame_latency: Default::default(),
+                alpha_mode: Default::default(),
+                view_formats: (),
+            },
+        );
+
+        Ok(AppState {
+            device,
+            light_view_projection,
+            view_projection,
+            cube_uniforms,
+            plane_uniforms,
+            cube_bind_group,
+            plane_bind_group,
+            shadow_bind_group,
+            vertex_buffer,
+            index_buffer,
+            shadow_map,
+            shadow_bundle,
+            scene_pipeline,
+            depth_texture,
+            window,
+            surface,
+        })
+    }
+
+    pub fn resize(&mut self, width: u32, height: u32) {
+        self.surface.resize(width, height);
+        self.depth_texture = self.device.create_texture_2d(&Texture2DDescriptor {
+            format: depth24plus,
+            usage: texture::Usages::render_attachment(),
+            view_formats: (),
+            width,
+            height,
+            layers: 1,
+            mipmap_levels: MipmapLevels::Partial(1),
+        });
+        self.view_projection = camera_view_projection(width, height);
+    }
+
+    pub fn draw_frame(&self) {
+        let AppState {
+            device,
+            light_view_projection,
+            view_projection,
+            cube_uniforms,
+            plane_uniforms,
+            cube_bind_group,
+            plane_bind_group,
+            shadow_bind_group,
+            vertex_buffer,
+            index_buffer,
+            shadow_map,
+            shadow_bundle,
+            scene_pipeline,
+            depth_texture,
+            surface,
+            ..
+        } = self;
+
+        let current_system_time = SystemTime::now();
+        let duration_since_epoch = current_system_time.duration_since(UNIX_EPOCH).unwrap();
+        let ms = duration_since_epoch.as_millis() as u16;
+
+        let queue = device.queue();
+
+        let rotate_x = Mat4::from_rotation_x(ms as f32 / 1000.0);
+        let rotate_y = Mat4::from_rotation_y(ms as f32 / 1000.0);
+
+        queue.write_buffer(
+            cube_uniforms.view(),
+            &Uniforms {
+                model: (rotate_y * rotate_x).to_abi(),
+                view_projection: view_projection.to_abi(),
+                light_view_projection: light_view_projection.to_abi(),
+            },
+        );
+        queue.write_buffer(
+            plane_uniforms.view(),
+            &Uniforms {
+                model: Mat4::IDENTITY.to_abi(),
+                view_projection: view_projection.to_abi(),
+                light_view_projection: light_view_projection.to_abi(),
+            },
+        );
+
+        let frame = surface
+            .get_current_texture()
+            .expect("Failed to acquire next swap chain texture");
+
+        let command_buffer = device
+            .create_command_encoder()
+            // Depth-only shadow pass: the render target has no color attachments.
+            .begin_render_pass(RenderPassDescriptor::new(&RenderTarget {
+                color: (),
+                depth_stencil: DepthAttachment {
+                    image: shadow_map.attachable_image(&AttachableImageDescriptor::default()),
+                    load_op: LoadOp::Clear(DepthValue::ONE),
+                    store_op: StoreOp::Store,
+                },
+            }))
+            .execute_bundle(shadow_bundle)
+            .end()
+            .begin_render_pass(RenderPassDescriptor::new(&RenderTarget {
+                color: FloatAttachment {
+                    image: frame.attachable_image(&AttachableImageDescriptor::default()),
+                    load_op: LoadOp::Clear([0.1, 0.1, 0.1, 1.0]),
+                    store_op: StoreOp::Store,
+                },
+                depth_stencil: DepthAttachment {
+                    image: depth_texture.attachable_image(&AttachableImageDescriptor::default()),
+                    load_op: LoadOp::Clear(DepthValue::ONE),
+                    store_op: StoreOp::Discard,
+                },
+            }))
+            .set_pipeline(scene_pipeline)
+            .set_vertex_buffers(vertex_buffer)
+            .set_index_buffer(index_buffer)
+            .set_bind_groups((cube_bind_group, shadow_bind_group))
+            .draw_indexed(DrawIndexed {
+                index_count: 36,
+                instance_count: 1,
+                first_index: 0,
+                first_instance: 0,
+                base_vertex: 0,
+            })
+            .set_bind_groups((plane_bind_group, shadow_bind_group))
+            .draw_indexed(DrawIndexed {
+                index_count: 6,
+                instance_count: 1,
+                first_index: 36,
+                first_instance: 0,
+                base_vertex: 0,
+            })
+            .end()
+            .finish();
+
+        queue.submit(command_buffer);
+
+        frame.present();
+    }
+}
+
+struct App {
+    state: Option<AppState>,
+}
+
+impl App {
+    fn new() -> Self {
+        App { state: None }
+    }
+}
+
+impl ApplicationHandler for App {
+    fn resumed(&mut self, event_loop: &ActiveEventLoop) {
+        let window = event_loop
+            .create_window(Window::default_attributes())
+            .unwrap();
+
+        let state = pollster::block_on(async move { AppState::init(window).await }).unwrap();
+
+        self.state = Some(state);
+    }
+
+    fn window_event(
+        &mut self,
+        event_loop: &ActiveEventLoop,
+        _window_id: WindowId,
+        event: WindowEvent,
+    ) {
+        match event {
+            WindowEvent::CloseRequested => {
+                event_loop.exit();
+            }
+            WindowEvent::Resized(size) => {
+                if let Some(state) = self.state.as_mut() {
+                    let width = size.width.max(1);
+                    let height = size.height.max(1);
+
+                    state.resize(width, height);
+                }
+            }
+            WindowEvent::RedrawRequested => {
+                let state = self.state.as_ref().unwrap();
+
+                state.draw_frame();
+                state.window.request_redraw();
+            }
+            _ => (),
+        }
+    }
+}
+
+pub fn main() {
+    let event_loop = EventLoop::new().unwrap();
+
+    event_loop.set_control_flow(ControlFlow::Poll);
+
+    let mut app = App::new();
+
+    event_loop.run_app(&mut app).unwrap();
+}
@@ -0,0 +1,53 @@
+struct Uniforms {
+    model: mat4x4<f32>,
+    view_projection: mat4x4<f32>,
+    light_view_projection: mat4x4<f32>
+}
+
+struct VertexIn {
+    @location(0) position: vec4<f32>,
+    @location(1) color: vec4<f32>
+}
+
+struct VertexOut {
+    @builtin(position) position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+    @location(1) shadow_position: vec3<f32>
+}
+
+@group(0) @binding(0)
+var<uniform> uniforms: Uniforms;
+
+@group(1) @binding(0)
+var shadow_map: texture_depth_2d;
+
+@group(1) @binding(1)
+var shadow_sampler: sampler_comparison;
+
+@vertex
+fn vert_main(vertex: VertexIn) -> VertexOut {
+    let world_position = uniforms.model * vertex.position;
+    let light_position = uniforms.light_view_projection * world_position;
+    let light_ndc = light_position.xyz / light_position.w;
+
+    var result = VertexOut();
+
+    result.position = uniforms.view_projection * world_position;
+    result.color = vertex.color;
+    // Map from normalized device coordinates to shadow map texture coordinates.
+    result.shadow_position = vec3(light_ndc.xy * vec2(0.5, -0.5) + vec2(0.5), light_ndc.z);
+
+    return result;
+}
+
+@fragment
+fn frag_main(@location(0) color: vec4<f32>, @location(1) shadow_position: vec3<f32>) -> @location(0) vec4<f32> {
+    let visibility = textureSampleCompare(
+        shadow_map,
+        shadow_sampler,
+        shadow_position.xy,
+        shadow_position.z - 0.005
+    );
+
+    return vec4(color.rgb * (0.3 + 0.7 * visibility), 1.0);
+}
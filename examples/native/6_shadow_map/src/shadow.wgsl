@@ -0,0 +1,15 @@
+struct Uniforms {
+    model: mat4x4<f32>,
+    view_projection: mat4x4<f32>,
+    light_view_projection: mat4x4<f32>
+}
+
+@group(0) @binding(0)
+var<uniform> uniforms: Uniforms;
+
+// The shadow pass only needs the depth of each vertex as seen from the light; the pipeline has
+// no fragment stage.
+@vertex
+fn vert_main(@location(0) position: vec4<f32>) -> @builtin(position) vec4<f32> {
+    return uniforms.light_view_projection * uniforms.model * position;
+}
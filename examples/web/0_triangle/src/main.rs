@@ -55,6 +55,7 @@ async fn render() -> Result<(), Box<dyn Error>> {
         usage: texture::Usages::render_attachment(),
         view_formats: (),
         alpha_mode: AlphaMode::Opaque,
+        frame_latency: Default::default(),
     });
 
     let shader = device.create_shader_module(&SHADER);
@@ -80,7 +81,7 @@ async fn render() -> Result<(), Box<dyn Error>> {
                 )
                 .finish(),
         )
-        .await;
+        .await?;
 
     let vertex_data = [
         MyVertex {
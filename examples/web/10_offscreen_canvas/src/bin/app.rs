@@ -0,0 +1,27 @@
+use wasm_bindgen::JsCast;
+use web_sys::{HtmlCanvasElement, Worker};
+
+// Transfers control of the canvas to a dedicated worker, which does all the rendering (see
+// `worker.rs`).
+fn main() {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let canvas: HtmlCanvasElement = document
+        .get_element_by_id("canvas")
+        .expect("canvas not found")
+        .unchecked_into();
+
+    let offscreen_canvas = canvas
+        .transfer_control_to_offscreen()
+        .expect("failed to transfer canvas control");
+
+    let worker = Worker::new("./worker_loader.js").expect("failed to create worker");
+
+    let transfer = js_sys::Array::of1(&offscreen_canvas);
+
+    worker
+        .post_message_with_transfer(&offscreen_canvas, &transfer)
+        .expect("failed to post canvas to worker");
+
+    // Keep the worker alive for the lifetime of the page.
+    std::mem::forget(worker);
+}
@@ -0,0 +1,410 @@
+use std::error::Error;
+use std::f32::consts::PI;
+
+use arwa::dom::{selector, ParentNode};
+use arwa::html::HtmlCanvasElement;
+use arwa::window::window;
+use empa::arwa::{
+    AlphaMode, AnimationLoop, CanvasConfiguration, HtmlCanvasElementExt, NavigatorExt,
+    RequestAdapterOptions,
+};
+use empa::buffer::{Buffer, Uniform};
+use empa::command::{
+    DrawIndexed, DrawIndexedCommandEncoder, RenderBundleEncoderDescriptor, RenderPassDescriptor,
+    RenderStateEncoder, ResourceBindingCommandEncoder,
+};
+use empa::device::DeviceDescriptor;
+use empa::render_pipeline::{
+    ColorOutput, ColorWrite, DepthStencilTest, FragmentStageBuilder,
+    RenderPipelineDescriptorBuilder, VertexStageBuilder,
+};
+use empa::render_target::{
+    DepthAttachment, DepthValue, FloatAttachment, LoadOp, RenderTarget, StoreOp,
+};
+use empa::resource_binding::Resources;
+use empa::sampler::{ComparisonSampler, ComparisonSamplerDescriptor, FilterMode};
+use empa::shader_module::{shader_source, ShaderSource};
+use empa::texture::format::{depth24plus, depth32float, rgba8unorm};
+use empa::texture::{AttachableImageDescriptor, MipmapLevels, Sampled2DDepth, Texture2DDescriptor};
+use empa::{abi, buffer, texture, CompareFunction};
+use empa_glam::ToAbi;
+use futures::FutureExt;
+use glam::f32::{Mat4, Vec3};
+
+const SHADOW_MAP_SIZE: u32 = 1024;
+
+#[derive(empa::render_pipeline::Vertex, Clone, Copy)]
+struct MyVertex {
+    #[vertex_attribute(location = 0, format = "float32x4")]
+    position: [f32; 4],
+    #[vertex_attribute(location = 1, format = "unorm8x4")]
+    color: [u8; 4],
+}
+
+#[derive(empa::abi::Sized, Clone, Copy)]
+#[repr(C)]
+struct Uniforms {
+    model: abi::Mat4x4,
+    view_projection: abi::Mat4x4,
+    light_view_projection: abi::Mat4x4,
+}
+
+#[derive(empa::resource_binding::Resources)]
+struct ObjectResources<'a> {
+    #[resource(binding = 0, visibility = "VERTEX")]
+    uniform_buffer: Uniform<'a, Uniforms>,
+}
+
+#[derive(empa::resource_binding::Resources)]
+struct ShadowResources<'a> {
+    #[resource(binding = 0, visibility = "FRAGMENT")]
+    shadow_map: Sampled2DDepth<'a>,
+    #[resource(binding = 1, visibility = "FRAGMENT")]
+    shadow_sampler: &'a ComparisonSampler,
+}
+
+const SHADOW_SHADER: ShaderSource = shader_source!("shadow.wgsl");
+
+const SCENE_SHADER: ShaderSource = shader_source!("scene.wgsl");
+
+fn main() {
+    arwa::spawn_local(render().map(|res| res.unwrap()));
+}
+
+async fn render() -> Result<(), Box<dyn Error>> {
+    let window = window();
+    let empa = window.navigator().empa();
+    let canvas: HtmlCanvasElement = window
+        .document()
+        .query_selector(&selector!("#canvas"))
+        .ok_or("canvas not found")?
+        .try_into()?;
+
+    let adapter = empa
+        .request_adapter(&RequestAdapterOptions::default())
+        .await
+        .ok_or("adapter not found")?;
+    let device = adapter.request_device(&DeviceDescriptor::default()).await?;
+
+    let context = canvas.empa_context().configure(&CanvasConfiguration {
+        device: &device,
+        format: rgba8unorm,
+        usage: texture::Usages::render_attachment(),
+        view_formats: (),
+        alpha_mode: AlphaMode::Opaque,
+        frame_latency: Default::default(),
+    });
+
+    let shadow_shader = device.create_shader_module(&SHADOW_SHADER);
+    let scene_shader = device.create_shader_module(&SCENE_SHADER);
+
+    type ObjectLayout<'a> = <ObjectResources<'a> as Resources>::Layout;
+    type ShadowLayout<'a> = <ShadowResources<'a> as Resources>::Layout;
+
+    let object_layout = device.create_bind_group_layout::<ObjectLayout>();
+    let shadow_layout = device.create_bind_group_layout::<ShadowLayout>();
+
+    let shadow_pipeline_layout = device.create_pipeline_layout(&object_layout);
+    let scene_pipeline_layout = device.create_pipeline_layout((&object_layout, &shadow_layout));
+
+    // A depth-only pipeline: there is no fragment stage, so the pipeline's render layout has no
+    // color formats.
+    let shadow_pipeline = device
+        .create_render_pipeline(
+            &RenderPipelineDescriptorBuilder::begin()
+                .layout(&shadow_pipeline_layout)
+                .vertex(
+                    VertexStageBuilder::begin(&shadow_shader, "vert_main")
+                        .vertex_layout::<MyVertex>()
+                        .finish(),
+                )
+                .depth_stencil_test(
+                    DepthStencilTest::read_write::<depth32float>()
+                        .depth_compare(CompareFunction::LessEqual),
+                )
+                .finish(),
+        )
+        .await?;
+
+    let scene_pipeline = device
+        .create_render_pipeline(
+            &RenderPipelineDescriptorBuilder::begin()
+                .layout(&scene_pipeline_layout)
+                .vertex(
+                    VertexStageBuilder::begin(&scene_shader, "vert_main")
+                        .vertex_layout::<MyVertex>()
+                        .finish(),
+                )
+                .fragment(
+                    FragmentStageBuilder::begin(&scene_shader, "frag_main")
+                        .color_outputs(ColorOutput {
+                            format: rgba8unorm,
+                            write_mask: ColorWrite::All,
+                        })
+                        .finish(),
+                )
+                .depth_stencil_test(
+                    DepthStencilTest::read_write::<depth24plus>()
+                        .depth_compare(CompareFunction::LessEqual),
+                )
+                .finish(),
+        )
+        .await?;
+
+    let cube_color = [200, 80, 80, 255];
+    let plane_color = [220, 220, 220, 255];
+
+    let vertex_data = [
+        // Cube
+        MyVertex {
+            position: [-5.0, -5.0, -5.0, 1.0],
+            color: cube_color,
+        },
+        MyVertex {
+            position: [5.0, -5.0, -5.0, 1.0],
+            color: cube_color,
+        },
+        MyVertex {
+            position: [-5.0, 5.0, -5.0, 1.0],
+            color: cube_color,
+        },
+        MyVertex {
+            position: [5.0, 5.0, -5.0, 1.0],
+            color: cube_color,
+        },
+        MyVertex {
+            position: [-5.0, -5.0, 5.0, 1.0],
+            color: cube_color,
+        },
+        MyVertex {
+            position: [5.0, -5.0, 5.0, 1.0],
+            color: cube_color,
+        },
+        MyVertex {
+            position: [-5.0, 5.0, 5.0, 1.0],
+            color: cube_color,
+        },
+        MyVertex {
+            position: [5.0, 5.0, 5.0, 1.0],
+            color: cube_color,
+        },
+        // Ground plane
+        MyVertex {
+            position: [-25.0, -12.0, -25.0, 1.0],
+            color: plane_color,
+        },
+        MyVertex {
+            position: [25.0, -12.0, -25.0, 1.0],
+            color: plane_color,
+        },
+        MyVertex {
+            position: [25.0, -12.0, 25.0, 1.0],
+            color: plane_color,
+        },
+        MyVertex {
+            position: [-25.0, -12.0, 25.0, 1.0],
+            color: plane_color,
+        },
+    ];
+
+    let vertex_buffer: Buffer<[MyVertex], _> =
+        device.create_buffer(vertex_data, buffer::Usages::vertex());
+
+    let index_data: Vec<u16> = vec![
+        0, 2, 1, // Back
+        1, 2, 3, //
+        0, 6, 2, // Left
+        0, 4, 6, //
+        1, 3, 7, // Right
+        1, 7, 5, //
+        2, 7, 3, // Top
+        2, 6, 7, //
+        0, 1, 5, // Bottom
+        0, 5, 4, //
+        4, 5, 7, // Front
+        6, 4, 7, //
+        8, 10, 9, // Ground plane
+        8, 11, 10, //
+    ];
+
+    let index_buffer: Buffer<[u16], _> = device.create_buffer(index_data, buffer::Usages::index());
+
+    let light_view = Mat4::look_at_rh(Vec3::new(15.0, 30.0, 15.0), Vec3::ZERO, Vec3::Y);
+    let light_projection = Mat4::orthographic_rh(-30.0, 30.0, -30.0, 30.0, 1.0, 80.0);
+    let light_view_projection = (light_projection * light_view).to_abi();
+
+    let view = Mat4::look_at_rh(
+        Vec3::new(0.0, 15.0, 40.0),
+        Vec3::new(0.0, -5.0, 0.0),
+        Vec3::Y,
+    );
+    let aspect = canvas.width() as f32 / canvas.height() as f32;
+    let projection = Mat4::perspective_rh(0.3 * PI, aspect, 1.0, 100.0);
+    let view_projection = (projection * view).to_abi();
+
+    let uniforms = Uniforms {
+        model: Mat4::IDENTITY.to_abi(),
+        view_projection,
+        light_view_projection,
+    };
+
+    let cube_uniforms =
+        device.create_buffer(uniforms, buffer::Usages::uniform_binding().and_copy_dst());
+    let plane_uniforms =
+        device.create_buffer(uniforms, buffer::Usages::uniform_binding().and_copy_dst());
+
+    let cube_bind_group = device.create_bind_group(
+        &object_layout,
+        ObjectResources {
+            uniform_buffer: cube_uniforms.uniform(),
+        },
+    );
+    let plane_bind_group = device.create_bind_group(
+        &object_layout,
+        ObjectResources {
+            uniform_buffer: plane_uniforms.uniform(),
+        },
+    );
+
+    let shadow_map = device.create_texture_2d(&Texture2DDescriptor {
+        format: depth32float,
+        usage: texture::Usages::render_attachment().and_texture_binding(),
+        view_formats: (),
+        width: SHADOW_MAP_SIZE,
+        height: SHADOW_MAP_SIZE,
+        layers: 1,
+        mipmap_levels: MipmapLevels::Partial(1),
+    });
+
+    let shadow_sampler = device.create_comparison_sampler(&ComparisonSamplerDescriptor {
+        compare: CompareFunction::LessEqual,
+        magnification_filter: FilterMode::Linear,
+        minification_filter: FilterMode::Linear,
+        ..Default::default()
+    });
+
+    let shadow_bind_group = device.create_bind_group(
+        &shadow_layout,
+        ShadowResources {
+            shadow_map: shadow_map.sampled_depth(&Default::default()),
+            shadow_sampler: &shadow_sampler,
+        },
+    );
+
+    let depth_texture = device.create_texture_2d(&Texture2DDescriptor {
+        format: depth24plus,
+        usage: texture::Usages::render_attachment(),
+        view_formats: (),
+        width: canvas.width(),
+        height: canvas.height(),
+        layers: 1,
+        mipmap_levels: MipmapLevels::Partial(1),
+    });
+
+    // A render bundle without color formats, for use in the depth-only shadow pass.
+    let shadow_bundle = device
+        .create_render_bundle_encoder(
+            &RenderBundleEncoderDescriptor::new::<()>().depth_stencil_format::<depth32float>(),
+        )
+        .set_pipeline(&shadow_pipeline)
+        .set_vertex_buffers(&vertex_buffer)
+        .set_index_buffer(&index_buffer)
+        .set_bind_groups(&cube_bind_group)
+        .draw_indexed(DrawIndexed {
+            index_count: 36,
+            instance_count: 1,
+            first_index: 0,
+            first_instance: 0,
+            base_vertex: 0,
+        })
+        .set_bind_groups(&plane_bind_group)
+        .draw_indexed(DrawIndexed {
+            index_count: 6,
+            instance_count: 1,
+            first_index: 36,
+            first_instance: 0,
+            base_vertex: 0,
+        })
+        .finish();
+
+    let scene_bundle = device
+        .create_render_bundle_encoder(
+            &RenderBundleEncoderDescriptor::new::<rgba8unorm>()
+                .depth_stencil_format::<depth24plus>(),
+        )
+        .set_pipeline(&scene_pipeline)
+        .set_vertex_buffers(&vertex_buffer)
+        .set_index_buffer(&index_buffer)
+        .set_bind_groups((&cube_bind_group, &shadow_bind_group))
+        .draw_indexed(DrawIndexed {
+            index_count: 36,
+            instance_count: 1,
+            first_index: 0,
+            first_instance: 0,
+            base_vertex: 0,
+        })
+        .set_bind_groups((&plane_bind_group, &shadow_bind_group))
+        .draw_indexed(DrawIndexed {
+            index_count: 6,
+            instance_count: 1,
+            first_index: 36,
+            first_instance: 0,
+            base_vertex: 0,
+        })
+        .finish();
+
+    let queue = device.queue();
+
+    let mut animation_loop = AnimationLoop::new();
+
+    loop {
+        let time = animation_loop.next_frame(&context).await.time;
+        let time = time as f32;
+
+        let rotate_x = Mat4::from_rotation_x(time / 1000.0);
+        let rotate_y = Mat4::from_rotation_y(time / 1000.0);
+        let model = rotate_y * rotate_x;
+
+        queue.write_buffer(
+            cube_uniforms.view(),
+            &Uniforms {
+                model: model.to_abi(),
+                view_projection,
+                light_view_projection,
+            },
+        );
+
+        let command_buffer = device
+            .create_command_encoder()
+            // Depth-only shadow pass: the render target has no color attachments.
+            .begin_render_pass(RenderPassDescriptor::new(&RenderTarget {
+                color: (),
+                depth_stencil: DepthAttachment {
+                    image: shadow_map.attachable_image(&AttachableImageDescriptor::default()),
+                    load_op: LoadOp::Clear(DepthValue::ONE),
+                    store_op: StoreOp::Store,
+                },
+            }))
+            .execute_bundle(&shadow_bundle)
+            .end()
+            .begin_render_pass(RenderPassDescriptor::new(&RenderTarget {
+                color: FloatAttachment {
+                    image: context
+                        .get_current_texture()
+                        .attachable_image(&AttachableImageDescriptor::default()),
+                    load_op: LoadOp::Clear([0.1, 0.1, 0.1, 1.0]),
+                    store_op: StoreOp::Store,
+                },
+                depth_stencil: DepthAttachment {
+                    image: depth_texture.attachable_image(&AttachableImageDescriptor::default()),
+                    load_op: LoadOp::Clear(DepthValue::ONE),
+                    store_op: StoreOp::Discard,
+                },
+            }))
+            .execute_bundle(&scene_bundle)
+            .end()
+            .finish();
+
+        queue.submit(command_buffer);
+    }
+}
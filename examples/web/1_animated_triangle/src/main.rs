@@ -4,7 +4,8 @@ use arwa::dom::{selector, ParentNode};
 use arwa::html::HtmlCanvasElement;
 use arwa::window::window;
 use empa::arwa::{
-    AlphaMode, CanvasConfiguration, HtmlCanvasElementExt, NavigatorExt, RequestAdapterOptions,
+    AlphaMode, AnimationLoop, CanvasConfiguration, HtmlCanvasElementExt, NavigatorExt,
+    RequestAdapterOptions,
 };
 use empa::buffer::{Buffer, Uniform};
 use empa::command::{
@@ -65,6 +66,7 @@ async fn render() -> Result<(), Box<dyn Error>> {
         usage: texture::Usages::render_attachment(),
         view_formats: (),
         alpha_mode: AlphaMode::Opaque,
+        frame_latency: Default::default(),
     });
 
     let shader = device.create_shader_module(&SHADER);
@@ -93,7 +95,7 @@ async fn render() -> Result<(), Box<dyn Error>> {
                 )
                 .finish(),
         )
-        .await;
+        .await?;
 
     let vertex_data = [
         MyVertex {
@@ -124,8 +126,10 @@ async fn render() -> Result<(), Box<dyn Error>> {
 
     let queue = device.queue();
 
+    let mut animation_loop = AnimationLoop::new();
+
     loop {
-        let time = window.request_animation_frame().await;
+        let time = animation_loop.next_frame(&context).await.time;
 
         queue.write_buffer(uniform_buffer.view(), &f32::sin(time as f32 * 0.001));
 
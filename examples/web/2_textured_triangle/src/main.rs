@@ -72,6 +72,7 @@ async fn render() -> Result<(), Box<dyn Error>> {
         usage: texture::Usages::render_attachment(),
         view_formats: (),
         alpha_mode: AlphaMode::Opaque,
+        frame_latency: Default::default(),
     });
 
     let shader = device.create_shader_module(&SHADER);
@@ -100,7 +101,7 @@ async fn render() -> Result<(), Box<dyn Error>> {
                 )
                 .finish(),
         )
-        .await;
+        .await?;
 
     let img: HtmlImgElement = document
         .query_selector(&selector!("#checkerboard_gradient"))
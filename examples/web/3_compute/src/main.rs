@@ -51,7 +51,7 @@ async fn render() -> Result<(), Box<dyn Error>> {
                 .compute(ComputeStageBuilder::begin(&shader, "main").finish())
                 .finish(),
         )
-        .await;
+        .await?;
 
     let data: Vec<u32> = (0..1024).collect();
 
@@ -5,7 +5,8 @@ use arwa::dom::{selector, ParentNode};
 use arwa::html::HtmlCanvasElement;
 use arwa::window::window;
 use empa::arwa::{
-    AlphaMode, CanvasConfiguration, HtmlCanvasElementExt, NavigatorExt, RequestAdapterOptions,
+    AlphaMode, AnimationLoop, CanvasConfiguration, HtmlCanvasElementExt, NavigatorExt,
+    RequestAdapterOptions,
 };
 use empa::buffer::{Buffer, Uniform};
 use empa::command::{
@@ -37,6 +38,7 @@ struct MyVertex {
 }
 
 #[derive(empa::abi::Sized, Clone, Copy)]
+#[repr(C)]
 struct Uniforms {
     model: abi::Mat4x4,
     view: abi::Mat4x4,
@@ -76,6 +78,7 @@ async fn render() -> Result<(), Box<dyn Error>> {
         usage: texture::Usages::render_attachment(),
         view_formats: (),
         alpha_mode: AlphaMode::Opaque,
+        frame_latency: Default::default(),
     });
 
     let shader = device.create_shader_module(&SHADER);
@@ -108,7 +111,7 @@ async fn render() -> Result<(), Box<dyn Error>> {
                 )
                 .finish(),
         )
-        .await;
+        .await?;
 
     let vertex_data = [
         MyVertex {
@@ -215,8 +218,10 @@ async fn render() -> Result<(), Box<dyn Error>> {
 
     let queue = device.queue();
 
+    let mut animation_loop = AnimationLoop::new();
+
     loop {
-        let time = window.request_animation_frame().await;
+        let time = animation_loop.next_frame(&context).await.time;
         let time = time as f32;
 
         let rotate_x = glam::f32::Mat4::from_rotation_x(time / 1000.0);
@@ -1,5 +1,3 @@
-#![feature(int_roundings)]
-
 use std::collections::VecDeque;
 use std::error::Error;
 
@@ -66,7 +64,8 @@ impl Evaluator {
                     .compute(ComputeStageBuilder::begin(&scan_shader, "main").finish())
                     .finish(),
             )
-            .await;
+            .await
+            .unwrap();
 
         let uniform_add_shader = device.create_shader_module(&UNIFORM_ADD_SHADER);
 
@@ -81,7 +80,8 @@ impl Evaluator {
                     .compute(ComputeStageBuilder::begin(&uniform_add_shader, "main").finish())
                     .finish(),
             )
-            .await;
+            .await
+            .unwrap();
 
         Evaluator {
             device,
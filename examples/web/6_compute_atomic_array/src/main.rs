@@ -50,7 +50,7 @@ async fn render() -> Result<(), Box<dyn Error>> {
                 .compute(ComputeStageBuilder::begin(&shader, "main").finish())
                 .finish(),
         )
-        .await;
+        .await?;
 
     let data: Vec<u32> = vec![0; 32];
 